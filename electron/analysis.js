@@ -0,0 +1,1100 @@
+const { spawn } = require('child_process');
+const { resolveFfmpegPath } = require('./ffmpeg');
+const { clampOption } = require('./filterUtils');
+const { buildMixdownFilters, buildChannelMapFilters } = require('./audio');
+const { stemForTrack } = require('./stems');
+const { mixAudioClips } = require('./audioLanguages');
+const { runFfprobeJson } = require('./metadata');
+const { ErrorCode, StarscapeError } = require('./errors');
+
+// Sample rate the timeline mix is rendered at for peak data
+const WAVEFORM_SAMPLE_RATE = 8000;
+
+/**
+ * Run ffmpeg and collect everything it writes to stdout
+ * @param {Function} trackProcessFn - Optional function to track the ffmpeg process
+ * @returns {Promise<Buffer>}
+ */
+function runFfmpegOutput(args, trackProcessFn = null) {
+  return new Promise((resolve, reject) => {
+    const proc = spawn(resolveFfmpegPath(), args);
+    if (trackProcessFn) trackProcessFn(proc);
+    const chunks = [];
+    let stderr = '';
+
+    proc.stdout.on('data', (chunk) => chunks.push(chunk));
+    proc.stderr.on('data', (chunk) => {
+      stderr += chunk.toString();
+    });
+    proc.on('error', (err) => reject(`ffmpeg decode failed: ${err.message}`));
+    proc.on('close', (code) => {
+      if (code !== 0) {
+        reject(`ffmpeg decode failed (exit ${code}): ${stderr.trim()}`);
+        return;
+      }
+      resolve(Buffer.concat(chunks));
+    });
+  });
+}
+
+/**
+ * Run ffmpeg writing mono s16le to stdout and collect the samples
+ * @returns {Promise<Float32Array>} Samples normalized to [-1, 1]
+ */
+async function runPcmDecode(args, trackProcessFn = null) {
+  const buffer = await runFfmpegOutput(args, trackProcessFn);
+  const samples = new Float32Array(Math.floor(buffer.length / 2));
+  for (let i = 0; i < samples.length; i++) {
+    samples[i] = buffer.readInt16LE(i * 2) / 32768;
+  }
+  return samples;
+}
+
+/**
+ * Decode the first audio stream of a file to mono 16-bit PCM
+ * @param {string} inputPath - Media file path
+ * @param {number} sampleRate - Output sample rate in Hz
+ * @param {number|null} maxSeconds - Limit on decoded duration (null for the whole file)
+ * @param {Function} trackProcessFn - Optional function to track the ffmpeg process
+ * @returns {Promise<Float32Array>} Samples normalized to [-1, 1]
+ */
+function decodePcm(inputPath, sampleRate, maxSeconds = null, trackProcessFn = null) {
+  const args = ['-v', 'error', '-i', inputPath, '-vn', '-ac', '1', '-ar', String(sampleRate)];
+  if (maxSeconds) {
+    args.push('-t', String(maxSeconds));
+  }
+  args.push('-f', 's16le', 'pipe:1');
+  return runPcmDecode(args, trackProcessFn);
+}
+
+/**
+ * Peak absolute sample per bucket
+ * @param {Float32Array} samples - Mono samples
+ * @param {number} buckets - Number of peaks to return
+ * @returns {number[]} Peaks in [0, 1]
+ */
+function computePeaks(samples, buckets) {
+  const peaks = new Array(buckets).fill(0);
+  if (samples.length === 0) return peaks;
+  for (let b = 0; b < buckets; b++) {
+    const from = Math.floor((b * samples.length) / buckets);
+    const to = Math.max(from + 1, Math.floor(((b + 1) * samples.length) / buckets));
+    let peak = 0;
+    for (let i = from; i < to && i < samples.length; i++) {
+      const value = Math.abs(samples[i]);
+      if (value > peak) peak = value;
+    }
+    peaks[b] = Math.round(Math.min(1, peak) * 10000) / 10000;
+  }
+  return peaks;
+}
+
+/**
+ * Clips heard in the exported program, shaped for buildMixdownFilters
+ * Main-track clips play their own audio unless frozen or detached (J/L-cuts play from the audio track);
+ * sources without an audio stream are left out.
+ * @param {Function} hasAudio - (clip) => whether the clip's source has audio
+ */
+function programAudioClips(plan, hasAudio) {
+  const main = plan.mainTrack
+    .filter((clip) => clip.srcPath && !clip.freezeFrame && !clip.audioDetached && hasAudio(clip))
+    .map((clip) => ({ ...clip, trackId: '_program' }));
+  const audio = mixAudioClips(plan.audioTrack || [], plan.audioTracks || {}).filter((clip) => clip.srcPath && hasAudio(clip));
+  return [...main, ...audio];
+}
+
+/**
+ * Render the mixed program audio (trims, loops, effects, ducking) into peak data for the timeline
+ * @param {Object} plan - Edit plan with generator clips materialized
+ * @param {number} buckets - Number of peaks across the program
+ * @param {Function} probeSource - (srcPath) => Promise<metadata|null>
+ * @returns {Promise<{ start_ms: number, duration_ms: number, buckets: number, peaks: number[] }>}
+ */
+async function generateTimelineWaveform(plan, buckets, probeSource) {
+  const count = Math.round(clampOption(buckets, 1, 10000, 1000));
+  // Same span as the export: the main track, or the audio track when there is no main track
+  const spanClips = plan.mainTrack.length > 0 ? plan.mainTrack : plan.audioTrack || [];
+  const startMs = spanClips.length > 0 ? Math.min(...spanClips.map((clip) => clip.startMs)) : 0;
+  const endMs = spanClips.length > 0 ? Math.max(...spanClips.map((clip) => clip.endMs)) : 0;
+  const durationMs = endMs - startMs;
+  const result = { start_ms: startMs, duration_ms: durationMs, buckets: count };
+  if (durationMs <= 0) {
+    return { ...result, peaks: new Array(count).fill(0) };
+  }
+
+  const withAudio = new Set();
+  for (const clip of [...plan.mainTrack, ...(plan.audioTrack || [])]) {
+    const meta = clip.srcPath ? await probeSource(clip.srcPath) : null;
+    if (meta && meta.has_audio) withAudio.add(clip.srcPath);
+  }
+  const clips = programAudioClips(plan, (clip) => withAudio.has(clip.srcPath));
+  if (clips.length === 0) {
+    return { ...result, peaks: new Array(count).fill(0) };
+  }
+
+  const durationSec = durationMs / 1000;
+  const { filters, output } = buildMixdownFilters(clips, startMs, durationSec, false, plan.audioTracks || {});
+  const samples = await runPcmDecode([
+    '-v', 'error',
+    // Input 0 stands in for the program file the mixdown graph expects; it is never read
+    '-f', 'lavfi', '-t', String(durationSec), '-i', `anullsrc=r=${WAVEFORM_SAMPLE_RATE}:cl=mono`,
+    ...clips.flatMap((clip) => ['-i', clip.srcPath]),
+    '-filter_complex', filters.join(';'),
+    '-map', `[${output}]`,
+    '-ac', '1', '-ar', String(WAVEFORM_SAMPLE_RATE),
+    '-t', String(durationSec),
+    '-f', 's16le', 'pipe:1',
+  ]);
+  return { ...result, peaks: computePeaks(samples, count) };
+}
+
+/**
+ * In-place iterative radix-2 FFT
+ * @param {Float64Array} re - Real parts (length must be a power of two)
+ * @param {Float64Array} im - Imaginary parts
+ * @param {boolean} inverse - Compute the inverse transform (unscaled)
+ */
+function fft(re, im, inverse = false) {
+  const n = re.length;
+
+  // Bit-reversal permutation
+  for (let i = 1, j = 0; i < n; i++) {
+    let bit = n >> 1;
+    for (; j & bit; bit >>= 1) {
+      j ^= bit;
+    }
+    j ^= bit;
+    if (i < j) {
+      [re[i], re[j]] = [re[j], re[i]];
+      [im[i], im[j]] = [im[j], im[i]];
+    }
+  }
+
+  for (let len = 2; len <= n; len <<= 1) {
+    const angle = ((inverse ? 2 : -2) * Math.PI) / len;
+    const wRe = Math.cos(angle);
+    const wIm = Math.sin(angle);
+    for (let i = 0; i < n; i += len) {
+      let curRe = 1;
+      let curIm = 0;
+      for (let k = 0; k < len / 2; k++) {
+        const aRe = re[i + k];
+        const aIm = im[i + k];
+        const bRe = re[i + k + len / 2] * curRe - im[i + k + len / 2] * curIm;
+        const bIm = re[i + k + len / 2] * curIm + im[i + k + len / 2] * curRe;
+        re[i + k] = aRe + bRe;
+        im[i + k] = aIm + bIm;
+        re[i + k + len / 2] = aRe - bRe;
+        im[i + k + len / 2] = aIm - bIm;
+        const nextRe = curRe * wRe - curIm * wIm;
+        curIm = curRe * wIm + curIm * wRe;
+        curRe = nextRe;
+      }
+    }
+  }
+}
+
+/**
+ * Find the lag that best aligns signal b with signal a via FFT cross-correlation
+ * A positive lag means b's content appears `lag` samples later in a.
+ * @returns {{ lag: number, confidence: number }}
+ */
+function crossCorrelate(a, b, maxLag) {
+  let size = 1;
+  while (size < a.length + b.length) {
+    size <<= 1;
+  }
+
+  const aRe = new Float64Array(size);
+  const aIm = new Float64Array(size);
+  const bRe = new Float64Array(size);
+  const bIm = new Float64Array(size);
+  aRe.set(a);
+  bRe.set(b);
+
+  fft(aRe, aIm);
+  fft(bRe, bIm);
+
+  // A * conj(B)
+  for (let i = 0; i < size; i++) {
+    const re = aRe[i] * bRe[i] + aIm[i] * bIm[i];
+    const im = aIm[i] * bRe[i] - aRe[i] * bIm[i];
+    aRe[i] = re;
+    aIm[i] = im;
+  }
+  fft(aRe, aIm, true);
+
+  let bestLag = 0;
+  let bestValue = -Infinity;
+  for (let lag = -maxLag; lag <= maxLag; lag++) {
+    const idx = lag >= 0 ? lag : size + lag;
+    if (idx < 0 || idx >= size) continue;
+    if (aRe[idx] > bestValue) {
+      bestValue = aRe[idx];
+      bestLag = lag;
+    }
+  }
+
+  let energyA = 0;
+  let energyB = 0;
+  for (let i = 0; i < a.length; i++) energyA += a[i] * a[i];
+  for (let i = 0; i < b.length; i++) energyB += b[i] * b[i];
+  const norm = Math.sqrt(energyA * energyB) * size;
+  const confidence = norm > 0 ? Math.max(0, Math.min(1, bestValue / norm)) : 0;
+
+  return { lag: bestLag, confidence };
+}
+
+/**
+ * Compute the offset that syncs clip B to clip A by cross-correlating their audio
+ * @param {string} pathA - Reference media (e.g. screen recording)
+ * @param {string} pathB - Media to align (e.g. webcam recording)
+ * @param {Object} options - { maxOffsetMs, analyzeSeconds, sampleRate }
+ * @returns {Promise<{ offset_ms: number, confidence: number }>}
+ *   offset_ms is how far after A's start B should be placed on the timeline
+ */
+async function alignClips(pathA, pathB, options = {}) {
+  const { maxOffsetMs = 30000, analyzeSeconds = 120, sampleRate = 8000 } = options;
+
+  const [a, b] = await Promise.all([
+    decodePcm(pathA, sampleRate, analyzeSeconds),
+    decodePcm(pathB, sampleRate, analyzeSeconds),
+  ]);
+
+  if (a.length === 0 || b.length === 0) {
+    throw new Error('Both files must contain audio to align');
+  }
+
+  const maxLag = Math.round((maxOffsetMs / 1000) * sampleRate);
+  const { lag, confidence } = crossCorrelate(a, b, maxLag);
+
+  return {
+    offset_ms: Math.round((lag / sampleRate) * 1000),
+    confidence,
+  };
+}
+
+// Beat analysis: sample rate, FFT frame and hop of the onset envelope (~11.6ms per frame)
+const BEAT_SAMPLE_RATE = 22050;
+const ONSET_FRAME_SIZE = 1024;
+const ONSET_HOP = 256;
+
+/**
+ * Spectral-flux onset envelope: how much louder each frame got across the spectrum
+ * Magnitudes are log-compressed so quiet and loud passages contribute comparably.
+ * @returns {{ envelope: Float64Array, frameMs: number }}
+ */
+function onsetEnvelope(samples, sampleRate, frameSize = ONSET_FRAME_SIZE, hop = ONSET_HOP) {
+  const frames = samples.length >= frameSize ? Math.floor((samples.length - frameSize) / hop) + 1 : 0;
+  const envelope = new Float64Array(frames);
+  const window = new Float64Array(frameSize);
+  for (let i = 0; i < frameSize; i++) {
+    window[i] = 0.5 - 0.5 * Math.cos((2 * Math.PI * i) / (frameSize - 1));
+  }
+  const bins = frameSize / 2;
+  const previous = new Float64Array(bins);
+  const re = new Float64Array(frameSize);
+  const im = new Float64Array(frameSize);
+
+  for (let f = 0; f < frames; f++) {
+    const offset = f * hop;
+    for (let i = 0; i < frameSize; i++) {
+      re[i] = samples[offset + i] * window[i];
+      im[i] = 0;
+    }
+    fft(re, im);
+    let flux = 0;
+    for (let k = 0; k < bins; k++) {
+      const magnitude = Math.log1p(100 * Math.hypot(re[k], im[k]));
+      const rise = magnitude - previous[k];
+      if (rise > 0) flux += rise;
+      previous[k] = magnitude;
+    }
+    // The first frame rises from silence everywhere
+    envelope[f] = f === 0 ? 0 : flux;
+  }
+  return { envelope, frameMs: (hop * 1000) / sampleRate };
+}
+
+/**
+ * Frames where the envelope peaks clearly above its local average
+ */
+function pickOnsets(envelope, radius = 8) {
+  let max = 0;
+  for (const value of envelope) max = Math.max(max, value);
+  const onsets = [];
+  for (let i = 1; i < envelope.length - 1; i++) {
+    if (envelope[i] <= envelope[i - 1] || envelope[i] < envelope[i + 1] || envelope[i] < 0.1 * max) continue;
+    let sum = 0;
+    let count = 0;
+    for (let j = Math.max(0, i - radius); j <= Math.min(envelope.length - 1, i + radius); j++) {
+      sum += envelope[j];
+      count++;
+    }
+    if (envelope[i] > 1.5 * (sum / count)) {
+      onsets.push(i);
+    }
+  }
+  return onsets;
+}
+
+/**
+ * Tempo from the autocorrelation of the onset envelope
+ * Lags are weighted toward 120 BPM (one octave spread) to settle half/double-tempo ambiguity.
+ * @returns {{ bpm: number, periodFrames: number, confidence: number }|null}
+ */
+function estimateTempo(envelope, frameMs, minBpm = 60, maxBpm = 200) {
+  const minLag = Math.max(1, Math.floor(60000 / maxBpm / frameMs));
+  const maxLag = Math.ceil(60000 / minBpm / frameMs);
+  if (envelope.length <= maxLag + 1) return null;
+
+  let mean = 0;
+  for (const value of envelope) mean += value;
+  mean /= envelope.length;
+  const centered = envelope.map((value) => value - mean);
+  const correlate = (lag) => {
+    let sum = 0;
+    for (let i = 0; i + lag < centered.length; i++) sum += centered[i] * centered[i + lag];
+    return sum / (centered.length - lag);
+  };
+
+  const energy = correlate(0);
+  if (energy <= 0) return null;
+  const values = new Float64Array(maxLag + 2);
+  for (let lag = minLag - 1; lag <= maxLag + 1; lag++) values[lag] = correlate(lag);
+
+  let bestLag = minLag;
+  let bestScore = -Infinity;
+  for (let lag = minLag; lag <= maxLag; lag++) {
+    const bpm = 60000 / (lag * frameMs);
+    const score = values[lag] * Math.exp(-0.5 * Math.log2(bpm / 120) ** 2);
+    if (score > bestScore) {
+      bestScore = score;
+      bestLag = lag;
+    }
+  }
+
+  // Parabolic interpolation between neighbouring lags for a sub-frame period
+  const [a, b, c] = [values[bestLag - 1], values[bestLag], values[bestLag + 1]];
+  const denominator = a - 2 * b + c;
+  const period = denominator < 0 ? bestLag + (0.5 * (a - c)) / denominator : bestLag;
+  return {
+    bpm: Math.round((60000 / (period * frameMs)) * 10) / 10,
+    periodFrames: period,
+    confidence: Math.max(0, Math.min(1, b / energy)),
+  };
+}
+
+/**
+ * Beat frames on a fixed grid at the tempo period, phased to hit the strongest onsets
+ * Each beat snaps to the envelope peak within a tenth of a period.
+ */
+function trackBeats(envelope, period) {
+  let bestOffset = 0;
+  let bestScore = -Infinity;
+  for (let offset = 0; offset < Math.ceil(period); offset++) {
+    let score = 0;
+    for (let t = offset; t < envelope.length; t += period) score += envelope[Math.round(t)] || 0;
+    if (score > bestScore) {
+      bestScore = score;
+      bestOffset = offset;
+    }
+  }
+
+  const slack = Math.max(1, Math.round(period / 10));
+  const beats = [];
+  for (let t = bestOffset; Math.round(t) < envelope.length; t += period) {
+    const center = Math.round(t);
+    let frame = center;
+    for (let j = Math.max(0, center - slack); j <= Math.min(envelope.length - 1, center + slack); j++) {
+      if (envelope[j] > envelope[frame]) frame = j;
+    }
+    if (beats.length === 0 || frame > beats[beats.length - 1]) {
+      beats.push(frame);
+    }
+  }
+  return beats;
+}
+
+/**
+ * Detect the tempo, beats and onsets of a file's audio
+ * @param {Object} options - { minBpm, maxBpm }
+ * @returns {Promise<{ bpm: number|null, confidence: number, beats_ms: number[], onsets_ms: number[] }>}
+ */
+async function analyzeBeats(inputPath, options = {}) {
+  const minBpm = clampOption(options.minBpm, 30, 300, 60);
+  const maxBpm = clampOption(options.maxBpm, minBpm + 1, 400, 200);
+  const samples = await decodePcm(inputPath, BEAT_SAMPLE_RATE);
+  if (samples.length === 0) {
+    throw new Error('File has no audio to analyze');
+  }
+
+  const { envelope, frameMs } = onsetEnvelope(samples, BEAT_SAMPLE_RATE);
+  // An onset first shows up in the frame whose window just reaches it, so it lies in that window's last hop
+  const toMs = (frame) => Math.round(frame * frameMs + ((ONSET_FRAME_SIZE - ONSET_HOP / 2) * 1000) / BEAT_SAMPLE_RATE);
+  const onsets = pickOnsets(envelope).map(toMs);
+  const tempo = estimateTempo(envelope, frameMs, minBpm, maxBpm);
+  if (!tempo) {
+    return { bpm: null, confidence: 0, beats_ms: [], onsets_ms: onsets };
+  }
+  return {
+    bpm: tempo.bpm,
+    confidence: Math.round(tempo.confidence * 1000) / 1000,
+    beats_ms: trackBeats(envelope, tempo.periodFrames).map(toMs),
+    onsets_ms: onsets,
+  };
+}
+
+const SPEECH_SAMPLE_RATE = 16000;
+// 10ms decisions over 32ms analysis windows
+const SPEECH_HOP = 160;
+const SPEECH_FRAME_SIZE = 512;
+// Band holding most voiced speech energy
+const SPEECH_BAND_HZ = [300, 3400];
+
+/**
+ * Per-frame speech decision from level and spectral shape
+ * A frame counts as speech when it's well above the recording's noise floor
+ * and most of its energy sits in the voice band (rules out rumble and hiss).
+ * @param {Object} options - { marginDb (above the noise floor), minLevelDb, bandRatio }
+ * @returns {{ flags: Uint8Array, frameMs: number }}
+ */
+function speechFrames(samples, sampleRate, options = {}) {
+  const marginDb = clampOption(options.marginDb, 3, 40, 12);
+  const minLevelDb = clampOption(options.minLevelDb, -90, -10, -50);
+  const bandRatio = clampOption(options.bandRatio, 0, 1, 0.5);
+  const frames = samples.length >= SPEECH_FRAME_SIZE ? Math.floor((samples.length - SPEECH_FRAME_SIZE) / SPEECH_HOP) + 1 : 0;
+  const levels = new Float64Array(frames);
+  const ratios = new Float64Array(frames);
+  const window = new Float64Array(SPEECH_FRAME_SIZE);
+  for (let i = 0; i < SPEECH_FRAME_SIZE; i++) {
+    window[i] = 0.5 - 0.5 * Math.cos((2 * Math.PI * i) / (SPEECH_FRAME_SIZE - 1));
+  }
+  const binHz = sampleRate / SPEECH_FRAME_SIZE;
+  const [lowBin, highBin] = SPEECH_BAND_HZ.map((hz) => Math.round(hz / binHz));
+  const re = new Float64Array(SPEECH_FRAME_SIZE);
+  const im = new Float64Array(SPEECH_FRAME_SIZE);
+
+  for (let f = 0; f < frames; f++) {
+    const offset = f * SPEECH_HOP;
+    let sum = 0;
+    for (let i = 0; i < SPEECH_FRAME_SIZE; i++) {
+      const sample = samples[offset + i];
+      sum += sample * sample;
+      re[i] = sample * window[i];
+      im[i] = 0;
+    }
+    levels[f] = 10 * Math.log10(sum / SPEECH_FRAME_SIZE + 1e-10);
+    fft(re, im);
+    let total = 0;
+    let band = 0;
+    for (let k = 1; k < SPEECH_FRAME_SIZE / 2; k++) {
+      const power = re[k] * re[k] + im[k] * im[k];
+      total += power;
+      if (k >= lowBin && k <= highBin) band += power;
+    }
+    ratios[f] = total > 0 ? band / total : 0;
+  }
+
+  // Noise floor: the quietest tenth of the recording
+  const sorted = Array.from(levels).sort((a, b) => a - b);
+  const floor = sorted.length > 0 ? sorted[Math.floor(sorted.length * 0.1)] : minLevelDb;
+  const threshold = Math.max(minLevelDb, floor + marginDb);
+  const flags = new Uint8Array(frames);
+  for (let f = 0; f < frames; f++) {
+    flags[f] = levels[f] >= threshold && ratios[f] >= bandRatio ? 1 : 0;
+  }
+  return { flags, frameMs: (SPEECH_HOP * 1000) / sampleRate };
+}
+
+/**
+ * Turn frame decisions into alternating speech / non-speech segments covering the whole file
+ * Speech bursts shorter than minSpeechMs are dropped, pauses shorter than minSilenceMs are bridged,
+ * and speech is padded by paddingMs on both sides so word edges aren't clipped.
+ * @returns {Array<{ start_ms: number, end_ms: number, speech: boolean }>}
+ */
+function speechSegments(flags, frameMs, durationMs, options = {}) {
+  const minSpeechMs = clampOption(options.minSpeechMs, 0, 5000, 100);
+  const minSilenceMs = clampOption(options.minSilenceMs, 0, 5000, 200);
+  const paddingMs = clampOption(options.paddingMs, 0, 1000, 30);
+
+  let spans = [];
+  let start = null;
+  for (let f = 0; f <= flags.length; f++) {
+    if (f < flags.length && flags[f]) {
+      if (start === null) start = f;
+    } else if (start !== null) {
+      spans.push({ start: start * frameMs, end: f * frameMs });
+      start = null;
+    }
+  }
+
+  // Bridge short pauses first so a word split by a plosive isn't dropped as too short
+  const bridged = [];
+  for (const span of spans) {
+    const last = bridged[bridged.length - 1];
+    if (last && span.start - last.end < minSilenceMs) {
+      last.end = span.end;
+    } else {
+      bridged.push({ ...span });
+    }
+  }
+  spans = bridged.filter((span) => span.end - span.start >= minSpeechMs);
+
+  const segments = [];
+  let cursor = 0;
+  for (const span of spans) {
+    const speechStart = Math.max(cursor, Math.round(span.start - paddingMs));
+    const speechEnd = Math.min(durationMs, Math.round(span.end + paddingMs));
+    if (speechEnd <= speechStart) continue;
+    if (speechStart > cursor) {
+      segments.push({ start_ms: cursor, end_ms: speechStart, speech: false });
+    }
+    const last = segments[segments.length - 1];
+    if (last && last.speech) {
+      last.end_ms = speechEnd;
+    } else {
+      segments.push({ start_ms: speechStart, end_ms: speechEnd, speech: true });
+    }
+    cursor = speechEnd;
+  }
+  if (cursor < durationMs) {
+    segments.push({ start_ms: cursor, end_ms: Math.round(durationMs), speech: false });
+  }
+  return segments;
+}
+
+/**
+ * Speech / non-speech segmentation of a file's audio at 10ms resolution
+ * @param {Object} options - speechFrames and speechSegments options
+ * @returns {Promise<{ frame_ms: number, speech_ms: number, segments: Array<{ start_ms, end_ms, speech }> }>}
+ */
+async function detectSpeech(inputPath, options = {}) {
+  const samples = await decodePcm(inputPath, SPEECH_SAMPLE_RATE);
+  if (samples.length === 0) {
+    throw new Error('File has no audio to analyze');
+  }
+  const durationMs = (samples.length * 1000) / SPEECH_SAMPLE_RATE;
+  const { flags, frameMs } = speechFrames(samples, SPEECH_SAMPLE_RATE, options);
+  const segments = speechSegments(flags, frameMs, durationMs, options);
+  return {
+    frame_ms: frameMs,
+    speech_ms: segments.filter((s) => s.speech).reduce((sum, s) => sum + s.end_ms - s.start_ms, 0),
+    segments,
+  };
+}
+
+// Highlight scoring works on windows of this length
+const HIGHLIGHT_WINDOW_MS = 1000;
+const HIGHLIGHT_SAMPLE_RATE = 8000;
+const HIGHLIGHT_WEIGHTS = { audio: 0.5, scene: 0.3, keyword: 0.2 };
+
+/**
+ * Times of scene changes in a video (ffmpeg scene score over a downscaled copy)
+ * @param {number} threshold - Scene score (0-1) above which a frame counts as a cut
+ * @param {Function} trackProcessFn - Optional function to track the ffmpeg process
+ * @returns {Promise<Array<{ at_ms: number, score: number }>>}
+ */
+async function detectSceneChanges(inputPath, threshold = 0.3, trackProcessFn = null) {
+  const output = await runFfmpegOutput([
+    '-hide_banner', '-loglevel', 'error',
+    '-i', inputPath,
+    '-an', '-sn',
+    '-vf', `scale=160:-2,select='gt(scene,${threshold})',metadata=print:file=-`,
+    '-f', 'null', '-',
+  ], trackProcessFn);
+
+  const changes = [];
+  let atMs = null;
+  for (const line of output.toString().split('\n')) {
+    const frame = line.match(/pts_time:([\d.]+)/);
+    if (frame) {
+      atMs = Math.round(parseFloat(frame[1]) * 1000);
+      continue;
+    }
+    const score = line.match(/lavfi\.scene_score=([\d.]+)/);
+    if (score && atMs !== null) {
+      changes.push({ at_ms: atMs, score: parseFloat(score[1]) });
+      atMs = null;
+    }
+  }
+  return changes;
+}
+
+// Crop detection: seconds analyzed at each of a few points spread over the file
+const CROP_SAMPLE_SECONDS = 2;
+const CROP_SAMPLE_POINTS = [0.1, 0.3, 0.5, 0.7, 0.9];
+
+/**
+ * Active picture rect from `cropdetect,metadata=print` output (the last frame's, as cropdetect
+ * without reset grows its rect over every frame it has seen)
+ * @returns {{ x: number, y: number, width: number, height: number }|null}
+ */
+function parseCropDetect(output) {
+  let rect = null;
+  let frame = {};
+  for (const line of output.split('\n')) {
+    const match = /lavfi\.cropdetect\.(x|y|w|h)=(-?\d+)/.exec(line);
+    if (!match) continue;
+    frame[match[1]] = parseInt(match[2], 10);
+    if (['x', 'y', 'w', 'h'].every((key) => key in frame)) {
+      if (frame.w > 0 && frame.h > 0) {
+        rect = { x: frame.x, y: frame.y, width: frame.w, height: frame.h };
+      }
+      frame = {};
+    }
+  }
+  return rect;
+}
+
+/**
+ * Smallest rect containing every sample's active picture, so a dark shot can't crop into a bright one
+ */
+function unionCropRects(rects) {
+  const valid = rects.filter(Boolean);
+  if (valid.length === 0) return null;
+  const x = Math.min(...valid.map((rect) => rect.x));
+  const y = Math.min(...valid.map((rect) => rect.y));
+  const right = Math.max(...valid.map((rect) => rect.x + rect.width));
+  const bottom = Math.max(...valid.map((rect) => rect.y + rect.height));
+  return { x, y, width: right - x, height: bottom - y };
+}
+
+/**
+ * Find black bars baked into a video with cropdetect, sampled at a few points through the file
+ * @param {string} inputPath - Video file
+ * @param {Object} info - Probed metadata ({ duration_ms, width, height, rotation_deg, codec_video })
+ * @param {Function} trackProcessFn - Optional function to track ffmpeg processes
+ * @returns {Promise<{ x: number, y: number, width: number, height: number, source_width: number,
+ *   source_height: number, has_bars: boolean }>} Rect in upright (autorotated) source pixels
+ */
+async function detectCrop(inputPath, info, trackProcessFn = null) {
+  if (!info.codec_video || !info.width || !info.height) {
+    throw new Error('File has no video to analyze');
+  }
+  const sideways = info.rotation_deg === 90 || info.rotation_deg === 270;
+  const sourceWidth = sideways ? info.height : info.width;
+  const sourceHeight = sideways ? info.width : info.height;
+
+  const durationSec = (info.duration_ms || 0) / 1000;
+  const starts = durationSec > CROP_SAMPLE_SECONDS * CROP_SAMPLE_POINTS.length
+    ? CROP_SAMPLE_POINTS.map((point) => point * durationSec)
+    : [0];
+  const rects = [];
+  for (const startSec of starts) {
+    const output = await runFfmpegOutput([
+      '-hide_banner', '-loglevel', 'error',
+      '-ss', String(startSec),
+      '-t', String(CROP_SAMPLE_SECONDS),
+      '-i', inputPath,
+      '-an', '-sn',
+      '-vf', 'cropdetect=limit=24:round=2:reset=0,metadata=print:file=-',
+      '-f', 'null', '-',
+    ], trackProcessFn);
+    rects.push(parseCropDetect(output.toString()));
+  }
+
+  // All-black samples find nothing; keep the whole frame
+  const rect = unionCropRects(rects) || { x: 0, y: 0, width: sourceWidth, height: sourceHeight };
+  return {
+    ...rect,
+    source_width: sourceWidth,
+    source_height: sourceHeight,
+    has_bars: rect.width < sourceWidth || rect.height < sourceHeight,
+  };
+}
+
+/**
+ * RMS level of each window in dBFS
+ */
+function windowEnergyDb(samples, sampleRate, windowMs = HIGHLIGHT_WINDOW_MS) {
+  const size = Math.max(1, Math.round((sampleRate * windowMs) / 1000));
+  const levels = [];
+  for (let start = 0; start < samples.length; start += size) {
+    const end = Math.min(samples.length, start + size);
+    let sum = 0;
+    for (let i = start; i < end; i++) {
+      sum += samples[i] * samples[i];
+    }
+    levels.push(10 * Math.log10(sum / (end - start) + 1e-10));
+  }
+  return levels;
+}
+
+function median(values) {
+  if (values.length === 0) return 0;
+  const sorted = [...values].sort((a, b) => a - b);
+  const mid = Math.floor(sorted.length / 2);
+  return sorted.length % 2 ? sorted[mid] : (sorted[mid - 1] + sorted[mid]) / 2;
+}
+
+/**
+ * Transcript segments whose text contains one of the keywords (case-insensitive, whole words)
+ * @param {Array<{ start_ms: number, end_ms: number, text: string }>} transcript
+ * @returns {Array<{ start_ms: number, end_ms: number, keyword: string }>}
+ */
+function findKeywordHits(transcript, keywords) {
+  const words = (keywords || []).map((k) => String(k).trim().toLowerCase()).filter(Boolean);
+  if (words.length === 0) return [];
+  const escape = (word) => word.replace(/[.*+?^${}()|[\]\\]/g, '\\$&');
+  const patterns = words.map((word) => ({ word, pattern: new RegExp(`(^|[^\\p{L}\\p{N}])${escape(word)}($|[^\\p{L}\\p{N}])`, 'iu') }));
+  const hits = [];
+  for (const segment of transcript || []) {
+    if (!segment || typeof segment.text !== 'string') continue;
+    for (const { word, pattern } of patterns) {
+      if (pattern.test(segment.text)) {
+        hits.push({ start_ms: segment.start_ms, end_ms: segment.end_ms, keyword: word });
+      }
+    }
+  }
+  return hits;
+}
+
+/**
+ * Score each window of a recording and grow the best ones into highlight ranges
+ * Signals are scored 0-1 per window: loudness spikes relative to the recording's median level,
+ * density of scene changes nearby, and keyword hits; missing signals drop out of the weighting.
+ * @param {Object} signals - { durationMs, energyDb: number[] | null, scenes: [{ at_ms }] | null, keywordHits: [{ start_ms, end_ms }] | null }
+ * @param {Object} options - { maxResults, minDurationMs, maxDurationMs, preRollMs, postRollMs, minScore }
+ * @returns {Array<{ start_ms: number, end_ms: number, score: number, reasons: string[] }>} Best first
+ */
+function scoreHighlights(signals, options = {}) {
+  const maxResults = Math.round(clampOption(options.maxResults, 1, 100, 10));
+  const minDurationMs = clampOption(options.minDurationMs, 1000, 600000, 5000);
+  const maxDurationMs = clampOption(options.maxDurationMs, minDurationMs, 600000, Math.max(minDurationMs, 30000));
+  const preRollMs = clampOption(options.preRollMs, 0, 60000, 3000);
+  const postRollMs = clampOption(options.postRollMs, 0, 60000, 5000);
+  const minScore = clampOption(options.minScore, 0, 1, 0.35);
+  const durationMs = signals.durationMs;
+  const count = Math.ceil(durationMs / HIGHLIGHT_WINDOW_MS);
+  if (count <= 0) return [];
+
+  const sources = {};
+  if (signals.energyDb && signals.energyDb.length > 0) {
+    // Robust z-score: spikes above the typical level, capped at 3 deviations
+    const levels = signals.energyDb;
+    const center = median(levels);
+    const spread = Math.max(1, median(levels.map((level) => Math.abs(level - center))) * 1.4826);
+    sources.audio = Array.from({ length: count }, (_, i) => {
+      const z = ((levels[i] ?? center) - center) / spread;
+      return Math.max(0, Math.min(3, z)) / 3;
+    });
+  }
+  if (signals.scenes) {
+    // Cuts within two seconds either side; three or more counts as fully busy
+    const density = new Float32Array(count);
+    for (const change of signals.scenes) {
+      const index = Math.floor(change.at_ms / HIGHLIGHT_WINDOW_MS);
+      for (let i = Math.max(0, index - 2); i <= Math.min(count - 1, index + 2); i++) {
+        density[i] += 1;
+      }
+    }
+    sources.scene = Array.from(density, (value) => Math.min(3, value) / 3);
+  }
+  if (signals.keywordHits && signals.keywordHits.length > 0) {
+    const hits = new Float32Array(count);
+    for (const hit of signals.keywordHits) {
+      const first = Math.max(0, Math.floor(hit.start_ms / HIGHLIGHT_WINDOW_MS));
+      const last = Math.min(count - 1, Math.floor(Math.max(hit.start_ms, hit.end_ms - 1) / HIGHLIGHT_WINDOW_MS));
+      for (let i = first; i <= last; i++) {
+        hits[i] = 1;
+      }
+    }
+    sources.keyword = Array.from(hits);
+  }
+
+  const active = Object.keys(sources);
+  if (active.length === 0) return [];
+  const totalWeight = active.reduce((sum, name) => sum + HIGHLIGHT_WEIGHTS[name], 0);
+  const scores = Array.from({ length: count }, (_, i) =>
+    active.reduce((sum, name) => sum + sources[name][i] * HIGHLIGHT_WEIGHTS[name], 0) / totalWeight
+  );
+
+  const seeds = scores
+    .map((score, index) => ({ score, index }))
+    .filter((seed) => seed.score >= minScore)
+    .sort((a, b) => b.score - a.score || a.index - b.index);
+
+  const ranges = [];
+  for (const seed of seeds) {
+    if (ranges.length >= maxResults) break;
+    const seedMs = seed.index * HIGHLIGHT_WINDOW_MS;
+    if (ranges.some((range) => seedMs >= range.start_ms && seedMs < range.end_ms)) continue;
+
+    // Grow over neighbouring windows that stay above half the seed's score
+    let first = seed.index;
+    let last = seed.index;
+    const keep = seed.score / 2;
+    while (first > 0 && scores[first - 1] >= keep && (seed.index - first + 1) * HIGHLIGHT_WINDOW_MS < maxDurationMs / 2) first--;
+    while (last < count - 1 && scores[last + 1] >= keep && (last - seed.index + 1) * HIGHLIGHT_WINDOW_MS < maxDurationMs / 2) last++;
+
+    let start = Math.max(0, first * HIGHLIGHT_WINDOW_MS - preRollMs);
+    let end = Math.min(durationMs, (last + 1) * HIGHLIGHT_WINDOW_MS + postRollMs);
+    if (end - start < minDurationMs) {
+      const pad = (minDurationMs - (end - start)) / 2;
+      start = Math.max(0, start - pad);
+      end = Math.min(durationMs, start + minDurationMs);
+      start = Math.max(0, end - minDurationMs);
+    }
+    if (end - start > maxDurationMs) {
+      start = Math.max(start, Math.min(seedMs - preRollMs, end - maxDurationMs));
+      end = start + maxDurationMs;
+    }
+    // Don't overlap a better range already chosen
+    for (const range of ranges) {
+      if (start < range.end_ms && end > range.end_ms) start = range.end_ms;
+      if (end > range.start_ms && start < range.start_ms) end = range.start_ms;
+    }
+    if (end - start < HIGHLIGHT_WINDOW_MS) continue;
+
+    const reasons = active.filter((name) => {
+      for (let i = first; i <= last; i++) {
+        if (sources[name][i] >= 0.5) return true;
+      }
+      return false;
+    });
+    ranges.push({
+      start_ms: Math.round(start),
+      end_ms: Math.round(end),
+      score: Math.round(seed.score * 1000) / 1000,
+      reasons,
+    });
+  }
+  return ranges;
+}
+
+/**
+ * Suggest highlight ranges in a long recording
+ * @param {string} inputPath - Media file
+ * @param {Object} info - Probed metadata ({ duration_ms, has_audio, codec_video })
+ * @param {Object} options - { transcript, keywords, sceneThreshold } plus scoreHighlights options
+ * @param {Function} trackProcessFn - Optional function to track ffmpeg processes
+ */
+async function suggestHighlights(inputPath, info, options = {}, trackProcessFn = null) {
+  const durationMs = info.duration_ms;
+  if (!durationMs) {
+    throw new Error('File has no duration to analyze');
+  }
+  const energyDb = info.has_audio
+    ? windowEnergyDb(await decodePcm(inputPath, HIGHLIGHT_SAMPLE_RATE, null, trackProcessFn), HIGHLIGHT_SAMPLE_RATE)
+    : null;
+  const scenes = info.codec_video
+    ? await detectSceneChanges(inputPath, clampOption(options.sceneThreshold, 0.05, 1, 0.3), trackProcessFn)
+    : null;
+  const keywordHits = findKeywordHits(options.transcript, options.keywords);
+
+  return {
+    duration_ms: durationMs,
+    signals: {
+      audio: !!energyDb,
+      scene: !!scenes,
+      keyword: keywordHits.length > 0,
+    },
+    highlights: scoreHighlights({ durationMs, energyDb, scenes, keywordHits }, options),
+  };
+}
+
+// Clip gain analysis: quieter than this counts as silence and gets no suggestion
+const GAIN_SILENCE_LUFS = -70;
+const MAX_SUGGESTED_GAIN_DB = 24;
+
+/**
+ * Integrated loudness (EBU R128) of a source range, with the clip's channel routing applied
+ * @returns {Promise<number|null>} LUFS, or null when the range is silent
+ */
+async function measureLoudness(inputPath, inMs, outMs, channelFilters = [], trackProcessFn = null) {
+  const output = await runFfmpegOutput([
+    '-hide_banner', '-loglevel', 'error',
+    '-ss', String(inMs / 1000),
+    '-t', String((outMs - inMs) / 1000),
+    '-i', inputPath,
+    '-vn', '-sn',
+    '-af', [...channelFilters, 'ebur128=metadata=1', 'ametadata=print:key=lavfi.r128.I:file=-'].join(','),
+    '-f', 'null', '-',
+  ], trackProcessFn);
+
+  // Every frame reports the loudness integrated so far; the last one covers the whole range
+  let integrated = null;
+  for (const match of output.toString().matchAll(/lavfi\.r128\.I=(\S+)/g)) {
+    integrated = parseFloat(match[1]);
+  }
+  return Number.isFinite(integrated) && integrated > GAIN_SILENCE_LUFS ? integrated : null;
+}
+
+/**
+ * Dialogue clips of a plan, one entry per clip with the source range it plays
+ * Dialogue is main-track audio (including detached J/L-cut audio) and voice-track clips, as in stems.
+ * @param {Function} hasAudio - (clip) => whether the clip's source has audio
+ * @returns {Array<{ clipId: string, trackId: string, srcPath: string, inMs: number, outMs: number,
+ *   audioChannels: Object|undefined, gainDb: number }>}
+ */
+function dialogueClips(plan, hasAudio) {
+  const detachedIds = new Set(plan.mainTrack.filter((clip) => clip.audioDetached).map((clip) => clip.clipId));
+  const candidates = [
+    ...plan.mainTrack.filter((clip) => !clip.freezeFrame && !clip.audioDetached),
+    ...(plan.audioTrack || []).filter((clip) =>
+      detachedIds.has(clip.clipId) || stemForTrack((plan.audioTracks || {})[clip.trackId]) === 'dialogue'),
+  ].filter((clip) => clip.clipId && clip.srcPath && !clip.generator && hasAudio(clip));
+
+  // Freeze frames split a main clip into pieces; measure its whole played range once
+  const byClip = new Map();
+  for (const clip of candidates) {
+    const entry = byClip.get(clip.clipId);
+    if (entry) {
+      entry.inMs = Math.min(entry.inMs, clip.inMs);
+      entry.outMs = Math.max(entry.outMs, clip.outMs);
+    } else {
+      byClip.set(clip.clipId, {
+        clipId: clip.clipId,
+        trackId: clip.trackId,
+        srcPath: clip.srcPath,
+        inMs: clip.inMs,
+        outMs: clip.outMs,
+        audioChannels: clip.audioChannels,
+        gainDb: clip.gainDb || 0,
+      });
+    }
+  }
+  return [...byClip.values()];
+}
+
+/**
+ * Gain per dialogue clip that brings them all to one loudness
+ * Clips are measured without their current gain, so each suggestion is the clip's new gainDb.
+ * The target is options.targetLufs, else the median clip, which evens levels out without
+ * making the program louder or quieter overall.
+ * @param {Object} plan - Edit plan
+ * @param {Object} options - { targetLufs }
+ * @param {Function} probeSource - (srcPath) => Promise<metadata|null>
+ * @param {Function} trackProcessFn - Optional function to track ffmpeg processes
+ * @returns {Promise<{ target_lufs: number|null, clips: Array<{ clip_id: string, track_id: string,
+ *   loudness_lufs: number|null, current_gain_db: number, suggested_gain_db: number }> }>}
+ */
+async function analyzeClipGain(plan, options = {}, probeSource, trackProcessFn = null) {
+  const sources = new Map();
+  for (const clip of [...plan.mainTrack, ...(plan.audioTrack || [])]) {
+    if (clip.srcPath && !sources.has(clip.srcPath)) {
+      sources.set(clip.srcPath, await probeSource(clip.srcPath));
+    }
+  }
+  const clips = dialogueClips(plan, (clip) => {
+    const meta = sources.get(clip.srcPath);
+    return !!meta && !!meta.has_audio;
+  });
+
+  const measured = [];
+  for (const clip of clips) {
+    const loudness = await measureLoudness(clip.srcPath, clip.inMs, clip.outMs, buildChannelMapFilters(clip.audioChannels), trackProcessFn);
+    measured.push({ ...clip, loudness });
+  }
+
+  const round = (value) => Math.round(value * 10) / 10;
+  const levels = measured.map((clip) => clip.loudness).filter((level) => level !== null);
+  const target = Number.isFinite(options.targetLufs)
+    ? clampOption(options.targetLufs, -40, -5, -16)
+    : levels.length > 0 ? round(median(levels)) : null;
+
+  return {
+    target_lufs: target,
+    clips: measured.map((clip) => ({
+      clip_id: clip.clipId,
+      track_id: clip.trackId,
+      loudness_lufs: clip.loudness === null ? null : round(clip.loudness),
+      current_gain_db: clip.gainDb,
+      suggested_gain_db: clip.loudness === null || target === null
+        ? clip.gainDb
+        : round(clampOption(target - clip.loudness, -MAX_SUGGESTED_GAIN_DB, MAX_SUGGESTED_GAIN_DB, 0)),
+    })),
+  };
+}
+
+// A/V drift: less than this (about a frame) isn't worth correcting
+const DRIFT_THRESHOLD_MS = 40;
+
+/**
+ * Audio/video drift of a recording from its streams and audio packets
+ * Drift is how much longer the audio runs than the video, beyond any offset at the start. Gaps are
+ * stretches the audio timestamps cover without samples (dropped audio): those are fixed by
+ * resampling against the timestamps; a steady clock mismatch is fixed by changing the audio tempo.
+ * @param {Object} probe - ffprobe { streams, format }
+ * @param {Array} packets - Audio packets of the first audio stream ({ pts_time, duration_time })
+ * @returns {{ video_ms: number, audio_ms: number, start_offset_ms: number, drift_ms: number, gap_ms: number,
+ *   correction: { method: 'resample' }|{ method: 'atempo', driftMs: number, durationMs: number }|null }}
+ */
+function estimateAvDrift({ streams = [], format = {} }, packets = []) {
+  const video = streams.find((stream) => stream.codec_type === 'video' && !(stream.disposition && stream.disposition.attached_pic));
+  const timed = packets.filter((packet) => Number.isFinite(parseFloat(packet.pts_time)));
+  if (!video || timed.length === 0) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, 'Drift needs a file with both video and audio');
+  }
+  const seconds = (value) => parseFloat(value) || 0;
+  const videoStart = seconds(video.start_time);
+  const videoSec = seconds(video.duration) || seconds(format.duration) - videoStart;
+  const first = timed[0];
+  const last = timed[timed.length - 1];
+  const audioStart = seconds(first.pts_time);
+  const audioSec = seconds(last.pts_time) + seconds(last.duration_time) - audioStart;
+  const samplesSec = timed.reduce((sum, packet) => sum + seconds(packet.duration_time), 0);
+
+  const ms = (value) => Math.round(value * 1000);
+  const driftMs = ms(audioSec - videoSec);
+  const gapMs = ms(audioSec - samplesSec);
+  let correction = null;
+  if (Math.abs(gapMs) >= DRIFT_THRESHOLD_MS) {
+    correction = { method: 'resample' };
+  } else if (Math.abs(driftMs) >= DRIFT_THRESHOLD_MS && videoSec > 0) {
+    correction = { method: 'atempo', driftMs, durationMs: ms(videoSec) };
+  }
+  return {
+    video_ms: ms(videoSec),
+    audio_ms: ms(audioSec),
+    start_offset_ms: ms(audioStart - videoStart),
+    drift_ms: driftMs,
+    gap_ms: gapMs,
+    correction,
+  };
+}
+
+/**
+ * Measure a recording's A/V drift (reads every audio packet's timestamp, so no decoding)
+ * @param {Object} options - { timeoutMs, signal } for ffprobe
+ */
+async function measureAvDrift(inputPath, options = {}) {
+  const probe = await runFfprobeJson(['-show_format', '-show_streams', inputPath], options);
+  const { packets = [] } = await runFfprobeJson(
+    ['-select_streams', 'a:0', '-show_entries', 'packet=pts_time,duration_time', inputPath],
+    { timeoutMs: 120000, ...options }
+  );
+  return estimateAvDrift(probe, packets);
+}
+
+module.exports = {
+  decodePcm,
+  computePeaks,
+  programAudioClips,
+  generateTimelineWaveform,
+  fft,
+  crossCorrelate,
+  alignClips,
+  onsetEnvelope,
+  pickOnsets,
+  estimateTempo,
+  trackBeats,
+  analyzeBeats,
+  speechFrames,
+  speechSegments,
+  detectSpeech,
+  detectSceneChanges,
+  windowEnergyDb,
+  findKeywordHits,
+  scoreHighlights,
+  suggestHighlights,
+  parseCropDetect,
+  unionCropRects,
+  detectCrop,
+  measureLoudness,
+  dialogueClips,
+  analyzeClipGain,
+  estimateAvDrift,
+  measureAvDrift,
+};
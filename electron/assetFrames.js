@@ -0,0 +1,159 @@
+const fs = require('fs-extra');
+const path = require('path');
+const crypto = require('crypto');
+
+/**
+ * Asset frames - single source frames for the clip trimmer, cached by (asset, frame, width)
+ * Kept apart from plan previews (which key on clip content and effects) with their own
+ * size budget, so trimming through a file evicts its own frames rather than timeline previews.
+ */
+
+// Disk budget for cached trimmer frames; least recently used frames go first
+const DEFAULT_MAX_BYTES = 256 * 1024 * 1024;
+
+/**
+ * Requested width rounded to an even size the scaler accepts
+ */
+function normalizeFrameWidth(maxWidth) {
+  return Number.isFinite(maxWidth) ? Math.max(64, Math.min(1920, Math.round(maxWidth / 2) * 2)) : 320;
+}
+
+/**
+ * Frame index containing a source time, and the time to seek to for it (mid-frame, clear of rounding at the edges)
+ * Without a known frame rate frames are taken per millisecond.
+ */
+function frameAt(atMs, fps) {
+  const rate = fps > 0 ? fps : 1000;
+  const index = Math.floor((Math.max(0, atMs) * rate) / 1000 + 1e-6);
+  return { index, seekMs: ((index + 0.5) * 1000) / rate };
+}
+
+/**
+ * Asset identity: path plus size and mtime, so a replaced file doesn't serve stale frames
+ */
+async function assetKey(srcPath) {
+  const stats = await fs.stat(srcPath);
+  return crypto
+    .createHash('sha1')
+    .update(`${srcPath}:${stats.size}:${stats.mtimeMs}`)
+    .digest('hex')
+    .slice(0, 16);
+}
+
+class AssetFrameCache {
+  /**
+   * @param {string} dir - Directory holding one subdirectory of frames per asset
+   * @param {Object} options - { maxBytes }
+   */
+  constructor(dir, options = {}) {
+    this.dir = dir;
+    this.maxBytes = options.maxBytes || DEFAULT_MAX_BYTES;
+    // Insertion order is recency order: touched entries are re-inserted at the end
+    this.entries = new Map();
+    this.totalBytes = 0;
+    this.pending = new Map();
+  }
+
+  /**
+   * Pick up frames left by earlier runs (oldest first, by mtime)
+   */
+  async load() {
+    const found = [];
+    const assets = await fs.readdir(this.dir).catch(() => []);
+    for (const asset of assets) {
+      const assetDir = path.join(this.dir, asset);
+      const files = await fs.readdir(assetDir).catch(() => []);
+      for (const file of files) {
+        const filePath = path.join(assetDir, file);
+        const stats = await fs.stat(filePath).catch(() => null);
+        if (stats && stats.isFile()) {
+          found.push({ filePath, size: stats.size, mtimeMs: stats.mtimeMs });
+        }
+      }
+    }
+    found.sort((a, b) => a.mtimeMs - b.mtimeMs);
+    for (const { filePath, size } of found) {
+      this.entries.set(filePath, size);
+      this.totalBytes += size;
+    }
+    await this.evict();
+  }
+
+  framePath(key, width, index) {
+    return path.join(this.dir, key, `${width}_${index}.jpg`);
+  }
+
+  touch(filePath, size) {
+    if (this.entries.has(filePath)) {
+      this.totalBytes -= this.entries.get(filePath);
+      this.entries.delete(filePath);
+    }
+    this.entries.set(filePath, size);
+    this.totalBytes += size;
+  }
+
+  /**
+   * Remove least recently used frames until the cache fits its budget
+   */
+  async evict() {
+    for (const [filePath, size] of this.entries) {
+      if (this.totalBytes <= this.maxBytes) break;
+      this.entries.delete(filePath);
+      this.totalBytes -= size;
+      await fs.remove(filePath);
+    }
+  }
+
+  /**
+   * Cached frame of an asset, extracting it on a miss
+   * Concurrent requests for the same frame share one extraction.
+   * @param {string} srcPath - Media file
+   * @param {number} atMs - Source time
+   * @param {number} maxWidth - Frame width (never upscaled past the source)
+   * @param {Object} options - { fps: source frame rate, extract: (srcPath, seekMs, outputPath, width) => Promise }
+   * @returns {Promise<{ path: string, frame: number, at_ms: number, width: number, cached: boolean }>}
+   */
+  async get(srcPath, atMs, maxWidth, options) {
+    const width = normalizeFrameWidth(maxWidth);
+    const { index, seekMs } = frameAt(atMs, options.fps);
+    const key = await assetKey(srcPath);
+    const outputPath = this.framePath(key, width, index);
+    const result = { path: outputPath, frame: index, at_ms: Math.round(seekMs), width };
+
+    if (this.pending.has(outputPath)) {
+      await this.pending.get(outputPath);
+      return { ...result, cached: true };
+    }
+    if (this.entries.has(outputPath) && (await fs.pathExists(outputPath))) {
+      this.touch(outputPath, this.entries.get(outputPath));
+      return { ...result, cached: true };
+    }
+
+    const extraction = (async () => {
+      await fs.ensureDir(path.dirname(outputPath));
+      const partialPath = `${outputPath}.partial.jpg`;
+      try {
+        await options.extract(srcPath, seekMs, partialPath, width);
+        await fs.move(partialPath, outputPath, { overwrite: true });
+      } finally {
+        await fs.remove(partialPath);
+      }
+      this.touch(outputPath, (await fs.stat(outputPath)).size);
+      await this.evict();
+    })();
+    this.pending.set(outputPath, extraction);
+    try {
+      await extraction;
+    } finally {
+      this.pending.delete(outputPath);
+    }
+    return { ...result, cached: false };
+  }
+}
+
+module.exports = {
+  DEFAULT_MAX_BYTES,
+  normalizeFrameWidth,
+  frameAt,
+  AssetFrameCache,
+};
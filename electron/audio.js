@@ -0,0 +1,648 @@
+const ffmpeg = require('fluent-ffmpeg');
+const { clampOption, escapeFilterPath } = require('./filterUtils');
+const { pathToFileUrl } = require('./paths');
+const { audioQualityOptions } = require('./encoding');
+const { pluginEffectFilters } = require('./effectPlugins');
+
+/**
+ * Compressor presets - tuned for common spoken-word and music use cases
+ */
+const COMPRESSOR_PRESETS = {
+  podcast_voice: { thresholdDb: -18, ratio: 3, attackMs: 5, releaseMs: 100, makeupDb: 4 },
+  broadcast: { thresholdDb: -24, ratio: 4, attackMs: 10, releaseMs: 200, makeupDb: 6 },
+  gentle: { thresholdDb: -12, ratio: 2, attackMs: 20, releaseMs: 250, makeupDb: 2 },
+};
+
+/**
+ * Full effect-chain presets that expand into a list of individual effects
+ */
+const AUDIO_EFFECT_PRESETS = {
+  podcast_voice: [
+    { type: 'highpass', frequency: 80 },
+    { type: 'denoise', method: 'afftdn', strength: 12 },
+    { type: 'deesser', intensity: 0.4 },
+    { type: 'compressor', preset: 'podcast_voice' },
+  ],
+  voice_cleanup: [
+    { type: 'highpass', frequency: 100 },
+    { type: 'denoise', method: 'afftdn', strength: 20 },
+  ],
+};
+
+/**
+ * Convert decibels to a linear gain factor
+ */
+function dbToLinear(db) {
+  return Math.pow(10, db / 20);
+}
+
+/**
+ * Translate a single audio effect descriptor into ffmpeg filter strings
+ */
+function audioEffectToFilters(effect) {
+  if (!effect || !effect.type) {
+    return [];
+  }
+
+  switch (effect.type) {
+    case 'denoise': {
+      if (effect.method === 'arnndn') {
+        // arnndn requires an RNNoise model file - skip silently when not provided
+        if (!effect.model) {
+          console.warn('arnndn denoise requested without a model file, skipping');
+          return [];
+        }
+        const mix = clampOption(effect.mix, -1, 1, 1);
+        return [`arnndn=m='${escapeFilterPath(effect.model)}':mix=${mix}`];
+      }
+      const strength = clampOption(effect.strength, 0.01, 97, 12);
+      const floor = clampOption(effect.noiseFloorDb, -80, -20, -50);
+      return [`afftdn=nr=${strength}:nf=${floor}`];
+    }
+
+    case 'highpass': {
+      const frequency = clampOption(effect.frequency, 20, 2000, 80);
+      return [`highpass=f=${frequency}`];
+    }
+
+    case 'lowpass': {
+      const frequency = clampOption(effect.frequency, 1000, 20000, 12000);
+      return [`lowpass=f=${frequency}`];
+    }
+
+    case 'deesser': {
+      const intensity = clampOption(effect.intensity, 0, 1, 0.4);
+      return [`deesser=i=${intensity}`];
+    }
+
+    case 'compressor': {
+      const preset = COMPRESSOR_PRESETS[effect.preset] || COMPRESSOR_PRESETS.podcast_voice;
+      const thresholdDb = clampOption(effect.thresholdDb, -60, 0, preset.thresholdDb);
+      const ratio = clampOption(effect.ratio, 1, 20, preset.ratio);
+      const attackMs = clampOption(effect.attackMs, 0.01, 2000, preset.attackMs);
+      const releaseMs = clampOption(effect.releaseMs, 0.01, 9000, preset.releaseMs);
+      const makeupDb = clampOption(effect.makeupDb, 0, 36, preset.makeupDb);
+      return [
+        `acompressor=threshold=${dbToLinear(thresholdDb).toFixed(6)}:ratio=${ratio}` +
+          `:attack=${attackMs}:release=${releaseMs}:makeup=${dbToLinear(makeupDb).toFixed(4)}`,
+      ];
+    }
+
+    case 'preset': {
+      const chain = AUDIO_EFFECT_PRESETS[effect.name];
+      if (!chain) {
+        console.warn(`Unknown audio effect preset: ${effect.name}`);
+        return [];
+      }
+      return chain.flatMap(audioEffectToFilters);
+    }
+
+    case 'plugin':
+      return pluginEffectFilters(effect, 'audio');
+
+    default:
+      console.warn(`Unknown audio effect type: ${effect.type}`);
+      return [];
+  }
+}
+
+/**
+ * Build the ffmpeg audio filter chain for a clip's effect list
+ * @param {Array} effects - Audio effect descriptors from the project
+ * @returns {string[]} Filter strings, in order
+ */
+function buildAudioEffectFilters(effects) {
+  if (!Array.isArray(effects)) {
+    return [];
+  }
+  return effects.flatMap(audioEffectToFilters);
+}
+
+/**
+ * Source channel routing a clip's audio plays with (run before the stereo conform)
+ * select plays one channel on both sides, downmix mixes the channels to mono (weighted when
+ * gains are given) and split routes two source channels to left and right. Screen recordings
+ * with the mic on one channel and system audio on the other use select or split.
+ * @param {Object} map - Clip audioChannels ({ mode, channel, gains, left, right })
+ * @returns {string[]} Filter strings (empty to play the source as it is)
+ */
+function buildChannelMapFilters(map) {
+  if (!map || typeof map !== 'object') {
+    return [];
+  }
+  const channel = (value) => Math.round(clampOption(value, 0, 63, 0));
+  switch (map.mode) {
+    case 'select':
+      return [`pan=mono|c0=c${channel(map.channel)}`];
+    case 'downmix':
+      if (Array.isArray(map.gains) && map.gains.length > 0) {
+        const terms = map.gains.map((gain, i) => `${clampOption(gain, 0, 4, 1)}*c${i}`);
+        return [`pan=mono|c0=${terms.join('+')}`];
+      }
+      return ['aformat=channel_layouts=mono'];
+    case 'split':
+      return [`channelmap=map=${channel(map.left)}-FL|${channel(map.right)}-FR:channel_layout=stereo`];
+    default:
+      return [];
+  }
+}
+
+/**
+ * Highest source channel a channel map reads (-1 when it reads none by index)
+ */
+function channelMapMaxChannel(map) {
+  if (!map || typeof map !== 'object') return -1;
+  if (map.mode === 'select') return Math.round(clampOption(map.channel, 0, 63, 0));
+  if (map.mode === 'split') return Math.round(Math.max(clampOption(map.left, 0, 63, 0), clampOption(map.right, 0, 63, 0)));
+  if (map.mode === 'downmix' && Array.isArray(map.gains)) return map.gains.length - 1;
+  return -1;
+}
+
+/**
+ * Clip gain as a volume filter (none at 0 dB)
+ */
+function buildGainFilters(gainDb) {
+  const db = clampOption(gainDb, -60, 24, 0);
+  return db === 0 ? [] : [`volume=${db}dB`];
+}
+
+// Most samples per second aresample may add or drop to follow the timestamps
+const ASYNC_MAX_COMPENSATION = 1000;
+
+/**
+ * Clip speed as atempo (pitch kept; none at 1x)
+ * One atempo covers 0.5x-2x, so speeds outside that chain several.
+ */
+function buildSpeedFilters(speed) {
+  if (!speed || speed === 1) {
+    return [];
+  }
+  const filters = [];
+  let tempo = speed;
+  for (; tempo < 0.5; tempo /= 0.5) filters.push('atempo=0.5');
+  for (; tempo > 2; tempo /= 2) filters.push('atempo=2');
+  filters.push(`atempo=${Number(tempo.toFixed(6))}`);
+  return filters;
+}
+
+/**
+ * Drift correction for a clip's audio, from measure-av-drift
+ * atempo plays audio that runs long (or short) against the video at the matching speed, after
+ * skipping (or padding) the drift built up before the in point; resample lets aresample fill
+ * gaps and drop overlaps by timestamp.
+ * @param {Object} sync - clip.audioSync: { method: 'atempo', driftMs, durationMs } or { method: 'resample' }
+ * @param {number} inMs - Source position the audio starts at (the input is already seeked there)
+ * @returns {string[]} Filter strings (empty when there's nothing to correct)
+ */
+function buildDriftFilters(sync, inMs = 0) {
+  if (!sync || typeof sync !== 'object') {
+    return [];
+  }
+  if (sync.method === 'resample') {
+    return [`aresample=async=${ASYNC_MAX_COMPENSATION}:first_pts=0`];
+  }
+  if (sync.method !== 'atempo' || !Number.isFinite(sync.driftMs) || !(sync.durationMs > 0) || sync.driftMs === 0) {
+    return [];
+  }
+  const tempo = clampOption((sync.durationMs + sync.driftMs) / sync.durationMs, 0.5, 2, 1);
+  const leadMs = Math.round(inMs * (tempo - 1));
+  const filters = [];
+  if (leadMs > 0) {
+    filters.push(`atrim=start=${leadMs / 1000}`, 'asetpts=PTS-STARTPTS');
+  } else if (leadMs < 0) {
+    filters.push(`adelay=${-leadMs}:all=1`);
+  }
+  filters.push(`atempo=${Number(tempo.toFixed(6))}`);
+  return filters;
+}
+
+/**
+ * A clip's own audio filters: drift correction, speed, channel routing, gain, then its effects
+ */
+function buildClipAudioFilters(clip) {
+  return [
+    ...buildDriftFilters(clip.audioSync, clip.inMs),
+    ...buildSpeedFilters(clip.speed),
+    ...buildChannelMapFilters(clip.audioChannels),
+    ...buildGainFilters(clip.gainDb),
+    ...buildAudioEffectFilters(clip.audioEffects),
+  ];
+}
+
+/**
+ * Audio format every export segment is conformed to before concat
+ * The concat demuxer takes stream parameters from the first segment, so mixed
+ * rates or layouts (44.1 kHz stereo next to 48 kHz mono) corrupt the output.
+ */
+const AUDIO_CONFORM = {
+  sampleRate: 48000,
+  channelLayout: 'stereo',
+  channels: 2,
+};
+
+/**
+ * Filters that resample to the conform rate and normalize the channel layout (run after effects)
+ */
+function buildConformFilters() {
+  return [
+    `aresample=${AUDIO_CONFORM.sampleRate}`,
+    `aformat=sample_rates=${AUDIO_CONFORM.sampleRate}:channel_layouts=${AUDIO_CONFORM.channelLayout}`,
+  ];
+}
+
+/**
+ * Whether probed audio already matches the conform format (so it can be stream copied)
+ */
+function audioConforms(meta) {
+  return !meta || !meta.has_audio || (meta.sample_rate === AUDIO_CONFORM.sampleRate && meta.channels === AUDIO_CONFORM.channels);
+}
+
+/**
+ * Default sidechain ducking parameters for music tracks
+ */
+const DEFAULT_DUCKING = {
+  thresholdDb: -30,
+  ratio: 8,
+  attackMs: 20,
+  releaseMs: 400,
+};
+
+/**
+ * Translate a track's ducking settings into a sidechaincompress filter
+ */
+function buildDuckingFilter(ducking) {
+  const thresholdDb = clampOption(ducking.thresholdDb, -60, 0, DEFAULT_DUCKING.thresholdDb);
+  const ratio = clampOption(ducking.ratio, 1, 20, DEFAULT_DUCKING.ratio);
+  const attackMs = clampOption(ducking.attackMs, 0.01, 2000, DEFAULT_DUCKING.attackMs);
+  const releaseMs = clampOption(ducking.releaseMs, 0.01, 9000, DEFAULT_DUCKING.releaseMs);
+  return `sidechaincompress=threshold=${dbToLinear(thresholdDb).toFixed(6)}:ratio=${ratio}` +
+    `:attack=${attackMs}:release=${releaseMs}`;
+}
+
+/**
+ * Build filter_complex strings mixing audio-track clips over the base program audio
+ * Input 0 is the program (video) file; audio clip inputs start at index 1.
+ * Music tracks with ducking enabled are compressed using voice tracks as the sidechain key.
+ * @param {Array} audioClips - Plan clips from audio tracks
+ * @param {number} timelineStartMs - Timeline position that maps to t=0 in the output
+ * @param {number} durationSec - Total program duration in seconds
+ * @param {boolean} baseHasAudio - Whether input 0 carries an audio stream
+ * @param {Object} audioTracks - Map of trackId -> { audioRole, ducking }
+ * @returns {{ filters: string[], output: string }}
+ */
+function buildMixdownFilters(audioClips, timelineStartMs, durationSec, baseHasAudio, audioTracks = {}) {
+  const filters = [];
+  const mixInputs = [];
+
+  if (baseHasAudio) {
+    filters.push(`[0:a]aformat=channel_layouts=stereo,atrim=duration=${durationSec}[base]`);
+  } else {
+    filters.push(`anullsrc=r=48000:cl=stereo,atrim=duration=${durationSec}[base]`);
+  }
+  mixInputs.push('[base]');
+
+  // Render each clip into its own delayed stream, grouped by track
+  const trackStreams = new Map();
+  audioClips.forEach((clip, idx) => {
+    let inMs = clip.inMs;
+    let delayMs = clip.startMs - timelineStartMs;
+
+    // Clips starting before the program begins lose their head (in source time at the clip's speed)
+    if (delayMs < 0) {
+      inMs -= delayMs * (clip.speed || 1);
+      delayMs = 0;
+    }
+    if (inMs >= clip.outMs) {
+      return;
+    }
+
+    const chain = [
+      `atrim=start=${inMs / 1000}:end=${clip.outMs / 1000}`,
+      'asetpts=PTS-STARTPTS',
+      ...buildSpeedFilters(clip.speed),
+      ...buildChannelMapFilters(clip.audioChannels),
+      'aformat=channel_layouts=stereo',
+    ];
+
+    // Looping clips repeat the trimmed piece until the timeline duration is filled
+    if (clip.loop) {
+      const pieceSamples = Math.round(((clip.outMs - inMs) / 1000) * 48000);
+      const fillSec = (clip.endMs - clip.startMs - (inMs - clip.inMs)) / 1000;
+      chain.push(
+        'aresample=48000',
+        `aloop=loop=-1:size=${pieceSamples}`,
+        `atrim=duration=${fillSec}`,
+        'asetpts=PTS-STARTPTS'
+      );
+    }
+
+    chain.push(
+      ...buildGainFilters(clip.gainDb),
+      ...buildAudioEffectFilters(clip.audioEffects),
+      `adelay=${Math.round(delayMs)}|${Math.round(delayMs)}`
+    );
+    const label = `a${idx}`;
+    filters.push(`[${idx + 1}:a]${chain.join(',')}[${label}]`);
+
+    const trackId = clip.trackId || '_default';
+    if (!trackStreams.has(trackId)) {
+      trackStreams.set(trackId, []);
+    }
+    trackStreams.get(trackId).push(`[${label}]`);
+  });
+
+  // Collapse each track's clips into a single stream
+  const trackOutputs = [];
+  let trackIdx = 0;
+  for (const [trackId, labels] of trackStreams) {
+    let label = labels[0];
+    if (labels.length > 1) {
+      label = `[t${trackIdx}]`;
+      filters.push(`${labels.join('')}amix=inputs=${labels.length}:duration=longest:dropout_transition=0:normalize=0${label}`);
+    }
+    trackOutputs.push({ trackId, label, settings: audioTracks[trackId] || {} });
+    trackIdx++;
+  }
+
+  const voiceTracks = trackOutputs.filter((t) => t.settings.audioRole === 'voice');
+  const duckedTracks = trackOutputs.filter(
+    (t) => t.settings.audioRole === 'music' && t.settings.ducking && t.settings.ducking.enabled
+  );
+
+  if (voiceTracks.length > 0 && duckedTracks.length > 0) {
+    // Combine voice tracks into one key and split it: one copy per ducked track plus the mix itself
+    let voiceLabel = voiceTracks[0].label;
+    if (voiceTracks.length > 1) {
+      voiceLabel = '[voice]';
+      filters.push(
+        `${voiceTracks.map((t) => t.label).join('')}amix=inputs=${voiceTracks.length}:duration=longest:dropout_transition=0:normalize=0${voiceLabel}`
+      );
+    }
+    const keyLabels = duckedTracks.map((_, i) => `[key${i}]`);
+    filters.push(`${voiceLabel}asplit=${duckedTracks.length + 1}[voicemix]${keyLabels.join('')}`);
+
+    duckedTracks.forEach((track, i) => {
+      const duckedLabel = `[ducked${i}]`;
+      // Pad the key so the ducked track is never cut short when the voice ends first
+      filters.push(`${keyLabels[i]}apad[keypad${i}]`);
+      filters.push(`${track.label}[keypad${i}]${buildDuckingFilter(track.settings.ducking)}${duckedLabel}`);
+      track.label = duckedLabel;
+    });
+
+    mixInputs.push('[voicemix]');
+    for (const track of trackOutputs) {
+      if (!voiceTracks.includes(track)) {
+        mixInputs.push(track.label);
+      }
+    }
+  } else {
+    mixInputs.push(...trackOutputs.map((t) => t.label));
+  }
+
+  filters.push(
+    `${mixInputs.join('')}amix=inputs=${mixInputs.length}:duration=first:dropout_transition=0:normalize=0[aout]`
+  );
+
+  return { filters, output: 'aout' };
+}
+
+/**
+ * Mix audio-track clips into the program file
+ * @param {string} videoPath - Program file produced by concatenation
+ * @param {Array} audioClips - Plan clips from audio tracks
+ * @param {number} timelineStartMs - Timeline position of the program start
+ * @param {number} durationSec - Program duration in seconds
+ * @param {boolean} baseHasAudio - Whether the program file has an audio stream
+ * @param {Object} audioTracks - Map of trackId -> { audioRole, ducking }
+ * @param {string} outputPath - Mixed output file path
+ * @param {Object} encoding - Resolved encoder settings (see encoding.js)
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ */
+function mixdownAudio(videoPath, audioClips, timelineStartMs, durationSec, baseHasAudio, audioTracks, outputPath, encoding, trackProcessFn) {
+  return new Promise((resolve, reject) => {
+    const command = ffmpeg().input(videoPath);
+    for (const clip of audioClips) {
+      command.input(clip.srcPath);
+    }
+
+    const { filters, output } = buildMixdownFilters(audioClips, timelineStartMs, durationSec, baseHasAudio, audioTracks);
+
+    command
+      .complexFilter(filters)
+      .outputOptions([
+        '-map 0:v?',
+        `-map [${output}]`,
+        '-c:v copy',
+        `-c:a ${encoding.audioCodec}`,
+        ...audioQualityOptions(encoding),
+      ])
+      .output(outputPath)
+      .on('start', () => {
+        if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+      })
+      .on('end', () => resolve())
+      .on('error', (err) => reject(err))
+      .run();
+  });
+}
+
+/**
+ * Crossfade applied at main-track cuts when the project doesn't set one (hides clicks at hard cuts)
+ */
+const DEFAULT_AUDIO_CROSSFADE_MS = 15;
+
+const MAX_AUDIO_CROSSFADE_MS = 2000;
+
+/**
+ * Main-track cuts that get an audio crossfade
+ * A cut is two clips with no gap between them; freeze frames, loops, retimed clips and detached audio are left as hard cuts.
+ * The incoming clip's `audioCrossfadeMs` overrides the project default (0 turns a cut off).
+ * @returns {Array<{ index: number, durationMs: number }>} Index of the incoming clip and the fade length
+ */
+function findCrossfadeCuts(plan) {
+  const projectMs = clampOption(plan.audioCrossfadeMs, 0, MAX_AUDIO_CROSSFADE_MS, DEFAULT_AUDIO_CROSSFADE_MS);
+  const cuts = [];
+  for (let i = 1; i < plan.mainTrack.length; i++) {
+    const outgoing = plan.mainTrack[i - 1];
+    const incoming = plan.mainTrack[i];
+    if (outgoing.endMs !== incoming.startMs) continue;
+    if (outgoing.freezeFrame || incoming.freezeFrame || outgoing.loop || incoming.loop || outgoing.speed || incoming.speed) continue;
+    if (outgoing.audioDetached || incoming.audioDetached) continue;
+    const durationMs = clampOption(incoming.audioCrossfadeMs, 0, MAX_AUDIO_CROSSFADE_MS, projectMs);
+    if (durationMs > 0) {
+      cuts.push({ index: i, durationMs });
+    }
+  }
+  return cuts;
+}
+
+/**
+ * Resolve crossfade cuts against the sources
+ * Each side of a cut lends half the fade from media beyond its edit point, so the program keeps
+ * its length; the fade shrinks to the handles the sources have, and cuts without audio are skipped.
+ * @param {Object} plan - Edit plan
+ * @param {number} timelineStartMs - Timeline position of the program start
+ * @param {Function} probeSource - Memoized probe (null for unprobeable sources)
+ * @returns {Promise<Array<{ atSec: number, halfSec: number, outgoing: Object, incoming: Object }>>}
+ */
+async function resolveCrossfadeCuts(plan, timelineStartMs, probeSource) {
+  const cuts = [];
+  for (const { index, durationMs } of findCrossfadeCuts(plan)) {
+    const outgoing = plan.mainTrack[index - 1];
+    const incoming = plan.mainTrack[index];
+    const outMeta = await probeSource(outgoing.srcPath);
+    const inMeta = await probeSource(incoming.srcPath);
+    if (!outMeta || !inMeta || !outMeta.has_audio || !inMeta.has_audio) continue;
+
+    const halfMs = Math.min(
+      durationMs / 2,
+      incoming.inMs,
+      Math.max(0, (outMeta.duration_ms || 0) - outgoing.outMs),
+      // Leave room for the cut at each clip's other end
+      (outgoing.endMs - outgoing.startMs) / 2,
+      (incoming.endMs - incoming.startMs) / 2
+    );
+    if (halfMs < 1) continue;
+
+    cuts.push({
+      atSec: (incoming.startMs - timelineStartMs) / 1000,
+      halfSec: halfMs / 1000,
+      outgoing: { srcPath: outgoing.srcPath, startSec: outgoing.outMs / 1000 },
+      incoming: { srcPath: incoming.srcPath, startSec: (incoming.inMs - halfMs) / 1000 },
+    });
+  }
+  return cuts;
+}
+
+/**
+ * Build filter_complex strings crossfading the program audio at each cut
+ * Input 0 is the program; each cut adds two handle inputs (outgoing tail, then incoming head),
+ * already seeked to where the handle starts. The program is split at the cuts, each piece is
+ * extended with its handles, and neighbours are joined with acrossfade.
+ * @param {Array} cuts - From resolveCrossfadeCuts, in timeline order
+ * @returns {{ filters: string[], output: string }}
+ */
+function buildCrossfadeFilters(cuts) {
+  const filters = [];
+  const pieceCount = cuts.length + 1;
+  const conform = buildConformFilters().join(',');
+  filters.push(`[0:a]asplit=${pieceCount}${Array.from({ length: pieceCount }, (_, i) => `[p${i}]`).join('')}`);
+
+  for (let i = 0; i < pieceCount; i++) {
+    const start = i === 0 ? 0 : cuts[i - 1].atSec;
+    const end = i < cuts.length ? `:end=${cuts[i].atSec}` : '';
+    filters.push(`[p${i}]atrim=start=${start}${end},asetpts=PTS-STARTPTS[s${i}]`);
+  }
+
+  cuts.forEach((cut, k) => {
+    filters.push(`[${1 + 2 * k}:a]atrim=duration=${cut.halfSec},asetpts=PTS-STARTPTS,${conform}[t${k}]`);
+    filters.push(`[${2 + 2 * k}:a]atrim=duration=${cut.halfSec},asetpts=PTS-STARTPTS,${conform}[h${k}]`);
+  });
+
+  // Piece i = incoming head of cut i-1 + program piece + outgoing tail of cut i
+  for (let i = 0; i < pieceCount; i++) {
+    const parts = [i > 0 && `[h${i - 1}]`, `[s${i}]`, i < cuts.length && `[t${i}]`].filter(Boolean);
+    filters.push(`${parts.join('')}concat=n=${parts.length}:v=0:a=1[q${i}]`);
+  }
+
+  let prev = 'q0';
+  cuts.forEach((cut, k) => {
+    const out = `x${k}`;
+    filters.push(`[${prev}][q${k + 1}]acrossfade=d=${2 * cut.halfSec}:c1=tri:c2=tri[${out}]`);
+    prev = out;
+  });
+
+  return { filters, output: prev };
+}
+
+/**
+ * Replace the program audio with a version crossfaded at every cut (video is stream copied)
+ * @param {string} inputPath - Program file
+ * @param {Array} cuts - From resolveCrossfadeCuts
+ * @param {string} outputPath - Output file path
+ * @param {Object} encoding - Resolved encoder settings (see encoding.js)
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ */
+function crossfadeProgramAudio(inputPath, cuts, outputPath, encoding, trackProcessFn) {
+  return new Promise((resolve, reject) => {
+    const command = ffmpeg().input(inputPath);
+    for (const cut of cuts) {
+      for (const handle of [cut.outgoing, cut.incoming]) {
+        command.input(handle.srcPath).seekInput(handle.startSec).inputOptions([`-t ${cut.halfSec}`]);
+      }
+    }
+
+    const { filters, output } = buildCrossfadeFilters(cuts);
+
+    command
+      .complexFilter(filters)
+      .outputOptions([
+        '-map 0:v?',
+        `-map [${output}]`,
+        '-c:v copy',
+        `-c:a ${encoding.audioCodec}`,
+        ...audioQualityOptions(encoding),
+      ])
+      .output(outputPath)
+      .on('start', () => {
+        if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+      })
+      .on('end', () => resolve())
+      .on('error', (err) => reject(err))
+      .run();
+  });
+}
+
+/**
+ * Render a short audition of an effect chain applied to a source file
+ * @param {string} inputPath - Source media path
+ * @param {Array} effects - Audio effect descriptors
+ * @param {number} startMs - Audition start within the source
+ * @param {number} durationMs - Audition length
+ * @param {string} outputPath - Output audio file path
+ */
+function renderAudioEffectPreview(inputPath, effects, startMs, durationMs, outputPath) {
+  return new Promise((resolve, reject) => {
+    const filters = buildAudioEffectFilters(effects);
+    const command = ffmpeg(inputPath)
+      .seekInput(startMs / 1000)
+      .duration(durationMs / 1000)
+      .noVideo()
+      .audioCodec('aac')
+      .audioBitrate('192k');
+
+    if (filters.length > 0) {
+      command.audioFilters(filters);
+    }
+
+    command
+      .output(outputPath)
+      .on('end', () => resolve(pathToFileUrl(outputPath)))
+      .on('error', (err) => reject(`ffmpeg audio preview failed: ${err.message}`))
+      .run();
+  });
+}
+
+module.exports = {
+  COMPRESSOR_PRESETS,
+  AUDIO_EFFECT_PRESETS,
+  DEFAULT_DUCKING,
+  AUDIO_CONFORM,
+  buildAudioEffectFilters,
+  buildChannelMapFilters,
+  channelMapMaxChannel,
+  buildGainFilters,
+  buildDriftFilters,
+  buildSpeedFilters,
+  buildClipAudioFilters,
+  buildConformFilters,
+  audioConforms,
+  buildMixdownFilters,
+  mixdownAudio,
+  DEFAULT_AUDIO_CROSSFADE_MS,
+  findCrossfadeCuts,
+  resolveCrossfadeCuts,
+  buildCrossfadeFilters,
+  crossfadeProgramAudio,
+  renderAudioEffectPreview,
+};
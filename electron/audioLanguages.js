@@ -0,0 +1,131 @@
+const ffmpeg = require('fluent-ffmpeg');
+const path = require('path');
+const { ErrorCode, StarscapeError } = require('./errors');
+
+/**
+ * Alternate audio tracks - commentary and dubbed versions muxed as extra audio streams
+ * Audio tracks with a commentary or dub role stay out of the main mix (and out of stems) and each
+ * becomes its own language-tagged stream. Commentary plays over the program mix; a dub track is a
+ * complete mix in its language, the way dubbing is delivered, so it stands alone.
+ */
+
+const ALTERNATE_ROLES = ['commentary', 'dub'];
+
+const ROLE_TITLES = {
+  commentary: 'Commentary',
+  dub: 'Dub',
+};
+
+/**
+ * Check an ISO 639-2 language code ("eng", "spa", "jpn"); unset is untagged
+ * @returns {string|null} Lowercase code
+ */
+function normalizeLanguage(language) {
+  if (language === undefined || language === null || language === '') return null;
+  const code = typeof language === 'string' ? language.trim().toLowerCase() : '';
+  if (!/^[a-z]{3}$/.test(code)) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Invalid language code: ${language} (expected ISO 639-2, e.g. "eng")`, {
+      language,
+    });
+  }
+  return code;
+}
+
+/**
+ * Whether a track's clips are muxed as their own stream instead of mixed
+ * @param {Object} trackSettings - { audioRole } from plan.audioTracks
+ */
+function isAlternateTrack(trackSettings = {}) {
+  return ALTERNATE_ROLES.includes(trackSettings.audioRole);
+}
+
+/**
+ * Audio-track clips that go into the main mix (everything but commentary/dub tracks)
+ */
+function mixAudioClips(audioClips, audioTracks = {}) {
+  return audioClips.filter((clip) => !isAlternateTrack(audioTracks[clip.trackId]));
+}
+
+/**
+ * Alternate streams of a plan, one per commentary/dub track that has clips, in track order
+ * @param {Array} audioClips - Plan clips from audio tracks
+ * @param {Object} audioTracks - Map of trackId -> { audioRole, language, name }
+ * @returns {Array<{ trackId: string, role: string, language: string|null, title: string, clips: Array }>}
+ */
+function alternateAudioTracks(audioClips, audioTracks = {}) {
+  return Object.entries(audioTracks)
+    .filter(([, settings]) => isAlternateTrack(settings))
+    .map(([trackId, settings]) => ({
+      trackId,
+      role: settings.audioRole,
+      language: normalizeLanguage(settings.language),
+      title: settings.name || ROLE_TITLES[settings.audioRole],
+      clips: audioClips.filter((clip) => clip.trackId === trackId),
+    }))
+    .filter((track) => track.clips.length > 0);
+}
+
+/**
+ * Output options appending alternate tracks (inputs 1..n) after the streams already in the file
+ * Existing streams are copied untouched; MOV keeps the new ones as PCM and MP4 encodes them like the mix.
+ * @param {Array} alternates - From alternateAudioTracks
+ * @param {Object} options - { existingAudio: audio streams already in input 0, mixLanguage, ext, encoding }
+ */
+function languageStreamOptions(alternates, { existingAudio, mixLanguage = null, ext, encoding }) {
+  const options = ['-map', '0', '-c', 'copy'];
+  if (mixLanguage && existingAudio > 0) {
+    options.push('-metadata:s:a:0', `language=${mixLanguage}`);
+  }
+  alternates.forEach((track, i) => {
+    const stream = existingAudio + i;
+    options.push('-map', `${i + 1}:a`);
+    if (ext === 'mov') {
+      options.push(`-c:a:${stream}`, 'pcm_s24le');
+    } else {
+      options.push(`-c:a:${stream}`, encoding.audioCodec, `-b:a:${stream}`, `${encoding.audioBitrate}k`);
+    }
+    options.push(`-metadata:s:a:${stream}`, `title=${track.title}`);
+    if (track.language) {
+      options.push(`-metadata:s:a:${stream}`, `language=${track.language}`);
+    }
+  });
+  return options;
+}
+
+/**
+ * Mux rendered alternate tracks into the program file and tag the mix's language
+ * @param {string} programPath - Program file
+ * @param {Array<{ title: string, language: string|null, path: string }>} rendered - Rendered WAVs, in stream order
+ * @param {string} outputPath - Output path (its extension picks the codec of the new streams)
+ * @param {Object} options - { existingAudio, mixLanguage, encoding }
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ */
+function muxLanguageStreams(programPath, rendered, outputPath, { existingAudio, mixLanguage, encoding }, trackProcessFn) {
+  const ext = path.extname(outputPath).slice(1).toLowerCase();
+  return new Promise((resolve, reject) => {
+    const command = ffmpeg().input(programPath);
+    for (const track of rendered) {
+      command.input(track.path);
+    }
+
+    command
+      .outputOptions(...languageStreamOptions(rendered, { existingAudio, mixLanguage, ext, encoding }))
+      .output(outputPath)
+      .on('start', () => {
+        if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+      })
+      .on('end', () => resolve())
+      .on('error', (err) => reject(err))
+      .run();
+  });
+}
+
+module.exports = {
+  ALTERNATE_ROLES,
+  normalizeLanguage,
+  isAlternateTrack,
+  mixAudioClips,
+  alternateAudioTracks,
+  languageStreamOptions,
+  muxLanguageStreams,
+};
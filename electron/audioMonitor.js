@@ -0,0 +1,176 @@
+const { spawn } = require('child_process');
+const { resolveFfmpegPath } = require('./ffmpeg');
+const { ErrorCode, StarscapeError } = require('./errors');
+
+/**
+ * Audio monitors - live input level metering for a microphone, before or during a recording
+ * One ffmpeg per monitor captures the device as 16 kHz mono PCM on a pipe; every window
+ * of samples is reduced to RMS and peak levels (dBFS) and handed to onLevel.
+ */
+
+const MONITOR_SAMPLE_RATE = 16000;
+
+// Length of each metered window; ~20 updates a second is enough for a meter
+const LEVEL_INTERVAL_MS = 50;
+
+// Floor reported for digital silence
+const SILENCE_DB = -96;
+
+/**
+ * ffmpeg arguments capturing an input device as raw PCM on stdout
+ * @param {string} platform - process.platform
+ * @param {string} device - avfoundation audio index or name, dshow device name, or PulseAudio source
+ */
+function audioMonitorArgs(platform, device) {
+  const input = {
+    darwin: ['-f', 'avfoundation', '-i', `:${device}`],
+    win32: ['-f', 'dshow', '-i', `audio=${device}`],
+    linux: ['-f', 'pulse', '-i', device || 'default'],
+  }[platform];
+  if (!input) {
+    throw new StarscapeError(ErrorCode.DEVICE_NOT_FOUND, `Audio monitoring is not supported on ${platform}`, { platform });
+  }
+  return [
+    '-hide_banner', '-loglevel', 'error',
+    ...input,
+    '-vn', '-ac', '1', '-ar', String(MONITOR_SAMPLE_RATE),
+    '-f', 's16le', 'pipe:1',
+  ];
+}
+
+function toDb(level) {
+  return level > 0 ? Math.max(SILENCE_DB, 20 * Math.log10(level)) : SILENCE_DB;
+}
+
+/**
+ * RMS and peak of a window of samples (-1..1), in dBFS
+ */
+function measureLevel(samples) {
+  let sum = 0;
+  let peak = 0;
+  for (const sample of samples) {
+    sum += sample * sample;
+    peak = Math.max(peak, Math.abs(sample));
+  }
+  const rms = samples.length > 0 ? Math.sqrt(sum / samples.length) : 0;
+  return {
+    rms_db: Math.round(toDb(rms) * 10) / 10,
+    peak_db: Math.round(toDb(peak) * 10) / 10,
+    clipped: peak >= 0.999,
+  };
+}
+
+class AudioMonitor {
+  /**
+   * @param {string} id - Monitor id
+   * @param {string} device - Input device (see audioMonitorArgs)
+   * @param {Object} options - { onLevel: ({ monitor_id, rms_db, peak_db, clipped }) => void,
+   *   onEnd: (error|null) => void, platform, spawnProcess: (args) => ChildProcess, trackProcessFn }
+   */
+  constructor(id, device, options = {}) {
+    this.id = id;
+    this.device = device;
+    this.onLevel = options.onLevel || (() => {});
+    this.onEnd = options.onEnd || (() => {});
+    this.windowSamples = Math.round((MONITOR_SAMPLE_RATE * LEVEL_INTERVAL_MS) / 1000);
+    this.pending = [];
+    this.remainder = null;
+    const args = audioMonitorArgs(options.platform || process.platform, device);
+    const spawnProcess = options.spawnProcess || ((a) => spawn(resolveFfmpegPath(), a, { stdio: ['ignore', 'pipe', 'pipe'] }));
+    this.proc = spawnProcess(args);
+    if (options.trackProcessFn) options.trackProcessFn(this.proc);
+
+    let stderr = '';
+    if (this.proc.stderr) {
+      this.proc.stderr.on('data', (chunk) => {
+        stderr = (stderr + chunk).slice(-2000);
+      });
+    }
+    this.proc.stdout.on('data', (chunk) => this.push(chunk));
+    this.proc.on('error', (err) => this.end(err));
+    this.proc.on('close', (code) => {
+      const failed = code !== 0 && code !== null;
+      this.end(failed ? new StarscapeError(ErrorCode.FFMPEG_FAILED, `Audio monitor failed: ${stderr.trim() || `exit ${code}`}`, { device }) : null);
+    });
+  }
+
+  /**
+   * Append PCM bytes, emitting a level for every full window
+   */
+  push(chunk) {
+    let bytes = this.remainder ? Buffer.concat([this.remainder, chunk]) : chunk;
+    // An odd trailing byte is half a sample; keep it for the next chunk
+    const usable = bytes.length - (bytes.length % 2);
+    this.remainder = usable < bytes.length ? bytes.subarray(usable) : null;
+    bytes = bytes.subarray(0, usable);
+    for (let i = 0; i < bytes.length; i += 2) {
+      this.pending.push(bytes.readInt16LE(i) / 32768);
+      if (this.pending.length >= this.windowSamples) {
+        this.onLevel({ monitor_id: this.id, ...measureLevel(this.pending) });
+        this.pending = [];
+      }
+    }
+  }
+
+  end(error) {
+    if (!this.proc) return;
+    this.proc = null;
+    this.onEnd(error);
+  }
+
+  stop() {
+    const proc = this.proc;
+    this.proc = null;
+    if (proc) {
+      proc.stdout.removeAllListeners('data');
+      proc.kill('SIGTERM');
+    }
+  }
+}
+
+const monitors = new Map();
+let monitorCounter = 0;
+
+/**
+ * Start metering an input device
+ * @returns {AudioMonitor}
+ */
+function startAudioMonitor(device, options = {}) {
+  const id = `monitor_${++monitorCounter}`;
+  const monitor = new AudioMonitor(id, device, {
+    ...options,
+    onEnd: (error) => {
+      monitors.delete(id);
+      if (options.onEnd) options.onEnd(error);
+    },
+  });
+  monitors.set(id, monitor);
+  return monitor;
+}
+
+function stopAudioMonitor(id) {
+  const monitor = monitors.get(id);
+  if (!monitor) {
+    throw new StarscapeError(ErrorCode.NOT_FOUND, `Audio monitor not found: ${id}`, { monitor_id: id });
+  }
+  monitor.stop();
+  monitors.delete(id);
+}
+
+function stopAllAudioMonitors() {
+  for (const monitor of monitors.values()) {
+    monitor.stop();
+  }
+  monitors.clear();
+}
+
+module.exports = {
+  LEVEL_INTERVAL_MS,
+  SILENCE_DB,
+  audioMonitorArgs,
+  measureLevel,
+  AudioMonitor,
+  startAudioMonitor,
+  stopAudioMonitor,
+  stopAllAudioMonitors,
+};
@@ -0,0 +1,229 @@
+const crypto = require('crypto');
+const fs = require('fs');
+const http = require('http');
+const path = require('path');
+const { ErrorCode, StarscapeError, toStarscapeError } = require('./errors');
+
+/**
+ * Local automation API - an opt-in JSON server for Stream Deck buttons and scripts
+ * Listens on 127.0.0.1 only and every request needs the bearer token from the settings store.
+ * Requests carrying an Origin header are refused and the Host must be a loopback name, so a web
+ * page can't reach the API through the browser (CSRF or DNS rebinding) even with a guessed port.
+ *
+ *   GET  /v1/status                 App version, open project, running jobs and recordings
+ *   GET  /v1/projects               Projects open in the app
+ *   POST /v1/exports                { project_id | project, settings } -> { export_id }; queued, one render at a time
+ *   GET  /v1/exports/:id            Queued/running/completed/failed, with the job id and result
+ *   POST /v1/recording/:action      start | stop | pause (same as the recording hotkeys)
+ */
+
+const DEFAULT_PORT = 37411;
+
+const RECORDING_ACTIONS = ['start', 'stop', 'pause'];
+
+// Project JSON arrives in export requests; anything bigger is refused
+const MAX_BODY_BYTES = 20 * 1024 * 1024;
+
+const LOOPBACK_HOSTS = ['127.0.0.1', 'localhost', '[::1]'];
+
+const STATUS_FOR_CODE = {
+  [ErrorCode.INVALID_ARGUMENT]: 400,
+  [ErrorCode.NOT_FOUND]: 404,
+};
+
+let storePath = null;
+let settings = { enabled: false, port: DEFAULT_PORT, token: null };
+
+function saveStore() {
+  if (!storePath) return;
+  try {
+    fs.mkdirSync(path.dirname(storePath), { recursive: true });
+    fs.writeFileSync(storePath, JSON.stringify(settings, null, 2), { mode: 0o600 });
+  } catch (e) {
+    console.error('Failed to save automation settings:', e.message);
+  }
+}
+
+function newToken() {
+  return crypto.randomBytes(24).toString('hex');
+}
+
+/**
+ * Current settings ({ enabled, port, token })
+ */
+function getAutomationSettings() {
+  return { ...settings };
+}
+
+/**
+ * Update settings; enabling creates a token the first time and `regenerateToken` replaces it
+ * @param {Object} changes - { enabled, port, regenerateToken }
+ */
+function setAutomationSettings(changes = {}) {
+  const next = { ...settings };
+  if (changes.enabled !== undefined) next.enabled = !!changes.enabled;
+  if (changes.port !== undefined) {
+    const port = Number(changes.port);
+    if (!Number.isInteger(port) || port < 1024 || port > 65535) {
+      throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Automation port must be 1024-65535: ${changes.port}`, { port: changes.port });
+    }
+    next.port = port;
+  }
+  if (changes.regenerateToken || (next.enabled && !next.token)) {
+    next.token = newToken();
+  }
+  settings = next;
+  saveStore();
+  return getAutomationSettings();
+}
+
+/**
+ * Whether a request may use the API (loopback Host, no Origin, matching bearer token)
+ */
+function isAuthorized(req, token) {
+  if (!token || req.headers.origin !== undefined) return false;
+  const host = (req.headers.host || '').replace(/:\d+$/, '').toLowerCase();
+  if (!LOOPBACK_HOSTS.includes(host)) return false;
+  const match = /^Bearer (.+)$/.exec(req.headers.authorization || '');
+  if (!match) return false;
+  const given = Buffer.from(match[1]);
+  const expected = Buffer.from(token);
+  return given.length === expected.length && crypto.timingSafeEqual(given, expected);
+}
+
+function readBody(req) {
+  return new Promise((resolve, reject) => {
+    const chunks = [];
+    let size = 0;
+    req.on('data', (chunk) => {
+      size += chunk.length;
+      if (size > MAX_BODY_BYTES) {
+        reject(new StarscapeError(ErrorCode.INVALID_ARGUMENT, 'Request body too large'));
+        req.destroy();
+        return;
+      }
+      chunks.push(chunk);
+    });
+    req.on('end', () => {
+      const text = Buffer.concat(chunks).toString('utf8');
+      try {
+        resolve(text ? JSON.parse(text) : {});
+      } catch (e) {
+        reject(new StarscapeError(ErrorCode.INVALID_ARGUMENT, 'Request body is not valid JSON'));
+      }
+    });
+    req.on('error', reject);
+  });
+}
+
+function notFound(what) {
+  return new StarscapeError(ErrorCode.NOT_FOUND, `${what} not found`);
+}
+
+/**
+ * Route one request to a handler
+ * @param {Object} handlers - { status, listProjects, enqueueExport, getExport, recording }
+ * @returns {Promise<Object>} Response body
+ */
+async function route(req, handlers) {
+  const { pathname } = new URL(req.url, 'http://localhost');
+  const parts = pathname.split('/').filter(Boolean);
+  if (parts[0] !== 'v1') throw notFound('Endpoint');
+
+  const endpoint = `${req.method} /${parts.slice(1, 2).join('/')}`;
+  switch (endpoint) {
+    case 'GET /status':
+      if (parts.length === 2) return handlers.status();
+      break;
+    case 'GET /projects':
+      if (parts.length === 2) return { projects: await handlers.listProjects() };
+      break;
+    case 'POST /exports':
+      if (parts.length === 2) return handlers.enqueueExport(await readBody(req));
+      break;
+    case 'GET /exports':
+      if (parts.length === 3) {
+        const entry = await handlers.getExport(decodeURIComponent(parts[2]));
+        if (!entry) throw notFound('Export');
+        return entry;
+      }
+      break;
+    case 'POST /recording':
+      if (parts.length === 3) {
+        if (!RECORDING_ACTIONS.includes(parts[2])) throw notFound('Recording action');
+        return handlers.recording(parts[2]);
+      }
+      break;
+    default:
+      break;
+  }
+  throw notFound('Endpoint');
+}
+
+/**
+ * Create the API server (not yet listening)
+ * @param {Object} handlers - { status, listProjects, enqueueExport, getExport, recording }
+ * @param {Function} getToken - () => current token, read per request so regenerating takes effect at once
+ */
+function createAutomationServer(handlers, getToken = () => settings.token) {
+  return http.createServer(async (req, res) => {
+    let status = 200;
+    let body;
+    if (!isAuthorized(req, getToken())) {
+      status = 401;
+      body = { error: { code: 'unauthorized', message: 'Missing or invalid token' } };
+    } else {
+      try {
+        body = (await route(req, handlers)) || {};
+      } catch (error) {
+        const typed = toStarscapeError(error);
+        status = STATUS_FOR_CODE[typed.code] || 500;
+        body = { error: { code: typed.code, message: typed.message } };
+      }
+    }
+    res.writeHead(status, { 'Content-Type': 'application/json', 'Cache-Control': 'no-store' });
+    res.end(JSON.stringify(body));
+  });
+}
+
+/**
+ * Start listening on the loopback interface
+ * @returns {Promise<http.Server>}
+ */
+function listen(server, port) {
+  return new Promise((resolve, reject) => {
+    server.once('error', reject);
+    server.listen(port, '127.0.0.1', () => {
+      server.off('error', reject);
+      resolve(server);
+    });
+  });
+}
+
+/**
+ * Load settings from the store
+ */
+function init(filePath) {
+  storePath = filePath;
+  try {
+    const stored = JSON.parse(fs.readFileSync(filePath, 'utf8'));
+    settings = {
+      enabled: !!stored.enabled,
+      port: Number.isInteger(stored.port) ? stored.port : DEFAULT_PORT,
+      token: typeof stored.token === 'string' && stored.token ? stored.token : null,
+    };
+  } catch (e) {
+    settings = { enabled: false, port: DEFAULT_PORT, token: null };
+  }
+}
+
+module.exports = {
+  DEFAULT_PORT,
+  RECORDING_ACTIONS,
+  init,
+  getAutomationSettings,
+  setAutomationSettings,
+  isAuthorized,
+  createAutomationServer,
+  listen,
+};
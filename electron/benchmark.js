@@ -0,0 +1,155 @@
+const ffmpeg = require('fluent-ffmpeg');
+const fs = require('fs-extra');
+const { getFfmpegCapabilities, hasEncoder } = require('./capabilities');
+
+/**
+ * Encoder benchmark - encodes a synthetic clip with each encoder/preset and scores speed and quality
+ */
+
+const BENCH_WIDTH = 1280;
+const BENCH_HEIGHT = 720;
+const BENCH_FPS = 30;
+const BENCH_SECONDS = 4;
+const BENCH_SOURCE = `testsrc2=size=${BENCH_WIDTH}x${BENCH_HEIGHT}:rate=${BENCH_FPS}:duration=${BENCH_SECONDS}`;
+
+/**
+ * SSIM at or above which an encode is treated as visually equivalent to libx264 CRF 23
+ */
+const MIN_RECOMMENDED_SSIM = 0.97;
+
+/**
+ * Encoder/preset combinations to try; hardware ones simply fail where unavailable
+ */
+const BENCHMARK_CANDIDATES = [
+  { encoder: 'libx264', preset: 'ultrafast', options: ['-preset ultrafast', '-crf 23'] },
+  { encoder: 'libx264', preset: 'veryfast', options: ['-preset veryfast', '-crf 23'] },
+  { encoder: 'libx264', preset: 'medium', options: ['-preset medium', '-crf 23'] },
+  { encoder: 'libx265', preset: 'fast', options: ['-preset fast', '-crf 28', '-tag:v hvc1'] },
+  { encoder: 'h264_videotoolbox', preset: 'default', options: ['-q:v 65', '-allow_sw 0'] },
+  { encoder: 'hevc_videotoolbox', preset: 'default', options: ['-q:v 65', '-allow_sw 0', '-tag:v hvc1'] },
+  { encoder: 'h264_nvenc', preset: 'p4', options: ['-preset p4', '-cq 23'] },
+  { encoder: 'h264_qsv', preset: 'veryfast', options: ['-preset veryfast', '-global_quality 23'] },
+];
+
+/**
+ * Encode the synthetic source with one candidate
+ */
+function encodeCandidate(candidate, outputPath, trackProcessFn) {
+  return new Promise((resolve, reject) => {
+    const command = ffmpeg()
+      .input(BENCH_SOURCE)
+      .inputFormat('lavfi')
+      .videoCodec(candidate.encoder)
+      .outputOptions([...candidate.options, '-pix_fmt yuv420p', '-an'])
+      .output(outputPath)
+      .on('start', () => {
+        if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+      })
+      .on('end', () => resolve())
+      .on('error', (err) => reject(err));
+
+    command.run();
+  });
+}
+
+/**
+ * Score an encode against the synthetic source with ffmpeg's ssim filter
+ * @returns {Promise<number|null>} SSIM "All" value (1 = identical)
+ */
+function measureSsim(encodedPath, trackProcessFn) {
+  return new Promise((resolve, reject) => {
+    let ssim = null;
+    const command = ffmpeg()
+      .input(encodedPath)
+      .input(BENCH_SOURCE)
+      .inputFormat('lavfi')
+      .complexFilter(['[0:v][1:v]ssim'])
+      .outputOptions(['-f null'])
+      .output('-')
+      .on('start', () => {
+        if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+      })
+      .on('stderr', (line) => {
+        const match = /SSIM .*All:([\d.]+)/.exec(line);
+        if (match) ssim = parseFloat(match[1]);
+      })
+      .on('end', () => resolve(ssim))
+      .on('error', (err) => reject(err));
+
+    command.run();
+  });
+}
+
+/**
+ * Pick the fastest encode whose quality holds up (falls back to the best-quality one)
+ */
+function recommend(results) {
+  const succeeded = results.filter((result) => result.available);
+  if (succeeded.length === 0) return null;
+  const acceptable = succeeded.filter((result) => result.ssim !== null && result.ssim >= MIN_RECOMMENDED_SSIM);
+  const pool = acceptable.length > 0 ? acceptable : succeeded;
+  const best = acceptable.length > 0
+    ? pool.reduce((a, b) => (b.encode_fps > a.encode_fps ? b : a))
+    : pool.reduce((a, b) => ((b.ssim || 0) > (a.ssim || 0) ? b : a));
+  return { encoder: best.encoder, preset: best.preset };
+}
+
+/**
+ * Encode a short synthetic clip with every encoder/preset combination and report speed/quality
+ * @param {CacheDirs} cache - Cache directories (encodes go to the segments dir and are removed)
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ * @returns {Promise<{ results: Array, recommended: { encoder: string, preset: string }|null }>}
+ */
+async function runEncodeBenchmark(cache, trackProcessFn) {
+  const frames = BENCH_FPS * BENCH_SECONDS;
+  const results = [];
+  const caps = await getFfmpegCapabilities().catch(() => null);
+
+  for (const candidate of BENCHMARK_CANDIDATES) {
+    const outputPath = cache.stagePath('benchmark', `${candidate.encoder}_${candidate.preset}`, 'mp4');
+    const result = {
+      encoder: candidate.encoder,
+      preset: candidate.preset,
+      available: false,
+      encode_ms: null,
+      encode_fps: null,
+      realtime_factor: null,
+      bitrate_kbps: null,
+      ssim: null,
+      error: null,
+    };
+
+    if (caps && !hasEncoder(caps, candidate.encoder)) {
+      result.error = 'Encoder not included in this FFmpeg build';
+      results.push(result);
+      continue;
+    }
+
+    try {
+      const startedAt = Date.now();
+      await encodeCandidate(candidate, outputPath, trackProcessFn);
+      const elapsedMs = Math.max(1, Date.now() - startedAt);
+      const stats = await fs.stat(outputPath);
+
+      result.available = true;
+      result.encode_ms = elapsedMs;
+      result.encode_fps = Math.round((frames / (elapsedMs / 1000)) * 10) / 10;
+      result.realtime_factor = Math.round(((BENCH_SECONDS * 1000) / elapsedMs) * 100) / 100;
+      result.bitrate_kbps = Math.round((stats.size * 8) / BENCH_SECONDS / 1000);
+      result.ssim = await measureSsim(outputPath, trackProcessFn).catch(() => null);
+    } catch (err) {
+      result.error = String(err.message || err).split('\n')[0];
+    } finally {
+      await fs.remove(outputPath);
+    }
+
+    results.push(result);
+  }
+
+  return { results, recommended: recommend(results) };
+}
+
+module.exports = {
+  BENCHMARK_CANDIDATES,
+  runEncodeBenchmark,
+};
@@ -0,0 +1,180 @@
+const ffmpeg = require('fluent-ffmpeg');
+const fs = require('fs-extra');
+const path = require('path');
+const { toRational, msToFrame } = require('./timebase');
+const { framesToTimecode, timecodeToFrames } = require('./timecode');
+const { escapeFilterPath } = require('./filterUtils');
+const { videoQualityOptions } = require('./encoding');
+const { assTime, escapeAssText, textDirectionEncoding, assScript, assFilter, collectFonts } = require('./captions');
+
+// Clip names can be in any script, so they go through libass rather than drawtext
+const LABEL_FONT_FAMILY = 'Arial';
+
+/**
+ * Monospace fonts to try for drawtext when ffmpeg has no fontconfig
+ */
+const MONOSPACE_FONT_CANDIDATES = [
+  '/System/Library/Fonts/Menlo.ttc',
+  '/System/Library/Fonts/Monaco.ttf',
+  'C:/Windows/Fonts/consola.ttf',
+  'C:/Windows/Fonts/cour.ttf',
+  '/usr/share/fonts/truetype/dejavu/DejaVuSansMono.ttf',
+  '/usr/share/fonts/TTF/DejaVuSansMono.ttf',
+  '/usr/share/fonts/dejavu/DejaVuSansMono.ttf',
+];
+
+/**
+ * Pick the drawtext font option: a known font file, or a fontconfig family name
+ */
+function resolveFontOption() {
+  const fontFile = MONOSPACE_FONT_CANDIDATES.find((candidate) => fs.existsSync(candidate));
+  return fontFile ? `fontfile='${escapeFilterPath(fontFile)}'` : "font='monospace'";
+}
+
+/**
+ * Source frame (from 00:00:00:00) shown at a clip's first frame
+ * Uses the source's embedded start timecode when present.
+ */
+function sourceStartFrame(clip, startTimecode, rate) {
+  let base = 0;
+  if (startTimecode) {
+    try {
+      base = timecodeToFrames(startTimecode, rate);
+    } catch (e) {
+      base = 0;
+    }
+  }
+  return base + msToFrame(clip.inMs, rate);
+}
+
+/**
+ * Font size and margin for review overlay text at an output height
+ */
+function overlayMetrics(height) {
+  const fontSize = Math.max(12, Math.round(height / 30));
+  return { fontSize, margin: Math.round(fontSize / 2) };
+}
+
+/**
+ * Label shown for a clip in review renders
+ */
+function clipLabel(clip) {
+  const name = clip.assetName || path.basename(clip.srcPath);
+  return clip.freezeFrame ? `${name} (freeze)` : name;
+}
+
+/**
+ * Build drawtext filters burning in source timecode and program frame number
+ * (clip names are drawn by buildBurnInLabels). drawtext derives its timecode from the output frame count, so each clip's counter is
+ * offset by the program frame at which the clip starts.
+ * @param {Array} clips - Main track plan clips
+ * @param {Object} sourceTimecodes - srcPath -> embedded start timecode (or null)
+ * @param {number} timelineStartMs - Timeline position that maps to t=0 in the output
+ * @param {{ num: number, den: number }} rate - Program frame rate
+ * @param {number} height - Output height (text scales with it)
+ */
+function buildBurnInFilters(clips, sourceTimecodes, timelineStartMs, rate, height) {
+  const font = resolveFontOption();
+  const { fontSize, margin } = overlayMetrics(height);
+  const box = `fontcolor=white:box=1:boxcolor=black@0.6:boxborderw=${Math.round(fontSize / 4)}`;
+  const rateStr = `${rate.num}/${rate.den}`;
+  const framesPerDay = Math.round((rate.num / rate.den) * 86400);
+  const filters = [];
+
+  for (const clip of clips) {
+    const startSec = Math.max(0, (clip.startMs - timelineStartMs) / 1000);
+    const endSec = Math.max(startSec, (clip.endMs - timelineStartMs) / 1000);
+    const enable = `between(t,${startSec},${endSec})`;
+
+    // drawtext shows start + n, so start the counter n frames before the clip's source frame
+    const programFrame = msToFrame(clip.startMs - timelineStartMs, rate);
+    const sourceFrame = clip.freezeFrame ? null : sourceStartFrame(clip, sourceTimecodes[clip.srcPath], rate);
+    if (sourceFrame !== null) {
+      const counterStart = (((sourceFrame - programFrame) % framesPerDay) + framesPerDay) % framesPerDay;
+      const timecode = framesToTimecode(counterStart, rate).replace(/[:;]/g, (sep) => `\\${sep}`);
+      filters.push(
+        `drawtext=${font}:timecode='${timecode}':rate=${rateStr}:tc24hmax=1:fontsize=${fontSize}:${box}:x=${margin}:y=${margin}:enable='${enable}'`
+      );
+    }
+  }
+
+  filters.push(
+    `drawtext=${font}:text='%{eif\\:n\\:d}':fontsize=${fontSize}:${box}:x=w-tw-${margin}:y=${margin}`
+  );
+  return filters;
+}
+
+/**
+ * Build an ASS script drawing each clip's name under its timecode
+ * libass shapes the text, so names in Arabic/Hebrew or with emoji render correctly.
+ * @param {Array} clips - Main track plan clips
+ * @param {number} timelineStartMs - Timeline position that maps to t=0 in the output
+ * @param {number} width - Output width
+ * @param {number} height - Output height
+ */
+function buildBurnInLabels(clips, timelineStartMs, width, height) {
+  const { fontSize, margin } = overlayMetrics(height);
+  const labels = clips.map(clipLabel);
+  const rtl = labels.some((label) => textDirectionEncoding(label) === -1);
+  // White on a translucent black box (BorderStyle 3), anchored top-left
+  const style = `Style: Label,${LABEL_FONT_FAMILY},${fontSize},&H00FFFFFF,&H00FFFFFF,&H66000000,&H00000000,0,0,0,0,100,100,0,0,3,${Math.round(fontSize / 4)},0,7,0,0,0,${rtl ? -1 : 1}`;
+  const events = clips.map((clip, i) => {
+    const start = assTime(Math.max(0, clip.startMs - timelineStartMs));
+    const end = assTime(Math.max(0, clip.endMs - timelineStartMs));
+    return `Dialogue: 0,${start},${end},Label,,0,0,0,,{\\pos(${margin},${margin * 2 + fontSize})}${escapeAssText(labels[i])}`;
+  });
+  return assScript(width, height, [style], events);
+}
+
+/**
+ * Burn review information into the program picture
+ * @param {string} inputPath - Program file
+ * @param {string} outputPath - Output file path
+ * @param {Array} clips - Main track plan clips
+ * @param {Object} sourceTimecodes - srcPath -> embedded start timecode (or null)
+ * @param {number} timelineStartMs - Timeline position of the program start
+ * @param {number|string} fps - Program frame rate
+ * @param {number} width - Program frame width
+ * @param {number} height - Program frame height
+ * @param {string} assPath - Where to write the temporary clip-name script
+ * @param {Object} encoding - Resolved encoder settings (see encoding.js)
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ */
+async function burnInReviewOverlay(inputPath, outputPath, clips, sourceTimecodes, timelineStartMs, fps, width, height, assPath, encoding, trackProcessFn) {
+  const rate = toRational(fps) || { num: 30, den: 1 };
+  await fs.writeFile(assPath, buildBurnInLabels(clips, timelineStartMs, width, height), 'utf8');
+  const fontsDir = `${assPath}.fonts`;
+
+  try {
+    const fontCount = await collectFonts([{ family: LABEL_FONT_FAMILY }], clips.map(clipLabel), fontsDir);
+    await new Promise((resolve, reject) => {
+      const command = ffmpeg(inputPath)
+        .videoFilters([
+          ...buildBurnInFilters(clips, sourceTimecodes, timelineStartMs, rate, height),
+          assFilter(assPath, fontCount > 0 ? fontsDir : null),
+        ])
+        .videoCodec(encoding.videoCodec)
+        .outputOptions([
+          '-c:a copy',
+          ...videoQualityOptions(encoding),
+        ])
+        .output(outputPath)
+        .on('start', () => {
+          if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+        })
+        .on('end', () => resolve())
+        .on('error', (err) => reject(err));
+
+      command.run();
+    });
+  } finally {
+    await fs.remove(assPath);
+    await fs.remove(fontsDir);
+  }
+}
+
+module.exports = {
+  buildBurnInFilters,
+  buildBurnInLabels,
+  burnInReviewOverlay,
+};
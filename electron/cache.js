@@ -1,5 +1,7 @@
 const path = require('path');
 const fs = require('fs-extra');
+const { toLongPath } = require('./paths');
+const { expandFilenameTemplate, uniqueOutputPath } = require('./outputNames');
 
 /**
  * Cache directory management - matches Rust CacheDirs structure
@@ -16,6 +18,8 @@ class CacheDirs {
     this.segments = path.join(this.base, 'segments');
     this.renders = path.join(appDataPath, appName, 'projects');
     this.captures = path.join(this.base, 'captures');
+    this.indexes = path.join(this.base, 'indexes');
+    this.frames = path.join(this.base, 'frames');
   }
 
   /**
@@ -28,13 +32,15 @@ class CacheDirs {
     await fs.ensureDir(this.segments);
     await fs.ensureDir(this.renders);
     await fs.ensureDir(this.captures);
+    await fs.ensureDir(this.indexes);
+    await fs.ensureDir(this.frames);
   }
 
   /**
-   * Get preview file path for a specific plan and timestamp
+   * Get preview file path for a clip hash and source timestamp
    */
-  previewFile(planId, atMs) {
-    const filename = `${planId}_${atMs}.jpg`;
+  previewFile(clipKey, atMs) {
+    const filename = `${clipKey}_${atMs}.jpg`;
     return path.join(this.previews, filename);
   }
 
@@ -48,8 +54,85 @@ class CacheDirs {
   /**
    * Get segment path for export
    */
-  segmentPath(index) {
-    return path.join(this.segments, `segment_${String(index).padStart(4, '0')}.mp4`);
+  segmentPath(index, ext = 'mp4') {
+    return path.join(this.segments, `segment_${String(index).padStart(4, '0')}.${ext}`);
+  }
+
+  /**
+   * Get path for a segment cached under its content hash (reused across exports)
+   */
+  segmentCachePath(key, ext = 'mp4') {
+    return path.join(this.segments, `seg_${key}.${ext}`);
+  }
+
+  /**
+   * Get path for an encoded clip handle (head or tail of a smart-rendered clip)
+   */
+  handlePath(key) {
+    return path.join(this.segments, `handle_${key}.ts`);
+  }
+
+  /**
+   * Get export-resume manifest path for a plan + settings hash
+   */
+  exportManifestPath(key) {
+    return path.join(this.segments, `export_${key}.json`);
+  }
+
+  /**
+   * Get intermediate export stage path (program, composite, ...)
+   */
+  stagePath(planId, stage, ext) {
+    return path.join(this.segments, `${planId}_${stage}.${ext}`);
+  }
+
+  /**
+   * Get audio effect audition file path
+   */
+  audioPreviewFile(key) {
+    return path.join(this.previews, `audio_${key}.m4a`);
+  }
+
+  /**
+   * Get keyframe index path for an asset key
+   */
+  keyframeIndexPath(key) {
+    return path.join(this.indexes, `keyframes_${key}.json`);
+  }
+
+  /**
+   * Get stored media metadata path for a source path hash
+   */
+  metadataPath(key) {
+    return path.join(this.indexes, `meta_${key}.json`);
+  }
+
+  /**
+   * Get contact sheet path for a source/layout hash
+   */
+  contactSheetPath(key) {
+    return path.join(this.previews, `contact_${key}.jpg`);
+  }
+
+  /**
+   * Get path for media synthesized by a generator clip (content hash)
+   */
+  generatorPath(key, ext) {
+    return path.join(this.segments, `generator_${key}.${ext}`);
+  }
+
+  /**
+   * Get rendered title overlay path for a template/params hash
+   */
+  titlePath(key) {
+    return path.join(this.previews, `title_${key}.mov`);
+  }
+
+  /**
+   * Get path for a single loop iteration rendered before repeating
+   */
+  loopPiecePath(index, ext = 'mp4') {
+    return path.join(this.segments, `loop_piece_${String(index).padStart(4, '0')}.${ext}`);
   }
 
   /**
@@ -64,9 +147,66 @@ class CacheDirs {
    * Get render output path with custom filename
    */
   renderOutputPathWithFilename(filename, ext) {
-    // Sanitize filename to remove path separators and invalid characters
-    const sanitizedFilename = filename.replace(/[\/\\:*?"<>|]/g, '_');
-    return path.join(this.renders, `${sanitizedFilename}.${ext}`);
+    // Sanitize filename to remove path separators, invalid and control characters
+    const sanitizedFilename = filename.replace(/[\/\\:*?"<>|\x00-\x1f]/g, '_');
+    return toLongPath(path.join(this.renders, `${sanitizedFilename}.${ext}`));
+  }
+
+  /**
+   * Get render output path for a default name, numbered (_2, _3...) when the name is taken
+   */
+  uniqueRenderOutputPath(name, ext) {
+    const sanitizedName = name.replace(/[\/\\:*?"<>|\x00-\x1f]/g, '_');
+    return toLongPath(uniqueOutputPath(this.renders, sanitizedName, ext));
+  }
+
+  /**
+   * Get render output path from a filename template, numbered when the name is taken
+   * @param {string} template - e.g. {project}_{preset}_{date}_{resolution}
+   * @param {Object} fields - Values from outputNameFields
+   */
+  renderOutputPathFromTemplate(template, fields, ext) {
+    return toLongPath(uniqueOutputPath(this.renders, expandFilenameTemplate(template, fields), ext));
+  }
+
+  /**
+   * Get output path for a still extracted from a video
+   */
+  stillOutputPath() {
+    const timestamp = Date.now();
+    return path.join(this.captures, `still_${timestamp}.png`);
+  }
+
+  /**
+   * Get output path for a display or window screenshot
+   */
+  screenshotPath(ext) {
+    const timestamp = Date.now();
+    return path.join(this.captures, `screenshot_${timestamp}.${ext}`);
+  }
+
+  /**
+   * Get output path for audio extracted from a file (named after the source, since it becomes the asset name)
+   */
+  extractedAudioPath(sourceName, ext) {
+    const sanitized = sourceName.replace(/[\/\\:*?"<>|\x00-\x1f]/g, '_');
+    return path.join(this.captures, `${sanitized}_audio.${ext}`);
+  }
+
+  /**
+   * Get output path for one stream split out of an imported recording (named after the source and track)
+   */
+  importedStreamPath(sourceName, ext) {
+    const sanitized = sourceName.replace(/[\/\\:*?"<>|\x00-\x1f]/g, '_');
+    return path.join(this.captures, `${sanitized}.${ext}`);
+  }
+
+  /**
+   * Get output path for a diagnostics bundle (kept outside the cache so clearing it keeps reports)
+   */
+  diagnosticsBundlePath() {
+    const timestamp = Math.floor(Date.now() / 1000);
+    return path.join(path.dirname(this.base), 'diagnostics', `starscape_diagnostics_${timestamp}.zip`);
   }
 
   /**
@@ -0,0 +1,191 @@
+const { execFile } = require('child_process');
+const { resolveFfmpegPath } = require('./ffmpeg');
+const { ErrorCode, StarscapeError } = require('./errors');
+const logger = require('./logger');
+
+/**
+ * FFmpeg capability matrix - what the installed build can encode, decode and filter
+ * Probed once per session; export code consults it before building filter graphs.
+ */
+
+let capabilitiesPromise = null;
+
+// Hardware decode methods to try for previews, most preferred first
+const DECODE_HWACCELS = {
+  darwin: ['videotoolbox'],
+  win32: ['d3d11va', 'cuda', 'dxva2'],
+  linux: ['cuda', 'vaapi'],
+};
+
+function runFfmpeg(args) {
+  return new Promise((resolve, reject) => {
+    execFile(
+      resolveFfmpegPath(),
+      ['-hide_banner', ...args],
+      { maxBuffer: 16 * 1024 * 1024, timeout: 15000 },
+      (err, stdout) => {
+        if (err) {
+          reject(new StarscapeError(ErrorCode.FFMPEG_FAILED, `ffmpeg ${args.join(' ')} failed: ${err.message}`));
+          return;
+        }
+        resolve(stdout);
+      }
+    );
+  });
+}
+
+/**
+ * Parse `ffmpeg -version` into version, configuration flags and library versions
+ */
+function parseVersion(output) {
+  const versionMatch = /ffmpeg version (\S+)/.exec(output);
+  const configMatch = /configuration:(.*)/.exec(output);
+  const libraries = {};
+  for (const match of output.matchAll(/^(lib\w+)\s+(\d+\.\s*\d+\.\s*\d+)/gm)) {
+    libraries[match[1]] = match[2].replace(/\s+/g, '');
+  }
+  return {
+    version: versionMatch ? versionMatch[1] : null,
+    configuration: configMatch ? configMatch[1].trim().split(/\s+/).filter(Boolean) : [],
+    libraries,
+  };
+}
+
+/**
+ * Parse `-encoders` / `-decoders` listings (" V....D libx264  description")
+ */
+function parseCodecList(output) {
+  const codecs = { video: [], audio: [], subtitle: [] };
+  const body = output.split(/^\s*-{3,}\s*$/m)[1] || '';
+  for (const line of body.split('\n')) {
+    const match = /^\s*([VAS])[A-Z.]{5}\s+(\S+)/.exec(line);
+    if (!match) continue;
+    const kind = match[1] === 'V' ? 'video' : match[1] === 'A' ? 'audio' : 'subtitle';
+    codecs[kind].push(match[2]);
+  }
+  return codecs;
+}
+
+/**
+ * Parse `-filters` (" TSC gblur  V->V  Gaussian blur.")
+ */
+function parseFilterList(output) {
+  const filters = [];
+  for (const line of output.split('\n')) {
+    const match = /^\s*[A-Z.|]{2,3}\s+(\S+)\s+\S*->\S*\s/.exec(line);
+    if (match) filters.push(match[1]);
+  }
+  return filters;
+}
+
+/**
+ * Parse `-hwaccels` (one method per line after the heading)
+ */
+function parseHwaccels(output) {
+  return output
+    .split('\n')
+    .slice(1)
+    .map((line) => line.trim())
+    .filter((line) => line && !line.endsWith(':'));
+}
+
+async function probeCapabilities() {
+  const [version, encoders, decoders, filters, hwaccels] = await Promise.all([
+    runFfmpeg(['-version']),
+    runFfmpeg(['-encoders']),
+    runFfmpeg(['-decoders']),
+    runFfmpeg(['-filters']),
+    runFfmpeg(['-hwaccels']),
+  ]);
+  return {
+    ...parseVersion(version),
+    encoders: parseCodecList(encoders),
+    decoders: parseCodecList(decoders),
+    filters: parseFilterList(filters),
+    hwaccels: parseHwaccels(hwaccels),
+  };
+}
+
+/**
+ * Get the capability matrix (probed on first call, then cached)
+ * @param {boolean} refresh - Re-probe (e.g. after the ffmpeg binary changed)
+ */
+function getFfmpegCapabilities(refresh = false) {
+  if (!capabilitiesPromise || refresh) {
+    capabilitiesPromise = probeCapabilities().catch((err) => {
+      capabilitiesPromise = null;
+      throw err;
+    });
+  }
+  return capabilitiesPromise;
+}
+
+function hasEncoder(caps, name) {
+  return [...caps.encoders.video, ...caps.encoders.audio, ...caps.encoders.subtitle].includes(name);
+}
+
+function hasFilter(caps, name) {
+  return caps.filters.includes(name);
+}
+
+/**
+ * Hardware decode method for a platform from a build's -hwaccels list (null decodes in software)
+ */
+function pickDecodeHwaccel(hwaccels, platform = process.platform) {
+  return (DECODE_HWACCELS[platform] || []).find((name) => hwaccels.includes(name)) || null;
+}
+
+/**
+ * Run a decode with the preferred hardware decoder, retrying in software if it fails
+ * Timeouts and cancellations are not retried.
+ * @param {Function} run - (hwaccel: string|null) => Promise
+ */
+async function withDecodeHwaccel(run) {
+  const hwaccel = await getFfmpegCapabilities()
+    .then((caps) => pickDecodeHwaccel(caps.hwaccels))
+    .catch(() => null);
+  if (!hwaccel) {
+    return run(null);
+  }
+  try {
+    return await run(hwaccel);
+  } catch (err) {
+    if (err && (err.code === ErrorCode.TIMEOUT || err.code === ErrorCode.CANCELLED)) throw err;
+    logger.warn('Hardware decode failed, retrying in software', { hwaccel, error: err && err.message });
+    return run(null);
+  }
+}
+
+/**
+ * Throw a precise error when the build lacks filters or encoders a job needs
+ * @param {Object} requirements - { filters: string[], encoders: string[], feature: string }
+ */
+async function assertCapabilities({ filters = [], encoders = [], feature }) {
+  const caps = await getFfmpegCapabilities();
+  const missingFilters = filters.filter((name) => !hasFilter(caps, name));
+  const missingEncoders = encoders.filter((name) => !hasEncoder(caps, name));
+  if (missingFilters.length === 0 && missingEncoders.length === 0) return;
+
+  const missing = [
+    missingFilters.length > 0 && `filters: ${missingFilters.join(', ')}`,
+    missingEncoders.length > 0 && `encoders: ${missingEncoders.join(', ')}`,
+  ].filter(Boolean).join('; ');
+  throw new StarscapeError(
+    ErrorCode.FFMPEG_FEATURE_MISSING,
+    `${feature} needs FFmpeg features this build lacks (${missing})`,
+    { missing_filters: missingFilters, missing_encoders: missingEncoders }
+  );
+}
+
+module.exports = {
+  parseVersion,
+  parseCodecList,
+  parseFilterList,
+  parseHwaccels,
+  getFfmpegCapabilities,
+  hasEncoder,
+  hasFilter,
+  pickDecodeHwaccel,
+  withDecodeHwaccel,
+  assertCapabilities,
+};
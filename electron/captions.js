@@ -0,0 +1,443 @@
+const ffmpeg = require('fluent-ffmpeg');
+const fs = require('fs-extra');
+const path = require('path');
+const { escapeFilterPath, clampOption } = require('./filterUtils');
+const { videoQualityOptions } = require('./encoding');
+const { resolveFont } = require('./fonts');
+const { ErrorCode, StarscapeError } = require('./errors');
+const logger = require('./logger');
+
+/**
+ * Styled captions: caption-track clips rendered to an ASS script and burned in with libass
+ */
+
+/**
+ * Caption style defaults; sizes are pixels at 1080p and scale with the export height
+ */
+const DEFAULT_CAPTION_STYLE = {
+  fontFamily: 'Arial',
+  fontSize: 64,
+  bold: true,
+  italic: false,
+  color: '#FFFFFF',
+  highlightColor: '#FFD400',
+  outlineColor: '#000000',
+  outlineWidth: 4,
+  backgroundColor: null,
+  position: 'bottom',
+  margin: 80,
+  highlight: 'none',
+};
+
+// ASS numpad alignment for each caption position (horizontally centered)
+const ALIGNMENT = { bottom: 2, middle: 5, top: 8 };
+
+const HIGHLIGHT_MODES = ['none', 'karaoke', 'word'];
+
+// Hebrew, Arabic, Syriac, Thaana, NKo and their presentation forms
+const RTL_PATTERN = /[\u0590-\u08FF\uFB1D-\uFDFF\uFE70-\uFEFF]/;
+
+const EMOJI_PATTERN = /\p{Extended_Pictographic}/u;
+
+// Fallback faces for emoji, in order of preference (libass draws them monochrome)
+const EMOJI_FONT_FAMILIES = ['Apple Color Emoji', 'Segoe UI Emoji', 'Noto Color Emoji', 'Noto Emoji', 'Symbola'];
+
+/**
+ * Convert #RRGGBB / #RRGGBBAA to an ASS colour (&HAABBGGRR, alpha 00 = opaque)
+ */
+function assColor(hex, fallback) {
+  const match = /^#?([0-9a-f]{6})([0-9a-f]{2})?$/i.exec(typeof hex === 'string' ? hex.trim() : '');
+  if (!match) {
+    return fallback === undefined ? '&H00FFFFFF' : assColor(fallback);
+  }
+  const [r, g, b] = [0, 2, 4].map((i) => match[1].slice(i, i + 2));
+  const alpha = match[2] ? (255 - parseInt(match[2], 16)).toString(16).padStart(2, '0') : '00';
+  return `&H${alpha}${b}${g}${r}`.toUpperCase();
+}
+
+/**
+ * Format milliseconds as an ASS timestamp (H:MM:SS.cc)
+ */
+function assTime(ms) {
+  const cs = Math.max(0, Math.round(ms / 10));
+  const h = Math.floor(cs / 360000);
+  const m = Math.floor((cs % 360000) / 6000);
+  const s = Math.floor((cs % 6000) / 100);
+  const c = cs % 100;
+  return `${h}:${String(m).padStart(2, '0')}:${String(s).padStart(2, '0')}.${String(c).padStart(2, '0')}`;
+}
+
+/**
+ * Escape literal caption text for an ASS Dialogue line
+ * Braces would start override blocks and a backslash an escape, so both are replaced or escaped.
+ */
+function escapeAssText(text) {
+  return String(text)
+    .replace(/\\/g, '＼')
+    .replace(/\{/g, '\\{')
+    .replace(/\}/g, '\\}')
+    .replace(/\r?\n/g, '\\N');
+}
+
+/**
+ * Fill in a caption style from the defaults, dropping invalid values
+ */
+function resolveCaptionStyle(style = {}) {
+  const resolved = { ...DEFAULT_CAPTION_STYLE };
+  if (typeof style.fontFamily === 'string' && style.fontFamily.trim()) {
+    // Commas would split the Style line
+    resolved.fontFamily = style.fontFamily.replace(/,/g, ' ').trim();
+  }
+  resolved.fontSize = clampOption(style.fontSize, 8, 400, DEFAULT_CAPTION_STYLE.fontSize);
+  resolved.outlineWidth = clampOption(style.outlineWidth, 0, 40, DEFAULT_CAPTION_STYLE.outlineWidth);
+  resolved.margin = clampOption(style.margin, 0, 1000, DEFAULT_CAPTION_STYLE.margin);
+  if (typeof style.bold === 'boolean') resolved.bold = style.bold;
+  if (typeof style.italic === 'boolean') resolved.italic = style.italic;
+  for (const key of ['color', 'highlightColor', 'outlineColor', 'backgroundColor']) {
+    if (typeof style[key] === 'string') resolved[key] = style[key];
+  }
+  if (ALIGNMENT[style.position]) resolved.position = style.position;
+  if (HIGHLIGHT_MODES.includes(style.highlight)) resolved.highlight = style.highlight;
+  return resolved;
+}
+
+/**
+ * Words with valid timing, clamped to the clip and sorted (times relative to the clip start)
+ */
+function timedWords(clip) {
+  const durationMs = clip.endMs - clip.startMs;
+  return (clip.words || [])
+    .filter((w) => w && typeof w.text === 'string' && w.text.trim() && Number.isFinite(w.startMs))
+    .map((w) => {
+      const startMs = Math.min(durationMs, Math.max(0, w.startMs));
+      const endMs = Number.isFinite(w.endMs) ? Math.min(durationMs, Math.max(startMs, w.endMs)) : startMs;
+      return { text: w.text.trim(), startMs, endMs };
+    })
+    .sort((a, b) => a.startMs - b.startMs);
+}
+
+/**
+ * ASS Encoding field for a line of text: -1 lets libass detect the base direction (RTL scripts),
+ * 1 keeps the default left-to-right base
+ */
+function textDirectionEncoding(text) {
+  return RTL_PATTERN.test(text) ? -1 : 1;
+}
+
+/**
+ * Text a caption clip displays
+ */
+function captionText(clip) {
+  return clip.text || (clip.words || []).map((w) => (w && w.text) || '').join(' ');
+}
+
+/**
+ * Build the Style line for one caption clip
+ */
+function buildStyleLine(name, style, scale, encodingField = 1) {
+  const boxed = !!style.backgroundColor;
+  // Karaoke sweeps from SecondaryColour (unsung) to PrimaryColour (sung)
+  const karaoke = style.highlight === 'karaoke';
+  const primary = karaoke ? assColor(style.highlightColor, DEFAULT_CAPTION_STYLE.highlightColor) : assColor(style.color, DEFAULT_CAPTION_STYLE.color);
+  const secondary = karaoke ? assColor(style.color, DEFAULT_CAPTION_STYLE.color) : assColor(style.highlightColor, DEFAULT_CAPTION_STYLE.highlightColor);
+  // With an opaque box (BorderStyle 3) the outline colour fills the box
+  const outline = boxed ? assColor(style.backgroundColor, '#000000B0') : assColor(style.outlineColor, DEFAULT_CAPTION_STYLE.outlineColor);
+  const outlineWidth = boxed ? Math.round(style.fontSize * scale * 0.25) : Math.round(style.outlineWidth * scale);
+  const margin = Math.round(style.margin * scale);
+
+  return [
+    `Style: ${name}`,
+    style.fontFamily,
+    Math.round(style.fontSize * scale),
+    primary,
+    secondary,
+    outline,
+    '&H00000000',
+    style.bold ? -1 : 0,
+    style.italic ? -1 : 0,
+    0, 0, 100, 100, 0, 0,
+    boxed ? 3 : 1,
+    outlineWidth,
+    0,
+    ALIGNMENT[style.position],
+    margin,
+    margin,
+    margin,
+    encodingField,
+  ].join(',');
+}
+
+/**
+ * Dialogue lines for one caption clip, with word highlighting when the clip has word timings
+ * karaoke: one line whose words fill in as they are spoken (\kf sweeps)
+ * word: one line per word, with the active word drawn in the highlight colour
+ */
+function buildDialogueLines(clip, styleName, style, timelineStartMs) {
+  const offset = clip.startMs - timelineStartMs;
+  const durationMs = clip.endMs - clip.startMs;
+  const dialogue = (fromMs, toMs, text) =>
+    `Dialogue: 0,${assTime(offset + fromMs)},${assTime(offset + toMs)},${styleName},,0,0,0,,${text}`;
+
+  const words = timedWords(clip);
+  if (words.length === 0 || style.highlight === 'none') {
+    const text = clip.text || words.map((w) => w.text).join(' ');
+    return text.trim() ? [dialogue(0, durationMs, escapeAssText(text))] : [];
+  }
+
+  if (style.highlight === 'karaoke') {
+    // Each word's sweep lasts until the next word starts; the lead-in before the first word is a plain \k
+    const parts = [];
+    if (words[0].startMs > 0) {
+      parts.push(`{\\k${Math.round(words[0].startMs / 10)}}`);
+    }
+    words.forEach((word, i) => {
+      const untilMs = i + 1 < words.length ? words[i + 1].startMs : Math.max(word.endMs, word.startMs);
+      parts.push(`{\\kf${Math.max(1, Math.round((untilMs - word.startMs) / 10))}}${escapeAssText(word.text)}${i + 1 < words.length ? ' ' : ''}`);
+    });
+    return [dialogue(0, durationMs, parts.join(''))];
+  }
+
+  // Word mode: the whole line stays up, one event per active word
+  const highlight = assColor(style.highlightColor, DEFAULT_CAPTION_STYLE.highlightColor).replace(/^&H(..)/, '&H');
+  const line = (activeIndex) =>
+    words
+      .map((word, i) => (i === activeIndex ? `{\\c${highlight}&}${escapeAssText(word.text)}{\\r}` : escapeAssText(word.text)))
+      .join(' ');
+  const lines = [];
+  if (words[0].startMs > 0) {
+    lines.push(dialogue(0, words[0].startMs, line(-1)));
+  }
+  words.forEach((word, i) => {
+    const untilMs = i + 1 < words.length ? words[i + 1].startMs : durationMs;
+    if (untilMs > word.startMs) {
+      lines.push(dialogue(word.startMs, untilMs, line(i)));
+    }
+  });
+  return lines;
+}
+
+/**
+ * Render caption clips to an ASS script sized to the program frame
+ * @param {Array} captionClips - Plan caption clips ({ startMs, endMs, text, words, style })
+ * @param {number} timelineStartMs - Timeline position that maps to t=0 in the output
+ * @param {number} width - Program frame width
+ * @param {number} height - Program frame height
+ * @returns {string} ASS document
+ */
+function buildAssDocument(captionClips, timelineStartMs, width, height) {
+  const scale = height / 1080;
+  const styles = [];
+  const events = [];
+
+  captionClips.forEach((clip, index) => {
+    const name = `Caption${index + 1}`;
+    const style = resolveCaptionStyle(clip.style);
+    styles.push(buildStyleLine(name, style, scale, textDirectionEncoding(captionText(clip))));
+    events.push(...buildDialogueLines(clip, name, style, timelineStartMs));
+  });
+
+  return assScript(width, height, styles, events);
+}
+
+/**
+ * Assemble an ASS script from Style and Dialogue lines
+ */
+function assScript(width, height, styles, events) {
+  return [
+    '[Script Info]',
+    'ScriptType: v4.00+',
+    `PlayResX: ${width}`,
+    `PlayResY: ${height}`,
+    'WrapStyle: 0',
+    'ScaledBorderAndShadow: yes',
+    '',
+    '[V4+ Styles]',
+    'Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding',
+    ...styles,
+    '',
+    '[Events]',
+    'Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text',
+    ...events,
+    '',
+  ].join('\n');
+}
+
+/**
+ * Copy the font files a script asks for into one directory for libass's fontsdir
+ * An installed emoji face is added when any text contains emoji. Families that aren't
+ * installed are left to libass's own fallback.
+ * @param {Array<{ family: string, bold?: boolean, italic?: boolean }>} faces - Requested faces
+ * @param {string[]} texts - Text the script draws
+ * @param {string} fontsDir - Directory to fill
+ * @returns {Promise<number>} Number of font files copied
+ */
+async function collectFonts(faces, texts, fontsDir) {
+  await fs.ensureDir(fontsDir);
+  const copied = new Set();
+  const copy = async (font) => {
+    if (copied.has(font.path)) return;
+    await fs.copy(font.path, path.join(fontsDir, `${copied.size}_${path.basename(font.path)}`));
+    copied.add(font.path);
+  };
+
+  for (const face of faces) {
+    const font = await resolveFont(face.family, { bold: face.bold, italic: face.italic }).catch(() => null);
+    if (font) {
+      await copy(font);
+    } else {
+      logger.warn('Font not installed, using fallback', { family: face.family });
+    }
+  }
+
+  if (texts.some((text) => EMOJI_PATTERN.test(text))) {
+    for (const family of EMOJI_FONT_FAMILIES) {
+      const font = await resolveFont(family).catch(() => null);
+      if (font) {
+        await copy(font);
+        break;
+      }
+    }
+  }
+  return copied.size;
+}
+
+/**
+ * ass filter for a script, with HarfBuzz shaping (Arabic joining, bidi, ligatures) and bundled fonts
+ */
+function assFilter(assPath, fontsDir = null) {
+  const fontsOption = fontsDir ? `:fontsdir='${escapeFilterPath(fontsDir)}'` : '';
+  return `ass='${escapeFilterPath(assPath)}':shaping=complex${fontsOption}`;
+}
+
+/**
+ * Copy the faces caption clips use into a fontsdir
+ * @returns {Promise<number>} Number of font files copied
+ */
+function collectCaptionFonts(captionClips, fontsDir) {
+  const faces = captionClips.map((clip) => {
+    const style = resolveCaptionStyle(clip.style);
+    return { family: style.fontFamily, bold: style.bold, italic: style.italic };
+  });
+  return collectFonts(faces, captionClips.map(captionText), fontsDir);
+}
+
+/**
+ * Burn caption clips into the program picture with the ass filter
+ * @param {string} inputPath - Program file
+ * @param {string} outputPath - Output file path
+ * @param {Array} captionClips - Plan caption clips
+ * @param {number} timelineStartMs - Timeline position of the program start
+ * @param {number} width - Program frame width
+ * @param {number} height - Program frame height
+ * @param {string} assPath - Where to write the temporary ASS script
+ * @param {Object} encoding - Resolved encoder settings (see encoding.js)
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ */
+async function burnCaptions(inputPath, outputPath, captionClips, timelineStartMs, width, height, assPath, encoding, trackProcessFn) {
+  await fs.writeFile(assPath, buildAssDocument(captionClips, timelineStartMs, width, height), 'utf8');
+  const fontsDir = `${assPath}.fonts`;
+
+  try {
+    const fontCount = await collectCaptionFonts(captionClips, fontsDir);
+    await new Promise((resolve, reject) => {
+      const command = ffmpeg(inputPath)
+        .videoFilters([assFilter(assPath, fontCount > 0 ? fontsDir : null)])
+        .videoCodec(encoding.videoCodec)
+        .outputOptions([
+          '-c:a copy',
+          ...videoQualityOptions(encoding),
+        ])
+        .output(outputPath)
+        .on('start', () => {
+          if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+        })
+        .on('end', () => resolve())
+        .on('error', (err) => reject(err));
+
+      command.run();
+    });
+  } finally {
+    await fs.remove(assPath);
+    await fs.remove(fontsDir);
+  }
+}
+
+/**
+ * Timeline span a captions preview covers: from where the export starts (the first main-track clip)
+ * to whichever ends last of the program and the captions
+ */
+function captionPreviewSpan(plan) {
+  const captionClips = plan.captionTrack || [];
+  const startMs = plan.mainTrack.length > 0 ? plan.mainTrack[0].startMs : 0;
+  const endMs = Math.max(
+    ...plan.mainTrack.map((clip) => clip.endMs),
+    ...captionClips.map((clip) => clip.endMs),
+    startMs
+  );
+  return { startMs, endMs };
+}
+
+/**
+ * Render only the caption layer over black at low resolution, for proofreading subtitles
+ * Captions are laid out exactly as the export lays them out, scaled down to the preview frame.
+ * @param {Object} plan - Edit plan
+ * @param {string} outputPath - Output MP4
+ * @param {string} assPath - Where to write the temporary ASS script
+ * @param {Object} options - { width, height: program frame (default 1920x1080), previewHeight (default 360), fps (default 15) }
+ * @returns {Promise<{ duration_ms: number, width: number, height: number, captions: number }>}
+ */
+async function renderCaptionsPreview(plan, outputPath, assPath, options = {}, trackProcessFn = null) {
+  const captionClips = plan.captionTrack || [];
+  if (captionClips.length === 0) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, 'Project has no captions to preview', { plan_id: plan.id });
+  }
+  const { startMs, endMs } = captionPreviewSpan(plan);
+  const programWidth = clampOption(options.width, 16, 7680, 1920);
+  const programHeight = clampOption(options.height, 16, 4320, 1080);
+  const height = Math.round(clampOption(options.previewHeight, 144, 1080, 360) / 2) * 2;
+  const width = Math.max(2, Math.round((programWidth * height) / programHeight / 2) * 2);
+  const fps = clampOption(options.fps, 1, 60, 15);
+  const durationSec = (endMs - startMs) / 1000;
+
+  await fs.writeFile(assPath, buildAssDocument(captionClips, startMs, width, height), 'utf8');
+  const fontsDir = `${assPath}.fonts`;
+  try {
+    const fontCount = await collectCaptionFonts(captionClips, fontsDir);
+    await new Promise((resolve, reject) => {
+      const command = ffmpeg()
+        .input(`color=black:s=${width}x${height}:r=${fps}`)
+        .inputFormat('lavfi')
+        .duration(durationSec)
+        .videoFilters([assFilter(assPath, fontCount > 0 ? fontsDir : null)])
+        .videoCodec('libx264')
+        .outputOptions(['-preset', 'ultrafast', '-crf', '30', '-pix_fmt', 'yuv420p', '-movflags', '+faststart', '-an'])
+        .output(outputPath)
+        .on('start', () => {
+          if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+        })
+        .on('end', () => resolve())
+        .on('error', (err) => reject(err));
+
+      command.run();
+    });
+  } finally {
+    await fs.remove(assPath);
+    await fs.remove(fontsDir);
+  }
+  return { duration_ms: endMs - startMs, width, height, captions: captionClips.length };
+}
+
+module.exports = {
+  DEFAULT_CAPTION_STYLE,
+  assColor,
+  assTime,
+  escapeAssText,
+  textDirectionEncoding,
+  assScript,
+  assFilter,
+  collectFonts,
+  buildAssDocument,
+  burnCaptions,
+  captionPreviewSpan,
+  renderCaptionsPreview,
+};
@@ -0,0 +1,101 @@
+const crypto = require('crypto');
+const fs = require('fs-extra');
+const path = require('path');
+const { Transform } = require('stream');
+const { ErrorCode, StarscapeError } = require('./errors');
+
+/**
+ * Delivery checksums - MD5/SHA-256 of a render plus md5sum-style sidecar files
+ * Fragmented MP4/MOV is muxed front to back, so its bytes are hashed on their way to disk and the
+ * master is never read twice. Regular MP4/MOV muxers seek back into the file they write (the mdat
+ * size, faststart's moov move); those files are hashed with one read straight after the render,
+ * while they are still in the page cache.
+ */
+
+const CHECKSUM_ALGORITHMS = ['md5', 'sha256'];
+
+/**
+ * Requested algorithms, in a fixed order (unknown ones are rejected, none is an empty list)
+ */
+function normalizeChecksums(checksums) {
+  const list = Array.isArray(checksums) ? checksums : checksums ? [checksums] : [];
+  const unknown = list.filter((algorithm) => !CHECKSUM_ALGORITHMS.includes(algorithm));
+  if (unknown.length > 0) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Unsupported checksum: ${unknown.join(', ')}`, {
+      checksums: unknown,
+      supported: CHECKSUM_ALGORITHMS,
+    });
+  }
+  return CHECKSUM_ALGORITHMS.filter((algorithm) => list.includes(algorithm));
+}
+
+/**
+ * Pass-through stream that hashes every chunk written through it
+ * @returns {Transform} Stream with digests(): map of algorithm -> hex digest, once it has ended
+ */
+function createChecksumStream(algorithms) {
+  const hashes = algorithms.map((algorithm) => [algorithm, crypto.createHash(algorithm)]);
+  const stream = new Transform({
+    transform(chunk, encoding, callback) {
+      hashes.forEach(([, hash]) => hash.update(chunk));
+      callback(null, chunk);
+    },
+  });
+  stream.digests = () => Object.fromEntries(hashes.map(([algorithm, hash]) => [algorithm, hash.digest('hex')]));
+  return stream;
+}
+
+/**
+ * Hash a file once for every algorithm (the fallback for files muxed out of order)
+ * @returns {Promise<Object>} Map of algorithm -> hex digest
+ */
+function checksumFile(filePath, algorithms) {
+  const hashing = createChecksumStream(algorithms);
+  return new Promise((resolve, reject) => {
+    fs.createReadStream(filePath, { highWaterMark: 4 * 1024 * 1024 })
+      .on('error', reject)
+      .pipe(hashing)
+      .on('finish', () => resolve(hashing.digests()))
+      .resume();
+  });
+}
+
+/**
+ * Sidecar path for a file and algorithm (render.mov -> render.mov.md5)
+ */
+function sidecarPath(filePath, algorithm) {
+  return `${filePath}.${algorithm}`;
+}
+
+/**
+ * Write "<digest>  <filename>" sidecars next to a file, readable by md5sum -c / shasum -c
+ * @param {Object} checksums - Map of algorithm -> hex digest
+ * @returns {Promise<string[]>} Sidecar paths
+ */
+async function writeChecksumSidecars(filePath, checksums) {
+  const written = [];
+  for (const [algorithm, digest] of Object.entries(checksums)) {
+    const sidecar = sidecarPath(filePath, algorithm);
+    await fs.writeFile(sidecar, `${digest}  ${path.basename(filePath)}\n`, 'utf8');
+    written.push(sidecar);
+  }
+  return written;
+}
+
+/**
+ * Remove a file's sidecars (after the file itself was moved elsewhere)
+ */
+async function removeChecksumSidecars(filePath, checksums) {
+  for (const algorithm of Object.keys(checksums)) {
+    await fs.remove(sidecarPath(filePath, algorithm));
+  }
+}
+
+module.exports = {
+  CHECKSUM_ALGORITHMS,
+  normalizeChecksums,
+  createChecksumStream,
+  checksumFile,
+  writeChecksumSidecars,
+  removeChecksumSidecars,
+};
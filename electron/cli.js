@@ -0,0 +1,263 @@
+#!/usr/bin/env node
+const fs = require('fs-extra');
+const os = require('os');
+const path = require('path');
+const { parseArgs } = require('util');
+const { configureFfmpeg, requireBinary } = require('./ffmpeg');
+const { CacheDirs } = require('./cache');
+const { buildPlan } = require('./editPlan');
+const { executeExportJob } = require('./export');
+const { setLibraryRoot, urlToPath, pathToFileUrl } = require('./paths');
+const { QUALITY_PROFILES } = require('./encoding');
+const { CHECKSUM_ALGORITHMS } = require('./checksums');
+const { fillTemplate, fillTemplateValue } = require('./projectTemplates');
+const { getFfmpegCapabilities } = require('./capabilities');
+const { loadEffectPlugins } = require('./effectPlugins');
+const { ErrorCode, StarscapeError, toStarscapeError } = require('./errors');
+const { scrubValue, scrubRoots } = require('./diagnostics');
+const exportHooks = require('./exportHooks');
+const jobs = require('./jobs');
+const logger = require('./logger');
+
+/**
+ * Headless renders - the export pipeline from a terminal, without a window
+ * Uses the app's cache, job history and export hooks, so a scripted render reuses segments
+ * cached by the app (and the other way round) and shows up in the job list.
+ *
+ *   starscape-cli render project.json --preset master --output-dir ~/Deliveries
+ */
+
+const USAGE = `Usage: starscape-cli render <project.json> [options]
+
+Options:
+  --preset <name>         Quality profile (${Object.keys(QUALITY_PROFILES).join(', ')})
+  --settings <file>       ExportSettings JSON; the options below override it
+  --width <px>            Output width (default: the source's)
+  --height <px>           Output height (default: the source's)
+  --fps <rate>            Output frame rate (default: the source's)
+  --filename <name>       Output file name without extension
+  --output-dir <dir>      Move the finished file (and checksum sidecars) here
+  --checksum <alg>        Also write a checksum sidecar (${CHECKSUM_ALGORITHMS.join(', ')}); repeatable
+  --fragmented            Write fragmented MP4/MOV (checksums are computed while it is written)
+  --data <file>           Template data: one record or an array; renders one file per record
+  --json                  Print results as JSON
+  --quiet                 No progress output
+  -h, --help              Show this help`;
+
+const OPTIONS = {
+  preset: { type: 'string' },
+  settings: { type: 'string' },
+  width: { type: 'string' },
+  height: { type: 'string' },
+  fps: { type: 'string' },
+  filename: { type: 'string' },
+  'output-dir': { type: 'string' },
+  checksum: { type: 'string', multiple: true },
+  fragmented: { type: 'boolean' },
+  data: { type: 'string' },
+  json: { type: 'boolean' },
+  quiet: { type: 'boolean' },
+  help: { type: 'boolean', short: 'h' },
+};
+
+function usageError(message) {
+  return new StarscapeError(ErrorCode.INVALID_ARGUMENT, message);
+}
+
+function positiveNumber(name, value) {
+  const number = Number(value);
+  if (!Number.isFinite(number) || number <= 0) {
+    throw usageError(`--${name} must be a positive number`);
+  }
+  return number;
+}
+
+/**
+ * Parse a render command line into the project path and export settings
+ * @param {string[]} argv - Arguments after the program name
+ * @param {Function} readJson - (path) => parsed JSON, for --settings
+ * @returns {{ help: boolean, projectPath: string, settings: Object, dataPath: string|null, outputDir: string|null, json: boolean, quiet: boolean }}
+ */
+function parseRenderArgs(argv, readJson = (file) => fs.readJsonSync(file)) {
+  let parsed;
+  try {
+    parsed = parseArgs({ args: argv, options: OPTIONS, allowPositionals: true });
+  } catch (e) {
+    throw usageError(e.message);
+  }
+  const { values, positionals } = parsed;
+  if (values.help) {
+    return { help: true };
+  }
+
+  const [command, projectPath, ...extra] = positionals;
+  if (command !== 'render') {
+    throw usageError(command ? `Unknown command: ${command}` : 'Missing command');
+  }
+  if (!projectPath || extra.length > 0) {
+    throw usageError('Expected exactly one project file');
+  }
+
+  // Size and rate left unset follow the sources, as they do for exports from the app
+  const settings = { format: 'mp4', ...(values.settings ? readJson(values.settings) : {}) };
+  if (values.preset) {
+    if (!(values.preset in QUALITY_PROFILES)) {
+      throw usageError(`Unknown preset: ${values.preset}`);
+    }
+    settings.qualityProfile = values.preset;
+  }
+  for (const name of ['width', 'height', 'fps']) {
+    if (values[name] !== undefined) settings[name] = positiveNumber(name, values[name]);
+  }
+  if (values.filename) settings.filename = values.filename;
+  if (values.checksum) settings.checksums = values.checksum;
+  if (values.fragmented) settings.fragmented = true;
+
+  return {
+    help: false,
+    projectPath,
+    settings,
+    dataPath: values.data || null,
+    outputDir: values['output-dir'] || null,
+    json: !!values.json,
+    quiet: !!values.quiet,
+  };
+}
+
+/**
+ * App data folder Electron would use (renders, cache and job history live under it)
+ */
+function appDataPath() {
+  if (process.platform === 'darwin') return path.join(os.homedir(), 'Library', 'Application Support');
+  if (process.platform === 'win32') return process.env.APPDATA || path.join(os.homedir(), 'AppData', 'Roaming');
+  return process.env.XDG_CONFIG_HOME || path.join(os.homedir(), '.config');
+}
+
+/**
+ * Progress events of the export, printed to stderr in place of the window
+ */
+function progressReporter(quiet) {
+  return {
+    send(type, payload) {
+      if (quiet || payload.message === undefined) return;
+      const step = payload.total ? ` [${payload.current}/${payload.total}]` : '';
+      process.stderr.write(`${payload.message}${step}\n`);
+    },
+  };
+}
+
+/**
+ * Render one project and run its preset's export hooks (plus the --output-dir move)
+ */
+async function renderProject(projectJson, settings, { cache, outputDir, reporter, trackProcess }) {
+  const job = jobs.startJob('export', { settings, headless: true });
+  try {
+    const plan = buildPlan(projectJson, { fps: settings.fps });
+    const { metrics, ...rendered } = await executeExportJob(plan, settings, cache, reporter, jobs.trackingFor(job, trackProcess));
+    const actions = [...exportHooks.hooksForPreset(settings.qualityProfile)];
+    if (outputDir) {
+      actions.push({ type: 'move', destination: path.resolve(outputDir) });
+    }
+    let result = rendered;
+    if (actions.length > 0) {
+      const hooked = await exportHooks.runExportHooks(urlToPath(rendered.path), actions, {
+        reveal: () => {},
+        trackProcessFn: jobs.trackingFor(job, trackProcess),
+        checksums: rendered.checksums,
+      });
+      job.hooks = hooked.results;
+      result = { ...rendered, path: pathToFileUrl(hooked.path), hooks: hooked.results };
+    }
+    jobs.finishJob(job, null, metrics);
+    return { ...result, job_id: job.id };
+  } catch (error) {
+    jobs.finishJob(job, error);
+    throw error;
+  }
+}
+
+async function main(argv) {
+  let args;
+  try {
+    args = parseRenderArgs(argv);
+  } catch (e) {
+    process.stderr.write(`${e.message}\n\n${USAGE}\n`);
+    return 2;
+  }
+  if (args.help) {
+    process.stdout.write(`${USAGE}\n`);
+    return 0;
+  }
+
+  const appData = path.join(appDataPath(), 'com.starscape.studio');
+  logger.init(path.join(appData, 'logs'));
+  exportHooks.init(path.join(appData, 'export-hooks.json'));
+  configureFfmpeg();
+  const cache = new CacheDirs({ getPath: () => appDataPath() });
+  await cache.ensureDirectories();
+  jobs.init(path.join(appData, 'jobs.json'), (job) => scrubValue(job, scrubRoots(cache)));
+  setLibraryRoot(cache.base);
+
+  // Ctrl-C stops the running ffmpeg instead of leaving it behind
+  const processes = new Set();
+  const trackProcess = (proc) => {
+    processes.add(proc);
+    proc.on('exit', () => processes.delete(proc));
+  };
+  process.on('SIGINT', () => {
+    processes.forEach((proc) => proc.kill('SIGKILL'));
+    process.exit(130);
+  });
+
+  const reporter = progressReporter(args.quiet || args.json);
+  const failures = [];
+  const results = [];
+  try {
+    requireBinary('ffmpeg', 'Export');
+    requireBinary('ffprobe', 'Export');
+    const caps = await getFfmpegCapabilities();
+    const plugins = await loadEffectPlugins(path.join(appData, 'plugins'), caps.filters);
+    plugins.errors.forEach((entry) => process.stderr.write(`Skipped effect plugin ${entry.file}: ${entry.message}\n`));
+    const projectJson = await fs.readFile(args.projectPath, 'utf8');
+    const data = args.dataPath ? await fs.readJson(args.dataPath) : null;
+    const renders = data === null
+      ? [{ projectJson, settings: args.settings }]
+      : (Array.isArray(data) ? data : [data]).map((record) => ({
+        projectJson: fillTemplate(projectJson, record),
+        settings: fillTemplateValue(args.settings, record),
+      }));
+
+    for (const [index, render] of renders.entries()) {
+      try {
+        const result = await renderProject(render.projectJson, render.settings, { cache, outputDir: args.outputDir, reporter, trackProcess });
+        results.push({ index, result, error: null });
+        if (!args.json) process.stdout.write(`${urlToPath(result.path)}\n`);
+      } catch (e) {
+        const error = toStarscapeError(e).toJSON();
+        failures.push(index);
+        results.push({ index, result: null, error });
+        if (!args.json) process.stderr.write(`Render ${index + 1} failed: ${error.message}\n`);
+      }
+    }
+  } catch (e) {
+    process.stderr.write(`${toStarscapeError(e).message}\n`);
+    return 1;
+  }
+
+  if (args.json) {
+    process.stdout.write(`${JSON.stringify(args.dataPath ? results : results[0], null, 2)}\n`);
+  }
+  return failures.length > 0 ? 1 : 0;
+}
+
+if (require.main === module) {
+  main(process.argv.slice(2)).then((code) => {
+    process.exitCode = code;
+  });
+}
+
+module.exports = {
+  parseRenderArgs,
+  appDataPath,
+  main,
+};
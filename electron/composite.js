@@ -0,0 +1,324 @@
+const ffmpeg = require('fluent-ffmpeg');
+const path = require('path');
+const { buildKeyframeExpr, evaluateKeyframes, hasTransformKeyframes, normalizeKeyframes } = require('./keyframes');
+const { videoQualityOptions } = require('./encoding');
+const { buildVideoEffectFilters } = require('./effects');
+const { cropFilters } = require('./filterUtils');
+
+/**
+ * Logical canvas size used by the frontend Stage - canvasNode coordinates live in this space
+ */
+const CANVAS_WIDTH = 1920;
+const CANVAS_HEIGHT = 1080;
+
+/**
+ * Per-channel blend formulas (A = program below, B = overlay)
+ */
+const BLEND_FORMULAS = {
+  screen: '255-((255-A)*(255-B))/255',
+  multiply: 'A*B/255',
+  add: 'min(255,A+B)',
+};
+
+/**
+ * Check if file is an image based on extension
+ */
+function isImageFile(filePath) {
+  const ext = path.extname(filePath).toLowerCase();
+  return ['.jpg', '.jpeg', '.png', '.gif', '.bmp', '.webp'].includes(ext);
+}
+
+/**
+ * Round to an even pixel count (required by most yuv encoders)
+ */
+function even(n) {
+  return Math.max(2, Math.round(n / 2) * 2);
+}
+
+/**
+ * Resolve an overlay clip's placement in program pixels
+ */
+function resolvePlacement(clip, programWidth, programHeight) {
+  const node = clip.canvasNode || {
+    x: 0,
+    y: 0,
+    width: CANVAS_WIDTH,
+    height: CANVAS_HEIGHT,
+    rotation: 0,
+    opacity: 1,
+  };
+  const sx = programWidth / CANVAS_WIDTH;
+  const sy = programHeight / CANVAS_HEIGHT;
+  return {
+    x: Math.round(node.x * sx),
+    y: Math.round(node.y * sy),
+    width: even(node.width * sx),
+    height: even(node.height * sy),
+    rotation: node.rotation || 0,
+    opacity: typeof node.opacity === 'number' ? node.opacity : 1,
+  };
+}
+
+/**
+ * Placement at the clip's first frame, for compositing paths that can't animate the transform
+ */
+function resolveStaticPlacement(clip, programWidth, programHeight) {
+  const place = resolvePlacement(clip, programWidth, programHeight);
+  if (!hasTransformKeyframes(clip)) return place;
+  const tk = clip.transformKeyframes;
+  const sx = programWidth / CANVAS_WIDTH;
+  const sy = programHeight / CANVAS_HEIGHT;
+  const scale = evaluateKeyframes(tk.scale, 0, 1);
+  const nodeX = evaluateKeyframes(tk.x, 0, place.x / sx);
+  const nodeY = evaluateKeyframes(tk.y, 0, place.y / sy);
+  place.x = Math.round(nodeX * sx + (place.width - place.width * scale) / 2);
+  place.y = Math.round(nodeY * sy + (place.height - place.height * scale) / 2);
+  place.width = even(place.width * scale);
+  place.height = even(place.height * scale);
+  place.rotation = evaluateKeyframes(tk.rotation, 0, place.rotation);
+  return place;
+}
+
+/**
+ * Rotate a placed clip around its center, keeping the corners transparent
+ * @returns {{ x: number, y: number }} Top-left corner of the rotated frame
+ */
+function pushStaticRotation(chain, place) {
+  if (!place.rotation) {
+    return { x: place.x, y: place.y };
+  }
+  const rad = (place.rotation * Math.PI) / 180;
+  chain.push(`rotate=${rad}:c=none:ow=rotw(${rad}):oh=roth(${rad})`);
+  const rotW = Math.abs(place.width * Math.cos(rad)) + Math.abs(place.height * Math.sin(rad));
+  const rotH = Math.abs(place.width * Math.sin(rad)) + Math.abs(place.height * Math.cos(rad));
+  return {
+    x: Math.round(place.x + place.width / 2 - rotW / 2),
+    y: Math.round(place.y + place.height / 2 - rotH / 2),
+  };
+}
+
+/**
+ * Render the clips other overlay clips use as track mattes into full program frames
+ * A matte is placed like any overlay and padded with transparent black outside its frame and
+ * before it starts; after it ends its last frame holds. One labelled copy is made per user.
+ * @returns {Map<string, string[]>} Matte clip id -> stream labels left for its users
+ */
+function buildMatteFilters(overlayClips, timelineStartMs, programWidth, programHeight, filters) {
+  const uses = new Map();
+  for (const clip of overlayClips) {
+    // Blend modes composite full frames and don't take a matte
+    if (BLEND_FORMULAS[clip.blendMode]) continue;
+    if (clip.matte && clip.matte.clipId !== clip.clipId && overlayClips.some((other) => other.clipId === clip.matte.clipId)) {
+      uses.set(clip.matte.clipId, (uses.get(clip.matte.clipId) || 0) + 1);
+    }
+  }
+
+  const mattes = new Map();
+  overlayClips.forEach((clip, idx) => {
+    const count = uses.get(clip.clipId);
+    if (!count || mattes.has(clip.clipId)) return;
+    const startSec = Math.max(0, (clip.startMs - timelineStartMs) / 1000);
+    const place = resolveStaticPlacement(clip, programWidth, programHeight);
+    const chain = [...cropFilters(clip.crop), `scale=${place.width}:${place.height}`, ...buildVideoEffectFilters(clip.effects), 'format=rgba'];
+    const { x, y } = pushStaticRotation(chain, place);
+    chain.push(
+      `pad=${programWidth}:${programHeight}:${x}:${y}:color=black@0`,
+      `tpad=start_duration=${startSec}:color=black@0`
+    );
+    const labels = Array.from({ length: count }, (_, k) => `mt${idx}_${k}`);
+    const split = count > 1 ? `,split=${count}` : '';
+    filters.push(`[${idx + 1}:v]${chain.join(',')}${split}${labels.map((label) => `[${label}]`).join('')}`);
+    mattes.set(clip.clipId, labels);
+  });
+  return mattes;
+}
+
+/**
+ * Build the opacity expression for a clip (keyframes override the static canvasNode opacity)
+ */
+function buildOpacityExpr(clip, timeVar, startSec, staticOpacity) {
+  if (normalizeKeyframes(clip.opacityKeyframes).length > 0) {
+    return `clip(${buildKeyframeExpr(clip.opacityKeyframes, timeVar, startSec, staticOpacity)},0,1)`;
+  }
+  return String(staticOpacity);
+}
+
+/**
+ * Build filter_complex strings compositing overlay clips over the program video
+ * Input 0 is the program video; overlay inputs start at index 1.
+ * @param {Array} overlayClips - Plan clips from overlay video tracks, bottom to top
+ * @param {number} timelineStartMs - Timeline position that maps to t=0 in the output
+ * @param {number} programWidth - Program frame width in pixels
+ * @param {number} programHeight - Program frame height in pixels
+ * @returns {{ filters: string[], output: string }}
+ */
+function buildCompositeFilters(overlayClips, timelineStartMs, programWidth, programHeight) {
+  const filters = [];
+  let prev = '0:v';
+  const sx = programWidth / CANVAS_WIDTH;
+  const sy = programHeight / CANVAS_HEIGHT;
+  const mattes = buildMatteFilters(overlayClips, timelineStartMs, programWidth, programHeight, filters);
+
+  overlayClips.forEach((clip, idx) => {
+    // Matte clips only shape the clips using them
+    if (mattes.has(clip.clipId)) return;
+
+    const input = `${idx + 1}:v`;
+    const startSec = Math.max(0, (clip.startMs - timelineStartMs) / 1000);
+    const endSec = Math.max(startSec, (clip.endMs - timelineStartMs) / 1000);
+    const enable = `between(t,${startSec},${endSec})`;
+    const mode = BLEND_FORMULAS[clip.blendMode] ? clip.blendMode : 'normal';
+    const matte = mode === 'normal' && clip.matte && mattes.has(clip.matte.clipId) ? clip.matte : null;
+
+    const tk = clip.transformKeyframes || {};
+    const animated = mode === 'normal' && !matte && hasTransformKeyframes(clip);
+    // Blend modes and mattes pad to a fixed frame, so they take the transform at the clip's first frame
+    const place = animated
+      ? resolvePlacement(clip, programWidth, programHeight)
+      : resolveStaticPlacement(clip, programWidth, programHeight);
+
+    // A cropped clip is cropped to its active picture before it is placed
+    const chain = cropFilters(clip.crop);
+    let x = place.x;
+    let y = place.y;
+    // Clip effects run on the overlay at its placed size, before alpha is added
+    const effects = buildVideoEffectFilters(clip.effects);
+
+    if (animated) {
+      // Scale and rotation are evaluated per frame on the clip-local clock (t starts at 0)
+      if (normalizeKeyframes(tk.scale).length > 0) {
+        const scaleExpr = buildKeyframeExpr(tk.scale, 't', 0, 1);
+        chain.push(
+          `scale=w='max(2,trunc(${place.width}*(${scaleExpr})/2)*2)':h='max(2,trunc(${place.height}*(${scaleExpr})/2)*2)':eval=frame`
+        );
+      } else {
+        chain.push(`scale=${place.width}:${place.height}`);
+      }
+      chain.push(...effects, 'format=rgba');
+
+      if (normalizeKeyframes(tk.rotation).length > 0 || place.rotation) {
+        const rotExpr = buildKeyframeExpr(tk.rotation, 't', 0, place.rotation);
+        // Size the output for the worst case so the frame never changes size mid-clip
+        chain.push(`rotate=a='(${rotExpr})*PI/180':c=none:ow='hypot(iw,ih)':oh='hypot(iw,ih)'`);
+      }
+
+      // Position keyframes move the node's top-left corner; scaling/rotation stay centered on the node
+      const xExpr = buildKeyframeExpr(tk.x, 't', startSec, place.x / sx);
+      const yExpr = buildKeyframeExpr(tk.y, 't', startSec, place.y / sy);
+      x = `'(${xExpr})*${sx}+${place.width / 2}-overlay_w/2'`;
+      y = `'(${yExpr})*${sy}+${place.height / 2}-overlay_h/2'`;
+    } else {
+      chain.push(`scale=${place.width}:${place.height}`, ...effects, 'format=rgba');
+
+      ({ x, y } = pushStaticRotation(chain, place));
+    }
+
+    const out = `v${idx}`;
+
+    if (matte) {
+      // The matte's alpha or luma is multiplied into the clip's own alpha, both padded to the program frame
+      chain.push(
+        `pad=${programWidth}:${programHeight}:${x}:${y}:color=black@0`,
+        `tpad=start_duration=${startSec}:color=black@0`
+      );
+      const mask = [matte.type === 'luma' ? 'format=gray' : 'alphaextract', ...(matte.invert ? ['negate'] : [])];
+      filters.push(`[${input}]${chain.join(',')},split[ovc${idx}][ova${idx}]`);
+      filters.push(`[ova${idx}]alphaextract[oa${idx}]`);
+      filters.push(`[${mattes.get(matte.clipId).shift()}]${mask.join(',')}[mk${idx}]`);
+      filters.push(`[oa${idx}][mk${idx}]blend=all_mode=multiply[ma${idx}]`);
+      const opacity = normalizeKeyframes(clip.opacityKeyframes).length > 0
+        ? [`geq=r='r(X,Y)':g='g(X,Y)':b='b(X,Y)':a='alpha(X,Y)*${buildOpacityExpr(clip, 'T', startSec, place.opacity)}'`]
+        : place.opacity < 1 ? [`colorchannelmixer=aa=${place.opacity}`] : [];
+      filters.push(`[ovc${idx}][ma${idx}]alphamerge${opacity.map((f) => `,${f}`).join('')}[ov${idx}]`);
+      filters.push(`[${prev}][ov${idx}]overlay=x=0:y=0:eof_action=pass:enable='${enable}'[${out}]`);
+    } else if (mode === 'normal') {
+      chain.push(`setpts=PTS-STARTPTS+${startSec}/TB`);
+      if (normalizeKeyframes(clip.opacityKeyframes).length > 0) {
+        // Animated opacity needs a per-frame expression on the alpha plane
+        const opacity = buildOpacityExpr(clip, 'T', startSec, place.opacity);
+        chain.push(`geq=r='r(X,Y)':g='g(X,Y)':b='b(X,Y)':a='alpha(X,Y)*${opacity}'`);
+      } else if (place.opacity < 1) {
+        chain.push(`colorchannelmixer=aa=${place.opacity}`);
+      }
+      filters.push(`[${input}]${chain.join(',')}[ov${idx}]`);
+      filters.push(`[${prev}][ov${idx}]overlay=x=${x}:y=${y}:eof_action=pass:enable='${enable}'[${out}]`);
+    } else {
+      // Blend modes need full-frame inputs: pad the overlay in space and time with a neutral color
+      const neutral = mode === 'multiply' ? 'white' : 'black';
+      chain.push(
+        `pad=${programWidth}:${programHeight}:${x}:${y}:color=${neutral}`,
+        `tpad=start_duration=${startSec}:color=${neutral}`,
+        'format=gbrp'
+      );
+      const opacity = buildOpacityExpr(clip, 'T', startSec, place.opacity);
+      const expr = `A+((${BLEND_FORMULAS[mode]})-A)*(${opacity})`;
+      filters.push(`[${input}]${chain.join(',')}[ov${idx}]`);
+      filters.push(`[${prev}]format=gbrp[base${idx}]`);
+      filters.push(
+        `[base${idx}][ov${idx}]blend=all_expr='${expr}':eof_action=pass:enable='${enable}',format=yuv420p[${out}]`
+      );
+    }
+
+    prev = out;
+  });
+
+  return { filters, output: prev };
+}
+
+/**
+ * Composite overlay clips onto the program video
+ * @param {string} programPath - Program file produced by concatenation
+ * @param {Array} overlayClips - Plan clips from overlay video tracks
+ * @param {number} timelineStartMs - Timeline position of the program start
+ * @param {number} programWidth - Program frame width
+ * @param {number} programHeight - Program frame height
+ * @param {string} outputPath - Composited output file path
+ * @param {Object} encoding - Resolved encoder settings (see encoding.js)
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ */
+function compositeOverlays(programPath, overlayClips, timelineStartMs, programWidth, programHeight, outputPath, encoding, trackProcessFn) {
+  return new Promise((resolve, reject) => {
+    const command = ffmpeg().input(programPath);
+
+    for (const clip of overlayClips) {
+      const durationSec = (clip.outMs - clip.inMs) / 1000;
+      command.input(clip.srcPath);
+      if (path.extname(clip.srcPath).toLowerCase() === '.gif') {
+        // Animated GIFs play through (and repeat when looping) for the clip's timeline duration
+        const gifDurationSec = clip.loop ? (clip.endMs - clip.startMs) / 1000 : durationSec;
+        command.inputOptions([clip.loop ? '-ignore_loop 0' : '-ignore_loop 1', `-t ${gifDurationSec}`]);
+      } else if (isImageFile(clip.srcPath)) {
+        command.inputOptions(['-loop 1', `-t ${durationSec}`]);
+      } else {
+        command.inputOptions([`-ss ${clip.inMs / 1000}`, `-t ${durationSec}`]);
+      }
+    }
+
+    const { filters, output } = buildCompositeFilters(overlayClips, timelineStartMs, programWidth, programHeight);
+
+    command
+      .complexFilter(filters)
+      .outputOptions([
+        `-map [${output}]`,
+        '-map 0:a?',
+        '-c:a copy',
+        `-c:v ${encoding.videoCodec}`,
+        ...videoQualityOptions(encoding),
+      ])
+      .output(outputPath)
+      .on('start', () => {
+        if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+      })
+      .on('end', () => resolve())
+      .on('error', (err) => reject(err))
+      .run();
+  });
+}
+
+module.exports = {
+  CANVAS_WIDTH,
+  CANVAS_HEIGHT,
+  BLEND_FORMULAS,
+  buildCompositeFilters,
+  compositeOverlays,
+};
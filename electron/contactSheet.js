@@ -0,0 +1,146 @@
+const ffmpeg = require('fluent-ffmpeg');
+const fs = require('fs-extra');
+const path = require('path');
+const crypto = require('crypto');
+const { probeMedia, extractPosterFrame, resolveRotation } = require('./metadata');
+const { buildPlan, findVisibleClip, sourceTimeAt } = require('./editPlan');
+const { PREVIEW_FORMAT, materializeGenerators } = require('./generators');
+const { resolveEncoding } = require('./encoding');
+const { buildVideoEffectFilters } = require('./effects');
+const { conformVideoFilters } = require('./filterUtils');
+
+/**
+ * Size of each tile in the sheet; frames of any aspect ratio are fitted inside it over black
+ * (tile needs every frame the same size)
+ */
+const TILE_WIDTH = 320;
+const TILE_HEIGHT = 180;
+
+/**
+ * Evenly spaced sample times across a duration (centered in each slot)
+ */
+function sampleTimes(durationMs, count, intervalMs) {
+  const step = intervalMs && intervalMs > 0 ? intervalMs : durationMs / count;
+  const times = [];
+  for (let i = 0; i < count; i++) {
+    const t = Math.round(intervalMs ? i * step : (i + 0.5) * step);
+    if (t >= durationMs) break;
+    times.push(t);
+  }
+  return times;
+}
+
+/**
+ * Tile a sequence of images into one JPEG
+ */
+function tileFrames(framePattern, columns, rows, outputPath) {
+  return new Promise((resolve, reject) => {
+    ffmpeg(framePattern)
+      .inputOptions(['-start_number 0'])
+      .videoFilters([
+        ...conformVideoFilters(TILE_WIDTH, TILE_HEIGHT, null),
+        `tile=${columns}x${rows}:padding=4:margin=4`,
+      ])
+      .outputOptions(['-frames:v 1', '-q:v 3'])
+      .output(outputPath)
+      .on('end', () => resolve())
+      .on('error', (err) => reject(new Error(`ffmpeg tile failed: ${err.message}`)))
+      .run();
+  });
+}
+
+/**
+ * Extract the sample frames of a single file into numbered JPEGs
+ */
+async function extractAssetFrames(filePath, times, frameDir) {
+  for (let i = 0; i < times.length; i++) {
+    await extractPosterFrame(filePath, times[i], path.join(frameDir, `frame_${String(i).padStart(3, '0')}.jpg`));
+  }
+}
+
+/**
+ * Extract the sample frames of a project's main track into numbered JPEGs
+ */
+async function extractPlanFrames(plan, times, frameDir) {
+  let index = 0;
+  for (const atMs of times) {
+    const clip = findVisibleClip(plan, atMs);
+    if (!clip) continue;
+    const sourceMs = sourceTimeAt(clip, atMs);
+    const framePath = path.join(frameDir, `frame_${String(index++).padStart(3, '0')}.jpg`);
+    // Rendered generators are upright; sources get their (or the clip's override) rotation
+    const { rotationDeg } = clip.generator ? { rotationDeg: 0 } : await resolveRotation(clip.srcPath, clip.rotationOverride);
+    await extractPosterFrame(clip.srcPath, sourceMs, framePath, rotationDeg, buildVideoEffectFilters(clip.effects));
+  }
+  return index;
+}
+
+/**
+ * Generate a tiled JPEG of evenly spaced frames from an asset or a project timeline
+ * @param {Object} source - { path } for a media file or { projectJson } for a timeline
+ * @param {number} columns - Tiles per row
+ * @param {number} rows - Tile rows
+ * @param {number|null} intervalMs - Spacing between frames (null spreads them over the whole duration)
+ * @param {CacheDirs} cache - Cache directories
+ * @returns {Promise<{ path: string, frame_count: number, cached: boolean }>}
+ */
+async function generateContactSheet(source, columns, rows, intervalMs, cache) {
+  const cols = Math.max(1, Math.min(20, Math.round(columns) || 4));
+  const rowCount = Math.max(1, Math.min(20, Math.round(rows) || 4));
+  const count = cols * rowCount;
+
+  let key;
+  let durationMs;
+  let plan = null;
+
+  if (source && source.path) {
+    const stats = await fs.stat(source.path);
+    key = `${source.path}:${stats.size}:${stats.mtimeMs}`;
+    durationMs = (await probeMedia(source.path)).duration_ms;
+  } else if (source && source.projectJson) {
+    plan = buildPlan(source.projectJson);
+    key = source.projectJson;
+    durationMs = plan.mainTrack.length > 0 ? plan.mainTrack[plan.mainTrack.length - 1].endMs : 0;
+  } else {
+    throw new Error('Contact sheet source must have a path or projectJson');
+  }
+
+  if (!durationMs) {
+    throw new Error('Source has no duration');
+  }
+
+  const hash = crypto
+    .createHash('sha1')
+    .update(`${key}|${cols}x${rowCount}|${intervalMs || 'auto'}`)
+    .digest('hex')
+    .slice(0, 16);
+  const outputPath = cache.contactSheetPath(hash);
+
+  const times = sampleTimes(durationMs, count, intervalMs);
+  if (await fs.pathExists(outputPath)) {
+    return { path: outputPath, frame_count: times.length, cached: true };
+  }
+
+  const frameDir = path.join(cache.previews, `contact_${hash}`);
+  await fs.ensureDir(frameDir);
+  try {
+    let frameCount = times.length;
+    if (plan) {
+      plan = await materializeGenerators(plan, PREVIEW_FORMAT, resolveEncoding(), cache);
+      frameCount = await extractPlanFrames(plan, times, frameDir);
+    } else {
+      await extractAssetFrames(source.path, times, frameDir);
+    }
+    if (frameCount === 0) {
+      throw new Error('No frames to tile');
+    }
+    await tileFrames(path.join(frameDir, 'frame_%03d.jpg'), cols, rowCount, outputPath);
+    return { path: outputPath, frame_count: frameCount, cached: false };
+  } finally {
+    await fs.remove(frameDir);
+  }
+}
+
+module.exports = {
+  generateContactSheet,
+};
@@ -0,0 +1,116 @@
+const fs = require('fs-extra');
+const path = require('path');
+const ffmpeg = require('fluent-ffmpeg');
+const { createChecksumStream } = require('./checksums');
+
+/**
+ * Container-level finishing for exported files (no re-encode)
+ */
+
+// Written on every export so files can be traced back to the app
+const ENCODER_TAG = 'Starscape Studio';
+
+// Longest tag value written; longer values are truncated
+const MAX_TAG_LENGTH = 1024;
+
+/**
+ * Clean a user-supplied tag value (control characters dropped, trimmed, length capped)
+ */
+function tagValue(value) {
+  if (typeof value !== 'string') return null;
+  const cleaned = value.replace(/[\u0000-\u0008\u000b-\u001f\u007f]/g, '').trim();
+  return cleaned ? cleaned.slice(0, MAX_TAG_LENGTH) : null;
+}
+
+/**
+ * Map ExportSettings.metadata onto ffmpeg metadata keys
+ * The mp4/mov muxers write these as iTunes-style atoms (title -> ©nam, artist -> ©ART,
+ * comment -> ©cmt, date -> ©day, encoder -> ©too); creation_time goes into the movie header.
+ * @param {Object} metadata - { title, artist, author, comment, creationDate }
+ * @returns {Object} ffmpeg key -> value
+ */
+function buildMetadataTags(metadata = {}) {
+  const tags = {};
+  const title = tagValue(metadata.title);
+  const artist = tagValue(metadata.artist) || tagValue(metadata.author);
+  const comment = tagValue(metadata.comment);
+  if (title) tags.title = title;
+  if (artist) tags.artist = artist;
+  if (comment) tags.comment = comment;
+
+  const created = metadata.creationDate ? new Date(metadata.creationDate) : null;
+  if (created && !Number.isNaN(created.getTime())) {
+    tags.date = created.toISOString().slice(0, 10);
+    tags.creation_time = created.toISOString();
+  }
+
+  tags.encoder = ENCODER_TAG;
+  return tags;
+}
+
+/**
+ * Remux a finished export: write metadata tags and, when web-optimizing, move the moov atom
+ * to the front so players can start before the whole file downloads
+ * Streams and chapters are copied as-is. Fragmented output (an empty moov up front, then a moof/mdat
+ * pair per keyframe) is muxed front to back, so requested checksums are computed while it is written.
+ * @param {string} inputPath - Finished export
+ * @param {string} outputPath - Output path
+ * @param {{ faststart: boolean, fragmented: boolean, metadata: Object, checksums: string[] }} options - metadata is
+ *   ExportSettings.metadata, checksums the algorithms to hash the written bytes with (fragmented only)
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ * @returns {Promise<Object|null>} Map of algorithm -> hex digest, or null when the file wasn't hashed
+ */
+function finalizeContainer(inputPath, outputPath, { faststart, fragmented = false, metadata, checksums = [] }, trackProcessFn) {
+  // Passed as separate arguments so tag values containing spaces aren't split
+  const options = ['-map', '0', '-map_metadata', '0', '-map_chapters', '0', '-c', 'copy'];
+  for (const [key, value] of Object.entries(buildMetadataTags(metadata))) {
+    options.push('-metadata', `${key}=${value}`);
+  }
+  // A fragmented file already starts with its moov, and faststart's second pass would need a seekable output
+  if (fragmented) {
+    options.push('-movflags', '+frag_keyframe+empty_moov+default_base_moof');
+  } else if (faststart) {
+    options.push('-movflags', '+faststart');
+  }
+  const hashing = fragmented && checksums.length > 0 ? createChecksumStream(checksums) : null;
+
+  return new Promise((resolve, reject) => {
+    const command = ffmpeg()
+      .input(inputPath)
+      .outputOptions(...options)
+      .on('start', () => {
+        if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+      });
+
+    if (!hashing) {
+      command
+        .output(outputPath)
+        .on('end', () => resolve(null))
+        .on('error', (err) => reject(err));
+      command.run();
+      return;
+    }
+
+    // The muxed bytes go through the hashes on their way to the file
+    const file = fs.createWriteStream(outputPath);
+    const written = new Promise((resolveWrite, rejectWrite) => file.on('finish', resolveWrite).on('error', rejectWrite));
+    hashing.pipe(file);
+    const muxed = new Promise((resolveMux, rejectMux) => {
+      command
+        .format(path.extname(outputPath).slice(1))
+        .output(hashing)
+        .on('end', resolveMux)
+        .on('error', (err) => {
+          file.destroy();
+          rejectMux(err);
+        });
+    });
+    Promise.all([muxed, written]).then(() => resolve(hashing.digests()), reject);
+    command.run();
+  });
+}
+
+module.exports = {
+  buildMetadataTags,
+  finalizeContainer,
+};
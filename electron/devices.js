@@ -0,0 +1,140 @@
+const { execFile } = require('child_process');
+const { resolveFfmpegPath } = require('./ffmpeg');
+const { ErrorCode, StarscapeError } = require('./errors');
+
+/**
+ * Camera capabilities - the resolution/frame-rate modes a capture device supports
+ * Each platform's ffmpeg input device lists them differently: avfoundation prints its modes when
+ * asked for an impossible frame rate, dshow has -list_options, v4l2 has -list_formats (sizes only,
+ * no rates). The listing goes to stderr and ffmpeg exits with an error either way.
+ */
+
+/**
+ * ffmpeg arguments that make the platform's capture input list a device's modes
+ * @param {string} platform - process.platform
+ * @param {string} device - avfoundation index or name, dshow device name, or v4l2 device node
+ */
+function capabilityArgs(platform, device) {
+  switch (platform) {
+    case 'darwin':
+      return ['-hide_banner', '-f', 'avfoundation', '-framerate', '10000', '-i', `${device}:none`];
+    case 'win32':
+      return ['-hide_banner', '-f', 'dshow', '-list_options', 'true', '-i', `video=${device}`];
+    case 'linux':
+      if (!/^\/dev\/video\d+$/.test(device)) {
+        throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Not a video device: ${device}`, { device });
+      }
+      return ['-hide_banner', '-f', 'v4l2', '-list_formats', 'all', '-i', device];
+    default:
+      throw new StarscapeError(ErrorCode.DEVICE_NOT_FOUND, `Camera probing is not supported on ${platform}`, { platform });
+  }
+}
+
+/**
+ * avfoundation: "  1280x720@[1.000000 30.000000]fps"
+ */
+function parseAvfoundationModes(output) {
+  const modes = [];
+  for (const match of output.matchAll(/(\d+)x(\d+)@\[([\d.]+)\s+([\d.]+)\]fps/g)) {
+    modes.push({
+      width: Number(match[1]),
+      height: Number(match[2]),
+      min_fps: Number(match[3]),
+      max_fps: Number(match[4]),
+      format: null,
+    });
+  }
+  return modes;
+}
+
+/**
+ * dshow: "  pixel_format=yuyv422  min s=640x480 fps=5 max s=640x480 fps=30" (or vcodec=mjpeg)
+ */
+function parseDshowOptions(output) {
+  const modes = [];
+  const pattern = /(?:pixel_format|vcodec)=(\S+)\s+min s=(\d+)x(\d+) fps=([\d.]+)\s+max s=(\d+)x(\d+) fps=([\d.]+)/g;
+  for (const match of output.matchAll(pattern)) {
+    modes.push({
+      width: Number(match[5]),
+      height: Number(match[6]),
+      min_fps: Number(match[4]),
+      max_fps: Number(match[7]),
+      format: match[1],
+    });
+  }
+  return modes;
+}
+
+/**
+ * v4l2: "Raw       :     yuyv422 :  YUYV 4:2:2 : 640x480 1280x720"
+ * Frame rates aren't listed, so they come back null.
+ */
+function parseV4l2Formats(output) {
+  const modes = [];
+  for (const line of output.split('\n')) {
+    // The description can contain colons ("YUYV 4:2:2"), so the sizes are whatever follows the last one
+    const match = /(?:Raw|Compressed)\s*:\s*(\S+)\s*:.*:\s*((?:\d+x\d+\s*)+)$/.exec(line.trim());
+    if (!match) continue;
+    for (const size of match[2].trim().split(/\s+/)) {
+      const [width, height] = size.split('x').map(Number);
+      if (width > 0 && height > 0) {
+        modes.push({ width, height, min_fps: null, max_fps: null, format: match[1] });
+      }
+    }
+  }
+  return modes;
+}
+
+/**
+ * Drop duplicate modes and order them largest first, then fastest
+ */
+function normalizeModes(modes) {
+  const seen = new Set();
+  return modes
+    .filter((mode) => {
+      const key = `${mode.width}x${mode.height}@${mode.min_fps}-${mode.max_fps}:${mode.format}`;
+      if (seen.has(key)) return false;
+      seen.add(key);
+      return true;
+    })
+    .sort((a, b) => b.width * b.height - a.width * a.height || (b.max_fps || 0) - (a.max_fps || 0));
+}
+
+function parseModes(platform, output) {
+  const parse = { darwin: parseAvfoundationModes, win32: parseDshowOptions, linux: parseV4l2Formats }[platform];
+  return normalizeModes(parse ? parse(output) : []);
+}
+
+/**
+ * Probe a camera's supported modes
+ * @param {string} device - avfoundation index or name, dshow device name, or v4l2 device node
+ * @returns {Promise<{ device: string, modes: Array<{ width: number, height: number, min_fps: number|null, max_fps: number|null, format: string|null }> }>}
+ */
+async function getDeviceCapabilities(device, platform = process.platform) {
+  if (typeof device !== 'string' || device.trim() === '') {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, 'No capture device given', { device });
+  }
+  const args = capabilityArgs(platform, device);
+  return new Promise((resolve, reject) => {
+    execFile(resolveFfmpegPath(), args, { maxBuffer: 4 * 1024 * 1024, timeout: 10000 }, (err, stdout, stderr) => {
+      const modes = parseModes(platform, String(stderr));
+      if (modes.length === 0) {
+        reject(new StarscapeError(ErrorCode.DEVICE_NOT_FOUND, `No capture modes found for ${device}`, {
+          device,
+          detail: String(stderr).trim().split('\n').slice(-3).join('\n'),
+        }));
+        return;
+      }
+      resolve({ device, modes });
+    });
+  });
+}
+
+module.exports = {
+  capabilityArgs,
+  parseAvfoundationModes,
+  parseDshowOptions,
+  parseV4l2Formats,
+  parseModes,
+  getDeviceCapabilities,
+};
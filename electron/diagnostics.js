@@ -0,0 +1,172 @@
+const fs = require('fs-extra');
+const os = require('os');
+const path = require('path');
+const { execFile } = require('child_process');
+const { resolveFfmpegPath } = require('./ffmpeg');
+const logger = require('./logger');
+const { getLastJob } = require('./jobs');
+const { createZip } = require('./zip');
+
+/**
+ * Diagnostics bundle - one zip users can attach to a bug report
+ */
+
+const LOG_ENTRY_LIMIT = 2000;
+
+/**
+ * Strip the directories of absolute paths in a string
+ * Paths under the app's own roots keep their layout behind a placeholder; anything else
+ * (user media, home folders) is reduced to its file name, since extensions and containers
+ * still matter when reproducing a failure.
+ * @param {string} text - Text that may contain paths
+ * @param {Array<[string, string]>} roots - [absolute root, placeholder] pairs, most specific first
+ */
+function scrubPaths(text, roots) {
+  return String(text).replace(/(?:[A-Za-z]:)?[\\/](?:[^\\/\s'"=<>]+[\\/])+/g, (dir) => {
+    for (const [root, placeholder] of roots) {
+      if (root && dir.startsWith(root)) {
+        return placeholder + dir.slice(root.length);
+      }
+    }
+    return '<path>/';
+  });
+}
+
+/**
+ * Apply scrubPaths to every string in a JSON value (job settings, errors, file lists)
+ */
+function scrubValue(value, roots) {
+  if (typeof value === 'string') {
+    return scrubPaths(value, roots);
+  }
+  if (Array.isArray(value)) {
+    return value.map((item) => scrubValue(item, roots));
+  }
+  if (value && typeof value === 'object') {
+    return Object.fromEntries(Object.entries(value).map(([key, item]) => [key, scrubValue(item, roots)]));
+  }
+  return value;
+}
+
+/**
+ * The app roots whose layout survives scrubbing, with their placeholders
+ * @param {CacheDirs|null} cache - Cache directories (null before they are set up)
+ */
+function scrubRoots(cache) {
+  return cache
+    ? [
+        [cache.base, '<cache>'],
+        [cache.renders, '<projects>'],
+      ]
+    : [];
+}
+
+/**
+ * Run `ffmpeg -version` (includes the build configuration line)
+ */
+function ffmpegVersion() {
+  return new Promise((resolve) => {
+    execFile(resolveFfmpegPath(), ['-hide_banner', '-version'], { timeout: 10000 }, (err, stdout) => {
+      resolve(err ? `ffmpeg -version failed: ${err.message}` : stdout);
+    });
+  });
+}
+
+/**
+ * File count and total bytes under a directory
+ */
+async function directoryStats(dir) {
+  let files = 0;
+  let bytes = 0;
+  if (!(await fs.pathExists(dir))) {
+    return { files, bytes };
+  }
+  for (const name of await fs.readdir(dir)) {
+    const stats = await fs.stat(path.join(dir, name)).catch(() => null);
+    if (!stats) continue;
+    if (stats.isDirectory()) {
+      const nested = await directoryStats(path.join(dir, name));
+      files += nested.files;
+      bytes += nested.bytes;
+    } else {
+      files += 1;
+      bytes += stats.size;
+    }
+  }
+  return { files, bytes };
+}
+
+/**
+ * Sizes of each cache directory
+ */
+async function cacheStats(cache) {
+  const dirs = {
+    media: cache.mediaDir,
+    thumbnails: cache.thumbDir,
+    previews: cache.previews,
+    segments: cache.segments,
+    captures: cache.captures,
+    indexes: cache.indexes,
+    frames: cache.frames,
+    projects: cache.renders,
+  };
+  const stats = {};
+  for (const [name, dir] of Object.entries(dirs)) {
+    stats[name] = await directoryStats(dir);
+  }
+  return stats;
+}
+
+/**
+ * Write a diagnostics zip with recent logs, ffmpeg build info, cache stats and the last job's commands
+ * @param {CacheDirs} cache - Cache directories
+ * @param {Object} appInfo - { version, electron, platform } of the running app
+ * @returns {Promise<{ path: string, size_bytes: number }>}
+ */
+async function createDiagnosticsBundle(cache, appInfo = {}) {
+  const roots = scrubRoots(cache);
+  const scrub = (value) => scrubPaths(value, roots);
+
+  const logs = logger.getRecentLogs('debug', LOG_ENTRY_LIMIT)
+    .map((entry) => scrub(JSON.stringify(entry)))
+    .join('\n');
+
+  // Settings, errors and command lines all carry full paths
+  const lastJob = getLastJob() ? scrubValue(getLastJob(), roots) : null;
+  const job = lastJob
+    ? { ...lastJob, commands: lastJob.commands.map((args) => args.join(' ')) }
+    : null;
+
+  const system = {
+    created_at: new Date().toISOString(),
+    app_version: appInfo.version || null,
+    electron_version: appInfo.electron || process.versions.electron || null,
+    node_version: process.versions.node,
+    platform: process.platform,
+    arch: process.arch,
+    os_release: os.release(),
+    cpus: os.cpus().length,
+    total_memory_bytes: os.totalmem(),
+  };
+
+  const entries = [
+    { name: 'system.json', data: JSON.stringify(system, null, 2) },
+    { name: 'ffmpeg.txt', data: scrub(await ffmpegVersion()) },
+    { name: 'cache.json', data: JSON.stringify(await cacheStats(cache), null, 2) },
+    { name: 'last_job.json', data: JSON.stringify(job, null, 2) },
+    { name: 'logs.jsonl', data: logs },
+  ];
+
+  const outputPath = cache.diagnosticsBundlePath();
+  await fs.ensureDir(path.dirname(outputPath));
+  const zip = createZip(entries);
+  await fs.writeFile(outputPath, zip);
+  return { path: outputPath, size_bytes: zip.length };
+}
+
+module.exports = {
+  scrubPaths,
+  scrubValue,
+  scrubRoots,
+  createDiagnosticsBundle,
+};
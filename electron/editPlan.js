@@ -1,7 +1,188 @@
+const { normalizeMarkers } = require('./markers');
+const { toRational, quantizeClip, quantizeMs } = require('./timebase');
+const { resolveAssetSrc, toLongPath } = require('./paths');
+const { normalizeGenerator } = require('./generators');
+const { isAlternateTrack } = require('./audioLanguages');
+const { ErrorCode, StarscapeError } = require('./errors');
+
+const AUDIO_CHANNEL_MODES = ['select', 'downmix', 'split'];
+
+// Drift corrections measure-av-drift can suggest
+const AUDIO_SYNC_METHODS = ['atempo', 'resample'];
+
+// Slowest and fastest clip speeds
+const MIN_CLIP_SPEED = 0.1;
+const MAX_CLIP_SPEED = 10;
+
+/**
+ * Split a clip around its freeze frames
+ * Each freeze holds the frame at source time `atMs` for `durationMs` on the timeline,
+ * pushing the rest of the clip later. Freezes lie inside the clip's startMs..endMs (its span is
+ * the source range plus the holds), so anything pushed past endMs is cut. Freeze pieces carry
+ * `freezeFrame: true` and span [atMs, atMs + durationMs) in inMs/outMs so gap and duration
+ * math stays uniform.
+ */
+function expandFreezeFrames(seqClip, freezeFrames) {
+  if (!Array.isArray(freezeFrames) || freezeFrames.length === 0) {
+    return [seqClip];
+  }
+
+  const freezes = freezeFrames
+    .filter((f) => f && f.durationMs > 0 && f.atMs >= seqClip.inMs && f.atMs <= seqClip.outMs)
+    .sort((a, b) => a.atMs - b.atMs);
+  if (freezes.length === 0) {
+    return [seqClip];
+  }
+
+  const pieces = [];
+  let srcCursor = seqClip.inMs;
+  let tlCursor = seqClip.startMs;
+
+  for (const freeze of freezes) {
+    if (freeze.atMs > srcCursor) {
+      const length = freeze.atMs - srcCursor;
+      pieces.push({ ...seqClip, inMs: srcCursor, outMs: freeze.atMs, startMs: tlCursor, endMs: tlCursor + length });
+      tlCursor += length;
+      srcCursor = freeze.atMs;
+    }
+    pieces.push({
+      ...seqClip,
+      freezeFrame: true,
+      inMs: freeze.atMs,
+      outMs: freeze.atMs + freeze.durationMs,
+      startMs: tlCursor,
+      endMs: tlCursor + freeze.durationMs,
+    });
+    tlCursor += freeze.durationMs;
+  }
+
+  if (seqClip.outMs > srcCursor) {
+    const length = seqClip.outMs - srcCursor;
+    pieces.push({ ...seqClip, inMs: srcCursor, outMs: seqClip.outMs, startMs: tlCursor, endMs: tlCursor + length });
+  }
+
+  return pieces
+    .filter((piece) => piece.startMs < seqClip.endMs)
+    .map((piece) => {
+      const overMs = piece.endMs - seqClip.endMs;
+      return overMs > 0 ? { ...piece, outMs: piece.outMs - overMs, endMs: seqClip.endMs } : piece;
+    });
+}
+
+/**
+ * Flatten a multicam clip into single-angle pieces following its angle switches
+ * Group angles share a common clock: an angle's media starts `offsetMs` into the group
+ * (as reported by align-clips), and the clip's inMs/outMs are positions on that clock.
+ * Switches are { atMs, angleId } relative to the clip start on the timeline.
+ * @returns {Array} Pieces of { asset, inMs, outMs, startMs, endMs }
+ */
+function flattenMulticamClip(clipId, clip, group, assets) {
+  const angles = Array.isArray(group.angles) ? group.angles : [];
+  if (angles.length === 0) {
+    throw new Error(`Multicam group ${group.id} has no angles`);
+  }
+  const angleMap = {};
+  angles.forEach((angle) => {
+    angleMap[angle.id] = angle;
+  });
+
+  const clipDuration = clip.endMs - clip.startMs;
+  const switches = (Array.isArray(clip.multicam.switches) ? clip.multicam.switches : [])
+    .filter((s) => s && angleMap[s.angleId] && s.atMs >= 0 && s.atMs < clipDuration)
+    .sort((a, b) => a.atMs - b.atMs);
+
+  // The first angle plays until the first switch
+  if (switches.length === 0 || switches[0].atMs > 0) {
+    switches.unshift({ atMs: 0, angleId: angles[0].id });
+  }
+
+  const pieces = [];
+  switches.forEach((sw, i) => {
+    const localStart = sw.atMs;
+    const localEnd = i + 1 < switches.length ? switches[i + 1].atMs : clipDuration;
+    if (localEnd <= localStart) return;
+
+    const angle = angleMap[sw.angleId];
+    const asset = assets[angle.assetId];
+    if (!asset) {
+      throw new Error(`Multicam angle ${angle.id} references missing asset ${angle.assetId}`);
+    }
+
+    const offsetMs = angle.offsetMs || 0;
+    const inMs = clip.inMs + localStart - offsetMs;
+    const outMs = clip.inMs + localEnd - offsetMs;
+    if (inMs < 0 || (asset.duration_ms && outMs > asset.duration_ms)) {
+      throw new Error(`Clip ${clipId} switches to angle ${angle.id} outside its recorded media`);
+    }
+
+    // Merge with the previous piece when switching to the angle already playing
+    const prev = pieces[pieces.length - 1];
+    if (prev && prev.asset === asset && prev.outMs === inMs) {
+      prev.outMs = outMs;
+      prev.endMs = clip.startMs + localEnd;
+      return;
+    }
+
+    pieces.push({
+      asset,
+      inMs,
+      outMs,
+      startMs: clip.startMs + localStart,
+      endMs: clip.startMs + localEnd,
+    });
+  });
+
+  return pieces;
+}
+
+/**
+ * Split a main-track clip's audio off when its source range or timeline position differs from the video
+ * (J-cuts and L-cuts). Unset fields follow the video: audioInMs/audioOutMs default to inMs/outMs,
+ * audioOffsetMs (timeline shift from startMs) to 0.
+ * @returns {Object|null} Audio clip for the mixdown, or null when the audio stays with the video
+ */
+function detachAudio(clipId, clip, seqClip) {
+  const inMs = Number.isFinite(clip.audioInMs) ? clip.audioInMs : clip.inMs;
+  const outMs = Number.isFinite(clip.audioOutMs) ? clip.audioOutMs : clip.outMs;
+  const offsetMs = Number.isFinite(clip.audioOffsetMs) ? clip.audioOffsetMs : 0;
+  if (inMs === clip.inMs && outMs === clip.outMs && offsetMs === 0) {
+    return null;
+  }
+  if (inMs < 0 || outMs <= inMs) {
+    throw new Error(`Clip ${clipId} has invalid audio timing: audio out <= audio in`);
+  }
+
+  const startMs = clip.startMs + offsetMs;
+  const audioClip = {
+    clipId,
+    trackId: seqClip.trackId,
+    srcPath: seqClip.srcPath,
+    inMs,
+    outMs,
+    startMs,
+    endMs: startMs + (outMs - inMs),
+  };
+  if (seqClip.generator) {
+    audioClip.generator = seqClip.generator;
+  }
+  if (seqClip.audioEffects) {
+    audioClip.audioEffects = seqClip.audioEffects;
+  }
+  if (seqClip.audioChannels) {
+    audioClip.audioChannels = seqClip.audioChannels;
+  }
+  if (seqClip.gainDb) {
+    audioClip.gainDb = seqClip.gainDb;
+  }
+  return audioClip;
+}
+
 /**
  * Parse project JSON into EditPlan structure
+ * @param {string} projectJsonString - Project JSON
+ * @param {Object} options - { fps } snaps video clip boundaries to exact frames when set
  */
-function buildPlan(projectJsonString) {
+function buildPlan(projectJsonString, options = {}) {
   let parsed;
   try {
     parsed = JSON.parse(projectJsonString);
@@ -9,7 +190,7 @@ function buildPlan(projectJsonString) {
     throw new Error(`Invalid project JSON: ${e.message}`);
   }
 
-  const { id, assets = {}, clips = {}, tracks = {}, canvasNodes = {} } = parsed;
+  const { id, projectName, assets = {}, clips = {}, tracks = {}, canvasNodes = {}, multicamGroups = {}, markers = {}, audioCrossfadeMs } = parsed;
 
   if (!id) {
     throw new Error('Project JSON missing id field');
@@ -17,6 +198,10 @@ function buildPlan(projectJsonString) {
 
   const mainTrack = [];
   const overlayTrack = [];
+  const audioTrack = [];
+  const adjustmentTrack = [];
+  const captionTrack = [];
+  const audioTracks = {};
 
   // Create a map of clipId -> canvasNode for quick lookup
   const canvasNodeMap = {};
@@ -30,82 +215,314 @@ function buildPlan(projectJsonString) {
       continue;
     }
 
-    for (const clipId of track.clipOrder) {
-      const clip = clips[clipId];
-      if (!clip) continue;
+    // Disabled (hidden/muted) tracks are left out of preview and export
+    if (track.enabled === false) {
+      continue;
+    }
 
-      const asset = assets[clip.assetId];
-      if (!asset) continue;
+    // Record per-track mixdown settings (voice/music roles, ducking) and stream tags of commentary/dub tracks
+    if (track.type === 'audio') {
+      audioTracks[trackId] = {
+        audioRole: track.audioRole || null,
+        ducking: track.ducking || null,
+        ...(isAlternateTrack(track) ? { language: track.language || null, name: track.name || null } : {}),
+      };
+    }
 
-      // Convert file:// URLs to local paths
-      let srcPath = asset.src;
-      if (srcPath.startsWith('file://')) {
-        srcPath = srcPath.substring(7); // Remove 'file://' prefix
+    // Adjustment clips carry only effects, applied to everything beneath them
+    if (track.role === 'adjustment') {
+      for (const clipId of track.clipOrder) {
+        const clip = clips[clipId];
+        if (!clip || clip.enabled === false || !Array.isArray(clip.effects) || clip.effects.length === 0) continue;
+        if (clip.endMs <= clip.startMs) {
+          throw new Error(`Clip ${clipId} has invalid timing: end <= start`);
+        }
+        adjustmentTrack.push({
+          trackId,
+          startMs: clip.startMs,
+          endMs: clip.endMs,
+          effects: clip.effects,
+        });
       }
+      continue;
+    }
+
+    // Caption clips carry text (and optional word timings) burned in over the program
+    if (track.role === 'caption') {
+      for (const clipId of track.clipOrder) {
+        const clip = clips[clipId];
+        const caption = clip && clip.caption;
+        if (!caption || clip.enabled === false) continue;
+        const hasWords = Array.isArray(caption.words) && caption.words.length > 0;
+        if (!(typeof caption.text === 'string' && caption.text.trim()) && !hasWords) continue;
+        if (clip.endMs <= clip.startMs) {
+          throw new Error(`Clip ${clipId} has invalid timing: end <= start`);
+        }
+        const captionClip = {
+          trackId,
+          startMs: clip.startMs,
+          endMs: clip.endMs,
+          text: caption.text || '',
+        };
+        if (hasWords) {
+          captionClip.words = caption.words;
+        }
+        if (caption.style) {
+          captionClip.style = caption.style;
+        }
+        captionTrack.push(captionClip);
+      }
+      continue;
+    }
+
+    for (const clipId of track.clipOrder) {
+      const clip = clips[clipId];
+      if (!clip || clip.enabled === false) continue;
 
       // Validate clip timing
       if (clip.outMs <= clip.inMs) {
         throw new Error(`Clip ${clipId} has invalid timing: out <= in`);
       }
 
-      const seqClip = {
-        srcPath,
-        inMs: clip.inMs,
-        outMs: clip.outMs,
-        startMs: clip.startMs,
-        endMs: clip.endMs,
-      };
+      // Loops, freezes and angle switches are timed at 1x, so a retimed clip can't have them
+      const speed = Number.isFinite(clip.speed) && clip.speed !== 1 ? clip.speed : null;
+      if (speed !== null && (speed < MIN_CLIP_SPEED || speed > MAX_CLIP_SPEED)) {
+        throw new Error(`Clip ${clipId} has invalid speed: ${speed} (must be ${MIN_CLIP_SPEED}-${MAX_CLIP_SPEED})`);
+      }
+      if (speed !== null && (clip.loop || clip.multicam || (Array.isArray(clip.freezeFrames) && clip.freezeFrames.length > 0))) {
+        throw new Error(`Clip ${clipId} can't change speed while it loops, freezes or switches angles`);
+      }
 
-      // Attach asset metadata for aspect ratio preservation (especially for images)
-      if (asset.width && asset.height) {
-        seqClip.assetWidth = asset.width;
-        seqClip.assetHeight = asset.height;
+      // Multicam clips flatten into one piece per active angle range
+      let sources;
+      if (clip.multicam) {
+        const group = multicamGroups[clip.multicam.groupId];
+        if (!group) {
+          throw new Error(`Clip ${clipId} references missing multicam group ${clip.multicam.groupId}`);
+        }
+        sources = flattenMulticamClip(clipId, clip, group, assets);
+      } else {
+        const asset = assets[clip.assetId];
+        if (!asset) continue;
+        sources = [{ asset, inMs: clip.inMs, outMs: clip.outMs, startMs: clip.startMs, endMs: clip.endMs }];
       }
 
-      // Attach canvasNode for overlay tracks (PiP transforms)
-      if (track.role === 'overlay' && canvasNodeMap[clipId]) {
-        const canvasNode = canvasNodeMap[clipId];
-        seqClip.canvasNode = {
-          x: canvasNode.x,
-          y: canvasNode.y,
-          width: canvasNode.width,
-          height: canvasNode.height,
-          rotation: canvasNode.rotation,
-          opacity: canvasNode.opacity,
+      for (const source of sources) {
+        const { asset } = source;
+
+        // Generator assets have no file; their media is synthesized at render time
+        const isGenerator = asset.kind === 'generator';
+
+        // Convert file:// / media:// URLs to local paths ffmpeg can open (library URLs resolve into the cache)
+        const srcPath = isGenerator ? null : toLongPath(resolveAssetSrc(asset.src));
+
+        const seqClip = {
+          clipId,
+          trackId,
+          srcPath,
+          inMs: source.inMs,
+          outMs: source.outMs,
+          startMs: source.startMs,
+          endMs: source.endMs,
         };
-      }
 
-      // All clips go to mainTrack for now (we'll handle overlays later)
-      if (track.role === 'main') {
-        mainTrack.push(seqClip);
-      } else {
-        overlayTrack.push(seqClip);
+        if (isGenerator && speed !== null) {
+          throw new Error(`Clip ${clipId} can't change speed: generators render at their clip's length`);
+        }
+        if (isGenerator) {
+          try {
+            seqClip.generator = normalizeGenerator(asset.generator);
+          } catch (e) {
+            throw new Error(`Asset ${clip.assetId}: ${e.message}`);
+          }
+        }
+
+        // Asset name is used by review burn-ins
+        if (asset.name) {
+          seqClip.assetName = asset.name;
+        }
+
+        // Attach asset metadata for aspect ratio preservation (especially for images)
+        if (asset.width && asset.height) {
+          seqClip.assetWidth = asset.width;
+          seqClip.assetHeight = asset.height;
+        }
+
+        // Per-aspect-ratio framing offsets used when reframing exports
+        if (clip.reframe) {
+          seqClip.reframe = clip.reframe;
+        }
+
+        // Clockwise rotation replacing the source's rotation metadata (0 ignores it)
+        if (Number.isFinite(clip.rotationOverride)) {
+          seqClip.rotationOverride = clip.rotationOverride;
+        }
+
+        // Active picture in upright source pixels, cropped before the clip is fitted or placed
+        if (clip.crop && clip.crop.width > 0 && clip.crop.height > 0) {
+          seqClip.crop = { x: clip.crop.x || 0, y: clip.crop.y || 0, width: clip.crop.width, height: clip.crop.height };
+        }
+
+        // Audio crossfade into this clip from the one before it (overrides the project default)
+        if (Number.isFinite(clip.audioCrossfadeMs)) {
+          seqClip.audioCrossfadeMs = clip.audioCrossfadeMs;
+        }
+
+        // Looping clips repeat [inMs, outMs) until endMs on the timeline
+        if (clip.loop) {
+          seqClip.loop = true;
+        }
+
+        // Retimed clips play [inMs, outMs) at `speed` over startMs..endMs; slow motion may interpolate frames
+        if (speed !== null) {
+          seqClip.speed = speed;
+          if (clip.interpolateFrames) {
+            seqClip.interpolateFrames = true;
+          }
+        }
+
+        // Attach per-clip video effects (applied in segment rendering and compositing)
+        if (track.type !== 'audio' && Array.isArray(clip.effects) && clip.effects.length > 0) {
+          seqClip.effects = clip.effects;
+        }
+
+        // Attach per-clip audio effects (applied in segment rendering and mixdown)
+        if (Array.isArray(clip.audioEffects) && clip.audioEffects.length > 0) {
+          seqClip.audioEffects = clip.audioEffects;
+        }
+
+        // Source channel routing (select, downmix or split), applied before the effects
+        if (clip.audioChannels && AUDIO_CHANNEL_MODES.includes(clip.audioChannels.mode)) {
+          seqClip.audioChannels = clip.audioChannels;
+        }
+
+        // Clip gain in dB (e.g. from analyze-clip-gain), applied after routing and before the effects
+        if (Number.isFinite(clip.gainDb) && clip.gainDb !== 0) {
+          seqClip.gainDb = clip.gainDb;
+        }
+
+        // A/V drift correction (from measure-av-drift), applied first when the clip's segment renders
+        if (clip.audioSync && AUDIO_SYNC_METHODS.includes(clip.audioSync.method)) {
+          seqClip.audioSync = clip.audioSync;
+        }
+
+        // Attach compositing options for overlay tracks
+        if (track.role === 'overlay') {
+          if (clip.blendMode) {
+            seqClip.blendMode = clip.blendMode;
+          }
+          if (Array.isArray(clip.opacityKeyframes) && clip.opacityKeyframes.length > 0) {
+            seqClip.opacityKeyframes = clip.opacityKeyframes;
+          }
+          if (clip.transformKeyframes) {
+            seqClip.transformKeyframes = clip.transformKeyframes;
+          }
+          // Another overlay clip whose alpha (or luma) masks this one; the matte clip isn't drawn itself
+          if (clip.matte && clip.matte.clipId) {
+            seqClip.matte = {
+              clipId: clip.matte.clipId,
+              type: clip.matte.type === 'luma' ? 'luma' : 'alpha',
+              invert: !!clip.matte.invert,
+            };
+          }
+        }
+
+        // Attach canvasNode for overlay tracks (PiP transforms)
+        if (track.role === 'overlay' && canvasNodeMap[clipId]) {
+          const canvasNode = canvasNodeMap[clipId];
+          seqClip.canvasNode = {
+            x: canvasNode.x,
+            y: canvasNode.y,
+            width: canvasNode.width,
+            height: canvasNode.height,
+            rotation: canvasNode.rotation,
+            opacity: canvasNode.opacity,
+          };
+        }
+
+        // Audio tracks are mixed and overlay tracks composited over the program
+        if (track.type === 'audio') {
+          audioTrack.push(seqClip);
+        } else if (track.role === 'main') {
+          // Detached audio is mixed in like an audio-track clip; the picture renders over silence
+          const detached = !clip.multicam && !clip.loop ? detachAudio(clipId, clip, seqClip) : null;
+          if (detached && speed !== null) {
+            throw new Error(`Clip ${clipId} can't change speed with detached audio`);
+          }
+          if (detached) {
+            seqClip.audioDetached = true;
+            audioTrack.push(detached);
+          }
+          mainTrack.push(...expandFreezeFrames(seqClip, clip.freezeFrames));
+        } else {
+          overlayTrack.push(seqClip);
+        }
       }
     }
   }
 
+  for (const clip of overlayTrack) {
+    if (clip.matte && (clip.matte.clipId === clip.clipId || !overlayTrack.some((other) => other.clipId === clip.matte.clipId))) {
+      throw new Error(`Clip ${clip.clipId} uses ${clip.matte.clipId} as its matte, which is not an enabled overlay clip`);
+    }
+  }
+
+  // Snap video clip boundaries to frames so 29.97/23.976 exports don't drift by a frame
+  const rate = options.fps ? toRational(options.fps) : null;
+  if (rate) {
+    [mainTrack, overlayTrack].forEach((list) => {
+      list.forEach((clip, i) => {
+        list[i] = quantizeClip(clip, rate);
+      });
+    });
+    [...adjustmentTrack, ...captionTrack].forEach((clip) => {
+      clip.startMs = quantizeMs(clip.startMs, rate);
+      clip.endMs = quantizeMs(clip.endMs, rate);
+    });
+  }
+
   // Sort main track by start time
   mainTrack.sort((a, b) => a.startMs - b.startMs);
 
-  // TODO: Implement proper FFmpeg overlay compositing for overlayTrack clips
-  // For now, temporarily add overlay tracks to mainTrack to prevent them from disappearing
-  // This means they'll be concatenated sequentially rather than composited, but at least they're exported
-  // Future: Use FFmpeg overlay filter to composite PiP clips on top of main track
+  // Overlay clips are composited over the main track during export
   overlayTrack.sort((a, b) => a.startMs - b.startMs);
-  
-  // Merge overlay tracks into main track for now (they'll be processed sequentially)
-  // In a proper implementation, we'd use FFmpeg overlay filters to composite them
-  mainTrack.push(...overlayTrack);
-  mainTrack.sort((a, b) => a.startMs - b.startMs);
 
-  // For overlapping clips (clips on different tracks at same time),
-  // we'll just include all of them for now and let the export handle compositing
-  // In the future, we can add proper multi-track compositing logic here
+  // Overlays running past the last main clip (or with no main clip at all) play over black:
+  // the program is extended with a solid filler up to the latest overlay end
+  const overlayEndMs = Math.max(0, ...overlayTrack.map((clip) => clip.endMs));
+  const programEndMs = mainTrack.length > 0
+    ? mainTrack[mainTrack.length - 1].endMs
+    : Math.min(...overlayTrack.map((clip) => clip.startMs));
+  if (overlayTrack.length > 0 && overlayEndMs > programEndMs) {
+    mainTrack.push({
+      clipId: 'overlay-filler',
+      trackId: null,
+      srcPath: null,
+      generator: normalizeGenerator({ type: 'solid', color: '#000000' }),
+      inMs: 0,
+      outMs: overlayEndMs - programEndMs,
+      startMs: programEndMs,
+      endMs: overlayEndMs,
+    });
+  }
+  audioTrack.sort((a, b) => a.startMs - b.startMs);
+  adjustmentTrack.sort((a, b) => a.startMs - b.startMs);
+  captionTrack.sort((a, b) => a.startMs - b.startMs);
 
   return {
     id,
+    // Display name, used to name exports
+    ...(typeof projectName === 'string' && projectName.trim() ? { name: projectName.trim() } : {}),
     mainTrack,
     overlayTrack,
+    audioTrack,
+    adjustmentTrack,
+    captionTrack,
+    audioTracks,
+    markers: normalizeMarkers(markers),
+    frameRate: rate,
+    // Default crossfade at main-track cuts (null uses the built-in default)
+    audioCrossfadeMs: Number.isFinite(audioCrossfadeMs) ? audioCrossfadeMs : null,
   };
 }
 
@@ -116,7 +533,64 @@ function findVisibleClip(plan, tMs) {
   return plan.mainTrack.find((clip) => clip.startMs <= tMs && tMs < clip.endMs);
 }
 
+/**
+ * Source position a clip shows at a timeline time (freeze frames hold their frame, retimed clips run at their speed)
+ */
+function sourceTimeAt(clip, tMs) {
+  if (clip.freezeFrame) {
+    return clip.inMs;
+  }
+  return clip.inMs + (tMs - clip.startMs) * (clip.speed || 1);
+}
+
+/**
+ * Reduce a plan to one clip, moved to the start of the timeline, for exporting a single treated shot
+ * Every piece of the clip (freeze frames, multicam angles) keeps its trims, rotation, effects and
+ * audio effects, and adjustment layers over it are kept for its span. Overlay and audio-track clips
+ * play as the main track: overlay placement and blending only mean something over the program, and
+ * audio plays over black. Detached audio follows the picture again, and captions and markers are dropped.
+ * @param {Object} plan - Edit plan from buildPlan
+ * @param {string} clipId - Clip to keep
+ */
+function isolateClip(plan, clipId) {
+  const mainPieces = plan.mainTrack.filter((clip) => clip.clipId === clipId);
+  const otherPieces = [...plan.overlayTrack, ...plan.audioTrack].filter((clip) => clip.clipId === clipId);
+  const pieces = mainPieces.length > 0 ? mainPieces : otherPieces;
+  if (pieces.length === 0) {
+    throw new StarscapeError(ErrorCode.NOT_FOUND, `Clip not found in the plan: ${clipId}`, { clip_id: clipId });
+  }
+
+  const startMs = Math.min(...pieces.map((clip) => clip.startMs));
+  const endMs = Math.max(...pieces.map((clip) => clip.endMs));
+  const mainTrack = pieces.map((piece) => {
+    const clip = { ...piece, startMs: piece.startMs - startMs, endMs: piece.endMs - startMs };
+    ['audioDetached', 'canvasNode', 'blendMode', 'opacityKeyframes', 'transformKeyframes', 'matte'].forEach((key) => delete clip[key]);
+    return clip;
+  });
+  const adjustmentTrack = plan.adjustmentTrack
+    .filter((clip) => clip.startMs < endMs && clip.endMs > startMs)
+    .map((clip) => ({
+      ...clip,
+      startMs: Math.max(clip.startMs, startMs) - startMs,
+      endMs: Math.min(clip.endMs, endMs) - startMs,
+    }));
+
+  return {
+    ...plan,
+    id: `${plan.id}_${clipId}`,
+    mainTrack,
+    overlayTrack: [],
+    audioTrack: [],
+    adjustmentTrack,
+    captionTrack: [],
+    markers: [],
+  };
+}
+
 module.exports = {
   buildPlan,
+  flattenMulticamClip,
   findVisibleClip,
+  sourceTimeAt,
+  isolateClip,
 };
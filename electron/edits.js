@@ -0,0 +1,402 @@
+const { ErrorCode, StarscapeError } = require('./errors');
+
+/**
+ * Project edit operations on backend-format project JSON
+ */
+
+/**
+ * Parse project JSON, surfacing syntax errors consistently with buildPlan
+ */
+function parseProject(projectJsonString) {
+  try {
+    return JSON.parse(projectJsonString);
+  } catch (e) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Invalid project JSON: ${e.message}`);
+  }
+}
+
+/**
+ * Compare two JSON-compatible values structurally
+ */
+function sameValue(a, b) {
+  return JSON.stringify(a) === JSON.stringify(b);
+}
+
+/**
+ * Find the id of the track that owns a clip
+ */
+function findClipTrackId(project, clipId) {
+  const entry = Object.entries(project.tracks || {}).find(
+    ([, track]) => Array.isArray(track.clipOrder) && track.clipOrder.includes(clipId)
+  );
+  return entry ? entry[0] : null;
+}
+
+/**
+ * Compare two items ignoring their lock flag (so unlocking alone is not an edit)
+ */
+function sameIgnoringLock(a, b) {
+  if (!a || !b) return a === b;
+  return sameValue({ ...a, locked: undefined }, { ...b, locked: undefined });
+}
+
+/**
+ * Reject edits that touch locked tracks or clips
+ * A locked track's clip order and clips may not change; a locked clip may not change,
+ * move to another track or be deleted. Toggling the lock itself is always allowed.
+ * @param {Object} previous - Project before the edit
+ * @param {Object} next - Project after the edit
+ */
+function assertUnlocked(previous, next) {
+  const prevTracks = previous.tracks || {};
+  const nextTracks = next.tracks || {};
+  const prevClips = previous.clips || {};
+  const nextClips = next.clips || {};
+
+  for (const [trackId, track] of Object.entries(prevTracks)) {
+    if (!track.locked) continue;
+    const nextTrack = nextTracks[trackId];
+    if (!sameIgnoringLock(track, nextTrack)) {
+      throw new StarscapeError(ErrorCode.LOCKED, `Track ${trackId} is locked`, { track_id: trackId });
+    }
+    for (const clipId of track.clipOrder || []) {
+      if (!sameIgnoringLock(prevClips[clipId], nextClips[clipId])) {
+        throw new StarscapeError(ErrorCode.LOCKED, `Track ${trackId} is locked`, { track_id: trackId, clip_id: clipId });
+      }
+    }
+  }
+
+  for (const [clipId, clip] of Object.entries(prevClips)) {
+    if (!clip.locked) continue;
+    if (
+      !sameIgnoringLock(clip, nextClips[clipId]) ||
+      findClipTrackId(previous, clipId) !== findClipTrackId(next, clipId)
+    ) {
+      throw new StarscapeError(ErrorCode.LOCKED, `Clip ${clipId} is locked`, { clip_id: clipId });
+    }
+  }
+}
+
+/**
+ * Generate a clip id in the same format as the frontend
+ */
+function generateClipId() {
+  return Math.random().toString(36).substr(2, 9);
+}
+
+/**
+ * Shift a clip along the timeline
+ */
+function shiftClip(clip, deltaMs) {
+  clip.startMs += deltaMs;
+  clip.endMs += deltaMs;
+}
+
+/**
+ * Where a timeline offset into a clip falls in its source, with the clip's freezes divided there
+ * A freeze holds one source frame for durationMs, so across it the timeline runs ahead of the
+ * source; a freeze the offset lands in is split between the two sides. A retimed clip covers
+ * `speed` ms of source per timeline ms.
+ * @returns {{ srcMs: number, before: Array, after: Array }} Freezes before and after the offset
+ */
+function sourceAt(clip, offsetMs) {
+  const speed = clip.speed || 1;
+  const freezes = (clip.freezeFrames || [])
+    .filter((f) => f && f.durationMs > 0 && f.atMs >= clip.inMs && f.atMs <= clip.outMs)
+    .sort((a, b) => a.atMs - b.atMs);
+  const before = [];
+  const after = [];
+  let srcMs = clip.inMs;
+  let tlMs = 0;
+  let cutMs = null;
+  for (const freeze of freezes) {
+    if (cutMs !== null) {
+      after.push(freeze);
+      continue;
+    }
+    const playMs = (freeze.atMs - srcMs) / speed;
+    if (offsetMs <= tlMs + playMs) {
+      cutMs = srcMs + (offsetMs - tlMs) * speed;
+      after.push(freeze);
+      continue;
+    }
+    tlMs += playMs;
+    srcMs = freeze.atMs;
+    if (offsetMs < tlMs + freeze.durationMs) {
+      const heldMs = offsetMs - tlMs;
+      before.push({ ...freeze, durationMs: heldMs });
+      after.push({ ...freeze, durationMs: freeze.durationMs - heldMs });
+      cutMs = freeze.atMs;
+      continue;
+    }
+    tlMs += freeze.durationMs;
+    before.push(freeze);
+  }
+  return { srcMs: cutMs === null ? srcMs + (offsetMs - tlMs) * speed : cutMs, before, after };
+}
+
+function setFreezeFrames(clip, freezes) {
+  if (freezes.length > 0) {
+    clip.freezeFrames = freezes;
+  } else {
+    delete clip.freezeFrames;
+  }
+}
+
+/**
+ * Insert a new clip right after another on its track (with a copy of its canvas node)
+ */
+function insertClipAfter(project, trackId, clipId, newClip) {
+  project.clips[newClip.id] = newClip;
+  const order = project.tracks[trackId].clipOrder;
+  order.splice(order.indexOf(clipId) + 1, 0, newClip.id);
+  cloneCanvasNode(project, clipId, newClip.id);
+}
+
+/**
+ * Cut `cutMs` off the head of a clip, keeping its end in place
+ * A looping clip can only start at the top of its loop: cut partway through a pass, it becomes a
+ * plain clip playing out that pass, followed by a new looping clip for the rest of its span.
+ */
+function trimClipHead(project, trackId, clipId, cutMs) {
+  const clip = project.clips[clipId];
+  if (!clip.loop || !(clip.outMs > clip.inMs)) {
+    const { srcMs, after } = sourceAt(clip, cutMs);
+    clip.inMs = srcMs;
+    setFreezeFrames(clip, after);
+    clip.startMs += cutMs;
+    return;
+  }
+
+  clip.startMs += cutMs;
+  const loopMs = clip.outMs - clip.inMs;
+  const intoPassMs = cutMs % loopMs;
+  if (intoPassMs === 0) return;
+
+  const restOfPassMs = loopMs - intoPassMs;
+  const spanMs = clip.endMs - clip.startMs;
+  if (spanMs > restOfPassMs) {
+    insertClipAfter(project, trackId, clipId, { ...clip, id: generateClipId(), startMs: clip.startMs + restOfPassMs });
+  }
+  delete clip.loop;
+  clip.inMs += intoPassMs;
+  clip.outMs = clip.inMs + Math.min(spanMs, restOfPassMs);
+  clip.endMs = clip.startMs + Math.min(spanMs, restOfPassMs);
+}
+
+/**
+ * Cut a clip's tail so it ends at `atMs` on the timeline
+ */
+function trimClipTail(clip, atMs) {
+  if (!clip.loop) {
+    const { srcMs, before } = sourceAt(clip, atMs - clip.startMs);
+    clip.outMs = srcMs;
+    setFreezeFrames(clip, before);
+  }
+  clip.endMs = atMs;
+}
+
+/**
+ * Copy the canvas node of a clip for a new clip created by splitting
+ */
+function cloneCanvasNode(project, clipId, newClipId) {
+  const nodes = project.canvasNodes || {};
+  const node = Object.values(nodes).find((n) => n.clipId === clipId);
+  if (!node) return;
+  const nodeId = generateClipId();
+  nodes[nodeId] = { ...node, id: nodeId, clipId: newClipId };
+  project.canvasNodes = nodes;
+}
+
+/**
+ * Remove a clip, its canvas node and its track reference
+ */
+function removeClip(project, trackId, clipId) {
+  delete project.clips[clipId];
+  const track = project.tracks[trackId];
+  track.clipOrder = track.clipOrder.filter((id) => id !== clipId);
+  for (const [nodeId, node] of Object.entries(project.canvasNodes || {})) {
+    if (node.clipId === clipId) {
+      delete project.canvasNodes[nodeId];
+    }
+  }
+}
+
+/**
+ * Split a clip in two at a timeline position
+ * @returns {string|null} Id of the new right-hand clip (null when atMs is outside the clip)
+ */
+function splitClipInPlace(project, clipId, atMs) {
+  const clip = project.clips[clipId];
+  if (!clip || atMs <= clip.startMs || atMs >= clip.endMs) {
+    return null;
+  }
+  const trackId = findClipTrackId(project, clipId);
+  if (project.tracks[trackId].locked) {
+    throw new StarscapeError(ErrorCode.LOCKED, `Track ${trackId} is locked`, { track_id: trackId, clip_id: clipId });
+  }
+  if (clip.locked) {
+    throw new StarscapeError(ErrorCode.LOCKED, `Clip ${clipId} is locked`, { clip_id: clipId });
+  }
+
+  const newClipId = generateClipId();
+  insertClipAfter(project, trackId, clipId, { ...clip, id: newClipId });
+  trimClipHead(project, trackId, newClipId, atMs - clip.startMs);
+  trimClipTail(clip, atMs);
+  return newClipId;
+}
+
+/**
+ * Split a clip at a timeline position
+ * @param {string} projectJson - Project JSON
+ * @param {string} clipId - Clip to split
+ * @param {number} atMs - Timeline position of the cut
+ * @returns {{ project: string, new_clip_id: string }}
+ */
+function splitClipAt(projectJson, clipId, atMs) {
+  const project = parseProject(projectJson);
+  const clip = (project.clips || {})[clipId];
+  if (!clip) {
+    throw new StarscapeError(ErrorCode.NOT_FOUND, `Clip ${clipId} not found`, { clip_id: clipId });
+  }
+  const newClipId = splitClipInPlace(project, clipId, atMs);
+  if (!newClipId) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Split point ${atMs}ms is outside clip ${clipId}`, { clip_id: clipId, at_ms: atMs });
+  }
+  return { project: JSON.stringify(project), new_clip_id: newClipId };
+}
+
+/**
+ * Delete a timeline range and pull everything after it left
+ * Clips straddling the range are trimmed (or split when they span it). Locked tracks
+ * are left untouched; markers inside the range are removed and later ones shifted.
+ * @param {string} projectJson - Project JSON
+ * @param {number} startMs - Range start on the timeline
+ * @param {number} endMs - Range end on the timeline
+ * @param {string[]|null} trackIds - Tracks to ripple (null for all unlocked tracks)
+ * @returns {string} Transformed project JSON
+ */
+function rippleDeleteRange(projectJson, startMs, endMs, trackIds = null) {
+  if (!(endMs > startMs)) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, 'Ripple delete range must have end > start', { start_ms: startMs, end_ms: endMs });
+  }
+  const project = parseProject(projectJson);
+  project.clips = project.clips || {};
+  const rangeMs = endMs - startMs;
+
+  for (const [trackId, track] of Object.entries(project.tracks || {})) {
+    if (trackIds && !trackIds.includes(trackId)) continue;
+    if (track.locked || !Array.isArray(track.clipOrder)) continue;
+
+    for (const clipId of [...track.clipOrder]) {
+      const clip = project.clips[clipId];
+      if (!clip) continue;
+
+      // Entirely before the range
+      if (clip.endMs <= startMs) continue;
+
+      if (clip.locked) {
+        throw new StarscapeError(ErrorCode.LOCKED, `Clip ${clipId} is locked`, { clip_id: clipId });
+      }
+
+      // Clips created by trimming (the right side of a split, a loop's continuation) move left too
+      const existingIds = new Set(track.clipOrder);
+      const shiftNewClips = () => {
+        for (const id of track.clipOrder.filter((newId) => !existingIds.has(newId))) {
+          shiftClip(project.clips[id], -rangeMs);
+        }
+      };
+
+      if (clip.startMs >= endMs) {
+        // Entirely after the range
+        shiftClip(clip, -rangeMs);
+      } else if (clip.startMs >= startMs && clip.endMs <= endMs) {
+        // Entirely inside the range
+        removeClip(project, trackId, clipId);
+      } else if (clip.startMs < startMs && clip.endMs > endMs) {
+        // Spans the range: keep both sides and drop the middle
+        splitClipInPlace(project, clipId, endMs);
+        trimClipTail(clip, startMs);
+        shiftNewClips();
+      } else if (clip.startMs < startMs) {
+        // Tail overlaps the range
+        trimClipTail(clip, startMs);
+      } else {
+        // Head overlaps the range
+        trimClipHead(project, trackId, clipId, endMs - clip.startMs);
+        shiftClip(clip, -rangeMs);
+        shiftNewClips();
+      }
+    }
+  }
+
+  for (const [markerId, marker] of Object.entries(project.markers || {})) {
+    if (marker.timeMs >= endMs) {
+      marker.timeMs -= rangeMs;
+    } else if (marker.timeMs >= startMs) {
+      delete project.markers[markerId];
+    }
+  }
+
+  return JSON.stringify(project);
+}
+
+/**
+ * Remove the gaps between clips on a track, packing them from the timeline start
+ * @param {string} projectJson - Project JSON
+ * @param {string} trackId - Track to close gaps on
+ * @returns {string} Transformed project JSON
+ */
+function closeGaps(projectJson, trackId) {
+  const project = parseProject(projectJson);
+  const track = (project.tracks || {})[trackId];
+  if (!track) {
+    throw new StarscapeError(ErrorCode.NOT_FOUND, `Track ${trackId} not found`, { track_id: trackId });
+  }
+  if (track.locked) {
+    throw new StarscapeError(ErrorCode.LOCKED, `Track ${trackId} is locked`, { track_id: trackId });
+  }
+
+  const trackClipIds = (track.clipOrder || [])
+    .filter((clipId) => project.clips[clipId])
+    .sort((a, b) => project.clips[a].startMs - project.clips[b].startMs);
+
+  let cursorMs = 0;
+  for (const clipId of trackClipIds) {
+    const clip = project.clips[clipId];
+    if (clip.startMs !== cursorMs) {
+      if (clip.locked) {
+        throw new StarscapeError(ErrorCode.LOCKED, `Clip ${clipId} is locked`, { clip_id: clipId });
+      }
+      shiftClip(clip, cursorMs - clip.startMs);
+    }
+    cursorMs = clip.endMs;
+  }
+
+  return JSON.stringify(project);
+}
+
+/**
+ * Apply an edited project, enforcing track and clip locks against the last accepted state
+ * A project with a different id (one just opened or created) replaces the accepted one unchecked.
+ * @param {string} projectJson - Edited project JSON
+ * @param {string|null} acceptedProjectJson - Last accepted project JSON (null when none yet)
+ * @returns {Object} The accepted project
+ */
+function applyEdits(projectJson, acceptedProjectJson = null) {
+  const next = parseProject(projectJson);
+  const accepted = acceptedProjectJson ? parseProject(acceptedProjectJson) : null;
+  if (accepted && accepted.id === next.id) {
+    assertUnlocked(accepted, next);
+  }
+  return next;
+}
+
+module.exports = {
+  parseProject,
+  assertUnlocked,
+  applyEdits,
+  splitClipAt,
+  rippleDeleteRange,
+  closeGaps,
+};
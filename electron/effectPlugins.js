@@ -0,0 +1,311 @@
+const fs = require('fs-extra');
+const path = require('path');
+const { ErrorCode, StarscapeError } = require('./errors');
+
+/**
+ * Effect plugins - per-clip effects defined by JSON descriptors dropped into the plugins folder
+ *
+ *   {
+ *     "id": "posterize", "name": "Posterize", "kind": "video",
+ *     "parameters": { "levels": { "type": "number", "min": 2, "max": 32, "default": 6, "integer": true } },
+ *     "filter": "lutyuv=y=floor(val/(256/{{levels}}))*(256/{{levels}})"
+ *   }
+ *
+ * A clip uses one as { type: 'plugin', plugin: 'posterize', params: { levels: 4 } } in its effects
+ * (kind video) or audioEffects (kind audio). The filter is a plain chain: {{param}} placeholders are
+ * the only variable part, and parameter values are typed (clamped numbers, declared choices, colors,
+ * booleans), so project data can't inject filtergraph syntax. Descriptors are checked when loaded:
+ * every filter must be on the allowlist below, set only the options listed for it, and exist in
+ * the installed ffmpeg. Filters that read or write files, load models or libraries, or take
+ * runtime commands aren't on the list.
+ */
+
+const PLUGIN_KINDS = ['video', 'audio'];
+
+const PARAMETER_TYPES = ['number', 'choice', 'boolean', 'color'];
+
+// Filters plugins may use and the options each may set by name (enable works on all of them).
+// None has an option naming a file, model, library or socket, so positional values are safe too.
+const ALLOWED_FILTERS = {
+  // Video
+  boxblur: ['luma_radius', 'luma_power', 'chroma_radius', 'chroma_power', 'alpha_radius', 'alpha_power', 'lr', 'lp', 'cr', 'cp', 'ar', 'ap'],
+  chromashift: ['cbh', 'cbv', 'crh', 'crv', 'edge'],
+  colorbalance: ['rs', 'gs', 'bs', 'rm', 'gm', 'bm', 'rh', 'gh', 'bh', 'pl'],
+  colorchannelmixer: ['rr', 'rg', 'rb', 'ra', 'gr', 'gg', 'gb', 'ga', 'br', 'bg', 'bb', 'ba', 'ar', 'ag', 'ab', 'aa', 'pc', 'pa'],
+  colorize: ['hue', 'saturation', 'lightness', 'mix'],
+  colortemperature: ['temperature', 'mix', 'pl'],
+  drawbox: ['x', 'y', 'w', 'h', 'width', 'height', 'c', 'color', 't', 'thickness', 'replace'],
+  drawgrid: ['x', 'y', 'w', 'h', 'width', 'height', 'c', 'color', 't', 'thickness', 'replace'],
+  edgedetect: ['high', 'low', 'mode', 'planes'],
+  eq: ['contrast', 'brightness', 'saturation', 'gamma', 'gamma_r', 'gamma_g', 'gamma_b', 'gamma_weight'],
+  gblur: ['sigma', 'steps', 'planes', 'sigmaV'],
+  hflip: [],
+  hqdn3d: ['luma_spatial', 'chroma_spatial', 'luma_tmp', 'chroma_tmp'],
+  hue: ['h', 's', 'H', 'b'],
+  lagfun: ['decay', 'planes'],
+  lutrgb: ['r', 'g', 'b', 'a'],
+  lutyuv: ['y', 'u', 'v', 'a'],
+  monochrome: ['cb', 'cr', 'size', 'high'],
+  negate: ['components', 'negate_alpha'],
+  noise: ['alls', 'allf', 'all_seed', 'all_strength', 'all_flags', 'c0s', 'c0f', 'c1s', 'c1f', 'c2s', 'c2f'],
+  pixelize: ['width', 'height', 'w', 'h', 'mode', 'm', 'planes', 'p'],
+  rgbashift: ['rh', 'rv', 'gh', 'gv', 'bh', 'bv', 'ah', 'av', 'edge'],
+  tmix: ['frames', 'weights', 'scale', 'planes'],
+  unsharp: ['luma_msize_x', 'luma_msize_y', 'luma_amount', 'chroma_msize_x', 'chroma_msize_y', 'chroma_amount', 'lx', 'ly', 'la', 'cx', 'cy', 'ca'],
+  vflip: [],
+  vignette: ['angle', 'a', 'x0', 'y0', 'mode', 'eval', 'dither', 'aspect'],
+  // Audio
+  acompressor: ['level_in', 'mode', 'threshold', 'ratio', 'attack', 'release', 'makeup', 'knee', 'link', 'detection', 'mix'],
+  aecho: ['in_gain', 'out_gain', 'delays', 'decays'],
+  aphaser: ['in_gain', 'out_gain', 'delay', 'decay', 'speed', 'type'],
+  bass: ['frequency', 'f', 'width_type', 't', 'width', 'w', 'gain', 'g', 'poles', 'p', 'mix', 'm'],
+  chorus: ['in_gain', 'out_gain', 'delays', 'decays', 'speeds', 'depths'],
+  crystalizer: ['i', 'c'],
+  equalizer: ['frequency', 'f', 'width_type', 't', 'width', 'w', 'gain', 'g', 'mix', 'm'],
+  extrastereo: ['m', 'c'],
+  flanger: ['delay', 'depth', 'regen', 'width', 'speed', 'shape', 'phase', 'interp'],
+  highpass: ['frequency', 'f', 'poles', 'p', 'width_type', 't', 'width', 'w', 'mix', 'm'],
+  lowpass: ['frequency', 'f', 'poles', 'p', 'width_type', 't', 'width', 'w', 'mix', 'm'],
+  treble: ['frequency', 'f', 'width_type', 't', 'width', 'w', 'gain', 'g', 'poles', 'p', 'mix', 'm'],
+  tremolo: ['f', 'd'],
+  vibrato: ['f', 'd'],
+  volume: ['volume', 'precision', 'eval'],
+};
+
+// Timeline editing option every filter above accepts
+const COMMON_OPTIONS = ['enable'];
+
+// Labels and ; would let a template build its own graph instead of a chain
+const GRAPH_SYNTAX = /[[\];]/;
+
+const PLACEHOLDER = /\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}/g;
+
+let plugins = new Map();
+
+function invalid(message, context = {}) {
+  return new StarscapeError(ErrorCode.INVALID_ARGUMENT, message, context);
+}
+
+/**
+ * Check one parameter declaration and fill in its default
+ */
+function normalizeParameter(name, spec = {}) {
+  if (!PARAMETER_TYPES.includes(spec.type)) {
+    throw invalid(`Parameter ${name} has unsupported type: ${spec.type}`, { parameter: name, supported: PARAMETER_TYPES });
+  }
+  const label = typeof spec.label === 'string' && spec.label.trim() ? spec.label.trim() : name;
+  switch (spec.type) {
+    case 'number': {
+      const min = Number.isFinite(spec.min) ? spec.min : 0;
+      const max = Number.isFinite(spec.max) ? spec.max : 1;
+      if (min > max) throw invalid(`Parameter ${name} has min above max`, { parameter: name });
+      const integer = !!spec.integer;
+      return { type: 'number', label, min, max, integer, default: numberValue({ min, max, integer }, spec.default, min) };
+    }
+    case 'choice': {
+      const options = Array.isArray(spec.options) ? spec.options.filter((option) => /^[A-Za-z0-9_.+-]+$/.test(String(option))).map(String) : [];
+      if (options.length === 0) throw invalid(`Parameter ${name} needs options (letters, digits, _ . + -)`, { parameter: name });
+      return { type: 'choice', label, options, default: options.includes(spec.default) ? spec.default : options[0] };
+    }
+    case 'boolean':
+      return { type: 'boolean', label, default: !!spec.default };
+    default:
+      return { type: 'color', label, default: colorValue(spec.default) || '#FFFFFF' };
+  }
+}
+
+function numberValue(spec, value, fallback) {
+  const num = typeof value === 'number' && Number.isFinite(value) ? value : fallback;
+  const clamped = Math.min(spec.max, Math.max(spec.min, num));
+  return spec.integer ? Math.round(clamped) : clamped;
+}
+
+function colorValue(value) {
+  return typeof value === 'string' && /^#?[0-9a-f]{6}$/i.test(value.trim()) ? `#${value.trim().replace(/^#/, '').toUpperCase()}` : null;
+}
+
+/**
+ * Filter-string form of a parameter value (invalid values fall back to the default)
+ */
+function parameterValue(spec, value) {
+  switch (spec.type) {
+    case 'number':
+      return String(numberValue(spec, value, spec.default));
+    case 'choice':
+      return spec.options.includes(value) ? value : spec.default;
+    case 'boolean':
+      return (typeof value === 'boolean' ? value : spec.default) ? '1' : '0';
+    default:
+      return `0x${(colorValue(value) || spec.default).slice(1)}`;
+  }
+}
+
+/**
+ * Split text on a separator, ignoring separators inside '...' quotes or escaped with a backslash
+ */
+function splitUnquoted(text, separator) {
+  const parts = [];
+  let current = '';
+  let quoted = false;
+  for (let i = 0; i < text.length; i++) {
+    const ch = text[i];
+    if (ch === '\\' && i + 1 < text.length) {
+      current += ch + text[++i];
+    } else if (ch === separator && !quoted) {
+      parts.push(current);
+      current = '';
+    } else {
+      if (ch === "'") quoted = !quoted;
+      current += ch;
+    }
+  }
+  parts.push(current);
+  return parts;
+}
+
+/**
+ * The filters of a chain with the options each sets by name
+ * @returns {Array<{ name: string, options: string[] }>}
+ */
+function chainFilters(chain) {
+  return splitUnquoted(chain, ',').map((part) => {
+    const text = part.trim();
+    const eq = text.indexOf('=');
+    const name = eq === -1 ? text : text.slice(0, eq);
+    const args = eq === -1 ? [] : splitUnquoted(text.slice(eq + 1), ':');
+    const options = args.map((arg) => /^\s*([A-Za-z_][A-Za-z0-9_]*)=/.exec(arg)).filter(Boolean).map((match) => match[1]);
+    return { name, options };
+  });
+}
+
+function fillFilters(plugin, params = {}) {
+  return plugin.filters.map((filter) =>
+    filter.replace(PLACEHOLDER, (_, name) => parameterValue(plugin.parameters[name], params[name]))
+  );
+}
+
+/**
+ * Validate a plugin descriptor
+ * @param {Object} descriptor - Parsed JSON
+ * @param {string[]|null} availableFilters - Filters of the installed ffmpeg (null skips that check)
+ * @returns {Object} { id, name, description, kind, parameters, filters }
+ */
+function normalizePlugin(descriptor, availableFilters = null) {
+  if (!descriptor || typeof descriptor !== 'object') throw invalid('Plugin descriptor must be a JSON object');
+  const { id } = descriptor;
+  if (typeof id !== 'string' || !/^[a-z0-9][a-z0-9_-]*$/.test(id)) {
+    throw invalid(`Plugin id must be lowercase letters, digits, _ or -: ${id}`, { id });
+  }
+  const kind = descriptor.kind === undefined ? 'video' : descriptor.kind;
+  if (!PLUGIN_KINDS.includes(kind)) throw invalid(`Plugin ${id} has unsupported kind: ${kind}`, { id, supported: PLUGIN_KINDS });
+
+  const parameters = {};
+  for (const [name, spec] of Object.entries(descriptor.parameters || {})) {
+    if (!/^[A-Za-z_][A-Za-z0-9_]*$/.test(name)) throw invalid(`Plugin ${id} has an invalid parameter name: ${name}`, { id });
+    parameters[name] = normalizeParameter(name, spec);
+  }
+
+  const filters = (Array.isArray(descriptor.filter) ? descriptor.filter : [descriptor.filter]).filter((f) => typeof f === 'string' && f.trim());
+  if (filters.length === 0) throw invalid(`Plugin ${id} has no filter`, { id });
+  for (const filter of filters) {
+    if (GRAPH_SYNTAX.test(filter)) throw invalid(`Plugin ${id} filter must be a simple chain (no [labels] or ;)`, { id });
+    for (const match of filter.matchAll(PLACEHOLDER)) {
+      if (!parameters[match[1]]) throw invalid(`Plugin ${id} uses undeclared parameter: ${match[1]}`, { id, parameter: match[1] });
+    }
+  }
+
+  const plugin = {
+    id,
+    name: typeof descriptor.name === 'string' && descriptor.name.trim() ? descriptor.name.trim() : id,
+    description: typeof descriptor.description === 'string' ? descriptor.description : '',
+    kind,
+    parameters,
+    filters: filters.map((filter) => filter.trim()),
+  };
+
+  // Parameter values can't add options, so checking the defaults covers every use
+  const used = fillFilters(plugin).flatMap(chainFilters);
+  const names = [...new Set(used.map((filter) => filter.name))];
+  const blocked = names.filter((name) => !Object.prototype.hasOwnProperty.call(ALLOWED_FILTERS, name));
+  if (blocked.length > 0) throw invalid(`Plugin ${id} uses filters that aren't allowed: ${blocked.join(', ')}`, { id, filters: blocked });
+  const options = used.flatMap(({ name, options: set }) =>
+    set.filter((option) => !ALLOWED_FILTERS[name].includes(option) && !COMMON_OPTIONS.includes(option)).map((option) => `${name} ${option}`)
+  );
+  if (options.length > 0) throw invalid(`Plugin ${id} sets options that aren't allowed: ${options.join(', ')}`, { id, options });
+  const missing = availableFilters ? names.filter((name) => !availableFilters.includes(name)) : [];
+  if (missing.length > 0) {
+    throw new StarscapeError(ErrorCode.FFMPEG_FEATURE_MISSING, `Plugin ${id} needs filters this FFmpeg build lacks: ${missing.join(', ')}`, {
+      id,
+      missing_filters: missing,
+    });
+  }
+  return plugin;
+}
+
+/**
+ * Load every *.json descriptor in a folder, replacing the registered plugins
+ * A bad descriptor is reported and skipped; the first plugin with an id wins.
+ * @param {string} dir - Plugins folder (created when missing)
+ * @param {string[]|null} availableFilters - Filters of the installed ffmpeg
+ * @returns {Promise<{ plugins: Array, errors: Array<{ file: string, code: string, message: string }> }>}
+ */
+async function loadEffectPlugins(dir, availableFilters = null) {
+  await fs.ensureDir(dir);
+  const files = (await fs.readdir(dir)).filter((file) => file.toLowerCase().endsWith('.json')).sort();
+  const loaded = new Map();
+  const errors = [];
+  for (const file of files) {
+    try {
+      const plugin = normalizePlugin(await fs.readJson(path.join(dir, file)), availableFilters);
+      if (loaded.has(plugin.id)) throw invalid(`Duplicate plugin id: ${plugin.id}`, { id: plugin.id });
+      loaded.set(plugin.id, plugin);
+    } catch (err) {
+      errors.push({ file, code: err.code || ErrorCode.INVALID_ARGUMENT, message: err.message });
+    }
+  }
+  plugins = loaded;
+  return { plugins: listEffectPlugins(), errors };
+}
+
+/**
+ * Registered plugins (descriptor fields, without the filter template)
+ */
+function listEffectPlugins() {
+  return [...plugins.values()].map(({ filters, ...plugin }) => plugin);
+}
+
+/**
+ * Filters of a clip's plugin effect
+ * @param {Object} effect - { type: 'plugin', plugin, params }
+ * @param {string} kind - 'video' or 'audio' (which effect list the effect came from)
+ */
+function pluginEffectFilters(effect, kind) {
+  const plugin = plugins.get(effect.plugin);
+  if (!plugin) {
+    throw new StarscapeError(ErrorCode.NOT_FOUND, `Effect plugin not installed: ${effect.plugin}`, { plugin: effect.plugin });
+  }
+  if (plugin.kind !== kind) {
+    throw invalid(`Effect plugin ${plugin.id} is a ${plugin.kind} effect`, { plugin: plugin.id });
+  }
+  return fillFilters(plugin, effect.params || {});
+}
+
+/**
+ * Register plugins directly (tests, or descriptors loaded elsewhere)
+ */
+function registerEffectPlugins(descriptors, availableFilters = null) {
+  plugins = new Map(descriptors.map((descriptor) => {
+    const plugin = normalizePlugin(descriptor, availableFilters);
+    return [plugin.id, plugin];
+  }));
+}
+
+module.exports = {
+  PLUGIN_KINDS,
+  ALLOWED_FILTERS,
+  normalizePlugin,
+  loadEffectPlugins,
+  listEffectPlugins,
+  pluginEffectFilters,
+  registerEffectPlugins,
+};
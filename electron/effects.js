@@ -0,0 +1,142 @@
+const ffmpeg = require('fluent-ffmpeg');
+const { clampOption, escapeFilterPath } = require('./filterUtils');
+const { videoQualityOptions } = require('./encoding');
+const { pluginEffectFilters } = require('./effectPlugins');
+
+// Denoise strength presets: hqdn3d spatial/temporal luma and chroma strengths, nlmeans s
+// hqdn3d is fast and mostly temporal; nlmeans cleans heavier sensor noise but renders far slower
+const DENOISE_PRESETS = {
+  hqdn3d: {
+    light: 'hqdn3d=2:1.5:3:2.25',
+    medium: 'hqdn3d=4:3:6:4.5',
+    strong: 'hqdn3d=8:6:12:9',
+  },
+  nlmeans: {
+    light: 'nlmeans=s=2:p=7:r=15',
+    medium: 'nlmeans=s=4:p=7:r=15',
+    strong: 'nlmeans=s=8:p=7:r=15',
+  },
+};
+
+/**
+ * Convert one video effect into ffmpeg filter strings
+ * @param {Object} effect - { type: 'lut' | 'color' | 'blur' | 'noise' | 'vignette' | 'sharpen' | 'denoise' | 'plugin', ...options }
+ * @returns {string[]} Filters (without timeline options)
+ */
+function videoEffectToFilters(effect) {
+  switch (effect.type) {
+    case 'lut':
+      if (!effect.path) return [];
+      return [`lut3d=file='${escapeFilterPath(effect.path)}'`];
+    case 'color': {
+      const brightness = clampOption(effect.brightness, -1, 1, 0);
+      const contrast = clampOption(effect.contrast, 0, 3, 1);
+      const saturation = clampOption(effect.saturation, 0, 3, 1);
+      const gamma = clampOption(effect.gamma, 0.1, 10, 1);
+      return [`eq=brightness=${brightness}:contrast=${contrast}:saturation=${saturation}:gamma=${gamma}`];
+    }
+    case 'blur': {
+      const radius = clampOption(effect.radius, 0, 100, 5);
+      return radius > 0 ? [`gblur=sigma=${radius}`] : [];
+    }
+    case 'noise': {
+      // Film grain; temporal noise changes every frame, otherwise the pattern is fixed
+      const strength = Math.round(clampOption(effect.strength, 0, 100, 20));
+      const flags = effect.temporal === false ? 'u' : 't+u';
+      return strength > 0 ? [`noise=alls=${strength}:allf=${flags}`] : [];
+    }
+    case 'vignette': {
+      // Intensity 0-1 maps onto the lens angle (0.4 is ffmpeg's default of PI/5)
+      const intensity = clampOption(effect.intensity, 0, 1, 0.4);
+      return intensity > 0 ? [`vignette=angle=${(intensity * Math.PI) / 2}`] : [];
+    }
+    case 'sharpen': {
+      // Negative amounts blur; the matrix size must be odd
+      const amount = clampOption(effect.amount, -2, 5, 1);
+      const size = Math.floor(clampOption(effect.size, 3, 23, 5) / 2) * 2 + 1;
+      return amount !== 0 ? [`unsharp=luma_msize_x=${size}:luma_msize_y=${size}:luma_amount=${amount}`] : [];
+    }
+    case 'denoise': {
+      // Unknown methods fall back to hqdn3d, unknown strengths to medium
+      const presets = DENOISE_PRESETS[effect.method] || DENOISE_PRESETS.hqdn3d;
+      return [presets[effect.strength] || presets.medium];
+    }
+    case 'plugin':
+      return pluginEffectFilters(effect, 'video');
+    default:
+      return [];
+  }
+}
+
+/**
+ * Build the filter chain for a list of video effects
+ * @param {Array} effects - Video effects in application order
+ * @param {string|null} enable - Optional timeline expression limiting when the filters apply
+ * @returns {string[]} Filters for -vf / filter_complex
+ */
+function buildVideoEffectFilters(effects, enable = null) {
+  if (!Array.isArray(effects)) {
+    return [];
+  }
+  const filters = effects.flatMap((effect) => (effect ? videoEffectToFilters(effect) : []));
+  if (!enable) {
+    return filters;
+  }
+  return filters.map((filter) => `${filter}:enable='${enable}'`);
+}
+
+/**
+ * Build the filter chain for adjustment clips over the program timeline
+ * Each clip's effects are enabled only during its [startMs, endMs) range.
+ * @param {Array} adjustmentClips - Plan clips from adjustment tracks
+ * @param {number} timelineStartMs - Timeline position that maps to t=0 in the output
+ */
+function buildAdjustmentFilters(adjustmentClips, timelineStartMs) {
+  return adjustmentClips.flatMap((clip) => {
+    const startSec = Math.max(0, (clip.startMs - timelineStartMs) / 1000);
+    const endSec = Math.max(startSec, (clip.endMs - timelineStartMs) / 1000);
+    return buildVideoEffectFilters(clip.effects, `between(t,${startSec},${endSec})`);
+  });
+}
+
+/**
+ * Apply adjustment-layer effects to the program video
+ * @param {string} inputPath - Program file (after compositing)
+ * @param {Array} adjustmentClips - Plan clips from adjustment tracks
+ * @param {number} timelineStartMs - Timeline position of the program start
+ * @param {string} outputPath - Output file path
+ * @param {Object} encoding - Resolved encoder settings (see encoding.js)
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ */
+function applyAdjustments(inputPath, adjustmentClips, timelineStartMs, outputPath, encoding, trackProcessFn) {
+  return new Promise((resolve, reject) => {
+    const filters = buildAdjustmentFilters(adjustmentClips, timelineStartMs);
+    const command = ffmpeg(inputPath);
+
+    if (filters.length > 0) {
+      command.videoFilters(filters);
+    }
+
+    command
+      .videoCodec(encoding.videoCodec)
+      .outputOptions([
+        '-c:a copy',
+        ...videoQualityOptions(encoding),
+      ])
+      .output(outputPath)
+      .on('start', () => {
+        if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+      })
+      .on('end', () => resolve())
+      .on('error', (err) => reject(err));
+
+    command.run();
+  });
+}
+
+module.exports = {
+  DENOISE_PRESETS,
+  buildVideoEffectFilters,
+  buildAdjustmentFilters,
+  applyAdjustments,
+};
@@ -0,0 +1,306 @@
+const os = require('os');
+const { ErrorCode, StarscapeError } = require('./errors');
+const { clampOption } = require('./filterUtils');
+
+/**
+ * Encoder settings shared by every export encode (segments, post stages, final re-encode)
+ */
+
+/**
+ * Defaults matching what exports have always used
+ */
+const DEFAULT_ENCODING = {
+  videoCodec: 'libx264',
+  preset: 'veryfast',
+  crf: 23,
+  bitrate: null,
+  pixFmt: 'yuv420p',
+  profile: null,
+  level: null,
+  audioCodec: 'aac',
+  audioBitrate: 192,
+  threads: null,
+  scaler: null,
+  colorPrimaries: null,
+  colorTransfer: null,
+  colorMatrix: null,
+  colorRange: null,
+};
+
+// Encoders that understand x264-style -preset/-crf
+const X26X_ENCODERS = ['libx264', 'libx265'];
+
+const X26X_PRESETS = ['ultrafast', 'superfast', 'veryfast', 'faster', 'fast', 'medium', 'slow', 'slower', 'veryslow'];
+
+const PIX_FMTS = ['yuv420p', 'yuv422p', 'yuv444p', 'yuv420p10le', 'yuv422p10le', 'yuv444p10le'];
+
+// Intra-frame mezzanine encoders: quality comes from the profile, not a bitrate
+const MEZZANINE_ENCODERS = ['prores_ks', 'dnxhd'];
+
+// Color tags written into the stream (ffmpeg names); unset leaves them unspecified
+const COLOR_PRIMARIES = ['bt709', 'bt2020', 'smpte170m', 'bt470bg', 'smpte432'];
+const COLOR_TRANSFERS = ['bt709', 'smpte2084', 'arib-std-b67', 'smpte170m', 'bt470bg'];
+const COLOR_MATRICES = ['bt709', 'bt2020nc', 'smpte170m', 'bt470bg'];
+// limited is broadcast (16-235), full is 0-255
+const COLOR_RANGES = { limited: 'tv', full: 'pc' };
+
+// Pixel formats each encoder accepts (encoders not listed are left to ffmpeg)
+const CODEC_PIX_FMTS = {
+  libx264: ['yuv420p', 'yuv422p', 'yuv444p', 'yuv420p10le', 'yuv422p10le'],
+  libx265: ['yuv420p', 'yuv422p', 'yuv444p', 'yuv420p10le', 'yuv422p10le'],
+  h264_videotoolbox: ['yuv420p'],
+  hevc_videotoolbox: ['yuv420p', 'yuv420p10le'],
+  h264_nvenc: ['yuv420p', 'yuv444p'],
+  hevc_nvenc: ['yuv420p', 'yuv444p', 'yuv420p10le'],
+  h264_qsv: ['yuv420p'],
+  hevc_qsv: ['yuv420p', 'yuv420p10le'],
+  prores_ks: ['yuv422p10le', 'yuv444p10le'],
+  dnxhd: ['yuv422p', 'yuv422p10le', 'yuv444p10le'],
+};
+
+const PRORES_PROFILES = ['proxy', 'lt', 'standard', 'hq', '4444', '4444xq'];
+
+// DNxHR profiles and their pixel formats (hq/sq are 8-bit, hqx 10-bit, 444 full chroma)
+const DNXHR_PROFILE_PIX_FMTS = {
+  dnxhr_lb: ['yuv422p'],
+  dnxhr_sq: ['yuv422p'],
+  dnxhr_hq: ['yuv422p'],
+  dnxhr_hqx: ['yuv422p10le'],
+  dnxhr_444: ['yuv444p10le'],
+};
+
+// x264 profiles and the pixel formats they can carry
+const X264_PROFILE_PIX_FMTS = {
+  baseline: ['yuv420p'],
+  main: ['yuv420p'],
+  high: ['yuv420p'],
+  high10: ['yuv420p', 'yuv420p10le'],
+  high422: ['yuv420p', 'yuv422p', 'yuv420p10le', 'yuv422p10le'],
+  high444: ['yuv420p', 'yuv422p', 'yuv444p', 'yuv420p10le', 'yuv422p10le'],
+};
+
+// Scaling algorithms for fitting sources to the output frame; lanczos and spline keep
+// upscaled (e.g. 720p to 4K) footage sharper than the default bicubic
+const SCALERS = ['bicubic', 'lanczos', 'spline'];
+
+/**
+ * Named render targets (ExportSettings.qualityProfile); fields set explicitly in the settings win
+ * draft is for review copies: fastest preset at half the output size. The ProRes and DNxHR
+ * profiles are mezzanine masters for post houses: 10-bit (8-bit for DNxHR HQ), PCM audio, MOV.
+ */
+const QUALITY_PROFILES = {
+  draft: { preset: 'ultrafast', crf: 30, audioBitrate: 128, halfResolution: true },
+  standard: { preset: 'veryfast', crf: 23, audioBitrate: 192 },
+  master: { preset: 'slow', crf: 18, audioBitrate: 320 },
+  prores_422: { videoCodec: 'prores_ks', profile: 'standard', pixFmt: 'yuv422p10le', audioCodec: 'pcm_s16le', format: 'mov' },
+  prores_422hq: { videoCodec: 'prores_ks', profile: 'hq', pixFmt: 'yuv422p10le', audioCodec: 'pcm_s16le', format: 'mov' },
+  prores_4444: { videoCodec: 'prores_ks', profile: '4444', pixFmt: 'yuv444p10le', audioCodec: 'pcm_s16le', format: 'mov' },
+  dnxhr_hq: { videoCodec: 'dnxhd', profile: 'dnxhr_hq', pixFmt: 'yuv422p', audioCodec: 'pcm_s16le', format: 'mov' },
+  dnxhr_hqx: { videoCodec: 'dnxhd', profile: 'dnxhr_hqx', pixFmt: 'yuv422p10le', audioCodec: 'pcm_s16le', format: 'mov' },
+};
+
+/**
+ * Cap a per-job CPU setting to the machine's core count (null when unset)
+ */
+function coreLimit(value) {
+  if (typeof value !== 'number' || !Number.isFinite(value) || value < 1) {
+    return null;
+  }
+  return Math.min(Math.round(value), Math.max(1, os.cpus().length));
+}
+
+/**
+ * Fill in the settings a quality profile implies (unknown or unset profiles change nothing)
+ */
+function applyQualityProfile(settings = {}) {
+  const profile = QUALITY_PROFILES[settings.qualityProfile];
+  if (!profile) {
+    return settings;
+  }
+  const result = { ...settings };
+  for (const [key, value] of Object.entries(profile)) {
+    if (result[key] === undefined || result[key] === null) {
+      result[key] = value;
+    }
+  }
+  return result;
+}
+
+/**
+ * Normalize ExportSettings quality fields into a complete encoding description
+ * Unknown presets/pixel formats fall back to the defaults rather than failing the encode.
+ * `threads` caps encoder threads per ffmpeg process; unset leaves ffmpeg's automatic choice.
+ * @param {Object} settings - ExportSettings ({ videoCodec, preset, crf, bitrate, pixFmt, profile, level, audioCodec, audioBitrate, threads })
+ */
+function resolveEncoding(settings = {}) {
+  const positive = (value) => (typeof value === 'number' && Number.isFinite(value) && value > 0 ? value : null);
+  return {
+    videoCodec: typeof settings.videoCodec === 'string' && settings.videoCodec ? settings.videoCodec : DEFAULT_ENCODING.videoCodec,
+    preset: X26X_PRESETS.includes(settings.preset) ? settings.preset : DEFAULT_ENCODING.preset,
+    crf: Math.round(clampOption(settings.crf, 0, 51, DEFAULT_ENCODING.crf)),
+    bitrate: positive(settings.bitrate),
+    pixFmt: PIX_FMTS.includes(settings.pixFmt) ? settings.pixFmt : DEFAULT_ENCODING.pixFmt,
+    profile: typeof settings.profile === 'string' && /^[a-z0-9_]+$/i.test(settings.profile) ? settings.profile : null,
+    level: typeof settings.level === 'string' && /^\d(\.\d)?$/.test(settings.level) ? settings.level : null,
+    audioCodec: typeof settings.audioCodec === 'string' && settings.audioCodec ? settings.audioCodec : DEFAULT_ENCODING.audioCodec,
+    audioBitrate: positive(settings.audioBitrate) || DEFAULT_ENCODING.audioBitrate,
+    threads: coreLimit(settings.threads),
+    scaler: SCALERS.includes(settings.scaler) ? settings.scaler : DEFAULT_ENCODING.scaler,
+    colorPrimaries: COLOR_PRIMARIES.includes(settings.colorPrimaries) ? settings.colorPrimaries : DEFAULT_ENCODING.colorPrimaries,
+    colorTransfer: COLOR_TRANSFERS.includes(settings.colorTransfer) ? settings.colorTransfer : DEFAULT_ENCODING.colorTransfer,
+    colorMatrix: COLOR_MATRICES.includes(settings.colorMatrix) ? settings.colorMatrix : DEFAULT_ENCODING.colorMatrix,
+    colorRange: COLOR_RANGES[settings.colorRange] ? settings.colorRange : DEFAULT_ENCODING.colorRange,
+  };
+}
+
+/**
+ * Encoding as it goes into cache keys: a thread cap only changes how fast a file renders,
+ * so segments rendered with and without one are interchangeable (unset scaler and color tags
+ * are left out too, keeping segments cached before they existed)
+ */
+function encodingKey(encoding) {
+  const { threads, ...key } = encoding;
+  for (const field of ['scaler', 'colorPrimaries', 'colorTransfer', 'colorMatrix', 'colorRange']) {
+    if (key[field] === null) delete key[field];
+  }
+  return key;
+}
+
+/**
+ * Problems with an encoding a deliverable spec would reject or the encoder would refuse
+ * @returns {Array<{ code: string, message: string }>}
+ */
+function encodingIssues(encoding) {
+  const issues = [];
+  const tenBit = encoding.pixFmt.endsWith('10le');
+
+  const codecFormats = CODEC_PIX_FMTS[encoding.videoCodec];
+  if (codecFormats && !codecFormats.includes(encoding.pixFmt)) {
+    issues.push({ code: 'pix_fmt_unsupported', message: `${encoding.videoCodec} can't encode ${encoding.pixFmt}` });
+  }
+  const profileFormats = encoding.videoCodec === 'libx264' && encoding.profile ? X264_PROFILE_PIX_FMTS[encoding.profile] : null;
+  if (profileFormats && !profileFormats.includes(encoding.pixFmt)) {
+    issues.push({ code: 'profile_mismatch', message: `H.264 ${encoding.profile} profile can't carry ${encoding.pixFmt}` });
+  }
+  if (encoding.videoCodec === 'prores_ks' && encoding.profile && !PRORES_PROFILES.includes(encoding.profile)) {
+    issues.push({ code: 'profile_mismatch', message: `Unknown ProRes profile: ${encoding.profile}` });
+  }
+  // dnxhd without a DNxHR profile is fixed-resolution DNxHD, which needs a matching bitrate table entry
+  if (encoding.videoCodec === 'dnxhd') {
+    const dnxhrFormats = DNXHR_PROFILE_PIX_FMTS[encoding.profile];
+    if (!dnxhrFormats) {
+      issues.push({ code: 'profile_mismatch', message: 'dnxhd needs a DNxHR profile (dnxhr_lb, dnxhr_sq, dnxhr_hq, dnxhr_hqx, dnxhr_444)' });
+    } else if (!dnxhrFormats.includes(encoding.pixFmt)) {
+      issues.push({ code: 'profile_mismatch', message: `${encoding.profile} can't carry ${encoding.pixFmt}` });
+    }
+  }
+
+  // PQ/HLG are HDR transfers: they need 10-bit samples and BT.2020 color
+  const hdr = encoding.colorTransfer === 'smpte2084' || encoding.colorTransfer === 'arib-std-b67';
+  if (hdr && !tenBit) {
+    issues.push({ code: 'hdr_needs_10bit', message: `${encoding.colorTransfer} transfer needs a 10-bit pixel format` });
+  }
+  if (hdr && encoding.colorPrimaries && encoding.colorPrimaries !== 'bt2020') {
+    issues.push({ code: 'hdr_needs_bt2020', message: `${encoding.colorTransfer} transfer needs BT.2020 primaries` });
+  }
+  if (encoding.colorMatrix === 'bt2020nc' && encoding.colorPrimaries && encoding.colorPrimaries !== 'bt2020') {
+    issues.push({ code: 'matrix_mismatch', message: 'BT.2020 matrix needs BT.2020 primaries' });
+  }
+  return issues;
+}
+
+/**
+ * Reject an encoding with any issues before rendering starts
+ */
+function assertEncodingValid(encoding) {
+  const issues = encodingIssues(encoding);
+  if (issues.length > 0) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, issues.map((issue) => issue.message).join('; '), { issues });
+  }
+}
+
+/**
+ * How many timeline segments an export renders at once (ExportSettings.parallelSegments)
+ * Defaults to one at a time; never more than there are cores.
+ */
+function resolveSegmentConcurrency(settings = {}) {
+  return coreLimit(settings.parallelSegments) || 1;
+}
+
+/**
+ * Video rate-control and format options (codec itself is set separately)
+ * -preset/-crf are only passed to x264/x265; other encoders get bitrate control only.
+ * A thread cap is passed as -threads and, for x264/x265, to the encoder's own thread pool too.
+ */
+function videoQualityOptions(encoding) {
+  const options = [];
+  if (X26X_ENCODERS.includes(encoding.videoCodec)) {
+    options.push(`-preset ${encoding.preset}`, `-crf ${encoding.crf}`);
+  }
+  if (encoding.bitrate && !MEZZANINE_ENCODERS.includes(encoding.videoCodec)) {
+    options.push(`-b:v ${encoding.bitrate}k`);
+  }
+  options.push(`-pix_fmt ${encoding.pixFmt}`);
+  if (encoding.profile) {
+    options.push(`-profile:v ${encoding.profile}`);
+  }
+  if (encoding.level) {
+    options.push(`-level:v ${encoding.level}`);
+  }
+  // Color tags only describe the samples; the range is tagged without converting them
+  if (encoding.colorPrimaries) {
+    options.push(`-color_primaries ${encoding.colorPrimaries}`);
+  }
+  if (encoding.colorTransfer) {
+    options.push(`-color_trc ${encoding.colorTransfer}`);
+  }
+  if (encoding.colorMatrix) {
+    options.push(`-colorspace ${encoding.colorMatrix}`);
+  }
+  if (encoding.colorRange) {
+    options.push(`-color_range ${COLOR_RANGES[encoding.colorRange]}`);
+  }
+  if (encoding.threads) {
+    options.push(`-threads ${encoding.threads}`);
+    if (encoding.videoCodec === 'libx264') {
+      options.push(`-x264-params threads=${encoding.threads}`);
+    } else if (encoding.videoCodec === 'libx265') {
+      options.push(`-x265-params pools=${encoding.threads}`);
+    }
+  }
+  return options;
+}
+
+/**
+ * Audio bitrate option for the configured audio codec (PCM has no bitrate to set)
+ */
+function audioQualityOptions(encoding) {
+  return encoding.audioCodec.startsWith('pcm_') ? [] : [`-b:a ${encoding.audioBitrate}k`];
+}
+
+/**
+ * Whether an encode can only be carried by MOV (ProRes, DNxHR and PCM audio have no MP4 mapping),
+ * so the export and its intermediate files are written as MOV
+ */
+function requiresMov(encoding) {
+  return MEZZANINE_ENCODERS.includes(encoding.videoCodec) || encoding.audioCodec.startsWith('pcm_');
+}
+
+module.exports = {
+  DEFAULT_ENCODING,
+  X26X_PRESETS,
+  SCALERS,
+  COLOR_PRIMARIES,
+  COLOR_TRANSFERS,
+  COLOR_MATRICES,
+  QUALITY_PROFILES,
+  applyQualityProfile,
+  resolveEncoding,
+  resolveSegmentConcurrency,
+  encodingKey,
+  encodingIssues,
+  assertEncodingValid,
+  videoQualityOptions,
+  audioQualityOptions,
+  requiresMov,
+};
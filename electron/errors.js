@@ -0,0 +1,82 @@
+/**
+ * Typed errors shared by all IPC commands
+ * Handlers throw via toIpcError so the renderer receives { code, message, context }
+ * (Electron only forwards an error's message, so the payload travels JSON-encoded in it).
+ */
+
+const ErrorCode = {
+  INTERNAL: 'internal',
+  INVALID_ARGUMENT: 'invalid_argument',
+  NOT_FOUND: 'not_found',
+  FILE_NOT_FOUND: 'file_not_found',
+  PATH_NOT_ALLOWED: 'path_not_allowed',
+  DEPENDENCY_MISSING: 'dependency_missing',
+  FFMPEG_FEATURE_MISSING: 'ffmpeg_feature_missing',
+  FFMPEG_FAILED: 'ffmpeg_failed',
+  PROBE_FAILED: 'probe_failed',
+  TIMEOUT: 'timeout',
+  CANCELLED: 'cancelled',
+  CACHE_ERROR: 'cache_error',
+  DEVICE_NOT_FOUND: 'device_not_found',
+  RECORDING_NOT_FOUND: 'recording_not_found',
+  JOB_NOT_FOUND: 'job_not_found',
+  LOCKED: 'locked',
+};
+
+/**
+ * Prefix marking an IPC error message that carries a serialized StarscapeError
+ */
+const IPC_ERROR_MARKER = 'STARSCAPE_ERROR:';
+
+class StarscapeError extends Error {
+  /**
+   * @param {string} code - One of ErrorCode
+   * @param {string} message - Human-readable message
+   * @param {Object} context - Structured details (paths, ids, missing features...)
+   */
+  constructor(code, message, context = {}) {
+    super(message);
+    this.name = 'StarscapeError';
+    this.code = code;
+    this.context = context;
+  }
+
+  toJSON() {
+    return { code: this.code, message: this.message, context: this.context };
+  }
+}
+
+/**
+ * Wrap anything thrown (Error, string from an ffmpeg callback...) as a StarscapeError
+ * @param {*} error - Thrown value
+ * @param {string} fallbackCode - Code for errors that aren't already typed
+ */
+function toStarscapeError(error, fallbackCode = ErrorCode.INTERNAL) {
+  if (error instanceof StarscapeError) {
+    return error;
+  }
+  if (error && error.code === 'ENOENT') {
+    return new StarscapeError(ErrorCode.FILE_NOT_FOUND, error.message, { path: error.path });
+  }
+  const message = error && error.message ? error.message : String(error);
+  return new StarscapeError(fallbackCode, message);
+}
+
+/**
+ * Build the Error an IPC handler throws
+ * @param {*} error - Thrown value
+ * @param {string} action - What failed ("Failed to export project")
+ */
+function toIpcError(error, action) {
+  const typed = toStarscapeError(error);
+  const payload = { ...typed.toJSON(), message: action ? `${action}: ${typed.message}` : typed.message };
+  return new Error(`${IPC_ERROR_MARKER}${JSON.stringify(payload)}`);
+}
+
+module.exports = {
+  ErrorCode,
+  IPC_ERROR_MARKER,
+  StarscapeError,
+  toStarscapeError,
+  toIpcError,
+};
@@ -0,0 +1,92 @@
+/**
+ * Event contract - every main -> renderer event name and the fields its payload carries
+ * Payloads are stamped with a schema version so the renderer can detect shape changes.
+ */
+
+const EVENT_SCHEMA_VERSION = 1;
+
+const EventType = {
+  EXPORT_PROGRESS: 'export-progress',
+  JOB_STATE_CHANGED: 'job-state-changed',
+  RECORD_STATUS: 'record-status',
+  INGEST_PROGRESS: 'ingest-progress',
+  INGEST_TRANSCODE: 'ingest-transcode',
+  CACHE_EVICTED: 'cache-evicted',
+  SCRUB_FRAME: 'scrub-frame',
+  START_RECORDING: 'start-recording',
+  STOP_RECORDING: 'stop-recording',
+  PAUSE_RECORDING: 'pause-recording',
+  HOTKEY_TRIGGERED: 'hotkey-triggered',
+  AUDIO_LEVEL: 'audio-level',
+  POST_RECORD_PROGRESS: 'post-record-progress',
+  APP_SHUTTING_DOWN: 'app-shutting-down',
+  APP_ERROR: 'app-error',
+};
+
+/**
+ * Payload fields per event (anything else passed to emitEvent is dropped)
+ */
+const EVENT_FIELDS = {
+  [EventType.EXPORT_PROGRESS]: ['job_id', 'phase', 'current', 'total', 'message'],
+  [EventType.JOB_STATE_CHANGED]: ['job_id', 'kind', 'status', 'error'],
+  [EventType.RECORD_STATUS]: ['recording_id', 'status', 'output_path', 'duration_ms', 'segments'],
+  [EventType.INGEST_PROGRESS]: ['current', 'total', 'file_name', 'asset_id', 'status', 'error'],
+  // The completed event carries the converted file that replaces the asset's media
+  [EventType.INGEST_TRANSCODE]: ['job_id', 'asset_id', 'status', 'progress', 'reasons', 'file_path', 'library_url', 'file_size', 'metadata', 'error'],
+  [EventType.CACHE_EVICTED]: ['bucket', 'files', 'bytes', 'reason'],
+  // jpeg is the encoded frame (Buffer, arrives as a Uint8Array)
+  [EventType.SCRUB_FRAME]: ['session_id', 'seq', 'at_ms', 'jpeg'],
+  // Recording control events keep the camelCase fields the renderer recorder already uses
+  [EventType.START_RECORDING]: ['recordingId', 'sourceId', 'outputPath', 'settings'],
+  [EventType.STOP_RECORDING]: ['recordingId'],
+  [EventType.PAUSE_RECORDING]: ['recordingId', 'paused'],
+  [EventType.HOTKEY_TRIGGERED]: ['action', 'recording_id', 'output_path'],
+  // step is one of remux, probe, thumbnail, waveform; current/total count the recording's files
+  [EventType.POST_RECORD_PROGRESS]: ['job_id', 'recording_id', 'step', 'current', 'total', 'file_name'],
+  // The last event of a monitor has ended set (and error when the capture failed)
+  [EventType.AUDIO_LEVEL]: ['monitor_id', 'rms_db', 'peak_db', 'clipped', 'ended', 'error'],
+  [EventType.APP_SHUTTING_DOWN]: [],
+  [EventType.APP_ERROR]: ['type', 'message', 'stack', 'promise'],
+};
+
+/**
+ * Build a versioned payload containing only the event's declared fields
+ */
+function buildPayload(type, fields = {}) {
+  const allowed = EVENT_FIELDS[type];
+  if (!allowed) {
+    throw new Error(`Unknown event type: ${type}`);
+  }
+  const payload = { version: EVENT_SCHEMA_VERSION };
+  for (const key of allowed) {
+    if (fields[key] !== undefined) {
+      payload[key] = fields[key];
+    }
+  }
+  return payload;
+}
+
+/**
+ * Send an event to a window or webContents (no-op once it has been destroyed)
+ * @param {BrowserWindow|WebContents|null} target - Window or event.sender
+ * @param {string} type - One of EventType
+ * @param {Object} fields - Payload fields
+ */
+function emitEvent(target, type, fields = {}) {
+  const payload = buildPayload(type, fields);
+  if (!target || (target.isDestroyed && target.isDestroyed())) {
+    return;
+  }
+  const contents = target.webContents || target;
+  if (contents.isDestroyed && contents.isDestroyed()) {
+    return;
+  }
+  contents.send(type, payload);
+}
+
+module.exports = {
+  EVENT_SCHEMA_VERSION,
+  EventType,
+  buildPayload,
+  emitEvent,
+};
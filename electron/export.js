@@ -1,6 +1,39 @@
 const ffmpeg = require('fluent-ffmpeg');
 const fs = require('fs-extra');
 const path = require('path');
+const {
+  AUDIO_CONFORM,
+  buildClipAudioFilters,
+  buildConformFilters,
+  audioConforms,
+  mixdownAudio,
+  findCrossfadeCuts,
+  resolveCrossfadeCuts,
+  crossfadeProgramAudio,
+} = require('./audio');
+const { compositeOverlays } = require('./composite');
+const { ASPECT_RATIOS, computeReframeSize, reframeProgram } = require('./reframe');
+const { buildChapters, embedChapters } = require('./markers');
+const { finalizeContainer } = require('./container');
+const { probeMedia, clipRotation, applyRotation } = require('./metadata');
+const { applyAdjustments, buildAdjustmentFilters, buildVideoEffectFilters } = require('./effects');
+const { burnInReviewOverlay } = require('./burnin');
+const { burnCaptions } = require('./captions');
+const { STEM_MODES, groupStems, renderStem, muxStemStreams } = require('./stems');
+const { normalizeLanguage, mixAudioClips, alternateAudioTracks, muxLanguageStreams } = require('./audioLanguages');
+const { DEFAULT_FILENAME_TEMPLATE, outputNameFields } = require('./outputNames');
+const { normalizeChecksums, checksumFile, writeChecksumSidecars } = require('./checksums');
+const { toRational } = require('./timebase');
+const logger = require('./logger');
+const { createPhaseTimer, runLimited } = require('./jobs');
+const { assertCapabilities } = require('./capabilities');
+const { filterNamesIn, rotationFilters, cropFilters, speedVideoFilters, conformVideoFilters } = require('./filterUtils');
+const { pathToFileUrl, concatListEntry } = require('./paths');
+const { EventType, emitEvent } = require('./events');
+const { hashValue, hashPlan, hashClip } = require('./planHash');
+const { applyQualityProfile, resolveEncoding, resolveSegmentConcurrency, encodingKey, assertEncodingValid, videoQualityOptions, audioQualityOptions, requiresMov } = require('./encoding');
+const { generatorFilters, materializeGenerators } = require('./generators');
+const { canSmartRender, smartRenderClip } = require('./handles');
 
 /**
  * Check if file is an image based on extension
@@ -11,24 +44,252 @@ function isImageFile(filePath) {
   return imageExts.includes(ext);
 }
 
+/**
+ * Filters each post-concat stage always uses (content-dependent ones are added per plan)
+ */
+const STAGE_FILTERS = {
+  crossfade: ['asplit', 'atrim', 'asetpts', 'concat', 'acrossfade', 'aresample', 'aformat'],
+  composite: ['overlay', 'scale', 'rotate', 'setpts', 'colorchannelmixer', 'format'],
+  reframe: ['crop', 'scale'],
+  burnin: ['drawtext', 'ass'],
+  captions: ['ass'],
+  mixdown: ['amix', 'adelay', 'atrim', 'asetpts'],
+  stems: ['amix', 'adelay', 'atrim', 'asetpts', 'aformat'],
+  languages: ['amix', 'adelay', 'atrim', 'asetpts', 'aformat'],
+};
+
+/**
+ * Round a frame dimension down to an even number (4:2:0 encoders reject odd sizes)
+ */
+function evenDimension(value) {
+  return Math.max(2, Math.floor(value / 2) * 2);
+}
+
+/**
+ * Whether two frame rates ("30000/1001", 29.97, ...) are the same exact rate
+ */
+function sameFrameRate(a, b) {
+  const x = toRational(a);
+  const y = toRational(b);
+  return !!x && !!y && x.num * y.den === y.num * x.den;
+}
+
+/**
+ * Resolve the output frame size and rate for an export
+ * Explicit settings win; unset (or -1) fields inherit from the first video clip on the main track.
+ * halfResolution (draft renders) halves whichever size that gives.
+ * @param {Object} settings - ExportSettings
+ * @param {Object} plan - Edit plan
+ * @param {Function} probeSource - Memoized probe (null for unprobeable sources)
+ * @returns {Promise<{ width: number, height: number, frameRate: string }>}
+ */
+async function resolveOutputFormat(settings, plan, probeSource) {
+  let size = null;
+  let sourceRate = null;
+  for (const clip of plan.mainTrack) {
+    if (size && sourceRate) break;
+    // Generators have no native size or rate; they're rendered at whatever the output is
+    if (clip.generator) continue;
+    if (isImageFile(clip.srcPath)) {
+      if (!size && clip.assetWidth && clip.assetHeight) {
+        size = { width: clip.assetWidth, height: clip.assetHeight };
+      }
+      continue;
+    }
+    const meta = await probeSource(clip.srcPath);
+    if (!size && meta && meta.width && meta.height) {
+      // Sources are rendered upright, so a sideways rotation swaps the frame
+      const { rotationDeg } = clipRotation(meta, clip.rotationOverride);
+      const sideways = rotationDeg === 90 || rotationDeg === 270;
+      size = sideways ? { width: meta.height, height: meta.width } : { width: meta.width, height: meta.height };
+    } else if (!size && clip.assetWidth && clip.assetHeight) {
+      size = { width: clip.assetWidth, height: clip.assetHeight };
+    }
+    if (!sourceRate && meta && meta.frame_rate) {
+      sourceRate = meta.frame_rate;
+    }
+  }
+
+  const explicitSize = settings.width > 0 && settings.height > 0;
+  const scale = settings.halfResolution ? 0.5 : 1;
+  const width = (explicitSize ? settings.width : size ? size.width : 1920) * scale;
+  const height = (explicitSize ? settings.height : size ? size.height : 1080) * scale;
+  const rate = toRational(settings.fps > 0 ? settings.fps : sourceRate || plan.frameRate || 30) || { num: 30, den: 1 };
+
+  return {
+    width: evenDimension(width),
+    height: evenDimension(height),
+    frameRate: `${rate.num}/${rate.den}`,
+  };
+}
+
+/**
+ * Collect the ffmpeg filters and encoders an export will reference
+ */
+function collectRequirements(plan, postStages, encoding) {
+  const filters = new Set();
+  const add = (names) => names.forEach((name) => filters.add(name));
+
+  // Every segment's audio is conformed before concat, and transcoded video is fitted to the output frame
+  add(filterNamesIn(buildConformFilters()));
+  add(filterNamesIn(conformVideoFilters(2, 2, '30/1')));
+
+  for (const stage of postStages) {
+    add(STAGE_FILTERS[stage] || []);
+  }
+  if (plan.mainTrack.some((clip) => clip.freezeFrame)) {
+    add(['trim', 'tpad']);
+  }
+  const videoClips = [...plan.mainTrack, ...(plan.overlayTrack || [])];
+  add(filterNamesIn(videoClips.flatMap((clip) => speedVideoFilters(clip, '30/1'))));
+  if ((plan.overlayTrack || []).some((clip) => clip.blendMode && clip.blendMode !== 'normal')) {
+    add(['blend']);
+  }
+  if ((plan.overlayTrack || []).some((clip) => clip.matte)) {
+    add(['pad', 'tpad', 'split', 'alphaextract', 'negate', 'blend', 'alphamerge']);
+  }
+  if (postStages.includes('adjust')) {
+    add(filterNamesIn(buildAdjustmentFilters(plan.adjustmentTrack, 0)));
+  }
+  add(filterNamesIn(videoClips.flatMap((clip) => buildVideoEffectFilters(clip.effects))));
+  const audioEffectClips = [...plan.mainTrack, ...(plan.audioTrack || [])];
+  add(filterNamesIn(audioEffectClips.flatMap((clip) => buildClipAudioFilters(clip))));
+  for (const clip of [...plan.mainTrack, ...(plan.overlayTrack || []), ...(plan.audioTrack || [])]) {
+    if (clip.generator) add(generatorFilters(clip.generator));
+  }
+
+  const encoders = [encoding.videoCodec, encoding.audioCodec];
+  if (postStages.includes('stems') || postStages.includes('languages')) {
+    encoders.push('pcm_s24le');
+  }
+  return { filters: [...filters], encoders };
+}
+
 /**
  * Execute export job with progress tracking
  */
 async function executeExportJob(plan, settings, cache, mainWindow, trackProcessFn) {
-  // Calculate total steps: clips + gaps + concat + finalize
+  // Draft/standard/master profiles only fill in what the settings leave unset
+  settings = applyQualityProfile(settings);
+
+  // Post-concat stages run in order on intermediate files; the last one writes the output
+  const adjustmentClips = plan.adjustmentTrack || [];
+  const captionClips = plan.captionTrack || [];
+  const needsCrossfade = findCrossfadeCuts(plan).length > 0;
+  const needsComposite = (plan.overlayTrack || []).length > 0;
+  const needsAdjust = adjustmentClips.length > 0;
+  const needsReframe = !!ASPECT_RATIOS[settings.aspectRatio] && settings.aspectRatio !== '16:9';
+  const needsBurnIn = !!settings.burnIn;
+  const needsCaptions = captionClips.length > 0;
+  const needsMixdown = mixAudioClips(plan.audioTrack || [], plan.audioTracks || {}).length > 0;
+  // Stems go to WAV files next to the output ('files') or into extra audio streams ('streams')
+  const stemMode = STEM_MODES.includes(settings.stems) ? settings.stems : null;
+  const needsStems = !!stemMode;
+  // Commentary/dub tracks become their own language-tagged audio streams
+  const audioLanguage = normalizeLanguage(settings.audioLanguage);
+  const needsLanguages = alternateAudioTracks(plan.audioTrack || [], plan.audioTracks || {}).length > 0 || !!audioLanguage;
+  const needsChapters = (plan.markers || []).length > 0;
+  // Web-optimized (moov atom first) unless explicitly turned off
+  const webOptimized = settings.webOptimized !== false;
+  const checksumAlgorithms = normalizeChecksums(settings.checksums);
+  const postStages = [
+    needsCrossfade && 'crossfade',
+    needsComposite && 'composite',
+    needsAdjust && 'adjust',
+    needsReframe && 'reframe',
+    needsBurnIn && 'burnin',
+    needsCaptions && 'captions',
+    needsMixdown && 'mixdown',
+    needsStems && 'stems',
+    needsLanguages && 'languages',
+    needsChapters && 'chapters',
+    'container',
+  ].filter(Boolean);
+
+  // Encoder, preset and quality settings shared by every encode below
+  const encoding = resolveEncoding(settings);
+  assertEncodingValid(encoding);
+  // ProRes/DNxHR and PCM audio only fit in MOV, for the intermediates as well as the output
+  const segmentExt = requiresMov(encoding) ? 'mov' : 'mp4';
+  if (segmentExt === 'mov') {
+    settings = { ...settings, format: 'mov' };
+  }
+
+  // Fail before rendering anything if the ffmpeg build can't run every stage
+  await assertCapabilities({ ...collectRequirements(plan, postStages, encoding), feature: 'Export' });
+
+  // Calculate total steps: clips + gaps + concat + finalize + post-concat stages
   const gapCount = plan.mainTrack.length > 0 ? plan.mainTrack.length - 1 : 0;
-  const total = plan.mainTrack.length + gapCount + 2;
+  const timer = createPhaseTimer();
+  const startedAt = Date.now();
+  const probe = (filePath) => timer.measure('probe', () => probeMedia(filePath));
+  const total = plan.mainTrack.length + gapCount + 2 + postStages.length;
   let current = 0;
 
   const segmentPaths = [];
   let segmentIndex = 0;
 
-  // Extract resolution settings (width: -1, height: -1 means use source resolution)
-  const targetWidth = settings.width;
-  const targetHeight = settings.height;
-  const useSourceResolution = targetWidth === -1 || targetHeight === -1;
+  // Probe each source once; stream presence and rotation come from the same probe.
+  // The promise is kept so segments rendering in parallel share a probe still running
+  const sourceMeta = new Map();
+  const probeSource = (srcPath) => {
+    if (!sourceMeta.has(srcPath)) {
+      sourceMeta.set(srcPath, probe(srcPath).catch(() => null));
+    }
+    return sourceMeta.get(srcPath);
+  };
+
+  // Every segment is rendered at the output size and rate so concat can stream-copy
+  // (width/height of -1 or unset, and unset fps, inherit from the first video clip)
+  const { width: targetWidth, height: targetHeight, frameRate } = await resolveOutputFormat(settings, plan, probeSource);
+
+  // Generator clips (color, countdown, tone, ...) get synthesized source files before anything reads them
+  plan = await materializeGenerators(plan, { width: targetWidth, height: targetHeight, frameRate }, encoding, cache, trackProcessFn);
+
+  // Segments are cached under content hashes so a re-export only renders what changed;
+  // the manifest records progress so an interrupted export of the same plan resumes
+  const renderKey = { width: targetWidth, height: targetHeight, frameRate, encoding: encodingKey(encoding) };
+  const planHash = hashPlan(plan);
+  const manifestPath = cache.exportManifestPath(hashValue({ plan: planHash, settings: renderKey }));
+  const previousManifest = await fs.readJson(manifestPath).catch(() => null);
+  if (previousManifest && !previousManifest.completed) {
+    logger.info('Resuming interrupted export', { plan_id: plan.id, segments_done: previousManifest.segments.length });
+  }
+  const manifest = { plan_hash: planHash, settings: renderKey, segments: [], completed: false };
+  let reusedSegments = 0;
+  let manifestWrite = Promise.resolve();
+  // Renders in progress by key, so identical segments rendering in parallel share one encode
+  const pendingSegments = new Map();
+
+  const renderSegment = async (key, render) => {
+    const segPath = cache.segmentCachePath(key, segmentExt);
+    if (pendingSegments.has(key)) {
+      reusedSegments++;
+    } else {
+      pendingSegments.set(key, (async () => {
+        if (await fs.pathExists(segPath)) {
+          reusedSegments++;
+          return;
+        }
+        // Render under a temporary name so an interrupted encode is never reused
+        const partialPath = cache.segmentCachePath(`${key}.partial`, segmentExt);
+        await render(partialPath);
+        await fs.move(partialPath, segPath, { overwrite: true });
+      })());
+    }
+    await pendingSegments.get(key);
+    manifest.segments.push(key);
+    // Parallel segments finish out of order; chain the writes so they never interleave
+    manifestWrite = manifestWrite.then(() => fs.writeJson(manifestPath, manifest));
+    await manifestWrite;
+    return segPath;
+  };
 
   // Step 1: Process each clip and gaps between them
+  // Segments are independent encodes, queued in timeline order and rendered up to
+  // settings.parallelSegments at a time (one by default, so an export doesn't take every core)
+  timer.enter('segments');
+  const segmentTasks = [];
   for (let idx = 0; idx < plan.mainTrack.length; idx++) {
     const clip = plan.mainTrack[idx];
     
@@ -39,90 +300,182 @@ async function executeExportJob(plan, settings, cache, mainWindow, trackProcessF
       
       if (gapDurationMs > 0) {
         // Try to create black segment for gap
-        try {
-          // Send progress event for gap
-          if (mainWindow) {
-            mainWindow.webContents.send('export-progress', {
-              phase: 'segment',
-              current,
-              total,
-              message: `Creating gap ${idx}/${gapCount}`,
-            });
-          }
+        segmentTasks.push(async () => {
+          try {
+            // Send progress event for gap
+            if (mainWindow) {
+              emitEvent(mainWindow, EventType.EXPORT_PROGRESS, {
+                phase: 'segment',
+                current,
+                total,
+                message: `Creating gap ${idx}/${gapCount}`,
+              });
+            }
 
-          const gapPath = cache.segmentPath(segmentIndex++);
-          await createBlackSegment(gapPath, gapDurationMs / 1000, targetWidth, targetHeight, trackProcessFn);
-          segmentPaths.push(gapPath);
-          current++;
-        } catch (err) {
-          console.warn(`Failed to create gap segment: ${err.message}`);
-          console.warn(`Skipping ${gapDurationMs}ms gap - clips will be concatenated directly`);
-          // Continue without the gap - clips will just play back-to-back
-        }
+            const gapKey = hashValue({ gapMs: gapDurationMs, ...renderKey });
+            const gapPath = await renderSegment(gapKey, (outputPath) =>
+              createBlackSegment(outputPath, gapDurationMs / 1000, targetWidth, targetHeight, frameRate, encoding, trackProcessFn)
+            );
+            current++;
+            return gapPath;
+          } catch (err) {
+            console.warn(`Failed to create gap segment: ${err.message}`);
+            console.warn(`Skipping ${gapDurationMs}ms gap - clips will be concatenated directly`);
+            // Continue without the gap - clips will just play back-to-back
+            return null;
+          }
+        });
       }
     }
-    
-    // Send progress event for clip
-    if (mainWindow) {
-      mainWindow.webContents.send('export-progress', {
-        phase: 'segment',
-        current,
-        total,
-        message: `Processing clip ${idx + 1}/${plan.mainTrack.length}`,
+
+    const loopIndex = segmentIndex++;
+    segmentTasks.push(async () => {
+      // Send progress event for clip
+      if (mainWindow) {
+        emitEvent(mainWindow, EventType.EXPORT_PROGRESS, {
+          phase: 'segment',
+          current,
+          total,
+          message: `Processing clip ${idx + 1}/${plan.mainTrack.length}`,
+        });
+      }
+
+      const startSec = clip.inMs / 1000;
+      // Segments are cut to output time, which a retimed clip's span is (not its source range)
+      const durationSec = (clip.speed ? clip.endMs - clip.startMs : clip.outMs - clip.inMs) / 1000;
+
+      // Check if this is an image file
+      const isImage = isImageFile(clip.srcPath);
+
+      // Resolve rotation up front so the segment key and the encode agree (stills aren't rotated)
+      const meta = isImage ? null : await probeSource(clip.srcPath);
+      const { sourceDeg, rotationDeg } = isImage ? { sourceDeg: 0, rotationDeg: 0 } : clipRotation(meta, clip.rotationOverride);
+
+      // Streams a source lacks are synthesized so every segment carries video and audio;
+      // unprobeable sources are assumed to have both. Detached audio (J/L-cuts) plays from the mixdown instead.
+      const hasVideo = isImage || !meta || !!meta.codec_video;
+      const hasAudio = !isImage && !clip.audioDetached && (!meta || !!meta.has_audio);
+      const timelineSec = (clip.endMs - clip.startMs) / 1000;
+
+      const sourceStats = await fs.stat(clip.srcPath).catch(() => null);
+      const clipKey = hashValue({
+        clip: hashClip(clip),
+        source: sourceStats ? { size: sourceStats.size, mtimeMs: sourceStats.mtimeMs } : null,
+        rotation: rotationDeg,
+        ...renderKey,
       });
-    }
 
-    const segPath = cache.segmentPath(segmentIndex++);
-    const startSec = clip.inMs / 1000;
-    const durationSec = (clip.outMs - clip.inMs) / 1000;
-
-    // Check if this is an image file
-    const isImage = isImageFile(clip.srcPath);
-
-    if (isImage) {
-      // Convert image to video segment
-      // Images always need transcoding, no codec copy option
-      await convertImageToVideoSegment(
-        clip.srcPath, 
-        segPath, 
-        durationSec, 
-        useSourceResolution ? null : targetWidth,
-        useSourceResolution ? null : targetHeight,
-        clip.assetWidth,  // Pass asset dimensions for aspect ratio preservation
-        clip.assetHeight,
-        settings.bitrate, 
-        trackProcessFn
-      );
-      segmentPaths.push(segPath);
-    } else {
-      // Handle video clips
-      // If we need to scale, we must transcode (can't use codec copy)
-      const needsScaling = !useSourceResolution;
-      
-      if (needsScaling) {
-        // Must transcode to apply scaling
-        await trimSegment(clip.srcPath, segPath, startSec, durationSec, false, targetWidth, targetHeight, settings.bitrate, trackProcessFn);
-        segmentPaths.push(segPath);
-      } else {
-        // Try codec copy first for source resolution
-        try {
-          await trimSegment(clip.srcPath, segPath, startSec, durationSec, true, null, null, settings.bitrate, trackProcessFn);
-          segmentPaths.push(segPath);
-        } catch (err) {
-          // Fallback to transcode
-          console.log(`Codec copy failed for segment ${idx}, transcoding...`);
-          await trimSegment(clip.srcPath, segPath, startSec, durationSec, false, null, null, settings.bitrate, trackProcessFn);
-          segmentPaths.push(segPath);
+      const segPath = await renderSegment(clipKey, async (segmentPath) => {
+        // Video freeze frames already render over silence; other silent segments get it muxed in afterwards
+        const addSilence = hasVideo && !hasAudio && !(clip.freezeFrame && !isImage);
+        const outputPath = addSilence ? segmentPath.replace(/(\.\w+)$/, '.video$1') : segmentPath;
+        const videoFilters = buildVideoEffectFilters(clip.effects);
+        const sourceFilters = [...cropFilters(clip.crop), ...speedVideoFilters(clip, frameRate)];
+
+        if (!hasVideo && (clip.freezeFrame || clip.audioDetached)) {
+          // Freezing an audio-only clip (or moving its audio elsewhere) holds black over silence
+          await createBlackSegment(segmentPath, durationSec, targetWidth, targetHeight, frameRate, encoding, trackProcessFn);
+        } else if (!hasVideo) {
+          // Audio-only clips (voiceover on the main track) play over black
+          const audioFilters = buildClipAudioFilters(clip);
+          if (clip.loop) {
+            const piecePath = cache.loopPiecePath(loopIndex, segmentExt);
+            await renderAudioOnlySegment(clip.srcPath, piecePath, startSec, durationSec, targetWidth, targetHeight, frameRate, encoding, audioFilters, trackProcessFn);
+            await loopSegment(piecePath, segmentPath, timelineSec, trackProcessFn);
+            await fs.remove(piecePath);
+          } else {
+            await renderAudioOnlySegment(clip.srcPath, segmentPath, startSec, durationSec, targetWidth, targetHeight, frameRate, encoding, audioFilters, trackProcessFn);
+          }
+        } else if (clip.freezeFrame && !isImage) {
+          // Hold a single source frame for the freeze duration
+          await renderFreezeSegment(
+            clip.srcPath,
+            outputPath,
+            startSec,
+            durationSec,
+            targetWidth,
+            targetHeight,
+            frameRate,
+            encoding,
+            videoFilters,
+            rotationDeg,
+            sourceFilters,
+            trackProcessFn
+          );
+        } else if (clip.loop && !isImage) {
+          // Render the trimmed piece once, then repeat it to fill the timeline duration
+          const piecePath = cache.loopPiecePath(loopIndex, segmentExt);
+          await trimSegment(clip.srcPath, piecePath, startSec, durationSec, encoding, {
+            targetWidth, targetHeight, frameRate, rotationDeg,
+            sourceFilters, videoFilters, audioFilters: buildClipAudioFilters(clip), trackProcessFn,
+          });
+          await loopSegment(piecePath, outputPath, (clip.endMs - clip.startMs) / 1000, trackProcessFn);
+          await fs.remove(piecePath);
+        } else if (isImage) {
+          // Convert image to video segment
+          // Images always need transcoding, no codec copy option
+          // Looping images (animated GIFs) fill the whole timeline duration
+          await convertImageToVideoSegment(
+            clip.srcPath, 
+            outputPath, 
+            clip.loop ? (clip.endMs - clip.startMs) / 1000 : durationSec, 
+            targetWidth,
+            targetHeight,
+            frameRate,
+            encoding,
+            videoFilters,
+            trackProcessFn
+          );
+        } else {
+          // Handle video clips
+          // If we need to scale, change rate, apply effects, resample or filter audio, we must transcode (can't use codec copy)
+          // Rotated sources are transcoded too: copy keeps the rotation tag and concat takes the first segment's
+          const needsConform = !meta || meta.width !== targetWidth || meta.height !== targetHeight || !sameFrameRate(meta.frame_rate, frameRate);
+          const audioFilters = buildClipAudioFilters(clip);
+          const needsRotation = sourceDeg !== 0 || rotationDeg !== 0;
+        
+          if (needsConform || videoFilters.length > 0 || sourceFilters.length > 0 || audioFilters.length > 0 || needsRotation || !audioConforms(meta)) {
+            // Must transcode to apply scaling, rate conversion, rotation, crop, effects and/or the audio conform
+            await trimSegment(clip.srcPath, outputPath, startSec, durationSec, encoding, {
+              targetWidth, targetHeight, frameRate, rotationDeg,
+              sourceFilters, videoFilters, audioFilters, trackProcessFn,
+            });
+          } else {
+            // Source already matches the output frame: splice encoded handles around a copied middle
+            // when the source's codec matches the encoder, else try codec copy
+            try {
+              const keyframeMeta = canSmartRender(meta, encoding) ? await probeMedia(clip.srcPath, { keyframes: true, cache }) : null;
+              const smart = keyframeMeta && await smartRenderClip(clip.srcPath, clip.inMs, clip.outMs, keyframeMeta, outputPath, encoding, cache, trackProcessFn);
+              if (!smart) {
+                await trimSegment(clip.srcPath, outputPath, startSec, durationSec, encoding, { copyCodec: true, trackProcessFn });
+              }
+            } catch (err) {
+              // Fallback to transcode
+              console.log(`Codec copy failed for segment ${idx}, transcoding...`);
+              await trimSegment(clip.srcPath, outputPath, startSec, durationSec, encoding, {
+                targetWidth, targetHeight, frameRate, rotationDeg, trackProcessFn,
+              });
+            }
+          }
         }
-      }
-    }
 
-    current++;
+        if (addSilence) {
+          await muxSilentAudio(outputPath, segmentPath, timelineSec, encoding, trackProcessFn);
+          await fs.remove(outputPath);
+        }
+      });
+
+      current++;
+      return segPath;
+    });
   }
+  const renderedSegments = await runLimited(segmentTasks, resolveSegmentConcurrency(settings));
+  segmentPaths.push(...renderedSegments.filter(Boolean));
 
   // Step 2: Create concat list file
+  timer.enter('concat');
   if (mainWindow) {
-    mainWindow.webContents.send('export-progress', {
+    emitEvent(mainWindow, EventType.EXPORT_PROGRESS, {
       phase: 'concat',
       current,
       total,
@@ -132,14 +485,14 @@ async function executeExportJob(plan, settings, cache, mainWindow, trackProcessF
 
   const concatPath = cache.concatListPath(plan.id);
   const concatContent = segmentPaths
-    .map((segPath) => `file '${segPath}'`)
+    .map((segPath) => concatListEntry(segPath))
     .join('\n');
   await fs.writeFile(concatPath, concatContent, 'utf8');
   current++;
 
   // Step 3: Concatenate segments
   if (mainWindow) {
-    mainWindow.webContents.send('export-progress', {
+    emitEvent(mainWindow, EventType.EXPORT_PROGRESS, {
       phase: 'finalize',
       current,
       total,
@@ -148,32 +501,458 @@ async function executeExportJob(plan, settings, cache, mainWindow, trackProcessF
   }
 
   const ext = settings.format === 'mov' ? 'mov' : 'mp4';
-  const outPath = settings.filename ? cache.renderOutputPathWithFilename(settings.filename, ext) : cache.renderOutputPath(plan.id, ext);
+  // An explicit filename is used as-is; otherwise the template names the file after the project and render
+  const outputSize = needsReframe ? computeReframeSize(settings.aspectRatio, targetWidth, targetHeight) : { width: targetWidth, height: targetHeight };
+  const outPath = settings.filename
+    ? cache.renderOutputPathWithFilename(settings.filename, ext)
+    : cache.renderOutputPathFromTemplate(settings.filenameTemplate || DEFAULT_FILENAME_TEMPLATE, outputNameFields(plan, settings, outputSize), ext);
+
+  const stageOutputPath = (stage) =>
+    stage === postStages[postStages.length - 1] ? outPath : cache.stagePath(plan.id, stage, ext);
+  const programPath = postStages.length > 0 ? cache.stagePath(plan.id, 'program', ext) : outPath;
 
-  // If we're scaling, we already transcoded all segments to the target resolution
-  // so we can use codec copy for concat. If using source resolution, try codec copy first.
+  // Segments were rendered at the output size and rate, so concat normally stream-copies;
+  // the re-encode fallback fits and re-times anything that still slipped through
   try {
-    await concatenateSegments(concatPath, outPath, true, null, null, settings.bitrate, trackProcessFn);
+    await concatenateSegments(concatPath, programPath, true, null, null, null, encoding, trackProcessFn);
   } catch (err) {
     // Fallback to re-encode
-    console.log('Concat with copy failed, re-encoding...');
-    await concatenateSegments(concatPath, outPath, false, targetWidth, targetHeight, settings.bitrate, trackProcessFn);
+    logger.warn('Concat with copy failed, re-encoding', { plan_id: plan.id, error: String(err) });
+    await concatenateSegments(concatPath, programPath, false, targetWidth, targetHeight, frameRate, encoding, trackProcessFn);
   }
 
   current++;
 
-  // Get output file stats
-  const stats = await fs.stat(outPath);
-  
   // Calculate total duration: from first clip start to last clip end
   const firstClipStart = plan.mainTrack.length > 0 ? plan.mainTrack[0].startMs : 0;
   const lastClipEnd = plan.mainTrack.length > 0 ? plan.mainTrack[plan.mainTrack.length - 1].endMs : 0;
   const durationMs = lastClipEnd - firstClipStart;
 
+  let stageInput = programPath;
+  // Program before the mixdown, whose audio is the main track's own (the dialogue stem's base)
+  let stemSource = null;
+  const stemPaths = [];
+  let stemStreamCount = 0;
+  timer.enter('encode');
+
+  // Step 4: Crossfade the program audio at cuts so hard edits don't click
+  if (needsCrossfade) {
+    if (mainWindow) {
+      emitEvent(mainWindow, EventType.EXPORT_PROGRESS, {
+        phase: 'crossfade',
+        current,
+        total,
+        message: 'Crossfading audio at cuts',
+      });
+    }
+
+    const cuts = await resolveCrossfadeCuts(plan, firstClipStart, probeSource);
+    const crossfadePath = stageOutputPath('crossfade');
+    if (cuts.length > 0) {
+      await crossfadeProgramAudio(stageInput, cuts, crossfadePath, encoding, trackProcessFn);
+      await fs.remove(stageInput);
+    } else {
+      // No cut has audio handles on both sides
+      await fs.move(stageInput, crossfadePath, { overwrite: true });
+    }
+    stageInput = crossfadePath;
+    current++;
+  }
+
+  // Step 5: Composite overlay clips (PiP, lower-thirds, light leaks) over the program video
+  if (needsComposite) {
+    if (mainWindow) {
+      emitEvent(mainWindow, EventType.EXPORT_PROGRESS, {
+        phase: 'composite',
+        current,
+        total,
+        message: 'Compositing overlays',
+      });
+    }
+
+    // Looping and retimed overlays are pre-rendered to a file covering their whole timeline duration
+    const loopFiles = [];
+    const compositeClips = [];
+    for (const clip of plan.overlayTrack) {
+      if ((!clip.loop && !clip.speed) || isImageFile(clip.srcPath)) {
+        compositeClips.push(clip);
+        continue;
+      }
+      if (clip.speed) {
+        const retimedPath = cache.segmentPath(segmentIndex++, segmentExt);
+        await trimSegment(clip.srcPath, retimedPath, clip.inMs / 1000, (clip.endMs - clip.startMs) / 1000, encoding, {
+          frameRate, sourceFilters: speedVideoFilters(clip, frameRate), trackProcessFn,
+        });
+        loopFiles.push(retimedPath);
+        compositeClips.push({ ...clip, srcPath: retimedPath, inMs: 0, outMs: clip.endMs - clip.startMs });
+        continue;
+      }
+      const piecePath = cache.loopPiecePath(segmentIndex, segmentExt);
+      const loopPath = cache.segmentPath(segmentIndex++, segmentExt);
+      await trimSegment(clip.srcPath, piecePath, clip.inMs / 1000, (clip.outMs - clip.inMs) / 1000, encoding, { trackProcessFn });
+      await loopSegment(piecePath, loopPath, (clip.endMs - clip.startMs) / 1000, trackProcessFn);
+      await fs.remove(piecePath);
+      loopFiles.push(loopPath);
+      compositeClips.push({ ...clip, srcPath: loopPath, inMs: 0, outMs: clip.endMs - clip.startMs });
+    }
+
+    const programMeta = await probe(stageInput);
+    const compositePath = stageOutputPath('composite');
+    await compositeOverlays(
+      stageInput,
+      compositeClips,
+      firstClipStart,
+      programMeta.width || 1920,
+      programMeta.height || 1080,
+      compositePath,
+      encoding,
+      trackProcessFn
+    );
+    await fs.remove(stageInput);
+    for (const loopPath of loopFiles) {
+      await fs.remove(loopPath);
+    }
+    stageInput = compositePath;
+    current++;
+  }
+
+  // Step 6: Apply adjustment-layer effects to the composited program
+  if (needsAdjust) {
+    if (mainWindow) {
+      emitEvent(mainWindow, EventType.EXPORT_PROGRESS, {
+        phase: 'adjust',
+        current,
+        total,
+        message: 'Applying adjustment layers',
+      });
+    }
+
+    const adjustPath = stageOutputPath('adjust');
+    await applyAdjustments(stageInput, adjustmentClips, firstClipStart, adjustPath, encoding, trackProcessFn);
+    await fs.remove(stageInput);
+    stageInput = adjustPath;
+    current++;
+  }
+
+  // Step 7: Reframe to another aspect ratio, following per-clip pan offsets
+  if (needsReframe) {
+    if (mainWindow) {
+      emitEvent(mainWindow, EventType.EXPORT_PROGRESS, {
+        phase: 'reframe',
+        current,
+        total,
+        message: `Reframing to ${settings.aspectRatio}`,
+      });
+    }
+
+    const reframePath = stageOutputPath('reframe');
+    const size = computeReframeSize(settings.aspectRatio, targetWidth, targetHeight);
+    await reframeProgram(
+      stageInput,
+      reframePath,
+      plan.mainTrack,
+      settings.aspectRatio,
+      firstClipStart,
+      size.width,
+      size.height,
+      encoding,
+      trackProcessFn
+    );
+    await fs.remove(stageInput);
+    stageInput = reframePath;
+    current++;
+  }
+
+  // Step 8: Burn in source timecode, clip name and frame number for review renders
+  if (needsBurnIn) {
+    if (mainWindow) {
+      emitEvent(mainWindow, EventType.EXPORT_PROGRESS, {
+        phase: 'burnin',
+        current,
+        total,
+        message: 'Burning in review overlay',
+      });
+    }
+
+    const sourceTimecodes = {};
+    for (const clip of plan.mainTrack) {
+      if (clip.srcPath in sourceTimecodes) continue;
+      try {
+        sourceTimecodes[clip.srcPath] = (await probe(clip.srcPath)).timecode;
+      } catch (e) {
+        sourceTimecodes[clip.srcPath] = null;
+      }
+    }
+
+    const programMeta = await probe(stageInput);
+    const burnInPath = stageOutputPath('burnin');
+    await burnInReviewOverlay(
+      stageInput,
+      burnInPath,
+      plan.mainTrack,
+      sourceTimecodes,
+      firstClipStart,
+      plan.frameRate || programMeta.frame_rate || settings.fps || 30,
+      programMeta.width || targetWidth,
+      programMeta.height || targetHeight,
+      cache.stagePath(plan.id, 'burnin', 'ass'),
+      encoding,
+      trackProcessFn
+    );
+    await fs.remove(stageInput);
+    stageInput = burnInPath;
+    current++;
+  }
+
+  // Step 9: Burn in styled captions (libass), after the review overlay so captions stay on top
+  if (needsCaptions) {
+    if (mainWindow) {
+      emitEvent(mainWindow, EventType.EXPORT_PROGRESS, {
+        phase: 'captions',
+        current,
+        total,
+        message: 'Rendering captions',
+      });
+    }
+
+    const programMeta = await probe(stageInput);
+    const captionsPath = stageOutputPath('captions');
+    await burnCaptions(
+      stageInput,
+      captionsPath,
+      captionClips,
+      firstClipStart,
+      programMeta.width || targetWidth,
+      programMeta.height || targetHeight,
+      cache.stagePath(plan.id, 'captions', 'ass'),
+      encoding,
+      trackProcessFn
+    );
+    await fs.remove(stageInput);
+    stageInput = captionsPath;
+    current++;
+  }
+
+  // Step 10: Mix audio-track clips (with their effects) over the program audio
+  if (needsMixdown) {
+    if (mainWindow) {
+      emitEvent(mainWindow, EventType.EXPORT_PROGRESS, {
+        phase: 'mixdown',
+        current,
+        total,
+        message: 'Mixing audio tracks',
+      });
+    }
+
+    const programMeta = await probe(stageInput);
+    const mixdownPath = stageOutputPath('mixdown');
+    await mixdownAudio(
+      stageInput,
+      mixAudioClips(plan.audioTrack, plan.audioTracks || {}),
+      firstClipStart,
+      durationMs / 1000,
+      !!programMeta.has_audio,
+      plan.audioTracks || {},
+      mixdownPath,
+      encoding,
+      trackProcessFn
+    );
+    if (needsStems) {
+      stemSource = stageInput;
+    } else {
+      await fs.remove(stageInput);
+    }
+    stageInput = mixdownPath;
+    current++;
+  }
+
+  // Step 11: Render unducked dialogue/music/sfx stems for an external mix
+  if (needsStems) {
+    if (mainWindow) {
+      emitEvent(mainWindow, EventType.EXPORT_PROGRESS, {
+        phase: 'stems',
+        current,
+        total,
+        message: 'Rendering audio stems',
+      });
+    }
+
+    const source = stemSource || stageInput;
+    const sourceMeta = await probe(source);
+    const stems = groupStems(plan.audioTrack || [], plan.audioTracks || {}, !!sourceMeta.has_audio);
+    const rendered = [];
+    for (const stem of stems) {
+      const stemPath = stemMode === 'files'
+        ? path.join(path.dirname(outPath), `${path.basename(outPath, path.extname(outPath))}_${stem.name}.wav`)
+        : cache.stagePath(plan.id, `stem_${stem.name}`, 'wav');
+      await renderStem(source, stem, firstClipStart, durationMs / 1000, plan.audioTracks || {}, stemPath, trackProcessFn);
+      rendered.push({ name: stem.name, path: stemPath });
+    }
+    if (stemSource) {
+      await fs.remove(stemSource);
+    }
+
+    const stemsPath = stageOutputPath('stems');
+    if (stemMode === 'streams' && rendered.length > 0) {
+      await muxStemStreams(stageInput, rendered, stemsPath, encoding, trackProcessFn);
+      await fs.remove(stageInput);
+      for (const stem of rendered) {
+        await fs.remove(stem.path);
+      }
+    } else {
+      await fs.move(stageInput, stemsPath, { overwrite: true });
+      if (stemMode === 'files') {
+        stemPaths.push(...rendered.map((stem) => pathToFileUrl(stem.path)));
+      }
+    }
+    stemStreamCount = stemMode === 'streams' ? rendered.length : 0;
+    stageInput = stemsPath;
+    current++;
+  }
+
+  // Step 12: Add commentary/dub tracks as extra audio streams and tag stream languages
+  if (needsLanguages) {
+    if (mainWindow) {
+      emitEvent(mainWindow, EventType.EXPORT_PROGRESS, {
+        phase: 'languages',
+        current,
+        total,
+        message: 'Adding alternate audio tracks',
+      });
+    }
+
+    // The mix is the file's first audio stream; commentary is rendered over it, dubs stand alone
+    const programMeta = await probe(stageInput);
+    const existingAudio = programMeta.has_audio ? 1 + stemStreamCount : 0;
+    const rendered = [];
+    for (const track of alternateAudioTracks(plan.audioTrack || [], plan.audioTracks || {})) {
+      const trackPath = cache.stagePath(plan.id, `language_${rendered.length}`, 'wav');
+      const stem = { clips: track.clips, includeBase: track.role === 'commentary' && !!programMeta.has_audio };
+      await renderStem(stageInput, stem, firstClipStart, durationMs / 1000, plan.audioTracks || {}, trackPath, trackProcessFn);
+      rendered.push({ title: track.title, language: track.language, path: trackPath });
+    }
+
+    const languagesPath = stageOutputPath('languages');
+    await muxLanguageStreams(stageInput, rendered, languagesPath, { existingAudio, mixLanguage: audioLanguage, encoding }, trackProcessFn);
+    await fs.remove(stageInput);
+    for (const track of rendered) {
+      await fs.remove(track.path);
+    }
+    stageInput = languagesPath;
+    current++;
+  }
+
+  // Step 13: Embed timeline markers as chapters
+  if (needsChapters) {
+    if (mainWindow) {
+      emitEvent(mainWindow, EventType.EXPORT_PROGRESS, {
+        phase: 'chapters',
+        current,
+        total,
+        message: 'Writing chapters',
+      });
+    }
+
+    const chapters = buildChapters(plan.markers, firstClipStart, durationMs);
+    const chaptersPath = stageOutputPath('chapters');
+    if (chapters.length > 0) {
+      await embedChapters(stageInput, chapters, cache.stagePath(plan.id, 'chapters', 'txt'), chaptersPath, trackProcessFn);
+      await fs.remove(stageInput);
+    } else {
+      // Every marker falls outside the exported range
+      await fs.move(stageInput, chaptersPath, { overwrite: true });
+    }
+    stageInput = chaptersPath;
+    current++;
+  }
+
+  // Step 14: Write metadata tags and move the moov atom to the front so playback can start while downloading
+  if (mainWindow) {
+    emitEvent(mainWindow, EventType.EXPORT_PROGRESS, {
+      phase: 'container',
+      current,
+      total,
+      message: webOptimized && !settings.fragmented ? 'Optimizing for web playback' : 'Writing metadata',
+    });
+  }
+
+  const containerPath = stageOutputPath('container');
+  const streamedChecksums = await finalizeContainer(stageInput, containerPath, {
+    faststart: webOptimized,
+    fragmented: settings.fragmented === true,
+    metadata: settings.metadata,
+    checksums: checksumAlgorithms,
+  }, trackProcessFn);
+  await fs.remove(stageInput);
+  stageInput = containerPath;
+  current++;
+
+  timer.stop();
+
+  manifest.completed = true;
+  await fs.writeJson(manifestPath, manifest);
+  if (reusedSegments > 0) {
+    logger.info('Reused cached segments', { plan_id: plan.id, reused: reusedSegments, total: manifest.segments.length });
+  }
+
+  // Get output file stats
+  const stats = await fs.stat(outPath);
+
+  // Delivery checksums, with md5sum-style sidecars next to the file; a fragmented file was hashed
+  // as it was written, anything else is read back once
+  let checksums = streamedChecksums;
+  if (checksumAlgorithms.length > 0) {
+    if (!checksums) {
+      if (mainWindow) {
+        emitEvent(mainWindow, EventType.EXPORT_PROGRESS, { phase: 'checksum', current, total, message: 'Computing checksums' });
+      }
+      checksums = await checksumFile(outPath, checksumAlgorithms);
+    }
+    await writeChecksumSidecars(outPath, checksums);
+  }
+
   return {
-    path: `file://${outPath}`,
+    path: pathToFileUrl(outPath),
     duration_ms: durationMs,
     size_bytes: stats.size,
+    ...(stemMode === 'files' ? { stems: stemPaths } : {}),
+    ...(checksums ? { checksums } : {}),
+    metrics: await buildJobMetrics(plan, frameRate, timer.phases, Date.now() - startedAt, durationMs, stats.size),
+  };
+}
+
+/**
+ * Summarize where an export spent its time
+ * Encode fps is output frames over total wall time, so it can be compared across exports.
+ */
+async function buildJobMetrics(plan, frameRate, phases, elapsedMs, durationMs, outputBytes) {
+  const sources = new Set(
+    [...plan.mainTrack, ...(plan.overlayTrack || []), ...(plan.audioTrack || [])].map((clip) => clip.srcPath)
+  );
+  let inputBytes = 0;
+  for (const srcPath of sources) {
+    const stats = await fs.stat(srcPath).catch(() => null);
+    if (stats) inputBytes += stats.size;
+  }
+
+  const rate = toRational(frameRate) || { num: 30, den: 1 };
+  const outputFrames = Math.round((durationMs / 1000) * (rate.num / rate.den));
+
+  return {
+    phases_ms: {
+      probe: phases.probe || 0,
+      segments: phases.segments || 0,
+      concat: phases.concat || 0,
+      encode: phases.encode || 0,
+    },
+    total_ms: elapsedMs,
+    input_files: sources.size,
+    input_bytes: inputBytes,
+    output_bytes: outputBytes,
+    output_duration_ms: durationMs,
+    output_frames: outputFrames,
+    encode_fps: elapsedMs > 0 ? Math.round((outputFrames / (elapsedMs / 1000)) * 10) / 10 : null,
+    realtime_factor: elapsedMs > 0 ? Math.round((durationMs / elapsedMs) * 100) / 100 : null,
   };
 }
 
@@ -181,11 +960,10 @@ async function executeExportJob(plan, settings, cache, mainWindow, trackProcessF
  * Create a black video segment (for gaps)
  * If lavfi is not available, this will fail and we'll skip gaps
  */
-async function createBlackSegment(outputPath, durationSec, width, height, trackProcessFn) {
+async function createBlackSegment(outputPath, durationSec, width, height, frameRate, encoding, trackProcessFn) {
   const path = require('path');
   const os = require('os');
   
-  // Use provided resolution, or default to 1920x1080
   const resolutionStr = `${width}x${height}`;
   
   // Try multiple approaches in order of compatibility
@@ -197,18 +975,14 @@ async function createBlackSegment(outputPath, durationSec, width, height, trackP
         
         // Build raw ffmpeg command for maximum compatibility
         command
-          .input(`color=black:s=${resolutionStr}:r=30`)
+          .input(`color=black:s=${resolutionStr}:r=${frameRate}`)
           .inputFormat('lavfi')
-          .input('anullsrc=r=48000:cl=stereo')
+          .input(`anullsrc=r=${AUDIO_CONFORM.sampleRate}:cl=${AUDIO_CONFORM.channelLayout}`)
           .inputFormat('lavfi')
           .duration(durationSec)
-          .videoCodec('libx264')
-          .audioCodec('aac')
-          .outputOptions([
-            '-preset', 'ultrafast',
-            '-crf', '23',
-            '-pix_fmt', 'yuv420p',
-          ]);
+          .videoCodec(encoding.videoCodec)
+          .audioCodec(encoding.audioCodec)
+          .outputOptions([...videoQualityOptions(encoding), ...audioQualityOptions(encoding)]);
 
         const ffmpegProcess = command
           .output(outputPath)
@@ -242,6 +1016,190 @@ async function createBlackSegment(outputPath, durationSec, width, height, trackP
   throw new Error('Failed to create black segment - lavfi not available in FFmpeg build');
 }
 
+/**
+ * Render an audio-only source over black video
+ * @param {string} inputPath - Audio file path
+ * @param {string} outputPath - Output video file path
+ * @param {number} startSec - Start time in seconds
+ * @param {number} durationSec - Duration in seconds
+ * @param {number} width - Black frame width
+ * @param {number} height - Black frame height
+ * @param {string} frameRate - Black frame rate ("num/den")
+ * @param {Object} encoding - Resolved encoder settings (see encoding.js)
+ * @param {string[]} audioFilters - Audio effect filters to apply
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ */
+function renderAudioOnlySegment(inputPath, outputPath, startSec, durationSec, width, height, frameRate, encoding, audioFilters, trackProcessFn) {
+  return new Promise((resolve, reject) => {
+    const command = ffmpeg()
+      .input(`color=black:s=${width}x${height}:r=${frameRate}`)
+      .inputFormat('lavfi')
+      .input(inputPath)
+      .seekInput(startSec)
+      .outputOptions([
+        '-map 0:v',
+        '-map 1:a',
+        `-t ${durationSec}`,
+        ...videoQualityOptions(encoding),
+        ...audioQualityOptions(encoding),
+      ])
+      .videoCodec(encoding.videoCodec)
+      .audioCodec(encoding.audioCodec);
+
+    command.audioFilters([...(audioFilters || []), ...buildConformFilters()]);
+
+    const ffmpegProcess = command
+      .output(outputPath)
+      .on('end', () => {
+        if (trackProcessFn) trackProcessFn(ffmpegProcess);
+        resolve();
+      })
+      .on('error', (err) => {
+        if (trackProcessFn) trackProcessFn(ffmpegProcess);
+        reject(err);
+      })
+      .run();
+
+    // Track the process for cleanup
+    if (trackProcessFn) trackProcessFn(ffmpegProcess);
+  });
+}
+
+/**
+ * Add a silent stereo track to a video-only segment (video is stream copied)
+ * @param {string} inputPath - Segment without audio
+ * @param {string} outputPath - Output segment path
+ * @param {number} durationSec - Segment duration in seconds
+ * @param {Object} encoding - Resolved encoder settings (see encoding.js)
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ */
+function muxSilentAudio(inputPath, outputPath, durationSec, encoding, trackProcessFn) {
+  return new Promise((resolve, reject) => {
+    const command = ffmpeg()
+      .input(inputPath)
+      .input(`anullsrc=r=${AUDIO_CONFORM.sampleRate}:cl=${AUDIO_CONFORM.channelLayout}`)
+      .inputFormat('lavfi')
+      .outputOptions([
+        '-map 0:v',
+        '-map 1:a',
+        '-c:v copy',
+        `-t ${durationSec}`,
+        ...audioQualityOptions(encoding),
+      ])
+      .audioCodec(encoding.audioCodec);
+
+    const ffmpegProcess = command
+      .output(outputPath)
+      .on('end', () => {
+        if (trackProcessFn) trackProcessFn(ffmpegProcess);
+        resolve();
+      })
+      .on('error', (err) => {
+        if (trackProcessFn) trackProcessFn(ffmpegProcess);
+        reject(err);
+      })
+      .run();
+
+    // Track the process for cleanup
+    if (trackProcessFn) trackProcessFn(ffmpegProcess);
+  });
+}
+
+/**
+ * Repeat a rendered segment until it fills the requested duration (stream copy)
+ * @param {string} piecePath - Segment containing one iteration of the loop
+ * @param {string} outputPath - Output segment path
+ * @param {number} totalDurationSec - Timeline duration to fill
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ */
+function loopSegment(piecePath, outputPath, totalDurationSec, trackProcessFn) {
+  return new Promise((resolve, reject) => {
+    const command = ffmpeg()
+      .input(piecePath)
+      .inputOptions(['-stream_loop -1'])
+      .duration(totalDurationSec)
+      .outputOptions(['-c copy']);
+
+    const ffmpegProcess = command
+      .output(outputPath)
+      .on('end', () => {
+        if (trackProcessFn) trackProcessFn(ffmpegProcess);
+        resolve();
+      })
+      .on('error', (err) => {
+        if (trackProcessFn) trackProcessFn(ffmpegProcess);
+        reject(err);
+      })
+      .run();
+
+    // Track the process for cleanup
+    if (trackProcessFn) trackProcessFn(ffmpegProcess);
+  });
+}
+
+/**
+ * Render a freeze-frame segment: one source frame held with tpad, over silence
+ * @param {string} inputPath - Input video file path
+ * @param {string} outputPath - Output video file path
+ * @param {number} atSec - Source time of the frame to hold
+ * @param {number} durationSec - Hold duration in seconds
+ * @param {number|null} targetWidth - Target width (null for source resolution)
+ * @param {number|null} targetHeight - Target height (null for source resolution)
+ * @param {string|null} frameRate - Output rate as "num/den" (null keeps the source rate)
+ * @param {Object} encoding - Resolved encoder settings (see encoding.js)
+ * @param {string[]} videoFilters - Clip effect filters, applied in the output frame
+ * @param {number|null} rotationDeg - Clockwise rotation to apply (null uses ffmpeg's autorotate)
+ * @param {string[]} sourceFilters - Filters on the upright source frame before it is fitted (clip crop)
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ */
+function renderFreezeSegment(inputPath, outputPath, atSec, durationSec, targetWidth, targetHeight, frameRate, encoding, videoFilters, rotationDeg, sourceFilters, trackProcessFn) {
+  return new Promise((resolve, reject) => {
+    const rotate = Number.isFinite(rotationDeg);
+    const videoFilters = [
+      'trim=end_frame=1',
+      'setpts=PTS-STARTPTS',
+      ...(rotate ? rotationFilters(rotationDeg) : []),
+      ...(sourceFilters || []),
+      `tpad=stop_mode=clone:stop_duration=${durationSec}`,
+      ...conformVideoFilters(targetWidth, targetHeight, frameRate, encoding.scaler),
+      ...(videoFilters || []),
+    ];
+
+    const command = ffmpeg()
+      .input(inputPath)
+      .seekInput(atSec)
+      .inputOptions(rotate ? ['-noautorotate'] : [])
+      .input(`anullsrc=r=${AUDIO_CONFORM.sampleRate}:cl=${AUDIO_CONFORM.channelLayout}`)
+      .inputFormat('lavfi')
+      .complexFilter([`[0:v]${videoFilters.join(',')}[v]`])
+      .outputOptions([
+        '-map [v]',
+        '-map 1:a',
+        `-t ${durationSec}`,
+        ...videoQualityOptions(encoding),
+        ...audioQualityOptions(encoding),
+        ...(rotate ? ['-metadata:s:v:0 rotate=0'] : []),
+      ])
+      .videoCodec(encoding.videoCodec)
+      .audioCodec(encoding.audioCodec);
+
+    const ffmpegProcess = command
+      .output(outputPath)
+      .on('end', () => {
+        if (trackProcessFn) trackProcessFn(ffmpegProcess);
+        resolve();
+      })
+      .on('error', (err) => {
+        if (trackProcessFn) trackProcessFn(ffmpegProcess);
+        reject(err);
+      })
+      .run();
+
+    // Track the process for cleanup
+    if (trackProcessFn) trackProcessFn(ffmpegProcess);
+  });
+}
+
 /**
  * Convert static image to video segment with aspect ratio preservation
  * @param {string} inputPath - Input image file path
@@ -249,63 +1207,33 @@ async function createBlackSegment(outputPath, durationSec, width, height, trackP
  * @param {number} durationSec - Duration in seconds
  * @param {number|null} targetWidth - Target width (null for source resolution)
  * @param {number|null} targetHeight - Target height (null for source resolution)
- * @param {number|null} assetWidth - Original asset width (for aspect ratio calculation)
- * @param {number|null} assetHeight - Original asset height (for aspect ratio calculation)
- * @param {number} bitrate - Video bitrate in kbps
+ * @param {string|null} frameRate - Output rate as "num/den" (null for 30 fps)
+ * @param {Object} encoding - Resolved encoder settings (see encoding.js)
+ * @param {string[]} videoFilters - Clip effect filters, applied in the output frame
  * @param {Function} trackProcessFn - Function to track ffmpeg process
  */
-function convertImageToVideoSegment(inputPath, outputPath, durationSec, targetWidth, targetHeight, assetWidth, assetHeight, bitrate, trackProcessFn) {
+function convertImageToVideoSegment(inputPath, outputPath, durationSec, targetWidth, targetHeight, frameRate, encoding, videoFilters, trackProcessFn) {
   return new Promise((resolve, reject) => {
+    // Animated GIFs loop via the gif demuxer; stills loop via image2
+    const isGif = path.extname(inputPath).toLowerCase() === '.gif';
     const command = ffmpeg(inputPath)
-      .inputOptions([
-        '-loop 1',           // Loop the single image
-        '-framerate 30'      // Output framerate
-      ])
+      .inputOptions(isGif
+        ? ['-ignore_loop 0']   // Honor the GIF's own loop so it repeats
+        : [
+          '-loop 1',           // Loop the single image
+          `-framerate ${frameRate || 30}`  // Output framerate
+        ])
       .duration(durationSec);
 
-    // Apply scaling with aspect ratio preservation if target resolution is specified
-    if (targetWidth && targetHeight) {
-      // If we have asset dimensions, preserve aspect ratio with letterboxing/pillarboxing
-      if (assetWidth && assetHeight && assetWidth > 0 && assetHeight > 0) {
-        const assetAspect = assetWidth / assetHeight;
-        const targetAspect = targetWidth / targetHeight;
-        
-        // Calculate scale to fit within target dimensions while preserving aspect ratio
-        let scaleWidth, scaleHeight;
-        if (assetAspect > targetAspect) {
-          // Image is wider - fit to width
-          scaleWidth = targetWidth;
-          scaleHeight = Math.round(targetWidth / assetAspect);
-        } else {
-          // Image is taller - fit to height
-          scaleHeight = targetHeight;
-          scaleWidth = Math.round(targetHeight * assetAspect);
-        }
-        
-        // Use scale filter with padding to center the image
-        // Format: scale=W:H:force_original_aspect_ratio=decrease,pad=W:H:x:y:color
-        const padX = Math.round((targetWidth - scaleWidth) / 2);
-        const padY = Math.round((targetHeight - scaleHeight) / 2);
-        
-        command
-          .videoFilters([
-            `scale=${scaleWidth}:${scaleHeight}:force_original_aspect_ratio=decrease`,
-            `pad=${targetWidth}:${targetHeight}:${padX}:${padY}:black`
-          ]);
-      } else {
-        // No asset dimensions available - use simple size (may distort)
-        command.size(`${targetWidth}x${targetHeight}`);
-      }
+    // Letterbox/pillarbox into the target frame; GIFs are also re-timed to the output rate
+    const filters = [...conformVideoFilters(targetWidth, targetHeight, isGif ? frameRate : null, encoding.scaler), ...(videoFilters || [])];
+    if (filters.length > 0) {
+      command.videoFilters(filters);
     }
-    
+
     command
-      .videoCodec('libx264')
-      .outputOptions([
-        '-preset veryfast',
-        '-crf 23',
-        `-b:v ${bitrate}k`,
-        '-pix_fmt yuv420p',   // Ensure compatibility
-      ]);
+      .videoCodec(encoding.videoCodec)
+      .outputOptions(videoQualityOptions(encoding));
 
     const ffmpegProcess = command
       .output(outputPath)
@@ -330,33 +1258,46 @@ function convertImageToVideoSegment(inputPath, outputPath, durationSec, targetWi
  * @param {string} outputPath - Output video file path
  * @param {number} startSec - Start time in seconds
  * @param {number} durationSec - Duration in seconds
- * @param {boolean} copyCodec - Whether to use codec copy (no re-encoding)
- * @param {number|null} targetWidth - Target width (null for source resolution)
- * @param {number|null} targetHeight - Target height (null for source resolution)
- * @param {number} bitrate - Video bitrate in kbps
- * @param {Function} trackProcessFn - Function to track ffmpeg process
+ * @param {Object} encoding - Resolved encoder settings (see encoding.js)
+ * @param {Object} options - { copyCodec: stream copy instead of re-encoding, targetWidth/targetHeight (null keeps the
+ *   source size), frameRate: "num/den" (null keeps the source rate), rotationDeg: clockwise rotation (null uses ffmpeg's
+ *   autorotate), sourceFilters: on the upright source frame before it is fitted (clip crop), videoFilters: clip effects in
+ *   the output frame, audioFilters, trackProcessFn }; everything but trackProcessFn applies to transcodes only
  */
-function trimSegment(inputPath, outputPath, startSec, durationSec, copyCodec, targetWidth, targetHeight, bitrate, trackProcessFn) {
+function trimSegment(inputPath, outputPath, startSec, durationSec, encoding, options = {}) {
+  const {
+    copyCodec = false,
+    targetWidth = null,
+    targetHeight = null,
+    frameRate = null,
+    rotationDeg = null,
+    sourceFilters = [],
+    videoFilters = [],
+    audioFilters = [],
+    trackProcessFn = null,
+  } = options;
   return new Promise((resolve, reject) => {
     const command = ffmpeg(inputPath).seekInput(startSec).duration(durationSec);
 
     if (copyCodec) {
       command.outputOptions(['-c copy']);
     } else {
-      // Apply scaling if target resolution is specified
-      if (targetWidth && targetHeight) {
-        command.size(`${targetWidth}x${targetHeight}`);
+      // Rotate before scaling so portrait sources are sized upright
+      applyRotation(command, rotationDeg);
+
+      // Crop, fit into the target frame and convert the rate, if specified, then apply clip effects
+      const filters = [...sourceFilters, ...conformVideoFilters(targetWidth, targetHeight, frameRate, encoding.scaler), ...videoFilters];
+      if (filters.length > 0) {
+        command.videoFilters(filters);
       }
+
+      // Effects first, then resample/re-layout so every segment concatenates cleanly
+      command.audioFilters([...audioFilters, ...buildConformFilters()]);
       
       command
-        .videoCodec('libx264')
-        .audioCodec('aac')
-        .outputOptions([
-          '-preset veryfast',
-          '-crf 23',
-          `-b:v ${bitrate}k`,
-          '-b:a 192k',
-        ]);
+        .videoCodec(encoding.videoCodec)
+        .audioCodec(encoding.audioCodec)
+        .outputOptions([...videoQualityOptions(encoding), ...audioQualityOptions(encoding)]);
     }
 
     const ffmpegProcess = command
@@ -383,10 +1324,11 @@ function trimSegment(inputPath, outputPath, startSec, durationSec, copyCodec, ta
  * @param {boolean} copyCodec - Whether to use codec copy (no re-encoding)
  * @param {number|null} targetWidth - Target width (null for source resolution)
  * @param {number|null} targetHeight - Target height (null for source resolution)
- * @param {number} bitrate - Video bitrate in kbps
+ * @param {string|null} frameRate - Output rate as "num/den" (null keeps the segment rate)
+ * @param {Object} encoding - Resolved encoder settings (see encoding.js)
  * @param {Function} trackProcessFn - Function to track ffmpeg process
  */
-function concatenateSegments(concatListPath, outputPath, copyCodec, targetWidth, targetHeight, bitrate, trackProcessFn) {
+function concatenateSegments(concatListPath, outputPath, copyCodec, targetWidth, targetHeight, frameRate, encoding, trackProcessFn) {
   return new Promise((resolve, reject) => {
     const command = ffmpeg()
       .input(concatListPath)
@@ -395,20 +1337,16 @@ function concatenateSegments(concatListPath, outputPath, copyCodec, targetWidth,
     if (copyCodec) {
       command.outputOptions(['-c copy']);
     } else {
-      // Apply scaling if target resolution is specified
-      if (targetWidth && targetHeight) {
-        command.size(`${targetWidth}x${targetHeight}`);
+      // Fit into the target frame and convert the rate, if specified
+      const conform = conformVideoFilters(targetWidth, targetHeight, frameRate, encoding.scaler);
+      if (conform.length > 0) {
+        command.videoFilters(conform);
       }
       
       command
-        .videoCodec('libx264')
-        .audioCodec('aac')
-        .outputOptions([
-          '-preset veryfast',
-          '-crf 23',
-          `-b:v ${bitrate}k`,
-          '-b:a 192k',
-        ]);
+        .videoCodec(encoding.videoCodec)
+        .audioCodec(encoding.audioCodec)
+        .outputOptions([...videoQualityOptions(encoding), ...audioQualityOptions(encoding)]);
     }
 
     const ffmpegProcess = command
@@ -429,6 +1367,8 @@ function concatenateSegments(concatListPath, outputPath, copyCodec, targetWidth,
 }
 
 module.exports = {
+  isImageFile,
+  resolveOutputFormat,
   executeExportJob,
 };
 
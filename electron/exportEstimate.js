@@ -0,0 +1,170 @@
+const ffmpeg = require('fluent-ffmpeg');
+const fs = require('fs-extra');
+const { applyQualityProfile, resolveEncoding, videoQualityOptions, audioQualityOptions } = require('./encoding');
+const { conformVideoFilters } = require('./filterUtils');
+const { estimateExportSize } = require('./planStats');
+
+/**
+ * Export estimates - render time and file size predicted before anything is rendered
+ * Earlier exports with the same encoder settings give the most faithful numbers (they include
+ * every stage's overhead); without any, a few seconds from the middle of the longest main clip
+ * are encoded with the export's settings and scaled to the program length. When neither works
+ * the size comes from the bitrate model alone and the render time is unknown.
+ */
+
+const ESTIMATE_METHODS = ['auto', 'history', 'sample'];
+
+const SAMPLE_SECONDS = 3;
+
+// Recent matching exports averaged for a history estimate
+const HISTORY_JOBS = 5;
+
+/**
+ * Settings that decide how fast an export encodes and how large it gets
+ */
+function throughputKey(settings = {}) {
+  const resolved = applyQualityProfile(settings);
+  const encoding = resolveEncoding(resolved);
+  return JSON.stringify({
+    format: resolved.format === 'mov' ? 'mov' : 'mp4',
+    width: resolved.width > 0 ? resolved.width : null,
+    height: resolved.height > 0 ? resolved.height : null,
+    halfResolution: !!resolved.halfResolution,
+    videoCodec: encoding.videoCodec,
+    preset: encoding.preset,
+    crf: encoding.crf,
+    bitrate: encoding.bitrate,
+    audioBitrate: encoding.audioBitrate,
+    scaler: encoding.scaler,
+  });
+}
+
+/**
+ * Average throughput of recent completed exports made with the same settings
+ * @param {Array} jobList - Jobs from jobs.listJobs (newest first)
+ * @param {Object} settings - ExportSettings being estimated
+ * @returns {{ realtime_factor: number, bytes_per_second: number, jobs: number }|null}
+ */
+function historicalThroughput(jobList, settings) {
+  const key = throughputKey(settings);
+  const matching = jobList
+    .filter((job) => job.kind === 'export' && job.status === 'completed' && !job.clip_id && job.settings)
+    .filter((job) => job.metrics && job.metrics.realtime_factor > 0 && job.metrics.output_duration_ms > 0)
+    .filter((job) => throughputKey(job.settings) === key)
+    .slice(0, HISTORY_JOBS);
+  if (matching.length === 0) {
+    return null;
+  }
+  const average = (values) => values.reduce((sum, value) => sum + value, 0) / values.length;
+  return {
+    realtime_factor: average(matching.map((job) => job.metrics.realtime_factor)),
+    bytes_per_second: average(matching.map((job) => job.metrics.output_bytes / (job.metrics.output_duration_ms / 1000))),
+    jobs: matching.length,
+  };
+}
+
+/**
+ * Clip a sample is taken from: the longest main-track video clip
+ */
+function sampleClip(plan, isImageFile) {
+  const candidates = plan.mainTrack.filter((clip) => !clip.generator && !clip.freezeFrame && !isImageFile(clip.srcPath));
+  if (candidates.length === 0) return null;
+  return candidates.reduce((a, b) => (b.outMs - b.inMs > a.outMs - a.inMs ? b : a));
+}
+
+/**
+ * Encode a few seconds of a clip with the export's encoder settings and output format
+ * @returns {Promise<{ realtime_factor: number, bytes_per_second: number, sample_ms: number }>}
+ */
+async function sampleThroughput(clip, format, encoding, outputPath, trackProcessFn) {
+  const clipMs = clip.outMs - clip.inMs;
+  const sampleMs = Math.min(SAMPLE_SECONDS * 1000, clipMs);
+  const startMs = clip.inMs + Math.max(0, (clipMs - sampleMs) / 2);
+
+  const startedAt = Date.now();
+  await new Promise((resolve, reject) => {
+    const command = ffmpeg()
+      .input(clip.srcPath)
+      .seekInput(startMs / 1000)
+      .duration(sampleMs / 1000)
+      .videoFilters(conformVideoFilters(format.width, format.height, format.frameRate, encoding.scaler))
+      .videoCodec(encoding.videoCodec)
+      .outputOptions([...videoQualityOptions(encoding), ...audioQualityOptions(encoding)])
+      .audioCodec(encoding.audioCodec)
+      .output(outputPath)
+      .on('start', () => {
+        if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+      })
+      .on('end', () => resolve())
+      .on('error', (err) => reject(err));
+
+    command.run();
+  });
+  const elapsedMs = Math.max(1, Date.now() - startedAt);
+  const stats = await fs.stat(outputPath);
+
+  return {
+    realtime_factor: sampleMs / elapsedMs,
+    bytes_per_second: stats.size / (sampleMs / 1000),
+    sample_ms: sampleMs,
+  };
+}
+
+/**
+ * Predict an export's render time and output size
+ * @param {Object} plan - Edit plan
+ * @param {Object} settings - ExportSettings
+ * @param {Object} options - { method: 'auto'|'history'|'sample', jobList, resolveFormat: () => Promise<format>,
+ *   isImageFile, samplePath, trackProcessFn }
+ * @returns {Promise<{ duration_ms: number, estimated_render_ms: number|null, estimated_size_bytes: number,
+ *   realtime_factor: number|null, method: string, jobs?: number, sample_ms?: number }>}
+ */
+async function estimateExport(plan, settings, options = {}) {
+  const method = ESTIMATE_METHODS.includes(options.method) ? options.method : 'auto';
+  const resolved = applyQualityProfile(settings);
+  const startMs = plan.mainTrack.length > 0 ? plan.mainTrack[0].startMs : 0;
+  const endMs = plan.mainTrack.length > 0 ? plan.mainTrack[plan.mainTrack.length - 1].endMs : 0;
+  const durationMs = endMs - startMs;
+
+  const fromThroughput = (throughput, extra) => ({
+    duration_ms: durationMs,
+    estimated_render_ms: Math.round(durationMs / throughput.realtime_factor),
+    estimated_size_bytes: Math.round(throughput.bytes_per_second * (durationMs / 1000)),
+    realtime_factor: Math.round(throughput.realtime_factor * 100) / 100,
+    ...extra,
+  });
+
+  if (method !== 'sample') {
+    const history = historicalThroughput(options.jobList || [], settings);
+    if (history) {
+      return fromThroughput(history, { method: 'history', jobs: history.jobs });
+    }
+  }
+
+  const clip = method !== 'history' && durationMs > 0 ? sampleClip(plan, options.isImageFile) : null;
+  if (clip) {
+    try {
+      const format = await options.resolveFormat();
+      const sample = await sampleThroughput(clip, format, resolveEncoding(resolved), options.samplePath, options.trackProcessFn);
+      return fromThroughput(sample, { method: 'sample', sample_ms: sample.sample_ms });
+    } catch (err) {
+      // Unreadable source or missing encoder: fall back to the bitrate model
+    } finally {
+      await fs.remove(options.samplePath).catch(() => {});
+    }
+  }
+
+  return {
+    duration_ms: durationMs,
+    estimated_render_ms: null,
+    estimated_size_bytes: estimateExportSize(durationMs, resolved),
+    realtime_factor: null,
+    method: 'bitrate',
+  };
+}
+
+module.exports = {
+  ESTIMATE_METHODS,
+  historicalThroughput,
+  estimateExport,
+};
@@ -0,0 +1,189 @@
+const { execFile } = require('child_process');
+const fs = require('fs-extra');
+const path = require('path');
+const { ErrorCode, StarscapeError } = require('./errors');
+const { uniqueOutputPath } = require('./outputNames');
+const { writeChecksumSidecars, removeChecksumSidecars } = require('./checksums');
+
+/**
+ * Export completion hooks - actions run on a finished render, configured per quality profile
+ * copy and move deliver the file (e.g. to a NAS share), command runs a user script with the
+ * output path as its last argument and reveal opens the containing folder. Actions run in order
+ * on the current output path, so a command after a move sees the moved file. A failed action is
+ * reported and the rest still run; the export itself has already succeeded. Checksum sidecars
+ * travel with the file, renamed along with it. Programs and destination folders go through a
+ * target check (in the app, only paths the user picked for a hook in a dialog), both when hooks
+ * are saved and again before each one runs.
+ */
+
+const HOOK_ACTION_TYPES = ['copy', 'move', 'command', 'reveal'];
+
+// Exports without a quality profile use the custom hooks (matching {preset} in filenames)
+const CUSTOM_PRESET = 'custom';
+
+// A hook script that hangs shouldn't keep the job open forever
+const COMMAND_TIMEOUT_MS = 10 * 60 * 1000;
+
+let storePath = null;
+let hooksByPreset = {};
+
+function absoluteTarget(value) {
+  if (typeof value !== 'string' || !path.isAbsolute(value)) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Hook target must be an absolute path: ${value}`, { path: value });
+  }
+  return value;
+}
+
+/**
+ * Check one action and keep only its known fields
+ * @param {Function} checkTarget - (path) => checked path for programs and destinations; throws when not allowed
+ */
+function normalizeHookAction(action = {}, checkTarget = absoluteTarget) {
+  if (!HOOK_ACTION_TYPES.includes(action.type)) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Unsupported export hook: ${action.type}`, {
+      type: action.type,
+      supported: HOOK_ACTION_TYPES,
+    });
+  }
+  if (action.type === 'copy' || action.type === 'move') {
+    if (typeof action.destination !== 'string' || !path.isAbsolute(action.destination)) {
+      throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `${action.type} hook needs an absolute destination folder`, { destination: action.destination });
+    }
+    return { type: action.type, destination: checkTarget(action.destination) };
+  }
+  if (action.type === 'command') {
+    if (typeof action.command !== 'string' || !action.command.trim()) {
+      throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, 'command hook needs a program to run', { command: action.command });
+    }
+    const args = Array.isArray(action.args) ? action.args.map(String) : [];
+    return { type: 'command', command: checkTarget(action.command), args };
+  }
+  return { type: 'reveal' };
+}
+
+function saveStore() {
+  if (!storePath) return;
+  try {
+    fs.mkdirSync(path.dirname(storePath), { recursive: true });
+    fs.writeFileSync(storePath, JSON.stringify({ hooks: hooksByPreset }, null, 2));
+  } catch (e) {
+    console.error('Failed to save export hooks:', e.message);
+  }
+}
+
+/**
+ * Hooks for every preset that has any
+ * @returns {Object} Map of preset -> actions
+ */
+function getExportHooks() {
+  return { ...hooksByPreset };
+}
+
+/**
+ * Actions run after exports with a quality profile (custom when unset)
+ */
+function hooksForPreset(qualityProfile) {
+  return hooksByPreset[qualityProfile || CUSTOM_PRESET] || [];
+}
+
+/**
+ * Replace a preset's actions; an empty list removes them
+ * @param {Function} checkTarget - See normalizeHookAction
+ */
+function setExportHooks(preset, actions = [], checkTarget = absoluteTarget) {
+  if (typeof preset !== 'string' || !preset.trim()) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, 'Export hooks need a preset', { preset });
+  }
+  const normalized = (Array.isArray(actions) ? actions : []).map((action) => normalizeHookAction(action, checkTarget));
+  if (normalized.length > 0) {
+    hooksByPreset[preset] = normalized;
+  } else {
+    delete hooksByPreset[preset];
+  }
+  saveStore();
+  return hooksForPreset(preset);
+}
+
+function runCommand(command, args, trackProcessFn) {
+  return new Promise((resolve, reject) => {
+    const proc = execFile(command, args, { timeout: COMMAND_TIMEOUT_MS, maxBuffer: 1024 * 1024 }, (err, stdout, stderr) => {
+      if (err) {
+        const detail = String(stderr || '').trim().split('\n').pop();
+        reject(new Error(detail ? `${err.message.split('\n')[0]}: ${detail}` : err.message));
+      } else {
+        resolve();
+      }
+    });
+    if (trackProcessFn) trackProcessFn(proc);
+  });
+}
+
+/**
+ * Run a preset's actions on a finished export
+ * @param {string} outputPath - Rendered file
+ * @param {Array} actions - Actions from hooksForPreset
+ * @param {Object} options - { reveal: (path) => void, trackProcessFn, checksums: map of algorithm -> digest,
+ *   checkTarget: see normalizeHookAction }
+ * @returns {Promise<{ path: string, results: Array<{ type: string, success: boolean, path: string|null,
+ *   error: string|null, duration_ms: number }> }>} path is where the export ended up
+ */
+async function runExportHooks(outputPath, actions, options = {}) {
+  const checkTarget = options.checkTarget || absoluteTarget;
+  let currentPath = outputPath;
+  const results = [];
+  for (const action of actions) {
+    const startedAt = Date.now();
+    let resultPath = null;
+    let error = null;
+    try {
+      // Stored hooks are checked again, so a revoked or never-granted target doesn't run
+      if (action.type === 'copy' || action.type === 'move') {
+        checkTarget(action.destination);
+        await fs.ensureDir(action.destination);
+        const ext = path.extname(currentPath).slice(1);
+        resultPath = uniqueOutputPath(action.destination, path.basename(currentPath, path.extname(currentPath)), ext);
+        if (action.type === 'copy') {
+          await fs.copy(currentPath, resultPath);
+        } else {
+          await fs.move(currentPath, resultPath);
+          if (options.checksums) await removeChecksumSidecars(currentPath, options.checksums);
+          currentPath = resultPath;
+        }
+        if (options.checksums) await writeChecksumSidecars(resultPath, options.checksums);
+      } else if (action.type === 'command') {
+        checkTarget(action.command);
+        await runCommand(action.command, [...action.args, currentPath], options.trackProcessFn);
+      } else if (options.reveal) {
+        options.reveal(currentPath);
+      }
+    } catch (err) {
+      error = String(err.message || err);
+      resultPath = null;
+    }
+    results.push({ type: action.type, success: !error, path: resultPath, error, duration_ms: Date.now() - startedAt });
+  }
+  return { path: currentPath, results };
+}
+
+/**
+ * Load hooks from the settings store
+ */
+function init(filePath) {
+  storePath = filePath;
+  try {
+    const stored = JSON.parse(fs.readFileSync(filePath, 'utf8'));
+    hooksByPreset = stored.hooks && typeof stored.hooks === 'object' ? stored.hooks : {};
+  } catch (e) {
+    hooksByPreset = {};
+  }
+}
+
+module.exports = {
+  HOOK_ACTION_TYPES,
+  normalizeHookAction,
+  init,
+  getExportHooks,
+  hooksForPreset,
+  setExportHooks,
+  runExportHooks,
+};
@@ -1,47 +1,203 @@
+const fs = require('fs');
 const path = require('path');
+const { execFile } = require('child_process');
 const { app } = require('electron');
 const ffmpeg = require('fluent-ffmpeg');
+const { ErrorCode, StarscapeError } = require('./errors');
 
 /**
- * Resolve FFmpeg binary path based on environment
+ * Hints shown when a binary can't be found, per platform
  */
-function resolveFfmpegPath() {
-  if (app.isPackaged) {
+const INSTALL_HINTS = {
+  darwin: 'Reinstall Starscape Studio, or install FFmpeg with `brew install ffmpeg` and restart.',
+  win32: 'Reinstall Starscape Studio, or install FFmpeg (e.g. `winget install ffmpeg`) and make sure it is on PATH, then restart.',
+  linux: 'Reinstall Starscape Studio, or install FFmpeg with your package manager (e.g. `sudo apt install ffmpeg`) and restart.',
+};
+
+/**
+ * Features that keep working with only one of the two tools
+ */
+const TOOL_FEATURES = {
+  ffmpeg: ['export', 'thumbnails', 'previews', 'stills', 'audio analysis'],
+  ffprobe: ['metadata', 'chapters', 'keyframe index'],
+};
+
+function executableName(name) {
+  return process.platform === 'win32' ? `${name}.exe` : name;
+}
+
+/**
+ * Path of a binary shipped with the app (may not exist)
+ */
+function bundledBinaryPath(name) {
+  // `app` is undefined when loaded outside Electron (tests)
+  if (app && app.isPackaged) {
     // Production: binaries are in resources
-    return path.join(process.resourcesPath, 'bin', 'macos', 'ffmpeg');
-  } else {
-    // Development: binaries are in electron/bin/macos
-    return path.join(__dirname, 'bin', 'macos', 'ffmpeg');
+    return path.join(process.resourcesPath, 'bin', 'macos', name);
+  }
+  // Development: binaries are in electron/bin/macos
+  return path.join(__dirname, 'bin', 'macos', name);
+}
+
+function isExecutable(filePath) {
+  try {
+    fs.accessSync(filePath, process.platform === 'win32' ? fs.constants.F_OK : fs.constants.X_OK);
+    return fs.statSync(filePath).isFile();
+  } catch (e) {
+    return false;
+  }
+}
+
+/**
+ * Find a binary on PATH
+ */
+function findOnPath(name) {
+  const dirs = (process.env.PATH || '').split(path.delimiter).filter(Boolean);
+  for (const dir of dirs) {
+    const candidate = path.join(dir, executableName(name));
+    if (isExecutable(candidate)) {
+      return candidate;
+    }
   }
+  return null;
 }
 
 /**
- * Resolve FFprobe binary path based on environment
+ * Environment variables that override binary resolution (custom builds, tests)
+ */
+const ENV_OVERRIDES = {
+  ffmpeg: 'STARSCAPE_FFMPEG_PATH',
+  ffprobe: 'STARSCAPE_FFPROBE_PATH',
+};
+
+/**
+ * Resolve one binary independently: env override, then bundled copy, then PATH
+ * @returns {{ path: string|null, source: 'env'|'bundled'|'system'|null, searched: string[] }}
+ */
+function resolveBinary(name) {
+  const override = ENV_OVERRIDES[name] && process.env[ENV_OVERRIDES[name]];
+  if (override) {
+    return isExecutable(override)
+      ? { path: override, source: 'env', searched: [override] }
+      : { path: null, source: null, searched: [override] };
+  }
+
+  const bundled = bundledBinaryPath(name);
+  if (isExecutable(bundled)) {
+    return { path: bundled, source: 'bundled', searched: [bundled] };
+  }
+  const system = findOnPath(name);
+  if (system) {
+    return { path: system, source: 'system', searched: [bundled, 'PATH'] };
+  }
+  return { path: null, source: null, searched: [bundled, 'PATH'] };
+}
+
+/**
+ * Resolve FFmpeg binary path (falls back to the bundled location so errors name it)
+ */
+function resolveFfmpegPath() {
+  return resolveBinary('ffmpeg').path || bundledBinaryPath('ffmpeg');
+}
+
+/**
+ * Resolve FFprobe binary path (falls back to the bundled location so errors name it)
  */
 function resolveFfprobePath() {
-  if (app.isPackaged) {
-    return path.join(process.resourcesPath, 'bin', 'macos', 'ffprobe');
-  } else {
-    return path.join(__dirname, 'bin', 'macos', 'ffprobe');
+  return resolveBinary('ffprobe').path || bundledBinaryPath('ffprobe');
+}
+
+/**
+ * Explain which tool is missing, where we looked and how to fix it
+ */
+function missingToolMessage(name, searched) {
+  const hint = INSTALL_HINTS[process.platform] || INSTALL_HINTS.linux;
+  return `${name} was not found (looked in ${searched.join(' and ')}). ${hint}`;
+}
+
+/**
+ * Throw a descriptive error when a feature needs a tool that isn't installed
+ * @param {'ffmpeg'|'ffprobe'} name - Tool name
+ * @param {string} feature - Feature that needs it (for the message)
+ */
+function requireBinary(name, feature) {
+  const resolved = resolveBinary(name);
+  if (!resolved.path) {
+    throw new StarscapeError(
+      ErrorCode.DEPENDENCY_MISSING,
+      `${feature} requires ${missingToolMessage(name, resolved.searched)}`,
+      { tool: name, searched: resolved.searched, fix: INSTALL_HINTS[process.platform] || INSTALL_HINTS.linux }
+    );
   }
+  return resolved.path;
 }
 
 /**
- * Configure fluent-ffmpeg to use bundled binaries
+ * Check whether a binary is present and actually runs
+ */
+function checkBinary(name) {
+  const resolved = resolveBinary(name);
+  const base = { found: false, path: resolved.path, source: resolved.source, version: null, error: null, fix: null };
+  if (!resolved.path) {
+    return Promise.resolve({ ...base, error: missingToolMessage(name, resolved.searched), fix: INSTALL_HINTS[process.platform] || INSTALL_HINTS.linux });
+  }
+  return new Promise((resolve) => {
+    execFile(resolved.path, ['-hide_banner', '-version'], { timeout: 10000 }, (err, stdout) => {
+      if (err) {
+        resolve({
+          ...base,
+          error: `${name} at ${resolved.path} failed to run: ${err.message.trim()}`,
+          fix: INSTALL_HINTS[process.platform] || INSTALL_HINTS.linux,
+        });
+        return;
+      }
+      const match = new RegExp(`${name} version (\\S+)`).exec(stdout);
+      resolve({ ...base, found: true, version: match ? match[1] : null });
+    });
+  });
+}
+
+/**
+ * Report both tools and which features are usable with what was found
+ */
+async function checkDependencies() {
+  const [ffmpegStatus, ffprobeStatus] = await Promise.all([checkBinary('ffmpeg'), checkBinary('ffprobe')]);
+  const available = [];
+  const unavailable = [];
+  for (const [name, status] of [['ffmpeg', ffmpegStatus], ['ffprobe', ffprobeStatus]]) {
+    (status.found ? available : unavailable).push(...TOOL_FEATURES[name]);
+  }
+  return {
+    ok: ffmpegStatus.found && ffprobeStatus.found,
+    ffmpeg: ffmpegStatus,
+    ffprobe: ffprobeStatus,
+    available_features: available,
+    unavailable_features: unavailable,
+  };
+}
+
+/**
+ * Configure fluent-ffmpeg with whichever binaries were found (each one independently)
  */
 function configureFfmpeg() {
-  const ffmpegPath = resolveFfmpegPath();
-  const ffprobePath = resolveFfprobePath();
-  
-  ffmpeg.setFfmpegPath(ffmpegPath);
-  ffmpeg.setFfprobePath(ffprobePath);
-  
-  return { ffmpegPath, ffprobePath };
+  const ffmpegBinary = resolveBinary('ffmpeg');
+  const ffprobeBinary = resolveBinary('ffprobe');
+
+  if (ffmpegBinary.path) {
+    ffmpeg.setFfmpegPath(ffmpegBinary.path);
+  }
+  if (ffprobeBinary.path) {
+    ffmpeg.setFfprobePath(ffprobeBinary.path);
+  }
+
+  return { ffmpegPath: ffmpegBinary.path, ffprobePath: ffprobeBinary.path };
 }
 
 module.exports = {
+  resolveBinary,
   resolveFfmpegPath,
   resolveFfprobePath,
+  requireBinary,
+  checkDependencies,
   configureFfmpeg,
 };
-
@@ -0,0 +1,134 @@
+/**
+ * Shared helpers for building ffmpeg filtergraph strings
+ */
+
+/**
+ * Escape a file path for use as a filtergraph option value
+ */
+function escapeFilterPath(filePath) {
+  return filePath
+    .replace(/\\/g, '/')
+    .replace(/:/g, '\\:')
+    .replace(/'/g, "\\'");
+}
+
+/**
+ * Clamp a numeric option to a range, falling back to a default when missing
+ */
+function clampOption(value, min, max, fallback) {
+  const num = typeof value === 'number' && Number.isFinite(value) ? value : fallback;
+  return Math.min(max, Math.max(min, num));
+}
+
+/**
+ * Names of the filters referenced by filtergraph strings ("[0:v]scale=1280:-2,format=rgba[v]" -> scale, format)
+ */
+function filterNamesIn(filters) {
+  const names = new Set();
+  for (const filter of filters) {
+    // Quoted option values may contain commas and parentheses
+    const unquoted = String(filter).replace(/'(?:[^'\\]|\\.)*'/g, "''");
+    for (const match of unquoted.matchAll(/(?:^|[,;])\s*(?:\[[^\]]*\]\s*)*([a-z][a-z0-9_]*)(?==|,|;|\[|$)/g)) {
+      names.add(match[1]);
+    }
+  }
+  return [...names];
+}
+
+/**
+ * Filters that turn a frame by a clockwise rotation (0/90/180/270)
+ */
+function rotationFilters(rotationDeg) {
+  switch (rotationDeg) {
+    case 90:
+      return ['transpose=clock'];
+    case 180:
+      return ['hflip', 'vflip'];
+    case 270:
+      return ['transpose=cclock'];
+    default:
+      return [];
+  }
+}
+
+/**
+ * Crop to a clip's active picture (e.g. with baked-in black bars removed)
+ * @param {Object|undefined} crop - { x, y, width, height } in upright source pixels
+ */
+function cropFilters(crop) {
+  if (!crop || !(crop.width > 0) || !(crop.height > 0)) {
+    return [];
+  }
+  const x = Math.max(0, Math.round(crop.x) || 0);
+  const y = Math.max(0, Math.round(crop.y) || 0);
+  return [`crop=${Math.round(crop.width)}:${Math.round(crop.height)}:${x}:${y}`];
+}
+
+// Frame interpolation is only worth its cost for slow motion below this speed
+const INTERPOLATE_BELOW_SPEED = 0.5;
+
+/**
+ * Filters that play a clip's picture at its speed (unset or 1 plays it as recorded)
+ * Interpolated clips slower than 0.5x get motion-compensated in-between frames from minterpolate
+ * instead of repeated ones; it's slow, so clips opt in with interpolateFrames.
+ * @param {Object} clip - Plan clip ({ speed, interpolateFrames })
+ * @param {string|null} frameRate - Output rate as "num/den" the in-between frames are made at
+ */
+function speedVideoFilters(clip, frameRate) {
+  if (!clip.speed || clip.speed === 1) {
+    return [];
+  }
+  const filters = [`setpts=(PTS-STARTPTS)/${clip.speed}`];
+  if (clip.interpolateFrames && clip.speed < INTERPOLATE_BELOW_SPEED && frameRate) {
+    filters.push(`minterpolate=fps=${frameRate}:mi_mode=mci:mc_mode=aobmc:me_mode=bidir:vsbmc=1`);
+  }
+  return filters;
+}
+
+/**
+ * Filters that fit a frame inside width x height (letterbox/pillarbox in black) at a fixed rate
+ * @param {number|null} width - Output width (null leaves the size alone)
+ * @param {number|null} height - Output height
+ * @param {string|null} frameRate - Output rate as "num/den" (null leaves the rate alone)
+ * @param {string|null} scaler - swscale algorithm (null keeps ffmpeg's bicubic)
+ */
+function conformVideoFilters(width, height, frameRate, scaler = null) {
+  const filters = [];
+  if (width && height) {
+    filters.push(
+      `scale=${width}:${height}:force_original_aspect_ratio=decrease${scaler ? `:flags=${scaler}` : ''}`,
+      `pad=${width}:${height}:(ow-iw)/2:(oh-ih)/2:black`,
+      'setsar=1'
+    );
+  }
+  if (frameRate) {
+    filters.push(`fps=${frameRate}`);
+  }
+  return filters;
+}
+
+/**
+ * Filters converting HDR frames to SDR BT.709 (needs zscale and tonemap)
+ * @param {string} transfer - Source transfer (smpte2084 or arib-std-b67)
+ */
+function toneMapFilters(transfer) {
+  return [
+    `zscale=tin=${transfer}:pin=bt2020:min=bt2020nc:t=linear:npl=100`,
+    'format=gbrpf32le',
+    'zscale=p=bt709',
+    'tonemap=tonemap=hable:desat=0',
+    'zscale=t=bt709:m=bt709:r=tv',
+    'format=yuv420p',
+  ];
+}
+
+module.exports = {
+  escapeFilterPath,
+  rotationFilters,
+  cropFilters,
+  speedVideoFilters,
+  conformVideoFilters,
+  toneMapFilters,
+  clampOption,
+  filterNamesIn,
+};
@@ -0,0 +1,213 @@
+const fs = require('fs');
+const os = require('os');
+const path = require('path');
+
+/**
+ * System font enumeration - family/style/path of installed fonts, read from each file's name table
+ * Scanned once per session; text rendering resolves user-picked family names to files through it.
+ */
+
+const FONT_EXTENSIONS = ['.ttf', '.otf', '.ttc', '.otc'];
+
+// Deepest directory nesting scanned under each font root
+const MAX_SCAN_DEPTH = 6;
+
+let fontsPromise = null;
+
+/**
+ * Directories fonts are installed in, per platform
+ */
+function fontDirectories() {
+  const home = os.homedir();
+  switch (process.platform) {
+    case 'darwin':
+      return ['/System/Library/Fonts', '/Library/Fonts', path.join(home, 'Library', 'Fonts')];
+    case 'win32': {
+      const dirs = [path.join(process.env.WINDIR || 'C:\\Windows', 'Fonts')];
+      if (process.env.LOCALAPPDATA) {
+        dirs.push(path.join(process.env.LOCALAPPDATA, 'Microsoft', 'Windows', 'Fonts'));
+      }
+      return dirs;
+    }
+    default:
+      return ['/usr/share/fonts', '/usr/local/share/fonts', path.join(home, '.local', 'share', 'fonts'), path.join(home, '.fonts')];
+  }
+}
+
+/**
+ * Font files under a directory (recursive, missing directories yield nothing)
+ */
+async function findFontFiles(dir, depth = 0) {
+  let entries;
+  try {
+    entries = await fs.promises.readdir(dir, { withFileTypes: true });
+  } catch (e) {
+    return [];
+  }
+  const files = [];
+  for (const entry of entries) {
+    const fullPath = path.join(dir, entry.name);
+    if (entry.isDirectory() && depth < MAX_SCAN_DEPTH) {
+      files.push(...(await findFontFiles(fullPath, depth + 1)));
+    } else if (entry.isFile() && FONT_EXTENSIONS.includes(path.extname(entry.name).toLowerCase())) {
+      files.push(fullPath);
+    }
+  }
+  return files;
+}
+
+async function readAt(handle, offset, length) {
+  const buffer = Buffer.alloc(length);
+  const { bytesRead } = await handle.read(buffer, 0, length, offset);
+  if (bytesRead < length) {
+    throw new Error('Truncated font file');
+  }
+  return buffer;
+}
+
+/**
+ * Decode a name record: Windows/Unicode platforms are UTF-16BE, Macintosh Roman is close enough to latin1
+ */
+function decodeName(buffer, platformId) {
+  if (platformId === 0 || platformId === 3) {
+    const swapped = Buffer.from(buffer);
+    swapped.swap16();
+    return swapped.toString('utf16le');
+  }
+  return buffer.toString('latin1');
+}
+
+/**
+ * Family and style of the font whose offset table starts at `offset`
+ * Prefers the typographic family/subfamily (name IDs 16/17) over the legacy ones (1/2),
+ * and English Windows names over other records.
+ */
+async function readFaceNames(handle, offset) {
+  const header = await readAt(handle, offset, 12);
+  const numTables = header.readUInt16BE(4);
+  const directory = await readAt(handle, offset + 12, numTables * 16);
+
+  let nameTable = null;
+  for (let i = 0; i < numTables; i++) {
+    if (directory.toString('latin1', i * 16, i * 16 + 4) === 'name') {
+      nameTable = { offset: directory.readUInt32BE(i * 16 + 8), length: directory.readUInt32BE(i * 16 + 12) };
+      break;
+    }
+  }
+  if (!nameTable) return null;
+
+  const table = await readAt(handle, nameTable.offset, nameTable.length);
+  const count = table.readUInt16BE(2);
+  const stringOffset = table.readUInt16BE(4);
+  const names = {};
+  for (let i = 0; i < count; i++) {
+    const record = 6 + i * 12;
+    const platformId = table.readUInt16BE(record);
+    const languageId = table.readUInt16BE(record + 4);
+    const nameId = table.readUInt16BE(record + 6);
+    if (![1, 2, 16, 17].includes(nameId) || ![0, 1, 3].includes(platformId)) continue;
+    // Windows US English, then any Unicode/Windows record, then Macintosh
+    const rank = platformId === 3 && languageId === 0x409 ? 3 : platformId === 1 ? 1 : 2;
+    if (names[nameId] && names[nameId].rank >= rank) continue;
+    const start = stringOffset + table.readUInt16BE(record + 10);
+    const value = decodeName(table.subarray(start, start + table.readUInt16BE(record + 8)), platformId).trim();
+    if (value) {
+      names[nameId] = { rank, value };
+    }
+  }
+
+  const family = (names[16] || names[1] || {}).value;
+  const style = (names[17] || names[2] || {}).value || 'Regular';
+  return family ? { family, style } : null;
+}
+
+/**
+ * Faces in one font file (collections hold several)
+ * @returns {Promise<Array<{ family: string, style: string, path: string, index: number }>>}
+ */
+async function readFontFile(filePath) {
+  const handle = await fs.promises.open(filePath, 'r');
+  try {
+    const tag = (await readAt(handle, 0, 4)).toString('latin1');
+    const offsets = [0];
+    if (tag === 'ttcf') {
+      const header = await readAt(handle, 8, 4);
+      const numFonts = header.readUInt32BE(0);
+      const table = await readAt(handle, 12, numFonts * 4);
+      offsets.splice(0, 1, ...Array.from({ length: numFonts }, (_, i) => table.readUInt32BE(i * 4)));
+    }
+    const faces = [];
+    for (const [index, offset] of offsets.entries()) {
+      const names = await readFaceNames(handle, offset);
+      if (names) {
+        faces.push({ ...names, path: filePath, index });
+      }
+    }
+    return faces;
+  } finally {
+    await handle.close();
+  }
+}
+
+/**
+ * Enumerate installed fonts, sorted by family then style (cached for the session)
+ * Unreadable or malformed files are skipped.
+ * @param {boolean} refresh - Rescan instead of returning the cached list
+ */
+function listSystemFonts(refresh = false) {
+  if (!fontsPromise || refresh) {
+    fontsPromise = (async () => {
+      const seen = new Set();
+      const fonts = [];
+      for (const dir of fontDirectories()) {
+        for (const filePath of await findFontFiles(dir)) {
+          const faces = await readFontFile(filePath).catch(() => []);
+          for (const face of faces) {
+            const key = `${face.family}\u0000${face.style}`.toLowerCase();
+            if (seen.has(key)) continue;
+            seen.add(key);
+            fonts.push(face);
+          }
+        }
+      }
+      return fonts.sort((a, b) => a.family.localeCompare(b.family) || a.style.localeCompare(b.style));
+    })();
+  }
+  return fontsPromise;
+}
+
+/**
+ * Score how well a face's style matches the requested weight/slant (higher is better)
+ */
+function styleScore(style, bold, italic) {
+  const lower = style.toLowerCase();
+  const isBold = /bold|black|heavy/.test(lower) && !/semi|demi/.test(lower);
+  const isItalic = /italic|oblique/.test(lower);
+  let score = 0;
+  if (isBold === !!bold) score += 2;
+  if (isItalic === !!italic) score += 2;
+  if (/^(regular|normal|book|roman)$/.test(lower) || (bold && lower === 'bold')) score += 1;
+  return score;
+}
+
+/**
+ * Pick the installed face for a family name (case-insensitive), closest to the requested style
+ * @param {string} family - Family name as shown by listSystemFonts
+ * @param {{ bold?: boolean, italic?: boolean }} options
+ * @param {Array} fonts - Font list to search (defaults to the system list)
+ * @returns {Promise<{ family: string, style: string, path: string, index: number }|null>}
+ */
+async function resolveFont(family, { bold = false, italic = false } = {}, fonts = null) {
+  if (typeof family !== 'string' || !family.trim()) return null;
+  const wanted = family.trim().toLowerCase();
+  const candidates = (fonts || (await listSystemFonts())).filter((font) => font.family.toLowerCase() === wanted);
+  if (candidates.length === 0) return null;
+  return candidates.reduce((best, font) => (styleScore(font.style, bold, italic) > styleScore(best.style, bold, italic) ? font : best));
+}
+
+module.exports = {
+  fontDirectories,
+  readFontFile,
+  listSystemFonts,
+  resolveFont,
+};
@@ -0,0 +1,360 @@
+const ffmpeg = require('fluent-ffmpeg');
+const fs = require('fs-extra');
+const { assColor, assTime, assScript, assFilter, collectFonts, escapeAssText } = require('./captions');
+const { clampOption } = require('./filterUtils');
+const { videoQualityOptions, audioQualityOptions, encodingKey, requiresMov } = require('./encoding');
+const { hashValue } = require('./planHash');
+const { assertCapabilities } = require('./capabilities');
+
+/**
+ * Generator assets - media synthesized from lavfi sources instead of imported files
+ * (solid color, gradient, countdown, scrolling credits, test pattern, tone). Each generator clip is rendered
+ * once into the cache at the output size and rate; the file then stands in as the clip's source.
+ */
+
+const GENERATOR_TYPES = ['solid', 'gradient', 'countdown', 'credits', 'testPattern', 'tone'];
+
+const TEST_PATTERNS = { smpte: 'smptehdbars', testsrc: 'testsrc2' };
+
+const COUNTDOWN_FONT_FAMILY = 'Arial';
+
+const CREDITS_FONT_FAMILY = 'Arial';
+
+// Credits layout at 1080 lines; scaled to the output height
+const CREDITS_HEADING_SIZE = 44;
+const CREDITS_LINE_SIZE = 36;
+const CREDITS_LEADING = 1.5;
+
+// Frame format used when a generator is rendered for a preview rather than an export
+const PREVIEW_FORMAT = { width: 1280, height: 720, frameRate: '30/1' };
+
+function hexColor(value, fallback) {
+  return typeof value === 'string' && /^#?[0-9a-f]{6}$/i.test(value.trim())
+    ? `#${value.trim().replace(/^#/, '').toUpperCase()}`
+    : fallback;
+}
+
+/**
+ * Credits blocks with blank headings and lines dropped
+ * @param {Array} blocks - [{ heading, lines: string[] }]
+ */
+function creditsBlocks(blocks) {
+  const text = (value) => (typeof value === 'string' ? value.trim() : '');
+  const result = (Array.isArray(blocks) ? blocks : [])
+    .map((block) => ({
+      heading: text(block && block.heading),
+      lines: (Array.isArray(block && block.lines) ? block.lines : []).map(text).filter(Boolean),
+    }))
+    .filter((block) => block.heading || block.lines.length > 0);
+  if (result.length === 0) {
+    throw new Error('Credits need at least one heading or line');
+  }
+  return result;
+}
+
+/**
+ * Validate a generator description and fill in defaults
+ * @param {Object} generator - { type, color, colors, angle, textColor, blocks, speed, pattern, frequency, volumeDb }
+ */
+function normalizeGenerator(generator) {
+  const type = generator && generator.type;
+  switch (type) {
+    case 'solid':
+      return { type, color: hexColor(generator.color, '#000000') };
+    case 'gradient': {
+      const colors = Array.isArray(generator.colors) ? generator.colors : [];
+      return {
+        type,
+        colors: [hexColor(colors[0], '#1E3C72'), hexColor(colors[1], '#2A5298')],
+        angle: clampOption(generator.angle, 0, 360, 90),
+      };
+    }
+    case 'countdown':
+      return { type, color: hexColor(generator.color, '#202020'), textColor: hexColor(generator.textColor, '#FFFFFF') };
+    case 'credits':
+      return {
+        type,
+        color: hexColor(generator.color, '#000000'),
+        textColor: hexColor(generator.textColor, '#FFFFFF'),
+        blocks: creditsBlocks(generator.blocks),
+        // Pixels per second at 1080 lines; unset scrolls the whole roll through in the clip's length
+        speed: generator.speed === undefined || generator.speed === null ? null : clampOption(generator.speed, 10, 1000, 120),
+      };
+    case 'testPattern':
+      return { type, pattern: generator.pattern in TEST_PATTERNS ? generator.pattern : 'smpte' };
+    case 'tone':
+      return {
+        type,
+        frequency: Math.round(clampOption(generator.frequency, 20, 20000, 1000)),
+        volumeDb: clampOption(generator.volumeDb, -60, 0, -20),
+      };
+    default:
+      throw new Error(`Unknown generator type: ${type}`);
+  }
+}
+
+/**
+ * Whether a generator produces sound only (no picture)
+ */
+function isAudioGenerator(generator) {
+  return generator.type === 'tone';
+}
+
+/**
+ * lavfi color literal for #RRGGBB
+ */
+function lavfiColor(hex) {
+  return `0x${hex.replace(/^#/, '')}`;
+}
+
+/**
+ * Linear gradient endpoints spanning the frame along an angle (0 = left to right, 90 = top to bottom)
+ */
+function gradientPoints(angle, width, height) {
+  const rad = (angle * Math.PI) / 180;
+  const dx = Math.cos(rad);
+  const dy = Math.sin(rad);
+  const reach = (Math.abs(dx) * width + Math.abs(dy) * height) / 2;
+  const cx = width / 2;
+  const cy = height / 2;
+  return {
+    x0: Math.round(cx - dx * reach),
+    y0: Math.round(cy - dy * reach),
+    x1: Math.round(cx + dx * reach),
+    y1: Math.round(cy + dy * reach),
+  };
+}
+
+/**
+ * lavfi source expression for a generator
+ * @param {Object} generator - Normalized generator
+ * @param {{ width: number, height: number, frameRate: string }} format - Frame size and rate
+ * @param {number} durationSec - Length to synthesize
+ */
+function generatorSource(generator, format, durationSec) {
+  const { width, height, frameRate } = format;
+  const frame = `s=${width}x${height}:r=${frameRate}:d=${durationSec}`;
+  switch (generator.type) {
+    case 'solid':
+    case 'countdown':
+    case 'credits':
+      return `color=c=${lavfiColor(generator.color)}:${frame}`;
+    case 'gradient': {
+      const { x0, y0, x1, y1 } = gradientPoints(generator.angle, width, height);
+      const [c0, c1] = generator.colors.map(lavfiColor);
+      return `gradients=${frame}:c0=${c0}:c1=${c1}:x0=${x0}:y0=${y0}:x1=${x1}:y1=${y1}:nb_colors=2:speed=0`;
+    }
+    case 'testPattern':
+      return `${TEST_PATTERNS[generator.pattern]}=${frame}`;
+    case 'tone':
+      return `sine=frequency=${generator.frequency}:sample_rate=48000:duration=${durationSec}`;
+    default:
+      throw new Error(`Unknown generator type: ${generator.type}`);
+  }
+}
+
+/**
+ * ffmpeg filters a generator needs (lavfi sources are filters too)
+ */
+function generatorFilters(generator) {
+  switch (generator.type) {
+    case 'solid':
+      return ['color'];
+    case 'gradient':
+      return ['gradients'];
+    case 'countdown':
+    case 'credits':
+      return ['color', 'ass'];
+    case 'testPattern':
+      return [TEST_PATTERNS[generator.pattern]];
+    case 'tone':
+      return ['sine', 'volume'];
+    default:
+      return [];
+  }
+}
+
+/**
+ * ASS script showing the whole seconds left, one event per number (5, 4, 3, 2, 1 for a 5s clip)
+ */
+function buildCountdownScript(generator, format, durationMs) {
+  const { width, height } = format;
+  const size = Math.round(height * 0.4);
+  const color = assColor(generator.textColor);
+  const styles = [
+    `Style: Count,${COUNTDOWN_FONT_FAMILY},${size},${color},${color},&H00000000,&H00000000,-1,0,0,0,100,100,0,0,1,0,0,5,0,0,0,1`,
+  ];
+  const events = [];
+  for (let n = Math.ceil(durationMs / 1000); n >= 1; n--) {
+    const start = Math.max(0, durationMs - n * 1000);
+    const end = durationMs - (n - 1) * 1000;
+    events.push(`Dialogue: 0,${assTime(start)},${assTime(end)},Count,,0,0,0,,{\\pos(${width / 2},${height / 2})}${n}`);
+  }
+  return assScript(width, height, styles, events);
+}
+
+/**
+ * ASS script rolling the credits up from below the frame
+ * Every line is its own event moving at the same speed, so the roll stays in step without a
+ * tall pre-rendered image; lines start stacked under the bottom edge.
+ */
+function buildCreditsScript(generator, format, durationMs) {
+  const { width, height } = format;
+  const scale = height / 1080;
+  const headingSize = Math.round(CREDITS_HEADING_SIZE * scale);
+  const lineSize = Math.round(CREDITS_LINE_SIZE * scale);
+  const color = assColor(generator.textColor);
+  const styles = [
+    `Style: Heading,${CREDITS_FONT_FAMILY},${headingSize},${color},${color},&H00000000,&H00000000,-1,0,0,0,100,100,0,0,1,0,0,8,0,0,0,1`,
+    `Style: Line,${CREDITS_FONT_FAMILY},${lineSize},${color},${color},&H00000000,&H00000000,0,0,0,0,100,100,0,0,1,0,0,8,0,0,0,1`,
+  ];
+
+  // Stack the lines top to bottom, with a blank line between blocks
+  const lines = [];
+  let y = 0;
+  generator.blocks.forEach((block, i) => {
+    if (i > 0) y += lineSize * CREDITS_LEADING;
+    if (block.heading) {
+      lines.push({ style: 'Heading', text: block.heading, y });
+      y += headingSize * CREDITS_LEADING;
+    }
+    for (const text of block.lines) {
+      lines.push({ style: 'Line', text, y });
+      y += lineSize * CREDITS_LEADING;
+    }
+  });
+
+  const distance = generator.speed === null ? height + y : generator.speed * scale * (durationMs / 1000);
+  const x = Math.round(width / 2);
+  const events = lines.map((line) => {
+    const fromY = Math.round(height + line.y);
+    const toY = Math.round(height + line.y - distance);
+    return `Dialogue: 0,${assTime(0)},${assTime(durationMs)},${line.style},,0,0,0,,{\\move(${x},${fromY},${x},${toY})}${escapeAssText(line.text)}`;
+  });
+  return assScript(width, height, styles, events);
+}
+
+/**
+ * Render a generator clip's source media into the cache (reused while the parameters match)
+ * Generated sources start at 0, so the file covers [0, outMs) and the clip's in point trims as usual.
+ * @param {Object} clip - Plan clip carrying `generator`
+ * @param {{ width: number, height: number, frameRate: string }} format - Frame size and rate
+ * @param {Object} encoding - Resolved encoding (see encoding.js)
+ * @param {Object} cache - CacheDirs
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ * @returns {Promise<string>} Path of the generated file
+ */
+async function renderGenerator(clip, format, encoding, cache, trackProcessFn) {
+  const { generator } = clip;
+  const audioOnly = isAudioGenerator(generator);
+  const durationMs = Math.max(1, Math.ceil(clip.outMs));
+  const key = hashValue({
+    generator,
+    durationMs,
+    format: audioOnly ? null : format,
+    codec: audioOnly ? { audioCodec: encoding.audioCodec, audioBitrate: encoding.audioBitrate } : encodingKey(encoding),
+  });
+  // Mezzanine video and PCM audio don't fit MP4/M4A; they go in MOV and WAV like the export's intermediates
+  const ext = audioOnly
+    ? (encoding.audioCodec.startsWith('pcm_') ? 'wav' : 'm4a')
+    : (requiresMov(encoding) ? 'mov' : 'mp4');
+  const outputPath = cache.generatorPath(key, ext);
+  if (await fs.pathExists(outputPath)) {
+    return outputPath;
+  }
+
+  await assertCapabilities({
+    filters: generatorFilters(generator),
+    encoders: [audioOnly ? encoding.audioCodec : encoding.videoCodec],
+    feature: 'Generator clips',
+  });
+
+  const durationSec = durationMs / 1000;
+  const partialPath = `${outputPath}.partial.${ext}`;
+  const assPath = cache.stagePath(`generator_${key}`, generator.type, 'ass');
+  const fontsDir = `${assPath}.fonts`;
+
+  try {
+    let videoFilters = [];
+    if (generator.type === 'countdown') {
+      await fs.writeFile(assPath, buildCountdownScript(generator, format, durationMs), 'utf8');
+      const fontCount = await collectFonts([{ family: COUNTDOWN_FONT_FAMILY, bold: true }], [], fontsDir);
+      videoFilters = [assFilter(assPath, fontCount > 0 ? fontsDir : null)];
+    } else if (generator.type === 'credits') {
+      await fs.writeFile(assPath, buildCreditsScript(generator, format, durationMs), 'utf8');
+      const fonts = [{ family: CREDITS_FONT_FAMILY, bold: true }, { family: CREDITS_FONT_FAMILY, bold: false }];
+      const texts = generator.blocks.flatMap((block) => [block.heading, ...block.lines]);
+      const fontCount = await collectFonts(fonts, texts, fontsDir);
+      videoFilters = [assFilter(assPath, fontCount > 0 ? fontsDir : null)];
+    }
+
+    await new Promise((resolve, reject) => {
+      const command = ffmpeg()
+        .input(generatorSource(generator, format, durationSec))
+        .inputFormat('lavfi');
+
+      if (audioOnly) {
+        command
+          .audioFilters([`volume=${generator.volumeDb}dB`])
+          .outputOptions(['-vn', `-c:a ${encoding.audioCodec}`, ...audioQualityOptions(encoding), '-ar 48000', '-ac 2']);
+      } else {
+        if (videoFilters.length > 0) {
+          command.videoFilters(videoFilters);
+        }
+        command.outputOptions(['-an', `-c:v ${encoding.videoCodec}`, ...videoQualityOptions(encoding), `-r ${format.frameRate}`]);
+      }
+
+      command
+        .outputOptions([`-t ${durationSec}`])
+        .output(partialPath)
+        .on('start', () => {
+          if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+        })
+        .on('end', () => resolve())
+        .on('error', (err) => reject(err));
+
+      command.run();
+    });
+    await fs.move(partialPath, outputPath, { overwrite: true });
+  } finally {
+    await fs.remove(partialPath);
+    await fs.remove(assPath);
+    await fs.remove(fontsDir);
+  }
+
+  return outputPath;
+}
+
+/**
+ * Give every generator clip in a plan a rendered source file
+ * @returns {Promise<Object>} Plan copy whose generator clips have `srcPath` set
+ */
+async function materializeGenerators(plan, format, encoding, cache, trackProcessFn) {
+  // One render at a time; identical generator clips then share the first one's file
+  const materialize = async (clips) => {
+    const result = [];
+    for (const clip of clips || []) {
+      result.push(clip.generator ? { ...clip, srcPath: await renderGenerator(clip, format, encoding, cache, trackProcessFn) } : clip);
+    }
+    return result;
+  };
+  return {
+    ...plan,
+    mainTrack: await materialize(plan.mainTrack),
+    overlayTrack: await materialize(plan.overlayTrack),
+    audioTrack: await materialize(plan.audioTrack),
+  };
+}
+
+module.exports = {
+  GENERATOR_TYPES,
+  PREVIEW_FORMAT,
+  normalizeGenerator,
+  isAudioGenerator,
+  generatorSource,
+  generatorFilters,
+  buildCountdownScript,
+  buildCreditsScript,
+  renderGenerator,
+  materializeGenerators,
+};
@@ -0,0 +1,212 @@
+const ffmpeg = require('fluent-ffmpeg');
+const fs = require('fs-extra');
+const { buildConformFilters, audioConforms } = require('./audio');
+const { videoQualityOptions, audioQualityOptions, encodingKey } = require('./encoding');
+const { hashValue } = require('./planHash');
+const { concatListEntry } = require('./paths');
+
+/**
+ * Clip handles - smart rendering of trimmed clips whose source already matches the output frame
+ * Only the head (in point to the first keyframe) and tail (last keyframe to the out point) are
+ * encoded; the middle is stream-copied between keyframes. Handles are cached by source, cut
+ * points and encoding, so they can be precomputed when a clip is trimmed and reused by every
+ * export after that. Pieces are MPEG-TS so each one carries its own parameter sets through concat.
+ */
+
+// Middle shorter than this isn't worth splicing; the clip is encoded whole instead
+const MIN_COPY_MS = 2000;
+
+// Encoder families the copied middle can be spliced with
+const CODEC_FAMILIES = {
+  h264: /^(libx264|h264_\w+)$/,
+  hevc: /^(libx265|hevc_\w+)$/,
+};
+
+// ffprobe profile names and the encoder profile that reproduces them, per codec
+const SOURCE_PROFILES = {
+  h264: {
+    'Constrained Baseline': 'baseline',
+    Baseline: 'baseline',
+    Main: 'main',
+    High: 'high',
+    'High 10': 'high10',
+    'High 4:2:2': 'high422',
+    'High 4:4:4 Predictive': 'high444',
+  },
+  hevc: { Main: 'main', 'Main 10': 'main10' },
+};
+
+// Profiles the hardware encoders accept; the rest need libx264/libx265
+const HARDWARE_PROFILES = ['baseline', 'main', 'high', 'main10'];
+
+/**
+ * Encoder profile the handles need so they splice with the copied middle, or null when there's none
+ */
+function handleProfile(meta, encoding) {
+  const profile = (SOURCE_PROFILES[meta.codec_video] || {})[meta.video_profile] || null;
+  if (!profile || (encoding.profile && encoding.profile !== profile)) return null;
+  if (!/^libx26[45]$/.test(encoding.videoCodec) && !HARDWARE_PROFILES.includes(profile)) return null;
+  return profile;
+}
+
+/**
+ * Whether a clip's source can be smart rendered with this encoding
+ * The encoder has to produce the source's codec, pixel format, bit depth and profile, and the audio has
+ * to already be in the conform format and codec.
+ */
+function canSmartRender(meta, encoding) {
+  if (!meta || !meta.codec_video || !audioConforms(meta)) return false;
+  if (meta.has_audio && meta.codec_audio !== encoding.audioCodec) return false;
+  if (meta.pix_fmt !== encoding.pixFmt) return false;
+  if (meta.bit_depth && meta.bit_depth !== (encoding.pixFmt.endsWith('10le') ? 10 : 8)) return false;
+  const family = CODEC_FAMILIES[meta.codec_video];
+  return !!family && family.test(encoding.videoCodec) && !!handleProfile(meta, encoding);
+}
+
+/**
+ * Split a trimmed range at keyframes: head [inMs, headEndMs), copied middle [headEndMs, tailStartMs), tail [tailStartMs, outMs)
+ * @param {number[]} keyframesMs - Sorted keyframe times of the source
+ * @returns {{ headEndMs: number, tailStartMs: number }|null} Null when the middle would be too short
+ */
+function planClipHandles(inMs, outMs, keyframesMs, minCopyMs = MIN_COPY_MS) {
+  const headEndMs = keyframesMs.find((t) => t >= inMs - 1);
+  let tailStartMs = null;
+  for (const t of keyframesMs) {
+    if (t > outMs + 1) break;
+    tailStartMs = t;
+  }
+  if (headEndMs === undefined || tailStartMs === null || tailStartMs - headEndMs < minCopyMs) {
+    return null;
+  }
+  // A cut landing on a keyframe needs no handle on that side
+  return {
+    headEndMs: Math.abs(headEndMs - inMs) <= 1 ? inMs : headEndMs,
+    tailStartMs: Math.abs(tailStartMs - outMs) <= 1 ? outMs : tailStartMs,
+  };
+}
+
+/**
+ * Cut part of a source to an MPEG-TS piece, encoded (handles) or stream-copied (the middle)
+ */
+function renderPiece(srcPath, outputPath, fromMs, toMs, copy, encoding, trackProcessFn) {
+  return new Promise((resolve, reject) => {
+    const command = ffmpeg(srcPath).seekInput(fromMs / 1000).duration((toMs - fromMs) / 1000);
+    if (copy) {
+      command.outputOptions(['-c copy']);
+    } else {
+      command
+        .audioFilters(buildConformFilters())
+        .videoCodec(encoding.videoCodec)
+        .audioCodec(encoding.audioCodec)
+        .outputOptions([...videoQualityOptions(encoding), ...audioQualityOptions(encoding)]);
+    }
+    command
+      .outputOptions(['-f mpegts'])
+      .output(outputPath)
+      .on('start', () => {
+        if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+      })
+      .on('end', () => resolve())
+      .on('error', (err) => reject(err))
+      .run();
+  });
+}
+
+/**
+ * Render (or reuse) one encoded handle
+ * @returns {Promise<{ path: string, cached: boolean }>}
+ */
+async function ensureHandle(srcPath, sourceStats, fromMs, toMs, encoding, cache, trackProcessFn) {
+  const key = hashValue({
+    source: { path: srcPath, size: sourceStats.size, mtimeMs: sourceStats.mtimeMs },
+    fromMs,
+    toMs,
+    encoding: encodingKey(encoding),
+  });
+  const handlePath = cache.handlePath(key);
+  if (await fs.pathExists(handlePath)) {
+    return { path: handlePath, cached: true };
+  }
+  const partialPath = `${handlePath}.partial.ts`;
+  try {
+    await renderPiece(srcPath, partialPath, fromMs, toMs, false, encoding, trackProcessFn);
+    await fs.move(partialPath, handlePath, { overwrite: true });
+  } finally {
+    await fs.remove(partialPath);
+  }
+  return { path: handlePath, cached: false };
+}
+
+/**
+ * Encode (or reuse) a trimmed clip's head and tail handles
+ * @param {string} srcPath - Source file
+ * @param {number} inMs - Source in point
+ * @param {number} outMs - Source out point
+ * @param {Object} meta - probeMedia result including keyframes_ms
+ * @param {Object} encoding - Resolved encoding (see encoding.js)
+ * @param {Object} cache - CacheDirs
+ * @returns {Promise<{ head: Object|null, tail: Object|null, headEndMs: number, tailStartMs: number }|null>}
+ *   Null when the clip can't be smart rendered
+ */
+async function ensureClipHandles(srcPath, inMs, outMs, meta, encoding, cache, trackProcessFn) {
+  if (!canSmartRender(meta, encoding) || !Array.isArray(meta.keyframes_ms)) {
+    return null;
+  }
+  const split = planClipHandles(inMs, outMs, meta.keyframes_ms);
+  if (!split) {
+    return null;
+  }
+  // Handles are encoded in the source's profile even when the export leaves it to the encoder
+  const handleEncoding = { ...encoding, profile: handleProfile(meta, encoding) };
+  const sourceStats = await fs.stat(srcPath);
+  const head = split.headEndMs > inMs
+    ? await ensureHandle(srcPath, sourceStats, inMs, split.headEndMs, handleEncoding, cache, trackProcessFn)
+    : null;
+  const tail = outMs > split.tailStartMs
+    ? await ensureHandle(srcPath, sourceStats, split.tailStartMs, outMs, handleEncoding, cache, trackProcessFn)
+    : null;
+  return { head, tail, ...split };
+}
+
+/**
+ * Render a trimmed clip as encoded handles around a stream-copied middle
+ * @returns {Promise<boolean>} False when the clip can't be smart rendered (nothing was written)
+ */
+async function smartRenderClip(srcPath, inMs, outMs, meta, outputPath, encoding, cache, trackProcessFn) {
+  const handles = await ensureClipHandles(srcPath, inMs, outMs, meta, encoding, cache, trackProcessFn);
+  if (!handles) {
+    return false;
+  }
+  const middlePath = `${outputPath}.middle.ts`;
+  const listPath = `${outputPath}.pieces.txt`;
+  try {
+    await renderPiece(srcPath, middlePath, handles.headEndMs, handles.tailStartMs, true, encoding, trackProcessFn);
+    const pieces = [handles.head && handles.head.path, middlePath, handles.tail && handles.tail.path].filter(Boolean);
+    await fs.writeFile(listPath, pieces.map(concatListEntry).join('\n'), 'utf8');
+    await new Promise((resolve, reject) => {
+      const command = ffmpeg()
+        .input(listPath)
+        .inputOptions(['-f concat', '-safe 0'])
+        .outputOptions(['-c copy', '-bsf:a aac_adtstoasc'])
+        .output(outputPath)
+        .on('start', () => {
+          if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+        })
+        .on('end', () => resolve())
+        .on('error', (err) => reject(err));
+      command.run();
+    });
+  } finally {
+    await fs.remove(middlePath);
+    await fs.remove(listPath);
+  }
+  return true;
+}
+
+module.exports = {
+  MIN_COPY_MS,
+  canSmartRender,
+  planClipHandles,
+  ensureClipHandles,
+  smartRenderClip,
+};
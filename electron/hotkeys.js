@@ -0,0 +1,205 @@
+const fs = require('fs');
+const path = require('path');
+const { ErrorCode, StarscapeError } = require('./errors');
+
+/**
+ * Global hotkeys - system-wide shortcuts that drive recording while the app is unfocused
+ * Bindings are persisted next to the path grants; an action bound to null is disabled.
+ * Registration goes through an injected globalShortcut so the module stays testable.
+ */
+
+const HOTKEY_ACTIONS = ['start-recording', 'stop-recording', 'pause-recording', 'capture-screenshot'];
+
+const DEFAULT_HOTKEYS = {
+  'start-recording': 'CommandOrControl+Shift+F9',
+  'stop-recording': 'CommandOrControl+Shift+F10',
+  'pause-recording': 'CommandOrControl+Shift+F11',
+  'capture-screenshot': 'CommandOrControl+Shift+F12',
+};
+
+// Modifier spellings Electron accepts, mapped to one canonical name each
+const MODIFIERS = {
+  commandorcontrol: 'CommandOrControl',
+  cmdorctrl: 'CommandOrControl',
+  command: 'Command',
+  cmd: 'Command',
+  control: 'Control',
+  ctrl: 'Control',
+  alt: 'Alt',
+  option: 'Alt',
+  altgr: 'AltGr',
+  shift: 'Shift',
+  super: 'Super',
+  meta: 'Super',
+};
+
+const NAMED_KEYS = [
+  'Plus', 'Space', 'Tab', 'Capslock', 'Numlock', 'Scrolllock', 'Backspace', 'Delete', 'Insert',
+  'Return', 'Enter', 'Up', 'Down', 'Left', 'Right', 'Home', 'End', 'PageUp', 'PageDown', 'Escape',
+  'Esc', 'PrintScreen', 'num0', 'num1', 'num2', 'num3', 'num4', 'num5', 'num6', 'num7', 'num8',
+  'num9', 'numdec', 'numadd', 'numsub', 'nummult', 'numdiv',
+];
+
+let configPath = null;
+let shortcuts = null;
+let handlers = {};
+let bindings = { ...DEFAULT_HOTKEYS };
+let registered = new Set();
+
+function normalizeKey(key) {
+  if (/^[a-z0-9]$/i.test(key)) return key.toUpperCase();
+  if (/^f([1-9]|1[0-9]|2[0-4])$/i.test(key)) return key.toUpperCase();
+  if (/^[`~!@#$%^&*()\-_=[\]{}\\|;:'",.<>/?]$/.test(key)) return key;
+  return NAMED_KEYS.find((name) => name.toLowerCase() === key.toLowerCase()) || null;
+}
+
+/**
+ * Canonical form of an Electron accelerator ("ctrl+shift+r" -> "Control+Shift+R")
+ * Needs at least one modifier unless the key is a function key, so a plain letter can't be grabbed system-wide.
+ * @returns {string|null} Null when the accelerator is invalid
+ */
+function normalizeAccelerator(accelerator) {
+  if (typeof accelerator !== 'string') return null;
+  // A trailing "+" key is spelled Plus so the string splits cleanly
+  const parts = accelerator.trim().replace(/\+\s*\+$/, '+Plus').split('+').map((part) => part.trim());
+  const key = normalizeKey(parts.pop() || '');
+  if (!key) return null;
+  const modifiers = [];
+  for (const part of parts) {
+    const modifier = MODIFIERS[part.toLowerCase()];
+    if (!modifier || modifiers.includes(modifier)) return null;
+    modifiers.push(modifier);
+  }
+  if (modifiers.length === 0 && !/^F\d+$/.test(key)) return null;
+  return [...modifiers, key].join('+');
+}
+
+/**
+ * Validate a partial bindings update and merge it over the current bindings
+ * @param {Object} current - action -> accelerator|null
+ * @param {Object} update - action -> accelerator|null
+ */
+function mergeBindings(current, update) {
+  const merged = { ...current };
+  for (const [action, accelerator] of Object.entries(update || {})) {
+    if (!HOTKEY_ACTIONS.includes(action)) {
+      throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Unknown hotkey action: ${action}`, { action });
+    }
+    if (accelerator === null || accelerator === '') {
+      merged[action] = null;
+      continue;
+    }
+    const normalized = normalizeAccelerator(accelerator);
+    if (!normalized) {
+      throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Invalid hotkey for ${action}: ${accelerator}`, { action, accelerator });
+    }
+    merged[action] = normalized;
+  }
+  const seen = new Map();
+  for (const action of HOTKEY_ACTIONS) {
+    const accelerator = merged[action];
+    if (!accelerator) continue;
+    if (seen.has(accelerator)) {
+      throw new StarscapeError(
+        ErrorCode.INVALID_ARGUMENT,
+        `${accelerator} is bound to both ${seen.get(accelerator)} and ${action}`,
+        { accelerator, actions: [seen.get(accelerator), action] }
+      );
+    }
+    seen.set(accelerator, action);
+  }
+  return merged;
+}
+
+function saveBindings() {
+  if (!configPath) return;
+  try {
+    fs.mkdirSync(path.dirname(configPath), { recursive: true });
+    fs.writeFileSync(configPath, JSON.stringify(bindings, null, 2));
+  } catch (e) {
+    console.error('Failed to save hotkeys:', e.message);
+  }
+}
+
+function unregisterAll() {
+  if (shortcuts) {
+    for (const accelerator of registered) {
+      shortcuts.unregister(accelerator);
+    }
+  }
+  registered = new Set();
+}
+
+/**
+ * (Re)register every bound action; an accelerator another app already holds is left unregistered
+ */
+function registerAll() {
+  unregisterAll();
+  if (!shortcuts) return;
+  for (const action of HOTKEY_ACTIONS) {
+    const accelerator = bindings[action];
+    if (!accelerator) continue;
+    let ok = false;
+    try {
+      ok = shortcuts.register(accelerator, () => {
+        if (handlers[action]) handlers[action](action);
+      });
+    } catch (e) {
+      ok = false;
+    }
+    if (ok) registered.add(accelerator);
+  }
+}
+
+/**
+ * Current bindings and whether each one is live
+ * @returns {Array<{ action: string, accelerator: string|null, registered: boolean }>}
+ */
+function getHotkeys() {
+  return HOTKEY_ACTIONS.map((action) => ({
+    action,
+    accelerator: bindings[action] || null,
+    registered: !!bindings[action] && registered.has(bindings[action]),
+  }));
+}
+
+/**
+ * Change some bindings, re-register and persist them
+ * @param {Object} update - action -> accelerator (null disables the action)
+ */
+function setHotkeys(update) {
+  bindings = mergeBindings(bindings, update);
+  registerAll();
+  saveBindings();
+  return getHotkeys();
+}
+
+/**
+ * Load persisted bindings and register them
+ * @param {string} filePath - Where bindings are persisted
+ * @param {Object} globalShortcut - Electron's globalShortcut (register/unregister)
+ * @param {Object} actionHandlers - action -> () => void
+ */
+function init(filePath, globalShortcut, actionHandlers) {
+  configPath = filePath;
+  shortcuts = globalShortcut;
+  handlers = actionHandlers || {};
+  try {
+    const stored = JSON.parse(fs.readFileSync(filePath, 'utf8'));
+    bindings = mergeBindings(DEFAULT_HOTKEYS, stored);
+  } catch (e) {
+    bindings = { ...DEFAULT_HOTKEYS };
+  }
+  registerAll();
+}
+
+module.exports = {
+  HOTKEY_ACTIONS,
+  DEFAULT_HOTKEYS,
+  normalizeAccelerator,
+  mergeBindings,
+  init,
+  getHotkeys,
+  setHotkeys,
+  unregisterAll,
+};
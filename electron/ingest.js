@@ -2,7 +2,12 @@ const fs = require('fs-extra');
 const path = require('path');
 const { probeMedia } = require('./metadata');
 const ffmpeg = require('fluent-ffmpeg');
-const { configureFfmpeg } = require('./ffmpeg');
+const { configureFfmpeg, resolveBinary } = require('./ffmpeg');
+const logger = require('./logger');
+const { urlToPath, toLongPath, toLibraryUrl } = require('./paths');
+const { ErrorCode, StarscapeError } = require('./errors');
+const { withDecodeHwaccel } = require('./capabilities');
+const { classifyPath, listMounts } = require('./volumes');
 
 // Configure FFmpeg paths
 configureFfmpeg();
@@ -17,11 +22,12 @@ function generateAssetId() {
 }
 
 /**
- * Generate thumbnail for video file
+ * Generate thumbnail for video file (hardware decode when available)
  */
 async function generateVideoThumbnail(videoPath, outputPath) {
-  return new Promise((resolve, reject) => {
+  return withDecodeHwaccel((hwaccel) => new Promise((resolve, reject) => {
     ffmpeg(videoPath)
+      .inputOptions(hwaccel ? [`-hwaccel ${hwaccel}`] : [])
       .screenshots({
         timestamps: ['00:00:01'],
         filename: path.basename(outputPath),
@@ -30,7 +36,7 @@ async function generateVideoThumbnail(videoPath, outputPath) {
       })
       .on('end', () => resolve(outputPath))
       .on('error', (err) => reject(err));
-  });
+  }));
 }
 
 /**
@@ -74,24 +80,39 @@ function getAssetType(filePath, metadata = null) {
   return 'unknown';
 }
 
+const INGEST_MODES = ['copy', 'link'];
+
 /**
  * Ingest files from external paths into cache directory
+ * Link mode references sources where they are; files on a network share or removable drive are
+ * still copied (unless copyExternal is false) so the project survives the drive going away.
+ * @param {string[]} filePaths - Source paths or file:// URLs
+ * @param {CacheDirs} cache - Cache directories
+ * @param {Function} onProgress - Optional callback({ current, total, file_name, asset_id, status, error })
+ * @param {Object} options - { mode: 'copy'|'link', copyExternal: boolean, mounts } (mounts from listMounts)
  */
-async function ingestFiles(filePaths, cache) {
+async function ingestFiles(filePaths, cache, onProgress = null, options = {}) {
+  const mode = INGEST_MODES.includes(options.mode) ? options.mode : 'copy';
+  const mounts = options.mounts || await listMounts();
   const results = [];
+  const report = (fields) => {
+    if (onProgress) onProgress({ current: results.length, total: filePaths.length, ...fields });
+  };
 
-  for (const filePath of filePaths) {
+  for (const requestedPath of filePaths) {
+    const filePath = toLongPath(urlToPath(requestedPath));
+    let span = null;
     try {
       // Validate file exists
       const exists = await fs.pathExists(filePath);
       if (!exists) {
-        throw new Error(`File does not exist: ${filePath}`);
+        throw new StarscapeError(ErrorCode.FILE_NOT_FOUND, `File does not exist: ${filePath}`, { path: filePath });
       }
 
       // Check if it's a file
       const stats = await fs.stat(filePath);
       if (!stats.isFile()) {
-        throw new Error(`Path is not a file: ${filePath}`);
+        throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Path is not a file: ${filePath}`, { path: filePath });
       }
 
       // Extract original file name
@@ -100,25 +121,29 @@ async function ingestFiles(filePaths, cache) {
       // Generate unique asset ID
       const assetId = generateAssetId();
       const ext = path.extname(filePath).substring(1); // Remove leading dot
-      const cachedFilename = `${assetId}.${ext}`;
-      const cachedPath = path.join(cache.mediaDir, cachedFilename);
+      const volume = classifyPath(filePath, mounts);
+      const external = volume.kind !== 'local';
+      const copied = mode === 'copy' || (external && options.copyExternal !== false);
+      const cachedPath = copied ? path.join(cache.mediaDir, `${assetId}.${ext}`) : filePath;
 
-      console.log(`Ingesting file: ${filePath}`);
-      console.log(`Original name: ${originalFileName}`);
-      console.log(`Target cache path: ${cachedPath}`);
+      span = logger.span('ingest.file', { asset_id: assetId, source: filePath, volume: volume.kind });
 
       // Ensure cache directory exists
       await fs.ensureDir(cache.mediaDir);
       await fs.ensureDir(cache.thumbDir);
 
-      // Copy file to cache directory
-      await fs.copy(filePath, cachedPath);
-      console.log(`File copied successfully to: ${cachedPath}`);
+      if (copied) {
+        // Copy file to cache directory
+        await fs.copy(filePath, cachedPath);
+        span.event(mode === 'link' ? 'Copied from external volume' : 'Copied to cache', { cached_path: cachedPath, mount_point: volume.mount_point });
 
-      // Verify the copied file exists
-      const copiedExists = await fs.pathExists(cachedPath);
-      if (!copiedExists) {
-        throw new Error(`Failed to copy file to cache: ${cachedPath}`);
+        // Verify the copied file exists
+        const copiedExists = await fs.pathExists(cachedPath);
+        if (!copiedExists) {
+          throw new StarscapeError(ErrorCode.CACHE_ERROR, `Failed to copy file to cache: ${cachedPath}`, { path: cachedPath });
+        }
+      } else if (external) {
+        logger.warn('Linked media on an external volume', { asset_id: assetId, source: filePath, volume: volume.kind });
       }
 
       // Get file size
@@ -126,7 +151,8 @@ async function ingestFiles(filePaths, cache) {
       const fileSize = fileStats.size;
 
       // Extract metadata
-      const metadata = await probeMedia(cachedPath);
+      // Stored so reopening the project reads it back instead of probing again
+      const metadata = await probeMedia(cachedPath, { cache });
 
       // For images, set default duration to 5 seconds (5000ms)
       // This can be adjusted in the UI from 250ms to 60 seconds
@@ -135,36 +161,50 @@ async function ingestFiles(filePaths, cache) {
         metadata.duration_ms = 5000; // Default 5 seconds for images
       }
 
-      // Generate thumbnail
+      // Generate thumbnail (skipped when only ffprobe is installed)
       let thumbnailPath = null;
       
       try {
-        if (assetType === 'video') {
+        if (!resolveBinary('ffmpeg').path) {
+          logger.warn('Skipping thumbnail: ffmpeg not found', { asset_id: assetId });
+        } else if (assetType === 'video') {
           const thumbnailFilename = `${assetId}.jpg`;
           thumbnailPath = path.join(cache.thumbDir, thumbnailFilename);
           await generateVideoThumbnail(cachedPath, thumbnailPath);
-          console.log(`Thumbnail generated: ${thumbnailPath}`);
         } else if (assetType === 'image') {
           const thumbnailFilename = `${assetId}.jpg`;
           thumbnailPath = path.join(cache.thumbDir, thumbnailFilename);
           await generateImageThumbnail(cachedPath, thumbnailPath);
-          console.log(`Thumbnail generated: ${thumbnailPath}`);
         }
       } catch (thumbError) {
-        console.warn(`Failed to generate thumbnail for ${originalFileName}:`, thumbError);
+        logger.warn('Thumbnail generation failed', { asset_id: assetId, error: String(thumbError) });
         // Continue without thumbnail
       }
 
+      span.end({ asset_type: assetType, size_bytes: fileSize, duration_ms: metadata.duration_ms });
       results.push({
         asset_id: assetId,
         file_path: cachedPath,
+        // What projects should store: relative to the cache, so they survive it moving (null when linked)
+        library_url: toLibraryUrl(cachedPath),
+        copied,
+        // Where the source came from; network and removable sources may not be there next time
+        source_volume: volume.kind,
+        source_mount_point: volume.mount_point,
         original_file_name: originalFileName,
         thumbnail_path: thumbnailPath,
+        thumbnail_library_url: thumbnailPath ? toLibraryUrl(thumbnailPath) : null,
         file_size: fileSize,
         metadata,
       });
+      report({ file_name: originalFileName, asset_id: assetId, status: 'completed' });
     } catch (error) {
-      console.error(`Error ingesting file ${filePath}:`, error);
+      if (span) {
+        span.fail(error);
+      } else {
+        logger.error('Ingest failed', { source: filePath, error });
+      }
+      report({ file_name: path.basename(filePath), status: 'failed', error: error.message || String(error) });
       throw error;
     }
   }
@@ -173,6 +213,8 @@ async function ingestFiles(filePaths, cache) {
 }
 
 module.exports = {
+  INGEST_MODES,
+  getAssetType,
   ingestFiles,
 };
 
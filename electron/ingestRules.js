@@ -0,0 +1,241 @@
+const ffmpeg = require('fluent-ffmpeg');
+const fs = require('fs');
+const path = require('path');
+const { ErrorCode, StarscapeError } = require('./errors');
+const { toneMapFilters } = require('./filterUtils');
+
+/**
+ * Ingest transcode rules - conversions applied to newly ingested media so editing doesn't fight it
+ * video_codec re-encodes sources in a hard-to-decode codec (HEVC to H.264), max_resolution
+ * scales anything larger down to fit, and audio_sample_rate resamples audio files (e.g. WAV to
+ * 48 kHz). Every matching rule goes into one ffmpeg pass; the asset is usable straight away and
+ * the converted file replaces it when the background job finishes.
+ */
+
+const INGEST_RULE_TYPES = ['video_codec', 'max_resolution', 'audio_sample_rate'];
+
+const TARGET_VIDEO_ENCODERS = {
+  h264: 'libx264',
+  hevc: 'libx265',
+};
+
+let storePath = null;
+let rules = [];
+
+function positiveInt(value) {
+  return Number.isInteger(value) && value > 0 ? value : null;
+}
+
+/**
+ * Check one rule and keep only its known fields
+ */
+function normalizeIngestRule(rule = {}) {
+  const invalid = (message) => new StarscapeError(ErrorCode.INVALID_ARGUMENT, message, { rule });
+  switch (rule.type) {
+    case 'video_codec': {
+      const from = (Array.isArray(rule.from) ? rule.from : [rule.from]).filter((codec) => typeof codec === 'string' && codec);
+      if (from.length === 0) throw invalid('video_codec rule needs source codecs');
+      if (!TARGET_VIDEO_ENCODERS[rule.to]) throw invalid(`Unsupported target codec: ${rule.to}`);
+      return { type: 'video_codec', from: from.map((codec) => codec.toLowerCase()), to: rule.to };
+    }
+    case 'max_resolution': {
+      const width = positiveInt(rule.width);
+      const height = positiveInt(rule.height);
+      if (!width || !height) throw invalid('max_resolution rule needs a width and height');
+      return { type: 'max_resolution', width, height };
+    }
+    case 'audio_sample_rate': {
+      const rate = positiveInt(rule.rate);
+      if (!rate || rate < 8000 || rate > 192000) throw invalid('audio_sample_rate rule needs a rate between 8000 and 192000');
+      const extensions = (Array.isArray(rule.extensions) ? rule.extensions : [])
+        .filter((ext) => typeof ext === 'string' && ext)
+        .map((ext) => ext.replace(/^\./, '').toLowerCase());
+      return { type: 'audio_sample_rate', rate, extensions };
+    }
+    default:
+      throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Unsupported ingest rule: ${rule.type}`, {
+        type: rule.type,
+        supported: INGEST_RULE_TYPES,
+      });
+  }
+}
+
+function saveStore() {
+  if (!storePath) return;
+  try {
+    fs.mkdirSync(path.dirname(storePath), { recursive: true });
+    fs.writeFileSync(storePath, JSON.stringify({ rules }, null, 2));
+  } catch (e) {
+    console.error('Failed to save ingest rules:', e.message);
+  }
+}
+
+/**
+ * Rules applied to every ingest, in order
+ */
+function getIngestRules() {
+  return rules.slice();
+}
+
+/**
+ * Replace the rules (an empty list turns ingest transcoding off)
+ */
+function setIngestRules(nextRules = []) {
+  rules = (Array.isArray(nextRules) ? nextRules : []).map(normalizeIngestRule);
+  saveStore();
+  return getIngestRules();
+}
+
+/**
+ * Conversion an ingested file needs under the rules (null when it can be used as-is)
+ * @param {string} kind - Asset type from ingest (video, audio, image)
+ * @param {string} filePath - Ingested file
+ * @param {Object} metadata - probeMedia result
+ * @param {Array} ruleList - Normalized rules
+ * @returns {{ videoCodec: string|null, width: number|null, height: number|null, sampleRate: number|null,
+ *   ext: string, reasons: string[] }|null} videoCodec null copies the video stream
+ */
+function planIngestTranscode(kind, filePath, metadata, ruleList = rules) {
+  const ext = path.extname(filePath).slice(1).toLowerCase();
+  const transcode = { videoCodec: null, width: null, height: null, sampleRate: null, ext, reasons: [] };
+
+  for (const rule of ruleList) {
+    if (rule.type === 'video_codec' && kind === 'video') {
+      const codec = (metadata.codec_video || '').toLowerCase();
+      if (rule.from.includes(codec)) {
+        transcode.videoCodec = TARGET_VIDEO_ENCODERS[rule.to];
+        transcode.reasons.push(`${codec} to ${rule.to}`);
+      }
+    } else if (rule.type === 'max_resolution' && kind === 'video') {
+      const scale = Math.min(1, rule.width / (metadata.width || 1), rule.height / (metadata.height || 1));
+      if (metadata.width && metadata.height && scale < 1) {
+        // Even dimensions, as 4:2:0 encoders require
+        transcode.width = Math.max(2, Math.floor((metadata.width * scale) / 2) * 2);
+        transcode.height = Math.max(2, Math.floor((metadata.height * scale) / 2) * 2);
+        transcode.reasons.push(`${metadata.width}x${metadata.height} to ${transcode.width}x${transcode.height}`);
+      }
+    } else if (rule.type === 'audio_sample_rate' && kind === 'audio') {
+      const matches = rule.extensions.length === 0 || rule.extensions.includes(ext);
+      if (matches && metadata.sample_rate && metadata.sample_rate !== rule.rate) {
+        transcode.sampleRate = rule.rate;
+        transcode.reasons.push(`${metadata.sample_rate} Hz to ${rule.rate} Hz`);
+      }
+    }
+  }
+
+  if (transcode.reasons.length === 0) {
+    return null;
+  }
+  // A scaled stream has to be re-encoded even when its codec was fine
+  if (transcode.width && !transcode.videoCodec) {
+    transcode.videoCodec = TARGET_VIDEO_ENCODERS.h264;
+  }
+  if (transcode.videoCodec) {
+    transcode.ext = 'mp4';
+  }
+  return transcode;
+}
+
+/**
+ * ffmpeg output options for a planned conversion
+ * Beyond the rules' fields, the phone profile sets toneMap (source HDR transfer), audioStream (the one
+ * audio stream to keep, null for none) and downmix; a plan with only audio changes copies the video.
+ */
+function ingestTranscodeOptions(transcode, metadata) {
+  const pickAudio = transcode.audioStream !== undefined;
+  if (transcode.videoCodec || (pickAudio && metadata.codec_video)) {
+    const options = ['-map', '0:v:0'];
+    if (!pickAudio) {
+      options.push('-map', '0:a?');
+    } else if (transcode.audioStream !== null) {
+      options.push('-map', `0:a:${transcode.audioStream}`);
+    }
+    if (transcode.videoCodec) {
+      options.push('-c:v', transcode.videoCodec, '-preset', 'medium', '-crf', '18', '-pix_fmt', 'yuv420p');
+      const filters = [
+        ...(transcode.toneMap ? toneMapFilters(transcode.toneMap) : []),
+        ...(transcode.width ? [`scale=${transcode.width}:${transcode.height}`] : []),
+      ];
+      if (filters.length > 0) {
+        options.push('-vf', filters.join(','));
+      }
+      if (transcode.toneMap) {
+        options.push('-color_primaries', 'bt709', '-color_trc', 'bt709', '-colorspace', 'bt709');
+      }
+      if (transcode.videoCodec === 'libx265') {
+        options.push('-tag:v', 'hvc1');
+      }
+    } else {
+      options.push('-c:v', 'copy');
+    }
+    if (transcode.audioStream === null) {
+      options.push('-an');
+    } else {
+      options.push('-c:a', 'aac', '-b:a', '320k');
+      if (transcode.downmix) {
+        options.push('-ac', '2');
+      }
+    }
+    options.push('-movflags', '+faststart');
+    if (transcode.sampleRate) {
+      options.push('-ar', String(transcode.sampleRate));
+    }
+    return options;
+  }
+  // Audio-only: PCM keeps its sample format, compressed audio is re-encoded with the container's default codec
+  const codec = (metadata.codec_audio || '').startsWith('pcm_') ? metadata.codec_audio : null;
+  return ['-map', '0:a:0', ...(codec ? ['-c:a', codec] : []), '-ar', String(transcode.sampleRate)];
+}
+
+/**
+ * Run a planned conversion
+ * @param {Function} onProgress - (fraction 0-1) as ffmpeg reports its position
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ */
+function runIngestTranscode(inputPath, outputPath, transcode, metadata, onProgress, trackProcessFn) {
+  const durationSec = (metadata.duration_ms || 0) / 1000;
+  return new Promise((resolve, reject) => {
+    const command = ffmpeg()
+      .input(inputPath)
+      .outputOptions(ingestTranscodeOptions(transcode, metadata))
+      .output(outputPath)
+      .on('start', () => {
+        if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+      })
+      .on('progress', (progress) => {
+        const match = /^(\d+):(\d+):([\d.]+)$/.exec(progress.timemark || '');
+        if (match && durationSec > 0 && onProgress) {
+          const seconds = Number(match[1]) * 3600 + Number(match[2]) * 60 + Number(match[3]);
+          onProgress(Math.min(1, seconds / durationSec));
+        }
+      })
+      .on('end', () => resolve())
+      .on('error', (err) => reject(err));
+
+    command.run();
+  });
+}
+
+/**
+ * Load rules from the settings store
+ */
+function init(filePath) {
+  storePath = filePath;
+  try {
+    const stored = JSON.parse(fs.readFileSync(filePath, 'utf8'));
+    rules = Array.isArray(stored.rules) ? stored.rules : [];
+  } catch (e) {
+    rules = [];
+  }
+}
+
+module.exports = {
+  INGEST_RULE_TYPES,
+  normalizeIngestRule,
+  init,
+  getIngestRules,
+  setIngestRules,
+  planIngestTranscode,
+  ingestTranscodeOptions,
+  runIngestTranscode,
+};
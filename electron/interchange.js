@@ -0,0 +1,232 @@
+const path = require('path');
+const { pathToFileURL } = require('url');
+const { toRational, msToFrame } = require('./timebase');
+const { framesToTimecode, dropFramesPerMinute } = require('./timecode');
+
+/**
+ * Timeline interchange - the edit as a CMX 3600 EDL or an OpenTimelineIO document, markers included
+ * Both are built from the edit plan. An EDL has a single picture track, so it carries the main track
+ * with markers as Avid LOC comments; OTIO carries the video and audio tracks with the markers on the
+ * timeline's stack. Loops are written out pass by pass since neither format can repeat a source range.
+ */
+
+// OTIO marker colors with the RGB a project's hex color is matched against
+const MARKER_COLORS = {
+  PINK: [255, 105, 180],
+  RED: [255, 0, 0],
+  ORANGE: [255, 165, 0],
+  YELLOW: [255, 255, 0],
+  GREEN: [0, 255, 0],
+  CYAN: [0, 255, 255],
+  BLUE: [0, 0, 255],
+  PURPLE: [128, 0, 128],
+  MAGENTA: [255, 0, 255],
+  BLACK: [0, 0, 0],
+  WHITE: [255, 255, 255],
+};
+
+// Colors Avid LOC lines accept
+const LOC_COLORS = ['WHITE', 'RED', 'GREEN', 'BLUE', 'CYAN', 'MAGENTA', 'YELLOW', 'BLACK'];
+
+const TIMELINE_FORMATS = ['edl', 'otio'];
+
+/**
+ * Nearest named color for a marker color ("#f5a524" or a name), from the given names
+ */
+function markerColorName(color, names = Object.keys(MARKER_COLORS)) {
+  const value = String(color || '').trim();
+  if (names.includes(value.toUpperCase())) {
+    return value.toUpperCase();
+  }
+  const hex = /^#?([0-9a-f]{6})$/i.exec(value);
+  if (!hex) {
+    return 'RED';
+  }
+  const rgb = [0, 2, 4].map((i) => parseInt(hex[1].slice(i, i + 2), 16));
+  let best = names[0];
+  let bestDistance = Infinity;
+  for (const name of names) {
+    const distance = MARKER_COLORS[name].reduce((sum, channel, i) => sum + (channel - rgb[i]) ** 2, 0);
+    if (distance < bestDistance) {
+      best = name;
+      bestDistance = distance;
+    }
+  }
+  return best;
+}
+
+/**
+ * A looping clip as one plain clip per pass of its source range
+ */
+function loopPasses(clip) {
+  if (!clip.loop || !(clip.outMs > clip.inMs)) {
+    return [clip];
+  }
+  const loopMs = clip.outMs - clip.inMs;
+  const passes = [];
+  for (let startMs = clip.startMs; startMs < clip.endMs; startMs += loopMs) {
+    const endMs = Math.min(clip.endMs, startMs + loopMs);
+    passes.push({ ...clip, loop: false, outMs: clip.inMs + (endMs - startMs), startMs, endMs });
+  }
+  return passes;
+}
+
+function clipName(clip) {
+  if (clip.assetName) return clip.assetName;
+  if (clip.srcPath) return path.basename(clip.srcPath);
+  return clip.generator ? clip.generator.type : clip.clipId;
+}
+
+/**
+ * Build a CMX 3600 EDL of the main track
+ * Record times are timeline positions; source times are positions in each file from 00:00:00:00.
+ * Retimed clips and freeze frames get M2 motion lines.
+ * @param {Object} plan - Edit plan
+ * @param {Object} options - { fps, dropFrame } (fps defaults to the plan's rate, then 30)
+ * @returns {string} EDL text
+ */
+function buildEdl(plan, options = {}) {
+  const rate = toRational(options.fps || plan.frameRate || 30) || { num: 30, den: 1 };
+  const fps = rate.num / rate.den;
+  const dropFrame = dropFramesPerMinute(rate) > 0 && options.dropFrame !== false;
+  const tc = (ms) => framesToTimecode(msToFrame(ms, rate), rate, { dropFrame });
+
+  const lines = [
+    `TITLE: ${plan.name || plan.id}`,
+    `FCM: ${dropFrame ? 'DROP FRAME' : 'NON-DROP FRAME'}`,
+    '',
+  ];
+  const clips = plan.mainTrack.flatMap(loopPasses);
+  const markers = plan.markers || [];
+  let markerIndex = 0;
+
+  clips.forEach((clip, i) => {
+    const event = String(i + 1).padStart(3, '0');
+    // Generated media (solids, bars, titles) has no tape; BL is the conventional black reel
+    const reel = (clip.srcPath ? 'AX' : 'BL').padEnd(8);
+    const srcOutMs = clip.speed ? clip.outMs : clip.inMs + (clip.endMs - clip.startMs);
+    lines.push(`${event}  ${reel} V     C        ${tc(clip.inMs)} ${tc(srcOutMs)} ${tc(clip.startMs)} ${tc(clip.endMs)}`);
+    if (clip.speed || clip.freezeFrame) {
+      const motionFps = clip.freezeFrame ? 0 : fps * clip.speed;
+      lines.push(`M2   ${reel} ${motionFps.toFixed(1).padStart(5, '0')}                ${tc(clip.inMs)}`);
+    }
+    lines.push(`* FROM CLIP NAME: ${clipName(clip)}`);
+    if (clip.srcPath) {
+      lines.push(`* SOURCE FILE: ${clip.srcPath}`);
+    }
+
+    // Markers go under the event they fall in (or the last one before them); LOC lines are points
+    const next = clips[i + 1];
+    while (markerIndex < markers.length && (!next || markers[markerIndex].timeMs < next.startMs)) {
+      const marker = markers[markerIndex++];
+      lines.push(`* LOC: ${tc(marker.timeMs)} ${markerColorName(marker.color, LOC_COLORS).padEnd(7)} ${marker.name}`);
+    }
+    lines.push('');
+  });
+
+  return `${lines.join('\n')}\n`;
+}
+
+/**
+ * Build an OpenTimelineIO document of the main, overlay and audio tracks
+ * Range markers keep their duration. Retimed clips carry a LinearTimeWarp and freeze frames a FreezeFrame.
+ * @param {Object} plan - Edit plan
+ * @param {Object} options - { fps } (defaults to the plan's rate, then 30)
+ * @returns {string} .otio JSON
+ */
+function buildOtio(plan, options = {}) {
+  const rate = toRational(options.fps || plan.frameRate || 30) || { num: 30, den: 1 };
+  const fps = rate.num / rate.den;
+  const time = (ms) => ({ OTIO_SCHEMA: 'RationalTime.1', rate: fps, value: msToFrame(ms, rate) });
+  const range = (startMs, endMs) => ({
+    OTIO_SCHEMA: 'TimeRange.1',
+    start_time: time(startMs),
+    duration: { ...time(0), value: msToFrame(endMs, rate) - msToFrame(startMs, rate) },
+  });
+
+  const mediaReference = (clip) => (clip.srcPath
+    ? { OTIO_SCHEMA: 'ExternalReference.1', target_url: pathToFileURL(clip.srcPath).href, available_range: null, metadata: {} }
+    : {
+      OTIO_SCHEMA: 'GeneratorReference.1',
+      name: clipName(clip),
+      generator_kind: clip.generator ? clip.generator.type : 'solid',
+      parameters: clip.generator || {},
+      available_range: null,
+      metadata: {},
+    });
+
+  const otioClip = (clip) => {
+    const effects = [];
+    if (clip.freezeFrame) {
+      effects.push({ OTIO_SCHEMA: 'FreezeFrame.1', name: '', effect_name: 'FreezeFrame', time_scalar: 0, metadata: {} });
+    } else if (clip.speed) {
+      effects.push({ OTIO_SCHEMA: 'LinearTimeWarp.1', name: '', effect_name: 'LinearTimeWarp', time_scalar: clip.speed, metadata: {} });
+    }
+    return {
+      OTIO_SCHEMA: 'Clip.2',
+      name: clipName(clip),
+      source_range: range(clip.inMs, clip.inMs + (clip.endMs - clip.startMs)),
+      media_references: { DEFAULT_MEDIA: mediaReference(clip) },
+      active_media_reference_key: 'DEFAULT_MEDIA',
+      effects,
+      markers: [],
+      enabled: true,
+      metadata: {},
+    };
+  };
+
+  const otioTrack = (name, kind, clips) => {
+    const children = [];
+    let cursorMs = 0;
+    for (const clip of [...clips].sort((a, b) => a.startMs - b.startMs).flatMap(loopPasses)) {
+      if (clip.startMs > cursorMs) {
+        children.push({ OTIO_SCHEMA: 'Gap.1', name: '', source_range: range(0, clip.startMs - cursorMs), effects: [], markers: [], enabled: true, metadata: {} });
+      }
+      children.push(otioClip(clip));
+      cursorMs = clip.endMs;
+    }
+    return { OTIO_SCHEMA: 'Track.1', name, kind, source_range: null, children, effects: [], markers: [], enabled: true, metadata: {} };
+  };
+
+  const groupByTrack = (clips) => {
+    const groups = new Map();
+    for (const clip of clips) {
+      const trackId = clip.trackId || '_default';
+      if (!groups.has(trackId)) groups.set(trackId, []);
+      groups.get(trackId).push(clip);
+    }
+    return groups;
+  };
+
+  const tracks = [otioTrack('main', 'Video', plan.mainTrack)];
+  for (const [trackId, clips] of groupByTrack(plan.overlayTrack || [])) {
+    tracks.push(otioTrack(trackId, 'Video', clips));
+  }
+  for (const [trackId, clips] of groupByTrack(plan.audioTrack || [])) {
+    tracks.push(otioTrack(trackId, 'Audio', clips));
+  }
+
+  const markers = (plan.markers || []).map((marker) => ({
+    OTIO_SCHEMA: 'Marker.2',
+    name: marker.name,
+    color: markerColorName(marker.color),
+    marked_range: range(marker.timeMs, marker.timeMs + (marker.durationMs || 0)),
+    comment: '',
+    metadata: { starscape: { id: marker.id } },
+  }));
+
+  return JSON.stringify({
+    OTIO_SCHEMA: 'Timeline.1',
+    name: plan.name || plan.id,
+    global_start_time: null,
+    tracks: { OTIO_SCHEMA: 'Stack.1', name: 'tracks', source_range: null, children: tracks, effects: [], markers, enabled: true, metadata: {} },
+    metadata: {},
+  }, null, 2);
+}
+
+module.exports = {
+  TIMELINE_FORMATS,
+  markerColorName,
+  buildEdl,
+  buildOtio,
+};
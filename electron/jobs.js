@@ -0,0 +1,233 @@
+const fs = require('fs');
+const path = require('path');
+
+/**
+ * Job history - command lines, status and performance metrics of long-running jobs
+ * Metrics stay on this machine; the history file lives under app data.
+ */
+
+const MAX_COMMANDS_PER_JOB = 200;
+const MAX_HISTORY = 50;
+
+let historyPath = null;
+let history = [];
+let lastJob = null;
+let jobCounter = 0;
+let scrubJob = (job) => job;
+const stateListeners = [];
+
+/**
+ * Subscribe to job start/finish (the listener receives the job record)
+ */
+function onStateChange(listener) {
+  stateListeners.push(listener);
+}
+
+function notifyStateChange(job) {
+  for (const listener of stateListeners) {
+    try {
+      listener(job);
+    } catch (e) {
+      console.error('Job state listener failed:', e.message);
+    }
+  }
+}
+
+/**
+ * Load the persisted job history
+ * @param {string} filePath - History JSON file
+ * @param {Function} scrub - Strips paths from a finished job before it joins the history
+ */
+function init(filePath, scrub = null) {
+  historyPath = filePath;
+  scrubJob = scrub || ((job) => job);
+  try {
+    const parsed = JSON.parse(fs.readFileSync(filePath, 'utf8'));
+    history = Array.isArray(parsed) ? parsed : [];
+  } catch (e) {
+    history = [];
+  }
+}
+
+function saveHistory() {
+  if (!historyPath) return;
+  try {
+    fs.mkdirSync(path.dirname(historyPath), { recursive: true });
+    fs.writeFileSync(historyPath, JSON.stringify(history, null, 2));
+  } catch (e) {
+    console.error('Failed to save job history:', e.message);
+  }
+}
+
+/**
+ * Start recording a job
+ * @param {string} kind - Job kind (export, ingest, ...)
+ * @param {Object} fields - Extra context kept with the job
+ */
+function startJob(kind, fields = {}) {
+  const job = {
+    id: `job_${Date.now().toString(36)}_${(jobCounter++).toString(36)}`,
+    kind,
+    started_at: new Date().toISOString(),
+    finished_at: null,
+    status: 'running',
+    error: null,
+    metrics: null,
+    commands: [],
+    ...fields,
+  };
+  lastJob = job;
+  notifyStateChange(job);
+  return job;
+}
+
+/**
+ * Remember the command line of a spawned process on a job
+ * @param {Object} job - Job from startJob
+ * @param {ChildProcess} proc - Spawned ffmpeg process
+ */
+function recordCommand(job, proc) {
+  if (!job || !proc || !Array.isArray(proc.spawnargs)) return;
+  if (job.commands.length >= MAX_COMMANDS_PER_JOB) return;
+  job.commands.push(proc.spawnargs.slice());
+}
+
+/**
+ * Wrap a trackProcess function so every process it sees is also recorded on the job
+ */
+function trackingFor(job, trackProcessFn) {
+  return (proc) => {
+    recordCommand(job, proc);
+    if (trackProcessFn) trackProcessFn(proc);
+  };
+}
+
+/**
+ * Mark a job finished and add it to the history
+ * @param {Object} job - Job from startJob
+ * @param {Error|string|null} error - Set when the job failed
+ * @param {Object|null} metrics - Timings and sizes collected while running
+ */
+function finishJob(job, error = null, metrics = null) {
+  job.finished_at = new Date().toISOString();
+  job.status = error ? 'failed' : 'completed';
+  job.error = error ? String(error.message || error) : null;
+  if (metrics) {
+    job.metrics = metrics;
+  }
+  // The history is persisted: command lines stay in memory only and settings are scrubbed, since both carry full media paths
+  const { commands, ...entry } = job;
+  history = [...history.filter((other) => other.id !== job.id), scrubJob(entry)].slice(-MAX_HISTORY);
+  saveHistory();
+  notifyStateChange(job);
+}
+
+/**
+ * The most recently started job (null when none has run this session)
+ */
+function getLastJob() {
+  return lastJob;
+}
+
+/**
+ * Look up a job in the history (or the one still running)
+ */
+function getJob(jobId) {
+  if (lastJob && lastJob.id === jobId) return lastJob;
+  return history.find((job) => job.id === jobId) || null;
+}
+
+/**
+ * Recent jobs, newest first, without their command lines
+ */
+function listJobs(limit = MAX_HISTORY) {
+  return history
+    .slice(-Math.max(1, limit))
+    .reverse();
+}
+
+/**
+ * Accumulate wall time per phase; nested phases are charged only to the innermost one
+ */
+function createPhaseTimer() {
+  const phases = {};
+  const stack = [];
+  let mark = Date.now();
+
+  const flush = () => {
+    const now = Date.now();
+    if (stack.length > 0) {
+      const phase = stack[stack.length - 1];
+      phases[phase] = (phases[phase] || 0) + (now - mark);
+    }
+    mark = now;
+  };
+
+  return {
+    phases,
+    // Start a sequential top-level phase (ends the previous one)
+    enter(phase) {
+      flush();
+      if (stack.length > 0) {
+        stack[0] = phase;
+      } else {
+        stack.push(phase);
+      }
+    },
+    // Stop timing the current top-level phase
+    stop() {
+      flush();
+      stack.length = 0;
+    },
+    async measure(phase, fn) {
+      flush();
+      stack.push(phase);
+      try {
+        return await fn();
+      } finally {
+        flush();
+        stack.pop();
+      }
+    },
+  };
+}
+
+/**
+ * Run async tasks with at most `limit` in flight; results keep the tasks' order
+ * The first failure rejects and stops further tasks from starting.
+ * @param {Array<Function>} tasks - Functions returning promises
+ * @param {number} limit - Maximum concurrent tasks
+ */
+async function runLimited(tasks, limit) {
+  const results = new Array(tasks.length);
+  let next = 0;
+  let failed = false;
+  const worker = async () => {
+    while (!failed && next < tasks.length) {
+      const index = next++;
+      try {
+        results[index] = await tasks[index]();
+      } catch (err) {
+        failed = true;
+        throw err;
+      }
+    }
+  };
+  const workers = Array.from({ length: Math.max(1, Math.min(limit, tasks.length)) }, worker);
+  await Promise.all(workers);
+  return results;
+}
+
+module.exports = {
+  init,
+  onStateChange,
+  startJob,
+  recordCommand,
+  trackingFor,
+  finishJob,
+  getLastJob,
+  getJob,
+  listJobs,
+  createPhaseTimer,
+  runLimited,
+};
@@ -0,0 +1,108 @@
+/**
+ * Keyframe helpers - turn { timeMs, value } lists into ffmpeg expressions
+ */
+
+/**
+ * Easing curves as ffmpeg expressions over normalized progress p (0..1)
+ * The easing on a keyframe shapes the segment leading to the next keyframe.
+ */
+const EASING_EXPRS = {
+  linear: (p) => p,
+  'ease-in': (p) => `(${p})*(${p})`,
+  'ease-out': (p) => `(${p})*(2-(${p}))`,
+  'ease-in-out': (p) => `if(lt(${p},0.5),2*(${p})*(${p}),1-pow(2-2*(${p}),2)/2)`,
+  hold: () => '0',
+};
+
+/**
+ * Sort keyframes by time and drop malformed entries
+ */
+function normalizeKeyframes(keyframes) {
+  if (!Array.isArray(keyframes)) {
+    return [];
+  }
+  return keyframes
+    .filter((kf) => kf && Number.isFinite(kf.timeMs) && Number.isFinite(kf.value))
+    .sort((a, b) => a.timeMs - b.timeMs);
+}
+
+/**
+ * Build a piecewise ffmpeg expression for a keyframed value
+ * @param {Array} keyframes - [{ timeMs, value, easing? }] relative to clip start
+ * @param {string} timeVar - Expression variable holding the current time in seconds (t, T, ...)
+ * @param {number} offsetSec - Timeline position of the clip start in seconds
+ * @param {number} fallback - Value used when no keyframes are present
+ * @returns {string} Expression evaluating to the interpolated value
+ */
+function buildKeyframeExpr(keyframes, timeVar, offsetSec, fallback) {
+  const kfs = normalizeKeyframes(keyframes);
+  if (kfs.length === 0) {
+    return String(fallback);
+  }
+  if (kfs.length === 1) {
+    return String(kfs[0].value);
+  }
+
+  const local = offsetSec ? `(${timeVar}-${offsetSec})` : timeVar;
+
+  // Build from the last segment backwards so each step nests the remainder
+  let expr = String(kfs[kfs.length - 1].value);
+  for (let i = kfs.length - 2; i >= 0; i--) {
+    const a = kfs[i];
+    const b = kfs[i + 1];
+    const t0 = a.timeMs / 1000;
+    const t1 = b.timeMs / 1000;
+    const span = t1 - t0;
+    const ease = EASING_EXPRS[a.easing] || EASING_EXPRS.linear;
+    const progress = `(${local}-${t0})/${span}`;
+    const segment = span > 0
+      ? `${a.value}+(${b.value - a.value})*${ease(progress)}`
+      : String(b.value);
+    expr = `if(lt(${local},${t1}),${segment},${expr})`;
+  }
+
+  // Hold the first value before the first keyframe
+  const first = kfs[0];
+  return `if(lt(${local},${first.timeMs / 1000}),${first.value},${expr})`;
+}
+
+/**
+ * Evaluate keyframes at a given clip-local time (mirrors buildKeyframeExpr)
+ */
+function evaluateKeyframes(keyframes, timeMs, fallback) {
+  const kfs = normalizeKeyframes(keyframes);
+  if (kfs.length === 0) return fallback;
+  if (timeMs <= kfs[0].timeMs) return kfs[0].value;
+
+  for (let i = 0; i < kfs.length - 1; i++) {
+    const a = kfs[i];
+    const b = kfs[i + 1];
+    if (timeMs < b.timeMs) {
+      const p = (timeMs - a.timeMs) / (b.timeMs - a.timeMs);
+      let eased = p;
+      if (a.easing === 'ease-in') eased = p * p;
+      else if (a.easing === 'ease-out') eased = p * (2 - p);
+      else if (a.easing === 'ease-in-out') eased = p < 0.5 ? 2 * p * p : 1 - Math.pow(2 - 2 * p, 2) / 2;
+      else if (a.easing === 'hold') eased = 0;
+      return a.value + (b.value - a.value) * eased;
+    }
+  }
+  return kfs[kfs.length - 1].value;
+}
+
+/**
+ * Check whether a clip carries any animated transform property
+ */
+function hasTransformKeyframes(clip) {
+  const tk = clip.transformKeyframes;
+  if (!tk) return false;
+  return ['x', 'y', 'scale', 'rotation'].some((prop) => normalizeKeyframes(tk[prop]).length > 0);
+}
+
+module.exports = {
+  EASING_EXPRS,
+  evaluateKeyframes,
+  hasTransformKeyframes,
+  normalizeKeyframes,
+  buildKeyframeExpr,
+};
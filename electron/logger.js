@@ -0,0 +1,206 @@
+const fs = require('fs');
+const path = require('path');
+
+/**
+ * Structured logger - JSON lines in a size-rotated file under app data
+ */
+
+const LEVELS = { error: 0, warn: 1, info: 2, debug: 3 };
+
+const LOG_FILE_NAME = 'starscape.log';
+const MAX_FILE_BYTES = 5 * 1024 * 1024;
+const MAX_ROTATED_FILES = 5;
+const MEMORY_BUFFER_SIZE = 1000;
+
+let logDir = null;
+let logPath = null;
+let bytesWritten = 0;
+let minLevel = process.env.STARSCAPE_LOG_LEVEL && LEVELS[process.env.STARSCAPE_LOG_LEVEL] !== undefined
+  ? process.env.STARSCAPE_LOG_LEVEL
+  : 'info';
+const recent = [];
+let spanCounter = 0;
+
+/**
+ * Start writing logs to a directory (entries logged earlier stay in memory only)
+ * @param {string} dir - Log directory
+ */
+function init(dir) {
+  logDir = dir;
+  logPath = path.join(dir, LOG_FILE_NAME);
+  fs.mkdirSync(dir, { recursive: true });
+  try {
+    bytesWritten = fs.statSync(logPath).size;
+  } catch (e) {
+    bytesWritten = 0;
+  }
+}
+
+/**
+ * Get the directory logs are written to (null before init)
+ */
+function getLogDir() {
+  return logDir;
+}
+
+/**
+ * Shift starscape.log -> .1 -> .2 ... dropping the oldest
+ */
+function rotate() {
+  for (let i = MAX_ROTATED_FILES - 1; i >= 1; i--) {
+    const from = `${logPath}.${i}`;
+    if (fs.existsSync(from)) {
+      fs.renameSync(from, `${logPath}.${i + 1}`);
+    }
+  }
+  if (fs.existsSync(logPath)) {
+    fs.renameSync(logPath, `${logPath}.1`);
+  }
+  bytesWritten = 0;
+}
+
+/**
+ * Serialize error objects so they survive JSON.stringify
+ */
+function normalizeFields(fields) {
+  const out = {};
+  for (const [key, value] of Object.entries(fields || {})) {
+    if (value instanceof Error) {
+      out[key] = { message: value.message, stack: value.stack };
+    } else {
+      out[key] = value;
+    }
+  }
+  return out;
+}
+
+/**
+ * Write a log entry
+ * @param {'error'|'warn'|'info'|'debug'} level - Severity
+ * @param {string} message - Human-readable message
+ * @param {Object} fields - Structured context (job ids, paths, durations...)
+ */
+function log(level, message, fields = {}) {
+  if (LEVELS[level] === undefined || LEVELS[level] > LEVELS[minLevel]) {
+    return;
+  }
+
+  const entry = { ts: new Date().toISOString(), level, message, ...normalizeFields(fields) };
+  recent.push(entry);
+  if (recent.length > MEMORY_BUFFER_SIZE) {
+    recent.shift();
+  }
+
+  if (level === 'error') {
+    console.error(`[${level}] ${message}`, fields);
+  } else if (process.env.STARSCAPE_LOG_CONSOLE) {
+    console.log(`[${level}] ${message}`, fields);
+  }
+
+  if (!logPath) return;
+  try {
+    const line = `${JSON.stringify(entry)}\n`;
+    if (bytesWritten + line.length > MAX_FILE_BYTES) {
+      rotate();
+    }
+    fs.appendFileSync(logPath, line);
+    bytesWritten += Buffer.byteLength(line);
+  } catch (e) {
+    console.error('Failed to write log entry:', e.message);
+  }
+}
+
+/**
+ * Start a timed span; end() or fail() logs its duration with the span's fields
+ * @param {string} name - Span name (e.g. export.job, ingest.file)
+ * @param {Object} fields - Context attached to every entry of the span
+ */
+function span(name, fields = {}) {
+  const spanId = `${Date.now().toString(36)}-${(spanCounter++).toString(36)}`;
+  const startedAt = Date.now();
+  log('debug', `${name} started`, { span: name, span_id: spanId, ...fields });
+
+  return {
+    id: spanId,
+    event(message, extra = {}) {
+      log('info', message, { span: name, span_id: spanId, ...fields, ...extra });
+    },
+    end(extra = {}) {
+      log('info', `${name} finished`, { span: name, span_id: spanId, duration_ms: Date.now() - startedAt, ...fields, ...extra });
+    },
+    fail(error, extra = {}) {
+      log('error', `${name} failed`, {
+        span: name,
+        span_id: spanId,
+        duration_ms: Date.now() - startedAt,
+        error: error instanceof Error ? error : String(error),
+        ...fields,
+        ...extra,
+      });
+    },
+  };
+}
+
+/**
+ * Read entries from the log files, newest last
+ */
+function readLogFiles() {
+  if (!logPath) return [];
+  const files = [];
+  for (let i = MAX_ROTATED_FILES; i >= 1; i--) {
+    files.push(`${logPath}.${i}`);
+  }
+  files.push(logPath);
+
+  const entries = [];
+  for (const file of files) {
+    let content;
+    try {
+      content = fs.readFileSync(file, 'utf8');
+    } catch (e) {
+      continue;
+    }
+    for (const line of content.split('\n')) {
+      if (!line) continue;
+      try {
+        entries.push(JSON.parse(line));
+      } catch (e) {
+        // Skip partial lines from a crash mid-write
+      }
+    }
+  }
+  return entries;
+}
+
+/**
+ * Get the most recent log entries at or above a level
+ * @param {'error'|'warn'|'info'|'debug'} level - Minimum severity
+ * @param {number} limit - Maximum number of entries
+ */
+function getRecentLogs(level = 'info', limit = 200) {
+  const threshold = LEVELS[level] !== undefined ? LEVELS[level] : LEVELS.info;
+  const source = logPath ? readLogFiles() : recent;
+  return source.filter((entry) => LEVELS[entry.level] <= threshold).slice(-Math.max(1, limit));
+}
+
+/**
+ * Change the minimum level that gets recorded
+ */
+function setLevel(level) {
+  if (LEVELS[level] !== undefined) {
+    minLevel = level;
+  }
+}
+
+module.exports = {
+  init,
+  getLogDir,
+  setLevel,
+  log,
+  error: (message, fields) => log('error', message, fields),
+  warn: (message, fields) => log('warn', message, fields),
+  info: (message, fields) => log('info', message, fields),
+  debug: (message, fields) => log('debug', message, fields),
+  span,
+  getRecentLogs,
+};
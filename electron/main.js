@@ -1,7 +1,8 @@
-const { app, BrowserWindow, ipcMain, desktopCapturer, screen, dialog, protocol } = require('electron');
+const { app, BrowserWindow, ipcMain, desktopCapturer, screen, dialog, protocol, globalShortcut } = require('electron');
 const path = require('path');
 const fs = require('fs');
 const https = require('https');
+const crypto = require('crypto');
 const squirrelStartup = require('electron-squirrel-startup');
 const { shell } = require('electron'); // Added for reveal-in-finder
 
@@ -9,12 +10,66 @@ const { shell } = require('electron'); // Added for reveal-in-finder
 // Load from project root (one level up from electron directory)
 require('dotenv').config({ path: path.join(__dirname, '..', '.env') });
 
-const { configureFfmpeg } = require('./ffmpeg');
+const { configureFfmpeg, requireBinary, resolveBinary, checkDependencies } = require('./ffmpeg');
+const logger = require('./logger');
+const { ErrorCode, StarscapeError, toStarscapeError, toIpcError } = require('./errors');
+const { EventType, emitEvent } = require('./events');
+const jobs = require('./jobs');
+const { createDiagnosticsBundle, scrubValue, scrubRoots } = require('./diagnostics');
+const { runEncodeBenchmark } = require('./benchmark');
+const { getFfmpegCapabilities, hasFilter } = require('./capabilities');
+const effectPlugins = require('./effectPlugins');
+const { listSystemFonts } = require('./fonts');
 const { CacheDirs } = require('./cache');
-const { probeMedia, extractPosterFrame } = require('./metadata');
-const { buildPlan, findVisibleClip } = require('./editPlan');
-const { executeExportJob } = require('./export');
-const { ingestFiles } = require('./ingest');
+const { pathToFileUrl, urlToPath, toLongPath, toLibraryUrl, setLibraryRoot, resolveAssetSrc } = require('./paths');
+const pathPolicy = require('./pathPolicy');
+const hotkeys = require('./hotkeys');
+const exportHooks = require('./exportHooks');
+const { getDeviceCapabilities } = require('./devices');
+const { RecordingSegmenter } = require('./segmenter');
+const { postProcessRecording } = require('./postRecord');
+const { startAudioMonitor, stopAudioMonitor, stopAllAudioMonitors } = require('./audioMonitor');
+const recordPresets = require('./recordPresets');
+const { normalizeRecordSettings } = recordPresets;
+const { openScrubSession, getScrubSession, closeScrubSession, closeAllScrubSessions } = require('./scrub');
+const { probeMedia, probeMediaBatch, extractPosterFrame, extractStill, resolveRotation, clipRotation, cancelProbes } = require('./metadata');
+const { buildPlan, findVisibleClip, sourceTimeAt, isolateClip } = require('./editPlan');
+const { hashClip } = require('./planHash');
+const { isImageFile, resolveOutputFormat, executeExportJob } = require('./export');
+const { estimateExport } = require('./exportEstimate');
+const { getAssetType, ingestFiles } = require('./ingest');
+const { importObsRecording } = require('./obsImport');
+const { planPhoneFixups } = require('./phoneFootage');
+const ingestRules = require('./ingestRules');
+const automation = require('./automation');
+const { renderAudioEffectPreview } = require('./audio');
+const { alignClips, generateTimelineWaveform, analyzeBeats, detectSpeech, suggestHighlights, analyzeClipGain, detectCrop, measureAvDrift } = require('./analysis');
+const { validatePlan } = require('./validate');
+const { getPlanStats } = require('./planStats');
+const { applyEdits, splitClipAt, rippleDeleteRange, closeGaps } = require('./edits');
+const { quantizeProject } = require('./timebase');
+const { msToTimecode, timecodeToMs } = require('./timecode');
+const { addMarker, listMarkers } = require('./markers');
+const { TIMELINE_FORMATS, buildEdl, buildOtio } = require('./interchange');
+const { generateContactSheet } = require('./contactSheet');
+const { renderCaptionsPreview } = require('./captions');
+const { AssetFrameCache } = require('./assetFrames');
+const { ensureClipHandles } = require('./handles');
+const {
+  REMUX_CONTAINERS,
+  remuxFile,
+  remuxFilename,
+  audioExtractTarget,
+  extractAudio,
+  quickTrim,
+  trimFilename,
+  concatFiles,
+} = require('./mediaTools');
+const { listTitleTemplates, renderTitle } = require('./titles');
+const { PREVIEW_FORMAT, renderGenerator, materializeGenerators } = require('./generators');
+const { applyQualityProfile, resolveEncoding, requiresMov } = require('./encoding');
+const { buildVideoEffectFilters } = require('./effects');
+const { fillTemplate, fillTemplateValue } = require('./projectTemplates');
 
 // Handle Squirrel events on Windows
 if (squirrelStartup) {
@@ -23,10 +78,19 @@ if (squirrelStartup) {
 
 let mainWindow = null;
 let cacheDirs = null;
+let assetFrames = null; // Trimmer frame cache
 let isQuitting = false;
 let isCleaningUp = false; // Prevent multiple cleanup calls
 let activeProcesses = new Set(); // Track active FFmpeg processes
 let activeRecordings = new Map(); // Track active screen recordings
+let recordingSegmenters = new Map(); // Segmented recordings still receiving chunks (outlive stop until finished)
+let ingestTranscodeQueue = Promise.resolve(); // Ingest conversions run one at a time
+let lastRecordSettings = {}; // Active preset or last recording's settings, reused by the start hotkey
+let automationServer = null; // Local automation API, when enabled
+let automationExportQueue = Promise.resolve(); // Exports queued through the API run one at a time
+const automationExports = new Map(); // export_id -> { export_id, status, job_id, result, error }
+let openProject = null; // Project open in the renderer, published while the automation API is on
+let acceptedProjectJson = null; // Last project accepted by apply-edits, the baseline for lock checks
 
 /**
  * Create the main application window
@@ -112,9 +176,11 @@ function cleanup() {
   
   // Cancel any ongoing operations
   if (mainWindow && !mainWindow.isDestroyed()) {
-    mainWindow.webContents.send('app-shutting-down');
+    emitEvent(mainWindow, EventType.APP_SHUTTING_DOWN);
   }
   
+  hotkeys.unregisterAll();
+
   // Stop all active screen recordings
   if (activeRecordings.size > 0) {
     console.log(`Stopping ${activeRecordings.size} active recordings...`);
@@ -129,6 +195,12 @@ function cleanup() {
     }
     activeRecordings.clear();
   }
+
+  // Close segmented recordings' input so their open segment is finalized
+  for (const segmenter of recordingSegmenters.values()) {
+    segmenter.finish().catch(() => {});
+  }
+  recordingSegmenters.clear();
   
   // Kill all active FFmpeg processes
   if (activeProcesses.size > 0) {
@@ -161,6 +233,16 @@ function cleanup() {
       }
     }, 3000);
   }
+
+  closeAllScrubSessions();
+  stopAllAudioMonitors();
+  stopAutomationServer();
+
+  // Probes and frame grabs aren't tracked as jobs; stop any still running
+  const cancelledProbes = cancelProbes();
+  if (cancelledProbes > 0) {
+    logger.info('Cancelled running probes', { count: cancelledProbes });
+  }
   
   console.log('Cleanup completed');
 }
@@ -169,27 +251,66 @@ function cleanup() {
  * Initialize application
  */
 async function initialize() {
-  // Configure FFmpeg paths
-  configureFfmpeg();
+  // Start file logging under app data
+  logger.init(path.join(app.getPath('appData'), 'com.starscape.studio', 'logs'));
+  jobs.init(
+    path.join(app.getPath('appData'), 'com.starscape.studio', 'jobs.json'),
+    (job) => scrubValue(job, scrubRoots(cacheDirs))
+  );
+  jobs.onStateChange((job) =>
+    emitEvent(mainWindow, EventType.JOB_STATE_CHANGED, {
+      job_id: job.id,
+      kind: job.kind,
+      status: job.status,
+      error: job.error,
+    })
+  );
+  logger.info('Starscape Studio starting', { version: app.getVersion(), platform: process.platform });
+
+  // Configure FFmpeg paths (ffmpeg and ffprobe are resolved independently)
+  const binaries = configureFfmpeg();
+  if (!binaries.ffmpegPath || !binaries.ffprobePath) {
+    logger.warn('FFmpeg tools missing', { ffmpeg: binaries.ffmpegPath, ffprobe: binaries.ffprobePath });
+  }
 
   // Initialize cache directories
   cacheDirs = new CacheDirs(app);
   await cacheDirs.ensureDirectories();
+  // Projects reference ingested media relative to the cache
+  setLibraryRoot(cacheDirs.base);
+  assetFrames = new AssetFrameCache(cacheDirs.frames);
+  assetFrames.load().catch((e) => logger.warn('Failed to load trimmer frame cache', { error: e.message }));
+
+  // Renderer-supplied paths are limited to app folders and files picked in a dialog
+  pathPolicy.init(
+    [cacheDirs.base, cacheDirs.renders, path.dirname(cacheDirs.diagnosticsBundlePath())],
+    path.join(app.getPath('appData'), 'com.starscape.studio', 'path-grants.json'),
+    path.join(app.getPath('appData'), 'com.starscape.studio', 'hook-grants.json')
+  );
+
+  // Recording presets and system-wide recording shortcuts
+  recordPresets.init(path.join(app.getPath('appData'), 'com.starscape.studio', 'recording-presets.json'));
+  lastRecordSettings = recordPresets.activeRecordSettings();
+  hotkeys.init(path.join(app.getPath('appData'), 'com.starscape.studio', 'hotkeys.json'), globalShortcut, hotkeyHandlers);
+  exportHooks.init(path.join(app.getPath('appData'), 'com.starscape.studio', 'export-hooks.json'));
+  ingestRules.init(path.join(app.getPath('appData'), 'com.starscape.studio', 'ingest-rules.json'));
+  automation.init(path.join(app.getPath('appData'), 'com.starscape.studio', 'automation.json'));
+  await startAutomationServer();
+  loadEffectPlugins().catch((e) => logger.warn('Failed to load effect plugins', { error: e.message }));
 
   // Register custom protocol for serving local media files
   protocol.registerFileProtocol('media', (request, callback) => {
     try {
-      // Extract file path from media:// URL
-      let url = request.url.substring('media://'.length);
-      
-      // Add leading slash if missing (protocol strips it)
-      if (!url.startsWith('/')) {
-        url = '/' + url;
+      // Extract file path from media:// URL (percent-decoded, drive letters and UNC shares handled)
+      let filePath;
+      try {
+        filePath = pathPolicy.checkPath(request.url);
+      } catch (policyError) {
+        console.error(`[media://] ${policyError.message}`);
+        callback({ error: -10 }); // NET::ERR_ACCESS_DENIED
+        return;
       }
       
-      // Decode URI component to handle spaces and special characters
-      const filePath = decodeURIComponent(url);
-      
       console.log(`[media://] Request for: ${request.url}`);
       console.log(`[media://] Decoded path: ${filePath}`);
       
@@ -280,7 +401,7 @@ process.on('uncaughtException', (error) => {
   
   // Don't exit immediately, let user save work
   if (mainWindow && !mainWindow.isDestroyed()) {
-    mainWindow.webContents.send('app-error', {
+    emitEvent(mainWindow, EventType.APP_ERROR, {
       type: 'uncaughtException',
       message: error.message,
       stack: error.stack
@@ -294,7 +415,7 @@ process.on('unhandledRejection', (reason, promise) => {
   cleanup();
   
   if (mainWindow && !mainWindow.isDestroyed()) {
-    mainWindow.webContents.send('app-error', {
+    emitEvent(mainWindow, EventType.APP_ERROR, {
       type: 'unhandledRejection',
       message: reason.toString(),
       promise: promise.toString()
@@ -388,289 +509,1745 @@ ipcMain.handle('list-capture-devices', async () => {
       audio_inputs: audioInputs
     };
   } catch (error) {
-    throw new Error(`Failed to list capture devices: ${error.message}`);
+    throw toIpcError(error, 'Failed to list capture devices');
   }
 });
 
 /**
- * Start screen recording
+ * Resolution/frame-rate modes a camera supports, so the UI only offers combinations that will start
  */
-ipcMain.handle('start-screen-record', async (event, settings) => {
+ipcMain.handle('get-device-capabilities', async (event, device) => {
   try {
-    const { fps = 30, display_index = 0, audio_index = 0 } = settings;
-    
-    // Get available sources
-    const sources = await desktopCapturer.getSources({
-      types: ['screen', 'window'],
-      thumbnailSize: { width: 150, height: 150 }
-    });
-    
-    if (display_index >= sources.length) {
-      throw new Error(`Display index ${display_index} out of range. Available displays: ${sources.length}`);
-    }
-    
-    const source = sources[display_index];
-    const recordingId = `recording_${Date.now()}_${Math.random().toString(36).substr(2, 9)}`;
-    
-    // Generate output path
-    const outputPath = path.join(
-      cacheDirs ? cacheDirs.captures : app.getPath('temp'),
-      `screen_recording_${recordingId}.webm`
-    );
-    
-    // Store recording info
-    activeRecordings.set(recordingId, {
-      source,
-      outputPath,
-      startTime: Date.now(),
-      settings: { fps, display_index, audio_index }
-    });
-    
-    console.log(`Started screen recording ${recordingId} to ${outputPath}`);
-    
-    // Send the source info to the renderer process to start recording
-    event.sender.send('start-recording', {
-      recordingId,
-      sourceId: source.id,
-      outputPath,
-      settings: { fps, display_index, audio_index }
-    });
-    
-    return {
-      recordingId,
-      outPath: outputPath
-    };
+    return await getDeviceCapabilities(device);
   } catch (error) {
-    throw new Error(`Failed to start screen recording: ${error.message}`);
+    throw toIpcError(error, 'Failed to probe capture device');
   }
 });
 
 /**
- * Stop screen recording
+ * Meter a microphone's input level; levels arrive as audio-level events until stop-audio-monitor
  */
-ipcMain.handle('stop-screen-record', async (event, recordingId) => {
+ipcMain.handle('start-audio-monitor', async (event, device) => {
   try {
-    const recording = activeRecordings.get(recordingId);
-    if (!recording) {
-      throw new Error(`Recording ${recordingId} not found`);
-    }
-    
-    // Send stop signal to renderer process
-    event.sender.send('stop-recording', { recordingId });
-    
-    // Remove from active recordings
-    activeRecordings.delete(recordingId);
-    
-    console.log(`Stopped screen recording ${recordingId}`);
-    
-    return recording.outputPath;
+    const monitor = startAudioMonitor(String(device ?? ''), {
+      trackProcessFn: trackProcess,
+      onLevel: (level) => emitEvent(event.sender, EventType.AUDIO_LEVEL, level),
+      onEnd: (error) => emitEvent(event.sender, EventType.AUDIO_LEVEL, {
+        monitor_id: monitor.id,
+        ended: true,
+        error: error ? error.message : undefined,
+      }),
+    });
+    return { monitor_id: monitor.id };
   } catch (error) {
-    throw new Error(`Failed to stop screen recording: ${error.message}`);
+    throw toIpcError(error, 'Failed to start audio monitor');
   }
 });
 
 /**
- * Get media metadata
+ * Stop an audio level monitor and its capture process
  */
-ipcMain.handle('get-media-metadata', async (event, filePath) => {
+ipcMain.handle('stop-audio-monitor', async (event, monitorId) => {
   try {
-    const metadata = await probeMedia(filePath);
-    return metadata;
+    stopAudioMonitor(monitorId);
+    return { stopped: true };
   } catch (error) {
-    throw new Error(`Failed to get metadata: ${error}`);
+    throw toIpcError(error, 'Failed to stop audio monitor');
   }
 });
 
 /**
- * Generate preview frame
+ * Capture a display or window to an image in the captures folder
+ * @param {number|string} target - Source index from list-capture-devices, or a source id
+ * @param {string} format - 'png' or 'jpg'
+ * @returns {Promise<{ path: string, width: number, height: number, source_name: string }>}
  */
-ipcMain.handle('generate-preview', async (event, projectJson, atMs) => {
-  try {
-    const plan = buildPlan(projectJson);
-    const visibleClip = findVisibleClip(plan, atMs);
-    
-    if (!visibleClip) {
-      throw new Error('No clip visible at this time');
-    }
+async function captureScreenshot(target, format = 'png') {
+  const ext = format === 'jpeg' ? 'jpg' : format;
+  if (ext !== 'png' && ext !== 'jpg') {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Unsupported screenshot format: ${format}`, { format });
+  }
 
-    // Calculate timestamp relative to clip source
-    const relativeMs = atMs - visibleClip.startMs + visibleClip.inMs;
-    const outputPath = cacheDirs.previewFile(plan.id, atMs);
-    
-    const url = await extractPosterFrame(visibleClip.srcPath, relativeMs, outputPath);
-    
-    return {
-      url,
-      ts: atMs,
-    };
-  } catch (error) {
-    throw new Error(`Failed to generate preview: ${error}`);
+  // Thumbnails are fitted into this box, so it has to hold the largest display at native pixels
+  const displays = screen.getAllDisplays();
+  const nativeSize = (display) => ({
+    width: Math.round(display.size.width * display.scaleFactor),
+    height: Math.round(display.size.height * display.scaleFactor),
+  });
+  const thumbnailSize = displays.map(nativeSize).reduce(
+    (box, size) => ({ width: Math.max(box.width, size.width), height: Math.max(box.height, size.height) }),
+    { width: 1920, height: 1080 }
+  );
+  const sources = await desktopCapturer.getSources({ types: ['screen', 'window'], thumbnailSize });
+  const source = typeof target === 'string' ? sources.find((s) => s.id === target) : sources[target];
+  if (!source) {
+    throw new StarscapeError(ErrorCode.DEVICE_NOT_FOUND, `Capture source ${target} not found. Available sources: ${sources.length}`, {
+      target,
+      available: sources.length,
+    });
+  }
+  if (source.thumbnail.isEmpty()) {
+    throw new StarscapeError(ErrorCode.DEVICE_NOT_FOUND, `${source.name} has nothing to capture (minimized window?)`, { target });
+  }
+
+  // A smaller display comes back scaled up to the box; bring it back to its own pixels
+  let image = source.thumbnail;
+  const display = displays.find((d) => String(d.id) === source.display_id);
+  if (display) {
+    const size = nativeSize(display);
+    const current = image.getSize();
+    if (current.width !== size.width || current.height !== size.height) {
+      image = image.resize({ width: size.width, height: size.height, quality: 'best' });
+    }
   }
-});
+
+  const outputPath = cacheDirs.screenshotPath(ext);
+  await fs.promises.writeFile(outputPath, ext === 'png' ? image.toPNG() : image.toJPEG(92));
+  const { width, height } = image.getSize();
+  logger.info('Screenshot captured', { output_path: outputPath, source: source.name, width, height });
+  return { path: outputPath, width, height, source_name: source.name };
+}
 
 /**
- * Export project
+ * Screenshot a display or window into the captures folder, optionally ingesting it as an image asset
  */
-ipcMain.handle('export-project', async (event, projectJson, settings) => {
+ipcMain.handle('capture-screenshot', async (event, target, format = 'png', options = {}) => {
   try {
-    const plan = buildPlan(projectJson);
-    const result = await executeExportJob(plan, settings, cacheDirs, mainWindow, trackProcess);
-    return result;
+    const shot = await captureScreenshot(target, format);
+    if (!options.ingest) {
+      return shot;
+    }
+    const [asset] = await ingestFiles([shot.path], cacheDirs);
+    return { ...shot, asset };
   } catch (error) {
-    throw new Error(`Failed to export project: ${error}`);
+    throw toIpcError(error, 'Failed to capture screenshot');
   }
 });
 
 /**
- * Convert WebM to MP4 using ffmpeg
+ * Start a screen recording; the renderer's recorder picks it up from the START_RECORDING event
+ * @param {WebContents|BrowserWindow} target - Where recording events go
+ * @param {Object} settings - RecordSettings (see recordPresets.js); missing fields take the defaults
  */
-function convertWebmToMp4(inputPath, outputPath) {
-  return new Promise((resolve, reject) => {
-    const ffmpeg = require('fluent-ffmpeg');
-    
-    console.log(`Converting WebM to MP4: ${inputPath} -> ${outputPath}`);
-    
-    const command = ffmpeg(inputPath)
-      .videoCodec('libx264')
-      .audioCodec('aac')
-      .outputOptions([
-        '-preset fast',
-        '-crf 23',
-        '-movflags +faststart'
-      ])
-      .output(outputPath)
-      .on('start', (commandLine) => {
-        console.log('FFmpeg command:', commandLine);
-      })
-      .on('progress', (progress) => {
-        if (progress.percent) {
-          console.log(`Conversion progress: ${Math.round(progress.percent)}%`);
-        }
-      })
-      .on('end', () => {
-        console.log('WebM to MP4 conversion completed');
-        resolve(outputPath);
-      })
-      .on('error', (err) => {
-        console.error('Conversion error:', err);
-        reject(new Error(`FFmpeg conversion failed: ${err.message}`));
-      });
-    
-    // Track the process
-    const process = command.run();
-    trackProcess(process);
+async function startScreenRecording(target, settings = {}) {
+  const recordSettings = normalizeRecordSettings(settings);
+  const { fps, display_index } = recordSettings;
+
+  // Get available sources
+  const sources = await desktopCapturer.getSources({
+    types: ['screen', 'window'],
+    thumbnailSize: { width: 150, height: 150 }
+  });
+
+  if (display_index >= sources.length) {
+    throw new StarscapeError(
+      ErrorCode.DEVICE_NOT_FOUND,
+      `Display index ${display_index} out of range. Available displays: ${sources.length}`,
+      { display_index, available: sources.length }
+    );
+  }
+
+  const source = sources[display_index];
+  const recordingId = `recording_${Date.now()}_${Math.random().toString(36).substr(2, 9)}`;
+
+  // Generate output path
+  let outputPath = path.join(
+    cacheDirs ? cacheDirs.captures : app.getPath('temp'),
+    `screen_recording_${recordingId}.webm`
+  );
+
+  // Segmented recordings are streamed to disk as they're recorded, a new file every segment_minutes
+  if (recordSettings.segment_minutes) {
+    const baseName = `screen_recording_${recordingId}`;
+    recordingSegmenters.set(recordingId, new RecordingSegmenter(cacheDirs.mediaDir, baseName, recordSettings.segment_minutes, {
+      trackProcessFn: trackProcess,
+    }));
+    outputPath = path.join(cacheDirs.mediaDir, `${baseName}_000.webm`);
+  }
+
+  // Store recording info
+  activeRecordings.set(recordingId, {
+    source,
+    outputPath,
+    startTime: Date.now(),
+    paused: false,
+    settings: recordSettings
+  });
+  lastRecordSettings = recordSettings;
+
+  logger.info('Screen recording started', { recording_id: recordingId, output_path: outputPath, fps, display_index });
+
+  // Send the source info to the renderer process to start recording
+  emitEvent(target, EventType.START_RECORDING, {
+    recordingId,
+    sourceId: source.id,
+    outputPath,
+    settings: recordSettings
+  });
+  emitEvent(target, EventType.RECORD_STATUS, {
+    recording_id: recordingId,
+    status: 'recording',
+    output_path: outputPath,
+  });
+
+  return {
+    recordingId,
+    outPath: outputPath
+  };
+}
+
+function getRecording(recordingId) {
+  const recording = activeRecordings.get(recordingId);
+  if (!recording) {
+    throw new StarscapeError(ErrorCode.RECORDING_NOT_FOUND, `Recording ${recordingId} not found`, { recording_id: recordingId });
+  }
+  return recording;
+}
+
+/**
+ * Stop a screen recording
+ * @returns {string} The recording's output path
+ */
+function stopScreenRecording(target, recordingId) {
+  const recording = getRecording(recordingId);
+
+  // Send stop signal to renderer process
+  emitEvent(target, EventType.STOP_RECORDING, { recordingId });
+  emitEvent(target, EventType.RECORD_STATUS, {
+    recording_id: recordingId,
+    status: 'stopped',
+    output_path: recording.outputPath,
+    duration_ms: Date.now() - recording.startTime,
   });
+
+  // Remove from active recordings
+  activeRecordings.delete(recordingId);
+
+  logger.info('Screen recording stopped', {
+    recording_id: recordingId,
+    duration_ms: Date.now() - recording.startTime,
+  });
+
+  return recording.outputPath;
 }
 
 /**
- * Convert WebM audio to MP3 using ffmpeg
+ * Pause or resume a screen recording
+ * @param {boolean|null} paused - New state (null toggles)
  */
-function convertWebmToMp3(inputPath, outputPath) {
-  return new Promise((resolve, reject) => {
-    const ffmpeg = require('fluent-ffmpeg');
-    
-    console.log(`Converting WebM audio to MP3: ${inputPath} -> ${outputPath}`);
-    
-    const command = ffmpeg(inputPath)
-      .audioCodec('libmp3lame')
-      .audioBitrate('192k')
-      .audioChannels(2)
-      .audioFrequency(44100)
-      .output(outputPath)
-      .on('start', (commandLine) => {
-        console.log('FFmpeg command:', commandLine);
-      })
-      .on('progress', (progress) => {
-        if (progress.percent) {
-          console.log(`Conversion progress: ${Math.round(progress.percent)}%`);
-        }
-      })
-      .on('end', () => {
-        console.log('WebM to MP3 conversion completed');
-        resolve(outputPath);
-      })
-      .on('error', (err) => {
-        console.error('Conversion error:', err);
-        reject(new Error(`FFmpeg audio conversion failed: ${err.message}`));
-      });
-    
-    // Track the process
-    const process = command.run();
-    trackProcess(process);
+function pauseScreenRecording(target, recordingId, paused = null) {
+  const recording = getRecording(recordingId);
+  recording.paused = paused === null ? !recording.paused : !!paused;
+
+  emitEvent(target, EventType.PAUSE_RECORDING, { recordingId, paused: recording.paused });
+  emitEvent(target, EventType.RECORD_STATUS, {
+    recording_id: recordingId,
+    status: recording.paused ? 'paused' : 'recording',
+    output_path: recording.outputPath,
   });
+
+  logger.info(recording.paused ? 'Screen recording paused' : 'Screen recording resumed', { recording_id: recordingId });
+  return recording.paused;
 }
 
 /**
- * Save blob data to file (converts WebM to MP4 for video, MP3 for audio)
+ * Most recently started recording still running, if any
  */
-ipcMain.handle('save-blob-to-file', async (event, blobData, filename) => {
-  try {
-    const fs = require('fs');
-    const path = require('path');
-    const buffer = Buffer.from(blobData);
-    
-    // Construct full path in cache/media directory
-    const webmPath = path.join(cacheDirs.mediaDir, filename);
-    
-    // Save the blob to the webm file
-    await fs.promises.writeFile(webmPath, buffer);
-    console.log(`Saved WebM recording to: ${webmPath}`);
-    
-    // Check if it's a microphone recording (audio-only) by filename
-    if (filename.startsWith('microphone_recording_')) {
-      // For audio recordings, convert to MP3
-      const mp3Filename = filename.replace('.webm', '.mp3');
-      const mp3Path = path.join(cacheDirs.mediaDir, mp3Filename);
-      
-      await convertWebmToMp3(webmPath, mp3Path);
-      
-      // Delete the original WebM file
-      await fs.promises.unlink(webmPath);
-      console.log(`Deleted temporary WebM file: ${webmPath}`);
-      
-      return { success: true, path: mp3Path };
-    } else {
-      // For video recordings (webcam/screen), convert to MP4
-      const mp4Filename = filename.replace('.webm', '.mp4');
-      const mp4Path = path.join(cacheDirs.mediaDir, mp4Filename);
-      
-      await convertWebmToMp4(webmPath, mp4Path);
-      
-      // Delete the original WebM file
-      await fs.promises.unlink(webmPath);
-      console.log(`Deleted temporary WebM file: ${webmPath}`);
-      
-      return { success: true, path: mp4Path };
+function latestRecordingId() {
+  const ids = [...activeRecordings.keys()];
+  return ids.length > 0 ? ids[ids.length - 1] : null;
+}
+
+/**
+ * Global hotkey actions - they call the recording functions directly, so they work while the app is unfocused
+ */
+const hotkeyHandlers = {
+  'start-recording': async (action) => {
+    if (activeRecordings.size > 0) return;
+    try {
+      const { recordingId } = await startScreenRecording(mainWindow, lastRecordSettings);
+      emitEvent(mainWindow, EventType.HOTKEY_TRIGGERED, { action, recording_id: recordingId });
+    } catch (error) {
+      logger.error('Hotkey failed to start recording', { error });
+    }
+  },
+  'stop-recording': (action) => {
+    const recordingId = latestRecordingId();
+    if (!recordingId) return;
+    stopScreenRecording(mainWindow, recordingId);
+    emitEvent(mainWindow, EventType.HOTKEY_TRIGGERED, { action, recording_id: recordingId });
+  },
+  'pause-recording': (action) => {
+    const recordingId = latestRecordingId();
+    if (!recordingId) return;
+    pauseScreenRecording(mainWindow, recordingId);
+    emitEvent(mainWindow, EventType.HOTKEY_TRIGGERED, { action, recording_id: recordingId });
+  },
+  // Captures the display last chosen for recording
+  'capture-screenshot': async (action) => {
+    try {
+      const shot = await captureScreenshot(lastRecordSettings.display_index || 0, 'png');
+      emitEvent(mainWindow, EventType.HOTKEY_TRIGGERED, { action, output_path: shot.path });
+    } catch (error) {
+      logger.error('Hotkey failed to capture screenshot', { error });
     }
+  },
+};
+
+/**
+ * Start screen recording
+ */
+ipcMain.handle('start-screen-record', async (event, settings) => {
+  try {
+    return await startScreenRecording(event.sender, settings);
   } catch (error) {
-    throw new Error(`Failed to save blob to file: ${error.message}`);
+    logger.error('Screen recording failed to start', { error });
+    throw toIpcError(error, 'Failed to start screen recording');
   }
 });
 
 /**
- * Ingest files
+ * Stop screen recording
  */
-ipcMain.handle('ingest-files', async (event, request) => {
+ipcMain.handle('stop-screen-record', async (event, recordingId) => {
   try {
-    const { file_paths } = request;
-    const results = await ingestFiles(file_paths, cacheDirs);
-    return results;
+    return stopScreenRecording(event.sender, recordingId);
   } catch (error) {
-    throw new Error(`Failed to ingest files: ${error}`);
+    throw toIpcError(error, 'Failed to stop screen recording');
   }
 });
 
 /**
- * Apply edits to project (placeholder implementation)
+ * Run the post-record pipeline (remux, probe, thumbnail, waveform) over a recording's files as a background job
+ * @returns {Promise<{ job_id: string, files: Object[] }>}
  */
-ipcMain.handle('apply-edits', async (event, projectJson) => {
+async function runPostRecordJob(target, recordingId, filePaths) {
+  const job = jobs.startJob('post-record', { recording_id: recordingId });
+  const trackProcessFn = jobs.trackingFor(job, trackProcess);
+  try {
+    const files = [];
+    for (const [index, filePath] of filePaths.entries()) {
+      files.push(await postProcessRecording(filePath, cacheDirs, {
+        trackProcessFn,
+        onStep: (step) => emitEvent(target, EventType.POST_RECORD_PROGRESS, {
+          job_id: job.id,
+          recording_id: recordingId,
+          step,
+          current: index + 1,
+          total: filePaths.length,
+          file_name: path.basename(filePath),
+        }),
+      }));
+    }
+    jobs.finishJob(job);
+    emitEvent(target, EventType.RECORD_STATUS, {
+      recording_id: recordingId,
+      status: 'processed',
+      output_path: files[0].path,
+      segments: files.map((file) => file.path),
+    });
+    return { job_id: job.id, files };
+  } catch (error) {
+    jobs.finishJob(job, error);
+    throw error;
+  }
+}
+
+/**
+ * Append a chunk of a segmented recording (sent by the renderer's recorder as it records)
+ */
+ipcMain.handle('write-recording-chunk', async (event, recordingId, data) => {
   try {
-    // For now, this is a placeholder - in a real implementation,
-    // this would process the project JSON and apply any pending edits
-    console.log('Apply edits called with project:', JSON.parse(projectJson));
+    const segmenter = recordingSegmenters.get(recordingId);
+    if (!segmenter) {
+      throw new StarscapeError(ErrorCode.RECORDING_NOT_FOUND, `Segmented recording ${recordingId} not found`, { recording_id: recordingId });
+    }
+    await segmenter.write(Buffer.from(data));
     return { success: true };
   } catch (error) {
-    throw new Error(`Failed to apply edits: ${error.message}`);
+    throw toIpcError(error, 'Failed to write recording');
+  }
+});
+
+/**
+ * Close a segmented recording after its last chunk, post-process it and return every segment file
+ */
+ipcMain.handle('finish-screen-record', async (event, recordingId) => {
+  try {
+    const segmenter = recordingSegmenters.get(recordingId);
+    if (!segmenter) {
+      throw new StarscapeError(ErrorCode.RECORDING_NOT_FOUND, `Segmented recording ${recordingId} not found`, { recording_id: recordingId });
+    }
+    recordingSegmenters.delete(recordingId);
+    const segments = await segmenter.finish();
+    emitEvent(event.sender, EventType.RECORD_STATUS, {
+      recording_id: recordingId,
+      status: 'saved',
+      output_path: segments[0],
+      segments,
+    });
+    logger.info('Segmented recording saved', { recording_id: recordingId, segments: segments.length });
+    const processed = await runPostRecordJob(event.sender, recordingId, segments);
+    return { segments: processed.files.map((file) => file.path), job_id: processed.job_id, files: processed.files };
+  } catch (error) {
+    throw toIpcError(error, 'Failed to finish recording');
+  }
+});
+
+/**
+ * Pause or resume screen recording (paused omitted toggles)
+ */
+ipcMain.handle('pause-screen-record', async (event, recordingId, paused = null) => {
+  try {
+    return pauseScreenRecording(event.sender, recordingId, paused);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to pause screen recording');
+  }
+});
+
+/**
+ * Built-in and saved recording presets
+ */
+ipcMain.handle('list-record-presets', async () => {
+  try {
+    return recordPresets.listRecordPresets();
+  } catch (error) {
+    throw toIpcError(error, 'Failed to list recording presets');
+  }
+});
+
+/**
+ * Make a recording preset active and return its settings (overrides such as the display win)
+ */
+ipcMain.handle('apply-record-preset', async (event, presetId, overrides = {}) => {
+  try {
+    const applied = recordPresets.applyRecordPreset(presetId, overrides);
+    lastRecordSettings = applied.settings;
+    return applied;
+  } catch (error) {
+    throw toIpcError(error, 'Failed to apply recording preset');
+  }
+});
+
+/**
+ * Save the given settings as a named recording preset
+ */
+ipcMain.handle('save-record-preset', async (event, name, settings) => {
+  try {
+    return recordPresets.saveRecordPreset(name, settings);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to save recording preset');
+  }
+});
+
+/**
+ * Global hotkey bindings and whether each is registered
+ */
+ipcMain.handle('get-hotkeys', async () => {
+  try {
+    return hotkeys.getHotkeys();
+  } catch (error) {
+    throw toIpcError(error, 'Failed to get hotkeys');
+  }
+});
+
+/**
+ * Rebind global hotkeys ({ action: accelerator }, null disables an action)
+ */
+ipcMain.handle('set-hotkeys', async (event, bindings) => {
+  try {
+    return hotkeys.setHotkeys(bindings);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to set hotkeys');
+  }
+});
+
+/**
+ * Conversions applied to newly ingested media
+ */
+ipcMain.handle('get-ingest-rules', async () => {
+  try {
+    return ingestRules.getIngestRules();
+  } catch (error) {
+    throw toIpcError(error, 'Failed to get ingest rules');
+  }
+});
+
+/**
+ * Replace the ingest rules (an empty list turns ingest conversion off)
+ */
+ipcMain.handle('set-ingest-rules', async (event, rules = []) => {
+  try {
+    return ingestRules.setIngestRules(rules);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to set ingest rules');
+  }
+});
+
+/**
+ * Post-export actions for every quality profile (custom for exports without one)
+ */
+ipcMain.handle('get-export-hooks', async () => {
+  try {
+    return exportHooks.getExportHooks();
+  } catch (error) {
+    throw toIpcError(error, 'Failed to get export hooks');
+  }
+});
+
+/**
+ * Replace a quality profile's post-export actions (an empty list removes them)
+ * Hook programs and destination folders must have been picked with pick-export-hook-target.
+ */
+ipcMain.handle('set-export-hooks', async (event, preset, actions = []) => {
+  try {
+    return exportHooks.setExportHooks(preset, actions, pathPolicy.checkHookTarget);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to set export hooks');
+  }
+});
+
+/**
+ * Pick a program (kind 'command') or destination folder (kind 'folder') for an export hook
+ * @returns {{ path: string|null }} null when the dialog was cancelled
+ */
+ipcMain.handle('pick-export-hook-target', async (event, kind) => {
+  try {
+    if (kind !== 'command' && kind !== 'folder') {
+      throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Unknown hook target kind: ${kind}`, { kind });
+    }
+    const result = await dialog.showOpenDialog(mainWindow, {
+      title: kind === 'command' ? 'Choose a program to run after export' : 'Choose a folder for exports',
+      properties: kind === 'command' ? ['openFile'] : ['openDirectory', 'createDirectory'],
+    });
+    if (result.canceled || result.filePaths.length === 0) {
+      return { path: null };
+    }
+    pathPolicy.grantHookTarget(result.filePaths);
+    return { path: result.filePaths[0] };
+  } catch (error) {
+    throw toIpcError(error, 'Failed to pick export hook target');
+  }
+});
+
+// Finished API exports kept for status queries
+const MAX_AUTOMATION_EXPORTS = 100;
+
+/**
+ * Queue an export requested through the automation API
+ * @param {Object} body - { project_id } (the open project) or { project } (project JSON), plus settings
+ */
+function enqueueAutomationExport(body = {}) {
+  let projectJson;
+  if (body.project !== undefined) {
+    projectJson = typeof body.project === 'string' ? body.project : JSON.stringify(body.project);
+  } else if (body.project_id !== undefined) {
+    if (!openProject || openProject.id !== body.project_id) {
+      throw new StarscapeError(ErrorCode.NOT_FOUND, `Project not open: ${body.project_id}`, { project_id: body.project_id });
+    }
+    projectJson = openProject.projectJson;
+  } else {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, 'Expected project_id or project');
+  }
+  try {
+    buildPlan(projectJson);
+  } catch (error) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Invalid project: ${error.message}`);
+  }
+  // Width, height and fps come from the sources unless the request sets them
+  const settings = { format: 'mp4', ...(body.settings || {}) };
+
+  const exportId = `export_${crypto.randomBytes(6).toString('hex')}`;
+  const entry = { export_id: exportId, status: 'queued', job_id: null, result: null, error: null };
+  automationExports.set(exportId, entry);
+  for (const id of [...automationExports.keys()].slice(0, -MAX_AUTOMATION_EXPORTS)) {
+    automationExports.delete(id);
+  }
+
+  automationExportQueue = automationExportQueue.then(async () => {
+    entry.status = 'running';
+    try {
+      entry.result = await exportProjectJob(projectJson, settings, { automation_export: exportId });
+      entry.job_id = entry.result.job_id;
+      entry.status = 'completed';
+    } catch (error) {
+      const job = jobs.listJobs().find((candidate) => candidate.automation_export === exportId);
+      entry.job_id = job ? job.id : null;
+      entry.error = toStarscapeError(error).toJSON();
+      entry.status = 'failed';
+    }
+  });
+  return { export_id: exportId, status: entry.status };
+}
+
+const automationHandlers = {
+  status: () => ({
+    version: app.getVersion(),
+    open_project: openProject ? { id: openProject.id, name: openProject.name } : null,
+    recordings: [...activeRecordings.keys()],
+    exports: [...automationExports.values()].filter((entry) => entry.status === 'queued' || entry.status === 'running').length,
+  }),
+  listProjects: () => (openProject ? [{ id: openProject.id, name: openProject.name, updated_at: openProject.updatedAt }] : []),
+  enqueueExport: enqueueAutomationExport,
+  getExport: (exportId) => automationExports.get(exportId) || null,
+  // Same behavior as the recording hotkeys (start uses the active preset)
+  recording: async (action) => {
+    await hotkeyHandlers[`${action}-recording`](`${action}-recording`);
+    return { recording_id: latestRecordingId() };
+  },
+};
+
+/**
+ * Start the automation API when it is enabled (a taken port is logged, not fatal)
+ */
+async function startAutomationServer() {
+  const { enabled, port } = automation.getAutomationSettings();
+  if (!enabled || automationServer) return;
+  try {
+    automationServer = await automation.listen(automation.createAutomationServer(automationHandlers), port);
+    logger.info('Automation API listening', { port });
+  } catch (error) {
+    automationServer = null;
+    logger.error('Automation API failed to start', { port, error });
+  }
+}
+
+function stopAutomationServer() {
+  if (!automationServer) return;
+  automationServer.close();
+  automationServer = null;
+}
+
+/**
+ * Automation API settings ({ enabled, port, token }) and whether the server is listening
+ */
+ipcMain.handle('get-automation-settings', async () => {
+  try {
+    return { ...automation.getAutomationSettings(), running: !!automationServer };
+  } catch (error) {
+    throw toIpcError(error, 'Failed to get automation settings');
+  }
+});
+
+/**
+ * Turn the automation API on or off, move it to another port or issue a new token
+ */
+ipcMain.handle('set-automation-settings', async (event, changes = {}) => {
+  try {
+    const before = automation.getAutomationSettings();
+    const after = automation.setAutomationSettings(changes);
+    if (!after.enabled || after.port !== before.port) {
+      stopAutomationServer();
+    }
+    await startAutomationServer();
+    return { ...after, running: !!automationServer };
+  } catch (error) {
+    throw toIpcError(error, 'Failed to set automation settings');
+  }
+});
+
+/**
+ * Share the open project with the automation API (listed by /v1/projects, exported by id)
+ */
+ipcMain.handle('publish-open-project', async (event, projectJson) => {
+  try {
+    const project = JSON.parse(projectJson);
+    openProject = { id: project.id, name: project.projectName || project.id, projectJson, updatedAt: new Date().toISOString() };
+    return { published: true };
+  } catch (error) {
+    throw toIpcError(error, 'Failed to publish project');
+  }
+});
+
+// Descriptors skipped by the last plugin load
+let effectPluginErrors = [];
+
+function effectPluginsDir() {
+  return path.join(app.getPath('appData'), 'com.starscape.studio', 'plugins');
+}
+
+/**
+ * Load the effect plugin folder, checking filters against the installed ffmpeg when it can be probed
+ */
+async function loadEffectPlugins() {
+  const caps = await getFfmpegCapabilities().catch(() => null);
+  const loaded = await effectPlugins.loadEffectPlugins(effectPluginsDir(), caps ? caps.filters : null);
+  loaded.errors.forEach((entry) => logger.warn('Skipped effect plugin', entry));
+  effectPluginErrors = loaded.errors;
+  return { ...loaded, folder: effectPluginsDir() };
+}
+
+/**
+ * Effect plugins available to clips, plus the plugins folder
+ */
+ipcMain.handle('list-effect-plugins', async () => {
+  try {
+    return { plugins: effectPlugins.listEffectPlugins(), errors: effectPluginErrors, folder: effectPluginsDir() };
+  } catch (error) {
+    throw toIpcError(error, 'Failed to list effect plugins');
+  }
+});
+
+/**
+ * Re-read the plugins folder (after adding or editing descriptors); reports skipped files
+ */
+ipcMain.handle('reload-effect-plugins', async () => {
+  try {
+    return await loadEffectPlugins();
+  } catch (error) {
+    throw toIpcError(error, 'Failed to reload effect plugins');
+  }
+});
+
+/**
+ * Get recent structured log entries for diagnostics
+ */
+ipcMain.handle('get-recent-logs', async (event, level = 'info', limit = 200) => {
+  try {
+    return logger.getRecentLogs(level, limit);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to read logs');
+  }
+});
+
+/**
+ * Report whether ffmpeg and ffprobe were found, and which features work without the missing one
+ */
+ipcMain.handle('check-dependencies', async () => {
+  try {
+    return await checkDependencies();
+  } catch (error) {
+    throw toIpcError(error, 'Failed to check dependencies');
+  }
+});
+
+/**
+ * Get the encoders, decoders, filters and hwaccels of the installed ffmpeg build (cached)
+ */
+ipcMain.handle('get-ffmpeg-capabilities', async (event, refresh = false) => {
+  try {
+    return await getFfmpegCapabilities(refresh);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to probe ffmpeg capabilities');
+  }
+});
+
+/**
+ * List installed fonts (family, style, file) for caption styling (cached)
+ */
+ipcMain.handle('list-system-fonts', async (event, refresh = false) => {
+  try {
+    return await listSystemFonts(refresh);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to list system fonts');
+  }
+});
+
+/**
+ * Encode a synthetic clip with each encoder/preset to recommend export defaults
+ */
+ipcMain.handle('run-encode-benchmark', async () => {
+  const span = logger.span('benchmark.encode');
+  try {
+    const result = await runEncodeBenchmark(cacheDirs, trackProcess);
+    span.end({ recommended: result.recommended });
+    return result;
+  } catch (error) {
+    span.fail(error);
+    throw toIpcError(error, 'Failed to run encode benchmark');
+  }
+});
+
+/**
+ * Get per-phase timings, sizes and encode speed recorded for a job
+ */
+ipcMain.handle('get-job-metrics', async (event, jobId) => {
+  try {
+    const job = jobs.getJob(jobId);
+    if (!job) {
+      throw new StarscapeError(ErrorCode.JOB_NOT_FOUND, `Job not found: ${jobId}`, { job_id: jobId });
+    }
+    return {
+      job_id: job.id,
+      kind: job.kind,
+      status: job.status,
+      started_at: job.started_at,
+      finished_at: job.finished_at,
+      error: job.error,
+      metrics: job.metrics,
+      hooks: job.hooks || null,
+      checksums: job.checksums || null,
+    };
+  } catch (error) {
+    throw toIpcError(error, 'Failed to get job metrics');
+  }
+});
+
+/**
+ * Zip recent logs, ffmpeg build info, cache stats and the last job's commands for bug reports
+ */
+ipcMain.handle('create-diagnostics-bundle', async () => {
+  try {
+    return await createDiagnosticsBundle(cacheDirs, {
+      version: app.getVersion(),
+      electron: process.versions.electron,
+    });
+  } catch (error) {
+    throw toIpcError(error, 'Failed to create diagnostics bundle');
+  }
+});
+
+/**
+ * Get media metadata
+ * Probes time out after options.timeoutMs (default 10s) with a `timeout` error.
+ */
+ipcMain.handle('get-media-metadata', async (event, filePath, options = {}) => {
+  try {
+    const metadata = await probeMedia(toLongPath(pathPolicy.checkPath(filePath)), {
+      keyframes: !!options.keyframes,
+      cache: cacheDirs,
+      timeoutMs: options.timeoutMs,
+    });
+    return metadata;
+  } catch (error) {
+    throw toIpcError(error, 'Failed to get metadata');
+  }
+});
+
+/**
+ * Get metadata for many files in one call, probing a few at a time
+ * Returns one { path, metadata, error } per input path, in order; failures don't reject the batch.
+ */
+ipcMain.handle('get-media-metadata-batch', async (event, filePaths, options = {}) => {
+  try {
+    if (!Array.isArray(filePaths)) {
+      throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, 'Expected an array of file paths');
+    }
+    return await probeMediaBatch(filePaths, {
+      resolvePath: (filePath) => toLongPath(pathPolicy.checkPath(filePath)),
+      keyframes: !!options.keyframes,
+      cache: cacheDirs,
+      timeoutMs: options.timeoutMs,
+    });
+  } catch (error) {
+    throw toIpcError(error, 'Failed to get metadata');
+  }
+});
+
+/**
+ * Cancel every running metadata probe (their callers get a `cancelled` error)
+ */
+ipcMain.handle('cancel-media-probes', async () => {
+  try {
+    return { cancelled: cancelProbes() };
+  } catch (error) {
+    throw toIpcError(error, 'Failed to cancel probes');
+  }
+});
+
+/**
+ * Generate preview frame
+ */
+ipcMain.handle('generate-preview', async (event, projectJson, atMs) => {
+  try {
+    const plan = buildPlan(projectJson);
+    pathPolicy.checkPlanPaths(plan);
+    const visibleClip = findVisibleClip(plan, atMs);
+    
+    if (!visibleClip) {
+      throw new StarscapeError(ErrorCode.NOT_FOUND, 'No clip visible at this time', { at_ms: atMs });
+    }
+
+    // Calculate timestamp relative to clip source
+    const relativeMs = sourceTimeAt(visibleClip, atMs);
+    const outputPath = cacheDirs.previewFile(hashClip(visibleClip), relativeMs);
+
+    // Same clip content at the same source time renders the same frame
+    let url;
+    if (fs.existsSync(outputPath)) {
+      url = pathToFileUrl(outputPath);
+    } else if (visibleClip.generator) {
+      // Generators are synthesized at preview size, then read like any other source
+      const srcPath = await renderGenerator(visibleClip, PREVIEW_FORMAT, resolveEncoding(), cacheDirs, trackProcess);
+      url = await extractPosterFrame(srcPath, relativeMs, outputPath, 0, buildVideoEffectFilters(visibleClip.effects));
+    } else {
+      const { rotationDeg } = await resolveRotation(visibleClip.srcPath, visibleClip.rotationOverride);
+      url = await extractPosterFrame(visibleClip.srcPath, relativeMs, outputPath, rotationDeg, buildVideoEffectFilters(visibleClip.effects));
+    }
+    
+    return {
+      url,
+      ts: atMs,
+    };
+  } catch (error) {
+    throw toIpcError(error, 'Failed to generate preview');
+  }
+});
+
+/**
+ * Single source frame of an asset for the clip trimmer (no effects), cached per asset and frame
+ */
+ipcMain.handle('get-asset-frame', async (event, filePath, atMs, maxWidth = 320) => {
+  try {
+    const srcPath = pathPolicy.checkPath(filePath);
+    const meta = await probeMedia(srcPath, { cache: cacheDirs });
+    const { rotationDeg } = clipRotation(meta);
+    const result = await assetFrames.get(srcPath, atMs, maxWidth, {
+      fps: meta.fps,
+      extract: (src, seekMs, outputPath, width) =>
+        extractPosterFrame(src, seekMs, outputPath, rotationDeg, [`scale='min(${width},iw)':-2`]),
+    });
+    return { ...result, url: pathToFileUrl(result.path) };
+  } catch (error) {
+    throw toIpcError(error, 'Failed to get asset frame');
+  }
+});
+
+/**
+ * Open a scrub stream for a media file (reused while the same file/size/rate is open)
+ * Frames arrive as scrub-frame events; scrub-seek answers with the delivered frame's seq and time.
+ */
+ipcMain.handle('scrub-open', async (event, filePath, options = {}) => {
+  try {
+    const session = openScrubSession(toLongPath(pathPolicy.checkPath(filePath)), {
+      width: options.width,
+      fps: options.fps,
+      trackProcessFn: trackProcess,
+      onFrame: (frame) => emitEvent(event.sender, EventType.SCRUB_FRAME, frame),
+    });
+    return { session_id: session.id, width: session.format.width, fps: session.format.fps };
+  } catch (error) {
+    throw toIpcError(error, 'Failed to open scrub stream');
+  }
+});
+
+/**
+ * Move a scrub stream to a source time
+ */
+ipcMain.handle('scrub-seek', async (event, sessionId, atMs) => {
+  try {
+    return await getScrubSession(sessionId).seek(atMs);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to scrub');
+  }
+});
+
+/**
+ * Close a scrub stream and its ffmpeg process
+ */
+ipcMain.handle('scrub-close', async (event, sessionId) => {
+  try {
+    return { closed: closeScrubSession(sessionId) };
+  } catch (error) {
+    throw toIpcError(error, 'Failed to close scrub stream');
+  }
+});
+
+/**
+ * Check a project for problems before export
+ */
+ipcMain.handle('validate-plan', async (event, projectJson, settings = {}) => {
+  try {
+    pathPolicy.checkPlanPaths(buildPlan(projectJson));
+    return await validatePlan(projectJson, settings);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to validate project');
+  }
+});
+
+/**
+ * Timeline duration, per-track clip counts and gaps, and estimated export size per quality profile
+ */
+ipcMain.handle('get-plan-stats', async (event, projectJson, settings = {}) => {
+  try {
+    return getPlanStats(projectJson, settings);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to get plan stats');
+  }
+});
+
+/**
+ * Predict an export's render time and size from earlier exports or a short sample encode
+ * options.method: 'auto' (history, then a sample), 'history' or 'sample'
+ */
+ipcMain.handle('estimate-export', async (event, projectJson, settings = {}, options = {}) => {
+  try {
+    const plan = buildPlan(projectJson, { fps: settings.fps });
+    pathPolicy.checkPlanPaths(plan);
+    const resolved = applyQualityProfile(settings);
+    return await estimateExport(plan, settings, {
+      method: options.method,
+      jobList: jobs.listJobs(),
+      isImageFile,
+      resolveFormat: () => resolveOutputFormat(resolved, plan, (srcPath) => probeMedia(srcPath).catch(() => null)),
+      samplePath: cacheDirs.stagePath(plan.id, 'estimate', resolved.format === 'mov' || requiresMov(resolveEncoding(resolved)) ? 'mov' : 'mp4'),
+      trackProcessFn: trackProcess,
+    });
+  } catch (error) {
+    throw toIpcError(error, 'Failed to estimate export');
+  }
+});
+
+/**
+ * Export a project (or one of its clips) as one tracked job
+ * @param {Object} jobFields - Extra fields recorded on the job
+ * @param {Object} options - { clipId: export only this clip, see isolateClip }
+ */
+async function exportProjectJob(projectJson, settings, jobFields = {}, options = {}) {
+  const clipFields = options.clipId ? { clip_id: options.clipId } : {};
+  const job = jobs.startJob('export', { settings, ...clipFields, ...jobFields });
+  const span = logger.span('export.job', { job_id: job.id, ...clipFields, format: settings.format, width: settings.width, height: settings.height });
+  try {
+    requireBinary('ffmpeg', 'Export');
+    requireBinary('ffprobe', 'Export');
+    const fullPlan = buildPlan(projectJson, { fps: settings.fps });
+    const plan = options.clipId ? isolateClip(fullPlan, options.clipId) : fullPlan;
+    pathPolicy.checkPlanPaths(plan);
+    span.event('Plan built', { plan_id: plan.id, main_clips: plan.mainTrack.length, overlay_clips: plan.overlayTrack.length, audio_clips: plan.audioTrack.length });
+    const { metrics, ...rendered } = await executeExportJob(plan, settings, cacheDirs, mainWindow, jobs.trackingFor(job, trackProcess));
+    const result = await runExportHooksFor(job, settings, rendered);
+    jobs.finishJob(job, null, metrics);
+    span.end({ output: result.path, size_bytes: result.size_bytes, duration_ms_output: result.duration_ms, ...metrics.phases_ms });
+    return { ...result, job_id: job.id };
+  } catch (error) {
+    jobs.finishJob(job, error);
+    span.fail(error);
+    throw error;
+  }
+}
+
+/**
+ * Export project
+ */
+ipcMain.handle('export-project', async (event, projectJson, settings) => {
+  try {
+    return await exportProjectJob(projectJson, settings);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to export project');
+  }
+});
+
+/**
+ * Fill a template project's {{field}} placeholders from data records and export one video per record
+ * Every record is checked before anything renders; records then export one after another, and a
+ * failed render is reported in its entry without stopping the rest. Settings (filename template,
+ * metadata) can carry placeholders too.
+ * Returns one { index, result, error } per record, in order.
+ */
+ipcMain.handle('assemble-from-template', async (event, templateJson, data, settings = {}) => {
+  try {
+    const records = Array.isArray(data) ? data : [data];
+    const filled = records.map((record) => ({
+      projectJson: fillTemplate(templateJson, record),
+      settings: fillTemplateValue(settings, record),
+    }));
+
+    const results = [];
+    for (const [index, entry] of filled.entries()) {
+      try {
+        results.push({ index, result: await exportProjectJob(entry.projectJson, entry.settings, { template_record: index }), error: null });
+      } catch (error) {
+        logger.warn('Template record failed to export', { index, error: error.message });
+        results.push({ index, result: null, error: toStarscapeError(error).toJSON() });
+      }
+    }
+    return results;
+  } catch (error) {
+    throw toIpcError(error, 'Failed to assemble from template');
+  }
+});
+
+/**
+ * Run the export's quality-profile hooks on its output and keep their results on the job
+ * The returned result points at wherever the hooks left the file (a move hook relocates it).
+ */
+async function runExportHooksFor(job, settings, result) {
+  if (result.checksums) {
+    job.checksums = result.checksums;
+  }
+  const actions = exportHooks.hooksForPreset(settings.qualityProfile);
+  if (actions.length === 0) {
+    return result;
+  }
+  const hooked = await exportHooks.runExportHooks(urlToPath(result.path), actions, {
+    reveal: (filePath) => shell.showItemInFolder(filePath),
+    trackProcessFn: jobs.trackingFor(job, trackProcess),
+    checksums: result.checksums,
+    checkTarget: pathPolicy.checkHookTarget,
+  });
+  job.hooks = hooked.results;
+  for (const failed of hooked.results.filter((entry) => !entry.success)) {
+    logger.warn('Export hook failed', { job_id: job.id, type: failed.type, error: failed.error });
+  }
+  return { ...result, path: pathToFileUrl(hooked.path), hooks: hooked.results };
+}
+
+/**
+ * Export one clip on its own, with its trims, rotation, effects and audio effects applied
+ */
+ipcMain.handle('export-clip', async (event, projectJson, clipId, settings) => {
+  try {
+    return await exportProjectJob(projectJson, settings, {}, { clipId });
+  } catch (error) {
+    throw toIpcError(error, 'Failed to export clip');
+  }
+});
+
+/**
+ * Encode a trimmed clip's head/tail handles ahead of export (called after a trim)
+ * Exports with the same encoding then only stream-copy the clip's middle.
+ */
+ipcMain.handle('precompute-clip-handles', async (event, filePath, inMs, outMs, settings = {}) => {
+  try {
+    const srcPath = pathPolicy.checkPath(filePath);
+    const meta = await probeMedia(srcPath, { keyframes: true, cache: cacheDirs });
+    const handles = await ensureClipHandles(srcPath, inMs, outMs, meta, resolveEncoding(applyQualityProfile(settings)), cacheDirs, trackProcess);
+    if (!handles) {
+      return { smart_render: false, head_ms: 0, tail_ms: 0, copied_ms: 0, cached: false };
+    }
+    return {
+      smart_render: true,
+      head_ms: handles.headEndMs - inMs,
+      tail_ms: outMs - handles.tailStartMs,
+      copied_ms: handles.tailStartMs - handles.headEndMs,
+      cached: [handles.head, handles.tail].every((handle) => !handle || handle.cached),
+    };
+  } catch (error) {
+    throw toIpcError(error, 'Failed to precompute clip handles');
+  }
+});
+
+/**
+ * Copy a file's streams into another container (no re-encode), written to the projects folder
+ */
+ipcMain.handle('remux-file', async (event, filePath, container, options = {}) => {
+  try {
+    const inputPath = pathPolicy.checkPath(filePath);
+    const target = REMUX_CONTAINERS[container];
+    if (!target) {
+      throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Unsupported container: ${container}`, { container });
+    }
+    // An explicit filename replaces any earlier file; the default name is numbered so a second remux keeps the first
+    const outputPath = options.filename
+      ? cacheDirs.renderOutputPathWithFilename(options.filename, target.ext)
+      : cacheDirs.uniqueRenderOutputPath(remuxFilename(inputPath), target.ext);
+    await remuxFile(inputPath, outputPath, { container, streams: options.streams || 'all' }, trackProcess);
+    const stats = await fs.promises.stat(outputPath);
+    return { path: outputPath, url: pathToFileUrl(outputPath), size_bytes: stats.size };
+  } catch (error) {
+    throw toIpcError(error, 'Failed to remux file');
+  }
+});
+
+/**
+ * Cut a file directly, without a project (keyframe copy, or an accurate re-encode)
+ * Writes to outPath (inside the app's folders) or next to exports as <source>_trim (numbered when taken).
+ */
+ipcMain.handle('quick-trim', async (event, filePath, inMs, outMs, outPath = null, reencode = false) => {
+  try {
+    const inputPath = pathPolicy.checkPath(filePath);
+    const outputPath = outPath
+      ? pathPolicy.checkPath(outPath, 'write')
+      : cacheDirs.uniqueRenderOutputPath(trimFilename(inputPath), reencode ? 'mp4' : path.extname(inputPath).slice(1) || 'mp4');
+    await quickTrim(inputPath, outputPath, { inMs, outMs, reencode: !!reencode, encoding: resolveEncoding() }, trackProcess);
+    const stats = await fs.promises.stat(outputPath);
+    return { path: outputPath, url: pathToFileUrl(outputPath), size_bytes: stats.size };
+  } catch (error) {
+    throw toIpcError(error, 'Failed to trim file');
+  }
+});
+
+/**
+ * Join files end to end without a project (stream copy when they match, else normalized and re-encoded)
+ * Writes to outPath (inside the app's folders) or next to exports as <first file>_merged (numbered when taken).
+ */
+ipcMain.handle('concat-files', async (event, filePaths, outPath = null, options = {}) => {
+  try {
+    const inputPaths = (filePaths || []).map((filePath) => pathPolicy.checkPath(filePath));
+    const entries = [];
+    for (const inputPath of inputPaths) {
+      entries.push({ path: inputPath, meta: await probeMedia(inputPath, { cache: cacheDirs }) });
+    }
+    const first = inputPaths[0] || '';
+    const outputPath = outPath
+      ? pathPolicy.checkPath(outPath, 'write')
+      : cacheDirs.uniqueRenderOutputPath(`${path.basename(first, path.extname(first))}_merged`, path.extname(first).slice(1) || 'mp4');
+    const result = await concatFiles(entries, outputPath, { encoding: resolveEncoding(), reencode: !!options.reencode }, trackProcess);
+    const stats = await fs.promises.stat(outputPath);
+    return { path: outputPath, url: pathToFileUrl(outputPath), size_bytes: stats.size, ...result };
+  } catch (error) {
+    throw toIpcError(error, 'Failed to merge files');
+  }
+});
+
+/**
+ * Extract a file's audio (stream copy or transcode) and ingest it as a new audio asset
+ */
+ipcMain.handle('extract-audio', async (event, filePath, codec = 'copy') => {
+  try {
+    const inputPath = pathPolicy.checkPath(filePath);
+    const meta = await probeMedia(inputPath, { cache: cacheDirs });
+    if (!meta.has_audio) {
+      throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, 'File has no audio to extract', { path: filePath });
+    }
+    const { ext, args } = audioExtractTarget(codec, meta.codec_audio);
+    const audioPath = cacheDirs.extractedAudioPath(path.basename(inputPath, path.extname(inputPath)), ext);
+    try {
+      await extractAudio(inputPath, audioPath, args, trackProcess);
+      const [result] = await ingestFiles([audioPath], cacheDirs);
+      return result;
+    } finally {
+      await fs.promises.rm(audioPath, { force: true });
+    }
+  } catch (error) {
+    throw toIpcError(error, 'Failed to extract audio');
+  }
+});
+
+/**
+ * Import an OBS recording as a video asset plus one audio asset per track, with each track's offset from the video
+ * Variable frame rate video is re-encoded at a constant rate (options.conform: auto, always or never).
+ */
+ipcMain.handle('import-obs-recording', async (event, filePath, options = {}) => {
+  const job = jobs.startJob('obs-import', { path: filePath });
+  try {
+    const result = await importObsRecording(pathPolicy.checkPath(filePath), cacheDirs, {
+      conform: options.conform,
+      frameRate: options.frame_rate,
+      encoding: resolveEncoding(),
+      trackProcessFn: jobs.trackingFor(job, trackProcess),
+    });
+    jobs.finishJob(job);
+    return { ...result, job_id: job.id };
+  } catch (error) {
+    jobs.finishJob(job, error);
+    throw toIpcError(error, 'Failed to import recording');
+  }
+});
+
+/**
+ * Extract a still frame from a video and ingest it as a new image asset
+ */
+ipcMain.handle('extract-still', async (event, filePath, atMs) => {
+  try {
+    const stillPath = await extractStill(pathPolicy.checkPath(filePath), atMs, cacheDirs.stillOutputPath());
+    const [result] = await ingestFiles([stillPath], cacheDirs);
+    await fs.promises.unlink(stillPath);
+    return result;
+  } catch (error) {
+    throw toIpcError(error, 'Failed to extract still');
+  }
+});
+
+/**
+ * Generate a tiled JPEG of evenly spaced frames from a file or project timeline
+ */
+ipcMain.handle('generate-contact-sheet', async (event, source, columns = 4, rows = 4, intervalMs = null) => {
+  try {
+    if (source && source.path) {
+      pathPolicy.checkPath(source.path);
+    } else if (source && source.projectJson) {
+      pathPolicy.checkPlanPaths(buildPlan(source.projectJson));
+    }
+    const result = await generateContactSheet(source, columns, rows, intervalMs, cacheDirs);
+    return { ...result, url: pathToFileUrl(result.path) };
+  } catch (error) {
+    throw toIpcError(error, 'Failed to generate contact sheet');
+  }
+});
+
+/**
+ * Quick low-res render of just the caption layer over black, for proofreading subtitles
+ */
+ipcMain.handle('render-captions-preview', async (event, projectJson, options = {}) => {
+  try {
+    const plan = buildPlan(projectJson);
+    const outputPath = cacheDirs.renderOutputPath(`${plan.id}_captions`, 'mp4');
+    const result = await renderCaptionsPreview(plan, outputPath, cacheDirs.stagePath(plan.id, 'captions_preview', 'ass'), options, trackProcess);
+    return { ...result, path: outputPath, url: pathToFileUrl(outputPath) };
+  } catch (error) {
+    throw toIpcError(error, 'Failed to render captions preview');
+  }
+});
+
+/**
+ * List title and lower-third templates (fields, default colors, logo slot, animation)
+ */
+ipcMain.handle('list-title-templates', async () => {
+  try {
+    return listTitleTemplates();
+  } catch (error) {
+    throw toIpcError(error, 'Failed to list title templates');
+  }
+});
+
+/**
+ * Render a title template to a transparent overlay clip (cached by its parameters)
+ */
+ipcMain.handle('render-title', async (event, params) => {
+  try {
+    const logoPath = params && params.logoPath ? pathPolicy.checkPath(params.logoPath) : null;
+    const result = await renderTitle({ ...params, logoPath }, cacheDirs, trackProcess);
+    return { ...result, url: pathToFileUrl(result.path) };
+  } catch (error) {
+    throw toIpcError(error, 'Failed to render title');
+  }
+});
+
+/**
+ * Compute the sync offset between two recordings by cross-correlating their audio
+ */
+ipcMain.handle('align-clips', async (event, pathA, pathB, options = {}) => {
+  try {
+    return await alignClips(pathPolicy.checkPath(pathA), pathPolicy.checkPath(pathB), options);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to align clips');
+  }
+});
+
+/**
+ * Detect tempo, beats and onsets in a file's audio (for snapping cuts to music)
+ */
+ipcMain.handle('analyze-beats', async (event, filePath, options = {}) => {
+  try {
+    return await analyzeBeats(pathPolicy.checkPath(filePath), options);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to analyze beats');
+  }
+});
+
+/**
+ * Speech / non-speech segments of a file's audio (voice activity detection)
+ */
+ipcMain.handle('detect-speech', async (event, filePath, options = {}) => {
+  try {
+    return await detectSpeech(pathPolicy.checkPath(filePath), options);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to detect speech');
+  }
+});
+
+/**
+ * Suggest highlight ranges in a long recording from loudness spikes, scene changes and transcript keywords
+ */
+ipcMain.handle('suggest-highlights', async (event, filePath, options = {}) => {
+  const job = jobs.startJob('highlights', { path: filePath });
+  try {
+    const inputPath = pathPolicy.checkPath(filePath);
+    const info = await probeMedia(inputPath, { cache: cacheDirs });
+    const result = await suggestHighlights(inputPath, info, options, jobs.trackingFor(job, trackProcess));
+    jobs.finishJob(job);
+    return { ...result, job_id: job.id };
+  } catch (error) {
+    jobs.finishJob(job, error);
+    throw toIpcError(error, 'Failed to suggest highlights');
+  }
+});
+
+/**
+ * Find the active picture of a video with black bars baked in (path or project asset URL)
+ */
+ipcMain.handle('detect-crop', async (event, filePath) => {
+  try {
+    const inputPath = pathPolicy.checkPath(resolveAssetSrc(filePath));
+    const info = await probeMedia(inputPath, { cache: cacheDirs });
+    return await detectCrop(inputPath, info, trackProcess);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to detect crop');
+  }
+});
+
+/**
+ * Measure how far a recording's audio drifts from its video (path or project asset URL)
+ * The suggested correction goes on the clip as audioSync and is applied when its segment renders.
+ */
+ipcMain.handle('measure-av-drift', async (event, filePath) => {
+  try {
+    return await measureAvDrift(pathPolicy.checkPath(resolveAssetSrc(filePath)));
+  } catch (error) {
+    throw toIpcError(error, 'Failed to measure audio drift');
+  }
+});
+
+/**
+ * Measure each dialogue clip's loudness and suggest the gain that levels them all
+ */
+ipcMain.handle('analyze-clip-gain', async (event, projectJson, options = {}) => {
+  const job = jobs.startJob('gain-analysis', {});
+  try {
+    const plan = buildPlan(projectJson);
+    pathPolicy.checkPlanPaths(plan);
+    const probeSource = (srcPath) => probeMedia(srcPath, { cache: cacheDirs }).catch(() => null);
+    const result = await analyzeClipGain(plan, options, probeSource, jobs.trackingFor(job, trackProcess));
+    jobs.finishJob(job);
+    return { ...result, job_id: job.id };
+  } catch (error) {
+    jobs.finishJob(job, error);
+    throw toIpcError(error, 'Failed to analyze clip gain');
+  }
+});
+
+/**
+ * Peak data of the whole program's mixed audio (trims, loops, effects and ducking applied)
+ */
+ipcMain.handle('generate-timeline-waveform', async (event, projectJson, buckets = 1000) => {
+  try {
+    const plan = buildPlan(projectJson);
+    pathPolicy.checkPlanPaths(plan);
+    const materialized = await materializeGenerators(plan, PREVIEW_FORMAT, resolveEncoding(), cacheDirs, trackProcess);
+    const probeSource = (srcPath) => probeMedia(srcPath, { cache: cacheDirs }).catch(() => null);
+    return await generateTimelineWaveform(materialized, buckets, probeSource);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to generate timeline waveform');
+  }
+});
+
+/**
+ * Render a short audition of audio effects applied to a source file
+ */
+ipcMain.handle('preview-audio-effect', async (event, filePath, effects, startMs = 0, durationMs = 5000) => {
+  try {
+    const outputPath = cacheDirs.audioPreviewFile(Date.now());
+    for (const effect of effects || []) {
+      if (effect && effect.model) pathPolicy.checkPath(effect.model);
+    }
+    const url = await renderAudioEffectPreview(pathPolicy.checkPath(filePath), effects, startMs, durationMs, outputPath);
+    return { url };
+  } catch (error) {
+    throw toIpcError(error, 'Failed to preview audio effect');
+  }
+});
+
+/**
+ * Convert WebM to MP4 using ffmpeg
+ */
+function convertWebmToMp4(inputPath, outputPath) {
+  return new Promise((resolve, reject) => {
+    const ffmpeg = require('fluent-ffmpeg');
+    
+    console.log(`Converting WebM to MP4: ${inputPath} -> ${outputPath}`);
+    
+    const command = ffmpeg(inputPath)
+      .videoCodec('libx264')
+      .audioCodec('aac')
+      .outputOptions([
+        '-preset fast',
+        '-crf 23',
+        '-movflags +faststart'
+      ])
+      .output(outputPath)
+      .on('start', (commandLine) => {
+        console.log('FFmpeg command:', commandLine);
+      })
+      .on('progress', (progress) => {
+        if (progress.percent) {
+          console.log(`Conversion progress: ${Math.round(progress.percent)}%`);
+        }
+      })
+      .on('end', () => {
+        console.log('WebM to MP4 conversion completed');
+        resolve(outputPath);
+      })
+      .on('error', (err) => {
+        console.error('Conversion error:', err);
+        reject(new Error(`FFmpeg conversion failed: ${err.message}`));
+      });
+    
+    // Track the process
+    const process = command.run();
+    trackProcess(process);
+  });
+}
+
+/**
+ * Convert WebM audio to MP3 using ffmpeg
+ */
+function convertWebmToMp3(inputPath, outputPath) {
+  return new Promise((resolve, reject) => {
+    const ffmpeg = require('fluent-ffmpeg');
+    
+    console.log(`Converting WebM audio to MP3: ${inputPath} -> ${outputPath}`);
+    
+    const command = ffmpeg(inputPath)
+      .audioCodec('libmp3lame')
+      .audioBitrate('192k')
+      .audioChannels(2)
+      .audioFrequency(44100)
+      .output(outputPath)
+      .on('start', (commandLine) => {
+        console.log('FFmpeg command:', commandLine);
+      })
+      .on('progress', (progress) => {
+        if (progress.percent) {
+          console.log(`Conversion progress: ${Math.round(progress.percent)}%`);
+        }
+      })
+      .on('end', () => {
+        console.log('WebM to MP3 conversion completed');
+        resolve(outputPath);
+      })
+      .on('error', (err) => {
+        console.error('Conversion error:', err);
+        reject(new Error(`FFmpeg audio conversion failed: ${err.message}`));
+      });
+    
+    // Track the process
+    const process = command.run();
+    trackProcess(process);
+  });
+}
+
+/**
+ * Save blob data to file (converts WebM to MP4 for video, MP3 for audio)
+ */
+ipcMain.handle('save-blob-to-file', async (event, blobData, filename) => {
+  try {
+    const fs = require('fs');
+    const path = require('path');
+    const buffer = Buffer.from(blobData);
+    
+    // Construct full path in cache/media directory
+    const webmPath = path.join(cacheDirs.mediaDir, pathPolicy.checkFileName(filename));
+    
+    // Save the blob to the webm file
+    await fs.promises.writeFile(webmPath, buffer);
+    console.log(`Saved WebM recording to: ${webmPath}`);
+    
+    // Check if it's a microphone recording (audio-only) by filename
+    if (filename.startsWith('microphone_recording_')) {
+      // For audio recordings, convert to MP3
+      const mp3Filename = filename.replace('.webm', '.mp3');
+      const mp3Path = path.join(cacheDirs.mediaDir, mp3Filename);
+      
+      await convertWebmToMp3(webmPath, mp3Path);
+      
+      // Delete the original WebM file
+      await fs.promises.unlink(webmPath);
+      console.log(`Deleted temporary WebM file: ${webmPath}`);
+      
+      return { success: true, path: mp3Path };
+    } else {
+      // For video recordings (webcam/screen), convert to MP4
+      const mp4Filename = filename.replace('.webm', '.mp4');
+      const mp4Path = path.join(cacheDirs.mediaDir, mp4Filename);
+      
+      await convertWebmToMp4(webmPath, mp4Path);
+      
+      // Delete the original WebM file
+      await fs.promises.unlink(webmPath);
+      console.log(`Deleted temporary WebM file: ${webmPath}`);
+      
+      // Probe, thumbnail and waveform so the recording is ready to edit; the saved file stands either way
+      let processed = null;
+      try {
+        processed = (await runPostRecordJob(event.sender, path.basename(filename, '.webm'), [mp4Path])).files[0];
+      } catch (postError) {
+        logger.warn('Recording post-processing failed', { path: mp4Path, error: String(postError) });
+      }
+      
+      return { success: true, path: mp4Path, processed };
+    }
+  } catch (error) {
+    throw toIpcError(error, 'Failed to save blob to file');
+  }
+});
+
+/**
+ * Queue a background job converting an ingested asset under the ingest rules
+ * The original stays in the library; the renderer switches the asset over on the completed event.
+ * @returns {string} Job id
+ */
+function startIngestTranscodeJob(target, asset, transcode) {
+  const job = jobs.startJob('ingest-transcode', { asset_id: asset.asset_id, reasons: transcode.reasons });
+  const emit = (fields) => emitEvent(target, EventType.INGEST_TRANSCODE, { job_id: job.id, asset_id: asset.asset_id, ...fields });
+  const outputPath = path.join(cacheDirs.mediaDir, `${asset.asset_id}_converted.${transcode.ext}`);
+  const trackProcessFn = jobs.trackingFor(job, trackProcess);
+
+  emit({ status: 'queued', progress: 0, reasons: transcode.reasons });
+  ingestTranscodeQueue = ingestTranscodeQueue.then(async () => {
+    try {
+      emit({ status: 'running', progress: 0 });
+      await ingestRules.runIngestTranscode(asset.file_path, outputPath, transcode, asset.metadata,
+        (progress) => emit({ status: 'running', progress }), trackProcessFn);
+      const metadata = await probeMedia(outputPath, { cache: cacheDirs });
+      const stats = await fs.promises.stat(outputPath);
+      jobs.finishJob(job);
+      emit({ status: 'completed', progress: 1, file_path: outputPath, library_url: toLibraryUrl(outputPath), file_size: stats.size, metadata });
+    } catch (error) {
+      await fs.promises.rm(outputPath, { force: true }).catch(() => {});
+      jobs.finishJob(job, error);
+      logger.warn('Ingest transcode failed', { asset_id: asset.asset_id, error: String(error.message || error) });
+      emit({ status: 'failed', error: String(error.message || error) });
+    }
+  });
+  return job.id;
+}
+
+/**
+ * Phone profile fixups for ingested files, converted in the same background job as the ingest rules
+ */
+async function applyPhoneProfile(target, results, rules) {
+  const caps = await getFfmpegCapabilities().catch(() => null);
+  const toneMap = !!caps && hasFilter(caps, 'zscale') && hasFilter(caps, 'tonemap');
+  return results.map((result) => {
+    const { fixups, transcode } = planPhoneFixups(getAssetType(result.file_path, result.metadata), result.file_path, result.metadata, { rules, toneMap });
+    if (fixups) {
+      logger.info('Phone footage fixups', { asset_id: result.asset_id, hdr: fixups.hdr, changes: fixups.changes });
+    }
+    const fixed = fixups ? { ...result, phone_fixups: fixups } : result;
+    return transcode ? { ...fixed, transcode_job_id: startIngestTranscodeJob(target, result, transcode) } : fixed;
+  });
+}
+
+/**
+ * Ingest files (copied into the library, or linked in place with mode: 'link')
+ * Files matching an ingest rule come back with transcode_job_id; their conversion runs afterwards.
+ * profile: 'phone' adds the phone footage fixups (HDR, HEVC, rotation, slow-mo, spatial audio).
+ */
+ipcMain.handle('ingest-files', async (event, request) => {
+  try {
+    const { file_paths, mode, copy_external, profile } = request;
+    const results = await ingestFiles(
+      file_paths.map((filePath) => pathPolicy.checkPath(filePath)),
+      cacheDirs,
+      (progress) => emitEvent(event.sender, EventType.INGEST_PROGRESS, progress),
+      { mode, copyExternal: copy_external }
+    );
+    const rules = ingestRules.getIngestRules();
+    if (rules.length === 0 && profile !== 'phone') {
+      return results;
+    }
+    if (!resolveBinary('ffmpeg').path) {
+      logger.warn('Skipping ingest rules: ffmpeg not found');
+      return results;
+    }
+    if (profile === 'phone') {
+      return await applyPhoneProfile(event.sender, results, rules);
+    }
+    return results.map((result) => {
+      const transcode = ingestRules.planIngestTranscode(getAssetType(result.file_path, result.metadata), result.file_path, result.metadata, rules);
+      return transcode ? { ...result, transcode_job_id: startIngestTranscodeJob(event.sender, result, transcode) } : result;
+    });
+  } catch (error) {
+    throw toIpcError(error, 'Failed to ingest files');
+  }
+});
+
+/**
+ * Accept an edited project, rejecting changes to locked tracks and clips
+ * Every project change from the renderer comes through here and is diffed against the last
+ * project accepted, so locks hold whatever the caller sends.
+ */
+ipcMain.handle('apply-edits', async (event, projectJson) => {
+  try {
+    applyEdits(projectJson, acceptedProjectJson);
+    acceptedProjectJson = projectJson;
+    return { success: true };
+  } catch (error) {
+    throw toIpcError(error, 'Failed to apply edits');
+  }
+});
+
+/**
+ * Delete a timeline range and pull later clips left
+ */
+ipcMain.handle('ripple-delete-range', async (event, projectJson, startMs, endMs, trackIds = null) => {
+  try {
+    return rippleDeleteRange(projectJson, startMs, endMs, trackIds);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to ripple delete');
+  }
+});
+
+/**
+ * Remove the gaps between clips on a track
+ */
+ipcMain.handle('close-gaps', async (event, projectJson, trackId) => {
+  try {
+    return closeGaps(projectJson, trackId);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to close gaps');
+  }
+});
+
+/**
+ * Split a clip at a timeline position
+ */
+ipcMain.handle('split-clip-at', async (event, projectJson, clipId, atMs) => {
+  try {
+    return splitClipAt(projectJson, clipId, atMs);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to split clip');
+  }
+});
+
+/**
+ * Add a point or range marker to a project
+ */
+ipcMain.handle('add-marker', async (event, projectJson, marker) => {
+  try {
+    return addMarker(projectJson, marker);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to add marker');
+  }
+});
+
+/**
+ * List a project's markers in timeline order
+ */
+ipcMain.handle('list-markers', async (event, projectJson) => {
+  try {
+    return listMarkers(projectJson);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to list markers');
+  }
+});
+
+/**
+ * Export the timeline, markers included, as a CMX 3600 EDL or OpenTimelineIO document
+ */
+ipcMain.handle('export-timeline', async (event, projectJson, format, options = {}) => {
+  try {
+    if (!TIMELINE_FORMATS.includes(format)) {
+      throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Unknown timeline format: ${format}`, { format });
+    }
+    const plan = buildPlan(projectJson, { fps: options.fps });
+    return format === 'edl' ? buildEdl(plan, options) : buildOtio(plan, options);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to export timeline');
+  }
+});
+
+/**
+ * Snap clip and marker times to exact frame boundaries
+ */
+ipcMain.handle('quantize-project', async (event, projectJson, fps) => {
+  try {
+    return quantizeProject(projectJson, fps);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to quantize project');
+  }
+});
+
+/**
+ * Convert milliseconds to SMPTE timecode
+ */
+ipcMain.handle('ms-to-timecode', async (event, ms, fps, options = {}) => {
+  try {
+    return msToTimecode(ms, fps, options);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to convert to timecode');
+  }
+});
+
+/**
+ * Convert SMPTE timecode to milliseconds
+ */
+ipcMain.handle('timecode-to-ms', async (event, timecode, fps, options = {}) => {
+  try {
+    return timecodeToMs(timecode, fps, options);
+  } catch (error) {
+    throw toIpcError(error, 'Failed to parse timecode');
   }
 });
 
@@ -691,13 +2268,14 @@ ipcMain.handle('open-file-dialog', async () => {
     });
     
     if (!result.canceled && result.filePaths.length > 0) {
+      pathPolicy.grant(result.filePaths);
       return { filePaths: result.filePaths };
     }
     
     return { filePaths: [] };
   } catch (error) {
     console.error('Error opening file dialog:', error);
-    throw new Error(`Failed to open file dialog: ${error.message}`);
+    throw toIpcError(error, 'Failed to open file dialog');
   }
 });
 
@@ -706,11 +2284,11 @@ ipcMain.handle('open-file-dialog', async () => {
  */
 ipcMain.handle('reveal-in-finder', async (event, filePath) => {
   try {
-    shell.showItemInFolder(filePath);
+    shell.showItemInFolder(pathPolicy.checkPath(filePath));
     return { success: true };
   } catch (error) {
     console.error('Error revealing file in finder:', error);
-    throw new Error(`Failed to reveal file: ${error.message}`);
+    throw toIpcError(error, 'Failed to reveal file');
   }
 });
 
@@ -720,7 +2298,7 @@ ipcMain.handle('reveal-in-finder', async (event, filePath) => {
 ipcMain.handle('delete-file', async (event, filePath) => {
   try {
     const fs = require('fs');
-    await fs.promises.unlink(filePath);
+    await fs.promises.unlink(pathPolicy.checkPath(filePath, 'write'));
     console.log(`Deleted file: ${filePath}`);
     return { success: true };
   } catch (error) {
@@ -730,7 +2308,7 @@ ipcMain.handle('delete-file', async (event, filePath) => {
       return { success: true };
     }
     console.error('Error deleting file:', error);
-    throw new Error(`Failed to delete file: ${error.message}`);
+    throw toIpcError(error, 'Failed to delete file');
   }
 });
 
@@ -849,7 +2427,7 @@ ipcMain.handle('generate-image', async (event, userPrompt) => {
     return { success: true, path: outputPath };
   } catch (error) {
     console.error('Error generating image:', error);
-    throw new Error(`Failed to generate image: ${error.message}`);
+    throw toIpcError(error, 'Failed to generate image');
   }
 });
 
@@ -0,0 +1,167 @@
+const ffmpeg = require('fluent-ffmpeg');
+const fs = require('fs-extra');
+const { parseProject } = require('./edits');
+const { ErrorCode, StarscapeError } = require('./errors');
+
+// Same as the frontend's addMarker
+const DEFAULT_MARKER_COLOR = '#f5a524';
+
+/**
+ * Normalize project markers (object map or array) into a sorted list
+ * @param {Object|Array} markers - Project markers keyed by id
+ * @returns {Array} [{ id, name, color, timeMs, durationMs? }] sorted by timeMs
+ */
+function normalizeMarkers(markers) {
+  const list = Array.isArray(markers) ? markers : Object.values(markers || {});
+  return list
+    .filter((m) => m && Number.isFinite(m.timeMs) && m.timeMs >= 0)
+    .map((m) => {
+      const marker = {
+        id: m.id,
+        name: m.name || '',
+        color: m.color || null,
+        timeMs: m.timeMs,
+      };
+      if (Number.isFinite(m.durationMs) && m.durationMs > 0) {
+        marker.durationMs = m.durationMs;
+      }
+      return marker;
+    })
+    .sort((a, b) => a.timeMs - b.timeMs);
+}
+
+/**
+ * Add a marker to a project
+ * @param {string} projectJson - Project JSON
+ * @param {Object} marker - { timeMs, name?, color?, durationMs? } (a duration makes it a range)
+ * @returns {{ project: string, marker_id: string }}
+ */
+function addMarker(projectJson, marker = {}) {
+  const project = parseProject(projectJson);
+  if (!Number.isFinite(marker.timeMs) || marker.timeMs < 0) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Invalid marker time: ${marker.timeMs}`);
+  }
+  if (marker.durationMs !== undefined && marker.durationMs !== null && !(Number.isFinite(marker.durationMs) && marker.durationMs >= 0)) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Invalid marker duration: ${marker.durationMs}`);
+  }
+
+  const markers = project.markers || {};
+  const count = Array.isArray(markers) ? markers.length : Object.keys(markers).length;
+  const markerId = Math.random().toString(36).substr(2, 9);
+  const added = {
+    id: markerId,
+    name: marker.name || `Marker ${count + 1}`,
+    color: marker.color || DEFAULT_MARKER_COLOR,
+    timeMs: marker.timeMs,
+  };
+  if (marker.durationMs > 0) {
+    added.durationMs = marker.durationMs;
+  }
+  if (Array.isArray(markers)) {
+    markers.push(added);
+  } else {
+    markers[markerId] = added;
+  }
+  project.markers = markers;
+  return { project: JSON.stringify(project), marker_id: markerId };
+}
+
+/**
+ * A project's markers, sorted by time
+ * @param {string} projectJson - Project JSON
+ * @returns {Array} Normalized markers (see normalizeMarkers)
+ */
+function listMarkers(projectJson) {
+  return normalizeMarkers(parseProject(projectJson).markers);
+}
+
+/**
+ * Escape a value for the ffmetadata format
+ */
+function escapeMetadata(value) {
+  return String(value).replace(/([=;#\\\n])/g, '\\$1');
+}
+
+/**
+ * Convert markers into chapter ranges on the export clock
+ * Point markers run until the next marker (or the end); range markers keep their duration.
+ * @param {Array} markers - Normalized markers
+ * @param {number} timelineStartMs - Timeline position that maps to t=0 in the output
+ * @param {number} durationMs - Output duration
+ * @returns {Array} [{ title, startMs, endMs }]
+ */
+function buildChapters(markers, timelineStartMs, durationMs) {
+  const chapters = [];
+  markers.forEach((marker, i) => {
+    const startMs = Math.round(marker.timeMs - timelineStartMs);
+    if (startMs < 0 || startMs >= durationMs) return;
+
+    const next = markers[i + 1];
+    const defaultEnd = next ? next.timeMs - timelineStartMs : durationMs;
+    const endMs = marker.durationMs ? startMs + marker.durationMs : defaultEnd;
+
+    chapters.push({
+      title: marker.name || `Chapter ${chapters.length + 1}`,
+      startMs,
+      endMs: Math.round(Math.min(durationMs, Math.max(startMs + 1, endMs))),
+    });
+  });
+  return chapters;
+}
+
+/**
+ * Render chapters as an ffmetadata document
+ */
+function buildChapterMetadata(chapters) {
+  const lines = [';FFMETADATA1'];
+  for (const chapter of chapters) {
+    lines.push(
+      '[CHAPTER]',
+      'TIMEBASE=1/1000',
+      `START=${chapter.startMs}`,
+      `END=${chapter.endMs}`,
+      `title=${escapeMetadata(chapter.title)}`
+    );
+  }
+  return `${lines.join('\n')}\n`;
+}
+
+/**
+ * Remux a file with chapter markers (streams are copied)
+ * @param {string} inputPath - Rendered program
+ * @param {Array} chapters - Chapters from buildChapters
+ * @param {string} metadataPath - Scratch path for the ffmetadata file
+ * @param {string} outputPath - Output file path
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ */
+async function embedChapters(inputPath, chapters, metadataPath, outputPath, trackProcessFn) {
+  await fs.writeFile(metadataPath, buildChapterMetadata(chapters), 'utf8');
+
+  try {
+    await new Promise((resolve, reject) => {
+      const command = ffmpeg()
+        .input(inputPath)
+        .input(metadataPath)
+        .outputOptions(['-map 0', '-map_metadata 0', '-map_chapters 1', '-c copy'])
+        .output(outputPath)
+        .on('start', () => {
+          if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+        })
+        .on('end', () => resolve())
+        .on('error', (err) => reject(err));
+
+      command.run();
+    });
+  } finally {
+    await fs.remove(metadataPath);
+  }
+}
+
+module.exports = {
+  normalizeMarkers,
+  addMarker,
+  listMarkers,
+  buildChapters,
+  buildChapterMetadata,
+  embedChapters,
+};
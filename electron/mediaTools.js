@@ -0,0 +1,383 @@
+const ffmpeg = require('fluent-ffmpeg');
+const fs = require('fs-extra');
+const path = require('path');
+const { ErrorCode, StarscapeError } = require('./errors');
+const { videoQualityOptions, audioQualityOptions } = require('./encoding');
+const { AUDIO_CONFORM, buildConformFilters } = require('./audio');
+const { conformVideoFilters } = require('./filterUtils');
+const { clipRotation } = require('./metadata');
+const { concatListEntry } = require('./paths');
+
+/**
+ * Standalone media tools - one-off file operations that don't need a timeline
+ */
+
+// Containers a file can be remuxed into; subtitles are converted where the container needs its own format
+const REMUX_CONTAINERS = {
+  mp4: { ext: 'mp4', subtitleCodec: 'mov_text', faststart: true },
+  mov: { ext: 'mov', subtitleCodec: 'mov_text', faststart: true },
+  mkv: { ext: 'mkv', subtitleCodec: 'copy', faststart: false },
+  m4a: { ext: 'm4a', subtitleCodec: null, faststart: true, audioOnly: true },
+};
+
+// Which streams are carried over
+const REMUX_STREAMS = ['all', 'video', 'audio'];
+
+/**
+ * ffmpeg output options copying the chosen streams into a container
+ * Optional maps (trailing ?) let a file without, say, subtitles remux without error.
+ */
+function remuxOptions(container, streams = 'all') {
+  const target = REMUX_CONTAINERS[container];
+  if (!target) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Unsupported container: ${container}`, { container });
+  }
+  if (!REMUX_STREAMS.includes(streams)) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Unknown stream selection: ${streams}`, { streams });
+  }
+  const keepVideo = !target.audioOnly && streams !== 'audio';
+  const keepAudio = streams !== 'video';
+  const keepSubtitles = streams === 'all' && !!target.subtitleCodec;
+
+  const options = [];
+  if (keepVideo) options.push('-map', '0:v?');
+  if (keepAudio) options.push('-map', '0:a?');
+  if (keepSubtitles) options.push('-map', '0:s?');
+  options.push('-map_metadata', '0', '-map_chapters', '0', '-c', 'copy');
+  if (keepSubtitles && target.subtitleCodec !== 'copy') {
+    options.push('-c:s', target.subtitleCodec);
+  }
+  if (target.faststart) {
+    options.push('-movflags', '+faststart');
+  }
+  return options;
+}
+
+/**
+ * Copy a file's streams into another container without re-encoding
+ * (e.g. an MKV screen capture to MP4, or just the audio of a video into M4A)
+ * @param {string} inputPath - Source file
+ * @param {string} outputPath - Destination (written under a temporary name first)
+ * @param {{ container: string, streams: 'all'|'video'|'audio' }} options
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ * @returns {Promise<string>} outputPath
+ */
+async function remuxFile(inputPath, outputPath, { container, streams = 'all' }, trackProcessFn) {
+  const options = remuxOptions(container, streams);
+  const partialPath = `${outputPath}.partial.${REMUX_CONTAINERS[container].ext}`;
+  try {
+    await new Promise((resolve, reject) => {
+      const command = ffmpeg()
+        .input(inputPath)
+        .outputOptions(...options)
+        .output(partialPath)
+        .on('start', () => {
+          if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+        })
+        .on('end', () => resolve())
+        .on('error', (err) => {
+          // Usually a codec the container can't hold without transcoding
+          reject(new StarscapeError(ErrorCode.FFMPEG_FAILED, `Remux to ${container} failed: ${err.message}`, { path: inputPath, container }));
+        });
+      command.run();
+    });
+    await fs.move(partialPath, outputPath, { overwrite: true });
+  } finally {
+    await fs.remove(partialPath);
+  }
+  return outputPath;
+}
+
+// Audio extraction targets: encoder arguments and the file extension they're written with
+const AUDIO_EXTRACT_CODECS = {
+  aac: { ext: 'm4a', args: ['-c:a', 'aac', '-b:a', '192k'] },
+  mp3: { ext: 'mp3', args: ['-c:a', 'libmp3lame', '-q:a', '2'] },
+  wav: { ext: 'wav', args: ['-c:a', 'pcm_s16le'] },
+  flac: { ext: 'flac', args: ['-c:a', 'flac'] },
+};
+
+// Containers a copied audio stream goes into, by source codec (all importable as audio assets)
+const AUDIO_COPY_EXTENSIONS = {
+  aac: 'm4a',
+  alac: 'm4a',
+  mp3: 'mp3',
+  flac: 'flac',
+  vorbis: 'ogg',
+  opus: 'ogg',
+  pcm_s16le: 'wav',
+  pcm_s24le: 'wav',
+  pcm_f32le: 'wav',
+};
+
+/**
+ * Output extension and ffmpeg arguments for extracting one of a file's audio streams
+ * 'copy' keeps the source bitstream when its codec has an importable container, else falls back to AAC.
+ * @param {string} codec - copy, aac, mp3, wav or flac
+ * @param {string|null} sourceCodec - Probed codec of that audio stream
+ * @param {number} audioIndex - Which audio stream (0 is the first)
+ * @returns {{ ext: string, args: string[], copied: boolean }}
+ */
+function audioExtractTarget(codec, sourceCodec, audioIndex = 0) {
+  const map = ['-map', `0:a:${audioIndex}`, '-vn'];
+  if (codec === 'copy') {
+    const ext = AUDIO_COPY_EXTENSIONS[sourceCodec];
+    if (ext) {
+      return { ext, args: [...map, '-c:a', 'copy'], copied: true };
+    }
+    codec = 'aac';
+  }
+  const target = AUDIO_EXTRACT_CODECS[codec];
+  if (!target) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Unsupported audio codec: ${codec}`, { codec });
+  }
+  return { ext: target.ext, args: [...map, ...target.args], copied: false };
+}
+
+/**
+ * Write one of a file's audio streams to its own file (stream copy or transcode)
+ * @param {string} inputPath - Source file (must have audio)
+ * @param {string} outputPath - Destination, with the extension from audioExtractTarget
+ * @param {string[]} args - ffmpeg output arguments from audioExtractTarget
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ */
+function extractAudio(inputPath, outputPath, args, trackProcessFn) {
+  return new Promise((resolve, reject) => {
+    const command = ffmpeg()
+      .input(inputPath)
+      .outputOptions(...args)
+      .output(outputPath)
+      .on('start', () => {
+        if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+      })
+      .on('end', () => resolve(outputPath))
+      .on('error', (err) => {
+        reject(new StarscapeError(ErrorCode.FFMPEG_FAILED, `Audio extraction failed: ${err.message}`, { path: inputPath }));
+      });
+    command.run();
+  });
+}
+
+/**
+ * ffmpeg output options for a quick trim
+ * Copy starts at the keyframe at or before the in point (fast, may include a little extra);
+ * re-encoding cuts exactly on the in point.
+ */
+function quickTrimOptions(reencode, encoding) {
+  if (!reencode) {
+    return ['-map', '0', '-c', 'copy', '-avoid_negative_ts', 'make_zero'];
+  }
+  return [
+    '-map', '0:v?', '-map', '0:a?',
+    '-c:v', encoding.videoCodec,
+    ...videoQualityOptions(encoding).flatMap((option) => option.split(' ')),
+    '-c:a', encoding.audioCodec,
+    ...audioQualityOptions(encoding).flatMap((option) => option.split(' ')),
+    '-movflags', '+faststart',
+  ];
+}
+
+/**
+ * Cut a file to [inMs, outMs) without building a project
+ * @param {string} inputPath - Source file
+ * @param {string} outputPath - Destination (written under a temporary name first)
+ * @param {{ inMs: number, outMs: number, reencode: boolean, encoding: Object }} options - encoding is used when re-encoding
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ * @returns {Promise<string>} outputPath
+ */
+async function quickTrim(inputPath, outputPath, { inMs, outMs, reencode, encoding }, trackProcessFn) {
+  if (!(Number.isFinite(inMs) && Number.isFinite(outMs) && inMs >= 0 && outMs > inMs)) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Invalid trim range ${inMs}-${outMs}ms`, { in_ms: inMs, out_ms: outMs });
+  }
+  const partialPath = `${outputPath}.partial${path.extname(outputPath)}`;
+  try {
+    await new Promise((resolve, reject) => {
+      const command = ffmpeg()
+        .input(inputPath)
+        .seekInput(inMs / 1000)
+        .duration((outMs - inMs) / 1000)
+        .outputOptions(...quickTrimOptions(reencode, encoding))
+        .output(partialPath)
+        .on('start', () => {
+          if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+        })
+        .on('end', () => resolve())
+        .on('error', (err) => {
+          reject(new StarscapeError(ErrorCode.FFMPEG_FAILED, `Trim failed: ${err.message}`, { path: inputPath, reencode }));
+        });
+      command.run();
+    });
+    await fs.move(partialPath, outputPath, { overwrite: true });
+  } finally {
+    await fs.remove(partialPath);
+  }
+  return outputPath;
+}
+
+// Stream properties that must match across files for a stream-copy concat
+const CONCAT_FIELDS = [
+  ['codec_video', 'video codec'],
+  ['width', 'width'],
+  ['height', 'height'],
+  ['frame_rate', 'frame rate'],
+  ['rotation_deg', 'rotation'],
+  ['has_audio', 'audio presence'],
+  ['codec_audio', 'audio codec'],
+  ['sample_rate', 'sample rate'],
+  ['channels', 'channel count'],
+];
+
+/**
+ * Why a list of files can't be joined by stream copy (empty when they can)
+ * @param {Array<{ path: string, meta: Object }>} entries - Files with their probed metadata
+ * @returns {string[]} One message per mismatch against the first file
+ */
+function concatMismatches(entries) {
+  const [first, ...rest] = entries;
+  const reasons = [];
+  for (const entry of rest) {
+    for (const [field, label] of CONCAT_FIELDS) {
+      const a = first.meta[field] ?? null;
+      const b = entry.meta[field] ?? null;
+      if (a !== b) {
+        reasons.push(`${path.basename(entry.path)}: ${label} ${b} differs from ${a}`);
+      }
+    }
+  }
+  return reasons;
+}
+
+/**
+ * Filter graph joining files that don't match: every input is fitted into the first video's frame
+ * and rate and its audio conformed; files without video get black, files without audio get silence
+ * @param {Array<{ path: string, meta: Object }>} entries
+ * @returns {{ filters: string[], hasVideo: boolean }}
+ */
+function concatNormalizeGraph(entries) {
+  const firstVideo = entries.find((entry) => entry.meta.codec_video);
+  const hasVideo = !!firstVideo;
+  let width = 0;
+  let height = 0;
+  let frameRate = '30/1';
+  if (hasVideo) {
+    // Inputs are decoded upright, so the frame of a sideways source is swapped
+    const { rotationDeg } = clipRotation(firstVideo.meta);
+    const sideways = rotationDeg === 90 || rotationDeg === 270;
+    const even = (value) => Math.max(2, Math.floor(value / 2) * 2);
+    width = even(sideways ? firstVideo.meta.height : firstVideo.meta.width);
+    height = even(sideways ? firstVideo.meta.width : firstVideo.meta.height);
+    frameRate = firstVideo.meta.frame_rate || frameRate;
+  }
+
+  const filters = [];
+  const pads = [];
+  entries.forEach(({ meta }, i) => {
+    const durationSec = (meta.duration_ms || 0) / 1000;
+    if (hasVideo) {
+      filters.push(meta.codec_video
+        ? `[${i}:v]${[...conformVideoFilters(width, height, frameRate), 'format=yuv420p'].join(',')}[v${i}]`
+        : `color=c=black:s=${width}x${height}:r=${frameRate}:d=${durationSec},format=yuv420p[v${i}]`);
+      pads.push(`[v${i}]`);
+    }
+    filters.push(meta.has_audio
+      ? `[${i}:a:0]${buildConformFilters().join(',')}[a${i}]`
+      : `anullsrc=r=${AUDIO_CONFORM.sampleRate}:cl=${AUDIO_CONFORM.channelLayout},atrim=duration=${durationSec}[a${i}]`);
+    pads.push(`[a${i}]`);
+  });
+  filters.push(`${pads.join('')}concat=n=${entries.length}:v=${hasVideo ? 1 : 0}:a=1${hasVideo ? '[v]' : ''}[a]`);
+  return { filters, hasVideo };
+}
+
+/**
+ * Join files end to end without a project
+ * Matching files are stream-copied through the concat demuxer; otherwise (or when forced) they're
+ * normalized to the first video's frame and rate and re-encoded in one pass.
+ * @param {Array<{ path: string, meta: Object }>} entries - Files in order with their probed metadata
+ * @param {string} outputPath - Destination (written under a temporary name first)
+ * @param {{ encoding: Object, reencode: boolean }} options
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ * @returns {Promise<{ mode: 'copy'|'reencode', mismatches: string[] }>}
+ */
+async function concatFiles(entries, outputPath, { encoding, reencode = false }, trackProcessFn) {
+  if (entries.length < 2) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, 'Merging needs at least two files', { count: entries.length });
+  }
+  const mismatches = concatMismatches(entries);
+  const mode = reencode || mismatches.length > 0 ? 'reencode' : 'copy';
+  const partialPath = `${outputPath}.partial${path.extname(outputPath)}`;
+  const listPath = `${outputPath}.list.txt`;
+
+  try {
+    if (mode === 'copy') {
+      await fs.writeFile(listPath, entries.map((entry) => concatListEntry(entry.path)).join('\n'), 'utf8');
+    }
+    await new Promise((resolve, reject) => {
+      let command;
+      if (mode === 'copy') {
+        command = ffmpeg()
+          .input(listPath)
+          .inputOptions(['-f concat', '-safe 0'])
+          .outputOptions('-map', '0', '-c', 'copy', '-movflags', '+faststart');
+      } else {
+        command = ffmpeg();
+        for (const entry of entries) {
+          command.input(entry.path);
+        }
+        const { filters, hasVideo } = concatNormalizeGraph(entries);
+        command.complexFilter(filters);
+        const options = hasVideo
+          ? ['-map', '[v]', '-map', '[a]', '-c:v', encoding.videoCodec, ...videoQualityOptions(encoding).flatMap((option) => option.split(' '))]
+          : ['-map', '[a]'];
+        command.outputOptions(
+          ...options,
+          '-c:a', encoding.audioCodec,
+          ...audioQualityOptions(encoding).flatMap((option) => option.split(' ')),
+          '-movflags', '+faststart'
+        );
+      }
+      command
+        .output(partialPath)
+        .on('start', () => {
+          if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+        })
+        .on('end', () => resolve())
+        .on('error', (err) => {
+          reject(new StarscapeError(ErrorCode.FFMPEG_FAILED, `Merge failed: ${err.message}`, { mode, count: entries.length }));
+        });
+      command.run();
+    });
+    await fs.move(partialPath, outputPath, { overwrite: true });
+  } finally {
+    await fs.remove(partialPath);
+    await fs.remove(listPath);
+  }
+  return { mode, mismatches };
+}
+
+/**
+ * Default name (without extension) for a remuxed copy of a file
+ */
+function remuxFilename(inputPath) {
+  return `${path.basename(inputPath, path.extname(inputPath))}_remux`;
+}
+
+/**
+ * Default name (without extension) for a trimmed copy of a file
+ */
+function trimFilename(inputPath) {
+  return `${path.basename(inputPath, path.extname(inputPath))}_trim`;
+}
+
+module.exports = {
+  REMUX_CONTAINERS,
+  remuxOptions,
+  remuxFile,
+  remuxFilename,
+  trimFilename,
+  audioExtractTarget,
+  extractAudio,
+  quickTrimOptions,
+  quickTrim,
+  concatMismatches,
+  concatNormalizeGraph,
+  concatFiles,
+};
@@ -1,84 +1,494 @@
 const ffmpeg = require('fluent-ffmpeg');
+const fs = require('fs-extra');
+const crypto = require('crypto');
+const { execFile } = require('child_process');
 const { resolveFfprobePath } = require('./ffmpeg');
+const { pathToFileUrl } = require('./paths');
+const { ErrorCode, StarscapeError, toStarscapeError } = require('./errors');
+const { runLimited } = require('./jobs');
+const { rotationFilters } = require('./filterUtils');
+const { withDecodeHwaccel } = require('./capabilities');
 
 /**
- * Probe media file and extract metadata
+ * Bit depth implied by a pixel format name (yuv420p10le -> 10), or null when there's none
+ */
+function pixFmtBitDepth(pixFmt) {
+  if (!pixFmt) return null;
+  const match = /p(\d+)(le|be)$/.exec(pixFmt);
+  return match ? parseInt(match[1], 10) : 8;
+}
+
+/**
+ * Parse an ffprobe frame rate ("30000/1001") into frames per second
+ */
+function parseFrameRate(rate) {
+  if (!rate || typeof rate !== 'string') return null;
+  const [num, den] = rate.split('/').map(Number);
+  if (!num || !den) return null;
+  return Math.round((num / den) * 1000) / 1000;
+}
+
+/**
+ * Keep an ffprobe rational rate string ("30000/1001") only when it is usable
+ */
+function validRate(rate) {
+  return parseFrameRate(rate) ? rate : null;
+}
+
+/**
+ * Snap a rotation in degrees to a clockwise 0/90/180/270
+ */
+function normalizeRotation(deg) {
+  const value = Number(deg);
+  if (!Number.isFinite(value)) return 0;
+  return ((Math.round(value / 90) * 90) % 360 + 360) % 360;
+}
+
+/**
+ * Clockwise display rotation of a video stream, from the legacy rotate tag or the display matrix
+ * The display matrix reports counter-clockwise degrees (-90 for a portrait phone clip).
+ */
+function streamRotation(stream) {
+  if (stream.tags && stream.tags.rotate) {
+    return normalizeRotation(parseInt(stream.tags.rotate, 10));
+  }
+  const matrix = (stream.side_data_list || []).find((data) => data && typeof data.rotation === 'number');
+  return matrix ? normalizeRotation(-matrix.rotation) : null;
+}
+
+// How long a probe or frame grab may run before it is killed (a sleeping network drive can hang it forever)
+const DEFAULT_PROBE_TIMEOUT_MS = 10000;
+
+// Keyframe scans decode every keyframe of the file, so long sources get more time
+const KEYFRAME_TIMEOUT_MS = 120000;
+
+// Files a batch probes at once
+const BATCH_PROBE_CONCURRENCY = 4;
+
+let probeTimeoutMs = DEFAULT_PROBE_TIMEOUT_MS;
+
+// Probe and frame-grab processes in flight, so they can be cancelled together
+const activeProbes = new Set();
+
+/**
+ * Change the default probe timeout (milliseconds)
  */
-async function probeMedia(inputPath) {
+function setProbeTimeout(ms) {
+  probeTimeoutMs = Number.isFinite(ms) && ms > 0 ? ms : DEFAULT_PROBE_TIMEOUT_MS;
+}
+
+/**
+ * Kill every running probe; their callers reject with a cancelled error
+ * @returns {number} Number of probes cancelled
+ */
+function cancelProbes() {
+  const probes = Array.from(activeProbes);
+  probes.forEach((probe) => probe.cancel());
+  return probes.length;
+}
+
+/**
+ * Run a child process under a timeout and cancellation
+ * @param {Function} start - (resolve, reject) => kill; starts the process and returns how to stop it
+ * @param {string} label - What is running, for error messages ("ffprobe")
+ * @param {Object} context - Error context (path, ...)
+ * @param {Object} options - { timeoutMs, signal: AbortSignal }
+ */
+function superviseProcess(start, label, context, options = {}) {
+  const timeoutMs = Number.isFinite(options.timeoutMs) && options.timeoutMs > 0 ? options.timeoutMs : probeTimeoutMs;
   return new Promise((resolve, reject) => {
-    ffmpeg.ffprobe(inputPath, (err, metadata) => {
-      if (err) {
-        reject(`ffprobe failed: ${err.message}`);
-        return;
-      }
+    let settled = false;
+    let timer = null;
+    const probe = {};
+    const finish = (settle, value) => {
+      if (settled) return;
+      settled = true;
+      clearTimeout(timer);
+      activeProbes.delete(probe);
+      if (options.signal) options.signal.removeEventListener('abort', probe.cancel);
+      settle(value);
+    };
+    const kill = start((value) => finish(resolve, value), (err) => finish(reject, err));
+    const stop = (error) => {
+      if (settled) return;
+      kill();
+      finish(reject, error);
+    };
 
-      // Initialize result
-      const result = {
-        duration_ms: 0,
-        width: null,
-        height: null,
-        has_audio: null,
-        codec_video: null,
-        codec_audio: null,
-        rotation_deg: null,
-      };
-
-      // Extract duration from format
-      if (metadata.format && metadata.format.duration) {
-        result.duration_ms = Math.round(metadata.format.duration * 1000);
+    probe.cancel = () => stop(new StarscapeError(ErrorCode.CANCELLED, `${label} was cancelled`, context));
+    timer = setTimeout(
+      () => stop(new StarscapeError(ErrorCode.TIMEOUT, `${label} timed out after ${timeoutMs / 1000}s`, { ...context, timeout_ms: timeoutMs })),
+      timeoutMs
+    );
+    activeProbes.add(probe);
+    if (options.signal) {
+      if (options.signal.aborted) {
+        probe.cancel();
+      } else {
+        options.signal.addEventListener('abort', probe.cancel);
       }
+    }
+  });
+}
 
-      // Process streams
-      for (const stream of metadata.streams) {
-        if (stream.codec_type === 'video') {
-          result.width = stream.width || null;
-          result.height = stream.height || null;
-          result.codec_video = stream.codec_name || null;
-          
-          // Check for rotation in stream tags or metadata
-          if (stream.tags && stream.tags.rotate) {
-            result.rotation_deg = parseInt(stream.tags.rotate, 10);
-          }
-          
-          // If duration not in format, try to get from video stream
-          if (!result.duration_ms && stream.duration) {
-            result.duration_ms = Math.round(parseFloat(stream.duration) * 1000);
-          }
-        } else if (stream.codec_type === 'audio') {
-          result.has_audio = true;
-          result.codec_audio = stream.codec_name || null;
+/**
+ * Run ffprobe with JSON output
+ * @param {Object} options - { timeoutMs, signal } (see superviseProcess)
+ */
+function runFfprobeJson(args, options = {}) {
+  return superviseProcess((resolve, reject) => {
+    const child = execFile(
+      resolveFfprobePath(),
+      ['-v', 'error', '-of', 'json', ...args],
+      { maxBuffer: 64 * 1024 * 1024 },
+      (err, stdout) => {
+        if (err) {
+          reject(new StarscapeError(ErrorCode.PROBE_FAILED, `ffprobe failed: ${err.message}`, { args }));
+          return;
+        }
+        try {
+          resolve(JSON.parse(stdout));
+        } catch (e) {
+          reject(new StarscapeError(ErrorCode.PROBE_FAILED, `ffprobe returned invalid JSON: ${e.message}`, { args }));
         }
       }
+    );
+    return () => child.kill('SIGKILL');
+  }, 'ffprobe', { path: args[args.length - 1] }, options);
+}
+
+/**
+ * Chapters from ffprobe's JSON as [{ title, start_ms, end_ms }]
+ */
+function parseChapters(chapters) {
+  return (chapters || []).map((chapter, i) => ({
+    title: (chapter.tags && chapter.tags.title) || `Chapter ${i + 1}`,
+    start_ms: Math.round(parseFloat(chapter.start_time) * 1000),
+    end_ms: Math.round(parseFloat(chapter.end_time) * 1000),
+  }));
+}
+
+/**
+ * Read embedded chapters (e.g. from screen recorders that write them)
+ * @returns {Promise<Array>} [{ title, start_ms, end_ms }]
+ */
+async function probeChapters(inputPath, options = {}) {
+  const data = await runFfprobeJson(['-show_chapters', inputPath], options);
+  return parseChapters(data.chapters);
+}
+
+/**
+ * List keyframe timestamps of the first video stream (only keyframes are decoded)
+ * @returns {Promise<number[]>} Keyframe times in milliseconds, ascending
+ */
+async function probeKeyframes(inputPath, options = {}) {
+  const data = await runFfprobeJson([
+    '-select_streams', 'v:0',
+    '-skip_frame', 'nokey',
+    '-show_frames',
+    '-show_entries', 'frame=pts_time,best_effort_timestamp_time',
+    inputPath,
+  ], { timeoutMs: KEYFRAME_TIMEOUT_MS, ...options });
+  const times = (data.frames || [])
+    .map((frame) => parseFloat(frame.pts_time !== undefined ? frame.pts_time : frame.best_effort_timestamp_time))
+    .filter(Number.isFinite)
+    .map((sec) => Math.round(sec * 1000));
+  return [...new Set(times)].sort((a, b) => a - b);
+}
+
+/**
+ * Load (or build and cache) the keyframe index of a file, keyed by path + size + mtime
+ */
+async function loadKeyframeIndex(inputPath, cache, options = {}) {
+  if (!cache) {
+    return probeKeyframes(inputPath, options);
+  }
+  const stats = await fs.stat(inputPath);
+  const key = crypto
+    .createHash('sha1')
+    .update(`${inputPath}:${stats.size}:${stats.mtimeMs}`)
+    .digest('hex')
+    .slice(0, 16);
+  const indexPath = cache.keyframeIndexPath(key);
+
+  try {
+    return await fs.readJson(indexPath);
+  } catch (e) {
+    const keyframes = await probeKeyframes(inputPath, options);
+    await fs.outputJson(indexPath, keyframes);
+    return keyframes;
+  }
+}
+
+// Bump when the probed metadata shape changes so cached entries are re-probed
+const METADATA_CACHE_VERSION = 3;
+
+/**
+ * Load (or probe and store) a file's metadata, reused while its size and mtime are unchanged
+ * Entries are keyed by path alone, so a changed file overwrites its stale entry.
+ */
+async function loadSourceInfo(inputPath, cache, limits) {
+  const stats = await fs.stat(inputPath);
+  const key = crypto.createHash('sha1').update(inputPath).digest('hex').slice(0, 16);
+  const entryPath = cache.metadataPath(key);
+
+  const entry = await fs.readJson(entryPath).catch(() => null);
+  if (entry && entry.version === METADATA_CACHE_VERSION && entry.size === stats.size && entry.mtime_ms === stats.mtimeMs) {
+    return entry.metadata;
+  }
+
+  const metadata = await probeStreams(inputPath, limits);
+  await fs.outputJson(entryPath, { version: METADATA_CACHE_VERSION, size: stats.size, mtime_ms: stats.mtimeMs, metadata });
+  return metadata;
+}
+
+/**
+ * Probe media file and extract metadata
+ * @param {string} inputPath - Media file path
+ * @param {Object} options - { keyframes: include the keyframe index, cache: CacheDirs for the metadata store and index,
+ *   timeoutMs: per-probe timeout (default 10s), signal: AbortSignal cancelling the probes }
+ */
+async function probeMedia(inputPath, options = {}) {
+  const limits = { timeoutMs: options.timeoutMs, signal: options.signal };
+  const result = options.cache
+    ? await loadSourceInfo(inputPath, options.cache, limits)
+    : await probeStreams(inputPath, limits);
 
-      resolve(result);
-    });
+  if (options.keyframes && result.codec_video) {
+    result.keyframes_ms = await loadKeyframeIndex(inputPath, options.cache, { signal: options.signal });
+  }
+
+  return result;
+}
+
+/**
+ * Probe many files at once (bounded pool); one file failing doesn't fail the batch
+ * @param {string[]} paths - Media file paths
+ * @param {Object} options - probeMedia options, plus resolvePath: validates/maps each path before probing
+ * @returns {Promise<Array<{ path: string, metadata: Object|null, error: Object|null }>>} In input order;
+ *   error is the { code, message, context } of a StarscapeError
+ */
+async function probeMediaBatch(paths, options = {}) {
+  const { resolvePath = (filePath) => filePath, ...probeOptions } = options;
+  const tasks = paths.map((filePath) => async () => {
+    try {
+      return { path: filePath, metadata: await probeMedia(resolvePath(filePath), probeOptions), error: null };
+    } catch (e) {
+      return { path: filePath, metadata: null, error: toStarscapeError(e, ErrorCode.PROBE_FAILED).toJSON() };
+    }
   });
+  return runLimited(tasks, BATCH_PROBE_CONCURRENCY);
+}
+
+/**
+ * Probe stream, format and chapter information in one ffprobe run
+ * @param {Object} options - { timeoutMs, signal } (see superviseProcess)
+ */
+async function probeStreams(inputPath, options = {}) {
+  let metadata;
+  try {
+    metadata = await runFfprobeJson(['-show_format', '-show_streams', '-show_chapters', inputPath], options);
+  } catch (err) {
+    if (err.code !== ErrorCode.PROBE_FAILED) throw err;
+    throw new StarscapeError(ErrorCode.PROBE_FAILED, err.message, { path: inputPath });
+  }
+  const streams = metadata.streams || [];
+
+  // Initialize result
+  const result = {
+    duration_ms: 0,
+    width: null,
+    height: null,
+    has_audio: null,
+    codec_video: null,
+    video_profile: null,
+    pix_fmt: null,
+    bit_depth: null,
+    codec_audio: null,
+    sample_rate: null,
+    channels: null,
+    channel_layout: null,
+    rotation_deg: null,
+    fps: null,
+    frame_rate: null,
+    timecode: null,
+    color_transfer: null,
+    color_primaries: null,
+    dolby_vision_profile: null,
+    audio_codecs: [],
+    chapters: parseChapters(metadata.chapters),
+  };
+
+  // Extract duration from format
+  if (metadata.format && metadata.format.duration) {
+    result.duration_ms = Math.round(metadata.format.duration * 1000);
+  }
+
+  // Process streams
+  for (const stream of streams) {
+    if (stream.codec_type === 'video') {
+      result.width = stream.width || null;
+      result.height = stream.height || null;
+      result.codec_video = stream.codec_name || null;
+      result.video_profile = stream.profile || null;
+      result.pix_fmt = stream.pix_fmt || null;
+      result.bit_depth = parseInt(stream.bits_per_raw_sample, 10) || pixFmtBitDepth(stream.pix_fmt);
+      result.fps = parseFrameRate(stream.avg_frame_rate) || parseFrameRate(stream.r_frame_rate);
+      result.frame_rate = validRate(stream.avg_frame_rate) || validRate(stream.r_frame_rate);
+      if (stream.tags && stream.tags.timecode) {
+        result.timecode = stream.tags.timecode;
+      }
+      result.color_transfer = stream.color_transfer || null;
+      result.color_primaries = stream.color_primaries || null;
+      const dovi = (stream.side_data_list || []).find((side) => side.side_data_type === 'DOVI configuration record');
+      result.dolby_vision_profile = dovi && Number.isInteger(dovi.dv_profile) ? dovi.dv_profile : null;
+      
+      // Check for rotation in stream tags or the display matrix side data
+      result.rotation_deg = streamRotation(stream);
+      
+      // If duration not in format, try to get from video stream
+      if (!result.duration_ms && stream.duration) {
+        result.duration_ms = Math.round(parseFloat(stream.duration) * 1000);
+      }
+    } else if (stream.codec_type === 'audio') {
+      result.has_audio = true;
+      result.codec_audio = stream.codec_name || null;
+      result.sample_rate = parseInt(stream.sample_rate, 10) || null;
+      result.channels = stream.channels || null;
+      result.channel_layout = stream.channel_layout || null;
+      // Every audio stream's codec; phones add a spatial track (apac) next to the stereo one
+      result.audio_codecs.push(stream.codec_name || 'unknown');
+    }
+  }
+
+  // Start timecode may live on the container or a tmcd data stream instead
+  if (!result.timecode) {
+    const tagged = [metadata.format, ...streams].find((entry) => entry && entry.tags && entry.tags.timecode);
+    result.timecode = tagged ? tagged.tags.timecode : null;
+  }
+
+  return result;
+}
+
+/**
+ * Resolve the rotation a clip should be rendered with
+ * @param {string} inputPath - Source video path
+ * @param {number|null} override - Per-clip clockwise override (0 ignores the source metadata)
+ * @returns {Promise<{ sourceDeg: number, rotationDeg: number }>}
+ */
+async function resolveRotation(inputPath, override = null) {
+  let meta = null;
+  try {
+    meta = await probeStreams(inputPath);
+  } catch (e) {
+    // Unprobeable sources render as decoded
+  }
+  return clipRotation(meta, override);
+}
+
+/**
+ * Rotation for a clip from already-probed metadata (null metadata counts as unrotated)
+ */
+function clipRotation(meta, override = null) {
+  const sourceDeg = (meta && meta.rotation_deg) || 0;
+  const rotationDeg = Number.isFinite(override) ? normalizeRotation(override) : sourceDeg;
+  return { sourceDeg, rotationDeg };
+}
+
+/**
+ * Apply an explicit rotation to a fluent-ffmpeg command in place of ffmpeg's autorotate
+ * Leaves the command alone when the rotation wasn't resolved (null), keeping ffmpeg's default.
+ */
+function applyRotation(command, rotationDeg) {
+  if (!Number.isFinite(rotationDeg)) {
+    return command;
+  }
+  command.inputOptions(['-noautorotate']);
+  const filters = rotationFilters(rotationDeg);
+  if (filters.length > 0) {
+    command.videoFilters(filters);
+  }
+  return command.outputOptions(['-metadata:s:v:0 rotate=0']);
 }
 
 /**
  * Extract a poster frame from video at specified timestamp
+ * Decodes on the GPU when the build has a hardware decoder for this platform, else in software.
+ * @param {number|null} rotationDeg - Resolved clip rotation (null uses ffmpeg's autorotate)
+ * @param {string[]} videoFilters - Clip effect filters applied after rotation
+ * @param {Object} options - { timeoutMs, signal } (see superviseProcess)
  */
-async function extractPosterFrame(inputPath, atMs, outputPath) {
-  return new Promise((resolve, reject) => {
+async function extractPosterFrame(inputPath, atMs, outputPath, rotationDeg = null, videoFilters = [], options = {}) {
+  return withDecodeHwaccel((hwaccel) => superviseProcess((resolve, reject) => {
     const timestamp = atMs / 1000; // Convert to seconds
     
-    ffmpeg(inputPath)
+    const command = ffmpeg(inputPath)
       .seekInput(timestamp)
       .frames(1)
-      .outputOptions(['-q:v 5'])
+      .outputOptions(['-q:v 5']);
+
+    if (hwaccel) {
+      command.inputOptions([`-hwaccel ${hwaccel}`]);
+    }
+    applyRotation(command, rotationDeg);
+    if (videoFilters.length > 0) {
+      command.videoFilters(videoFilters);
+    }
+
+    command
       .output(outputPath)
       .on('end', () => {
-        resolve(`file://${outputPath}`);
+        resolve(pathToFileUrl(outputPath));
       })
       .on('error', (err) => {
-        reject(`ffmpeg frame extraction failed: ${err.message}`);
+        reject(new StarscapeError(ErrorCode.FFMPEG_FAILED, `ffmpeg frame extraction failed: ${err.message}`, { path: inputPath, at_ms: atMs, hwaccel }));
       })
       .run();
-  });
+    return () => command.kill('SIGKILL');
+  }, 'Frame extraction', { path: inputPath, at_ms: atMs }, options));
+}
+
+/**
+ * Extract a full-quality still (PNG) from video at specified timestamp
+ * @param {Object} options - { timeoutMs, signal } (see superviseProcess)
+ */
+async function extractStill(inputPath, atMs, outputPath, options = {}) {
+  return withDecodeHwaccel((hwaccel) => superviseProcess((resolve, reject) => {
+    const command = ffmpeg(inputPath)
+      .seekInput(atMs / 1000)
+      .frames(1)
+      .output(outputPath)
+      .on('end', () => {
+        resolve(outputPath);
+      })
+      .on('error', (err) => {
+        reject(new StarscapeError(ErrorCode.FFMPEG_FAILED, `ffmpeg still extraction failed: ${err.message}`, { path: inputPath, at_ms: atMs, hwaccel }));
+      });
+    if (hwaccel) {
+      command.inputOptions([`-hwaccel ${hwaccel}`]);
+    }
+    command.run();
+    return () => command.kill('SIGKILL');
+  }, 'Still extraction', { path: inputPath, at_ms: atMs }, options));
 }
 
 module.exports = {
+  DEFAULT_PROBE_TIMEOUT_MS,
+  parseFrameRate,
+  runFfprobeJson,
+  setProbeTimeout,
+  cancelProbes,
+  superviseProcess,
   probeMedia,
+  probeMediaBatch,
+  probeChapters,
+  probeKeyframes,
   extractPosterFrame,
+  extractStill,
+  normalizeRotation,
+  streamRotation,
+  resolveRotation,
+  clipRotation,
+  applyRotation,
 };
 
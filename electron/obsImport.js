@@ -0,0 +1,173 @@
+const ffmpeg = require('fluent-ffmpeg');
+const fs = require('fs-extra');
+const path = require('path');
+const { ErrorCode, StarscapeError } = require('./errors');
+const { runFfprobeJson, parseFrameRate } = require('./metadata');
+const { remuxFile, audioExtractTarget, extractAudio } = require('./mediaTools');
+const { videoQualityOptions } = require('./encoding');
+const { ingestFiles } = require('./ingest');
+
+/**
+ * OBS recording import - splits a multi-track capture into a video asset and one asset per audio track
+ * OBS writes MKVs with several audio tracks (desktop audio, mic, ...) and, when frames were dropped
+ * or the capture ran variable-rate, uneven frame timing. The video is rewrapped as MP4 (re-encoded at
+ * a constant rate when it is variable), every audio track is copied to its own file, and all of them
+ * are ingested together with each track's offset from the video, so the timeline can place them in sync.
+ */
+
+const IMPORT_STEPS = ['probe', 'video', 'audio', 'ingest'];
+
+const CONFORM_MODES = ['auto', 'always', 'never'];
+
+// Average rate this far from the nominal rate means frames are unevenly spaced
+const VFR_TOLERANCE = 0.002;
+
+/**
+ * Video and audio layout of a recording from ffprobe streams
+ * @param {Array} streams - ffprobe -show_streams entries
+ * @returns {{ video: Object|null, audio: Array, multitrack: boolean }} video is { index, codec, frame_rate, vfr, start_ms };
+ *   audio entries are { index, codec, channels, title, start_ms }, where index counts audio streams only
+ */
+function recordingLayout(streams = []) {
+  const startMs = (stream) => Math.round((parseFloat(stream.start_time) || 0) * 1000);
+  const videoStream = streams.find((stream) => stream.codec_type === 'video' && !(stream.disposition && stream.disposition.attached_pic));
+  let video = null;
+  if (videoStream) {
+    const nominal = parseFrameRate(videoStream.r_frame_rate);
+    const average = parseFrameRate(videoStream.avg_frame_rate);
+    video = {
+      index: videoStream.index,
+      codec: videoStream.codec_name || null,
+      frame_rate: nominal ? videoStream.r_frame_rate : null,
+      // Matroska often leaves the average unset; without it the timing can't be trusted either
+      vfr: !nominal || !average || Math.abs(average - nominal) / nominal > VFR_TOLERANCE,
+      start_ms: startMs(videoStream),
+    };
+  }
+  const audio = streams
+    .filter((stream) => stream.codec_type === 'audio')
+    .map((stream, index) => {
+      const tags = stream.tags || {};
+      return {
+        index,
+        codec: stream.codec_name || null,
+        channels: stream.channels || null,
+        title: tags.title || tags.TITLE || tags.handler_name || `Track ${index + 1}`,
+        start_ms: startMs(stream),
+      };
+    });
+  return { video, audio, multitrack: audio.length > 1 };
+}
+
+/**
+ * ffmpeg output options re-encoding the first video stream at a constant rate (no audio)
+ * @param {string} frameRate - Target rate as "num/den"
+ * @param {Object} encoding - Resolved encoding
+ */
+function conformVideoOptions(frameRate, encoding) {
+  return [
+    '-map', '0:v:0', '-an', '-sn',
+    '-vf', `fps=${frameRate}`,
+    '-fps_mode', 'cfr',
+    '-c:v', encoding.videoCodec,
+    ...videoQualityOptions(encoding).flatMap((option) => option.split(' ')),
+    '-movflags', '+faststart',
+  ];
+}
+
+function conformVideo(inputPath, outputPath, options, trackProcessFn) {
+  return new Promise((resolve, reject) => {
+    const command = ffmpeg()
+      .input(inputPath)
+      .outputOptions(...options)
+      .output(outputPath)
+      .on('start', () => {
+        if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+      })
+      .on('end', () => resolve(outputPath))
+      .on('error', (err) => {
+        reject(new StarscapeError(ErrorCode.FFMPEG_FAILED, `Frame rate conform failed: ${err.message}`, { path: inputPath }));
+      });
+    command.run();
+  });
+}
+
+/**
+ * Import a recording as a synced video + audio tracks group
+ * @param {string} inputPath - Recording (usually an OBS MKV)
+ * @param {Object} cache - CacheDirs
+ * @param {Object} options - { conform: 'auto'|'always'|'never', frameRate: "num/den" (default the nominal rate),
+ *   encoding: resolved encoding for the conform, onStep: (step) => void, trackProcessFn }
+ * @returns {Promise<Object>} { group_id, name, conformed, frame_rate, video: IngestResult,
+ *   audio_tracks: [IngestResult + { track_index, title, offset_ms }] }
+ */
+async function importObsRecording(inputPath, cache, options = {}) {
+  const conform = options.conform === undefined ? 'auto' : options.conform;
+  if (!CONFORM_MODES.includes(conform)) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Unknown conform mode: ${conform}`, { conform, supported: CONFORM_MODES });
+  }
+  const onStep = options.onStep || (() => {});
+  const trackProcessFn = options.trackProcessFn || null;
+
+  onStep('probe');
+  const { streams } = await runFfprobeJson(['-show_streams', inputPath]);
+  const layout = recordingLayout(streams);
+  if (!layout.video) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, 'Recording has no video stream', { path: inputPath });
+  }
+  const frameRate = options.frameRate || layout.video.frame_rate;
+  const conformed = conform === 'always' || (conform === 'auto' && layout.video.vfr);
+  if (conformed && !parseFrameRate(frameRate)) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, 'Recording has no usable frame rate to conform to; pass one', { path: inputPath });
+  }
+
+  const name = path.basename(inputPath, path.extname(inputPath));
+  const written = [];
+  try {
+    onStep('video');
+    const videoPath = cache.importedStreamPath(`${name} - Video`, 'mp4');
+    written.push(videoPath);
+    if (conformed) {
+      await conformVideo(inputPath, videoPath, conformVideoOptions(frameRate, options.encoding), trackProcessFn);
+    } else {
+      await remuxFile(inputPath, videoPath, { container: 'mp4', streams: 'video' }, trackProcessFn);
+    }
+
+    onStep('audio');
+    const titles = new Set();
+    for (const track of layout.audio) {
+      const { ext, args } = audioExtractTarget('copy', track.codec, track.index);
+      const label = titles.has(track.title) ? `${track.title} ${track.index + 1}` : track.title;
+      titles.add(track.title);
+      const audioPath = cache.importedStreamPath(`${name} - ${label}`, ext);
+      written.push(audioPath);
+      await extractAudio(inputPath, audioPath, args, trackProcessFn);
+    }
+
+    onStep('ingest');
+    const [video, ...audio] = await ingestFiles(written, cache);
+    return {
+      group_id: video.asset_id.replace(/^asset_/, 'group_'),
+      name,
+      conformed,
+      frame_rate: conformed ? frameRate : layout.video.frame_rate,
+      video,
+      audio_tracks: audio.map((result, i) => ({
+        ...result,
+        track_index: layout.audio[i].index,
+        title: layout.audio[i].title,
+        offset_ms: layout.audio[i].start_ms - layout.video.start_ms,
+      })),
+    };
+  } finally {
+    await Promise.all(written.map((file) => fs.remove(file)));
+  }
+}
+
+module.exports = {
+  IMPORT_STEPS,
+  CONFORM_MODES,
+  recordingLayout,
+  conformVideoOptions,
+  importObsRecording,
+};
@@ -0,0 +1,74 @@
+const fs = require('fs');
+const path = require('path');
+const { ErrorCode, StarscapeError } = require('./errors');
+
+/**
+ * Export file naming - ExportSettings.filenameTemplate expanded with project and render fields
+ * A name that's already taken gets _2, _3... so an export never overwrites an earlier one.
+ */
+
+const DEFAULT_FILENAME_TEMPLATE = '{project}_{preset}_{date}_{resolution}';
+
+const FILENAME_FIELDS = ['project', 'preset', 'date', 'time', 'resolution', 'id'];
+
+// Path separators, characters Windows rejects and control characters
+const UNSAFE_FILENAME_CHARS = /[\/\\:*?"<>|\x00-\x1f]/g;
+
+function pad(value) {
+  return String(value).padStart(2, '0');
+}
+
+/**
+ * Template fields for an export
+ * @param {Object} plan - Edit plan (name and id)
+ * @param {Object} settings - ExportSettings (qualityProfile)
+ * @param {{ width: number, height: number }} size - Output frame size
+ * @param {Date} now - Render time (local date and time are used)
+ */
+function outputNameFields(plan, settings, size, now = new Date()) {
+  return {
+    project: (plan.name || plan.id).replace(/\s+/g, '_'),
+    preset: settings.qualityProfile || 'custom',
+    date: `${now.getFullYear()}-${pad(now.getMonth() + 1)}-${pad(now.getDate())}`,
+    time: `${pad(now.getHours())}${pad(now.getMinutes())}${pad(now.getSeconds())}`,
+    resolution: `${size.width}x${size.height}`,
+    id: plan.id,
+  };
+}
+
+/**
+ * Expand {field} tokens into a file name (without extension)
+ * @throws {StarscapeError} invalid_argument for unknown fields or a template that expands to nothing
+ */
+function expandFilenameTemplate(template, fields) {
+  const name = String(template).replace(/\{([^{}]*)\}/g, (token, key) => {
+    if (!FILENAME_FIELDS.includes(key)) {
+      throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Unknown filename field ${token}`, { template, supported: FILENAME_FIELDS });
+    }
+    return String(fields[key] == null ? '' : fields[key]);
+  }).replace(UNSAFE_FILENAME_CHARS, '_').trim();
+  if (!name || /^\.+$/.test(name)) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, 'Filename template gives an empty name', { template });
+  }
+  return name;
+}
+
+/**
+ * First free path for a name in a folder: name.ext, then name_2.ext, name_3.ext...
+ * @param {Function} exists - Existence check (fs.existsSync by default)
+ */
+function uniqueOutputPath(dir, name, ext, exists = fs.existsSync) {
+  let candidate = path.join(dir, `${name}.${ext}`);
+  for (let n = 2; exists(candidate); n++) {
+    candidate = path.join(dir, `${name}_${n}.${ext}`);
+  }
+  return candidate;
+}
+
+module.exports = {
+  DEFAULT_FILENAME_TEMPLATE,
+  FILENAME_FIELDS,
+  outputNameFields,
+  expandFilenameTemplate,
+  uniqueOutputPath,
+};
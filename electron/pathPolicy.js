@@ -0,0 +1,187 @@
+const fs = require('fs');
+const path = require('path');
+const { urlToPath } = require('./paths');
+const { ErrorCode, StarscapeError } = require('./errors');
+
+/**
+ * Path policy - which filesystem paths renderer-supplied arguments may touch
+ * Reads are allowed under the app's own roots (cache, projects) and for files the user
+ * picked in a native dialog; writes and deletes only under the app's roots. Paths are
+ * canonicalized with realpath so symlinks can't point outside an allowed location.
+ * Export hooks run programs and write to folders outside the app, so their targets have a grant
+ * set of their own: only the exact program or folder picked for a hook, never the app roots
+ * (the renderer can write files there).
+ */
+
+let grantsPath = null;
+let appRoots = [];
+let grants = new Set();
+let hookGrantsPath = null;
+let hookGrants = new Set();
+
+function isWithin(child, parent) {
+  const relative = path.relative(parent, child);
+  return relative === '' || (!relative.startsWith('..') && !path.isAbsolute(relative));
+}
+
+/**
+ * realpath that tolerates a missing leaf (for outputs): resolves the nearest existing ancestor
+ */
+function canonicalize(filePath) {
+  const absolute = path.resolve(filePath);
+  try {
+    return fs.realpathSync.native(absolute);
+  } catch (e) {
+    const parent = path.dirname(absolute);
+    if (parent === absolute) return absolute;
+    return path.join(canonicalize(parent), path.basename(absolute));
+  }
+}
+
+function saveGrants(filePath, granted) {
+  if (!filePath) return;
+  try {
+    fs.mkdirSync(path.dirname(filePath), { recursive: true });
+    fs.writeFileSync(filePath, JSON.stringify([...granted], null, 2));
+  } catch (e) {
+    console.error('Failed to save path grants:', e.message);
+  }
+}
+
+function loadGrants(filePath) {
+  try {
+    const stored = JSON.parse(fs.readFileSync(filePath, 'utf8'));
+    return new Set(Array.isArray(stored) ? stored : []);
+  } catch (e) {
+    return new Set();
+  }
+}
+
+/**
+ * Configure the app-owned roots and load persisted dialog grants
+ * @param {string[]} roots - Directories the app owns (cache, projects)
+ * @param {string} filePath - Where dialog grants are persisted
+ * @param {string} hookFilePath - Where export hook grants are persisted
+ */
+function init(roots, filePath, hookFilePath = null) {
+  appRoots = roots.map((root) => canonicalize(root));
+  grantsPath = filePath;
+  grants = loadGrants(filePath);
+  hookGrantsPath = hookFilePath;
+  hookGrants = hookFilePath ? loadGrants(hookFilePath) : new Set();
+}
+
+/**
+ * Allow reading paths the user chose in a native dialog (files or folders)
+ */
+function grant(paths) {
+  for (const filePath of paths) {
+    grants.add(canonicalize(filePath));
+  }
+  saveGrants(grantsPath, grants);
+}
+
+/**
+ * Allow export hooks to use a program or folder the user picked for them in a native dialog
+ */
+function grantHookTarget(paths) {
+  for (const filePath of paths) {
+    hookGrants.add(canonicalize(filePath));
+  }
+  saveGrants(hookGrantsPath, hookGrants);
+}
+
+/**
+ * Check an export hook's program or destination folder: it must be exactly a path picked for a hook
+ * @returns {string} Canonical absolute path
+ */
+function checkHookTarget(value) {
+  if (typeof value !== 'string' || value.trim() === '' || !path.isAbsolute(urlToPath(value))) {
+    throw new StarscapeError(ErrorCode.PATH_NOT_ALLOWED, 'Path not allowed: hooks need an absolute path', { path: value });
+  }
+  const canonical = canonicalize(urlToPath(value));
+  if (!hookGrants.has(canonical)) {
+    throw new StarscapeError(
+      ErrorCode.PATH_NOT_ALLOWED,
+      `Path not allowed: ${value} wasn't picked for an export hook`,
+      { path: value }
+    );
+  }
+  return canonical;
+}
+
+/**
+ * Canonicalize a renderer-supplied path and check it against the policy
+ * @param {string} value - Path or file:// / media:// URL
+ * @param {'read'|'write'} access - Writes (and deletes) are limited to app roots
+ * @returns {string} Canonical absolute path
+ */
+function checkPath(value, access = 'read') {
+  if (typeof value !== 'string' || value.trim() === '') {
+    throw new StarscapeError(ErrorCode.PATH_NOT_ALLOWED, 'Path not allowed: empty path');
+  }
+  const filePath = urlToPath(value);
+  if (!path.isAbsolute(filePath)) {
+    throw new StarscapeError(ErrorCode.PATH_NOT_ALLOWED, `Path not allowed: ${filePath} is not absolute`, { path: filePath });
+  }
+
+  const canonical = canonicalize(filePath);
+  if (appRoots.some((root) => isWithin(canonical, root))) {
+    return canonical;
+  }
+  if (access === 'read' && [...grants].some((granted) => isWithin(canonical, granted))) {
+    return canonical;
+  }
+  throw new StarscapeError(
+    ErrorCode.PATH_NOT_ALLOWED,
+    `Path not allowed: ${filePath} is outside the app's folders and files you opened`,
+    { path: filePath, access }
+  );
+}
+
+/**
+ * Check every source and effect file referenced by an edit plan
+ */
+function checkPlanPaths(plan) {
+  const clips = [
+    ...plan.mainTrack,
+    ...(plan.overlayTrack || []),
+    ...(plan.audioTrack || []),
+    ...(plan.adjustmentTrack || []),
+  ];
+  for (const clip of clips) {
+    if (clip.srcPath) {
+      checkPath(clip.srcPath);
+    }
+    for (const effect of clip.effects || []) {
+      if (effect && effect.path) {
+        checkPath(effect.path);
+      }
+    }
+    for (const effect of clip.audioEffects || []) {
+      if (effect && effect.model) {
+        checkPath(effect.model);
+      }
+    }
+  }
+}
+
+/**
+ * Reject file names that would escape the directory they are joined to
+ */
+function checkFileName(fileName) {
+  if (typeof fileName !== 'string' || fileName === '' || fileName !== path.basename(fileName) || fileName === '..' || fileName === '.') {
+    throw new StarscapeError(ErrorCode.PATH_NOT_ALLOWED, `Path not allowed: invalid file name ${fileName}`, { file_name: fileName });
+  }
+  return fileName;
+}
+
+module.exports = {
+  init,
+  grant,
+  grantHookTarget,
+  checkPath,
+  checkHookTarget,
+  checkPlanPaths,
+  checkFileName,
+};
@@ -0,0 +1,223 @@
+const path = require('path');
+
+/**
+ * Conversions between local paths and file:// / media:// URLs
+ * Windows drive letters and UNC shares are handled regardless of the host platform,
+ * so project files written on one OS still resolve on another.
+ */
+
+const WINDOWS_DRIVE = /^[A-Za-z]:[\\/]/;
+
+/**
+ * Files in the app's cache (ingested media, thumbnails) are referenced by projects as
+ * media://library/<path relative to the cache>, so a project survives the app data folder
+ * moving or being opened on another machine. The folder name marks absolute paths older
+ * builds stored, which are mapped into the current library.
+ */
+const LIBRARY_URL_PREFIX = 'media://library/';
+const LIBRARY_DIR_MARKER = /[\\/]com\.starscape\.studio[\\/]cache[\\/](.+)$/;
+
+let libraryRoot = null;
+const LONG_PATH_PREFIX = '\\\\?\\';
+
+/**
+ * Paths at or beyond this length need the \\?\ prefix on Windows (MAX_PATH less room for a file name)
+ */
+const WINDOWS_MAX_PATH = 248;
+
+function isWindows(platform) {
+  return (platform || process.platform) === 'win32';
+}
+
+/**
+ * Decode percent-escapes, leaving the text as-is when it isn't valid escaping
+ * (e.g. a literal '%' in a path that was never encoded)
+ */
+function safeDecode(text) {
+  try {
+    return decodeURIComponent(text);
+  } catch (e) {
+    return text;
+  }
+}
+
+/**
+ * Convert a file://, media:// URL or plain path to a local filesystem path
+ * @param {string} value - URL or path
+ * @param {string} platform - Target platform (defaults to the current one)
+ */
+function urlToPath(value, platform = process.platform) {
+  if (typeof value !== 'string' || value === '') {
+    return value;
+  }
+
+  if (libraryRoot && value.toLowerCase().startsWith(LIBRARY_URL_PREFIX)) {
+    // Relative segments can't climb out of the library
+    const segments = value.slice(LIBRARY_URL_PREFIX.length).split('/')
+      .map(safeDecode)
+      .filter((segment) => segment && segment !== '.' && segment !== '..');
+    return path.join(libraryRoot, ...segments);
+  }
+
+  const match = /^(file|media):\/\/(.*)$/i.exec(value);
+  if (!match) {
+    return value;
+  }
+
+  let rest = match[2];
+  let host = '';
+
+  if (rest.startsWith('/')) {
+    // file:///path - empty host
+    rest = rest.replace(/^\/+/, '/');
+  } else if (WINDOWS_DRIVE.test(rest) || /^[A-Za-z]:$/.test(rest) || /^[A-Za-z]%3A/i.test(rest)) {
+    // Malformed file://C:\dir form written by older builds
+    rest = `/${rest}`;
+  } else if (match[1].toLowerCase() === 'media') {
+    // media:// URLs carry the path directly after the scheme
+    rest = `/${rest}`;
+  } else {
+    // file://host/share/path - UNC
+    const slash = rest.indexOf('/');
+    host = slash === -1 ? rest : rest.slice(0, slash);
+    rest = slash === -1 ? '/' : rest.slice(slash);
+    if (host.toLowerCase() === 'localhost') host = '';
+  }
+
+  const decoded = safeDecode(rest);
+
+  if (host) {
+    return `\\\\${host}${decoded.replace(/\//g, '\\')}`;
+  }
+
+  // /C:/dir -> C:\dir
+  if (/^\/[A-Za-z]:([\\/]|$)/.test(decoded)) {
+    const drivePath = decoded.slice(1);
+    return drivePath.replace(/\//g, '\\');
+  }
+
+  return isWindows(platform) ? decoded.replace(/\//g, '\\') : decoded;
+}
+
+/**
+ * Remove a Windows \\?\ (or \\?\UNC\) prefix
+ */
+function stripLongPathPrefix(filePath) {
+  if (typeof filePath !== 'string' || !filePath.startsWith(LONG_PATH_PREFIX)) {
+    return filePath;
+  }
+  const rest = filePath.slice(LONG_PATH_PREFIX.length);
+  return /^UNC\\/i.test(rest) ? `\\\\${rest.slice(4)}` : rest;
+}
+
+/**
+ * Add the \\?\ prefix to long absolute Windows paths so ffmpeg can open them
+ * Node's fs adds it internally, but child processes get the path as-is.
+ * @param {string} filePath - Absolute path
+ * @param {string} platform - Target platform (defaults to the current one)
+ */
+function toLongPath(filePath, platform = process.platform) {
+  if (!isWindows(platform) || typeof filePath !== 'string' || filePath.length < WINDOWS_MAX_PATH) {
+    return filePath;
+  }
+  if (filePath.startsWith(LONG_PATH_PREFIX)) {
+    return filePath;
+  }
+  const normalized = filePath.replace(/\//g, '\\');
+  if (normalized.startsWith('\\\\')) {
+    return `${LONG_PATH_PREFIX}UNC\\${normalized.slice(2)}`;
+  }
+  if (WINDOWS_DRIVE.test(normalized)) {
+    return `${LONG_PATH_PREFIX}${normalized}`;
+  }
+  return filePath;
+}
+
+/**
+ * Line for an ffmpeg concat-demuxer list
+ * Single quotes can't be escaped inside a quoted token, so they close the quote,
+ * emit an escaped quote and reopen it. The list itself must be written as UTF-8.
+ */
+function concatListEntry(filePath) {
+  return `file '${String(filePath).replace(/'/g, "'\\''")}'`;
+}
+
+/**
+ * Convert a local path to a properly encoded file:// URL
+ * @param {string} filePath - Absolute path
+ */
+function pathToFileUrl(filePath) {
+  filePath = stripLongPathPrefix(filePath);
+  const encodeSegments = (p) => p.split('/').map((segment) => encodeURIComponent(segment)).join('/');
+
+  if (filePath.startsWith('\\\\') || filePath.startsWith('//')) {
+    // UNC: \\host\share\dir -> file://host/share/dir
+    const [host, ...parts] = filePath.replace(/\\/g, '/').replace(/^\/+/, '').split('/');
+    return `file://${host}/${encodeSegments(parts.join('/'))}`;
+  }
+
+  if (WINDOWS_DRIVE.test(filePath) || /^[A-Za-z]:$/.test(filePath)) {
+    const normalized = filePath.replace(/\\/g, '/');
+    return `file:///${normalized.slice(0, 2)}${encodeSegments(normalized.slice(2))}`;
+  }
+
+  return `file://${encodeSegments(filePath)}`;
+}
+
+/**
+ * Set the folder media://library/ URLs resolve against (the cache directory)
+ */
+function setLibraryRoot(dir) {
+  libraryRoot = dir;
+}
+
+/**
+ * Library URL for a file in the cache, or for a cache path recorded by an older build
+ * @param {string} filePath - Absolute path
+ * @returns {string|null} media://library/ URL, or null for files outside the library
+ */
+function toLibraryUrl(filePath) {
+  if (typeof filePath !== 'string' || !filePath) return null;
+  const plain = stripLongPathPrefix(filePath);
+  let relative = null;
+  if (libraryRoot) {
+    const rel = path.relative(libraryRoot, plain);
+    if (rel && !rel.startsWith('..') && !path.isAbsolute(rel)) {
+      relative = rel;
+    }
+  }
+  if (relative === null) {
+    const legacy = LIBRARY_DIR_MARKER.exec(plain);
+    if (!legacy) return null;
+    relative = legacy[1];
+  }
+  return LIBRARY_URL_PREFIX + relative.split(/[\\/]/).map((segment) => encodeURIComponent(segment)).join('/');
+}
+
+/**
+ * Local path for a project asset's src: library URLs, paths relative to the library and
+ * absolute cache paths from older builds all resolve into the current library
+ * @param {string} src - media://, file:// URL or path
+ */
+function resolveAssetSrc(src) {
+  const filePath = urlToPath(src);
+  if (typeof filePath !== 'string' || !filePath) return filePath;
+  const isAbsolute = filePath.startsWith('/') || filePath.startsWith('\\\\') || WINDOWS_DRIVE.test(filePath);
+  if (!isAbsolute) {
+    return libraryRoot ? urlToPath(LIBRARY_URL_PREFIX + filePath.split(/[\\/]/).map(encodeURIComponent).join('/')) : filePath;
+  }
+  const libraryUrl = libraryRoot ? toLibraryUrl(filePath) : null;
+  return libraryUrl ? urlToPath(libraryUrl) : filePath;
+}
+
+module.exports = {
+  LIBRARY_URL_PREFIX,
+  setLibraryRoot,
+  toLibraryUrl,
+  resolveAssetSrc,
+  urlToPath,
+  pathToFileUrl,
+  toLongPath,
+  stripLongPathPrefix,
+  concatListEntry,
+};
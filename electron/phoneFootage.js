@@ -0,0 +1,114 @@
+const { planIngestTranscode } = require('./ingestRules');
+
+/**
+ * Phone footage ingest profile - the fixes iPhone and Android clips need before they edit smoothly
+ * Checked together so one ffmpeg pass covers them all:
+ *   HDR       HDR10, HLG and Dolby Vision (profile 8 has an HDR10/HLG base layer) are tone-mapped to SDR
+ *   HEVC      re-encoded as H.264, which every decoder scrubs quickly
+ *   rotation  phones store portrait as a rotated landscape frame; a re-encode bakes the rotation in
+ *   slow-mo   120/240 fps captures are flagged and keep every frame
+ *   audio     a spatial track (APAC) is dropped for the stereo one; multichannel audio is downmixed
+ * User ingest rules still apply and are merged into the same conversion.
+ */
+
+// Capture rates from here up are slow-motion footage
+const SLOW_MOTION_FPS = 100;
+
+// Audio codecs ffmpeg can't decode, written by phones next to a stereo track
+const SPATIAL_AUDIO_CODECS = ['apac'];
+
+const HDR_TRANSFERS = {
+  smpte2084: 'hdr10',
+  'arib-std-b67': 'hlg',
+};
+
+/**
+ * HDR format of probed video (null for SDR)
+ * @returns {'dolby_vision'|'hdr10'|'hlg'|null}
+ */
+function hdrFormat(metadata) {
+  if (Number.isInteger(metadata.dolby_vision_profile)) return 'dolby_vision';
+  return HDR_TRANSFERS[metadata.color_transfer] || null;
+}
+
+/**
+ * What the phone profile does to one ingested file
+ * @param {string} kind - Asset type from ingest (only video is touched)
+ * @param {string} filePath - Ingested file
+ * @param {Object} metadata - probeMedia result
+ * @param {Object} options - { rules: ingest rules merged into the conversion, toneMap: false when ffmpeg lacks zscale/tonemap }
+ * @returns {{ fixups: Object|null, transcode: Object|null }} fixups is { hdr, dolby_vision_profile, rotation_deg,
+ *   slow_motion, capture_fps, spatial_audio, changes }; transcode is for runIngestTranscode
+ */
+function planPhoneFixups(kind, filePath, metadata, options = {}) {
+  const transcode = planIngestTranscode(kind, filePath, metadata, options.rules || []);
+  if (kind !== 'video') {
+    return { fixups: null, transcode };
+  }
+
+  const plan = transcode || { videoCodec: null, width: null, height: null, sampleRate: null, ext: 'mp4', reasons: [] };
+  const hdr = hdrFormat(metadata);
+  const fps = metadata.fps || 0;
+  const audioCodecs = metadata.audio_codecs || [];
+  const spatial = audioCodecs.some((codec) => SPATIAL_AUDIO_CODECS.includes(codec));
+  const fixups = {
+    hdr,
+    dolby_vision_profile: metadata.dolby_vision_profile === undefined ? null : metadata.dolby_vision_profile,
+    rotation_deg: metadata.rotation_deg || 0,
+    slow_motion: fps >= SLOW_MOTION_FPS,
+    capture_fps: fps || null,
+    spatial_audio: spatial,
+    changes: [],
+  };
+
+  // A rule may already re-encode (scaling, or its own hevc rule); only a rule asking for HEVC keeps it
+  const hevc = (metadata.codec_video || '').toLowerCase() === 'hevc';
+  if (hevc && plan.videoCodec !== 'libx265' && !plan.reasons.includes('hevc to h264')) {
+    plan.videoCodec = 'libx264';
+    plan.reasons.push('hevc to h264');
+  }
+  if (hdr) {
+    const transfer = metadata.color_transfer;
+    if (options.toneMap === false || !HDR_TRANSFERS[transfer]) {
+      // Dolby Vision profile 5 has no HDR10/HLG base layer to map from
+      fixups.changes.push(`${hdr} kept: ${options.toneMap === false ? 'ffmpeg lacks zscale/tonemap' : 'no HDR10/HLG base layer'}`);
+    } else {
+      plan.toneMap = transfer;
+      plan.videoCodec = 'libx264';
+      plan.reasons.push(`${hdr} tone-mapped to SDR`);
+    }
+  }
+
+  if (spatial) {
+    const stereo = audioCodecs.findIndex((codec) => !SPATIAL_AUDIO_CODECS.includes(codec));
+    plan.audioStream = stereo >= 0 ? stereo : null;
+    plan.reasons.push(stereo >= 0 ? 'spatial audio track dropped, stereo track kept' : 'spatial audio dropped (no stereo track)');
+  } else if (metadata.channels > 2) {
+    plan.audioStream = 0;
+    plan.downmix = true;
+    plan.reasons.push(`${metadata.channels}-channel audio downmixed to stereo`);
+  }
+
+  if (plan.reasons.length === 0) {
+    return { fixups, transcode: null };
+  }
+  if (plan.videoCodec && fixups.rotation_deg) {
+    // ffmpeg turns the frame before the scale runs, so a portrait target has its sides swapped
+    if (plan.width && fixups.rotation_deg % 180 === 90) {
+      [plan.width, plan.height] = [plan.height, plan.width];
+    }
+    plan.reasons.push(`${fixups.rotation_deg}° rotation baked in`);
+  }
+  if (plan.videoCodec && fixups.slow_motion) {
+    plan.reasons.push(`${fps} fps slow-motion kept at full rate`);
+  }
+  plan.ext = 'mp4';
+  fixups.changes.unshift(...plan.reasons);
+  return { fixups, transcode: plan };
+}
+
+module.exports = {
+  SLOW_MOTION_FPS,
+  hdrFormat,
+  planPhoneFixups,
+};
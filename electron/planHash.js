@@ -0,0 +1,76 @@
+const crypto = require('crypto');
+
+/**
+ * Deterministic hashing of edit plans and clips for cache keys
+ * Keys only change when something that affects rendered pixels or samples changes,
+ * so identical edits hit the preview/segment caches and real edits invalidate them.
+ */
+
+// Bump when the canonical form or what goes into a key changes
+const HASH_VERSION = 1;
+
+// Clip fields that don't change a rendered segment (timeline placement, labels, post stages)
+const SEGMENT_INDEPENDENT_FIELDS = ['clipId', 'trackId', 'startMs', 'endMs', 'assetName', 'reframe', 'canvasNode'];
+
+/**
+ * Serialize a value with sorted object keys; undefined members are dropped and -0 becomes 0
+ */
+function canonicalize(value) {
+  if (value === undefined || value === null) {
+    return 'null';
+  }
+  if (typeof value === 'number') {
+    if (!Number.isFinite(value)) {
+      throw new Error(`Cannot hash non-finite number: ${value}`);
+    }
+    return JSON.stringify(Object.is(value, -0) ? 0 : value);
+  }
+  if (Array.isArray(value)) {
+    return `[${value.map(canonicalize).join(',')}]`;
+  }
+  if (typeof value === 'object') {
+    const keys = Object.keys(value).filter((key) => value[key] !== undefined).sort();
+    return `{${keys.map((key) => `${JSON.stringify(key)}:${canonicalize(value[key])}`).join(',')}}`;
+  }
+  return JSON.stringify(value);
+}
+
+/**
+ * Short stable hash of any JSON-like value
+ */
+function hashValue(value) {
+  return crypto
+    .createHash('sha1')
+    .update(`v${HASH_VERSION}|${canonicalize(value)}`)
+    .digest('hex')
+    .slice(0, 16);
+}
+
+/**
+ * Hash a whole plan; the project id is left out so duplicated projects share cache entries
+ */
+function hashPlan(plan) {
+  const { id, ...content } = plan;
+  return hashValue(content);
+}
+
+/**
+ * Hash what a clip renders to, independent of where it sits on the timeline
+ * Looping clips fill their timeline duration, so that duration is part of the key.
+ */
+function hashClip(clip) {
+  const content = { ...clip };
+  for (const field of SEGMENT_INDEPENDENT_FIELDS) {
+    delete content[field];
+  }
+  content.durationMs = clip.endMs - clip.startMs;
+  return hashValue(content);
+}
+
+module.exports = {
+  HASH_VERSION,
+  canonicalize,
+  hashValue,
+  hashPlan,
+  hashClip,
+};
@@ -0,0 +1,139 @@
+const { buildPlan } = require('./editPlan');
+const { QUALITY_PROFILES, applyQualityProfile, resolveEncoding } = require('./encoding');
+
+/**
+ * Timeline statistics - durations, per-track usage, gaps and export size estimates for a project
+ * The program runs from the first to the last main-track clip, exactly as the export renders it,
+ * so every number here matches what an export of the same plan produces.
+ */
+
+// x264 bits per pixel at crf 23 for typical footage; every 6 crf steps halves or doubles it
+const BITS_PER_PIXEL_CRF23 = 0.1;
+
+// Mezzanine encoders run at a near-fixed rate per profile (ProRes 422 is ~147 Mbps at 1080p30)
+const MEZZANINE_BITS_PER_PIXEL = {
+  prores_ks: { standard: 2.36, hq: 3.54, '4444': 5.3 },
+  dnxhd: { dnxhr_lb: 0.73, dnxhr_sq: 2.33, dnxhr_hq: 2.33, dnxhr_hqx: 3.54, dnxhr_444: 7.08 },
+};
+
+/**
+ * Audio rate in kbps: PCM is uncompressed 48 kHz stereo, everything else its bitrate
+ */
+function audioKbps(encoding) {
+  const pcm = /^pcm_[su](\d+)/.exec(encoding.audioCodec);
+  return pcm ? (48000 * 2 * Number(pcm[1])) / 1000 : encoding.audioBitrate;
+}
+
+/**
+ * Merge [startMs, endMs) ranges into sorted, non-overlapping spans
+ */
+function mergeSpans(clips) {
+  const spans = [];
+  for (const clip of [...clips].sort((a, b) => a.startMs - b.startMs)) {
+    const last = spans[spans.length - 1];
+    if (last && clip.startMs <= last.endMs) {
+      last.endMs = Math.max(last.endMs, clip.endMs);
+    } else {
+      spans.push({ startMs: clip.startMs, endMs: clip.endMs });
+    }
+  }
+  return spans;
+}
+
+/**
+ * Empty stretches between a track's clips
+ */
+function findGaps(spans) {
+  const gaps = [];
+  for (let i = 1; i < spans.length; i++) {
+    gaps.push({ start_ms: spans[i - 1].endMs, end_ms: spans[i].startMs, duration_ms: spans[i].startMs - spans[i - 1].endMs });
+  }
+  return gaps;
+}
+
+/**
+ * Estimated output bytes for a render of the program
+ * Explicit bitrates are used as-is; crf encodes are estimated from the frame size and rate.
+ * @param {number} durationMs - Program duration
+ * @param {Object} settings - ExportSettings (a quality profile is applied first)
+ */
+function estimateExportSize(durationMs, settings = {}) {
+  const resolved = applyQualityProfile(settings);
+  const encoding = resolveEncoding(resolved);
+  const scale = resolved.halfResolution ? 0.5 : 1;
+  const width = (resolved.width > 0 ? resolved.width : 1920) * scale;
+  const height = (resolved.height > 0 ? resolved.height : 1080) * scale;
+  const fps = resolved.fps > 0 ? resolved.fps : 30;
+  const mezzanine = MEZZANINE_BITS_PER_PIXEL[encoding.videoCodec];
+  const videoKbps = mezzanine
+    ? (width * height * fps * (mezzanine[encoding.profile] || Object.values(mezzanine)[0])) / 1000
+    : encoding.bitrate || (width * height * fps * BITS_PER_PIXEL_CRF23 * Math.pow(2, (23 - encoding.crf) / 6)) / 1000;
+  return Math.round(((videoKbps + audioKbps(encoding)) * 1000 / 8) * (durationMs / 1000));
+}
+
+/**
+ * Statistics for a project's timeline
+ * @param {string} projectJson - Project JSON
+ * @param {Object} settings - ExportSettings the size estimates start from (size, fps, bitrate)
+ * @returns {{ duration_ms: number, start_ms: number, end_ms: number, timeline_end_ms: number,
+ *   clip_count: number, tracks: Array, estimated_size_bytes: Object }}
+ */
+function getPlanStats(projectJson, settings = {}) {
+  const plan = buildPlan(projectJson);
+  const layers = [
+    ['main', plan.mainTrack],
+    ['overlay', plan.overlayTrack],
+    ['audio', plan.audioTrack],
+    ['adjustment', plan.adjustmentTrack],
+    ['caption', plan.captionTrack],
+  ];
+
+  // Detached audio sits in the audio list under its main-track id, so tracks are keyed by id alone
+  const byTrack = new Map();
+  for (const [role, clips] of layers) {
+    for (const clip of clips) {
+      if (!byTrack.has(clip.trackId)) {
+        byTrack.set(clip.trackId, { role, clips: [] });
+      }
+      byTrack.get(clip.trackId).clips.push(clip);
+    }
+  }
+
+  const tracks = [...byTrack].map(([trackId, { role, clips }]) => {
+    const spans = mergeSpans(clips);
+    return {
+      track_id: trackId,
+      role,
+      clip_count: new Set(clips.map((clip, i) => clip.clipId || `${trackId}_${i}`)).size,
+      duration_ms: spans.reduce((sum, span) => sum + span.endMs - span.startMs, 0),
+      start_ms: spans[0].startMs,
+      end_ms: spans[spans.length - 1].endMs,
+      gaps: findGaps(spans),
+    };
+  });
+
+  const startMs = plan.mainTrack.length > 0 ? plan.mainTrack[0].startMs : 0;
+  const endMs = plan.mainTrack.length > 0 ? plan.mainTrack[plan.mainTrack.length - 1].endMs : 0;
+  const durationMs = endMs - startMs;
+  // Only the output format carries over; each profile brings its own rate control
+  const format = { width: settings.width, height: settings.height, fps: settings.fps, bitrate: settings.bitrate };
+  const estimatedSize = {};
+  for (const profile of Object.keys(QUALITY_PROFILES)) {
+    estimatedSize[profile] = estimateExportSize(durationMs, { ...format, qualityProfile: profile });
+  }
+
+  return {
+    duration_ms: durationMs,
+    start_ms: startMs,
+    end_ms: endMs,
+    timeline_end_ms: Math.max(0, ...tracks.map((track) => track.end_ms)),
+    clip_count: tracks.reduce((sum, track) => sum + track.clip_count, 0),
+    tracks,
+    estimated_size_bytes: estimatedSize,
+  };
+}
+
+module.exports = {
+  estimateExportSize,
+  getPlanStats,
+};
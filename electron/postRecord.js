@@ -0,0 +1,80 @@
+const fs = require('fs-extra');
+const path = require('path');
+const { remuxFile } = require('./mediaTools');
+const { probeMedia, extractPosterFrame } = require('./metadata');
+const { decodePcm, computePeaks } = require('./analysis');
+const logger = require('./logger');
+
+/**
+ * Post-record pipeline - makes a finished recording editable without the UI driving each step
+ * The recorder's WebM is rewrapped as a faststart MP4 (its duration and seek index are missing
+ * while it is being written), then probed, given a poster frame and reduced to waveform peaks.
+ * Only the probe is required; a failed remux keeps the WebM, and a missing thumbnail or waveform
+ * is logged and left null.
+ */
+
+const POST_RECORD_STEPS = ['remux', 'probe', 'thumbnail', 'waveform'];
+
+// Decode rate for waveform peaks: plenty for an overview, and an hour stays ~30 MB of samples
+const WAVEFORM_SAMPLE_RATE = 2000;
+const WAVEFORM_BUCKETS = 1000;
+
+/**
+ * Run the pipeline on one recording file
+ * @param {string} inputPath - Recording (WebM from the recorder, or an already converted MP4)
+ * @param {Object} cache - CacheDirs
+ * @param {Object} options - { onStep: (step) => void, trackProcessFn }
+ * @returns {Promise<{ path: string, metadata: Object, thumbnail_path: string|null,
+ *   waveform: { buckets: number, peaks: number[] }|null }>}
+ */
+async function postProcessRecording(inputPath, cache, options = {}) {
+  const onStep = options.onStep || (() => {});
+  const trackProcessFn = options.trackProcessFn || null;
+
+  onStep('remux');
+  let outputPath = inputPath;
+  if (path.extname(inputPath).toLowerCase() !== '.mp4') {
+    const mp4Path = path.join(path.dirname(inputPath), `${path.basename(inputPath, path.extname(inputPath))}.mp4`);
+    try {
+      await remuxFile(inputPath, mp4Path, { container: 'mp4' }, trackProcessFn);
+      await fs.remove(inputPath);
+      outputPath = mp4Path;
+    } catch (error) {
+      logger.warn('Recording remux failed, keeping the original', { path: inputPath, error: String(error) });
+    }
+  }
+
+  onStep('probe');
+  const metadata = await probeMedia(outputPath, { cache });
+
+  onStep('thumbnail');
+  let thumbnailPath = null;
+  if (metadata.width > 0) {
+    try {
+      thumbnailPath = path.join(cache.thumbDir, `${path.basename(outputPath, path.extname(outputPath))}.jpg`);
+      const atMs = Math.min(1000, (metadata.duration_ms || 0) / 2);
+      await extractPosterFrame(outputPath, atMs, thumbnailPath);
+    } catch (error) {
+      logger.warn('Recording thumbnail failed', { path: outputPath, error: String(error) });
+      thumbnailPath = null;
+    }
+  }
+
+  onStep('waveform');
+  let waveform = null;
+  if (metadata.has_audio) {
+    try {
+      const samples = await decodePcm(outputPath, WAVEFORM_SAMPLE_RATE, null, trackProcessFn);
+      waveform = { buckets: WAVEFORM_BUCKETS, peaks: computePeaks(samples, WAVEFORM_BUCKETS) };
+    } catch (error) {
+      logger.warn('Recording waveform failed', { path: outputPath, error: String(error) });
+    }
+  }
+
+  return { path: outputPath, metadata, thumbnail_path: thumbnailPath, waveform };
+}
+
+module.exports = {
+  POST_RECORD_STEPS,
+  postProcessRecording,
+};
@@ -1,23 +1,127 @@
 const { contextBridge, ipcRenderer } = require('electron');
 
+/**
+ * Subscribe to a main-process event; returns the cleanup function
+ */
+function subscribe(channel, callback) {
+  const listener = (event, data) => callback(data);
+  ipcRenderer.on(channel, listener);
+  return () => {
+    ipcRenderer.removeListener(channel, listener);
+  };
+}
+
 /**
  * Expose protected methods to renderer process via context bridge
  */
 contextBridge.exposeInMainWorld('electronAPI', {
   // Media operations
-  getMediaMetadata: (path) => ipcRenderer.invoke('get-media-metadata', path),
+  getMediaMetadata: (path, options) => ipcRenderer.invoke('get-media-metadata', path, options),
+  
+  getMediaMetadataBatch: (paths, options) => ipcRenderer.invoke('get-media-metadata-batch', paths, options),
+  
+  cancelMediaProbes: () => ipcRenderer.invoke('cancel-media-probes'),
+  
+  applyEdits: (projectJson) =>
+    ipcRenderer.invoke('apply-edits', projectJson),
+  
+  rippleDeleteRange: (projectJson, startMs, endMs, trackIds) =>
+    ipcRenderer.invoke('ripple-delete-range', projectJson, startMs, endMs, trackIds),
+  
+  closeGaps: (projectJson, trackId) => ipcRenderer.invoke('close-gaps', projectJson, trackId),
+  
+  splitClipAt: (projectJson, clipId, atMs) => ipcRenderer.invoke('split-clip-at', projectJson, clipId, atMs),
+  
+  addMarker: (projectJson, marker) => ipcRenderer.invoke('add-marker', projectJson, marker),
+  
+  listMarkers: (projectJson) => ipcRenderer.invoke('list-markers', projectJson),
+  
+  exportTimeline: (projectJson, format, options) => ipcRenderer.invoke('export-timeline', projectJson, format, options),
+  
+  quantizeProject: (projectJson, fps) => ipcRenderer.invoke('quantize-project', projectJson, fps),
   
-  applyEdits: (projectJson) => ipcRenderer.invoke('apply-edits', projectJson),
+  msToTimecode: (ms, fps, options) => ipcRenderer.invoke('ms-to-timecode', ms, fps, options),
+  
+  timecodeToMs: (timecode, fps, options) => ipcRenderer.invoke('timecode-to-ms', timecode, fps, options),
   
   generatePreview: (projectJson, atMs) =>
     ipcRenderer.invoke('generate-preview', projectJson, atMs),
   
+  getAssetFrame: (path, atMs, maxWidth) => ipcRenderer.invoke('get-asset-frame', path, atMs, maxWidth),
+  
+  scrubOpen: (path, options) => ipcRenderer.invoke('scrub-open', path, options),
+  
+  scrubSeek: (sessionId, atMs) => ipcRenderer.invoke('scrub-seek', sessionId, atMs),
+  
+  scrubClose: (sessionId) => ipcRenderer.invoke('scrub-close', sessionId),
+  
   exportProject: (projectJson, settings) =>
     ipcRenderer.invoke('export-project', projectJson, settings),
   
+  exportClip: (projectJson, clipId, settings) =>
+    ipcRenderer.invoke('export-clip', projectJson, clipId, settings),
+  
+  assembleFromTemplate: (templateJson, data, settings) =>
+    ipcRenderer.invoke('assemble-from-template', templateJson, data, settings),
+  
+  validatePlan: (projectJson, settings) =>
+    ipcRenderer.invoke('validate-plan', projectJson, settings),
+  
+  getPlanStats: (projectJson, settings) =>
+    ipcRenderer.invoke('get-plan-stats', projectJson, settings),
+  
+  estimateExport: (projectJson, settings, options) =>
+    ipcRenderer.invoke('estimate-export', projectJson, settings, options),
+  
+  previewAudioEffect: (path, effects, startMs, durationMs) =>
+    ipcRenderer.invoke('preview-audio-effect', path, effects, startMs, durationMs),
+  
+  alignClips: (pathA, pathB, options) => ipcRenderer.invoke('align-clips', pathA, pathB, options),
+  
+  analyzeBeats: (path, options) => ipcRenderer.invoke('analyze-beats', path, options),
+  
+  detectSpeech: (path, options) => ipcRenderer.invoke('detect-speech', path, options),
+  
+  suggestHighlights: (path, options) => ipcRenderer.invoke('suggest-highlights', path, options),
+  
+  detectCrop: (path) => ipcRenderer.invoke('detect-crop', path),
+  
+  measureAvDrift: (path) => ipcRenderer.invoke('measure-av-drift', path),
+  
+  analyzeClipGain: (projectJson, options) =>
+    ipcRenderer.invoke('analyze-clip-gain', projectJson, options),
+  
+  generateTimelineWaveform: (projectJson, buckets) =>
+    ipcRenderer.invoke('generate-timeline-waveform', projectJson, buckets),
+  
   // File ingestion
   ingestFiles: (request) => ipcRenderer.invoke('ingest-files', request),
   
+  precomputeClipHandles: (path, inMs, outMs, settings) =>
+    ipcRenderer.invoke('precompute-clip-handles', path, inMs, outMs, settings),
+  
+  remuxFile: (path, container, options) => ipcRenderer.invoke('remux-file', path, container, options),
+  
+  quickTrim: (path, inMs, outMs, outPath, reencode) =>
+    ipcRenderer.invoke('quick-trim', path, inMs, outMs, outPath, reencode),
+  
+  concatFiles: (paths, outPath, options) => ipcRenderer.invoke('concat-files', paths, outPath, options),
+  
+  extractStill: (path, atMs) => ipcRenderer.invoke('extract-still', path, atMs),
+  
+  extractAudio: (path, codec) => ipcRenderer.invoke('extract-audio', path, codec),
+  importObsRecording: (path, options) => ipcRenderer.invoke('import-obs-recording', path, options),
+  
+  generateContactSheet: (source, columns, rows, intervalMs) =>
+    ipcRenderer.invoke('generate-contact-sheet', source, columns, rows, intervalMs),
+  
+  renderCaptionsPreview: (projectJson, options) =>
+    ipcRenderer.invoke('render-captions-preview', projectJson, options),
+  
+  listTitleTemplates: () => ipcRenderer.invoke('list-title-templates'),
+  
+  renderTitle: (params) => ipcRenderer.invoke('render-title', params),
+  
   // File dialog
   openFileDialog: () => ipcRenderer.invoke('open-file-dialog'),
   
@@ -33,10 +137,47 @@ contextBridge.exposeInMainWorld('electronAPI', {
   // AI Image Generation
   generateImage: (prompt) => ipcRenderer.invoke('generate-image', prompt),
   
+  // Diagnostics
+  getRecentLogs: (level, limit) => ipcRenderer.invoke('get-recent-logs', level, limit),
+  
+  createDiagnosticsBundle: () => ipcRenderer.invoke('create-diagnostics-bundle'),
+  
+  getJobMetrics: (jobId) => ipcRenderer.invoke('get-job-metrics', jobId),
+  
+  runEncodeBenchmark: () => ipcRenderer.invoke('run-encode-benchmark'),
+  
+  getFfmpegCapabilities: (refresh) => ipcRenderer.invoke('get-ffmpeg-capabilities', refresh),
+  
+  listSystemFonts: (refresh) => ipcRenderer.invoke('list-system-fonts', refresh),
+  
+  checkDependencies: () => ipcRenderer.invoke('check-dependencies'),
+  
   // Screen recording
   listCaptureDevices: () => ipcRenderer.invoke('list-capture-devices'),
+  getDeviceCapabilities: (device) => ipcRenderer.invoke('get-device-capabilities', device),
+  startAudioMonitor: (device) => ipcRenderer.invoke('start-audio-monitor', device),
+  stopAudioMonitor: (monitorId) => ipcRenderer.invoke('stop-audio-monitor', monitorId),
   startScreenRecord: (settings) => ipcRenderer.invoke('start-screen-record', settings),
   stopScreenRecord: (recordingId) => ipcRenderer.invoke('stop-screen-record', recordingId),
+  listRecordPresets: () => ipcRenderer.invoke('list-record-presets'),
+  applyRecordPreset: (presetId, overrides) => ipcRenderer.invoke('apply-record-preset', presetId, overrides),
+  saveRecordPreset: (name, settings) => ipcRenderer.invoke('save-record-preset', name, settings),
+  captureScreenshot: (target, format, options) => ipcRenderer.invoke('capture-screenshot', target, format, options),
+  writeRecordingChunk: (recordingId, data) => ipcRenderer.invoke('write-recording-chunk', recordingId, data),
+  finishScreenRecord: (recordingId) => ipcRenderer.invoke('finish-screen-record', recordingId),
+  pauseScreenRecord: (recordingId, paused) => ipcRenderer.invoke('pause-screen-record', recordingId, paused),
+  getHotkeys: () => ipcRenderer.invoke('get-hotkeys'),
+  setHotkeys: (bindings) => ipcRenderer.invoke('set-hotkeys', bindings),
+  getIngestRules: () => ipcRenderer.invoke('get-ingest-rules'),
+  setIngestRules: (rules) => ipcRenderer.invoke('set-ingest-rules', rules),
+  getExportHooks: () => ipcRenderer.invoke('get-export-hooks'),
+  setExportHooks: (preset, actions) => ipcRenderer.invoke('set-export-hooks', preset, actions),
+  pickExportHookTarget: (kind) => ipcRenderer.invoke('pick-export-hook-target', kind),
+  getAutomationSettings: () => ipcRenderer.invoke('get-automation-settings'),
+  setAutomationSettings: (changes) => ipcRenderer.invoke('set-automation-settings', changes),
+  publishOpenProject: (projectJson) => ipcRenderer.invoke('publish-open-project', projectJson),
+  listEffectPlugins: () => ipcRenderer.invoke('list-effect-plugins'),
+  reloadEffectPlugins: () => ipcRenderer.invoke('reload-effect-plugins'),
   
   // Progress events
   onExportProgress: (callback) => {
@@ -69,5 +210,17 @@ contextBridge.exposeInMainWorld('electronAPI', {
       ipcRenderer.removeListener('stop-recording', listener);
     };
   },
+  
+  // Job, recording, ingest and cache status events
+  onJobStateChanged: (callback) => subscribe('job-state-changed', callback),
+  onRecordStatus: (callback) => subscribe('record-status', callback),
+  onIngestProgress: (callback) => subscribe('ingest-progress', callback),
+  onIngestTranscode: (callback) => subscribe('ingest-transcode', callback),
+  onCacheEvicted: (callback) => subscribe('cache-evicted', callback),
+  onScrubFrame: (callback) => subscribe('scrub-frame', callback),
+  onPauseRecording: (callback) => subscribe('pause-recording', callback),
+  onHotkeyTriggered: (callback) => subscribe('hotkey-triggered', callback),
+  onAudioLevel: (callback) => subscribe('audio-level', callback),
+  onPostRecordProgress: (callback) => subscribe('post-record-progress', callback),
 });
 
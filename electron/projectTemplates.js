@@ -0,0 +1,70 @@
+const { ErrorCode, StarscapeError } = require('./errors');
+
+/**
+ * Template projects - a project whose strings carry {{field}} placeholders filled from a data record
+ * Placeholders can sit in any string value: caption and credits text, the project name, and asset
+ * `src` paths, so an image or video slot is an asset whose src is "{{photo}}". Clips keep the
+ * template's timing; a substituted clip source is trimmed to its slot like any other.
+ */
+
+const PLACEHOLDER = /\{\{\s*([A-Za-z0-9_.-]+)\s*\}\}/g;
+
+/**
+ * Placeholder names used anywhere in a value, in first-seen order
+ */
+function templateFields(value, fields = new Set()) {
+  if (typeof value === 'string') {
+    for (const match of value.matchAll(PLACEHOLDER)) fields.add(match[1]);
+  } else if (Array.isArray(value)) {
+    value.forEach((item) => templateFields(item, fields));
+  } else if (value && typeof value === 'object') {
+    Object.values(value).forEach((item) => templateFields(item, fields));
+  }
+  return [...fields];
+}
+
+/**
+ * Replace placeholders in every string of a value (object keys are left alone)
+ * @param {*} value - Project, settings or any JSON value
+ * @param {Object} record - Map of field -> text or number
+ */
+function fillTemplateValue(value, record = {}) {
+  const missing = templateFields(value).filter((field) => {
+    const data = record[field];
+    return !(typeof data === 'string' || (typeof data === 'number' && Number.isFinite(data)));
+  });
+  if (missing.length > 0) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Template fields missing from the data: ${missing.join(', ')}`, {
+      fields: missing,
+    });
+  }
+
+  const fill = (item) => {
+    if (typeof item === 'string') return item.replace(PLACEHOLDER, (_, field) => String(record[field]));
+    if (Array.isArray(item)) return item.map(fill);
+    if (item && typeof item === 'object') {
+      return Object.fromEntries(Object.entries(item).map(([key, entry]) => [key, fill(entry)]));
+    }
+    return item;
+  };
+  return fill(value);
+}
+
+/**
+ * Project JSON for one data record
+ * @param {string} templateJson - Template project JSON
+ * @param {Object} record - Map of field -> text or number
+ * @returns {string} Filled project JSON
+ */
+function fillTemplate(templateJson, record) {
+  if (!record || typeof record !== 'object' || Array.isArray(record)) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, 'Template data must be an object of field values');
+  }
+  return JSON.stringify(fillTemplateValue(JSON.parse(templateJson), record));
+}
+
+module.exports = {
+  templateFields,
+  fillTemplateValue,
+  fillTemplate,
+};
@@ -0,0 +1,173 @@
+const fs = require('fs');
+const path = require('path');
+const { ErrorCode, StarscapeError } = require('./errors');
+
+/**
+ * Recording presets - named, complete RecordSettings so a session isn't configured from scratch
+ * Built-in presets ship with the app; saved presets and the active one are persisted in the
+ * settings store next to the hotkeys. Applying a preset makes it the default for the next recording.
+ */
+
+const RECORD_VIDEO_CODECS = ['vp9', 'vp8', 'h264'];
+
+// What a recording uses for anything a preset or request leaves out
+const RECORD_DEFAULTS = {
+  display_index: 0,
+  audio_index: 0,
+  system_audio: false,
+  microphone: false,
+  fps: 30,
+  max_width: 1920,
+  max_height: 1080,
+  video_codec: 'vp9',
+  video_bitrate_kbps: null, // Recorder's own choice
+  audio_bitrate_kbps: 128,
+  segment_minutes: null, // One file for the whole session
+};
+
+const BUILTIN_RECORD_PRESETS = {
+  tutorial: {
+    name: 'Tutorial 1080p30',
+    settings: { fps: 30, max_width: 1920, max_height: 1080, video_codec: 'vp9', video_bitrate_kbps: 6000, microphone: true },
+  },
+  gameplay: {
+    name: 'Gameplay 60fps',
+    settings: { fps: 60, max_width: 1920, max_height: 1080, video_codec: 'h264', video_bitrate_kbps: 16000, system_audio: true, audio_bitrate_kbps: 192 },
+  },
+  // vp8 and a small frame are the cheapest to encode, leaving CPU for the call itself
+  meeting: {
+    name: 'Meeting low-CPU',
+    settings: { fps: 15, max_width: 1280, max_height: 720, video_codec: 'vp8', video_bitrate_kbps: 1500, microphone: true, system_audio: true, audio_bitrate_kbps: 96 },
+  },
+};
+
+let storePath = null;
+let savedPresets = {};
+let activePresetId = null;
+
+function clampInt(value, min, max, fallback) {
+  return Number.isFinite(value) ? Math.max(min, Math.min(max, Math.round(value))) : fallback;
+}
+
+/**
+ * Fill in and clamp every RecordSettings field
+ */
+function normalizeRecordSettings(settings = {}) {
+  const merged = { ...RECORD_DEFAULTS, ...settings };
+  if (!RECORD_VIDEO_CODECS.includes(merged.video_codec)) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `Unsupported recording codec: ${merged.video_codec}`, {
+      video_codec: merged.video_codec,
+      supported: RECORD_VIDEO_CODECS,
+    });
+  }
+  return {
+    display_index: clampInt(merged.display_index, 0, Number.MAX_SAFE_INTEGER, 0),
+    audio_index: clampInt(merged.audio_index, 0, Number.MAX_SAFE_INTEGER, 0),
+    system_audio: !!merged.system_audio,
+    microphone: !!merged.microphone,
+    fps: clampInt(merged.fps, 1, 120, RECORD_DEFAULTS.fps),
+    max_width: clampInt(merged.max_width, 320, 7680, RECORD_DEFAULTS.max_width),
+    max_height: clampInt(merged.max_height, 240, 4320, RECORD_DEFAULTS.max_height),
+    video_codec: merged.video_codec,
+    video_bitrate_kbps: merged.video_bitrate_kbps == null ? null : clampInt(merged.video_bitrate_kbps, 250, 100000, null),
+    audio_bitrate_kbps: clampInt(merged.audio_bitrate_kbps, 32, 512, RECORD_DEFAULTS.audio_bitrate_kbps),
+    segment_minutes: merged.segment_minutes ? clampInt(merged.segment_minutes, 1, 240, null) : null,
+  };
+}
+
+function presetId(name) {
+  return name.trim().toLowerCase().replace(/[^a-z0-9]+/g, '-').replace(/^-|-$/g, '');
+}
+
+function saveStore() {
+  if (!storePath) return;
+  try {
+    fs.mkdirSync(path.dirname(storePath), { recursive: true });
+    fs.writeFileSync(storePath, JSON.stringify({ active: activePresetId, presets: savedPresets }, null, 2));
+  } catch (e) {
+    console.error('Failed to save recording presets:', e.message);
+  }
+}
+
+function findPreset(id) {
+  if (BUILTIN_RECORD_PRESETS[id]) return { id, builtin: true, ...BUILTIN_RECORD_PRESETS[id] };
+  if (savedPresets[id]) return { id, builtin: false, ...savedPresets[id] };
+  return null;
+}
+
+/**
+ * Built-in presets followed by saved ones, each with its fully resolved settings
+ * @returns {Array<{ id: string, name: string, builtin: boolean, active: boolean, settings: Object }>}
+ */
+function listRecordPresets() {
+  return [...Object.keys(BUILTIN_RECORD_PRESETS), ...Object.keys(savedPresets)].map((id) => {
+    const preset = findPreset(id);
+    return { id, name: preset.name, builtin: preset.builtin, active: id === activePresetId, settings: normalizeRecordSettings(preset.settings) };
+  });
+}
+
+/**
+ * Resolve a preset (plus per-session overrides such as the display) and make it the active one
+ * @returns {{ preset_id: string, settings: Object }}
+ */
+function applyRecordPreset(id, overrides = {}) {
+  const preset = findPreset(id);
+  if (!preset) {
+    throw new StarscapeError(ErrorCode.NOT_FOUND, `Recording preset not found: ${id}`, { preset_id: id });
+  }
+  const settings = normalizeRecordSettings({ ...preset.settings, ...overrides });
+  activePresetId = id;
+  saveStore();
+  return { preset_id: id, settings };
+}
+
+/**
+ * Save (or replace) a user preset; built-in names can't be reused
+ */
+function saveRecordPreset(name, settings) {
+  const id = typeof name === 'string' ? presetId(name) : '';
+  if (!id) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, 'Recording preset needs a name', { name });
+  }
+  if (BUILTIN_RECORD_PRESETS[id]) {
+    throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, `${name} is a built-in preset`, { preset_id: id });
+  }
+  savedPresets[id] = { name: name.trim(), settings: normalizeRecordSettings(settings) };
+  saveStore();
+  return listRecordPresets().find((preset) => preset.id === id);
+}
+
+/**
+ * Settings of the active preset (defaults when none has been applied)
+ */
+function activeRecordSettings() {
+  const preset = activePresetId && findPreset(activePresetId);
+  return normalizeRecordSettings(preset ? preset.settings : {});
+}
+
+/**
+ * Load saved presets and the active preset from the settings store
+ */
+function init(filePath) {
+  storePath = filePath;
+  try {
+    const stored = JSON.parse(fs.readFileSync(filePath, 'utf8'));
+    savedPresets = stored.presets && typeof stored.presets === 'object' ? stored.presets : {};
+    activePresetId = findPreset(stored.active) ? stored.active : null;
+  } catch (e) {
+    savedPresets = {};
+    activePresetId = null;
+  }
+}
+
+module.exports = {
+  RECORD_DEFAULTS,
+  RECORD_VIDEO_CODECS,
+  BUILTIN_RECORD_PRESETS,
+  normalizeRecordSettings,
+  init,
+  listRecordPresets,
+  applyRecordPreset,
+  saveRecordPreset,
+  activeRecordSettings,
+};
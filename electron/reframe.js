@@ -0,0 +1,120 @@
+const ffmpeg = require('fluent-ffmpeg');
+const { videoQualityOptions } = require('./encoding');
+
+/**
+ * Supported export aspect ratios (width / height)
+ */
+const ASPECT_RATIOS = {
+  '16:9': 16 / 9,
+  '9:16': 9 / 16,
+  '1:1': 1,
+  '4:5': 4 / 5,
+};
+
+/**
+ * Round to an even pixel count (required by most yuv encoders)
+ */
+function even(n) {
+  return Math.max(2, Math.round(n / 2) * 2);
+}
+
+/**
+ * Compute output dimensions for a reframed export
+ * The short side of the requested resolution is kept (1080p -> 1080x1920 for 9:16).
+ */
+function computeReframeSize(aspectRatio, targetWidth, targetHeight) {
+  const ratio = ASPECT_RATIOS[aspectRatio];
+  const hasTarget = targetWidth > 0 && targetHeight > 0;
+  const shortSide = hasTarget ? Math.min(targetWidth, targetHeight) : 1080;
+
+  if (ratio >= 1) {
+    return { width: even(shortSide * ratio), height: even(shortSide) };
+  }
+  return { width: even(shortSide), height: even(shortSide / ratio) };
+}
+
+/**
+ * Clamp a pan offset to [-1, 1] (0 = centered)
+ */
+function clampPan(value) {
+  if (typeof value !== 'number' || !Number.isFinite(value)) return 0;
+  return Math.min(1, Math.max(-1, value));
+}
+
+/**
+ * Build a time-varying pan expression from per-clip framing offsets
+ * @param {Array} clips - Main track plan clips (sorted by startMs)
+ * @param {string} aspectRatio - Target aspect ratio key
+ * @param {'panX'|'panY'} axis - Which offset to read
+ * @param {number} timelineStartMs - Timeline position that maps to t=0
+ */
+function buildPanExpr(clips, aspectRatio, axis, timelineStartMs) {
+  let expr = '0';
+  for (let i = clips.length - 1; i >= 0; i--) {
+    const clip = clips[i];
+    const framing = clip.reframe && clip.reframe[aspectRatio];
+    const pan = framing ? clampPan(framing[axis]) : 0;
+    if (pan === 0) continue;
+    const start = (clip.startMs - timelineStartMs) / 1000;
+    const end = (clip.endMs - timelineStartMs) / 1000;
+    expr = `if(between(t,${start},${end}),${pan},${expr})`;
+  }
+  return expr;
+}
+
+/**
+ * Build the crop + scale filter chain that converts the program to another aspect ratio
+ */
+function buildReframeFilters(clips, aspectRatio, timelineStartMs, outWidth, outHeight) {
+  const ratio = ASPECT_RATIOS[aspectRatio];
+  const panX = buildPanExpr(clips, aspectRatio, 'panX', timelineStartMs);
+  const panY = buildPanExpr(clips, aspectRatio, 'panY', timelineStartMs);
+
+  return [
+    `crop=w='trunc(if(gt(iw/ih,${ratio}),ih*${ratio},iw)/2)*2'` +
+      `:h='trunc(if(gt(iw/ih,${ratio}),ih,iw/${ratio})/2)*2'` +
+      `:x='(iw-ow)/2*(1+(${panX}))'` +
+      `:y='(ih-oh)/2*(1+(${panY}))'`,
+    `scale=${outWidth}:${outHeight}`,
+    'setsar=1',
+  ];
+}
+
+/**
+ * Reframe the program video to a different aspect ratio, following per-clip pan offsets
+ * @param {string} inputPath - Program file
+ * @param {string} outputPath - Reframed output file
+ * @param {Array} clips - Main track plan clips
+ * @param {string} aspectRatio - Target aspect ratio key
+ * @param {number} timelineStartMs - Timeline position of the program start
+ * @param {number} outWidth - Output width
+ * @param {number} outHeight - Output height
+ * @param {Object} encoding - Resolved encoder settings (see encoding.js)
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ */
+function reframeProgram(inputPath, outputPath, clips, aspectRatio, timelineStartMs, outWidth, outHeight, encoding, trackProcessFn) {
+  return new Promise((resolve, reject) => {
+    const command = ffmpeg(inputPath)
+      .videoFilters(buildReframeFilters(clips, aspectRatio, timelineStartMs, outWidth, outHeight))
+      .videoCodec(encoding.videoCodec)
+      .outputOptions([
+        '-c:a copy',
+        ...videoQualityOptions(encoding),
+      ])
+      .output(outputPath)
+      .on('start', () => {
+        if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+      })
+      .on('end', () => resolve())
+      .on('error', (err) => reject(err));
+
+    command.run();
+  });
+}
+
+module.exports = {
+  ASPECT_RATIOS,
+  computeReframeSize,
+  buildReframeFilters,
+  reframeProgram,
+};
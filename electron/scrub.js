@@ -0,0 +1,273 @@
+const { spawn } = require('child_process');
+const { resolveFfmpegPath } = require('./ffmpeg');
+const { ErrorCode, StarscapeError } = require('./errors');
+
+/**
+ * Scrub sessions - one long-lived ffmpeg per asset streaming MJPEG frames over a pipe
+ * Playhead drags are answered from the running stream: forward seeks read ahead in it,
+ * backward or distant seeks restart it at the new position. Frames are raw source frames
+ * (no effects or compositing); the exact preview is still rendered by generate-preview on release.
+ */
+
+const SCRUB_DEFAULTS = { width: 640, fps: 15 };
+
+// Forward seeks within this distance read through the running stream instead of restarting ffmpeg
+const READ_AHEAD_MS = 3000;
+
+// Sessions kept open at once; opening another closes the least recently used
+const MAX_SCRUB_SESSIONS = 4;
+
+/**
+ * Split a byte stream of concatenated JPEGs (image2pipe + mjpeg) into frames
+ * Frames end at the EOI marker; the mjpeg encoder byte-stuffs 0xFF inside scan data, so EOI can't appear early.
+ */
+class MjpegSplitter {
+  constructor() {
+    this.buffer = Buffer.alloc(0);
+  }
+
+  push(chunk) {
+    this.buffer = this.buffer.length > 0 ? Buffer.concat([this.buffer, chunk]) : chunk;
+  }
+
+  /**
+   * Next complete frame, or null until more bytes arrive
+   */
+  next() {
+    const start = this.buffer.indexOf(Buffer.from([0xff, 0xd8]));
+    if (start < 0) return null;
+    const end = this.buffer.indexOf(Buffer.from([0xff, 0xd9]), start + 2);
+    if (end < 0) return null;
+    const frame = this.buffer.subarray(start, end + 2);
+    this.buffer = this.buffer.subarray(end + 2);
+    return frame;
+  }
+}
+
+function normalizeOptions(options = {}) {
+  const width = Number.isFinite(options.width) ? Math.max(64, Math.min(1920, Math.round(options.width / 2) * 2)) : SCRUB_DEFAULTS.width;
+  const fps = Number.isFinite(options.fps) ? Math.max(1, Math.min(60, Math.round(options.fps))) : SCRUB_DEFAULTS.fps;
+  return { width, fps };
+}
+
+/**
+ * ffmpeg arguments streaming frames from a position
+ */
+function scrubArgs(srcPath, startMs, { width, fps }) {
+  return [
+    '-hide_banner', '-loglevel', 'error',
+    '-ss', String(startMs / 1000),
+    '-i', srcPath,
+    '-an', '-sn',
+    '-vf', `fps=${fps},scale=${width}:-2`,
+    '-c:v', 'mjpeg', '-q:v', '5',
+    '-f', 'image2pipe',
+    'pipe:1',
+  ];
+}
+
+class ScrubSession {
+  /**
+   * @param {string} id - Session id
+   * @param {string} srcPath - Media file
+   * @param {Object} options - { width, fps, onFrame: ({ session_id, seq, at_ms, jpeg }) => void,
+   *   spawnProcess: (args) => ChildProcess (defaults to ffmpeg), trackProcessFn }
+   */
+  constructor(id, srcPath, options = {}) {
+    this.id = id;
+    this.srcPath = srcPath;
+    this.format = normalizeOptions(options);
+    this.onFrame = options.onFrame || (() => {});
+    this.spawnProcess = options.spawnProcess || ((args) => spawn(resolveFfmpegPath(), args, { stdio: ['ignore', 'pipe', 'ignore'] }));
+    this.trackProcessFn = options.trackProcessFn;
+    this.proc = null;
+    this.splitter = null;
+    this.streamStartMs = 0;
+    this.frameIndex = 0;
+    this.lastFrame = null;
+    this.request = null;
+    this.seq = 0;
+    this.lastUsed = Date.now();
+  }
+
+  get frameMs() {
+    return 1000 / this.format.fps;
+  }
+
+  // Time of the next frame the stream will produce
+  get positionMs() {
+    return this.streamStartMs + this.frameIndex * this.frameMs;
+  }
+
+  /**
+   * Deliver the frame at (or just after) a source time
+   * Superseded requests resolve with { seq, superseded: true } once a newer seek arrives.
+   * @returns {Promise<{ seq: number, at_ms: number }|{ seq: number, superseded: true }>}
+   */
+  seek(atMs) {
+    this.lastUsed = Date.now();
+    const target = Math.max(0, atMs);
+    const seq = ++this.seq;
+    if (this.request) {
+      this.request.resolve({ seq: this.request.seq, superseded: true });
+    }
+
+    // Same frame as last time: answer without touching the stream
+    if (this.lastFrame && Math.abs(this.lastFrame.atMs - target) < this.frameMs / 2) {
+      this.request = null;
+      return Promise.resolve(this.deliver(seq, this.lastFrame));
+    }
+
+    return new Promise((resolve, reject) => {
+      this.request = { seq, target, resolve, reject };
+      const ahead = target - this.positionMs;
+      if (!this.proc || ahead < -this.frameMs / 2 || ahead > READ_AHEAD_MS) {
+        this.restart(target);
+      } else {
+        this.proc.stdout.resume();
+        this.drain();
+      }
+    });
+  }
+
+  deliver(seq, frame) {
+    this.onFrame({ session_id: this.id, seq, at_ms: Math.round(frame.atMs), jpeg: frame.jpeg });
+    return { seq, at_ms: Math.round(frame.atMs) };
+  }
+
+  restart(startMs) {
+    this.stopProcess();
+    const splitter = new MjpegSplitter();
+    const proc = this.spawnProcess(scrubArgs(this.srcPath, startMs, this.format));
+    this.proc = proc;
+    this.splitter = splitter;
+    this.streamStartMs = startMs;
+    this.frameIndex = 0;
+    if (this.trackProcessFn) this.trackProcessFn(proc);
+
+    proc.stdout.on('data', (chunk) => {
+      if (this.proc !== proc) return;
+      splitter.push(chunk);
+      this.drain();
+    });
+    proc.on('error', (err) => this.fail(proc, err));
+    // 'close' (not 'exit') so frames still buffered in the pipe are read first
+    proc.on('close', () => this.fail(proc, null));
+  }
+
+  /**
+   * Read frames until the pending request is answered, then pause the pipe (ffmpeg blocks on it)
+   */
+  drain() {
+    const proc = this.proc;
+    while (this.request && proc) {
+      const jpeg = this.splitter.next();
+      if (!jpeg) return;
+      this.lastFrame = { atMs: this.positionMs, jpeg };
+      this.frameIndex++;
+      if (this.lastFrame.atMs + this.frameMs / 2 >= this.request.target) {
+        const { seq, resolve } = this.request;
+        this.request = null;
+        resolve(this.deliver(seq, this.lastFrame));
+      }
+    }
+    if (proc && !this.request) {
+      proc.stdout.pause();
+    }
+  }
+
+  /**
+   * The stream ended (end of file, crash or spawn error): answer with the last frame if there is one
+   */
+  fail(proc, err) {
+    if (this.proc !== proc) return;
+    this.proc = null;
+    if (!this.request) return;
+    const { seq, resolve, reject } = this.request;
+    this.request = null;
+    // Past the end of the file the stream's last frame stands in
+    if (this.frameIndex > 0 && !err) {
+      resolve(this.deliver(seq, this.lastFrame));
+    } else {
+      reject(new StarscapeError(ErrorCode.FFMPEG_FAILED, `Scrub stream failed: ${err ? err.message : 'no frame at this position'}`, { path: this.srcPath }));
+    }
+  }
+
+  stopProcess() {
+    const proc = this.proc;
+    this.proc = null;
+    if (proc) {
+      proc.stdout.removeAllListeners('data');
+      proc.stdout.destroy();
+      proc.kill('SIGKILL');
+    }
+  }
+
+  close() {
+    if (this.request) {
+      this.request.resolve({ seq: this.request.seq, superseded: true });
+      this.request = null;
+    }
+    this.stopProcess();
+  }
+}
+
+const sessions = new Map();
+let sessionCounter = 0;
+
+/**
+ * Open (or reuse) the scrub session for a file at a frame size and rate
+ * @returns {ScrubSession}
+ */
+function openScrubSession(srcPath, options = {}) {
+  const format = normalizeOptions(options);
+  for (const session of sessions.values()) {
+    if (session.srcPath === srcPath && session.format.width === format.width && session.format.fps === format.fps) {
+      session.onFrame = options.onFrame || session.onFrame;
+      session.lastUsed = Date.now();
+      return session;
+    }
+  }
+  if (sessions.size >= MAX_SCRUB_SESSIONS) {
+    const oldest = [...sessions.values()].reduce((a, b) => (a.lastUsed <= b.lastUsed ? a : b));
+    closeScrubSession(oldest.id);
+  }
+  const session = new ScrubSession(`scrub_${++sessionCounter}`, srcPath, { ...options, ...format });
+  sessions.set(session.id, session);
+  return session;
+}
+
+function getScrubSession(id) {
+  const session = sessions.get(id);
+  if (!session) {
+    throw new StarscapeError(ErrorCode.NOT_FOUND, `Scrub session not found: ${id}`, { session_id: id });
+  }
+  return session;
+}
+
+function closeScrubSession(id) {
+  const session = sessions.get(id);
+  if (session) {
+    session.close();
+    sessions.delete(id);
+  }
+  return !!session;
+}
+
+function closeAllScrubSessions() {
+  for (const id of [...sessions.keys()]) {
+    closeScrubSession(id);
+  }
+}
+
+module.exports = {
+  SCRUB_DEFAULTS,
+  READ_AHEAD_MS,
+  MjpegSplitter,
+  ScrubSession,
+  scrubArgs,
+  openScrubSession,
+  getScrubSession,
+  closeScrubSession,
+  closeAllScrubSessions,
+};
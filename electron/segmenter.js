@@ -0,0 +1,107 @@
+const { spawn } = require('child_process');
+const fs = require('fs-extra');
+const path = require('path');
+const { resolveFfmpegPath } = require('./ffmpeg');
+const { ErrorCode, StarscapeError } = require('./errors');
+
+/**
+ * Recording segmenter - writes a recording to disk as it arrives, rolling over to a new file every N minutes
+ * The renderer's recorder streams its WebM chunks here; ffmpeg's segment muxer stream-copies them
+ * into numbered files, so a long session is never held in memory and a crash loses at most the
+ * open segment. Cuts land on the first keyframe after each boundary.
+ */
+
+/**
+ * ffmpeg arguments reading WebM on stdin and writing numbered segments
+ * @param {string} pattern - Output pattern with a %03d segment number
+ */
+function segmentArgs(pattern, segmentSeconds) {
+  return [
+    '-hide_banner', '-loglevel', 'error',
+    '-f', 'webm', '-i', 'pipe:0',
+    '-c', 'copy',
+    '-f', 'segment',
+    '-segment_time', String(segmentSeconds),
+    '-segment_format', 'webm',
+    '-reset_timestamps', '1',
+    pattern,
+  ];
+}
+
+/**
+ * Segment files written for a base name, in order
+ */
+async function listSegments(dir, baseName) {
+  const files = await fs.readdir(dir).catch(() => []);
+  return files
+    .filter((file) => new RegExp(`^${baseName.replace(/[.*+?^${}()|[\]\\]/g, '\\$&')}_\\d{3}\\.webm$`).test(file))
+    .sort()
+    .map((file) => path.join(dir, file));
+}
+
+class RecordingSegmenter {
+  /**
+   * @param {string} dir - Folder the segments go in
+   * @param {string} baseName - Segment file prefix (segments are `${baseName}_000.webm`...)
+   * @param {number} segmentMinutes - Segment length
+   * @param {Object} options - { spawnProcess: (args) => ChildProcess, trackProcessFn }
+   */
+  constructor(dir, baseName, segmentMinutes, options = {}) {
+    this.dir = dir;
+    this.baseName = baseName;
+    const spawnProcess = options.spawnProcess || ((args) => spawn(resolveFfmpegPath(), args, { stdio: ['pipe', 'ignore', 'pipe'] }));
+    this.proc = spawnProcess(segmentArgs(path.join(dir, `${baseName}_%03d.webm`), Math.round(segmentMinutes * 60)));
+    if (options.trackProcessFn) options.trackProcessFn(this.proc);
+
+    let stderr = '';
+    if (this.proc.stderr) {
+      this.proc.stderr.on('data', (chunk) => {
+        stderr = (stderr + chunk).slice(-2000);
+      });
+    }
+    // Writes after ffmpeg has gone away would otherwise throw EPIPE
+    this.proc.stdin.on('error', () => {});
+    this.done = new Promise((resolve) => {
+      this.proc.on('error', (err) => resolve(err));
+      this.proc.on('close', (code) => {
+        resolve(code === 0 ? null : new StarscapeError(ErrorCode.FFMPEG_FAILED, `Recording segmenter failed: ${stderr.trim() || `exit ${code}`}`));
+      });
+    });
+    this.ended = false;
+  }
+
+  /**
+   * Append a chunk of the recording, waiting for ffmpeg to catch up when its pipe is full
+   */
+  async write(chunk) {
+    if (this.ended) {
+      throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, 'Recording has already been finished');
+    }
+    if (!this.proc.stdin.write(chunk)) {
+      await new Promise((resolve) => this.proc.stdin.once('drain', resolve));
+    }
+  }
+
+  /**
+   * Close the input and wait for the last segment to be written
+   * @returns {Promise<string[]>} Segment paths, in order
+   */
+  async finish() {
+    if (!this.ended) {
+      this.ended = true;
+      this.proc.stdin.end();
+    }
+    const error = await this.done;
+    const segments = await listSegments(this.dir, this.baseName);
+    if (error && segments.length === 0) {
+      throw error;
+    }
+    return segments;
+  }
+}
+
+module.exports = {
+  segmentArgs,
+  listSegments,
+  RecordingSegmenter,
+};
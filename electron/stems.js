@@ -0,0 +1,140 @@
+const ffmpeg = require('fluent-ffmpeg');
+const path = require('path');
+const { buildMixdownFilters } = require('./audio');
+const { isAlternateTrack } = require('./audioLanguages');
+
+/**
+ * Audio stems - the mix split by role for clients who mix externally
+ * Dialogue is the main track's own audio plus voice tracks, music is music tracks and sfx is
+ * sfx tracks and any audio track without a role. Commentary and dub tracks have their own
+ * streams (see audioLanguages.js) and aren't in any stem. Stems are left unducked and each covers the
+ * whole program, so they line up with the video and with each other from zero.
+ */
+
+const STEM_MODES = ['files', 'streams'];
+
+const STEM_NAMES = ['dialogue', 'music', 'sfx'];
+
+const STEM_TITLES = {
+  dialogue: 'Dialogue',
+  music: 'Music',
+  sfx: 'SFX',
+};
+
+/**
+ * Stem a track's clips belong to (null for commentary/dub tracks)
+ * @param {Object} trackSettings - { audioRole } from plan.audioTracks
+ */
+function stemForTrack(trackSettings = {}) {
+  if (isAlternateTrack(trackSettings)) return null;
+  if (trackSettings.audioRole === 'voice') return 'dialogue';
+  if (trackSettings.audioRole === 'music') return 'music';
+  return 'sfx';
+}
+
+/**
+ * Split audio-track clips into stems, dropping stems with nothing in them
+ * @param {Array} audioClips - Plan clips from audio tracks
+ * @param {Object} audioTracks - Map of trackId -> { audioRole, ducking }
+ * @param {boolean} baseHasAudio - Whether the program carries main-track audio (it goes in dialogue)
+ * @returns {Array<{ name: string, clips: Array, includeBase: boolean }>}
+ */
+function groupStems(audioClips, audioTracks = {}, baseHasAudio = false) {
+  return STEM_NAMES.map((name) => ({
+    name,
+    clips: audioClips.filter((clip) => stemForTrack(audioTracks[clip.trackId]) === name),
+    includeBase: name === 'dialogue' && baseHasAudio,
+  })).filter((stem) => stem.includeBase || stem.clips.length > 0);
+}
+
+/**
+ * Render one stem to a 24-bit 48 kHz WAV
+ * @param {string} programPath - Program file; its audio is the main track's (before the mixdown)
+ * @param {Object} stem - Stem from groupStems
+ * @param {number} timelineStartMs - Timeline position of the program start
+ * @param {number} durationSec - Program duration in seconds
+ * @param {Object} audioTracks - Map of trackId -> { audioRole, ducking }
+ * @param {string} outputPath - WAV path
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ */
+function renderStem(programPath, stem, timelineStartMs, durationSec, audioTracks, outputPath, trackProcessFn) {
+  return new Promise((resolve, reject) => {
+    const command = ffmpeg().input(programPath);
+    for (const clip of stem.clips) {
+      command.input(clip.srcPath);
+    }
+
+    const { filters, output } = buildMixdownFilters(stem.clips, timelineStartMs, durationSec, stem.includeBase, audioTracks);
+
+    command
+      .complexFilter(filters)
+      .outputOptions([`-map [${output}]`, '-c:a pcm_s24le', '-ar 48000'])
+      .output(outputPath)
+      .on('start', () => {
+        if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+      })
+      .on('end', () => resolve())
+      .on('error', (err) => reject(err))
+      .run();
+  });
+}
+
+/**
+ * Output options adding stem WAVs (inputs 1..n) as extra audio streams after the program mix
+ * MOV keeps the stems as PCM; MP4 can't carry PCM, so they're encoded like the mix.
+ * @param {string[]} stemNames - Stem names in input order
+ * @param {string} ext - Output container extension
+ * @param {Object} encoding - Resolved encoder settings (see encoding.js)
+ */
+function stemStreamOptions(stemNames, ext, encoding) {
+  const options = ['-map', '0:v?', '-map', '0:a', '-c:v', 'copy', '-c:a:0', 'copy', '-metadata:s:a:0', 'title=Mix'];
+  stemNames.forEach((name, i) => {
+    const stream = i + 1;
+    options.push('-map', `${stream}:a`);
+    if (ext === 'mov') {
+      options.push(`-c:a:${stream}`, 'pcm_s24le');
+    } else {
+      options.push(`-c:a:${stream}`, encoding.audioCodec, `-b:a:${stream}`, `${encoding.audioBitrate}k`);
+    }
+    options.push(`-metadata:s:a:${stream}`, `title=${STEM_TITLES[name]}`);
+  });
+  return options;
+}
+
+/**
+ * Mux rendered stems into the program file as additional audio streams
+ * @param {string} programPath - Program file with the final mix
+ * @param {Array<{ name: string, path: string }>} stems - Rendered stem WAVs
+ * @param {string} outputPath - Output path (its extension picks the stem codec)
+ * @param {Object} encoding - Resolved encoder settings (see encoding.js)
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ */
+function muxStemStreams(programPath, stems, outputPath, encoding, trackProcessFn) {
+  const ext = path.extname(outputPath).slice(1).toLowerCase();
+  return new Promise((resolve, reject) => {
+    const command = ffmpeg().input(programPath);
+    for (const stem of stems) {
+      command.input(stem.path);
+    }
+
+    command
+      .outputOptions(...stemStreamOptions(stems.map((stem) => stem.name), ext, encoding))
+      .output(outputPath)
+      .on('start', () => {
+        if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+      })
+      .on('end', () => resolve())
+      .on('error', (err) => reject(err))
+      .run();
+  });
+}
+
+module.exports = {
+  STEM_MODES,
+  STEM_NAMES,
+  stemForTrack,
+  groupStems,
+  renderStem,
+  stemStreamOptions,
+  muxStemStreams,
+};
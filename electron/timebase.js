@@ -0,0 +1,124 @@
+/**
+ * Frame-rate helpers - snap millisecond times to exact frame boundaries
+ */
+
+/**
+ * NTSC-style rates that are really N*1000/1001
+ */
+const NTSC_RATES = [23.976, 29.97, 47.952, 59.94, 119.88];
+
+/**
+ * Convert a frame rate into an exact rational { num, den }
+ * Accepts numbers (29.97), strings ("30000/1001", "25") or rationals.
+ */
+function toRational(fps) {
+  if (fps && typeof fps === 'object' && fps.num > 0 && fps.den > 0) {
+    return { num: fps.num, den: fps.den };
+  }
+  if (typeof fps === 'string' && fps.includes('/')) {
+    const [num, den] = fps.split('/').map(Number);
+    if (num > 0 && den > 0) return { num, den };
+    return null;
+  }
+
+  const value = Number(fps);
+  if (!Number.isFinite(value) || value <= 0) {
+    return null;
+  }
+  const ntsc = NTSC_RATES.find((rate) => Math.abs(rate - value) < 0.01);
+  if (ntsc) {
+    return { num: Math.round(ntsc * 1.001) * 1000, den: 1001 };
+  }
+  if (Number.isInteger(value)) {
+    return { num: value, den: 1 };
+  }
+  return { num: Math.round(value * 1000), den: 1000 };
+}
+
+/**
+ * Nearest frame index for a time in milliseconds
+ */
+function msToFrame(ms, rate) {
+  return Math.round((ms * rate.num) / (rate.den * 1000));
+}
+
+/**
+ * Exact start time of a frame in milliseconds
+ */
+function frameToMs(frame, rate) {
+  return (frame * rate.den * 1000) / rate.num;
+}
+
+/**
+ * Snap a time to the nearest frame boundary (rounded to the microsecond)
+ */
+function quantizeMs(ms, rate) {
+  return Math.round(frameToMs(msToFrame(ms, rate), rate) * 1000) / 1000;
+}
+
+/**
+ * Snap a clip's timing to frame boundaries
+ * Start and end are snapped on the timeline and the source range follows, so the
+ * source and timeline durations stay identical at the clip's speed (looping clips keep their own range).
+ * @param {Object} clip - Object with inMs, outMs, startMs, endMs
+ * @param {{ num: number, den: number }} rate - Frame rate
+ * @returns {Object} A new clip object with snapped times
+ */
+function quantizeClip(clip, rate) {
+  const startMs = quantizeMs(clip.startMs, rate);
+  const endMs = Math.max(startMs + frameToMs(1, rate), quantizeMs(clip.endMs, rate));
+  const inMs = quantizeMs(clip.inMs, rate);
+  const outMs = clip.loop || clip.freezeFrame
+    ? Math.max(inMs + frameToMs(1, rate), quantizeMs(clip.outMs, rate))
+    : inMs + (endMs - startMs) * (clip.speed || 1);
+
+  return {
+    ...clip,
+    inMs,
+    outMs: Math.round(outMs * 1000) / 1000,
+    startMs,
+    endMs: Math.round(endMs * 1000) / 1000,
+  };
+}
+
+/**
+ * Snap every clip and marker in a project to frame boundaries
+ * @param {string} projectJson - Project JSON
+ * @param {number|string} fps - Frame rate (29.97, "30000/1001", ...)
+ * @returns {string} Quantized project JSON
+ */
+function quantizeProject(projectJson, fps) {
+  const rate = toRational(fps);
+  if (!rate) {
+    throw new Error(`Invalid frame rate: ${fps}`);
+  }
+
+  let project;
+  try {
+    project = JSON.parse(projectJson);
+  } catch (e) {
+    throw new Error(`Invalid project JSON: ${e.message}`);
+  }
+
+  for (const [clipId, clip] of Object.entries(project.clips || {})) {
+    if ([clip.inMs, clip.outMs, clip.startMs, clip.endMs].every(Number.isFinite)) {
+      project.clips[clipId] = quantizeClip(clip, rate);
+    }
+  }
+  for (const marker of Object.values(project.markers || {})) {
+    if (Number.isFinite(marker.timeMs)) {
+      marker.timeMs = quantizeMs(marker.timeMs, rate);
+    }
+  }
+
+  return JSON.stringify(project);
+}
+
+module.exports = {
+  toRational,
+  msToFrame,
+  frameToMs,
+  quantizeMs,
+  quantizeClip,
+  quantizeProject,
+};
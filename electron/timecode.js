@@ -0,0 +1,141 @@
+const { toRational, msToFrame, frameToMs } = require('./timebase');
+
+/**
+ * SMPTE timecode helpers (drop-frame aware) on rational frame rates
+ */
+
+const TIMECODE_PATTERN = /^(\d{1,2})[:;.](\d{2})[:;.](\d{2})([:;.])(\d{2,3})$/;
+
+/**
+ * Nominal (integer) frame count per timecode second, e.g. 30 for 29.97
+ */
+function nominalFps(rate) {
+  return Math.round(rate.num / rate.den);
+}
+
+/**
+ * Frames dropped per minute for drop-frame timecode (0 when the rate has no drop-frame form)
+ */
+function dropFramesPerMinute(rate) {
+  if (rate.den !== 1001) return 0;
+  const nominal = nominalFps(rate);
+  return nominal % 30 === 0 ? (nominal / 30) * 2 : 0;
+}
+
+/**
+ * Resolve whether drop-frame counting applies (defaults to on for 29.97/59.94)
+ */
+function useDropFrame(rate, dropFrame) {
+  const drop = dropFramesPerMinute(rate);
+  return drop > 0 && dropFrame !== false;
+}
+
+function pad(value, width = 2) {
+  return String(value).padStart(width, '0');
+}
+
+/**
+ * Convert a frame index to a SMPTE timecode string
+ * @param {number} frame - Frame index from 00:00:00:00
+ * @param {number|string|Object} fps - Frame rate (29.97, "30000/1001", { num, den })
+ * @param {Object} options - { dropFrame } (defaults to true for 29.97/59.94)
+ */
+function framesToTimecode(frame, fps, options = {}) {
+  const rate = toRational(fps);
+  if (!rate) {
+    throw new Error(`Invalid frame rate: ${fps}`);
+  }
+  const nominal = nominalFps(rate);
+  const dropFrame = useDropFrame(rate, options.dropFrame);
+  const sign = frame < 0 ? '-' : '';
+  let count = Math.abs(Math.round(frame));
+
+  if (dropFrame) {
+    const drop = dropFramesPerMinute(rate);
+    const framesPer10Min = nominal * 600 - drop * 9;
+    const framesPerMin = nominal * 60 - drop;
+    const tens = Math.floor(count / framesPer10Min);
+    const rem = count % framesPer10Min;
+    count += drop * 9 * tens;
+    if (rem >= drop) {
+      count += drop * Math.floor((rem - drop) / framesPerMin);
+    }
+  }
+
+  const ff = count % nominal;
+  const totalSeconds = Math.floor(count / nominal);
+  const ss = totalSeconds % 60;
+  const mm = Math.floor(totalSeconds / 60) % 60;
+  const hh = Math.floor(totalSeconds / 3600);
+  const separator = dropFrame ? ';' : ':';
+
+  return `${sign}${pad(hh)}:${pad(mm)}:${pad(ss)}${separator}${pad(ff, nominal > 100 ? 3 : 2)}`;
+}
+
+/**
+ * Parse a SMPTE timecode string to a frame index
+ * A ';' before the frames field selects drop-frame counting.
+ */
+function timecodeToFrames(timecode, fps, options = {}) {
+  const rate = toRational(fps);
+  if (!rate) {
+    throw new Error(`Invalid frame rate: ${fps}`);
+  }
+  const match = TIMECODE_PATTERN.exec(String(timecode).trim());
+  if (!match) {
+    throw new Error(`Invalid timecode: ${timecode}`);
+  }
+
+  const [, hh, mm, ss, separator, ff] = match;
+  const nominal = nominalFps(rate);
+  const frames = Number(ff);
+  if (Number(mm) > 59 || Number(ss) > 59 || frames >= nominal) {
+    throw new Error(`Invalid timecode: ${timecode}`);
+  }
+
+  const dropFrame = options.dropFrame !== undefined
+    ? useDropFrame(rate, options.dropFrame)
+    : separator === ';' && dropFramesPerMinute(rate) > 0;
+
+  let count = ((Number(hh) * 60 + Number(mm)) * 60 + Number(ss)) * nominal + frames;
+  if (dropFrame) {
+    const drop = dropFramesPerMinute(rate);
+    // Drop-frame skips the first labels of every minute except each tenth, so those don't exist
+    if (Number(ss) === 0 && frames < drop && Number(mm) % 10 !== 0) {
+      throw new Error(`Invalid timecode: ${timecode} (drop-frame skips this label)`);
+    }
+    const totalMinutes = Number(hh) * 60 + Number(mm);
+    count -= drop * (totalMinutes - Math.floor(totalMinutes / 10));
+  }
+  return count;
+}
+
+/**
+ * Convert milliseconds to timecode (rounded to the nearest frame)
+ */
+function msToTimecode(ms, fps, options = {}) {
+  const rate = toRational(fps);
+  if (!rate) {
+    throw new Error(`Invalid frame rate: ${fps}`);
+  }
+  return framesToTimecode(msToFrame(ms, rate), rate, options);
+}
+
+/**
+ * Convert timecode to milliseconds (exact frame start)
+ */
+function timecodeToMs(timecode, fps, options = {}) {
+  const rate = toRational(fps);
+  if (!rate) {
+    throw new Error(`Invalid frame rate: ${fps}`);
+  }
+  return frameToMs(timecodeToFrames(timecode, rate, options), rate);
+}
+
+module.exports = {
+  dropFramesPerMinute,
+  framesToTimecode,
+  timecodeToFrames,
+  msToTimecode,
+  timecodeToMs,
+};
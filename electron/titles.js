@@ -0,0 +1,283 @@
+const ffmpeg = require('fluent-ffmpeg');
+const fs = require('fs-extra');
+const { assColor, assTime, escapeAssText, textDirectionEncoding, assScript, assFilter, collectFonts } = require('./captions');
+const { clampOption } = require('./filterUtils');
+const { hashValue } = require('./planHash');
+const { assertCapabilities } = require('./capabilities');
+
+/**
+ * Title cards and lower thirds: parameterized templates rendered to transparent overlay clips
+ * Text is laid out as an ASS script (libass shaping), the optional logo is overlaid with ffmpeg,
+ * and the result is a PNG-in-MOV file with alpha that composites like any overlay asset.
+ */
+
+const ANIMATIONS = ['slide', 'fade', 'none'];
+
+// Length of the in/out animation
+const ANIMATION_MS = 400;
+
+const TITLE_FONT_FAMILY = 'Arial';
+
+/**
+ * Available templates; positions and sizes are fractions of the frame
+ */
+const TITLE_TEMPLATES = [
+  {
+    id: 'lower-third-bar',
+    name: 'Lower Third (Accent Bar)',
+    kind: 'lower_third',
+    fields: [
+      { key: 'title', label: 'Name', default: 'Jane Doe' },
+      { key: 'subtitle', label: 'Role', default: 'Director' },
+    ],
+    colors: { text: '#FFFFFF', accent: '#FFD400', background: null },
+    logo: { x: 0.06, y: 0.7, height: 0.1 },
+    layout: { x: 0.06, y: 0.82, align: 'left', titleSize: 0.05, subtitleSize: 0.032, bar: true },
+    animation: 'slide',
+    durationMs: 5000,
+  },
+  {
+    id: 'lower-third-box',
+    name: 'Lower Third (Box)',
+    kind: 'lower_third',
+    fields: [
+      { key: 'title', label: 'Name', default: 'Jane Doe' },
+      { key: 'subtitle', label: 'Role', default: 'Director' },
+    ],
+    colors: { text: '#FFFFFF', accent: '#FFD400', background: '#000000B0' },
+    logo: null,
+    layout: { x: 0.06, y: 0.82, align: 'left', titleSize: 0.045, subtitleSize: 0.03, bar: false },
+    animation: 'fade',
+    durationMs: 5000,
+  },
+  {
+    id: 'title-card',
+    name: 'Title Card',
+    kind: 'title',
+    fields: [
+      { key: 'title', label: 'Title', default: 'Episode One' },
+      { key: 'subtitle', label: 'Subtitle', default: '' },
+    ],
+    colors: { text: '#FFFFFF', accent: '#FFD400', background: null },
+    logo: { x: 0.5, y: 0.25, height: 0.15 },
+    layout: { x: 0.5, y: 0.5, align: 'center', titleSize: 0.09, subtitleSize: 0.04, bar: false },
+    animation: 'fade',
+    durationMs: 4000,
+  },
+];
+
+/**
+ * Templates as shown to the renderer (layout internals left out)
+ */
+function listTitleTemplates() {
+  return TITLE_TEMPLATES.map(({ id, name, kind, fields, colors, logo, animation, durationMs }) => ({
+    id,
+    name,
+    kind,
+    fields,
+    colors,
+    has_logo_slot: !!logo,
+    animation,
+    duration_ms: durationMs,
+  }));
+}
+
+/**
+ * Merge render params over a template's defaults
+ * @param {Object} params - { templateId, fields, colors, logoPath, animation, durationMs, width, height, fps }
+ */
+function resolveTitleParams(params = {}) {
+  const template = TITLE_TEMPLATES.find((t) => t.id === params.templateId);
+  if (!template) {
+    throw new Error(`Unknown title template: ${params.templateId}`);
+  }
+  const fields = {};
+  for (const field of template.fields) {
+    const value = params.fields && params.fields[field.key];
+    fields[field.key] = typeof value === 'string' ? value : field.default;
+  }
+  const colors = { ...template.colors };
+  for (const key of Object.keys(colors)) {
+    if (params.colors && typeof params.colors[key] === 'string') {
+      colors[key] = params.colors[key];
+    }
+  }
+  const even = (value) => Math.floor(value / 2) * 2;
+  return {
+    template,
+    fields,
+    colors,
+    logoPath: template.logo && typeof params.logoPath === 'string' && params.logoPath ? params.logoPath : null,
+    animation: ANIMATIONS.includes(params.animation) ? params.animation : template.animation,
+    durationMs: Math.round(clampOption(params.durationMs, 1000, 60000, template.durationMs)),
+    width: even(clampOption(params.width, 64, 7680, 1920)),
+    height: even(clampOption(params.height, 64, 4320, 1080)),
+    fps: Math.round(clampOption(params.fps, 1, 120, 30)),
+  };
+}
+
+/**
+ * Override tags animating one line in and out
+ * slide: moves in from off-frame (left-aligned) or up from below (centered), then fades out
+ */
+function animationTags(animation, x, y, width, height, align) {
+  const pos = `\\pos(${x},${y})`;
+  if (animation === 'slide') {
+    const from = align === 'left' ? `${-Math.round(width * 0.4)},${y}` : `${x},${y + Math.round(height * 0.05)}`;
+    return `\\move(${from},${x},${y},0,${ANIMATION_MS})\\fad(0,${ANIMATION_MS})`;
+  }
+  if (animation === 'fade') {
+    return `${pos}\\fad(${ANIMATION_MS},${ANIMATION_MS})`;
+  }
+  return pos;
+}
+
+/**
+ * Lay out a title as an ASS script over a transparent frame
+ */
+function buildTitleScript(resolved) {
+  const { template, fields, colors, animation, durationMs, width, height } = resolved;
+  const { layout } = template;
+  const titleSize = Math.round(height * layout.titleSize);
+  const subtitleSize = Math.round(height * layout.subtitleSize);
+  const boxed = !!colors.background;
+  const outline = boxed ? assColor(colors.background) : '&H00000000';
+  const borderStyle = boxed ? 3 : 1;
+  const pad = boxed ? Math.round(titleSize * 0.3) : Math.max(1, Math.round(height / 540));
+  // Bottom-anchored (1/2) so the title sits on the layout line and the subtitle below it
+  const alignment = layout.align === 'left' ? 1 : 2;
+  const rtl = textDirectionEncoding(`${fields.title} ${fields.subtitle || ''}`);
+  const styles = [
+    `Style: Title,${TITLE_FONT_FAMILY},${titleSize},${assColor(colors.text)},${assColor(colors.text)},${outline},&H00000000,-1,0,0,0,100,100,0,0,${borderStyle},${pad},0,${alignment},0,0,0,${rtl}`,
+    `Style: Subtitle,${TITLE_FONT_FAMILY},${subtitleSize},${assColor(colors.text)},${assColor(colors.text)},${outline},&H00000000,0,0,0,0,100,100,0,0,${borderStyle},${pad},0,${alignment === 1 ? 7 : 8},0,0,0,${rtl}`,
+    `Style: Bar,${TITLE_FONT_FAMILY},10,${assColor(colors.accent)},${assColor(colors.accent)},&H00000000,&H00000000,0,0,0,0,100,100,0,0,1,0,0,7,0,0,0,1`,
+  ];
+
+  const x = Math.round(width * layout.x);
+  const y = Math.round(height * layout.y);
+  const gap = Math.round(subtitleSize * 0.4);
+  const textX = layout.bar ? x + Math.round(width * 0.015) : x;
+  const start = assTime(0);
+  const end = assTime(durationMs);
+  const events = [];
+  const line = (style, tags, text) => `Dialogue: 0,${start},${end},${style},,0,0,0,,{${tags}}${text}`;
+
+  if (layout.bar) {
+    // Vertical accent bar spanning both lines, drawn with an ASS vector shape
+    const barHeight = titleSize + (fields.subtitle ? gap + subtitleSize : 0);
+    const barWidth = Math.max(4, Math.round(width * 0.006));
+    events.push(line('Bar', `${animationTags(animation, x, y - titleSize, width, height, 'left')}\\p1`, `m 0 0 l ${barWidth} 0 ${barWidth} ${barHeight} 0 ${barHeight}`));
+  }
+  events.push(line('Title', animationTags(animation, textX, y, width, height, layout.align), escapeAssText(fields.title)));
+  if (fields.subtitle) {
+    events.push(line('Subtitle', animationTags(animation, textX, y + gap, width, height, layout.align), escapeAssText(fields.subtitle)));
+  }
+  return assScript(width, height, styles, events);
+}
+
+/**
+ * Filter graph: ASS text over transparent black, plus the logo faded with the text
+ */
+function buildTitleFilterGraph(resolved, assPath, fontsDir) {
+  const { template, logoPath, animation, durationMs, width, height } = resolved;
+  const text = `[0:v]format=rgba,${assFilter(assPath, fontsDir)}`;
+  if (!logoPath) {
+    return [`${text}[v]`];
+  }
+  const logoHeight = Math.round(height * template.logo.height / 2) * 2;
+  const logoX = template.layout.align === 'left'
+    ? Math.round(width * template.logo.x)
+    : `${Math.round(width * template.logo.x)}-w/2`;
+  const logoY = `${Math.round(height * template.logo.y)}-h`;
+  const fades = animation === 'none'
+    ? ''
+    : `,fade=t=in:st=0:d=${ANIMATION_MS / 1000}:alpha=1,fade=t=out:st=${(durationMs - ANIMATION_MS) / 1000}:d=${ANIMATION_MS / 1000}:alpha=1`;
+  return [
+    `${text}[t]`,
+    `[1:v]scale=-2:${logoHeight},format=rgba${fades}[logo]`,
+    `[t][logo]overlay=x=${logoX}:y=${logoY}:format=auto[v]`,
+  ];
+}
+
+/**
+ * Render a title template to a transparent overlay clip (cached by its parameters)
+ * @param {Object} params - See resolveTitleParams
+ * @param {Object} cache - CacheDirs
+ * @param {Function} trackProcessFn - Function to track ffmpeg process
+ * @returns {Promise<{ path: string, duration_ms: number, width: number, height: number, cached: boolean }>}
+ */
+async function renderTitle(params, cache, trackProcessFn) {
+  const resolved = resolveTitleParams(params);
+  const { template, logoPath, durationMs, width, height, fps } = resolved;
+
+  const logoStats = logoPath ? await fs.stat(logoPath) : null;
+  const key = hashValue({
+    template: template.id,
+    fields: resolved.fields,
+    colors: resolved.colors,
+    logo: logoStats ? { path: logoPath, size: logoStats.size, mtimeMs: logoStats.mtimeMs } : null,
+    animation: resolved.animation,
+    durationMs,
+    width,
+    height,
+    fps,
+  });
+  const outputPath = cache.titlePath(key);
+  const result = { path: outputPath, duration_ms: durationMs, width, height };
+  if (await fs.pathExists(outputPath)) {
+    return { ...result, cached: true };
+  }
+
+  await assertCapabilities({
+    filters: ['ass', 'format', ...(logoPath ? ['scale', 'fade', 'overlay'] : [])],
+    encoders: ['png'],
+    feature: 'Title rendering',
+  });
+
+  const assPath = cache.stagePath(`title_${key}`, 'text', 'ass');
+  const fontsDir = `${assPath}.fonts`;
+  await fs.writeFile(assPath, buildTitleScript(resolved), 'utf8');
+  const partialPath = `${outputPath}.partial.mov`;
+
+  try {
+    const fontCount = await collectFonts(
+      [{ family: TITLE_FONT_FAMILY, bold: true }, { family: TITLE_FONT_FAMILY }],
+      Object.values(resolved.fields),
+      fontsDir
+    );
+    await new Promise((resolve, reject) => {
+      const command = ffmpeg()
+        .input(`color=c=black@0.0:s=${width}x${height}:r=${fps}:d=${durationMs / 1000}`)
+        .inputFormat('lavfi');
+      if (logoPath) {
+        command.input(logoPath).inputOptions(['-loop 1', `-t ${durationMs / 1000}`]);
+      }
+      command
+        .complexFilter(buildTitleFilterGraph(resolved, assPath, fontCount > 0 ? fontsDir : null))
+        .outputOptions(['-map [v]', '-c:v png', '-pix_fmt rgba', `-t ${durationMs / 1000}`])
+        .output(partialPath)
+        .on('start', () => {
+          if (trackProcessFn && command.ffmpegProc) trackProcessFn(command.ffmpegProc);
+        })
+        .on('end', () => resolve())
+        .on('error', (err) => reject(err));
+
+      command.run();
+    });
+    await fs.move(partialPath, outputPath, { overwrite: true });
+  } finally {
+    await fs.remove(assPath);
+    await fs.remove(fontsDir);
+    await fs.remove(partialPath);
+  }
+
+  return { ...result, cached: false };
+}
+
+module.exports = {
+  TITLE_TEMPLATES,
+  listTitleTemplates,
+  resolveTitleParams,
+  buildTitleScript,
+  renderTitle,
+};
@@ -0,0 +1,211 @@
+const fs = require('fs-extra');
+const path = require('path');
+const { buildPlan } = require('./editPlan');
+const { probeMedia } = require('./metadata');
+const { CANVAS_WIDTH, CANVAS_HEIGHT } = require('./composite');
+const { channelMapMaxChannel } = require('./audio');
+const { applyQualityProfile, resolveEncoding, encodingIssues } = require('./encoding');
+
+/**
+ * Video codecs the export pipeline can decode reliably
+ */
+const SUPPORTED_VIDEO_CODECS = [
+  'h264', 'hevc', 'vp8', 'vp9', 'av1', 'mpeg4', 'mpeg2video', 'prores', 'dnxhd',
+  'mjpeg', 'png', 'gif', 'bmp', 'webp', 'tiff',
+];
+
+/**
+ * Audio codecs the export pipeline can decode reliably
+ */
+const SUPPORTED_AUDIO_CODECS = [
+  'aac', 'mp3', 'opus', 'vorbis', 'flac', 'alac', 'ac3', 'eac3',
+  'pcm_s16le', 'pcm_s24le', 'pcm_s32le', 'pcm_f32le', 'pcm_s16be',
+];
+
+/**
+ * Out-of-range tolerance for in/out points (container durations are rounded)
+ */
+const DURATION_TOLERANCE_MS = 50;
+
+/**
+ * Fraction of the canvas reserved as a margin for the action-safe area
+ */
+const ACTION_SAFE_MARGIN = 0.05;
+
+const IMAGE_EXTENSIONS = ['.jpg', '.jpeg', '.png', '.gif', '.bmp', '.webp'];
+
+function isImageFile(filePath) {
+  return IMAGE_EXTENSIONS.includes(path.extname(filePath).toLowerCase());
+}
+
+/**
+ * Create a structured warning
+ * @param {'error'|'warning'} severity - Errors will make the export fail; warnings will not
+ */
+function warning(severity, code, message, clip = null) {
+  return {
+    severity,
+    code,
+    message,
+    clip_id: clip ? clip.clipId || null : null,
+    track_id: clip ? clip.trackId || null : null,
+  };
+}
+
+/**
+ * Check overlay placement against the canvas and its action-safe area
+ */
+function checkSafeArea(clip) {
+  const node = clip.canvasNode;
+  if (!node) return [];
+
+  const right = node.x + node.width;
+  const bottom = node.y + node.height;
+
+  if (right <= 0 || bottom <= 0 || node.x >= CANVAS_WIDTH || node.y >= CANVAS_HEIGHT) {
+    return [warning('warning', 'off_canvas', 'Overlay is entirely outside the frame', clip)];
+  }
+
+  const marginX = CANVAS_WIDTH * ACTION_SAFE_MARGIN;
+  const marginY = CANVAS_HEIGHT * ACTION_SAFE_MARGIN;
+  const fullFrame = node.width >= CANVAS_WIDTH && node.height >= CANVAS_HEIGHT;
+  if (!fullFrame && (node.x < marginX || node.y < marginY || right > CANVAS_WIDTH - marginX || bottom > CANVAS_HEIGHT - marginY)) {
+    return [warning('warning', 'outside_safe_area', 'Overlay extends outside the action-safe area', clip)];
+  }
+  return [];
+}
+
+/**
+ * Validate a project before export
+ * @param {string} projectJson - Project JSON in backend format
+ * @param {Object} settings - Export settings ({ width, height, fps, ... })
+ * @returns {Promise<{ ok: boolean, warnings: Array }>} ok is false when any error-severity issue was found
+ */
+async function validatePlan(projectJson, settings = {}) {
+  let plan;
+  try {
+    plan = buildPlan(projectJson, { fps: settings.fps });
+  } catch (error) {
+    return { ok: false, warnings: [warning('error', 'invalid_plan', error.message)] };
+  }
+
+  const warnings = [];
+  // Generator clips have no source file to check
+  const allClips = [
+    ...plan.mainTrack,
+    ...plan.overlayTrack,
+    ...plan.audioTrack,
+  ].filter((clip) => !clip.generator);
+
+  if (plan.mainTrack.length === 0) {
+    warnings.push(warning('error', 'empty_main_track', 'The main video track has no clips'));
+  }
+
+  // Pixel format, profile and color tags the chosen encoder or spec can't take
+  for (const issue of encodingIssues(resolveEncoding(applyQualityProfile(settings)))) {
+    warnings.push(warning('error', issue.code, issue.message));
+  }
+
+  // Probe each source once
+  const probes = new Map();
+  for (const clip of allClips) {
+    if (probes.has(clip.srcPath)) continue;
+    if (!(await fs.pathExists(clip.srcPath))) {
+      probes.set(clip.srcPath, { missing: true });
+      continue;
+    }
+    try {
+      probes.set(clip.srcPath, { meta: await probeMedia(clip.srcPath) });
+    } catch (error) {
+      probes.set(clip.srcPath, { error: error.message || String(error) });
+    }
+  }
+
+  const targetWidth = settings.width > 0 ? settings.width : null;
+  const targetHeight = settings.height > 0 ? settings.height : null;
+  const audioClipSet = new Set(plan.audioTrack);
+
+  for (const clip of allClips) {
+    const name = path.basename(clip.srcPath);
+    const probe = probes.get(clip.srcPath);
+
+    if (probe.missing) {
+      warnings.push(warning('error', 'missing_file', `Source file not found: ${name}`, clip));
+      continue;
+    }
+    if (probe.error) {
+      warnings.push(warning('error', 'unreadable_file', `Could not read ${name}: ${probe.error}`, clip));
+      continue;
+    }
+
+    const meta = probe.meta;
+    const isImage = isImageFile(clip.srcPath);
+
+    // In/out points must lie within the source (freeze pieces only need their held frame)
+    if (!isImage && meta.duration_ms > 0) {
+      const lastNeededMs = clip.freezeFrame ? clip.inMs : clip.outMs;
+      if (clip.inMs < 0 || lastNeededMs > meta.duration_ms + DURATION_TOLERANCE_MS) {
+        warnings.push(warning(
+          'error',
+          'out_of_range',
+          `${name}: clip uses ${clip.inMs}-${lastNeededMs}ms but the source is ${meta.duration_ms}ms long`,
+          clip
+        ));
+      }
+    }
+
+    if (meta.codec_video && !SUPPORTED_VIDEO_CODECS.includes(meta.codec_video)) {
+      warnings.push(warning('warning', 'unsupported_codec', `${name}: video codec ${meta.codec_video} may not decode correctly`, clip));
+    }
+    if (meta.codec_audio && !SUPPORTED_AUDIO_CODECS.includes(meta.codec_audio)) {
+      warnings.push(warning('warning', 'unsupported_codec', `${name}: audio codec ${meta.codec_audio} may not decode correctly`, clip));
+    }
+
+    // Channel routing can only read channels the source has
+    const maxChannel = channelMapMaxChannel(clip.audioChannels);
+    if (maxChannel >= 0 && meta.has_audio && meta.channels && maxChannel >= meta.channels) {
+      warnings.push(warning(
+        'error',
+        'channel_out_of_range',
+        `${name}: channel mapping uses channel ${maxChannel + 1} but the source has ${meta.channels}`,
+        clip
+      ));
+    }
+
+    if (audioClipSet.has(clip)) {
+      if (!meta.has_audio) {
+        warnings.push(warning('warning', 'no_audio', `${name} is on an audio track but has no audio stream`, clip));
+      }
+      continue;
+    }
+
+    if (!isImage && settings.fps > 0 && meta.fps && Math.abs(meta.fps - settings.fps) > 0.01) {
+      warnings.push(warning('warning', 'fps_mismatch', `${name} is ${meta.fps} fps; export is ${settings.fps} fps`, clip));
+    }
+
+    if (targetWidth && targetHeight && meta.width && meta.height) {
+      const sourceAspect = meta.width / meta.height;
+      const targetAspect = targetWidth / targetHeight;
+      if (plan.mainTrack.includes(clip) && Math.abs(sourceAspect - targetAspect) > 0.01) {
+        warnings.push(warning('warning', 'resolution_mismatch', `${name} (${meta.width}x${meta.height}) will be letterboxed in ${targetWidth}x${targetHeight}`, clip));
+      } else if (meta.width < targetWidth && meta.height < targetHeight) {
+        warnings.push(warning('warning', 'upscaled', `${name} (${meta.width}x${meta.height}) will be upscaled to ${targetWidth}x${targetHeight}`, clip));
+      }
+    }
+  }
+
+  for (const clip of plan.overlayTrack) {
+    warnings.push(...checkSafeArea(clip));
+  }
+
+  return {
+    ok: !warnings.some((w) => w.severity === 'error'),
+    warnings,
+  };
+}
+
+module.exports = {
+  SUPPORTED_VIDEO_CODECS,
+  SUPPORTED_AUDIO_CODECS,
+  validatePlan,
+};
@@ -0,0 +1,151 @@
+const { execFile } = require('child_process');
+const fs = require('fs');
+const path = require('path');
+
+/**
+ * Source volumes - whether a file lives on a local disk, a network share or a removable drive
+ * Media referenced in place from a share or a card breaks the project once the drive goes away,
+ * so ingest copies it even when asked to link. Each platform reports mounts differently: Linux
+ * has /proc/self/mounts (plus /sys/block/<disk>/removable), macOS prints them from mount, and
+ * Windows gives a drive type per letter (UNC paths are always network).
+ */
+
+const VOLUME_KINDS = ['local', 'network', 'removable'];
+
+const NETWORK_FS_TYPES = /^(nfs\d?|cifs|smb\w*|afpfs|webdav|davfs\w*|9p|ceph|glusterfs|lustre|fuse\.(sshfs|rclone|davfs2?|s3fs|gcsfuse))$/;
+
+// Win32_LogicalDisk DriveType
+const WINDOWS_DRIVE_TYPES = { 2: 'removable', 4: 'network', 5: 'removable' };
+
+/**
+ * /proc/self/mounts: "device mountpoint fstype options 0 0" with octal escapes (\040 for space)
+ * @param {Function} isRemovableDevice - (device) => boolean, from /sys/block on a live system
+ */
+function parseProcMounts(output, isRemovableDevice = () => false) {
+  const unescape = (value) => value.replace(/\\(\d{3})/g, (m, octal) => String.fromCharCode(parseInt(octal, 8)));
+  const mounts = [];
+  for (const line of output.split('\n')) {
+    const [device, mountPoint, fsType] = line.trim().split(/\s+/);
+    if (!device || !mountPoint || !fsType) continue;
+    const point = unescape(mountPoint);
+    let kind = 'local';
+    if (NETWORK_FS_TYPES.test(fsType)) {
+      kind = 'network';
+    } else if (/^\/(run\/)?media\//.test(point) || isRemovableDevice(device)) {
+      kind = 'removable';
+    }
+    mounts.push({ mountPoint: point, fsType, kind });
+  }
+  return mounts;
+}
+
+/**
+ * macOS mount: "//user@nas/share on /Volumes/share (smbfs, nodev, nosuid, mounted by user)"
+ * Local volumes under /Volumes are external disks and cards.
+ */
+function parseDarwinMounts(output) {
+  const mounts = [];
+  for (const line of output.split('\n')) {
+    const match = /^(.+) on (.+) \(([^,)]+)/.exec(line.trim());
+    if (!match) continue;
+    const [, , mountPoint, fsType] = match;
+    let kind = 'local';
+    if (NETWORK_FS_TYPES.test(fsType)) {
+      kind = 'network';
+    } else if (mountPoint.startsWith('/Volumes/')) {
+      kind = 'removable';
+    }
+    mounts.push({ mountPoint, fsType, kind });
+  }
+  return mounts;
+}
+
+/**
+ * "C: 3" lines (drive letter and Win32_LogicalDisk DriveType)
+ */
+function parseWindowsDrives(output) {
+  const mounts = [];
+  for (const line of output.split('\n')) {
+    const match = /^([A-Z]):\s+(\d+)/i.exec(line.trim());
+    if (!match) continue;
+    mounts.push({ mountPoint: `${match[1].toUpperCase()}:\\`, fsType: null, kind: WINDOWS_DRIVE_TYPES[match[2]] || 'local' });
+  }
+  return mounts;
+}
+
+/**
+ * Volume a path is on: the longest mount point containing it
+ * @returns {{ kind: string, mount_point: string|null, fs_type: string|null }}
+ */
+function classifyPath(filePath, mounts, platform = process.platform) {
+  const pathApi = platform === 'win32' ? path.win32 : path.posix;
+  // \\?\UNC\server\share and \\server\share are shares whatever the drive list says
+  if (platform === 'win32' && /^(\\\\\?\\UNC\\|\\\\(?!\?))/i.test(filePath)) {
+    return { kind: 'network', mount_point: null, fs_type: null };
+  }
+  const target = platform === 'win32' ? filePath.replace(/^\\\\\?\\/, '').toLowerCase() : filePath;
+  let best = null;
+  for (const mount of mounts) {
+    const point = platform === 'win32' ? mount.mountPoint.toLowerCase() : mount.mountPoint;
+    const relative = pathApi.relative(point, target);
+    const inside = relative === '' || (!relative.startsWith('..') && !pathApi.isAbsolute(relative));
+    if (inside && (!best || mount.mountPoint.length > best.mountPoint.length)) {
+      best = mount;
+    }
+  }
+  return best
+    ? { kind: best.kind, mount_point: best.mountPoint, fs_type: best.fsType }
+    : { kind: 'local', mount_point: null, fs_type: null };
+}
+
+function run(command, args) {
+  return new Promise((resolve, reject) => {
+    execFile(command, args, { timeout: 10000, windowsHide: true }, (err, stdout) => (err ? reject(err) : resolve(stdout)));
+  });
+}
+
+/**
+ * /sys/block/<disk>/removable for /dev/sdb1, /dev/mmcblk0p1, ...
+ */
+function linuxRemovable(device) {
+  const match = /^\/dev\/((?:sd[a-z]+|mmcblk\d+|nvme\d+n\d+))/.exec(device);
+  if (!match) return false;
+  try {
+    return fs.readFileSync(`/sys/block/${match[1]}/removable`, 'utf8').trim() === '1';
+  } catch (e) {
+    return false;
+  }
+}
+
+/**
+ * Current mounts on this machine (empty when they can't be listed, so everything counts as local)
+ */
+async function listMounts(platform = process.platform) {
+  try {
+    if (platform === 'linux') {
+      return parseProcMounts(fs.readFileSync('/proc/self/mounts', 'utf8'), linuxRemovable);
+    }
+    if (platform === 'darwin') {
+      return parseDarwinMounts(await run('/sbin/mount', []));
+    }
+    if (platform === 'win32') {
+      const output = await run('powershell.exe', [
+        '-NoProfile', '-Command',
+        'Get-CimInstance Win32_LogicalDisk | ForEach-Object { "$($_.DeviceID) $($_.DriveType)" }',
+      ]);
+      return parseWindowsDrives(output);
+    }
+  } catch (e) {
+    // Unknown volumes are treated as local
+  }
+  return [];
+}
+
+module.exports = {
+  VOLUME_KINDS,
+  parseProcMounts,
+  parseDarwinMounts,
+  parseWindowsDrives,
+  classifyPath,
+  listMounts,
+};
@@ -14,6 +14,8 @@ import { useProjectStore } from "@/store/projectStore";
 import { usePlaybackStore } from "@/store/playbackStore";
 import { useUiStore } from "@/store/uiStore";
 import { pixelsToMs, snapToTimeline, msToPixels, formatTimecode, resolveClipCollision, cn } from "@/lib/utils";
+import { buildProjectJson, projectJsonSource, PROJECT_JSON_FIELDS, type ProjectJsonSource } from "@/lib/projectJson";
+import { applyEdits, publishOpenProject } from "@/lib/bindings";
 import type { DragItem, Clip } from "@/types";
 import { Play, Music, Image } from "lucide-react";
 import "./globals.css";
@@ -49,6 +51,68 @@ function App() {
     };
   }, []);
 
+  // Keep the automation API's copy of the open project current (debounced while editing)
+  useEffect(() => {
+    let timer: ReturnType<typeof setTimeout> | null = null;
+    const publish = () => {
+      publishOpenProject(buildProjectJson(useProjectStore.getState()))
+        .catch((err) => console.error('Failed to publish project:', err));
+    };
+    publish();
+    const unsubscribe = useProjectStore.subscribe(() => {
+      if (timer) clearTimeout(timer);
+      timer = setTimeout(publish, 1000);
+    });
+    return () => {
+      unsubscribe();
+      if (timer) clearTimeout(timer);
+    };
+  }, []);
+
+  // Every project change goes through the backend, which diffs it against the last accepted
+  // project and rejects changes to locked tracks and clips; a rejected change is rolled back
+  useEffect(() => {
+    let accepted = projectJsonSource(useProjectStore.getState());
+    let inFlight = false;
+    let queued: ProjectJsonSource | null = null;
+    const submit = (project: ProjectJsonSource) => {
+      inFlight = true;
+      applyEdits(buildProjectJson(project))
+        .then(() => {
+          accepted = project;
+        })
+        .catch((err) => {
+          queued = null;
+          if (err.code === 'locked') {
+            console.warn('Edit rejected:', err.message);
+            useProjectStore.setState(accepted);
+          } else {
+            console.error('Failed to apply edits:', err);
+          }
+        })
+        .finally(() => {
+          inFlight = false;
+          // Only the latest change made while a check ran needs checking
+          if (queued) {
+            const next = queued;
+            queued = null;
+            submit(next);
+          }
+        });
+    };
+    submit(accepted);
+    const unsubscribe = useProjectStore.subscribe((state, prev) => {
+      if (PROJECT_JSON_FIELDS.every((field) => state[field] === prev[field])) return;
+      const project = projectJsonSource(state);
+      if (inFlight) {
+        queued = project;
+      } else {
+        submit(project);
+      }
+    });
+    return unsubscribe;
+  }, []);
+
   // Handle spacebar for play/pause toggle
   useEffect(() => {
     const handleKeyDown = (e: KeyboardEvent) => {
@@ -1,10 +1,19 @@
-import { useState } from "react";
+import { useEffect, useMemo, useState } from "react";
 import { Dialog, DialogContent, DialogHeader, DialogTitle } from "@/components/ui/dialog";
 import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
-import { Download, X, CheckCircle } from "lucide-react";
+import { Download, X, CheckCircle, AlertTriangle } from "lucide-react";
 import { useProjectStore } from "@/store/projectStore";
-import { exportProject, listenExportProgress, revealInFinder, type ExportSettings, type ProgressEvent } from "@/lib/bindings";
+import type { AspectRatio } from "@/types";
+import { estimateExport, exportProject, getPlanStats, listenExportProgress, revealInFinder, validatePlan, type EncoderPreset, type ExportEstimate, type ExportSettings, type PlanStats, type PlanWarning, type ProgressEvent, type QualityProfile, type Scaler, type StemMode } from "@/lib/bindings";
+import { formatFileSize, formatTimecode } from "@/lib/utils";
+import { buildProjectJson } from "@/lib/projectJson";
+
+// Matches the backend default (outputNames.js)
+const DEFAULT_FILENAME_TEMPLATE = '{project}_{preset}_{date}_{resolution}';
+
+// Settings and project edits settle this long before the plan is checked again
+const PLAN_CHECK_DELAY_MS = 300;
 
 interface ExportDialogProps {
   open: boolean;
@@ -12,7 +21,7 @@ interface ExportDialogProps {
 }
 
 export function ExportDialog({ open, onOpenChange }: ExportDialogProps) {
-  const { id, projectName, assets, tracks, clips, canvasNodes } = useProjectStore();
+  const { id, projectName, assets, tracks, clips, canvasNodes, multicamGroups, markers, audioCrossfadeMs } = useProjectStore();
   
   // Export settings state
   const [settings, setSettings] = useState<ExportSettings>({
@@ -21,6 +30,9 @@ export function ExportDialog({ open, onOpenChange }: ExportDialogProps) {
     height: 1080,
     fps: 30,
     bitrate: 5000,
+    aspectRatio: '16:9',
+    preset: 'veryfast',
+    qualityProfile: 'standard',
   });
   
   // Export state
@@ -28,12 +40,54 @@ export function ExportDialog({ open, onOpenChange }: ExportDialogProps) {
   const [progress, setProgress] = useState<ProgressEvent | null>(null);
   const [exportResult, setExportResult] = useState<{ path: string; success: boolean } | null>(null);
   const [error, setError] = useState<string | null>(null);
+  const [warnings, setWarnings] = useState<PlanWarning[]>([]);
+  const [stats, setStats] = useState<PlanStats | null>(null);
+  const [estimate, setEstimate] = useState<ExportEstimate | null>(null);
+  const [isEstimating, setIsEstimating] = useState(false);
   
-  // Filename state - initialize with cleaned project name
-  const [filename, setFilename] = useState<string>(
-    `${projectName.replace(/[^a-zA-Z0-9]/g, '_')}_export`
+  // Filename template - expanded by the backend, which numbers names that are already taken
+  const [filename, setFilename] = useState<string>(DEFAULT_FILENAME_TEMPLATE);
+
+  const projectJson = useMemo(
+    () => buildProjectJson({ id, projectName, assets, tracks, clips, canvasNodes, multicamGroups, markers, audioCrossfadeMs }),
+    [id, projectName, assets, tracks, clips, canvasNodes, multicamGroups, markers, audioCrossfadeMs]
   );
 
+  // Check the project against the current settings whenever the dialog is open (debounced while editing)
+  useEffect(() => {
+    if (!open) return;
+    let cancelled = false;
+    setEstimate(null);
+    const timer = setTimeout(() => {
+      validatePlan(projectJson, settings)
+        .then((result) => {
+          if (!cancelled) setWarnings(result.warnings);
+        })
+        .catch((err) => console.error('Failed to validate project:', err));
+      getPlanStats(projectJson, settings)
+        .then((result) => {
+          if (!cancelled) setStats(result);
+        })
+        .catch((err) => console.error('Failed to get plan stats:', err));
+    }, PLAN_CHECK_DELAY_MS);
+    return () => {
+      cancelled = true;
+      clearTimeout(timer);
+    };
+  }, [open, settings, projectJson]);
+
+  // Sample-encode (or look up earlier exports) to predict how long this export takes
+  const handleEstimate = async () => {
+    setIsEstimating(true);
+    try {
+      setEstimate(await estimateExport(projectJson, settings));
+    } catch (err) {
+      console.error('Failed to estimate export:', err);
+    } finally {
+      setIsEstimating(false);
+    }
+  };
+
   const handleExport = async () => {
     try {
       setIsExporting(true);
@@ -41,93 +95,13 @@ export function ExportDialog({ open, onOpenChange }: ExportDialogProps) {
       setProgress(null);
       setExportResult(null);
 
-      // Transform project state to backend format
-      // Backend expects: { id, assets: {}, clips: {}, tracks: {} }
-      // where tracks have clipOrder and role fields
-      const backendAssets: Record<string, any> = {};
-      assets.forEach(asset => {
-        // Convert media:// URL to file path
-        let srcPath = asset.url;
-        if (srcPath.startsWith('media://')) {
-          srcPath = srcPath.replace('media://', '');
-        }
-        
-        backendAssets[asset.id] = {
-          id: asset.id,
-          src: srcPath,
-          duration_ms: asset.duration,
-          width: asset.metadata.width,
-          height: asset.metadata.height,
-        };
-      });
-      
-      const backendClips: Record<string, any> = {};
-      Object.values(clips).forEach(clip => {
-        backendClips[clip.id] = {
-          id: clip.id,
-          assetId: clip.assetId,
-          inMs: clip.trimStartMs,    // Trim start in source
-          outMs: clip.trimEndMs,      // Trim end in source
-          startMs: clip.startMs,      // Position on timeline
-          endMs: clip.endMs,          // Position on timeline
-        };
-      });
-      
-      const backendTracks: Record<string, any> = {};
-      // Find first video track to determine main track
-      const firstVideoTrack = tracks.find(t => t.type === 'video');
-      
-      tracks.forEach((track) => {
-        // First video track is 'main', subsequent video tracks are 'overlay' (PiP)
-        // Audio tracks are 'overlay' (they'll be mixed together)
-        let role: string;
-        if (track.type === 'video') {
-          role = track.id === firstVideoTrack?.id ? 'main' : 'overlay';
-        } else {
-          role = 'overlay';
-        }
-        
-        backendTracks[track.id] = {
-          id: track.id,
-          name: track.name,
-          type: track.type,
-          role: role,
-          clipOrder: track.clips,
-        };
-      });
-
-      // Create project JSON in backend format
-      // Include canvasNodes for PiP transforms
-      const backendCanvasNodes: Record<string, any> = {};
-      Object.values(canvasNodes).forEach(node => {
-        backendCanvasNodes[node.id] = {
-          id: node.id,
-          clipId: node.clipId,
-          x: node.x,
-          y: node.y,
-          width: node.width,
-          height: node.height,
-          rotation: node.rotation,
-          opacity: node.opacity,
-        };
-      });
-
-      const projectJson = JSON.stringify({
-        id,
-        projectName,
-        assets: backendAssets,
-        clips: backendClips,
-        tracks: backendTracks,
-        canvasNodes: backendCanvasNodes,
-      });
-
       // Set up progress listener
       const cleanup = await listenExportProgress((event) => {
         setProgress(event);
       });
 
       // Start export
-      const result = await exportProject(projectJson, { ...settings, filename });
+      const result = await exportProject(projectJson, { ...settings, filenameTemplate: filename });
       
       cleanup();
       setExportResult({ path: result.path, success: true });
@@ -153,18 +127,84 @@ export function ExportDialog({ open, onOpenChange }: ExportDialogProps) {
     { value: 'mov', label: 'MOV' },
   ];
 
+  const aspectOptions: { value: AspectRatio; label: string }[] = [
+    { value: '16:9', label: '16:9' },
+    { value: '9:16', label: '9:16' },
+    { value: '1:1', label: '1:1' },
+    { value: '4:5', label: '4:5' },
+  ];
+
   const resolutionOptions = [
     { value: '720p', label: '720p (1280x720)', width: 1280, height: 720 },
     { value: '1080p', label: '1080p (1920x1080)', width: 1920, height: 1080 },
     { value: 'source', label: 'Source Resolution', width: -1, height: -1 }, // -1 indicates source resolution
   ];
 
+  // Picking a profile hands preset and rate control back to it; the rows below still override
+  const profileOptions: { value: QualityProfile; label: string }[] = [
+    { value: 'draft', label: 'Draft (half size)' },
+    { value: 'standard', label: 'Standard' },
+    { value: 'master', label: 'Master' },
+  ];
+
+  // Masters for post houses; these always render MOV
+  const mezzanineOptions: { value: QualityProfile; label: string }[] = [
+    { value: 'prores_422', label: 'ProRes 422' },
+    { value: 'prores_422hq', label: 'ProRes 422 HQ' },
+    { value: 'prores_4444', label: 'ProRes 4444' },
+    { value: 'dnxhr_hq', label: 'DNxHR HQ' },
+    { value: 'dnxhr_hqx', label: 'DNxHR HQX' },
+  ];
+
+  // Stems are rendered unducked so they can be remixed
+  const stemOptions: { value: StemMode | undefined; label: string }[] = [
+    { value: undefined, label: 'Off' },
+    { value: 'files', label: 'WAV Files' },
+    { value: 'streams', label: 'Audio Streams' },
+  ];
+
+  // Sharper scaling for sources smaller than the output frame
+  const scalerOptions: { value: Scaler | undefined; label: string }[] = [
+    { value: undefined, label: 'Standard' },
+    { value: 'lanczos', label: 'Lanczos' },
+    { value: 'spline', label: 'Spline' },
+  ];
+
   const qualityOptions = [
     { value: 'low', label: 'Low (2 Mbps)', bitrate: 2000 },
     { value: 'medium', label: 'Medium (5 Mbps)', bitrate: 5000 },
     { value: 'high', label: 'High (10 Mbps)', bitrate: 10000 },
   ];
 
+  const speedOptions: { value: EncoderPreset; label: string }[] = [
+    { value: 'veryfast', label: 'Fast' },
+    { value: 'medium', label: 'Balanced' },
+    { value: 'slow', label: 'Smallest File' },
+  ];
+
+  // CPU budget: background caps encoder threads, maximum renders two segments at once
+  const cpuOptions: { value: string; label: string; threads?: number; parallelSegments?: number }[] = [
+    { value: 'background', label: 'Background', threads: 2, parallelSegments: 1 },
+    { value: 'normal', label: 'Normal' },
+    { value: 'maximum', label: 'Maximum', parallelSegments: 2 },
+  ];
+
+  // Delivery color: untagged, Rec.709 tags, or 10-bit 4:2:2 Rec.709 for broadcast specs
+  const colorOptions: { value: string; label: string; fields: Partial<ExportSettings> }[] = [
+    { value: 'off', label: 'Untagged', fields: {} },
+    { value: 'rec709', label: 'Rec.709', fields: { colorPrimaries: 'bt709', colorTransfer: 'bt709', colorMatrix: 'bt709', colorRange: 'limited' } },
+    {
+      value: 'broadcast',
+      label: 'Rec.709 10-bit 4:2:2',
+      fields: { colorPrimaries: 'bt709', colorTransfer: 'bt709', colorMatrix: 'bt709', colorRange: 'limited', pixFmt: 'yuv422p10le', profile: 'high422' },
+    },
+  ];
+  const colorFieldsOff: Partial<ExportSettings> = {
+    colorPrimaries: undefined, colorTransfer: undefined, colorMatrix: undefined, colorRange: undefined, pixFmt: undefined, profile: undefined,
+  };
+  const selectedColor = colorOptions.find((option) =>
+    (Object.keys(colorFieldsOff) as (keyof ExportSettings)[]).every((key) => settings[key] === option.fields[key]))?.value;
+
   return (
     <Dialog open={open} onOpenChange={handleClose}>
       <DialogContent className="max-w-xl min-w-[500px]">
@@ -196,6 +236,24 @@ export function ExportDialog({ open, onOpenChange }: ExportDialogProps) {
                 </div>
               </div>
 
+              {/* Aspect Ratio */}
+              <div className="space-y-sm">
+                <label className="text-body-small text-white/70">Aspect Ratio</label>
+                <div className="flex space-x-sm">
+                  {aspectOptions.map((option) => (
+                    <Button
+                      key={option.value}
+                      variant={settings.aspectRatio === option.value ? "default" : "outline"}
+                      size="sm"
+                      onClick={() => setSettings(prev => ({ ...prev, aspectRatio: option.value }))}
+                      className="flex-1"
+                    >
+                      {option.label}
+                    </Button>
+                  ))}
+                </div>
+              </div>
+
               {/* Resolution */}
               <div className="space-y-sm">
                 <label className="text-body-small text-white/70">Resolution</label>
@@ -218,6 +276,41 @@ export function ExportDialog({ open, onOpenChange }: ExportDialogProps) {
                 </div>
               </div>
 
+              {/* Render target */}
+              <div className="space-y-sm">
+                <label className="text-body-small text-white/70">Profile</label>
+                <div className="flex space-x-sm">
+                  {profileOptions.map((option) => (
+                    <Button
+                      key={option.value}
+                      variant={settings.qualityProfile === option.value ? "default" : "outline"}
+                      size="sm"
+                      onClick={() => setSettings(prev => ({ ...prev, qualityProfile: option.value, preset: undefined, bitrate: undefined }))}
+                      className="flex-1"
+                    >
+                      {option.label}
+                    </Button>
+                  ))}
+                </div>
+              </div>
+
+              {/* Mezzanine masters */}
+              <div className="space-y-sm">
+                <label className="text-body-small text-white/70">Mezzanine</label>
+                <div className="flex flex-wrap gap-sm">
+                  {mezzanineOptions.map((option) => (
+                    <Button
+                      key={option.value}
+                      variant={settings.qualityProfile === option.value ? "default" : "outline"}
+                      size="sm"
+                      onClick={() => setSettings(prev => ({ ...prev, qualityProfile: option.value, format: 'mov', preset: undefined, bitrate: undefined, pixFmt: undefined, profile: undefined }))}
+                    >
+                      {option.label}
+                    </Button>
+                  ))}
+                </div>
+              </div>
+
               {/* Quality */}
               <div className="space-y-sm">
                 <label className="text-body-small text-white/70">Quality</label>
@@ -236,6 +329,135 @@ export function ExportDialog({ open, onOpenChange }: ExportDialogProps) {
                 </div>
               </div>
 
+              {/* Encode speed */}
+              <div className="space-y-sm">
+                <label className="text-body-small text-white/70">Encode Speed</label>
+                <div className="flex space-x-sm">
+                  {speedOptions.map((option) => (
+                    <Button
+                      key={option.value}
+                      variant={settings.preset === option.value ? "default" : "outline"}
+                      size="sm"
+                      onClick={() => setSettings(prev => ({ ...prev, preset: option.value }))}
+                      className="flex-1"
+                    >
+                      {option.label}
+                    </Button>
+                  ))}
+                </div>
+              </div>
+
+              {/* CPU usage */}
+              <div className="space-y-sm">
+                <label className="text-body-small text-white/70">CPU Usage</label>
+                <div className="flex space-x-sm">
+                  {cpuOptions.map((option) => (
+                    <Button
+                      key={option.value}
+                      variant={settings.threads === option.threads && settings.parallelSegments === option.parallelSegments ? "default" : "outline"}
+                      size="sm"
+                      onClick={() => setSettings(prev => ({ ...prev, threads: option.threads, parallelSegments: option.parallelSegments }))}
+                      className="flex-1"
+                    >
+                      {option.label}
+                    </Button>
+                  ))}
+                </div>
+              </div>
+
+              {/* Color */}
+              <div className="space-y-sm">
+                <label className="text-body-small text-white/70">Color</label>
+                <div className="flex space-x-sm">
+                  {colorOptions.map((option) => (
+                    <Button
+                      key={option.value}
+                      variant={selectedColor === option.value ? "default" : "outline"}
+                      size="sm"
+                      onClick={() => setSettings(prev => ({ ...prev, ...colorFieldsOff, ...option.fields }))}
+                      className="flex-1"
+                    >
+                      {option.label}
+                    </Button>
+                  ))}
+                </div>
+              </div>
+
+              {/* Review overlay */}
+              <div className="space-y-sm">
+                <label className="text-body-small text-white/70">Review Overlay</label>
+                <Button
+                  variant={settings.burnIn ? "default" : "outline"}
+                  size="sm"
+                  onClick={() => setSettings(prev => ({ ...prev, burnIn: !prev.burnIn }))}
+                  className="w-full justify-start"
+                >
+                  Burn in timecode, clip name and frame number
+                </Button>
+              </div>
+
+              {/* Audio stems */}
+              <div className="space-y-sm">
+                <label className="text-body-small text-white/70">Audio Stems</label>
+                <div className="flex space-x-sm">
+                  {stemOptions.map((option) => (
+                    <Button
+                      key={option.label}
+                      variant={settings.stems === option.value ? "default" : "outline"}
+                      size="sm"
+                      onClick={() => setSettings(prev => ({ ...prev, stems: option.value }))}
+                      className="flex-1"
+                    >
+                      {option.label}
+                    </Button>
+                  ))}
+                </div>
+              </div>
+
+              {/* Upscaling */}
+              <div className="space-y-sm">
+                <label className="text-body-small text-white/70">Upscaling</label>
+                <div className="flex space-x-sm">
+                  {scalerOptions.map((option) => (
+                    <Button
+                      key={option.label}
+                      variant={settings.scaler === option.value ? "default" : "outline"}
+                      size="sm"
+                      onClick={() => setSettings(prev => ({ ...prev, scaler: option.value }))}
+                      className="flex-1"
+                    >
+                      {option.label}
+                    </Button>
+                  ))}
+                </div>
+              </div>
+
+              {/* Web playback */}
+              <div className="space-y-sm">
+                <label className="text-body-small text-white/70">Web Playback</label>
+                <Button
+                  variant={settings.webOptimized !== false ? "default" : "outline"}
+                  size="sm"
+                  onClick={() => setSettings(prev => ({ ...prev, webOptimized: prev.webOptimized === false }))}
+                  className="w-full justify-start"
+                >
+                  Optimize for streaming (start playback before download finishes)
+                </Button>
+              </div>
+
+              {/* Delivery checksums */}
+              <div className="space-y-sm">
+                <label className="text-body-small text-white/70">Checksums</label>
+                <Button
+                  variant={settings.checksums?.length ? "default" : "outline"}
+                  size="sm"
+                  onClick={() => setSettings(prev => ({ ...prev, checksums: prev.checksums?.length ? [] : ['md5', 'sha256'] }))}
+                  className="w-full justify-start"
+                >
+                  Write MD5 and SHA-256 sidecar files
+                </Button>
+              </div>
+
               {/* Filename */}
               <div className="space-y-sm">
                 <label className="text-body-small text-white/70">Filename</label>
@@ -243,12 +465,42 @@ export function ExportDialog({ open, onOpenChange }: ExportDialogProps) {
                   value={filename}
                   onChange={(e) => setFilename(e.target.value)}
                   className="bg-white/10 border-white/20 text-white"
-                  placeholder="Export filename"
+                  placeholder={DEFAULT_FILENAME_TEMPLATE}
                 />
+                <p className="text-caption text-white/50">
+                  Fields: {'{project} {preset} {date} {time} {resolution} {id}'}
+                </p>
               </div>
             </>
           )}
 
+          {/* Pre-export validation */}
+          {!isExporting && !exportResult && warnings.length > 0 && (
+            <div className="space-y-xs bg-white/5 rounded-lg p-md border border-white/10 max-h-40 overflow-y-auto">
+              {warnings.map((warning, idx) => (
+                <div key={`${warning.code}-${warning.clip_id ?? ''}-${idx}`} className="flex items-start space-x-sm">
+                  <AlertTriangle
+                    className={`h-4 w-4 shrink-0 mt-0.5 ${warning.severity === 'error' ? 'text-red-400' : 'text-yellow-400'}`}
+                  />
+                  <p className="text-caption text-white/70 break-words">{warning.message}</p>
+                </div>
+              ))}
+            </div>
+          )}
+
+          {/* Program length and estimated file size */}
+          {!isExporting && !exportResult && stats && (
+            <p className="text-caption text-white/50">
+              {formatTimecode(stats.duration_ms)} · {stats.clip_count} clips · about {formatFileSize(estimate?.estimated_size_bytes ?? stats.estimated_size_bytes[settings.qualityProfile ?? 'standard'])}
+              {estimate?.estimated_render_ms != null && <> · renders in about {formatTimecode(estimate.estimated_render_ms)}</>}
+              {!estimate && (
+                <button type="button" onClick={handleEstimate} disabled={isEstimating} className="ml-sm underline hover:text-white/80">
+                  {isEstimating ? 'Estimating…' : 'Estimate render time'}
+                </button>
+              )}
+            </p>
+          )}
+
           {/* Export Progress */}
           {isExporting && (
             <div className="space-y-md">
@@ -335,9 +587,8 @@ export function ExportDialog({ open, onOpenChange }: ExportDialogProps) {
                   variant="gradient"
                   onClick={async () => {
                     try {
-                      // Remove file:// protocol if present
-                      const filePath = exportResult.path.replace(/^file:\/\//, '');
-                      await revealInFinder(filePath);
+                      // Backend converts the file:// URL back to a native path
+                      await revealInFinder(exportResult.path);
                       handleClose();
                     } catch (err) {
                       console.error('Failed to reveal in finder:', err);
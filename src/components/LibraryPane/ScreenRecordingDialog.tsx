@@ -2,7 +2,7 @@ import { useState, useEffect, useRef } from "react";
 import { Dialog, DialogContent, DialogHeader, DialogTitle } from "@/components/ui/dialog";
 import { Button } from "@/components/ui/button";
 import { CheckCircle, Monitor, Circle } from "lucide-react";
-import { startScreenRecord, stopScreenRecord, listenStartRecording, listenStopRecording, saveBlobToFile, revealInFinder, deleteFile } from "@/lib/bindings";
+import { startScreenRecord, stopScreenRecord, listenStartRecording, listenStopRecording, listenPauseRecording, writeRecordingChunk, finishScreenRecord, saveBlobToFile, revealInFinder, deleteFile } from "@/lib/bindings";
 import { useProjectStore } from "@/store/projectStore";
 import { useUiStore } from "@/store/uiStore";
 
@@ -36,21 +36,27 @@ export function ScreenRecordingDialog({ open, onOpenChange }: ScreenRecordingDia
   useEffect(() => {
     let startUnlisten: (() => void) | undefined;
     let stopUnlisten: (() => void) | undefined;
+    let pauseUnlisten: (() => void) | undefined;
 
     const setupListeners = async () => {
       startUnlisten = await listenStartRecording(async (event) => {
         try {
-          // Get the screen source
+          // Recordings can also be started by a global hotkey
+          setRecordingId(event.recordingId);
+
+          // Get the screen source (size, rate and codec come from the active recording preset)
+          const maxWidth = event.settings.max_width || 1920;
+          const maxHeight = event.settings.max_height || 1080;
           const stream = await navigator.mediaDevices.getUserMedia({
             audio: false,
             video: {
               mandatory: {
                 chromeMediaSource: 'desktop',
                 chromeMediaSourceId: event.sourceId,
-                minWidth: 1280,
-                maxWidth: 1920,
-                minHeight: 720,
-                maxHeight: 1080,
+                minWidth: Math.min(1280, maxWidth),
+                maxWidth,
+                minHeight: Math.min(720, maxHeight),
+                maxHeight,
                 minFrameRate: event.settings.fps || 30,
                 maxFrameRate: event.settings.fps || 30
               }
@@ -67,15 +73,27 @@ export function ScreenRecordingDialog({ open, onOpenChange }: ScreenRecordingDia
           }
 
           // Create MediaRecorder
+          const codec = event.settings.video_codec || 'vp9';
           const recorder = new MediaRecorder(stream, {
-            mimeType: 'video/webm;codecs=vp9'
-          });
+            mimeType: `video/webm;codecs=${codec}`,
+            // Regular keyframes give the segmenter somewhere to cut
+            videoKeyFrameIntervalDuration: event.settings.segment_minutes ? 2000 : undefined,
+            videoBitsPerSecond: event.settings.video_bitrate_kbps ? event.settings.video_bitrate_kbps * 1000 : undefined
+          } as MediaRecorderOptions);
 
           const chunks: Blob[] = [];
+          // Segmented recordings stream each chunk to disk in order instead of buffering the session
+          const segmented = !!event.settings.segment_minutes;
+          let written: Promise<unknown> = Promise.resolve();
           
           recorder.ondataavailable = (e) => {
             if (e.data.size > 0) {
-              chunks.push(e.data);
+              if (segmented) {
+                const data = e.data;
+                written = written.then(async () => writeRecordingChunk(event.recordingId, await data.arrayBuffer()));
+              } else {
+                chunks.push(e.data);
+              }
             }
           };
 
@@ -83,17 +101,23 @@ export function ScreenRecordingDialog({ open, onOpenChange }: ScreenRecordingDia
             const blob = new Blob(chunks, { type: 'video/webm' });
             
             try {
-              // Convert blob to ArrayBuffer
-              const arrayBuffer = await blob.arrayBuffer();
-              
-              // Extract just the filename from the full path (saveBlobToFile expects just filename)
-              // event.outputPath is a full path like "/path/to/cache/captures/screen_recording_123.webm"
-              // We need just "screen_recording_123.webm"
-              const filename = event.outputPath.split('/').pop() || `screen_recording_${Date.now()}.webm`;
-              
-              // Save to the specified path
-              const result = await saveBlobToFile(arrayBuffer, filename);
-              setRecordingSuccess({ path: result.path });
+              if (segmented) {
+                await written;
+                const { segments } = await finishScreenRecord(event.recordingId);
+                setRecordingSuccess({ path: segments[0] });
+              } else {
+                // Convert blob to ArrayBuffer
+                const arrayBuffer = await blob.arrayBuffer();
+
+                // Extract just the filename from the full path (saveBlobToFile expects just filename)
+                // event.outputPath is a full path like "/path/to/cache/captures/screen_recording_123.webm"
+                // We need just "screen_recording_123.webm"
+                const filename = event.outputPath.split('/').pop() || `screen_recording_${Date.now()}.webm`;
+
+                // Save to the specified path
+                const result = await saveBlobToFile(arrayBuffer, filename);
+                setRecordingSuccess({ path: result.path });
+              }
               setRecordingState('success');
               stopDurationTimer();
             } catch (error) {
@@ -128,13 +152,23 @@ export function ScreenRecordingDialog({ open, onOpenChange }: ScreenRecordingDia
 
       stopUnlisten = await listenStopRecording(() => {
         try {
-          if (mediaRecorderRef.current && mediaRecorderRef.current.state === 'recording') {
+          if (mediaRecorderRef.current && mediaRecorderRef.current.state !== 'inactive') {
             mediaRecorderRef.current.stop();
           }
         } catch (error) {
           setError(String(error));
         }
       });
+
+      pauseUnlisten = await listenPauseRecording((event) => {
+        const recorder = mediaRecorderRef.current;
+        if (!recorder) return;
+        if (event.paused && recorder.state === 'recording') {
+          recorder.pause();
+        } else if (!event.paused && recorder.state === 'paused') {
+          recorder.resume();
+        }
+      });
     };
 
     if (open) {
@@ -144,6 +178,7 @@ export function ScreenRecordingDialog({ open, onOpenChange }: ScreenRecordingDia
     return () => {
       if (startUnlisten) startUnlisten();
       if (stopUnlisten) stopUnlisten();
+      if (pauseUnlisten) pauseUnlisten();
     };
   }, [open]);
 
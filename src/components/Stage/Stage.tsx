@@ -290,7 +290,7 @@ function getAllVisibleClips(
     // Find all clips on this track that are active at current time
     track.clips.forEach(clipId => {
       const clip = clips[clipId];
-      if (!clip) return;
+      if (!clip || clip.enabled === false) return;
       
       // Check if current time falls within clip bounds
       if (currentTimeMs >= clip.startMs && currentTimeMs < clip.endMs) {
@@ -328,7 +328,7 @@ function getAudioClips(clips: Record<string, Clip>, tracks: Track[], currentTime
   for (const track of tracks.filter(t => t.type === 'audio' && t.visible)) {
     for (const clipId of track.clips) {
       const clip = clips[clipId];
-      if (clip && currentTimeMs >= clip.startMs && currentTimeMs < clip.endMs) {
+      if (clip && clip.enabled !== false && currentTimeMs >= clip.startMs && currentTimeMs < clip.endMs) {
         audioClips.push(clip);
       }
     }
@@ -112,8 +112,10 @@ export class AudioManager {
       try {
         const audio = this.createAudioElement(asset, clip, track);
 
-        // Calculate source time accounting for trim
-        const sourceTimeMs = timelineMs - clip.startMs + clip.trimStartMs;
+        // Calculate source time accounting for trim and speed
+        const speed = clip.speed || 1;
+        const sourceTimeMs = (timelineMs - clip.startMs) * speed + clip.trimStartMs;
+        audio.playbackRate = speed;
 
         // Check if we're within the clip bounds
         if (timelineMs >= clip.startMs && timelineMs < clip.endMs) {
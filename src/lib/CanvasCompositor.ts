@@ -1,5 +1,16 @@
-import type { Asset, Clip, Track, CanvasNode } from '@/types';
+import type { Asset, Clip, Track, CanvasNode, BlendMode } from '@/types';
 import { videoPoolManager } from './VideoPoolManager';
+import { interpolateKeyframes } from './utils';
+
+/**
+ * Canvas composite operations matching the export blend modes
+ */
+const BLEND_COMPOSITE_OPS: Record<BlendMode, GlobalCompositeOperation> = {
+  normal: 'source-over',
+  screen: 'screen',
+  multiply: 'multiply',
+  add: 'lighter',
+};
 
 /**
  * Metadata for a clip being rendered
@@ -181,21 +192,30 @@ export class CanvasCompositor {
     if (canvasNode) {
       this.ctx.save();
       
-      // Set opacity
-      this.ctx.globalAlpha = canvasNode.opacity;
+      // Set opacity (keyframes override the static node opacity) and blend mode
+      const localMs = this.currentTimeMs - clip.startMs;
+      this.ctx.globalAlpha = interpolateKeyframes(clip.opacityKeyframes, localMs, canvasNode.opacity);
+      this.ctx.globalCompositeOperation = BLEND_COMPOSITE_OPS[clip.blendMode ?? 'normal'];
+
+      // Resolve animated transforms at the playhead
+      const transform = clip.transformKeyframes;
+      const nodeX = interpolateKeyframes(transform?.x, localMs, canvasNode.x);
+      const nodeY = interpolateKeyframes(transform?.y, localMs, canvasNode.y);
+      const nodeScale = interpolateKeyframes(transform?.scale, localMs, 1);
+      const nodeRotation = interpolateKeyframes(transform?.rotation, localMs, canvasNode.rotation);
       
       // Apply transforms: Translate → Rotate → Scale
       // Translate to position
-      this.ctx.translate(canvasNode.x + canvasNode.width / 2, canvasNode.y + canvasNode.height / 2);
+      this.ctx.translate(nodeX + canvasNode.width / 2, nodeY + canvasNode.height / 2);
       
       // Rotate (convert degrees to radians)
-      this.ctx.rotate((canvasNode.rotation * Math.PI) / 180);
+      this.ctx.rotate((nodeRotation * Math.PI) / 180);
       
       // Scale based on canvasNode dimensions vs original asset dimensions
       const originalWidth = asset.metadata.width || this.canvas.width;
       const originalHeight = asset.metadata.height || this.canvas.height;
-      const scaleX = canvasNode.width / originalWidth;
-      const scaleY = canvasNode.height / originalHeight;
+      const scaleX = (canvasNode.width * nodeScale) / originalWidth;
+      const scaleY = (canvasNode.height * nodeScale) / originalHeight;
       this.ctx.scale(scaleX, scaleY);
       
       // Render clip centered at origin (since we translated to center)
@@ -397,13 +417,15 @@ export class CanvasCompositor {
       if (asset.type === 'video') {
         visibleAssetIds.add(asset.id);
         
-        // Calculate the source time within the video file
-        const sourceTimeMs = (this.currentTimeMs - clip.startMs) + clip.trimStartMs;
+        // Calculate the source time within the video file (retimed clips cover speed ms of source per ms)
+        const speed = clip.speed || 1;
+        const sourceTimeMs = (this.currentTimeMs - clip.startMs) * speed + clip.trimStartMs;
         const sourceTimeSeconds = Math.max(0, sourceTimeMs / 1000);
         
         // Get the video and update its time
         const video = videoPoolManager.getVideo(asset.id);
         if (video) {
+          video.playbackRate = speed;
           if (video.readyState >= 1) {
             // Update time if significantly different
             const timeDiff = Math.abs(video.currentTime - sourceTimeSeconds);
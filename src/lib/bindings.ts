@@ -1,14 +1,94 @@
 // Electron IPC bindings - replaces Tauri commands
 // This maintains the same interface as the original Tauri bindings
 
+import type { AspectRatio, AudioEffect, AudioSync, Marker } from '@/types';
+
+export type ErrorCode =
+  | 'internal'
+  | 'invalid_argument'
+  | 'not_found'
+  | 'file_not_found'
+  | 'path_not_allowed'
+  | 'dependency_missing'
+  | 'ffmpeg_feature_missing'
+  | 'ffmpeg_failed'
+  | 'probe_failed'
+  | 'timeout'
+  | 'cancelled'
+  | 'cache_error'
+  | 'device_not_found'
+  | 'recording_not_found'
+  | 'job_not_found'
+  | 'locked';
+
+// Error thrown by every command; branch on `code`, show `message`
+export class StarscapeError extends Error {
+  code: ErrorCode;
+  context: Record<string, unknown>;
+
+  constructor(code: ErrorCode, message: string, context: Record<string, unknown> = {}) {
+    super(message);
+    this.name = 'StarscapeError';
+    this.code = code;
+    this.context = context;
+  }
+}
+
+const IPC_ERROR_MARKER = 'STARSCAPE_ERROR:';
+
+// Decode the { code, message, context } payload the main process encodes into IPC error messages
+export function toStarscapeError(error: unknown): StarscapeError {
+  if (error instanceof StarscapeError) return error;
+  const raw = error instanceof Error ? error.message : String(error);
+  const markerAt = raw.indexOf(IPC_ERROR_MARKER);
+  if (markerAt !== -1) {
+    try {
+      const payload = JSON.parse(raw.slice(markerAt + IPC_ERROR_MARKER.length));
+      return new StarscapeError(payload.code, payload.message, payload.context || {});
+    } catch {
+      // Fall through to an untyped error
+    }
+  }
+  return new StarscapeError('internal', raw.replace(/^Error invoking remote method '[^']+': (Error: )?/, ''));
+}
+
+async function invoke<T>(promise: Promise<T>): Promise<T> {
+  try {
+    return await promise;
+  } catch (error) {
+    throw toStarscapeError(error);
+  }
+}
+
 export interface MediaMeta {
   duration_ms: number;
   width?: number;
   height?: number;
   has_audio?: boolean;
   codec_video?: string;
+  video_profile?: string | null;  // ffprobe's name, e.g. "High" or "Main 10"
+  pix_fmt?: string | null;
+  bit_depth?: number | null;
   codec_audio?: string;
+  sample_rate?: number;
+  channels?: number;
+  channel_layout?: string;
   rotation_deg?: number;
+  fps?: number;
+  frame_rate?: string;  // Exact rational rate, e.g. "30000/1001"
+  timecode?: string;    // Start timecode from the container, if any
+  color_transfer?: string | null;        // smpte2084 (PQ) and arib-std-b67 (HLG) are HDR
+  color_primaries?: string | null;
+  dolby_vision_profile?: number | null;
+  audio_codecs?: string[];               // One per audio stream
+  chapters?: MediaChapter[];
+  keyframes_ms?: number[];  // Only when requested with { keyframes: true }
+}
+
+export interface MediaChapter {
+  title: string;
+  start_ms: number;
+  end_ms: number;
 }
 
 export interface PreviewResult {
@@ -18,26 +98,216 @@ export interface PreviewResult {
 
 export interface ExportSettings {
   format: 'mp4' | 'mov';
-  width?: number;
-  height?: number;
-  fps?: number;
-  bitrate?: number;
-  filename?: string;
+  width?: number;       // Output frame; unset or -1 inherits the first video clip's size
+  height?: number;      // Sources are fitted inside it with black bars
+  fps?: number;         // Unset inherits the first video clip's rate
+  bitrate?: number;     // Video kbps
+  filename?: string;     // Exact name, replacing any earlier file of that name
+  filenameTemplate?: string;  // Used without a filename; {project} {preset} {date} {time} {resolution} {id}, numbered _2, _3... when taken
+  aspectRatio?: AspectRatio;
+  burnIn?: boolean;     // Review render: burn in source timecode, clip name and frame number
+  webOptimized?: boolean;  // Faststart (moov atom first) so playback starts before download finishes; default true
+  fragmented?: boolean;  // Fragmented MP4/MOV (moov first, then a fragment per keyframe), written front to back; replaces faststart
+  metadata?: ExportMetadata;
+  videoCodec?: string;  // Default libx264
+  preset?: EncoderPreset;  // x264/x265 only, default veryfast
+  crf?: number;         // 0-51, x264/x265 only, default 23
+  pixFmt?: 'yuv420p' | 'yuv422p' | 'yuv444p' | 'yuv420p10le' | 'yuv422p10le';
+  profile?: string;     // e.g. high, main
+  level?: string;       // e.g. 4.1
+  audioCodec?: string;  // Default aac
+  audioBitrate?: number;  // kbps, default 192
+  threads?: number;     // Encoder threads per ffmpeg process; unset lets ffmpeg decide
+  parallelSegments?: number;  // Timeline segments rendered at once, default 1
+  qualityProfile?: QualityProfile;  // Fills in preset/crf/audio bitrate (and draft's half resolution) left unset
+  halfResolution?: boolean;  // Render at half the output size (review copies)
+  stems?: StemMode;     // Also write unducked dialogue/music/sfx stems
+  audioLanguage?: string;  // ISO 639-2 code tagged on the main mix; commentary/dub tracks carry their own
+  // Write <file>.md5 / <file>.sha256 sidecars. Fragmented files are hashed while they are written; other
+  // MP4/MOV files are read back once after the render, since their muxer seeks back into the file
+  checksums?: ChecksumAlgorithm[];
+  scaler?: Scaler;      // Algorithm fitting sources to the output frame, default bicubic
+  colorPrimaries?: 'bt709' | 'bt2020' | 'smpte170m' | 'bt470bg' | 'smpte432';  // Stream color tags; unset leaves them unspecified
+  colorTransfer?: 'bt709' | 'smpte2084' | 'arib-std-b67' | 'smpte170m' | 'bt470bg';  // PQ/HLG need a 10-bit pixFmt and bt2020
+  colorMatrix?: 'bt709' | 'bt2020nc' | 'smpte170m' | 'bt470bg';
+  colorRange?: 'limited' | 'full';  // Tagged only, samples aren't converted
 }
 
+// lanczos/spline keep sources upscaled to the output size (e.g. 720p to 4K) sharper, at some render cost
+export type Scaler = 'bicubic' | 'lanczos' | 'spline';
+
+// files: 24-bit WAVs next to the video; streams: extra audio tracks after the mix (PCM in MOV, the mix codec in MP4)
+export type StemMode = 'files' | 'streams';
+
+// draft: ultrafast, crf 30, half resolution; standard: veryfast, crf 23; master: slow, crf 18, 320k audio
+// prores_*/dnxhr_*: mezzanine masters for post (10-bit 4:2:2 or 4:4:4, DNxHR HQ 8-bit), PCM audio, always MOV
+export type QualityProfile = 'draft' | 'standard' | 'master' | 'prores_422' | 'prores_422hq' | 'prores_4444' | 'dnxhr_hq' | 'dnxhr_hqx';
+
+// Written as container tags (iTunes-style atoms in mp4/mov)
+export interface ExportMetadata {
+  title?: string;
+  artist?: string;
+  author?: string;      // Used when artist is unset
+  comment?: string;
+  creationDate?: string;  // ISO 8601
+}
+
+export type EncoderPreset = 'ultrafast' | 'superfast' | 'veryfast' | 'faster' | 'fast' | 'medium' | 'slow' | 'slower' | 'veryslow';
+
+export type ChecksumAlgorithm = 'md5' | 'sha256';
+
 export interface ExportResult {
   path: string;
   duration_ms: number;
   size_bytes: number;
+  job_id: string;
+  stems?: string[];     // WAV file URLs when stems: 'files'
+  checksums?: Partial<Record<ChecksumAlgorithm, string>>;  // Hex digests, when requested
+  hooks?: ExportHookResult[];  // Post-export actions of the quality profile, when it has any
+}
+
+// Post-export action: copy/move to a folder, run a program (output path appended to args) or reveal the file
+export type ExportHookAction =
+  | { type: 'copy' | 'move'; destination: string }
+  | { type: 'command'; command: string; args?: string[] }
+  | { type: 'reveal' };
+
+export interface ExportHookResult {
+  type: ExportHookAction['type'];
+  success: boolean;
+  path: string | null;   // Copy or moved file
+  error: string | null;
+  duration_ms: number;
+}
+
+export interface PlanWarning {
+  severity: 'error' | 'warning';   // Errors will make the export fail
+  code: string;                    // e.g. missing_file, out_of_range, fps_mismatch
+  message: string;
+  clip_id: string | null;
+  track_id: string | null;
+}
+
+export interface ValidationResult {
+  ok: boolean;
+  warnings: PlanWarning[];
+}
+
+export interface PlanGap {
+  start_ms: number;
+  end_ms: number;
+  duration_ms: number;
+}
+
+export interface TrackStats {
+  track_id: string;
+  role: 'main' | 'overlay' | 'audio' | 'adjustment' | 'caption';
+  clip_count: number;
+  duration_ms: number;  // Time covered by clips (overlaps counted once)
+  start_ms: number;
+  end_ms: number;
+  gaps: PlanGap[];      // Empty stretches between the track's clips
+}
+
+// duration_ms is the exported program: first to last main-track clip
+export interface PlanStats {
+  duration_ms: number;
+  start_ms: number;
+  end_ms: number;
+  timeline_end_ms: number;  // End of the last clip on any track
+  clip_count: number;
+  tracks: TrackStats[];
+  estimated_size_bytes: Record<QualityProfile, number>;
+}
+
+// Every event payload carries the schema version of electron/events.js
+export interface VersionedEvent {
+  version: number;
 }
 
-export interface ProgressEvent {
+export interface ProgressEvent extends VersionedEvent {
+  job_id?: string;
   phase: string;
   current: number;
   total: number;
   message: string;
 }
 
+export interface JobStateChangedEvent extends VersionedEvent {
+  job_id: string;
+  kind: string;
+  status: 'running' | 'completed' | 'failed';
+  error?: string | null;
+}
+
+export interface RecordStatusEvent extends VersionedEvent {
+  recording_id: string;
+  status: 'recording' | 'paused' | 'stopped' | 'saved' | 'processed';
+  output_path: string;
+  duration_ms?: number;
+  segments?: string[];   // Files of a segmented recording, in order ('saved', 'processed')
+}
+
+export interface IngestProgressEvent extends VersionedEvent {
+  current: number;
+  total: number;
+  file_name: string;
+  asset_id?: string;
+  status: 'completed' | 'failed';
+  error?: string;
+}
+
+export interface IngestTranscodeEvent extends VersionedEvent {
+  job_id: string;
+  asset_id: string;
+  status: 'queued' | 'running' | 'completed' | 'failed';
+  progress?: number;          // 0-1
+  reasons?: string[];         // e.g. "hevc to h264", "5760x3240 to 3840x2160" (queued)
+  file_path?: string;         // Converted file (completed)
+  library_url?: string | null;
+  file_size?: number;
+  metadata?: MediaMeta;
+  error?: string;
+}
+
+// Conversions applied to newly ingested media; every matching rule goes into one pass
+export type IngestRule =
+  | { type: 'video_codec'; from: string[]; to: 'h264' | 'hevc' }     // Source codecs as ffprobe names them (hevc, prores, ...)
+  | { type: 'max_resolution'; width: number; height: number }        // Larger video is scaled down to fit
+  | { type: 'audio_sample_rate'; rate: number; extensions?: string[] };  // Audio files, optionally only these extensions
+
+export interface CacheEvictedEvent extends VersionedEvent {
+  bucket: string;
+  files: number;
+  bytes: number;
+  reason: string;
+}
+
+export interface PostRecordProgressEvent extends VersionedEvent {
+  job_id: string;
+  recording_id: string;
+  step: 'remux' | 'probe' | 'thumbnail' | 'waveform';
+  current: number;    // File being processed (segments count separately)
+  total: number;
+  file_name: string;
+}
+
+export interface AudioLevelEvent extends VersionedEvent {
+  monitor_id: string;
+  rms_db?: number;    // dBFS over the last 50ms, -96 for silence
+  peak_db?: number;
+  clipped?: boolean;
+  ended?: boolean;    // Last event of the monitor
+  error?: string;     // Why the capture stopped, when it failed
+}
+
+export interface ScrubFrameEvent extends VersionedEvent {
+  session_id: string;
+  seq: number;
+  at_ms: number;
+  jpeg: Uint8Array;
+}
+
 export interface DisplayDevice {
   id: string;
   name: string;
@@ -50,21 +320,62 @@ export interface ListDevices {
   audio_inputs: string[];
 }
 
+export type RecordVideoCodec = 'vp9' | 'vp8' | 'h264';
+
 export interface RecordSettings {
   display_index?: number;
   audio_index?: number;
+  system_audio?: boolean;              // Mix in desktop audio
+  microphone?: boolean;                // Mix in the audio_index input
   fps?: number;
+  max_width?: number;
+  max_height?: number;
+  video_codec?: RecordVideoCodec;
+  video_bitrate_kbps?: number | null;  // null: recorder default
+  audio_bitrate_kbps?: number;
+  segment_minutes?: number | null;     // Roll over to a new file every N minutes; null: one file
+}
+
+export interface RecordPreset {
+  id: string;                          // "tutorial", "gameplay", "meeting" or a saved preset's slug
+  name: string;
+  builtin: boolean;
+  active: boolean;                     // Applied last; the start-recording hotkey uses it
+  settings: Required<RecordSettings>;
 }
 
 export interface IngestRequest {
   file_paths: string[];
+  mode?: 'copy' | 'link';     // Link references sources in place; default copies them into the library
+  copy_external?: boolean;    // Link mode still copies network/removable sources; default true
+  profile?: 'phone';          // Phone footage fixups (HDR tone-map, HEVC to H.264, rotation, slow-mo, spatial audio)
 }
 
+// What the phone profile found and changed; the conversion arrives as an IngestTranscodeEvent
+export interface PhoneFixups {
+  hdr: 'dolby_vision' | 'hdr10' | 'hlg' | null;
+  dolby_vision_profile: number | null;
+  rotation_deg: number;
+  slow_motion: boolean;       // Captured at 100 fps or more
+  capture_fps: number | null;
+  spatial_audio: boolean;
+  changes: string[];
+}
+
+export type VolumeKind = 'local' | 'network' | 'removable';
+
 export interface IngestResult {
   asset_id: string;
   file_path: string;
+  library_url: string | null;   // media://library/ URL to store in projects (portable across machines)
+  copied: boolean;              // False when linked in place (file_path is the source)
+  source_volume: VolumeKind;    // Network and removable sources may be gone next session
+  source_mount_point: string | null;
   original_file_name: string;
+  transcode_job_id?: string;    // An ingest rule matched; the converted file arrives as an IngestTranscodeEvent
+  phone_fixups?: PhoneFixups;   // Video ingested with profile: 'phone'
   thumbnail_path: string | null;
+  thumbnail_library_url: string | null;
   file_size: number;
   metadata: MediaMeta;
 }
@@ -75,23 +386,423 @@ export interface GenerateImageResult {
 }
 
 // Electron API calls
-export async function getMediaMetadata(path: string): Promise<MediaMeta> {
-  return window.electronAPI.getMediaMetadata(path);
+// Pass { keyframes: true } to include the (cached) keyframe index for snapping cuts
+// Rejects with code 'timeout' when ffprobe doesn't answer within timeoutMs (default 10s)
+export async function getMediaMetadata(path: string, options?: { keyframes?: boolean; timeoutMs?: number }): Promise<MediaMeta> {
+  return invoke(window.electronAPI.getMediaMetadata(path, options));
 }
 
+export interface MediaMetaBatchEntry {
+  path: string;
+  metadata: MediaMeta | null;
+  error: { code: ErrorCode; message: string; context: Record<string, unknown> } | null;
+}
+
+// Probe many files in one round trip; each entry carries its metadata or its own error
+export async function getMediaMetadataBatch(paths: string[], options?: { keyframes?: boolean; timeoutMs?: number }): Promise<MediaMetaBatchEntry[]> {
+  return invoke(window.electronAPI.getMediaMetadataBatch(paths, options));
+}
+
+// Kill running probes; their getMediaMetadata calls reject with code 'cancelled'
+export async function cancelMediaProbes(): Promise<{ cancelled: number }> {
+  return invoke(window.electronAPI.cancelMediaProbes());
+}
+
+// Submit the edited project; rejected with code 'locked' when it changes a locked track or clip
+// of the last accepted project (a project with another id replaces it)
 export async function applyEdits(projectJson: string): Promise<{ success: boolean }> {
-  return window.electronAPI.applyEdits(projectJson);
+  return invoke(window.electronAPI.applyEdits(projectJson));
+}
+
+// Timeline operations on project JSON; each returns the transformed project JSON
+export async function rippleDeleteRange(projectJson: string, startMs: number, endMs: number, trackIds?: string[]): Promise<string> {
+  return invoke(window.electronAPI.rippleDeleteRange(projectJson, startMs, endMs, trackIds));
+}
+
+export async function closeGaps(projectJson: string, trackId: string): Promise<string> {
+  return invoke(window.electronAPI.closeGaps(projectJson, trackId));
+}
+
+export async function splitClipAt(projectJson: string, clipId: string, atMs: number): Promise<{ project: string; new_clip_id: string }> {
+  return invoke(window.electronAPI.splitClipAt(projectJson, clipId, atMs));
+}
+
+export interface NewMarker {
+  timeMs: number;
+  name?: string;
+  color?: string;       // Hex; defaults to the timeline's marker orange
+  durationMs?: number;  // Makes it a range
+}
+
+// Markers stored in the project JSON (the result carries the updated project)
+export async function addMarker(projectJson: string, marker: NewMarker): Promise<{ project: string; marker_id: string }> {
+  return invoke(window.electronAPI.addMarker(projectJson, marker));
+}
+
+export async function listMarkers(projectJson: string): Promise<Marker[]> {
+  return invoke(window.electronAPI.listMarkers(projectJson));
+}
+
+export type TimelineFormat = 'edl' | 'otio';
+
+// The edit and its markers as a CMX 3600 EDL (main track) or OpenTimelineIO document (all tracks)
+export async function exportTimeline(projectJson: string, format: TimelineFormat, options?: { fps?: number | string; dropFrame?: boolean }): Promise<string> {
+  return invoke(window.electronAPI.exportTimeline(projectJson, format, options));
+}
+
+// Snap clip and marker times to frame boundaries (fps may be 29.97 or "30000/1001")
+export async function quantizeProject(projectJson: string, fps: number | string): Promise<string> {
+  return invoke(window.electronAPI.quantizeProject(projectJson, fps));
+}
+
+export interface TimecodeOptions {
+  dropFrame?: boolean;  // Defaults to drop-frame for 29.97/59.94
+}
+
+// SMPTE timecode conversion on rational frame rates
+export async function msToTimecode(ms: number, fps: number | string, options?: TimecodeOptions): Promise<string> {
+  return invoke(window.electronAPI.msToTimecode(ms, fps, options));
+}
+
+export async function timecodeToMs(timecode: string, fps: number | string, options?: TimecodeOptions): Promise<number> {
+  return invoke(window.electronAPI.timecodeToMs(timecode, fps, options));
+}
+
+export interface ContactSheetResult {
+  path: string;
+  url: string;
+  frame_count: number;
+  cached: boolean;
+}
+
+// Tiled JPEG of evenly spaced frames; pass { path } for a file or { projectJson } for the timeline
+export async function generateContactSheet(
+  source: { path: string } | { projectJson: string },
+  columns = 4,
+  rows = 4,
+  intervalMs?: number
+): Promise<ContactSheetResult> {
+  return invoke(window.electronAPI.generateContactSheet(source, columns, rows, intervalMs ?? null));
+}
+
+export interface CaptionsPreviewOptions {
+  width?: number;          // Program frame the captions are laid out for (default 1920x1080)
+  height?: number;
+  previewHeight?: number;  // Rendered height, default 360
+  fps?: number;            // Default 15
+}
+
+export interface CaptionsPreviewResult {
+  path: string;
+  url: string;
+  duration_ms: number;
+  width: number;
+  height: number;
+  captions: number;        // Caption clips rendered
+}
+
+// Captions alone over black at low resolution, timed like the export, for proofreading
+export async function renderCaptionsPreview(projectJson: string, options?: CaptionsPreviewOptions): Promise<CaptionsPreviewResult> {
+  return invoke(window.electronAPI.renderCaptionsPreview(projectJson, options));
+}
+
+export interface TitleField {
+  key: string;          // e.g. title, subtitle
+  label: string;
+  default: string;
+}
+
+export type TitleAnimation = 'slide' | 'fade' | 'none';
+
+export interface TitleTemplate {
+  id: string;
+  name: string;
+  kind: 'lower_third' | 'title';
+  fields: TitleField[];
+  colors: { text: string; accent: string; background: string | null };
+  has_logo_slot: boolean;
+  animation: TitleAnimation;
+  duration_ms: number;
+}
+
+export interface TitleParams {
+  templateId: string;
+  fields?: Record<string, string>;      // Missing fields use the template defaults
+  colors?: { text?: string; accent?: string; background?: string };  // #RRGGBB or #RRGGBBAA
+  logoPath?: string;                    // Ignored by templates without a logo slot
+  animation?: TitleAnimation;
+  durationMs?: number;
+  width?: number;                       // Defaults to 1920x1080 @ 30 fps
+  height?: number;
+  fps?: number;
+}
+
+export interface TitleRenderResult {
+  path: string;
+  url: string;
+  duration_ms: number;
+  width: number;
+  height: number;
+  cached: boolean;
+}
+
+// Title/lower-third templates available to renderTitle
+export async function listTitleTemplates(): Promise<TitleTemplate[]> {
+  return invoke(window.electronAPI.listTitleTemplates());
+}
+
+// Render a title to a transparent (PNG-in-MOV) overlay clip; identical params reuse the cached file
+export async function renderTitle(params: TitleParams): Promise<TitleRenderResult> {
+  return invoke(window.electronAPI.renderTitle(params));
 }
 
 export async function generatePreview(projectJson: string, atMs: number): Promise<PreviewResult> {
-  return window.electronAPI.generatePreview(projectJson, atMs);
+  return invoke(window.electronAPI.generatePreview(projectJson, atMs));
+}
+
+export interface AssetFrame {
+  path: string;
+  url: string;
+  frame: number;       // Source frame index the time falls in
+  at_ms: number;       // Time the frame was taken at
+  width: number;
+  cached: boolean;
+}
+
+// Single source frame of an asset (by its path) for the clip trimmer; cached apart from timeline previews
+export async function getAssetFrame(path: string, atMs: number, maxWidth = 320): Promise<AssetFrame> {
+  return invoke(window.electronAPI.getAssetFrame(path, atMs, maxWidth));
+}
+
+export interface ScrubSessionInfo {
+  session_id: string;
+  width: number;
+  fps: number;
+}
+
+export type ScrubSeekResult = { seq: number; at_ms: number } | { seq: number; superseded: true };
+
+// Scrubbing: one long-lived ffmpeg per file streams raw source frames (no effects) as scrub-frame events
+export async function scrubOpen(path: string, options?: { width?: number; fps?: number }): Promise<ScrubSessionInfo> {
+  return invoke(window.electronAPI.scrubOpen(path, options));
+}
+
+// Ask for the frame at a source time; a newer seek supersedes one still pending
+export async function scrubSeek(sessionId: string, atMs: number): Promise<ScrubSeekResult> {
+  return invoke(window.electronAPI.scrubSeek(sessionId, atMs));
+}
+
+export async function scrubClose(sessionId: string): Promise<{ closed: boolean }> {
+  return invoke(window.electronAPI.scrubClose(sessionId));
 }
 
 export async function exportProject(
   projectJson: string,
   settings: ExportSettings
 ): Promise<ExportResult> {
-  return window.electronAPI.exportProject(projectJson, settings);
+  return invoke(window.electronAPI.exportProject(projectJson, settings));
+}
+
+// Render a single clip with its trims and effects, starting at 0 (overlay and audio clips play full frame)
+export async function exportClip(projectJson: string, clipId: string, settings: ExportSettings): Promise<ExportResult> {
+  return invoke(window.electronAPI.exportClip(projectJson, clipId, settings));
+}
+
+// One export of a template batch; a failed record has `error` set and the rest still render
+export interface TemplateRenderEntry {
+  index: number;        // Position in the data records
+  result: ExportResult | null;
+  error: { code: ErrorCode; message: string; context: Record<string, unknown> } | null;
+}
+
+// Fill a template project's {{field}} placeholders (text, asset src, filename template) from each
+// data record and export one video per record; every record is checked before anything renders
+export async function assembleFromTemplate(
+  templateJson: string,
+  data: Record<string, string | number> | Record<string, string | number>[],
+  settings: ExportSettings
+): Promise<TemplateRenderEntry[]> {
+  return invoke(window.electronAPI.assembleFromTemplate(templateJson, data, settings));
+}
+
+// Check the project for missing files, out-of-range trims and format mismatches
+export async function validatePlan(projectJson: string, settings: ExportSettings): Promise<ValidationResult> {
+  return invoke(window.electronAPI.validatePlan(projectJson, settings));
+}
+
+// Size estimates use the settings' frame size, rate and bitrate with each quality profile's rate control
+export async function getPlanStats(projectJson: string, settings?: Partial<ExportSettings>): Promise<PlanStats> {
+  return invoke(window.electronAPI.getPlanStats(projectJson, settings));
+}
+
+export interface ExportEstimate {
+  duration_ms: number;                  // Program length
+  estimated_render_ms: number | null;   // Null when only the bitrate model could be used
+  estimated_size_bytes: number;
+  realtime_factor: number | null;       // Program seconds rendered per wall-clock second
+  method: 'history' | 'sample' | 'bitrate';
+  jobs?: number;                        // Earlier exports averaged ('history')
+  sample_ms?: number;                   // Length encoded ('sample')
+}
+
+// Predict render time and size; 'auto' uses earlier exports with the same settings, else a short sample encode
+export async function estimateExport(projectJson: string, settings: ExportSettings, options?: { method?: 'auto' | 'history' | 'sample' }): Promise<ExportEstimate> {
+  return invoke(window.electronAPI.estimateExport(projectJson, settings, options));
+}
+
+// Audition audio effects on a few seconds of a source file
+export async function previewAudioEffect(
+  path: string,
+  effects: AudioEffect[],
+  startMs?: number,
+  durationMs?: number
+): Promise<{ url: string }> {
+  return invoke(window.electronAPI.previewAudioEffect(path, effects, startMs, durationMs));
+}
+
+export interface AlignOptions {
+  maxOffsetMs?: number;
+  analyzeSeconds?: number;
+  sampleRate?: number;
+}
+
+export interface AlignResult {
+  offset_ms: number;   // Place clip B this far after clip A's start to sync them
+  confidence: number;  // 0-1 normalized correlation peak
+}
+
+// Sync two separately recorded files by cross-correlating their audio
+export async function alignClips(pathA: string, pathB: string, options?: AlignOptions): Promise<AlignResult> {
+  return invoke(window.electronAPI.alignClips(pathA, pathB, options));
+}
+
+export interface BeatAnalysis {
+  bpm: number | null;   // null when no steady tempo was found
+  confidence: number;   // 0-1 strength of the tempo's periodicity
+  beats_ms: number[];   // Beat grid in source time
+  onsets_ms: number[];  // Every detected note/hit onset
+}
+
+// Tempo and beat positions of a file's audio, for snapping cuts to music
+export async function analyzeBeats(path: string, options?: { minBpm?: number; maxBpm?: number }): Promise<BeatAnalysis> {
+  return invoke(window.electronAPI.analyzeBeats(path, options));
+}
+
+export interface SpeechOptions {
+  marginDb?: number;       // Level above the noise floor counted as speech (default 12)
+  minLevelDb?: number;     // Absolute level below which nothing is speech (default -50)
+  bandRatio?: number;      // Share of energy in the 300-3400 Hz voice band (default 0.5)
+  minSpeechMs?: number;    // Shorter bursts are ignored (default 100)
+  minSilenceMs?: number;   // Shorter pauses are bridged (default 200)
+  paddingMs?: number;      // Added around speech (default 30)
+}
+
+export interface SpeechSegment {
+  start_ms: number;
+  end_ms: number;
+  speech: boolean;
+}
+
+export interface SpeechDetection {
+  frame_ms: number;          // Decision resolution
+  speech_ms: number;         // Total speech duration
+  segments: SpeechSegment[]; // Alternating, covering the whole file
+}
+
+// Voice activity segmentation, for caption chunking and jump-cut suggestions
+export async function detectSpeech(path: string, options?: SpeechOptions): Promise<SpeechDetection> {
+  return invoke(window.electronAPI.detectSpeech(path, options));
+}
+
+export interface HighlightOptions {
+  transcript?: { start_ms: number; end_ms: number; text: string }[];
+  keywords?: string[];        // Matched as whole words against the transcript
+  sceneThreshold?: number;    // 0-1 scene score counted as a cut (default 0.3)
+  maxResults?: number;        // Default 10
+  minDurationMs?: number;     // Default 5000
+  maxDurationMs?: number;     // Default 30000
+  preRollMs?: number;         // Lead-in before the peak (default 3000)
+  postRollMs?: number;        // Tail after the peak (default 5000)
+  minScore?: number;          // 0-1 window score needed to seed a highlight (default 0.35)
+}
+
+export interface HighlightRange {
+  start_ms: number;
+  end_ms: number;
+  score: number;              // 0-1, best first
+  reasons: ('audio' | 'scene' | 'keyword')[];
+}
+
+export interface HighlightSuggestions {
+  duration_ms: number;
+  signals: { audio: boolean; scene: boolean; keyword: boolean };  // Which signals were available
+  highlights: HighlightRange[];
+  job_id: string;
+}
+
+// Suggested highlight ranges in a long gameplay/screen recording
+export async function suggestHighlights(path: string, options?: HighlightOptions): Promise<HighlightSuggestions> {
+  return invoke(window.electronAPI.suggestHighlights(path, options));
+}
+
+export interface CropDetection {
+  x: number;              // Active picture in upright source pixels
+  y: number;
+  width: number;
+  height: number;
+  source_width: number;
+  source_height: number;
+  has_bars: boolean;      // False when the picture already fills the frame
+}
+
+// Black bars baked into a video (letterboxed downloads, captures), for Clip.crop; path or asset URL
+export async function detectCrop(path: string): Promise<CropDetection> {
+  return invoke(window.electronAPI.detectCrop(path));
+}
+
+export interface AvDriftMeasurement {
+  video_ms: number;
+  audio_ms: number;          // Span of the audio timestamps
+  start_offset_ms: number;   // Audio start relative to the video start
+  drift_ms: number;          // How much longer the audio runs than the video (negative: shorter)
+  gap_ms: number;            // Timestamp span with no audio samples (dropped audio)
+  correction: AudioSync | null;  // Set as Clip.audioSync; null when within a frame
+}
+
+// Audio/video drift of a long recording (path or asset URL), read from packet timestamps
+export async function measureAvDrift(path: string): Promise<AvDriftMeasurement> {
+  return invoke(window.electronAPI.measureAvDrift(path));
+}
+
+export interface ClipGainSuggestion {
+  clip_id: string;
+  track_id: string;
+  loudness_lufs: number | null;   // Integrated loudness without clip gain, null when silent
+  current_gain_db: number;
+  suggested_gain_db: number;      // Clip gain to set (absolute, not an offset from the current one)
+}
+
+export interface ClipGainAnalysis {
+  target_lufs: number | null;     // Level every dialogue clip is brought to
+  clips: ClipGainSuggestion[];
+  job_id: string;
+}
+
+// Loudness of every dialogue clip and the clip gain that levels them (target defaults to the median clip)
+export async function analyzeClipGain(projectJson: string, options?: { targetLufs?: number }): Promise<ClipGainAnalysis> {
+  return invoke(window.electronAPI.analyzeClipGain(projectJson, options));
+}
+
+export interface TimelineWaveform {
+  start_ms: number;     // Timeline time of the first peak
+  duration_ms: number;
+  buckets: number;
+  peaks: number[];      // Peak level per bucket, 0-1
+}
+
+// Peak data of the mixed program audio (what the export will sound like), for the timeline overview
+export async function generateTimelineWaveform(projectJson: string, buckets?: number): Promise<TimelineWaveform> {
+  return invoke(window.electronAPI.generateTimelineWaveform(projectJson, buckets));
 }
 
 export async function listenExportProgress(
@@ -100,17 +811,92 @@ export async function listenExportProgress(
   return window.electronAPI.onExportProgress(handler);
 }
 
+export async function listenJobStateChanged(
+  handler: (event: JobStateChangedEvent) => void
+): Promise<() => void> {
+  return window.electronAPI.onJobStateChanged(handler);
+}
+
+export async function listenRecordStatus(
+  handler: (event: RecordStatusEvent) => void
+): Promise<() => void> {
+  return window.electronAPI.onRecordStatus(handler);
+}
+
+export async function listenIngestProgress(
+  handler: (event: IngestProgressEvent) => void
+): Promise<() => void> {
+  return window.electronAPI.onIngestProgress(handler);
+}
+
+export async function listenIngestTranscode(
+  handler: (event: IngestTranscodeEvent) => void
+): Promise<() => void> {
+  return window.electronAPI.onIngestTranscode(handler);
+}
+
+export async function listenCacheEvicted(
+  handler: (event: CacheEvictedEvent) => void
+): Promise<() => void> {
+  return window.electronAPI.onCacheEvicted(handler);
+}
+
+export async function listenScrubFrames(
+  handler: (event: ScrubFrameEvent) => void
+): Promise<() => void> {
+  return window.electronAPI.onScrubFrame(handler);
+}
+
 // Screen recording
 export async function listCaptureDevices(): Promise<ListDevices> {
-  return window.electronAPI.listCaptureDevices();
+  return invoke(window.electronAPI.listCaptureDevices());
+}
+
+export interface CaptureMode {
+  width: number;
+  height: number;
+  min_fps: number | null;  // null on Linux, where v4l2 doesn't list rates
+  max_fps: number | null;
+  format: string | null;   // Pixel format or codec (dshow, v4l2)
+}
+
+export interface DeviceCapabilities {
+  device: string;
+  modes: CaptureMode[];    // Largest first
+}
+
+// Meter an input device (avfoundation index/name, dshow name, or PulseAudio source) before or while recording
+export async function startAudioMonitor(device: string): Promise<{ monitor_id: string }> {
+  return invoke(window.electronAPI.startAudioMonitor(device));
+}
+
+export async function stopAudioMonitor(monitorId: string): Promise<{ stopped: boolean }> {
+  return invoke(window.electronAPI.stopAudioMonitor(monitorId));
+}
+
+export async function listenPostRecordProgress(
+  handler: (event: PostRecordProgressEvent) => void
+): Promise<() => void> {
+  return window.electronAPI.onPostRecordProgress(handler);
+}
+
+export async function listenAudioLevel(
+  handler: (event: AudioLevelEvent) => void
+): Promise<() => void> {
+  return window.electronAPI.onAudioLevel(handler);
+}
+
+// Supported modes of a camera: avfoundation index/name on macOS, dshow name on Windows, /dev/videoN on Linux
+export async function getDeviceCapabilities(device: string): Promise<DeviceCapabilities> {
+  return invoke(window.electronAPI.getDeviceCapabilities(device));
 }
 
 export async function startScreenRecord(settings: RecordSettings): Promise<{ recordingId: string; outPath: string }> {
-  return window.electronAPI.startScreenRecord(settings);
+  return invoke(window.electronAPI.startScreenRecord(settings));
 }
 
 export async function stopScreenRecord(recordingId: string): Promise<string> {
-  return window.electronAPI.stopScreenRecord(recordingId);
+  return invoke(window.electronAPI.stopScreenRecord(recordingId));
 }
 
 export async function listenStartRecording(
@@ -125,53 +911,512 @@ export async function listenStopRecording(
   return window.electronAPI.onStopRecording(handler);
 }
 
+export interface Screenshot {
+  path: string;          // Image in the captures folder
+  width: number;
+  height: number;
+  source_name: string;   // Display or window title
+  asset?: IngestResult;  // Present when ingested
+}
+
+// Built-in (Tutorial 1080p30, Gameplay 60fps, Meeting low-CPU) and saved recording presets
+export async function listRecordPresets(): Promise<RecordPreset[]> {
+  return invoke(window.electronAPI.listRecordPresets());
+}
+
+// Make a preset active and get its full settings; overrides (e.g. display_index) take precedence
+export async function applyRecordPreset(presetId: string, overrides?: RecordSettings): Promise<{ preset_id: string; settings: Required<RecordSettings> }> {
+  return invoke(window.electronAPI.applyRecordPreset(presetId, overrides));
+}
+
+export async function saveRecordPreset(name: string, settings: RecordSettings): Promise<RecordPreset> {
+  return invoke(window.electronAPI.saveRecordPreset(name, settings));
+}
+
+// Screenshot a display or window (index from listCaptureDevices, or a source id)
+export async function captureScreenshot(target: number | string, format: 'png' | 'jpg' = 'png', options?: { ingest?: boolean }): Promise<Screenshot> {
+  return invoke(window.electronAPI.captureScreenshot(target, format, options));
+}
+
+// Stream a chunk of a segmented recording to disk
+export async function writeRecordingChunk(recordingId: string, data: ArrayBuffer): Promise<{ success: boolean }> {
+  return invoke(window.electronAPI.writeRecordingChunk(recordingId, data));
+}
+
+export interface FinishedRecording {
+  segments: string[];          // Post-processed segment files, in order
+  job_id: string;              // post-record job
+  files: ProcessedRecording[];
+}
+
+// Finish a segmented recording after its last chunk; resolves once every segment is post-processed
+export async function finishScreenRecord(recordingId: string): Promise<FinishedRecording> {
+  return invoke(window.electronAPI.finishScreenRecord(recordingId));
+}
+
+// Pause or resume a recording (omit paused to toggle); resolves to the new paused state
+export async function pauseScreenRecord(recordingId: string, paused?: boolean): Promise<boolean> {
+  return invoke(window.electronAPI.pauseScreenRecord(recordingId, paused));
+}
+
+export async function listenPauseRecording(
+  handler: (event: { recordingId: string; paused: boolean }) => void
+): Promise<() => void> {
+  return window.electronAPI.onPauseRecording(handler);
+}
+
+export type HotkeyAction = 'start-recording' | 'stop-recording' | 'pause-recording' | 'capture-screenshot';
+
+export interface HotkeyBinding {
+  action: HotkeyAction;
+  accelerator: string | null;  // Electron accelerator, e.g. "CommandOrControl+Shift+F9"; null when disabled
+  registered: boolean;         // false when another app already holds the shortcut
+}
+
+export interface HotkeyTriggeredEvent extends VersionedEvent {
+  action: HotkeyAction;
+  recording_id?: string;
+  output_path?: string;  // Screenshot taken by capture-screenshot
+}
+
+// System-wide recording shortcuts (they work while the app is unfocused)
+export async function getHotkeys(): Promise<HotkeyBinding[]> {
+  return invoke(window.electronAPI.getHotkeys());
+}
+
+// Rebind some hotkeys; null disables an action
+export async function setHotkeys(bindings: Partial<Record<HotkeyAction, string | null>>): Promise<HotkeyBinding[]> {
+  return invoke(window.electronAPI.setHotkeys(bindings));
+}
+
+export async function getIngestRules(): Promise<IngestRule[]> {
+  return invoke(window.electronAPI.getIngestRules());
+}
+
+// Replace the ingest rules; an empty list turns ingest conversion off
+export async function setIngestRules(rules: IngestRule[]): Promise<IngestRule[]> {
+  return invoke(window.electronAPI.setIngestRules(rules));
+}
+
+// Post-export actions per quality profile ("custom" covers exports without one)
+export async function getExportHooks(): Promise<Record<string, ExportHookAction[]>> {
+  return invoke(window.electronAPI.getExportHooks());
+}
+
+// Replace a profile's actions; programs and destinations must come from pickExportHookTarget
+export async function setExportHooks(preset: string, actions: ExportHookAction[]): Promise<ExportHookAction[]> {
+  return invoke(window.electronAPI.setExportHooks(preset, actions));
+}
+
+// Pick a hook program or destination folder in a native dialog (path is null when cancelled)
+export async function pickExportHookTarget(kind: 'command' | 'folder'): Promise<{ path: string | null }> {
+  return invoke(window.electronAPI.pickExportHookTarget(kind));
+}
+
+// Local automation API for Stream Deck buttons and scripts (127.0.0.1 only, bearer token)
+export interface AutomationSettings {
+  enabled: boolean;
+  port: number;
+  token: string | null;  // Created when first enabled
+  running: boolean;      // Listening (false when the port is taken)
+}
+
+export async function getAutomationSettings(): Promise<AutomationSettings> {
+  return invoke(window.electronAPI.getAutomationSettings());
+}
+
+// Enable/disable the API, change its port or issue a new token (old clients stop working)
+export async function setAutomationSettings(changes: { enabled?: boolean; port?: number; regenerateToken?: boolean }): Promise<AutomationSettings> {
+  return invoke(window.electronAPI.setAutomationSettings(changes));
+}
+
+// Share the open project with the automation API so clients can list and export it
+export async function publishOpenProject(projectJson: string): Promise<{ published: boolean }> {
+  return invoke(window.electronAPI.publishOpenProject(projectJson));
+}
+
+// Effect plugins: JSON descriptors in the plugins folder, used as { type: 'plugin', plugin, params }
+export type EffectPluginParameter =
+  | { type: 'number'; label: string; min: number; max: number; integer: boolean; default: number }
+  | { type: 'choice'; label: string; options: string[]; default: string }
+  | { type: 'boolean'; label: string; default: boolean }
+  | { type: 'color'; label: string; default: string };
+
+export interface EffectPlugin {
+  id: string;
+  name: string;
+  description: string;
+  kind: 'video' | 'audio';
+  parameters: Record<string, EffectPluginParameter>;
+}
+
+export interface EffectPluginList {
+  plugins: EffectPlugin[];
+  errors: { file: string; code: string; message: string }[];  // Descriptors that were skipped
+  folder: string;
+}
+
+export async function listEffectPlugins(): Promise<EffectPluginList> {
+  return invoke(window.electronAPI.listEffectPlugins());
+}
+
+// Re-read the plugins folder after descriptors were added or edited
+export async function reloadEffectPlugins(): Promise<EffectPluginList> {
+  return invoke(window.electronAPI.reloadEffectPlugins());
+}
+
+export async function listenHotkeyTriggered(
+  handler: (event: HotkeyTriggeredEvent) => void
+): Promise<() => void> {
+  return window.electronAPI.onHotkeyTriggered(handler);
+}
+
 // File ingestion
 export async function ingestFiles(request: IngestRequest): Promise<IngestResult[]> {
-  return window.electronAPI.ingestFiles(request);
+  return invoke(window.electronAPI.ingestFiles(request));
+}
+
+export interface ClipHandlesResult {
+  smart_render: boolean;  // false: the clip will be encoded whole (codec mismatch, no keyframe index, too short)
+  head_ms: number;        // Encoded lead-in before the first keyframe
+  tail_ms: number;        // Encoded tail after the last keyframe
+  copied_ms: number;      // Middle stream-copied at export
+  cached: boolean;        // Handles were already rendered
+}
+
+// Pre-encode a trimmed clip's head/tail handles so later exports only stream-copy its middle
+export async function precomputeClipHandles(path: string, inMs: number, outMs: number, settings?: Partial<ExportSettings>): Promise<ClipHandlesResult> {
+  return invoke(window.electronAPI.precomputeClipHandles(path, inMs, outMs, settings));
+}
+
+export type RemuxContainer = 'mp4' | 'mov' | 'mkv' | 'm4a';
+
+export interface RemuxOptions {
+  streams?: 'all' | 'video' | 'audio';  // Default all (m4a keeps audio only)
+  filename?: string;                     // Without extension, replacing any earlier file; default <source>_remux, numbered when taken
+}
+
+export interface MediaFileResult {
+  path: string;
+  url: string;
+  size_bytes: number;
+}
+
+// Rewrap a file in another container without re-encoding (written to the projects folder)
+export async function remuxFile(path: string, container: RemuxContainer, options?: RemuxOptions): Promise<MediaFileResult> {
+  return invoke(window.electronAPI.remuxFile(path, container, options));
+}
+
+// Cut a file without a project: keyframe copy by default, reencode for a frame-accurate in point
+// outPath must be inside the app's folders; default is <source>_trim next to exports, numbered when taken
+export async function quickTrim(path: string, inMs: number, outMs: number, outPath?: string | null, reencode = false): Promise<MediaFileResult> {
+  return invoke(window.electronAPI.quickTrim(path, inMs, outMs, outPath ?? null, reencode));
+}
+
+export interface ConcatFilesResult extends MediaFileResult {
+  mode: 'copy' | 'reencode';
+  mismatches: string[];   // Why the files couldn't be stream-copied (empty for copy)
+}
+
+// Stitch files end to end (e.g. multi-part recordings): stream copy when codecs and formats match,
+// otherwise fitted to the first video's frame and rate and re-encoded
+export async function concatFiles(paths: string[], outPath?: string | null, options?: { reencode?: boolean }): Promise<ConcatFilesResult> {
+  return invoke(window.electronAPI.concatFiles(paths, outPath ?? null, options));
+}
+
+// Extract a still from a video at a timestamp as a new image asset
+export async function extractStill(path: string, atMs: number): Promise<IngestResult> {
+  return invoke(window.electronAPI.extractStill(path, atMs));
+}
+
+// copy keeps the source bitstream (falls back to aac when its codec has no importable container)
+export type AudioExtractCodec = 'copy' | 'aac' | 'mp3' | 'wav' | 'flac';
+
+// Extract a file's audio as a new audio asset (podcast versions, external audio editing)
+export async function extractAudio(path: string, codec: AudioExtractCodec = 'copy'): Promise<IngestResult> {
+  return invoke(window.electronAPI.extractAudio(path, codec));
+}
+
+// OBS recordings: the video (conformed to a constant rate when variable) and each audio track as its own asset
+export interface ObsAudioTrack extends IngestResult {
+  track_index: number;  // Audio stream order in the recording
+  title: string;        // Track name from the recording ("Mic/Aux"), else "Track N"
+  offset_ms: number;    // Where the track starts relative to the video; place it there to stay in sync
+}
+
+export interface ObsImportResult {
+  job_id: string;
+  group_id: string;
+  name: string;
+  conformed: boolean;   // Video was re-encoded at a constant frame rate
+  frame_rate: string | null;
+  video: IngestResult;
+  audio_tracks: ObsAudioTrack[];
+}
+
+export async function importObsRecording(path: string, options: { conform?: 'auto' | 'always' | 'never'; frame_rate?: string } = {}): Promise<ObsImportResult> {
+  return invoke(window.electronAPI.importObsRecording(path, options));
 }
 
 // Open file dialog
 export async function openFileDialog(): Promise<{ filePaths: string[] }> {
-  return window.electronAPI.openFileDialog();
+  return invoke(window.electronAPI.openFileDialog());
 }
 
 // Save blob to file
-export async function saveBlobToFile(blobData: ArrayBuffer, filePath: string): Promise<{ success: boolean; path: string }> {
-  return window.electronAPI.saveBlobToFile(blobData, filePath);
+// A recording after the post-record pipeline: clean MP4, probed, with a poster frame and waveform peaks
+export interface ProcessedRecording {
+  path: string;
+  metadata: MediaMeta;
+  thumbnail_path: string | null;
+  waveform: { buckets: number; peaks: number[] } | null;  // null when the recording has no audio
 }
 
-// Reveal file in Finder/Explorer
+export async function saveBlobToFile(blobData: ArrayBuffer, filePath: string): Promise<{ success: boolean; path: string; processed?: ProcessedRecording | null }> {
+  return invoke(window.electronAPI.saveBlobToFile(blobData, filePath));
+}
+
+// Reveal file in Finder/Explorer (accepts a native path or a file:// URL)
 export async function revealInFinder(filePath: string): Promise<{ success: boolean }> {
-  return window.electronAPI.revealInFinder(filePath);
+  return invoke(window.electronAPI.revealInFinder(filePath));
 }
 
 // Delete file
 export async function deleteFile(filePath: string): Promise<{ success: boolean }> {
-  return window.electronAPI.deleteFile(filePath);
+  return invoke(window.electronAPI.deleteFile(filePath));
 }
 
 // Generate cosmic image using AI
 export async function generateCosmicImage(prompt: string): Promise<GenerateImageResult> {
-  return window.electronAPI.generateImage(prompt);
+  return invoke(window.electronAPI.generateImage(prompt));
+}
+
+export interface LogEntry {
+  ts: string;
+  level: 'error' | 'warn' | 'info' | 'debug';
+  message: string;
+  span?: string;
+  span_id?: string;
+  duration_ms?: number;
+  [field: string]: unknown;
+}
+
+// Get recent structured log entries at or above a level (newest last)
+export async function getRecentLogs(level: LogEntry['level'] = 'info', limit = 200): Promise<LogEntry[]> {
+  return invoke(window.electronAPI.getRecentLogs(level, limit));
+}
+
+export interface DiagnosticsBundle {
+  path: string;
+  size_bytes: number;
+}
+
+// Zip recent logs, ffmpeg build info, cache stats and the last job's (path-scrubbed) commands
+export async function createDiagnosticsBundle(): Promise<DiagnosticsBundle> {
+  return invoke(window.electronAPI.createDiagnosticsBundle());
+}
+
+export interface JobMetrics {
+  phases_ms: { probe: number; segments: number; concat: number; encode: number };
+  total_ms: number;
+  input_files: number;
+  input_bytes: number;
+  output_bytes: number;
+  output_duration_ms: number;
+  output_frames: number;
+  encode_fps: number | null;
+  realtime_factor: number | null;
+}
+
+export interface JobMetricsResult {
+  job_id: string;
+  kind: string;
+  status: 'running' | 'completed' | 'failed';
+  started_at: string;
+  finished_at: string | null;
+  error: string | null;
+  metrics: JobMetrics | null;
+  hooks: ExportHookResult[] | null;   // Export completion hooks that ran, in order
+}
+
+// Get where a job (e.g. ExportResult.job_id) spent its time; metrics never leave this machine
+export async function getJobMetrics(jobId: string): Promise<JobMetricsResult> {
+  return invoke(window.electronAPI.getJobMetrics(jobId));
+}
+
+export interface EncodeBenchmarkEntry {
+  encoder: string;
+  preset: string;
+  available: boolean;
+  encode_ms: number | null;
+  encode_fps: number | null;
+  realtime_factor: number | null;
+  bitrate_kbps: number | null;
+  ssim: number | null;
+  error: string | null;
+}
+
+export interface EncodeBenchmarkResult {
+  results: EncodeBenchmarkEntry[];
+  recommended: { encoder: string; preset: string } | null;
+}
+
+// Encode a short synthetic clip with each encoder/preset; recommended is the fastest with SSIM >= 0.97
+export async function runEncodeBenchmark(): Promise<EncodeBenchmarkResult> {
+  return invoke(window.electronAPI.runEncodeBenchmark());
+}
+
+export interface CodecList {
+  video: string[];
+  audio: string[];
+  subtitle: string[];
+}
+
+export interface FfmpegCapabilities {
+  version: string | null;
+  configuration: string[];
+  libraries: Record<string, string>;
+  encoders: CodecList;
+  decoders: CodecList;
+  filters: string[];
+  hwaccels: string[];
+}
+
+// Get what the installed ffmpeg build supports (probed once per session unless refresh is set)
+export async function getFfmpegCapabilities(refresh = false): Promise<FfmpegCapabilities> {
+  return invoke(window.electronAPI.getFfmpegCapabilities(refresh));
+}
+
+export interface ToolStatus {
+  found: boolean;
+  path: string | null;
+  source: 'env' | 'bundled' | 'system' | null;
+  version: string | null;
+  error: string | null;
+  fix: string | null;
+}
+
+export interface DependencyReport {
+  ok: boolean;
+  ffmpeg: ToolStatus;
+  ffprobe: ToolStatus;
+  available_features: string[];
+  unavailable_features: string[];
+}
+
+// Check ffmpeg and ffprobe separately; metadata features still work when only ffprobe is found
+export async function checkDependencies(): Promise<DependencyReport> {
+  return invoke(window.electronAPI.checkDependencies());
+}
+
+export interface SystemFont {
+  family: string;
+  style: string;        // e.g. Regular, Bold Italic
+  path: string;
+  index: number;        // Face index within .ttc/.otc collections
+}
+
+// Installed fonts for caption styling (CaptionStyle.fontFamily takes a family name from here)
+export async function listSystemFonts(refresh = false): Promise<SystemFont[]> {
+  return invoke(window.electronAPI.listSystemFonts(refresh));
 }
 
 // Type declaration for Electron API
 declare global {
   interface Window {
     electronAPI: {
-      getMediaMetadata: (path: string) => Promise<MediaMeta>;
+      getMediaMetadata: (path: string, options?: { keyframes?: boolean; timeoutMs?: number }) => Promise<MediaMeta>;
+      getMediaMetadataBatch: (paths: string[], options?: { keyframes?: boolean; timeoutMs?: number }) => Promise<MediaMetaBatchEntry[]>;
+      cancelMediaProbes: () => Promise<{ cancelled: number }>;
       applyEdits: (projectJson: string) => Promise<{ success: boolean }>;
+      rippleDeleteRange: (projectJson: string, startMs: number, endMs: number, trackIds?: string[]) => Promise<string>;
+      closeGaps: (projectJson: string, trackId: string) => Promise<string>;
+      splitClipAt: (projectJson: string, clipId: string, atMs: number) => Promise<{ project: string; new_clip_id: string }>;
+      addMarker: (projectJson: string, marker: NewMarker) => Promise<{ project: string; marker_id: string }>;
+      listMarkers: (projectJson: string) => Promise<Marker[]>;
+      exportTimeline: (projectJson: string, format: TimelineFormat, options?: { fps?: number | string; dropFrame?: boolean }) => Promise<string>;
+      quantizeProject: (projectJson: string, fps: number | string) => Promise<string>;
+      msToTimecode: (ms: number, fps: number | string, options?: TimecodeOptions) => Promise<string>;
+      timecodeToMs: (timecode: string, fps: number | string, options?: TimecodeOptions) => Promise<number>;
       generatePreview: (projectJson: string, atMs: number) => Promise<PreviewResult>;
+      getAssetFrame: (path: string, atMs: number, maxWidth: number) => Promise<AssetFrame>;
+      scrubOpen: (path: string, options?: { width?: number; fps?: number }) => Promise<ScrubSessionInfo>;
+      scrubSeek: (sessionId: string, atMs: number) => Promise<ScrubSeekResult>;
+      scrubClose: (sessionId: string) => Promise<{ closed: boolean }>;
       exportProject: (projectJson: string, settings: ExportSettings) => Promise<ExportResult>;
+      exportClip: (projectJson: string, clipId: string, settings: ExportSettings) => Promise<ExportResult>;
+      assembleFromTemplate: (templateJson: string, data: Record<string, string | number> | Record<string, string | number>[], settings: ExportSettings) => Promise<TemplateRenderEntry[]>;
+      validatePlan: (projectJson: string, settings: ExportSettings) => Promise<ValidationResult>;
+      getPlanStats: (projectJson: string, settings?: Partial<ExportSettings>) => Promise<PlanStats>;
+      estimateExport: (projectJson: string, settings: ExportSettings, options?: { method?: 'auto' | 'history' | 'sample' }) => Promise<ExportEstimate>;
+      previewAudioEffect: (path: string, effects: AudioEffect[], startMs?: number, durationMs?: number) => Promise<{ url: string }>;
+      alignClips: (pathA: string, pathB: string, options?: AlignOptions) => Promise<AlignResult>;
+      analyzeBeats: (path: string, options?: { minBpm?: number; maxBpm?: number }) => Promise<BeatAnalysis>;
+      detectSpeech: (path: string, options?: SpeechOptions) => Promise<SpeechDetection>;
+      suggestHighlights: (path: string, options?: HighlightOptions) => Promise<HighlightSuggestions>;
+      detectCrop: (path: string) => Promise<CropDetection>;
+      measureAvDrift: (path: string) => Promise<AvDriftMeasurement>;
+      analyzeClipGain: (projectJson: string, options?: { targetLufs?: number }) => Promise<ClipGainAnalysis>;
+      generateTimelineWaveform: (projectJson: string, buckets?: number) => Promise<TimelineWaveform>;
       ingestFiles: (request: IngestRequest) => Promise<IngestResult[]>;
+      precomputeClipHandles: (path: string, inMs: number, outMs: number, settings?: Partial<ExportSettings>) => Promise<ClipHandlesResult>;
+      remuxFile: (path: string, container: RemuxContainer, options?: RemuxOptions) => Promise<MediaFileResult>;
+      quickTrim: (path: string, inMs: number, outMs: number, outPath: string | null, reencode: boolean) => Promise<MediaFileResult>;
+      concatFiles: (paths: string[], outPath: string | null, options?: { reencode?: boolean }) => Promise<ConcatFilesResult>;
+      extractStill: (path: string, atMs: number) => Promise<IngestResult>;
+      extractAudio: (path: string, codec: AudioExtractCodec) => Promise<IngestResult>;
+      importObsRecording: (path: string, options: { conform?: 'auto' | 'always' | 'never'; frame_rate?: string }) => Promise<ObsImportResult>;
+      generateContactSheet: (source: { path: string } | { projectJson: string }, columns: number, rows: number, intervalMs: number | null) => Promise<ContactSheetResult>;
+      renderCaptionsPreview: (projectJson: string, options?: CaptionsPreviewOptions) => Promise<CaptionsPreviewResult>;
+      listTitleTemplates: () => Promise<TitleTemplate[]>;
+      renderTitle: (params: TitleParams) => Promise<TitleRenderResult>;
       openFileDialog: () => Promise<{ filePaths: string[] }>;
-      saveBlobToFile: (blobData: ArrayBuffer, filePath: string) => Promise<{ success: boolean; path: string }>;
+      saveBlobToFile: (blobData: ArrayBuffer, filePath: string) => Promise<{ success: boolean; path: string; processed?: ProcessedRecording | null }>;
+      getRecentLogs: (level?: LogEntry['level'], limit?: number) => Promise<LogEntry[]>;
+      createDiagnosticsBundle: () => Promise<DiagnosticsBundle>;
+      getJobMetrics: (jobId: string) => Promise<JobMetricsResult>;
+      runEncodeBenchmark: () => Promise<EncodeBenchmarkResult>;
+      getFfmpegCapabilities: (refresh?: boolean) => Promise<FfmpegCapabilities>;
+      listSystemFonts: (refresh?: boolean) => Promise<SystemFont[]>;
+      checkDependencies: () => Promise<DependencyReport>;
       listCaptureDevices: () => Promise<ListDevices>;
+      getDeviceCapabilities: (device: string) => Promise<DeviceCapabilities>;
+      startAudioMonitor: (device: string) => Promise<{ monitor_id: string }>;
+      stopAudioMonitor: (monitorId: string) => Promise<{ stopped: boolean }>;
       startScreenRecord: (settings: RecordSettings) => Promise<{ recordingId: string; outPath: string }>;
       stopScreenRecord: (recordingId: string) => Promise<string>;
+      listRecordPresets: () => Promise<RecordPreset[]>;
+      applyRecordPreset: (presetId: string, overrides?: RecordSettings) => Promise<{ preset_id: string; settings: Required<RecordSettings> }>;
+      saveRecordPreset: (name: string, settings: RecordSettings) => Promise<RecordPreset>;
+      captureScreenshot: (target: number | string, format?: 'png' | 'jpg', options?: { ingest?: boolean }) => Promise<Screenshot>;
+      writeRecordingChunk: (recordingId: string, data: ArrayBuffer) => Promise<{ success: boolean }>;
+      finishScreenRecord: (recordingId: string) => Promise<FinishedRecording>;
+      pauseScreenRecord: (recordingId: string, paused?: boolean) => Promise<boolean>;
+      getHotkeys: () => Promise<HotkeyBinding[]>;
+      setHotkeys: (bindings: Partial<Record<HotkeyAction, string | null>>) => Promise<HotkeyBinding[]>;
+      getIngestRules: () => Promise<IngestRule[]>;
+      setIngestRules: (rules: IngestRule[]) => Promise<IngestRule[]>;
+      getExportHooks: () => Promise<Record<string, ExportHookAction[]>>;
+      setExportHooks: (preset: string, actions: ExportHookAction[]) => Promise<ExportHookAction[]>;
+      pickExportHookTarget: (kind: 'command' | 'folder') => Promise<{ path: string | null }>;
+      getAutomationSettings: () => Promise<AutomationSettings>;
+      setAutomationSettings: (changes: { enabled?: boolean; port?: number; regenerateToken?: boolean }) => Promise<AutomationSettings>;
+      publishOpenProject: (projectJson: string) => Promise<{ published: boolean }>;
+      listEffectPlugins: () => Promise<EffectPluginList>;
+      reloadEffectPlugins: () => Promise<EffectPluginList>;
       onExportProgress: (callback: (event: ProgressEvent) => void) => () => void;
       onStartRecording: (callback: (event: { recordingId: string; sourceId: string; outputPath: string; settings: RecordSettings }) => void) => () => void;
       onStopRecording: (callback: (event: { recordingId: string }) => void) => () => void;
+      onJobStateChanged: (callback: (event: JobStateChangedEvent) => void) => () => void;
+      onRecordStatus: (callback: (event: RecordStatusEvent) => void) => () => void;
+      onIngestProgress: (callback: (event: IngestProgressEvent) => void) => () => void;
+      onIngestTranscode: (callback: (event: IngestTranscodeEvent) => void) => () => void;
+      onCacheEvicted: (callback: (event: CacheEvictedEvent) => void) => () => void;
+      onScrubFrame: (callback: (event: ScrubFrameEvent) => void) => () => void;
+      onPauseRecording: (callback: (event: { recordingId: string; paused: boolean }) => void) => () => void;
+      onHotkeyTriggered: (callback: (event: HotkeyTriggeredEvent) => void) => () => void;
+      onAudioLevel: (callback: (event: AudioLevelEvent) => void) => () => void;
+      onPostRecordProgress: (callback: (event: PostRecordProgressEvent) => void) => () => void;
       revealInFinder: (filePath: string) => Promise<{ success: boolean }>;
       deleteFile: (filePath: string) => Promise<{ success: boolean }>;
       generateImage: (prompt: string) => Promise<GenerateImageResult>;
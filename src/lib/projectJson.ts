@@ -0,0 +1,130 @@
+import type { ProjectState } from "@/types";
+
+// Store fields that make up the project (selection and UI state aren't part of it)
+export const PROJECT_JSON_FIELDS = [
+  'id', 'projectName', 'assets', 'tracks', 'clips', 'canvasNodes', 'multicamGroups', 'markers', 'audioCrossfadeMs',
+] as const;
+
+export type ProjectJsonSource = Pick<ProjectState, typeof PROJECT_JSON_FIELDS[number]>;
+
+// The project fields of a store state, as a snapshot
+export function projectJsonSource(state: ProjectJsonSource): ProjectJsonSource {
+  const { id, projectName, assets, tracks, clips, canvasNodes, multicamGroups, markers, audioCrossfadeMs } = state;
+  return { id, projectName, assets, tracks, clips, canvasNodes, multicamGroups, markers, audioCrossfadeMs };
+}
+
+// Transform project state to the backend project JSON format (export, validation, automation API)
+export function buildProjectJson(state: ProjectJsonSource): string {
+  const { id, projectName, assets, tracks, clips, canvasNodes, multicamGroups, markers, audioCrossfadeMs } = state;
+  // Backend expects: { id, assets: {}, clips: {}, tracks: {} }
+  // where tracks have clipOrder and role fields
+  const backendAssets: Record<string, any> = {};
+  assets.forEach(asset => {
+    // Convert media:// URL to file path (library URLs are resolved by the backend)
+    let srcPath = asset.url;
+    if (srcPath.startsWith('media://') && !srcPath.startsWith('media://library/')) {
+      srcPath = srcPath.replace('media://', '');
+    }
+    
+    backendAssets[asset.id] = {
+      id: asset.id,
+      name: asset.name,
+      src: srcPath,
+      duration_ms: asset.duration,
+      width: asset.metadata.width,
+      height: asset.metadata.height,
+    };
+  });
+  
+  const backendClips: Record<string, any> = {};
+  Object.values(clips).forEach(clip => {
+    backendClips[clip.id] = {
+      id: clip.id,
+      assetId: clip.assetId,
+      inMs: clip.trimStartMs,    // Trim start in source
+      outMs: clip.trimEndMs,      // Trim end in source
+      startMs: clip.startMs,      // Position on timeline
+      endMs: clip.endMs,          // Position on timeline
+      enabled: clip.enabled !== false,
+      locked: !!clip.locked,
+      audioEffects: clip.audioEffects,
+      audioChannels: clip.audioChannels,
+      gainDb: clip.gainDb,
+      crop: clip.crop,
+      blendMode: clip.blendMode,
+      opacityKeyframes: clip.opacityKeyframes,
+      transformKeyframes: clip.transformKeyframes,
+      matte: clip.matte,
+      freezeFrames: clip.freezeFrames,
+      loop: clip.loop,
+      speed: clip.speed,
+      interpolateFrames: clip.interpolateFrames,
+      reframe: clip.reframe,
+      multicam: clip.multicam,
+      effects: clip.effects,
+      audioCrossfadeMs: clip.audioCrossfadeMs,
+      audioInMs: clip.audioInMs,
+      audioOutMs: clip.audioOutMs,
+      audioOffsetMs: clip.audioOffsetMs,
+    };
+  });
+  
+  const backendTracks: Record<string, any> = {};
+  // Find first video track to determine main track
+  const firstVideoTrack = tracks.find(t => t.type === 'video' && t.role !== 'adjustment');
+  
+  tracks.forEach((track) => {
+    // First video track is 'main', subsequent video tracks are 'overlay' (PiP)
+    // Audio tracks are 'overlay' (they'll be mixed together)
+    // Adjustment tracks only carry effects applied over the composited program
+    let role: string;
+    if (track.role === 'adjustment') {
+      role = 'adjustment';
+    } else if (track.type === 'video') {
+      role = track.id === firstVideoTrack?.id ? 'main' : 'overlay';
+    } else {
+      role = 'overlay';
+    }
+    
+    backendTracks[track.id] = {
+      id: track.id,
+      name: track.name,
+      type: track.type,
+      role: role,
+      enabled: track.visible,     // Hidden video / muted audio tracks are skipped
+      locked: track.locked,
+      clipOrder: track.clips,
+      audioRole: track.audioRole,
+      ducking: track.ducking,
+      language: track.language,
+    };
+  });
+
+  // Create project JSON in backend format
+  // Include canvasNodes for PiP transforms
+  const backendCanvasNodes: Record<string, any> = {};
+  Object.values(canvasNodes).forEach(node => {
+    backendCanvasNodes[node.id] = {
+      id: node.id,
+      clipId: node.clipId,
+      x: node.x,
+      y: node.y,
+      width: node.width,
+      height: node.height,
+      rotation: node.rotation,
+      opacity: node.opacity,
+    };
+  });
+
+  return JSON.stringify({
+    id,
+    projectName,
+    assets: backendAssets,
+    clips: backendClips,
+    tracks: backendTracks,
+    canvasNodes: backendCanvasNodes,
+    multicamGroups: multicamGroups ?? {},
+    markers: markers ?? {},
+    audioCrossfadeMs,
+  });
+}
@@ -1,5 +1,6 @@
 import { clsx, type ClassValue } from "clsx"
 import { twMerge } from "tailwind-merge"
+import type { Keyframe } from "@/types"
 
 export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs))
@@ -206,6 +207,28 @@ export function resolveClipCollision(
   };
 }
 
+// Interpolate keyframes at a clip-local time, matching the export's easing curves
+export function interpolateKeyframes(keyframes: Keyframe[] | undefined, timeMs: number, fallback: number): number {
+  const sorted = [...(keyframes ?? [])].sort((a, b) => a.timeMs - b.timeMs);
+  if (sorted.length === 0) return fallback;
+  if (timeMs <= sorted[0].timeMs) return sorted[0].value;
+
+  for (let i = 0; i < sorted.length - 1; i++) {
+    const a = sorted[i];
+    const b = sorted[i + 1];
+    if (timeMs < b.timeMs) {
+      const p = (timeMs - a.timeMs) / (b.timeMs - a.timeMs);
+      let eased = p;
+      if (a.easing === 'ease-in') eased = p * p;
+      else if (a.easing === 'ease-out') eased = p * (2 - p);
+      else if (a.easing === 'ease-in-out') eased = p < 0.5 ? 2 * p * p : 1 - Math.pow(2 - 2 * p, 2) / 2;
+      else if (a.easing === 'hold') eased = 0;
+      return a.value + (b.value - a.value) * eased;
+    }
+  }
+  return sorted[sorted.length - 1].value;
+}
+
 // Format file size in human-readable format
 export function formatFileSize(bytes: number): string {
   if (bytes === 0) return '0 B';
@@ -1,9 +1,9 @@
 import { create } from 'zustand';
 import { persist } from 'zustand/middleware';
 import { immer } from 'zustand/middleware/immer';
-import type { Asset, Clip, Track, CanvasNode, ProjectState } from '@/types';
+import type { Asset, Clip, Track, CanvasNode, Marker, ProjectState, VideoEffect } from '@/types';
 import { generateId } from '@/lib/utils';
-import { ingestFiles, type IngestResult } from '@/lib/bindings';
+import { detectCrop, ingestFiles, listenIngestTranscode, type ClipGainSuggestion, type IngestResult } from '@/lib/bindings';
 import { audioManager } from '@/lib/AudioManager';
 import { usePlaybackStore } from '@/store/playbackStore';
 
@@ -16,17 +16,21 @@ interface ProjectStore extends ProjectState {
 
   // Track actions
   addTrack: (type: 'video' | 'audio', name?: string) => void;
+  addAdjustmentTrack: (name?: string) => void;
   removeTrack: (trackId: string) => void;
   updateTrack: (trackId: string, updates: Partial<Track>) => void;
 
   // Clip actions
   createClip: (assetId: string, trackId: string, startMs: number) => string;
+  createAdjustmentClip: (trackId: string, startMs: number, durationMs: number, effects?: VideoEffect[]) => string;
   updateClip: (clipId: string, updates: Partial<Clip>) => void;
   deleteClip: (clipId: string) => void;
   moveClip: (clipId: string, trackId: string, startMs: number) => void;
   shiftClipsRight: (trackId: string, fromClipId: string, newStartMs: number) => void;
   trimClip: (clipId: string, side: 'left' | 'right', deltaMs: number) => void;
   splitClip: (clipId: string, atMs: number) => void;
+  applyClipGains: (suggestions: ClipGainSuggestion[]) => void;
+  removeBlackBars: (clipId: string) => Promise<void>;
 
   // Selection actions
   selectClips: (clipIds: string[]) => void;
@@ -38,8 +42,14 @@ interface ProjectStore extends ProjectState {
   updateCanvasNode: (nodeId: string, updates: Partial<CanvasNode>) => void;
   deleteCanvasNode: (nodeId: string) => void;
 
+  // Marker actions
+  addMarker: (timeMs: number, name?: string, durationMs?: number) => string;
+  updateMarker: (markerId: string, updates: Partial<Marker>) => void;
+  removeMarker: (markerId: string) => void;
+
   // Project actions
   updateProjectName: (name: string) => void;
+  setAudioCrossfade: (ms: number) => void;
   clearProject: () => void;
 
   // Derived state getters
@@ -48,6 +58,7 @@ interface ProjectStore extends ProjectState {
   getAssetById: (assetId: string) => Asset | undefined;
   getCanvasNodeByClipId: (clipId: string) => CanvasNode | undefined;
   getTimelineDuration: () => number;
+  getMarkers: () => Marker[];
 }
 
 const initialProjectState: ProjectState = {
@@ -82,6 +93,7 @@ const initialProjectState: ProjectState = {
   ],
   clips: {},
   canvasNodes: {},
+  markers: {},
   selectedClipIds: [],
   selectedTrackId: null,
 };
@@ -182,13 +194,16 @@ export const useProjectStore = create<ProjectStore>()(
               id: result.asset_id,
               type: assetType,
               name: result.original_file_name, // Use original file name
-              url: `media://${result.file_path}`, // Use custom media:// protocol for local files
-              thumbnailUrl: result.thumbnail_path ? `media://${result.thumbnail_path}` : undefined,
+              // Library URLs are relative to the app cache, so saved projects don't pin this machine's paths
+              url: result.library_url ?? `media://${result.file_path}`,
+              thumbnailUrl: result.thumbnail_library_url ?? (result.thumbnail_path ? `media://${result.thumbnail_path}` : undefined),
               fileSize: result.file_size,
               duration: result.metadata.duration_ms,
               metadata: {
                 width: result.metadata.width || 0,
                 height: result.metadata.height || 0,
+                fps: result.metadata.fps || undefined,
+                chapters: result.metadata.chapters?.length ? result.metadata.chapters : undefined,
               },
             };
           });
@@ -196,6 +211,30 @@ export const useProjectStore = create<ProjectStore>()(
           set((state) => {
             state.assets.push(...newAssets);
           });
+
+          // Ingest rules convert some files in the background; switch those assets over as each finishes
+          const pending = new Set(ingestResults.filter((result) => result.transcode_job_id).map((result) => result.transcode_job_id));
+          if (pending.size > 0) {
+            const stopListening = await listenIngestTranscode((event) => {
+              if (!pending.has(event.job_id) || (event.status !== 'completed' && event.status !== 'failed')) return;
+              pending.delete(event.job_id);
+              if (event.status === 'completed' && event.file_path && event.metadata) {
+                const { file_path, library_url, file_size, metadata } = event;
+                set((state) => {
+                  const asset = state.assets.find((a: Asset) => a.id === event.asset_id);
+                  if (!asset) return;
+                  asset.url = library_url ?? `media://${file_path}`;
+                  asset.fileSize = file_size;
+                  asset.metadata.width = metadata.width || asset.metadata.width;
+                  asset.metadata.height = metadata.height || asset.metadata.height;
+                  asset.metadata.fps = metadata.fps || asset.metadata.fps;
+                });
+              } else if (event.status === 'failed') {
+                console.error('Ingest conversion failed:', event.asset_id, event.error);
+              }
+              if (pending.size === 0) stopListening();
+            });
+          }
         } catch (error) {
           console.error('Error ingesting files:', error);
           throw error;
@@ -251,6 +290,20 @@ export const useProjectStore = create<ProjectStore>()(
         });
       },
 
+      addAdjustmentTrack: (name?: string) => {
+        set((state) => {
+          state.tracks.push({
+            id: generateId(),
+            name: name || `Adjustment ${state.tracks.filter((t: Track) => t.role === 'adjustment').length + 1}`,
+            type: 'video',
+            role: 'adjustment',
+            clips: [],
+            locked: false,
+            visible: true,
+          });
+        });
+      },
+
       removeTrack: (trackId: string) => {
         set((state) => {
           const track = state.tracks.find((t: Track) => t.id === trackId);
@@ -393,6 +446,29 @@ export const useProjectStore = create<ProjectStore>()(
         return clipId;
       },
 
+      createAdjustmentClip: (trackId: string, startMs: number, durationMs: number, effects: VideoEffect[] = []) => {
+        let clipId = '';
+        set((state) => {
+          const track = state.tracks.find((t: Track) => t.id === trackId);
+          if (!track || track.role !== 'adjustment') return;
+
+          clipId = generateId();
+          state.clips[clipId] = {
+            id: clipId,
+            assetId: '',
+            trackId,
+            startMs,
+            endMs: startMs + durationMs,
+            trimStartMs: 0,
+            trimEndMs: durationMs,
+            zIndex: 0,
+            effects,
+          };
+          track.clips.push(clipId);
+        });
+        return clipId;
+      },
+
       updateClip: (clipId: string, updates: Partial<Clip>) => {
         set((state) => {
           const clip = state.clips[clipId];
@@ -402,6 +478,33 @@ export const useProjectStore = create<ProjectStore>()(
         });
       },
 
+      // Set every analyzed clip to its suggested gain in one step
+      applyClipGains: (suggestions: ClipGainSuggestion[]) => {
+        set((state) => {
+          for (const suggestion of suggestions) {
+            const clip = state.clips[suggestion.clip_id];
+            if (clip && !clip.locked) {
+              clip.gainDb = suggestion.suggested_gain_db;
+            }
+          }
+        });
+      },
+
+      // Crop a clip to the picture inside black bars baked into its source
+      removeBlackBars: async (clipId: string) => {
+        const clip = get().clips[clipId];
+        const asset = clip && get().assets.find((a: Asset) => a.id === clip.assetId);
+        if (!clip || !asset || asset.type !== 'video' || clip.locked) return;
+        const detected = await detectCrop(asset.url);
+        set((state) => {
+          const target = state.clips[clipId];
+          if (!target) return;
+          target.crop = detected.has_bars
+            ? { x: detected.x, y: detected.y, width: detected.width, height: detected.height }
+            : undefined;
+        });
+      },
+
       deleteClip: (clipId: string) => {
         set((state) => {
           const clip = state.clips[clipId];
@@ -692,6 +795,36 @@ export const useProjectStore = create<ProjectStore>()(
         });
       },
 
+      // Marker actions
+      addMarker: (timeMs: number, name?: string, durationMs?: number) => {
+        const markerId = generateId();
+        set((state) => {
+          state.markers[markerId] = {
+            id: markerId,
+            name: name || `Marker ${Object.keys(state.markers).length + 1}`,
+            color: '#f5a524',
+            timeMs: Math.max(0, timeMs),
+            ...(durationMs && durationMs > 0 ? { durationMs } : {}),
+          };
+        });
+        return markerId;
+      },
+
+      updateMarker: (markerId: string, updates: Partial<Marker>) => {
+        set((state) => {
+          const marker = state.markers[markerId];
+          if (marker) {
+            Object.assign(marker, updates);
+          }
+        });
+      },
+
+      removeMarker: (markerId: string) => {
+        set((state) => {
+          delete state.markers[markerId];
+        });
+      },
+
       // Project actions
       updateProjectName: (name: string) => {
         set((state) => {
@@ -699,6 +832,12 @@ export const useProjectStore = create<ProjectStore>()(
         });
       },
 
+      setAudioCrossfade: (ms: number) => {
+        set((state) => {
+          state.audioCrossfadeMs = Math.max(0, ms);
+        });
+      },
+
       clearProject: () => {
         // Stop playback and reset timeline position
         const playbackState = usePlaybackStore.getState();
@@ -764,6 +903,11 @@ export const useProjectStore = create<ProjectStore>()(
         // Return at least 10 seconds (10000ms) for empty timeline
         return Math.max(maxEndMs, 10000);
       },
+
+      getMarkers: () => {
+        const state = get();
+        return Object.values(state.markers || {}).sort((a, b) => a.timeMs - b.timeMs);
+      },
     })),
     {
       name: 'starscape-project-storage',
@@ -774,6 +918,8 @@ export const useProjectStore = create<ProjectStore>()(
         tracks: state.tracks,
         clips: state.clips,
         canvasNodes: state.canvasNodes,
+        markers: state.markers,
+        audioCrossfadeMs: state.audioCrossfadeMs,
         selectedClipIds: state.selectedClipIds,
       }),
       // Merge function to handle backward compatibility (projects without id)
@@ -783,12 +929,29 @@ export const useProjectStore = create<ProjectStore>()(
           ...persistedState,
           // Ensure we always have an ID
           id: persistedState?.id || generateId(),
+          // Projects saved before markers existed
+          markers: persistedState?.markers || {},
+          // Projects saved with absolute cache paths
+          assets: (persistedState?.assets || currentState.assets).map(toLibraryAsset),
         };
       },
     }
   )
 );
 
+// Rewrite absolute app-cache URLs (saved by older builds) as media://library/ URLs
+const LEGACY_CACHE_PATH = /[\\/]com\.starscape\.studio[\\/]cache[\\/](.+)$/;
+
+function toLibraryUrl(url: string | undefined): string | undefined {
+  if (!url || !url.startsWith('media://') || url.startsWith('media://library/')) return url;
+  const match = LEGACY_CACHE_PATH.exec(url);
+  return match ? `media://library/${match[1].split(/[\\/]/).map(encodeURIComponent).join('/')}` : url;
+}
+
+function toLibraryAsset(asset: Asset): Asset {
+  return { ...asset, url: toLibraryUrl(asset.url) ?? asset.url, thumbnailUrl: toLibraryUrl(asset.thumbnailUrl) };
+}
+
 // Helper function to get asset type from filename
 function getAssetType(filename: string): 'video' | 'audio' | 'image' {
   const ext = filename.split('.').pop()?.toLowerCase() || '';
@@ -4,7 +4,7 @@ export interface Asset {
   id: string;
   type: 'video' | 'audio' | 'image';
   name: string;
-  url: string;          // Object URL for local files
+  url: string;          // media://library/ URL for ingested media (relative to the app cache), else an object URL
   duration: number;     // milliseconds
   thumbnailUrl?: string;
   fileSize?: number;    // bytes
@@ -12,7 +12,24 @@ export interface Asset {
     width?: number;
     height?: number;
     fps?: number;
+    chapters?: { title: string; start_ms: number; end_ms: number }[];  // Embedded by some recorders
   };
+  kind?: 'media' | 'generator';        // Generators have no file; export synthesizes their media
+  generator?: Generator;
+}
+
+// Synthesized media (lavfi sources); colors are #RRGGBB
+export type Generator =
+  | { type: 'solid'; color?: string }
+  | { type: 'gradient'; colors?: [string, string]; angle?: number }   // 0 = left to right, 90 = top to bottom
+  | { type: 'countdown'; color?: string; textColor?: string }         // Counts the seconds left in the clip
+  | { type: 'credits'; color?: string; textColor?: string; blocks: CreditsBlock[]; speed?: number }  // Rolls up; speed is px/s at 1080p, unset fits the clip
+  | { type: 'testPattern'; pattern?: 'smpte' | 'testsrc' }
+  | { type: 'tone'; frequency?: number; volumeDb?: number };          // Audio only
+
+export interface CreditsBlock {
+  heading?: string;     // e.g. "Directed by"
+  lines: string[];
 }
 
 export interface Clip {
@@ -24,15 +41,181 @@ export interface Clip {
   trimStartMs: number;  // Trim from source asset
   trimEndMs: number;    // Trim from source asset
   zIndex: number;
+  enabled?: boolean;                    // false leaves the clip out of preview and export
+  locked?: boolean;                     // Locked clips reject edits
+  audioEffects?: AudioEffect[];
+  audioChannels?: AudioChannelMap;      // Source channel routing, applied before audioEffects
+  gainDb?: number;                      // Clip gain, applied after channel routing
+  audioSync?: AudioSync;                // A/V drift correction from measureAvDrift, applied before channel routing
+  crop?: { x: number; y: number; width: number; height: number };  // Active picture in upright source pixels, cropped before fitting
+  blendMode?: BlendMode;                // Overlay tracks only
+  opacityKeyframes?: Keyframe[];        // Overlay tracks only, times relative to clip start
+  transformKeyframes?: TransformKeyframes; // Overlay tracks only, animates the canvas node
+  matte?: TrackMatte;                   // Overlay tracks only (normal blend), masked by another overlay clip
+  freezeFrames?: FreezeFrame[];         // Main track only; the holds are inside startMs..endMs (span = trim + holds)
+  loop?: boolean;                       // Repeat the trimmed source to fill startMs..endMs
+  speed?: number;                       // 0.1-10, plays the trim over startMs..endMs (span = trim / speed); not with loops or freezes
+  interpolateFrames?: boolean;          // Below 0.5x, synthesize in-between frames (minterpolate; slow to render)
+  rotationOverride?: 0 | 90 | 180 | 270; // Clockwise; replaces the source rotation metadata (0 ignores it)
+  reframe?: Partial<Record<AspectRatio, FramingOffset>>; // Main track pan per export aspect
+  multicam?: MulticamClip;              // Plays a multicam group instead of assetId
+  effects?: VideoEffect[];              // Video clips (applied in order) and adjustment clips
+  audioCrossfadeMs?: number;            // Main track: audio crossfade from the previous clip (0 = hard cut)
+  audioInMs?: number;                   // Main track J/L-cuts: audio source range (defaults to the video's trim)
+  audioOutMs?: number;
+  audioOffsetMs?: number;               // Audio start relative to startMs (negative leads the picture)
+  caption?: Caption;                    // Caption tracks only
+}
+
+// Burned-in caption; word timings (e.g. from a transcript) drive karaoke/word highlighting
+export interface Caption {
+  text: string;
+  words?: CaptionWord[];
+  style?: CaptionStyle;
+}
+
+export interface CaptionWord {
+  text: string;
+  startMs: number;      // Relative to the clip start
+  endMs?: number;
+}
+
+// Sizes are pixels at 1080p and scale with the export height
+export interface CaptionStyle {
+  fontFamily?: string;
+  fontSize?: number;
+  bold?: boolean;
+  italic?: boolean;
+  color?: string;            // #RRGGBB or #RRGGBBAA
+  highlightColor?: string;   // Spoken (karaoke) or active (word) word
+  outlineColor?: string;
+  outlineWidth?: number;
+  backgroundColor?: string;  // Draws a box behind the text instead of an outline
+  position?: 'top' | 'middle' | 'bottom';
+  margin?: number;           // Distance from the frame edge
+  highlight?: 'none' | 'karaoke' | 'word';
+}
+
+// Video effects for a clip, or for adjustment clips applied to everything beneath them
+export type VideoEffect =
+  | { type: 'lut'; path: string }       // .cube/.3dl file
+  | { type: 'color'; brightness?: number; contrast?: number; saturation?: number; gamma?: number }
+  | { type: 'blur'; radius?: number }
+  | { type: 'noise'; strength?: number; temporal?: boolean }  // Film grain, 0-100; temporal (default) changes every frame
+  | { type: 'vignette'; intensity?: number }                  // 0-1
+  | { type: 'sharpen'; amount?: number; size?: number }       // Unsharp mask; amount -2-5 (negative softens), size 3-23 px
+  | { type: 'denoise'; method?: 'hqdn3d' | 'nlmeans'; strength?: 'light' | 'medium' | 'strong' }  // hqdn3d (default) is fast, nlmeans is cleaner but slow
+  | EffectPluginRef;
+
+// Effect from a plugin descriptor in the plugins folder; params are checked against its declared parameters
+export interface EffectPluginRef {
+  type: 'plugin';
+  plugin: string;
+  params?: Record<string, number | string | boolean>;
+}
+
+// Several synced recordings of the same moment (e.g. screen + webcam)
+export interface MulticamGroup {
+  id: string;
+  name: string;
+  angles: MulticamAngle[];
+}
+
+export interface MulticamAngle {
+  id: string;
+  assetId: string;
+  offsetMs: number;     // Where this angle's media starts on the group clock (from alignClips)
+}
+
+// trimStartMs/trimEndMs of a multicam clip are positions on the group clock
+export interface MulticamClip {
+  groupId: string;
+  switches: AngleSwitch[];
+}
+
+export interface AngleSwitch {
+  atMs: number;         // Relative to the clip start on the timeline
+  angleId: string;
 }
 
+export type AspectRatio = '16:9' | '9:16' | '1:1' | '4:5';
+
+// Pan within the crop window when reframing: -1 (left/top) .. 0 (center) .. 1 (right/bottom)
+export interface FramingOffset {
+  panX: number;
+  panY: number;
+}
+
+// Hold the frame at source time atMs for durationMs on the timeline
+export interface FreezeFrame {
+  atMs: number;
+  durationMs: number;
+}
+
+export type BlendMode = 'normal' | 'screen' | 'multiply' | 'add';
+
+// The matte clip is only used as a mask: its alpha or luma (white shows) cuts out the clip using it
+export interface TrackMatte {
+  clipId: string;
+  type: 'alpha' | 'luma';
+  invert?: boolean;
+}
+
+export type KeyframeEasing = 'linear' | 'ease-in' | 'ease-out' | 'ease-in-out' | 'hold';
+
+export interface Keyframe {
+  timeMs: number;
+  value: number;
+  easing?: KeyframeEasing;  // Shapes the segment towards the next keyframe
+}
+
+export interface TransformKeyframes {
+  x?: Keyframe[];         // Canvas coordinates of the node's top-left corner
+  y?: Keyframe[];
+  scale?: Keyframe[];     // Multiplier on the node size, centered
+  rotation?: Keyframe[];  // Degrees
+}
+
+// Per-clip audio effects, rendered by the backend as ffmpeg filter chains
+// Which source channels a clip plays (0-based); e.g. a recording with the mic on channel 0 and system audio on 1
+export type AudioChannelMap =
+  | { mode: 'select'; channel: number }              // One channel on both sides
+  | { mode: 'downmix'; gains?: number[] }            // All channels mixed to mono; gains weight each channel
+  | { mode: 'split'; left: number; right: number };  // Source channels routed to the left and right output
+
+// atempo retimes audio whose clock ran fast/slow over durationMs; resample follows the timestamps across dropped audio
+export type AudioSync =
+  | { method: 'atempo'; driftMs: number; durationMs: number }
+  | { method: 'resample' };
+
+export type AudioEffect =
+  | { type: 'denoise'; method?: 'afftdn' | 'arnndn'; strength?: number; noiseFloorDb?: number; model?: string; mix?: number }
+  | { type: 'highpass'; frequency?: number }
+  | { type: 'lowpass'; frequency?: number }
+  | { type: 'deesser'; intensity?: number }
+  | { type: 'compressor'; preset?: 'podcast_voice' | 'broadcast' | 'gentle'; thresholdDb?: number; ratio?: number; attackMs?: number; releaseMs?: number; makeupDb?: number }
+  | { type: 'preset'; name: 'podcast_voice' | 'voice_cleanup' }
+  | EffectPluginRef;
+
 export interface Track {
   id: string;
   name: string;
   type: 'video' | 'audio';
+  role?: 'adjustment' | 'caption';  // Video tracks whose clips carry only effects / caption text
   clips: string[];      // Clip IDs
   locked: boolean;
   visible: boolean;
+  audioRole?: 'voice' | 'music' | 'sfx' | 'commentary' | 'dub';  // Used by the export mixdown; commentary/dub tracks get their own audio stream
+  ducking?: DuckingSettings;             // Music tracks only: duck under voice tracks
+  language?: string;                     // ISO 639-2 code ("eng", "spa") tagged on a commentary/dub track's stream
+}
+
+export interface DuckingSettings {
+  enabled: boolean;
+  thresholdDb?: number;  // Voice level that triggers ducking
+  ratio?: number;
+  attackMs?: number;
+  releaseMs?: number;
 }
 
 export interface CanvasNode {
@@ -46,6 +229,15 @@ export interface CanvasNode {
   opacity: number;
 }
 
+// Named point (or range, when durationMs is set) on the timeline; exported as chapters
+export interface Marker {
+  id: string;
+  name: string;
+  color: string;
+  timeMs: number;
+  durationMs?: number;
+}
+
 export interface ProjectState {
   id: string;               // Unique project identifier
   projectName: string;
@@ -53,6 +245,9 @@ export interface ProjectState {
   tracks: Track[];
   clips: Record<string, Clip>;
   canvasNodes: Record<string, CanvasNode>;
+  multicamGroups?: Record<string, MulticamGroup>;
+  markers: Record<string, Marker>;
+  audioCrossfadeMs?: number;  // Default audio crossfade at main-track cuts (15 ms when unset, 0 = off)
   selectedClipIds: string[];
   selectedTrackId: string | null;
 }
@@ -0,0 +1,191 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const {
+  computePeaks,
+  programAudioClips,
+  dialogueClips,
+  parseCropDetect,
+  unionCropRects,
+  onsetEnvelope,
+  pickOnsets,
+  estimateTempo,
+  trackBeats,
+  speechFrames,
+  speechSegments,
+  findKeywordHits,
+  scoreHighlights,
+  estimateAvDrift,
+} = require('../electron/analysis');
+
+test('peaks take the loudest sample of each bucket', () => {
+  const samples = Float32Array.from([0.1, -0.5, 0.2, 0.05, 0, -1, 0.3, 0.25]);
+  assert.deepEqual(computePeaks(samples, 4), [0.5, 0.2, 1, 0.3]);
+  assert.deepEqual(computePeaks(new Float32Array(0), 2), [0, 0]);
+});
+
+test('program audio leaves out frozen, detached and silent main clips', () => {
+  const plan = {
+    mainTrack: [
+      { id: 'a', srcPath: '/m/a.mov', startMs: 0, endMs: 1000 },
+      { id: 'b', srcPath: '/m/b.mov', startMs: 1000, endMs: 2000, freezeFrame: true },
+      { id: 'c', srcPath: '/m/c.mov', startMs: 2000, endMs: 3000, audioDetached: true },
+      { id: 'd', srcPath: '/m/silent.mov', startMs: 3000, endMs: 4000 },
+    ],
+    audioTrack: [{ id: 'c-audio', srcPath: '/m/c.mov', trackId: 't-audio', startMs: 1800, endMs: 3000 }],
+  };
+  const clips = programAudioClips(plan, (clip) => clip.srcPath !== '/m/silent.mov');
+  assert.deepEqual(clips.map((clip) => [clip.id, clip.trackId]), [['a', '_program'], ['c-audio', 't-audio']]);
+});
+
+test('crop detection keeps the last frame\'s rect and widens over samples', () => {
+  const output = [
+    'frame:0    pts:0       pts_time:0',
+    'lavfi.cropdetect.x1=0', 'lavfi.cropdetect.x=0', 'lavfi.cropdetect.y=144', 'lavfi.cropdetect.w=1920', 'lavfi.cropdetect.h=792',
+    'frame:1    pts:512     pts_time:0.04',
+    'lavfi.cropdetect.x=0', 'lavfi.cropdetect.y=140', 'lavfi.cropdetect.w=1920', 'lavfi.cropdetect.h=800',
+  ].join('\n');
+  assert.deepEqual(parseCropDetect(output), { x: 0, y: 140, width: 1920, height: 800 });
+  // An all-black sample reports an empty rect
+  assert.equal(parseCropDetect('lavfi.cropdetect.x=1920\nlavfi.cropdetect.y=1080\nlavfi.cropdetect.w=-1920\nlavfi.cropdetect.h=-1080'), null);
+
+  assert.deepEqual(
+    unionCropRects([{ x: 0, y: 140, width: 1920, height: 800 }, null, { x: 0, y: 132, width: 1920, height: 812 }]),
+    { x: 0, y: 132, width: 1920, height: 812 },
+  );
+  assert.equal(unionCropRects([null]), null);
+});
+
+test('dialogue clips are main-track audio and voice tracks, one entry per clip', () => {
+  const plan = {
+    mainTrack: [
+      { clipId: 'a', srcPath: '/m/a.mov', inMs: 0, outMs: 1000 },
+      { clipId: 'a', srcPath: '/m/a.mov', inMs: 1000, outMs: 1000, freezeFrame: true },
+      { clipId: 'a', srcPath: '/m/a.mov', inMs: 1000, outMs: 2500, gainDb: 3 },
+      { clipId: 'b', srcPath: '/m/b.mov', inMs: 0, outMs: 1000, audioDetached: true },
+    ],
+    audioTrack: [
+      { clipId: 'b', srcPath: '/m/b.mov', trackId: 't-main', inMs: 0, outMs: 1200 },
+      { clipId: 'vo', srcPath: '/m/vo.wav', trackId: 't-vo', inMs: 500, outMs: 900 },
+      { clipId: 'bed', srcPath: '/m/bed.wav', trackId: 't-music', inMs: 0, outMs: 9000 },
+    ],
+    audioTracks: { 't-vo': { audioRole: 'voice' }, 't-music': { audioRole: 'music' } },
+  };
+  const clips = dialogueClips(plan, () => true);
+  assert.deepEqual(clips.map((clip) => [clip.clipId, clip.inMs, clip.outMs, clip.gainDb]), [
+    ['a', 0, 2500, 0],
+    ['b', 0, 1200, 0],
+    ['vo', 500, 900, 0],
+  ]);
+});
+
+test('a 120 BPM click track gives its tempo and beat grid', () => {
+  const sampleRate = 22050;
+  const samples = new Float32Array(sampleRate * 10);
+  for (let t = 0.25; t < 10; t += 0.5) {
+    const start = Math.round(t * sampleRate);
+    for (let k = 0; k < 200; k++) samples[start + k] = Math.sin(k * 0.7) * Math.exp(-k / 60);
+  }
+  const { envelope, frameMs } = onsetEnvelope(samples, sampleRate);
+  const tempo = estimateTempo(envelope, frameMs);
+  assert.ok(Math.abs(tempo.bpm - 120) < 1, `bpm ${tempo.bpm}`);
+  assert.ok(tempo.confidence > 0.5);
+
+  const beats = trackBeats(envelope, tempo.periodFrames);
+  assert.equal(beats.length, 20);
+  assert.deepEqual(pickOnsets(envelope), beats);
+  // Consecutive beats sit one period apart
+  beats.slice(1).forEach((frame, i) => assert.ok(Math.abs((frame - beats[i]) * frameMs - 500) < frameMs * 1.5));
+});
+
+test('highlights grow around loud spikes and keyword hits', () => {
+  // 2 minutes of steady -30 dB with a shouting burst at 40-43s
+  const energyDb = Array.from({ length: 120 }, (_, i) => (i >= 40 && i < 43 ? -6 : -30 + (i % 3)));
+  const keywordHits = findKeywordHits(
+    [
+      { start_ms: 90000, end_ms: 92000, text: 'What a CLUTCH play!' },
+      { start_ms: 100000, end_ms: 101000, text: 'clutches are rare' },
+    ],
+    ['clutch']
+  );
+  assert.deepEqual(keywordHits, [{ start_ms: 90000, end_ms: 92000, keyword: 'clutch' }]);
+
+  const highlights = scoreHighlights({ durationMs: 120000, energyDb, scenes: [], keywordHits });
+  assert.deepEqual(highlights.map((h) => [h.start_ms, h.end_ms, h.reasons]), [
+    [37000, 48000, ['audio']],
+  ]);
+
+  // Keyword-only scoring still surfaces the mention, padded to the minimum length
+  const spoken = scoreHighlights({ durationMs: 120000, energyDb: null, scenes: null, keywordHits });
+  assert.deepEqual(spoken.map((h) => [h.start_ms, h.end_ms, h.score]), [[87000, 97000, 1]]);
+});
+
+test('highlights do not overlap and respect the maximum length', () => {
+  const scenes = Array.from({ length: 40 }, (_, i) => ({ at_ms: 10000 + i * 1000 }));
+  const highlights = scoreHighlights({ durationMs: 60000, energyDb: null, scenes, keywordHits: null }, { maxDurationMs: 15000 });
+  assert.ok(highlights.length > 1);
+  highlights.forEach((h) => assert.ok(h.end_ms - h.start_ms <= 15000));
+  const sorted = [...highlights].sort((a, b) => a.start_ms - b.start_ms);
+  sorted.slice(1).forEach((h, i) => assert.ok(h.start_ms >= sorted[i].end_ms));
+});
+
+test('speech frames pick out voiced bursts over a noise floor', () => {
+  const sampleRate = 16000;
+  const samples = new Float32Array(sampleRate * 3);
+  let seed = 1;
+  for (let i = 0; i < samples.length; i++) {
+    seed = (seed * 16807) % 2147483647;
+    samples[i] = ((seed / 2147483647) - 0.5) * 0.002;
+  }
+  // "Speech" (a 440 Hz tone with harmonics) at 0.5-1.2s and 2.0-2.6s; a 60 Hz hum at 1.5-1.8s
+  const add = (fromSec, toSec, fn) => {
+    for (let i = Math.round(fromSec * sampleRate); i < toSec * sampleRate; i++) samples[i] += fn(i / sampleRate);
+  };
+  const voice = (t) => 0.2 * (Math.sin(2 * Math.PI * 440 * t) + 0.6 * Math.sin(2 * Math.PI * 880 * t) + 0.3 * Math.sin(2 * Math.PI * 1320 * t));
+  add(0.5, 1.2, voice);
+  add(2.0, 2.6, voice);
+  add(1.5, 1.8, (t) => 0.3 * Math.sin(2 * Math.PI * 60 * t));
+
+  const { flags, frameMs } = speechFrames(samples, sampleRate);
+  assert.equal(frameMs, 10);
+  const segments = speechSegments(flags, frameMs, 3000);
+  const speech = segments.filter((s) => s.speech);
+  assert.equal(speech.length, 2);
+  assert.ok(Math.abs(speech[0].start_ms - 470) <= 40 && Math.abs(speech[0].end_ms - 1230) <= 40, JSON.stringify(speech[0]));
+  assert.ok(Math.abs(speech[1].start_ms - 1970) <= 40 && Math.abs(speech[1].end_ms - 2630) <= 40, JSON.stringify(speech[1]));
+  assert.equal(segments[0].start_ms, 0);
+  assert.equal(segments[segments.length - 1].end_ms, 3000);
+});
+
+test('speech segments bridge short pauses and drop clicks', () => {
+  const flags = new Uint8Array(100);
+  flags.fill(1, 10, 30);
+  flags.fill(1, 35, 50); // 50ms pause: bridged
+  flags.fill(1, 80, 84); // 40ms click: dropped
+  assert.deepEqual(speechSegments(flags, 10, 1000, { paddingMs: 0 }), [
+    { start_ms: 0, end_ms: 100, speech: false },
+    { start_ms: 100, end_ms: 500, speech: true },
+    { start_ms: 500, end_ms: 1000, speech: false },
+  ]);
+});
+
+test('drift compares the audio packet span with the video; gaps in the audio call for resampling', () => {
+  const probe = { streams: [{ codec_type: 'video', start_time: '0.000', duration: '3600.000' }], format: { duration: '3600.4' } };
+  const packets = (count, durationSec, gapAfter = -1) => Array.from({ length: count }, (_, i) => ({
+    pts_time: String(i * durationSec + (gapAfter >= 0 && i > gapAfter ? 0.2 : 0)),
+    duration_time: String(durationSec),
+  }));
+
+  // An hour of audio that runs 300 ms long: retime it
+  const slow = estimateAvDrift(probe, packets(3603, 3600.3 / 3603));
+  assert.equal(slow.drift_ms, 300);
+  assert.equal(slow.gap_ms, 0);
+  assert.deepEqual(slow.correction, { method: 'atempo', driftMs: 300, durationMs: 3600000 });
+
+  // 200 ms of dropped audio mid-way: the timestamps are right, the samples aren't
+  const gappy = estimateAvDrift(probe, packets(3600, 1, 1800));
+  assert.equal(gappy.gap_ms, 200);
+  assert.deepEqual(gappy.correction, { method: 'resample' });
+
+  assert.equal(estimateAvDrift(probe, packets(3600, 1)).correction, null);
+  assert.throws(() => estimateAvDrift(probe, []), { code: 'invalid_argument' });
+});
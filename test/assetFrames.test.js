@@ -0,0 +1,82 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const fs = require('fs-extra');
+const os = require('os');
+const path = require('path');
+const { AssetFrameCache, frameAt, normalizeFrameWidth } = require('../electron/assetFrames');
+
+async function tempDir() {
+  return fs.mkdtemp(path.join(os.tmpdir(), 'asset-frames-'));
+}
+
+test('times map to the frame they fall in', () => {
+  assert.deepEqual(frameAt(1000, 30), { index: 30, seekMs: 1016.6666666666666 });
+  assert.equal(frameAt(1033, 30).index, 30);
+  assert.equal(frameAt(-5, 25).index, 0);
+  assert.equal(frameAt(1234, null).index, 1234);
+  assert.equal(normalizeFrameWidth(333), 334);
+  assert.equal(normalizeFrameWidth(10), 64);
+});
+
+test('frames are cached per asset frame and evicted least recently used first', async () => {
+  const dir = await tempDir();
+  const asset = path.join(dir, 'clip.mp4');
+  await fs.writeFile(asset, 'media');
+  const cache = new AssetFrameCache(path.join(dir, 'frames'), { maxBytes: 250 });
+  const extracted = [];
+  const options = {
+    fps: 25,
+    extract: async (srcPath, seekMs, outputPath) => {
+      extracted.push(Math.round(seekMs));
+      await fs.writeFile(outputPath, Buffer.alloc(100));
+    },
+  };
+
+  try {
+    const first = await cache.get(asset, 0, 320, options);
+    assert.equal(first.cached, false);
+    // Same frame (0-39ms at 25fps) is served from the cache
+    assert.equal((await cache.get(asset, 30, 320, options)).cached, true);
+    await cache.get(asset, 40, 320, options);
+    await cache.get(asset, 10, 320, options); // touch frame 0
+    const third = await cache.get(asset, 80, 320, options);
+    assert.deepEqual(extracted, [20, 60, 100]);
+
+    // Frame 1 was least recently used
+    assert.equal(await fs.pathExists(first.path), true);
+    assert.equal(await fs.pathExists(path.join(path.dirname(first.path), '320_1.jpg')), false);
+    assert.equal(await fs.pathExists(third.path), true);
+
+    // A restarted cache picks up what is on disk
+    const reloaded = new AssetFrameCache(path.join(dir, 'frames'), { maxBytes: 250 });
+    await reloaded.load();
+    assert.equal(reloaded.totalBytes, 200);
+    assert.equal((await reloaded.get(asset, 0, 320, options)).cached, true);
+  } finally {
+    await fs.remove(dir);
+  }
+});
+
+test('concurrent requests for one frame share an extraction', async () => {
+  const dir = await tempDir();
+  const asset = path.join(dir, 'clip.mp4');
+  await fs.writeFile(asset, 'media');
+  const cache = new AssetFrameCache(path.join(dir, 'frames'));
+  let calls = 0;
+  const options = {
+    fps: 30,
+    extract: async (srcPath, seekMs, outputPath) => {
+      calls++;
+      await new Promise((resolve) => setTimeout(resolve, 10));
+      await fs.writeFile(outputPath, 'jpeg');
+    },
+  };
+
+  try {
+    const results = await Promise.all([cache.get(asset, 500, 640, options), cache.get(asset, 510, 640, options)]);
+    assert.equal(calls, 1);
+    assert.equal(results[0].path, results[1].path);
+  } finally {
+    await fs.remove(dir);
+  }
+});
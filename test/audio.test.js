@@ -0,0 +1,119 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const {
+  buildConformFilters,
+  audioConforms,
+  buildChannelMapFilters,
+  buildGainFilters,
+  buildClipAudioFilters,
+  buildMixdownFilters,
+  findCrossfadeCuts,
+  resolveCrossfadeCuts,
+  buildCrossfadeFilters,
+  buildDriftFilters,
+  buildSpeedFilters,
+} = require('../electron/audio');
+
+test('conform filters resample to 48 kHz stereo', () => {
+  assert.deepEqual(buildConformFilters(), ['aresample=48000', 'aformat=sample_rates=48000:channel_layouts=stereo']);
+});
+
+test('only 48 kHz stereo audio (or no audio) can be stream copied', () => {
+  assert.equal(audioConforms({ has_audio: true, sample_rate: 48000, channels: 2 }), true);
+  assert.equal(audioConforms({ has_audio: true, sample_rate: 44100, channels: 2 }), false);
+  assert.equal(audioConforms({ has_audio: true, sample_rate: 48000, channels: 1 }), false);
+  assert.equal(audioConforms({ has_audio: null }), true);
+});
+
+const cutPlan = {
+  audioCrossfadeMs: null,
+  mainTrack: [
+    { srcPath: '/a.mp4', inMs: 0, outMs: 2000, startMs: 0, endMs: 2000 },
+    { srcPath: '/b.mp4', inMs: 500, outMs: 1500, startMs: 2000, endMs: 3000 },
+    { srcPath: '/c.mp4', inMs: 0, outMs: 1000, startMs: 3500, endMs: 4500 },
+    { srcPath: '/d.mp4', inMs: 100, outMs: 1100, startMs: 4500, endMs: 5500, audioCrossfadeMs: 0 },
+  ],
+};
+
+test('channel maps select, downmix or split source channels', () => {
+  assert.deepEqual(buildChannelMapFilters({ mode: 'select', channel: 1 }), ['pan=mono|c0=c1']);
+  assert.deepEqual(buildChannelMapFilters({ mode: 'downmix' }), ['aformat=channel_layouts=mono']);
+  assert.deepEqual(buildChannelMapFilters({ mode: 'downmix', gains: [1, 0.3] }), ['pan=mono|c0=1*c0+0.3*c1']);
+  assert.deepEqual(buildChannelMapFilters({ mode: 'split', left: 1, right: 0 }), ['channelmap=map=1-FL|0-FR:channel_layout=stereo']);
+  assert.deepEqual(buildChannelMapFilters(undefined), []);
+
+  // Routing runs before the clip's effects
+  const filters = buildClipAudioFilters({ audioChannels: { mode: 'select', channel: 0 }, audioEffects: [{ type: 'highpass', frequency: 80 }] });
+  assert.equal(filters[0], 'pan=mono|c0=c0');
+  assert.ok(filters.length > 1);
+});
+
+test('clip gain becomes a clamped volume filter between routing and effects', () => {
+  assert.deepEqual(buildGainFilters(-4.5), ['volume=-4.5dB']);
+  assert.deepEqual(buildGainFilters(40), ['volume=24dB']);
+  assert.deepEqual(buildGainFilters(0), []);
+  assert.deepEqual(buildGainFilters(undefined), []);
+
+  const filters = buildClipAudioFilters({ audioChannels: { mode: 'downmix' }, gainDb: 6, audioEffects: [{ type: 'highpass', frequency: 80 }] });
+  assert.deepEqual(filters.slice(0, 2), ['aformat=channel_layouts=mono', 'volume=6dB']);
+});
+
+test('mixdown routes an audio clip\'s channels before the stereo conform', () => {
+  const clip = { trackId: 't', srcPath: '/a.wav', inMs: 0, outMs: 1000, startMs: 0, endMs: 1000, audioChannels: { mode: 'select', channel: 1 } };
+  const { filters } = buildMixdownFilters([clip], 0, 1, false);
+  assert.match(filters[1], /^\[1:a\]atrim=start=0:end=1,asetpts=PTS-STARTPTS,pan=mono\|c0=c1,aformat=channel_layouts=stereo,/);
+});
+
+test('cuts without a gap get the project crossfade unless the clip overrides it', () => {
+  assert.deepEqual(findCrossfadeCuts(cutPlan), [{ index: 1, durationMs: 15 }]);
+  assert.deepEqual(findCrossfadeCuts({ ...cutPlan, audioCrossfadeMs: 40 }), [{ index: 1, durationMs: 40 }]);
+  assert.deepEqual(findCrossfadeCuts({ ...cutPlan, audioCrossfadeMs: 0 }), []);
+});
+
+test('crossfades borrow handles from beyond each edit point and keep the program length', async () => {
+  const probeSource = async () => ({ has_audio: true, duration_ms: 2004 });
+  const cuts = await resolveCrossfadeCuts({ ...cutPlan, audioCrossfadeMs: 20 }, 0, probeSource);
+  // The outgoing source only has 4 ms past its out point, so the fade shrinks to 8 ms
+  assert.deepEqual(cuts, [{
+    atSec: 2,
+    halfSec: 0.004,
+    outgoing: { srcPath: '/a.mp4', startSec: 2 },
+    incoming: { srcPath: '/b.mp4', startSec: 0.496 },
+  }]);
+
+  const { filters, output } = buildCrossfadeFilters(cuts);
+  assert.equal(output, 'x0');
+  assert.deepEqual(filters.filter((f) => /concat|acrossfade/.test(f)), [
+    '[s0][t0]concat=n=2:v=0:a=1[q0]',
+    '[h0][s1]concat=n=2:v=0:a=1[q1]',
+    '[q0][q1]acrossfade=d=0.008:c1=tri:c2=tri[x0]',
+  ]);
+});
+
+test('drift correction retimes audio from the clip in point, before routing', () => {
+  const sync = { method: 'atempo', driftMs: 360, durationMs: 3600000 };
+  assert.deepEqual(buildDriftFilters(sync, 0), ['atempo=1.0001']);
+  // 30 minutes in, the audio is 180 ms ahead of where the seek put it
+  assert.deepEqual(buildDriftFilters(sync, 1800000), ['atrim=start=0.18', 'asetpts=PTS-STARTPTS', 'atempo=1.0001']);
+  assert.deepEqual(buildDriftFilters({ ...sync, driftMs: -360 }, 1800000), ['adelay=180:all=1', 'atempo=0.9999']);
+  assert.deepEqual(buildDriftFilters({ method: 'resample' }), ['aresample=async=1000:first_pts=0']);
+  assert.deepEqual(buildDriftFilters({ method: 'atempo', driftMs: 0, durationMs: 1000 }), []);
+
+  const filters = buildClipAudioFilters({ inMs: 0, audioSync: { method: 'resample' }, gainDb: 6 });
+  assert.equal(filters[0], 'aresample=async=1000:first_pts=0');
+});
+
+test('clip speed chains atempo within its 0.5x-2x range', () => {
+  assert.deepEqual(buildSpeedFilters(undefined), []);
+  assert.deepEqual(buildSpeedFilters(1), []);
+  assert.deepEqual(buildSpeedFilters(1.5), ['atempo=1.5']);
+  assert.deepEqual(buildSpeedFilters(0.25), ['atempo=0.5', 'atempo=0.5']);
+  assert.deepEqual(buildSpeedFilters(5), ['atempo=2', 'atempo=2', 'atempo=1.25']);
+  assert.deepEqual(buildClipAudioFilters({ speed: 2, gainDb: 6 }), ['atempo=2', 'volume=6dB']);
+});
+
+test('mixdown retimes a clip and maps a clipped head through its speed', () => {
+  const clip = { trackId: 't', srcPath: '/a.wav', inMs: 1000, outMs: 3000, startMs: 500, endMs: 1500, speed: 2 };
+  const { filters } = buildMixdownFilters([clip], 1000, 1, false);
+  assert.match(filters[1], /^\[1:a\]atrim=start=2:end=3,asetpts=PTS-STARTPTS,atempo=2,aformat=channel_layouts=stereo,/);
+});
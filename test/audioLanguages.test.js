@@ -0,0 +1,51 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { normalizeLanguage, mixAudioClips, alternateAudioTracks, languageStreamOptions } = require('../electron/audioLanguages');
+const { groupStems } = require('../electron/stems');
+
+const audioTracks = {
+  track_music: { audioRole: 'music', ducking: null, language: null, name: 'Music' },
+  track_director: { audioRole: 'commentary', ducking: null, language: 'ENG', name: "Director's Commentary" },
+  track_es: { audioRole: 'dub', ducking: null, language: 'spa', name: null },
+  track_fr: { audioRole: 'dub', ducking: null, language: 'fre', name: 'Français' },
+};
+
+const clips = [
+  { clipId: 'bed', trackId: 'track_music' },
+  { clipId: 'director', trackId: 'track_director' },
+  { clipId: 'es', trackId: 'track_es' },
+];
+
+test('commentary and dub tracks are kept out of the mix and the stems', () => {
+  assert.deepEqual(mixAudioClips(clips, audioTracks).map((clip) => clip.clipId), ['bed']);
+  assert.deepEqual(groupStems(clips, audioTracks, false).map((stem) => stem.name), ['music']);
+
+  // Tracks without clips get no stream; unnamed tracks are titled after their role
+  const alternates = alternateAudioTracks(clips, audioTracks);
+  assert.deepEqual(alternates.map(({ trackId, role, language, title }) => [trackId, role, language, title]), [
+    ['track_director', 'commentary', 'eng', "Director's Commentary"],
+    ['track_es', 'dub', 'spa', 'Dub'],
+  ]);
+});
+
+test('language codes must be ISO 639-2', () => {
+  assert.equal(normalizeLanguage(undefined), null);
+  assert.equal(normalizeLanguage(' Jpn '), 'jpn');
+  assert.throws(() => normalizeLanguage('en-US'), { code: 'invalid_argument' });
+  assert.throws(() => alternateAudioTracks(clips, { track_es: { audioRole: 'dub', language: 'es' } }), { code: 'invalid_argument' });
+});
+
+test('alternate streams follow the existing audio with title and language tags', () => {
+  const encoding = { audioCodec: 'aac', audioBitrate: 192 };
+  const alternates = alternateAudioTracks(clips, audioTracks);
+  const options = languageStreamOptions(alternates, { existingAudio: 2, mixLanguage: 'eng', ext: 'mp4', encoding });
+  assert.deepEqual(options, [
+    '-map', '0', '-c', 'copy', '-metadata:s:a:0', 'language=eng',
+    '-map', '1:a', '-c:a:2', 'aac', '-b:a:2', '192k', '-metadata:s:a:2', "title=Director's Commentary", '-metadata:s:a:2', 'language=eng',
+    '-map', '2:a', '-c:a:3', 'aac', '-b:a:3', '192k', '-metadata:s:a:3', 'title=Dub', '-metadata:s:a:3', 'language=spa',
+  ]);
+
+  // A silent program has no mix to tag; MOV keeps the new streams as PCM
+  const mov = languageStreamOptions(alternates.slice(1), { existingAudio: 0, mixLanguage: 'eng', ext: 'mov', encoding });
+  assert.deepEqual(mov, ['-map', '0', '-c', 'copy', '-map', '1:a', '-c:a:0', 'pcm_s24le', '-metadata:s:a:0', 'title=Dub', '-metadata:s:a:0', 'language=spa']);
+});
@@ -0,0 +1,57 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { EventEmitter } = require('events');
+const { PassThrough } = require('stream');
+const { AudioMonitor, audioMonitorArgs, measureLevel, SILENCE_DB } = require('../electron/audioMonitor');
+
+function fakeFfmpeg(spawned) {
+  return (args) => {
+    const proc = new EventEmitter();
+    proc.stdout = new PassThrough();
+    proc.kill = () => proc.emit('close', null);
+    spawned.push(proc);
+    return proc;
+  };
+}
+
+function pcm(value, samples) {
+  const buffer = Buffer.alloc(samples * 2);
+  for (let i = 0; i < samples; i++) buffer.writeInt16LE(value, i * 2);
+  return buffer;
+}
+
+test('levels are RMS and peak in dBFS', () => {
+  const half = measureLevel(new Array(100).fill(0.5));
+  assert.equal(half.rms_db, -6);
+  assert.equal(half.peak_db, -6);
+  assert.equal(half.clipped, false);
+  assert.equal(measureLevel(new Array(10).fill(0)).rms_db, SILENCE_DB);
+  assert.equal(measureLevel([1, -1]).clipped, true);
+  assert.deepEqual(audioMonitorArgs('darwin', '0').slice(3, 7), ['-f', 'avfoundation', '-i', ':0']);
+  assert.throws(() => audioMonitorArgs('aix', '0'), { code: 'device_not_found' });
+});
+
+test('a monitor emits one level per window, even across split samples', async () => {
+  const spawned = [];
+  const levels = [];
+  let ended = false;
+  const monitor = new AudioMonitor('m1', 'default', {
+    platform: 'linux',
+    spawnProcess: fakeFfmpeg(spawned),
+    onLevel: (level) => levels.push(level),
+    onEnd: () => { ended = true; },
+  });
+
+  // 50ms at 16kHz is 800 samples; the first window arrives in an odd-sized chunk
+  const window = pcm(16384, 800);
+  spawned[0].stdout.write(window.subarray(0, 801));
+  spawned[0].stdout.write(window.subarray(801));
+  spawned[0].stdout.write(pcm(0, 400));
+  await new Promise((resolve) => setImmediate(resolve));
+
+  assert.equal(levels.length, 1);
+  assert.deepEqual(levels[0], { monitor_id: 'm1', rms_db: -6, peak_db: -6, clipped: false });
+
+  monitor.stop();
+  assert.equal(ended, false);
+});
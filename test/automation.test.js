@@ -0,0 +1,90 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const fs = require('fs-extra');
+const http = require('http');
+const os = require('os');
+const path = require('path');
+const automation = require('../electron/automation');
+const { ErrorCode, StarscapeError } = require('../electron/errors');
+
+function request(port, method, urlPath, { token, headers = {}, body } = {}) {
+  return new Promise((resolve, reject) => {
+    const req = http.request({
+      host: '127.0.0.1',
+      port,
+      method,
+      path: urlPath,
+      headers: { ...(token ? { Authorization: `Bearer ${token}` } : {}), ...headers },
+    }, (res) => {
+      let text = '';
+      res.on('data', (chunk) => { text += chunk; });
+      res.on('end', () => resolve({ status: res.statusCode, body: JSON.parse(text) }));
+    });
+    req.on('error', reject);
+    req.end(body);
+  });
+}
+
+test('enabling the API creates a token and ports are checked', async () => {
+  const dir = await fs.mkdtemp(path.join(os.tmpdir(), 'automation-'));
+  try {
+    const storePath = path.join(dir, 'automation.json');
+    automation.init(storePath);
+    assert.deepEqual(automation.getAutomationSettings(), { enabled: false, port: automation.DEFAULT_PORT, token: null });
+
+    const enabled = automation.setAutomationSettings({ enabled: true });
+    assert.match(enabled.token, /^[0-9a-f]{48}$/);
+    assert.notEqual(automation.setAutomationSettings({ regenerateToken: true }).token, enabled.token);
+    assert.throws(() => automation.setAutomationSettings({ port: 80 }), { code: 'invalid_argument' });
+
+    // Settings survive a restart
+    const saved = automation.getAutomationSettings();
+    automation.init(storePath);
+    assert.deepEqual(automation.getAutomationSettings(), saved);
+  } finally {
+    await fs.remove(dir);
+  }
+});
+
+test('requests need the token and are routed to handlers', async () => {
+  const token = 'secret-token';
+  const calls = [];
+  const server = automation.createAutomationServer({
+    status: () => ({ version: '1.0.0' }),
+    listProjects: () => [{ id: 'proj_1', name: 'Promo' }],
+    enqueueExport: (body) => {
+      if (!body.project_id) throw new StarscapeError(ErrorCode.INVALID_ARGUMENT, 'Expected project_id or project');
+      calls.push(body);
+      return { export_id: 'export_1', status: 'queued' };
+    },
+    getExport: (id) => (id === 'export_1' ? { export_id: id, status: 'running' } : null),
+    recording: (action) => ({ action }),
+  }, () => token);
+  await automation.listen(server, 0);
+  const { port } = server.address();
+
+  try {
+    assert.equal((await request(port, 'GET', '/v1/status')).status, 401);
+    assert.equal((await request(port, 'GET', '/v1/status', { token: 'wrong-token!' })).status, 401);
+    // Browsers always send Origin on cross-site requests; those are refused even with the token
+    assert.equal((await request(port, 'GET', '/v1/status', { token, headers: { Origin: 'https://example.com' } })).status, 401);
+    assert.equal((await request(port, 'GET', '/v1/status', { token, headers: { Host: 'evil.example:1234' } })).status, 401);
+
+    assert.deepEqual(await request(port, 'GET', '/v1/status', { token }), { status: 200, body: { version: '1.0.0' } });
+    assert.deepEqual((await request(port, 'GET', '/v1/projects', { token })).body, { projects: [{ id: 'proj_1', name: 'Promo' }] });
+
+    const queued = await request(port, 'POST', '/v1/exports', { token, body: JSON.stringify({ project_id: 'proj_1', settings: { qualityProfile: 'draft' } }) });
+    assert.deepEqual(queued, { status: 200, body: { export_id: 'export_1', status: 'queued' } });
+    assert.deepEqual(calls, [{ project_id: 'proj_1', settings: { qualityProfile: 'draft' } }]);
+    assert.equal((await request(port, 'POST', '/v1/exports', { token, body: '{}' })).status, 400);
+    assert.equal((await request(port, 'POST', '/v1/exports', { token, body: 'not json' })).body.error.code, 'invalid_argument');
+
+    assert.equal((await request(port, 'GET', '/v1/exports/export_1', { token })).body.status, 'running');
+    assert.equal((await request(port, 'GET', '/v1/exports/export_2', { token })).status, 404);
+    assert.deepEqual((await request(port, 'POST', '/v1/recording/start', { token })).body, { action: 'start' });
+    assert.equal((await request(port, 'POST', '/v1/recording/rewind', { token })).status, 404);
+    assert.equal((await request(port, 'DELETE', '/v1/projects', { token })).status, 404);
+  } finally {
+    server.close();
+  }
+});
@@ -0,0 +1,19 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { buildBurnInFilters, buildBurnInLabels } = require('../electron/burnin');
+
+const clips = [
+  { srcPath: '/media/مقابلة.mp4', inMs: 0, outMs: 1000, startMs: 0, endMs: 1000 },
+  { srcPath: '/media/b.mp4', assetName: 'Party 🎉', inMs: 0, outMs: 1000, startMs: 1000, endMs: 2000, freezeFrame: true },
+];
+
+test('clip names are drawn by libass, not drawtext', () => {
+  const filters = buildBurnInFilters(clips, {}, 0, { num: 30, den: 1 }, 1080);
+  assert.ok(filters.every((filter) => !filter.includes('مقابلة') && !filter.includes('Party')));
+
+  const events = buildBurnInLabels(clips, 0, 1920, 1080).split('\n').filter((line) => line.startsWith('Dialogue:'));
+  assert.deepEqual(events, [
+    'Dialogue: 0,0:00:00.00,0:00:01.00,Label,,0,0,0,,{\\pos(18,72)}مقابلة.mp4',
+    'Dialogue: 0,0:00:01.00,0:00:02.00,Label,,0,0,0,,{\\pos(18,72)}Party 🎉 (freeze)',
+  ]);
+});
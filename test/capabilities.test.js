@@ -0,0 +1,12 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { parseHwaccels, pickDecodeHwaccel } = require('../electron/capabilities');
+
+test('hardware decode prefers the platform method the build has', () => {
+  const hwaccels = parseHwaccels('Hardware acceleration methods:\ncuda\nvaapi\nd3d11va\n');
+  assert.deepEqual(hwaccels, ['cuda', 'vaapi', 'd3d11va']);
+  assert.equal(pickDecodeHwaccel(hwaccels, 'win32'), 'd3d11va');
+  assert.equal(pickDecodeHwaccel(hwaccels, 'linux'), 'cuda');
+  assert.equal(pickDecodeHwaccel(hwaccels, 'darwin'), null);
+  assert.equal(pickDecodeHwaccel([], 'darwin'), null);
+});
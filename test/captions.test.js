@@ -0,0 +1,93 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { assColor, assTime, assFilter, buildAssDocument, captionPreviewSpan, renderCaptionsPreview } = require('../electron/captions');
+
+const dialogues = (doc) => doc.split('\n').filter((line) => line.startsWith('Dialogue:'));
+const styles = (doc) => doc.split('\n').filter((line) => line.startsWith('Style:'));
+
+test('colours convert to ASS &HAABBGGRR with inverted alpha', () => {
+  assert.equal(assColor('#FF8800'), '&H000088FF');
+  assert.equal(assColor('#00000080'), '&H7F000000');
+  assert.equal(assColor('red', '#FFFFFF'), '&H00FFFFFF');
+});
+
+test('timestamps are H:MM:SS.cc', () => {
+  assert.equal(assTime(0), '0:00:00.00');
+  assert.equal(assTime(3723456), '1:02:03.46');
+});
+
+test('plain captions are offset by the timeline start and escaped', () => {
+  const doc = buildAssDocument(
+    [{ startMs: 1500, endMs: 3000, text: 'Curly {braces}\nsecond line' }],
+    500,
+    1280,
+    720
+  );
+  assert.match(doc, /PlayResX: 1280\nPlayResY: 720/);
+  assert.deepEqual(dialogues(doc), ['Dialogue: 0,0:00:01.00,0:00:02.50,Caption1,,0,0,0,,Curly \\{braces\\}\\Nsecond line']);
+  // 64px at 1080p scales to 43px at 720p, bottom-centred
+  assert.equal(styles(doc)[0], 'Style: Caption1,Arial,43,&H00FFFFFF,&H0000D4FF,&H00000000,&H00000000,-1,0,0,0,100,100,0,0,1,3,0,2,53,53,53,1');
+});
+
+test('karaoke sweeps each word until the next one starts', () => {
+  const clip = {
+    startMs: 0,
+    endMs: 2000,
+    text: 'Hello there',
+    words: [{ text: 'Hello', startMs: 200, endMs: 600 }, { text: 'there', startMs: 700, endMs: 1400 }],
+    style: { highlight: 'karaoke' },
+  };
+  const doc = buildAssDocument([clip], 0, 1920, 1080);
+  assert.deepEqual(dialogues(doc), ['Dialogue: 0,0:00:00.00,0:00:02.00,Caption1,,0,0,0,,{\\k20}{\\kf50}Hello {\\kf70}there']);
+  // Sung words take the highlight colour (primary), unsung the base colour (secondary)
+  assert.match(styles(doc)[0], /^Style: Caption1,Arial,64,&H0000D4FF,&H00FFFFFF,/);
+});
+
+test('word highlighting emits one event per active word', () => {
+  const clip = {
+    startMs: 1000,
+    endMs: 3000,
+    words: [{ text: 'one', startMs: 0 }, { text: 'two', startMs: 500 }],
+    style: { highlight: 'word', highlightColor: '#00FF00' },
+  };
+  assert.deepEqual(dialogues(buildAssDocument([clip], 0, 1920, 1080)), [
+    'Dialogue: 0,0:00:01.00,0:00:01.50,Caption1,,0,0,0,,{\\c&H00FF00&}one{\\r} two',
+    'Dialogue: 0,0:00:01.50,0:00:03.00,Caption1,,0,0,0,,one {\\c&H00FF00&}two{\\r}',
+  ]);
+});
+
+test('a background colour switches to an opaque box', () => {
+  const doc = buildAssDocument([{ startMs: 0, endMs: 1000, text: 'Boxed', style: { backgroundColor: '#000000CC', position: 'top' } }], 0, 1920, 1080);
+  const fields = styles(doc)[0].split(',');
+  assert.equal(fields[5], '&H33000000');
+  assert.equal(fields[15], '3');
+  assert.equal(fields[18], '8');
+});
+
+test('right-to-left captions let libass detect the base direction', () => {
+  const doc = buildAssDocument([
+    { startMs: 0, endMs: 1000, text: 'שלום עולם' },
+    { startMs: 1000, endMs: 2000, text: 'Hello 👋' },
+  ], 0, 1920, 1080);
+  assert.deepEqual(styles(doc).map((line) => line.split(',').pop()), ['-1', '1']);
+  assert.match(dialogues(doc)[1], /Hello 👋$/);
+});
+
+test('the ass filter always shapes with HarfBuzz', () => {
+  assert.equal(assFilter('/tmp/a.ass'), "ass='/tmp/a.ass':shaping=complex");
+  assert.equal(assFilter('/tmp/a.ass', '/tmp/fonts'), "ass='/tmp/a.ass':shaping=complex:fontsdir='/tmp/fonts'");
+});
+
+test('the captions preview spans the program and any captions past its end', async () => {
+  const plan = {
+    id: 'p1',
+    mainTrack: [{ startMs: 1000, endMs: 5000 }, { startMs: 5000, endMs: 9000 }],
+    captionTrack: [{ startMs: 2000, endMs: 4000, text: 'Hi' }, { startMs: 8000, endMs: 11000, text: 'Bye' }],
+  };
+  assert.deepEqual(captionPreviewSpan(plan), { startMs: 1000, endMs: 11000 });
+  assert.deepEqual(captionPreviewSpan({ mainTrack: [], captionTrack: [{ startMs: 500, endMs: 1500 }] }), { startMs: 0, endMs: 1500 });
+  await assert.rejects(
+    renderCaptionsPreview({ id: 'p2', mainTrack: [], captionTrack: [] }, '/tmp/out.mp4', '/tmp/out.ass'),
+    { code: 'invalid_argument' }
+  );
+});
@@ -0,0 +1,55 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const crypto = require('crypto');
+const fs = require('fs-extra');
+const os = require('os');
+const path = require('path');
+const { normalizeChecksums, createChecksumStream, checksumFile, writeChecksumSidecars, removeChecksumSidecars } = require('../electron/checksums');
+
+test('requested checksums are ordered and unknown algorithms are rejected', () => {
+  assert.deepEqual(normalizeChecksums(undefined), []);
+  assert.deepEqual(normalizeChecksums(['sha256', 'md5']), ['md5', 'sha256']);
+  assert.deepEqual(normalizeChecksums('md5'), ['md5']);
+  assert.throws(() => normalizeChecksums(['crc32']), { code: 'invalid_argument' });
+});
+
+test('one read produces every digest and md5sum-style sidecars', async () => {
+  const dir = await fs.mkdtemp(path.join(os.tmpdir(), 'checksums-'));
+  try {
+    const file = path.join(dir, 'master.mov');
+    const data = crypto.randomBytes(9 * 1024 * 1024 + 17);
+    await fs.writeFile(file, data);
+
+    const checksums = await checksumFile(file, ['md5', 'sha256']);
+    assert.equal(checksums.md5, crypto.createHash('md5').update(data).digest('hex'));
+    assert.equal(checksums.sha256, crypto.createHash('sha256').update(data).digest('hex'));
+
+    const sidecars = await writeChecksumSidecars(file, checksums);
+    assert.deepEqual(sidecars, [`${file}.md5`, `${file}.sha256`]);
+    assert.equal(await fs.readFile(`${file}.md5`, 'utf8'), `${checksums.md5}  master.mov\n`);
+
+    await removeChecksumSidecars(file, checksums);
+    assert.equal(await fs.pathExists(`${file}.sha256`), false);
+  } finally {
+    await fs.remove(dir);
+  }
+});
+
+test('a checksum stream hashes the bytes it passes through unchanged', async () => {
+  const chunks = [crypto.randomBytes(1000), crypto.randomBytes(70000), Buffer.from('tail')];
+  const hashing = createChecksumStream(['md5', 'sha256']);
+  const received = [];
+  hashing.on('data', (chunk) => received.push(chunk));
+  await new Promise((resolve) => {
+    hashing.on('end', resolve);
+    chunks.forEach((chunk) => hashing.write(chunk));
+    hashing.end();
+  });
+
+  const data = Buffer.concat(chunks);
+  assert.deepEqual(Buffer.concat(received), data);
+  assert.deepEqual(hashing.digests(), {
+    md5: crypto.createHash('md5').update(data).digest('hex'),
+    sha256: crypto.createHash('sha256').update(data).digest('hex'),
+  });
+});
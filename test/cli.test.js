@@ -0,0 +1,33 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { parseRenderArgs } = require('../electron/cli');
+
+test('render arguments become export settings', () => {
+  const args = parseRenderArgs(
+    ['render', 'promo.json', '--preset', 'master', '--settings', 'delivery.json', '--width', '3840', '--checksum', 'md5', '--checksum', 'sha256', '--fragmented', '--output-dir', 'out'],
+    () => ({ width: 1280, height: 2160, aspectRatio: '16:9' })
+  );
+  assert.equal(args.projectPath, 'promo.json');
+  assert.equal(args.outputDir, 'out');
+  assert.deepEqual(args.settings, {
+    format: 'mp4',
+    width: 3840,
+    height: 2160,
+    aspectRatio: '16:9',
+    qualityProfile: 'master',
+    checksums: ['md5', 'sha256'],
+    fragmented: true,
+  });
+  assert.equal(parseRenderArgs(['--help']).help, true);
+});
+
+test('size and rate stay unset unless passed, so they follow the sources', () => {
+  assert.deepEqual(parseRenderArgs(['render', 'promo.json']).settings, { format: 'mp4' });
+  assert.deepEqual(parseRenderArgs(['render', 'promo.json', '--fps', '25']).settings, { format: 'mp4', fps: 25 });
+});
+
+test('bad command lines are usage errors', () => {
+  for (const argv of [[], ['encode', 'a.json'], ['render'], ['render', 'a.json', 'b.json'], ['render', 'a.json', '--preset', 'youtube'], ['render', 'a.json', '--fps', '0'], ['render', 'a.json', '--bogus']]) {
+    assert.throws(() => parseRenderArgs(argv), { code: 'invalid_argument' }, argv.join(' '));
+  }
+});
@@ -0,0 +1,29 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { buildMetadataTags } = require('../electron/container');
+
+test('metadata maps onto ffmpeg tag keys with the encoder tag', () => {
+  const tags = buildMetadataTags({
+    title: '  Launch Trailer ',
+    author: 'Starscape Team',
+    comment: 'Final cut',
+    creationDate: '2026-03-14T09:26:53Z',
+  });
+  assert.deepEqual(tags, {
+    title: 'Launch Trailer',
+    artist: 'Starscape Team',
+    comment: 'Final cut',
+    date: '2026-03-14',
+    creation_time: '2026-03-14T09:26:53.000Z',
+    encoder: 'Starscape Studio',
+  });
+});
+
+test('artist wins over author; blank, invalid and control characters are dropped', () => {
+  const tags = buildMetadataTags({ artist: 'A', author: 'B', title: '\u0007  ', comment: 'one\u0000two', creationDate: 'yesterday' });
+  assert.deepEqual(tags, { artist: 'A', comment: 'onetwo', encoder: 'Starscape Studio' });
+});
+
+test('missing metadata still tags the encoder', () => {
+  assert.deepEqual(buildMetadataTags(undefined), { encoder: 'Starscape Studio' });
+});
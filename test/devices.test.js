@@ -0,0 +1,45 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { capabilityArgs, parseModes } = require('../electron/devices');
+
+test('avfoundation modes are read from the unsupported frame rate error', () => {
+  const output = [
+    '[avfoundation @ 0x7f8] Selected framerate (10000.000000) is not supported by the device.',
+    '[avfoundation @ 0x7f8] Supported modes:',
+    '[avfoundation @ 0x7f8]   640x480@[1.000000 30.000000]fps',
+    '[avfoundation @ 0x7f8]   1920x1080@[1.000000 30.000000]fps',
+    '[avfoundation @ 0x7f8]   1280x720@[1.000000 60.000000]fps',
+    '[avfoundation @ 0x7f8]   1280x720@[1.000000 60.000000]fps',
+  ].join('\n');
+  assert.deepEqual(
+    parseModes('darwin', output).map((m) => `${m.width}x${m.height}@${m.max_fps}`),
+    ['1920x1080@30', '1280x720@60', '640x480@30']
+  );
+});
+
+test('dshow and v4l2 listings parse into modes', () => {
+  const dshow = [
+    '[dshow @ 000001] DirectShow video device options (from video devices)',
+    '[dshow @ 000001]  Pin "Capture" (alternative pin name "0")',
+    '[dshow @ 000001]   vcodec=mjpeg  min s=1920x1080 fps=5 max s=1920x1080 fps=30',
+    '[dshow @ 000001]   pixel_format=yuyv422  min s=640x480 fps=5 max s=640x480 fps=30',
+  ].join('\n');
+  assert.deepEqual(parseModes('win32', dshow), [
+    { width: 1920, height: 1080, min_fps: 5, max_fps: 30, format: 'mjpeg' },
+    { width: 640, height: 480, min_fps: 5, max_fps: 30, format: 'yuyv422' },
+  ]);
+
+  const v4l2 = [
+    '[video4linux2,v4l2 @ 0x55] Raw       :     yuyv422 :           YUYV 4:2:2 : 640x480 320x240',
+    '[video4linux2,v4l2 @ 0x55] Compressed:       mjpeg :          Motion-JPEG : 1280x720',
+  ].join('\n');
+  const modes = parseModes('linux', v4l2);
+  assert.deepEqual(modes.map((m) => `${m.width}x${m.height}:${m.format}`), ['1280x720:mjpeg', '640x480:yuyv422', '320x240:yuyv422']);
+  assert.equal(modes[0].max_fps, null);
+});
+
+test('v4l2 probing only accepts video device nodes', () => {
+  assert.deepEqual(capabilityArgs('linux', '/dev/video0').slice(-2), ['-i', '/dev/video0']);
+  assert.throws(() => capabilityArgs('linux', '/etc/passwd'), { code: 'invalid_argument' });
+  assert.equal(capabilityArgs('win32', 'HD Webcam').at(-1), 'video=HD Webcam');
+});
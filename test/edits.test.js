@@ -0,0 +1,197 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { applyEdits } = require('../electron/edits');
+
+function project(overrides = {}) {
+  return {
+    id: 'p1',
+    tracks: { t1: { id: 't1', clipOrder: ['c1', 'c2'] }, t2: { id: 't2', clipOrder: [] } },
+    clips: {
+      c1: { id: 'c1', inMs: 0, outMs: 1000, startMs: 0, endMs: 1000 },
+      c2: { id: 'c2', inMs: 0, outMs: 1000, startMs: 2000, endMs: 3000 },
+    },
+    markers: {},
+    ...overrides,
+  };
+}
+
+const json = (value) => JSON.stringify(value);
+
+test('applyEdits checks locks against the last accepted project', () => {
+  const locked = project();
+  locked.clips.c1.locked = true;
+  const moved = structuredClone(locked);
+  moved.clips.c1.startMs = 500;
+  assert.throws(() => applyEdits(json(moved), json(locked)), { code: 'locked', context: { clip_id: 'c1' } });
+
+  // Unlocking alone is allowed, and so are edits to unlocked clips
+  const unlocked = structuredClone(locked);
+  unlocked.clips.c1.locked = false;
+  assert.equal(applyEdits(json(unlocked), json(locked)).clips.c1.locked, false);
+  const other = structuredClone(locked);
+  other.clips.c2.startMs = 4000;
+  assert.equal(applyEdits(json(other), json(locked)).clips.c2.startMs, 4000);
+
+  // A locked track keeps its clips and order
+  const lockedTrack = project();
+  lockedTrack.tracks.t1.locked = true;
+  const reordered = structuredClone(lockedTrack);
+  reordered.tracks.t1.clipOrder = ['c2', 'c1'];
+  assert.throws(() => applyEdits(json(reordered), json(lockedTrack)), { code: 'locked', context: { track_id: 't1' } });
+
+  // Nothing accepted yet, or another project opened: accepted unchecked
+  assert.equal(applyEdits(json(moved)).id, 'p1');
+  assert.equal(applyEdits(json({ ...moved, id: 'p2' }), json(locked)).id, 'p2');
+});
+
+const { splitClipAt, rippleDeleteRange, closeGaps } = require('../electron/edits');
+
+const timing = ({ inMs, outMs, startMs, endMs, loop, freezeFrames }) => ({
+  inMs, outMs, startMs, endMs, ...(loop ? { loop } : {}), ...(freezeFrames ? { freezeFrames } : {}),
+});
+
+test('ripple delete trims, splits, removes and shifts clips around the range', () => {
+  const before = {
+    id: 'p1',
+    tracks: {
+      t1: { id: 't1', clipOrder: ['a', 'b', 'c', 'd', 'e'] },
+      t2: { id: 't2', clipOrder: ['f'] },
+      t3: { id: 't3', locked: true, clipOrder: ['g'] },
+    },
+    clips: {
+      a: { id: 'a', inMs: 0, outMs: 1000, startMs: 0, endMs: 1000 },
+      b: { id: 'b', inMs: 0, outMs: 1000, startMs: 1500, endMs: 2500 },
+      c: { id: 'c', inMs: 0, outMs: 500, startMs: 3000, endMs: 3500 },
+      d: { id: 'd', inMs: 100, outMs: 1300, startMs: 3800, endMs: 5000 },
+      e: { id: 'e', inMs: 0, outMs: 1000, startMs: 6000, endMs: 7000 },
+      f: { id: 'f', inMs: 0, outMs: 5000, startMs: 1000, endMs: 6000 },
+      g: { id: 'g', inMs: 0, outMs: 500, startMs: 2500, endMs: 3000 },
+    },
+    canvasNodes: { n1: { id: 'n1', clipId: 'f', x: 10 } },
+    markers: { m1: { id: 'm1', timeMs: 1000 }, m2: { id: 'm2', timeMs: 2500 }, m3: { id: 'm3', timeMs: 4500 } },
+  };
+  const after = JSON.parse(rippleDeleteRange(json(before), 2000, 4000));
+  const { clips } = after;
+
+  assert.deepEqual(clips.a, before.clips.a);                                               // Before the range
+  assert.deepEqual(timing(clips.b), { inMs: 0, outMs: 500, startMs: 1500, endMs: 2000 });    // Tail in the range
+  assert.equal(clips.c, undefined);                                                          // Inside the range
+  assert.deepEqual(timing(clips.d), { inMs: 300, outMs: 1300, startMs: 2000, endMs: 3000 }); // Head in the range
+  assert.deepEqual(timing(clips.e), { inMs: 0, outMs: 1000, startMs: 4000, endMs: 5000 });   // After the range
+  assert.deepEqual(after.tracks.t1.clipOrder, ['a', 'b', 'd', 'e']);
+
+  // Spanning the range: both sides kept, the right one with its own canvas node
+  const [left, rightId] = after.tracks.t2.clipOrder;
+  assert.equal(left, 'f');
+  assert.deepEqual(timing(clips.f), { inMs: 0, outMs: 1000, startMs: 1000, endMs: 2000 });
+  assert.deepEqual(timing(clips[rightId]), { inMs: 3000, outMs: 5000, startMs: 2000, endMs: 4000 });
+  assert.deepEqual(Object.values(after.canvasNodes).map((node) => node.clipId).sort(), ['f', rightId].sort());
+
+  // Locked tracks don't ripple
+  assert.deepEqual(clips.g, before.clips.g);
+
+  assert.deepEqual(Object.fromEntries(Object.values(after.markers).map((m) => [m.id, m.timeMs])), { m1: 1000, m3: 2500 });
+});
+
+test('ripple delete and split refuse locked clips and tracks', () => {
+  const locked = project();
+  locked.clips.c2.locked = true;
+  assert.throws(() => rippleDeleteRange(json(locked), 500, 1500), { code: 'locked', context: { clip_id: 'c2' } });
+  // Only the tracks asked for ripple
+  assert.equal(JSON.parse(rippleDeleteRange(json(locked), 500, 1500, ['t2'])).clips.c2.startMs, 2000);
+  assert.throws(() => splitClipAt(json(locked), 'c2', 2500), { code: 'locked', context: { clip_id: 'c2' } });
+  assert.throws(() => splitClipAt(json(locked), 'missing', 2500), { code: 'not_found' });
+  assert.throws(() => splitClipAt(json(locked), 'c1', 2500), { code: 'invalid_argument' });
+  assert.throws(() => rippleDeleteRange(json(locked), 1500, 500), { code: 'invalid_argument' });
+  assert.throws(() => rippleDeleteRange('{', 0, 500), { code: 'invalid_argument' });
+
+  const lockedTrack = project();
+  lockedTrack.tracks.t1.locked = true;
+  assert.throws(() => splitClipAt(json(lockedTrack), 'c1', 500), { code: 'locked', context: { track_id: 't1', clip_id: 'c1' } });
+  assert.throws(() => closeGaps(json(lockedTrack), 't1'), { code: 'locked', context: { track_id: 't1' } });
+});
+
+test('splitting a loop mid-pass plays that pass out before looping again', () => {
+  const looped = project();
+  looped.clips.c1 = { id: 'c1', inMs: 0, outMs: 1000, startMs: 0, endMs: 3500, loop: true };
+  looped.clips.c2 = { id: 'c2', inMs: 0, outMs: 1000, startMs: 4000, endMs: 5000 };
+  const { project: result, new_clip_id: rightId } = splitClipAt(json(looped), 'c1', 1250);
+  const { clips, tracks } = JSON.parse(result);
+  const continuationId = tracks.t1.clipOrder[2];
+  assert.deepEqual(tracks.t1.clipOrder, ['c1', rightId, continuationId, 'c2']);
+  assert.deepEqual(timing(clips.c1), { inMs: 0, outMs: 1000, startMs: 0, endMs: 1250, loop: true });
+  assert.deepEqual(timing(clips[rightId]), { inMs: 250, outMs: 1000, startMs: 1250, endMs: 2000 });
+  assert.deepEqual(timing(clips[continuationId]), { inMs: 0, outMs: 1000, startMs: 2000, endMs: 3500, loop: true });
+
+  // On a pass boundary the right side is still a loop; near the end it is only part of a pass
+  const atBoundary = JSON.parse(splitClipAt(json(looped), 'c1', 2000).project);
+  assert.deepEqual(atBoundary.tracks.t1.clipOrder.length, 3);
+  assert.deepEqual(timing(Object.values(atBoundary.clips).find((clip) => clip.startMs === 2000)), { inMs: 0, outMs: 1000, startMs: 2000, endMs: 3500, loop: true });
+  const nearEnd = JSON.parse(splitClipAt(json(looped), 'c1', 3200).project);
+  assert.deepEqual(timing(Object.values(nearEnd.clips).find((clip) => clip.startMs === 3200)), { inMs: 200, outMs: 500, startMs: 3200, endMs: 3500 });
+
+  // Ripple moves the continuation along with the trimmed head
+  const rippled = JSON.parse(rippleDeleteRange(json(looped), 0, 250, ['t1'])).clips;
+  assert.deepEqual(
+    Object.values(rippled).filter((clip) => clip.id !== 'c2').map(timing),
+    [{ inMs: 250, outMs: 1000, startMs: 0, endMs: 750 }, { inMs: 0, outMs: 1000, startMs: 750, endMs: 3250, loop: true }]
+  );
+});
+
+test('splits divide freeze frames and map the cut to source time around them', () => {
+  // Plays source 0-1000, holds 1000 for a second, then plays 1000-3000
+  const frozen = project();
+  frozen.clips.c1 = { id: 'c1', inMs: 0, outMs: 3000, startMs: 0, endMs: 4000, freezeFrames: [{ atMs: 1000, durationMs: 1000 }] };
+  frozen.clips.c2 = { id: 'c2', inMs: 0, outMs: 1000, startMs: 5000, endMs: 6000 };
+  const split = (atMs) => {
+    const { project: result, new_clip_id: rightId } = splitClipAt(json(frozen), 'c1', atMs);
+    const { clips } = JSON.parse(result);
+    return [timing(clips.c1), timing(clips[rightId])];
+  };
+
+  assert.deepEqual(split(800), [
+    { inMs: 0, outMs: 800, startMs: 0, endMs: 800 },
+    { inMs: 800, outMs: 3000, startMs: 800, endMs: 4000, freezeFrames: [{ atMs: 1000, durationMs: 1000 }] },
+  ]);
+  assert.deepEqual(split(1500), [
+    { inMs: 0, outMs: 1000, startMs: 0, endMs: 1500, freezeFrames: [{ atMs: 1000, durationMs: 500 }] },
+    { inMs: 1000, outMs: 3000, startMs: 1500, endMs: 4000, freezeFrames: [{ atMs: 1000, durationMs: 500 }] },
+  ]);
+  assert.deepEqual(split(2500), [
+    { inMs: 0, outMs: 1500, startMs: 0, endMs: 2500, freezeFrames: [{ atMs: 1000, durationMs: 1000 }] },
+    { inMs: 1500, outMs: 3000, startMs: 2500, endMs: 4000 },
+  ]);
+
+  // A ripple inside the freeze leaves what's held on either side of the range
+  const rippled = JSON.parse(rippleDeleteRange(json(frozen), 1200, 1700, ['t1']));
+  assert.deepEqual(rippled.tracks.t1.clipOrder.map((id) => timing(rippled.clips[id])), [
+    { inMs: 0, outMs: 1000, startMs: 0, endMs: 1200, freezeFrames: [{ atMs: 1000, durationMs: 200 }] },
+    { inMs: 1000, outMs: 3000, startMs: 1200, endMs: 3500, freezeFrames: [{ atMs: 1000, durationMs: 300 }] },
+    { inMs: 0, outMs: 1000, startMs: 4500, endMs: 5500 },
+  ]);
+});
+
+test('splitting a retimed clip cuts its source at the clip speed', () => {
+  // Half speed: source 0-2000 plays over 4 seconds
+  const slow = project();
+  slow.clips.c1 = { id: 'c1', inMs: 0, outMs: 2000, startMs: 0, endMs: 4000, speed: 0.5 };
+  slow.clips.c2 = { id: 'c2', inMs: 0, outMs: 1000, startMs: 5000, endMs: 6000 };
+  const { project: result, new_clip_id: rightId } = splitClipAt(json(slow), 'c1', 1000);
+  const { clips } = JSON.parse(result);
+  assert.deepEqual([timing(clips.c1), timing(clips[rightId])], [
+    { inMs: 0, outMs: 500, startMs: 0, endMs: 1000 },
+    { inMs: 500, outMs: 2000, startMs: 1000, endMs: 4000 },
+  ]);
+  assert.equal(clips[rightId].speed, 0.5);
+});
+
+test('closing gaps packs a track from the start', () => {
+  const gappy = project();
+  gappy.clips.c1 = { id: 'c1', inMs: 0, outMs: 1000, startMs: 500, endMs: 1500 };
+  const packed = JSON.parse(closeGaps(json(gappy), 't1')).clips;
+  assert.deepEqual([packed.c1, packed.c2].map(({ startMs, endMs }) => [startMs, endMs]), [[0, 1000], [1000, 2000]]);
+
+  gappy.clips.c2.locked = true;
+  assert.throws(() => closeGaps(json(gappy), 't1'), { code: 'locked', context: { clip_id: 'c2' } });
+  assert.throws(() => closeGaps(json(gappy), 'missing'), { code: 'not_found' });
+});
@@ -0,0 +1,89 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const fs = require('fs-extra');
+const os = require('os');
+const path = require('path');
+const { normalizePlugin, loadEffectPlugins, listEffectPlugins, registerEffectPlugins } = require('../electron/effectPlugins');
+const { buildVideoEffectFilters } = require('../electron/effects');
+const { buildAudioEffectFilters } = require('../electron/audio');
+
+const POSTERIZE = {
+  id: 'posterize',
+  name: 'Posterize',
+  parameters: {
+    levels: { type: 'number', min: 2, max: 32, default: 6, integer: true },
+    tint: { type: 'color', default: '#ff8800' },
+  },
+  filter: ['lutyuv=y=floor(val/(256/{{levels}}))*(256/{{levels}})', 'colorchannelmixer=rr=1', 'drawbox=c={{tint}}@0.2:t=fill'],
+};
+
+const ECHO = {
+  id: 'echo',
+  kind: 'audio',
+  parameters: { mode: { type: 'choice', options: ['short', 'long'] }, soft: { type: 'boolean' } },
+  filter: 'aecho=0.8:0.9:{{mode}}:0.3,volume=enable={{soft}}',
+};
+
+test('plugin effects fill typed parameters into the filter template', () => {
+  registerEffectPlugins([POSTERIZE, ECHO]);
+  assert.deepEqual(buildVideoEffectFilters([{ type: 'plugin', plugin: 'posterize', params: { levels: 99.6, tint: 'red' } }]), [
+    'lutyuv=y=floor(val/(256/32))*(256/32)',
+    'colorchannelmixer=rr=1',
+    'drawbox=c=0xFF8800@0.2:t=fill',
+  ]);
+  // Values that aren't declared options or of the right type fall back to the defaults
+  assert.deepEqual(buildAudioEffectFilters([{ type: 'plugin', plugin: 'echo', params: { mode: "long:1'[x]", soft: true } }]), [
+    'aecho=0.8:0.9:short:0.3,volume=enable=1',
+  ]);
+  assert.deepEqual(listEffectPlugins().map((plugin) => [plugin.id, plugin.kind, plugin.filters]), [['posterize', 'video', undefined], ['echo', 'audio', undefined]]);
+
+  assert.throws(() => buildVideoEffectFilters([{ type: 'plugin', plugin: 'missing' }]), { code: 'not_found' });
+  assert.throws(() => buildVideoEffectFilters([{ type: 'plugin', plugin: 'echo' }]), { code: 'invalid_argument' });
+});
+
+test('descriptors are checked for graph syntax, unsafe filters and ffmpeg support', () => {
+  const plugin = (filter, parameters = {}) => ({ id: 'fx', parameters, filter });
+  assert.throws(() => normalizePlugin(plugin('split[a][b];[a][b]overlay')), /simple chain/);
+  // Filters that read or write files (by name or position) aren't on the allowlist
+  for (const [filter, name] of [
+    ['movie=/etc/passwd', 'movie'],
+    ['drawtext=text=x:textfile=/tmp/a', 'drawtext'],
+    ['drawtext=/tmp/font.ttf:hello', 'drawtext'],
+    ['signature=filename=/tmp/sig', 'signature'],
+    ['libvmaf=log_path=/tmp/log:model_path=/tmp/m', 'libvmaf'],
+    ['arnndn=m=/tmp/model.rnnn', 'arnndn'],
+    ['sofalizer=sofa=/tmp/h.sofa', 'sofalizer'],
+    ['dnn_processing=model=/tmp/m.pb', 'dnn_processing'],
+    ["gblur,'movie'=x", "'movie'"],
+  ]) {
+    assert.throws(() => normalizePlugin(plugin(filter)), { message: new RegExp(`aren't allowed: ${name}`) }, filter);
+  }
+  assert.throws(() => normalizePlugin(plugin('gblur=sigma=2:stats_file=/tmp/x')), { context: { id: 'fx', options: ['gblur stats_file'] } });
+  assert.equal(normalizePlugin(plugin("volume=enable='between(t,1,2)':volume=0.5")).filters.length, 1);
+  assert.throws(() => normalizePlugin(plugin('gblur=sigma={{amount}}')), /undeclared parameter: amount/);
+  assert.throws(() => normalizePlugin({ ...plugin('gblur'), id: '../x' }), /Plugin id/);
+  assert.throws(() => normalizePlugin(plugin('gblur', { s: { type: 'text' } })), /unsupported type/);
+  assert.throws(() => normalizePlugin(plugin('gblur,vignette'), ['gblur']), { code: 'ffmpeg_feature_missing', context: { id: 'fx', missing_filters: ['vignette'] } });
+  assert.equal(normalizePlugin(plugin('gblur'), ['gblur']).kind, 'video');
+});
+
+test('loading a plugins folder skips bad descriptors and duplicate ids', async () => {
+  const dir = await fs.mkdtemp(path.join(os.tmpdir(), 'plugins-'));
+  try {
+    await fs.writeJson(path.join(dir, 'a.json'), POSTERIZE);
+    await fs.writeJson(path.join(dir, 'b.json'), { ...POSTERIZE, name: 'Copy' });
+    await fs.writeFile(path.join(dir, 'c.json'), '{ not json');
+    await fs.writeJson(path.join(dir, 'd.json'), ECHO);
+    await fs.writeFile(path.join(dir, 'notes.txt'), 'ignored');
+
+    const loaded = await loadEffectPlugins(dir, ['lutyuv', 'colorchannelmixer', 'drawbox']);
+    assert.deepEqual(loaded.plugins.map((plugin) => plugin.name), ['Posterize']);
+    assert.deepEqual(loaded.errors.map((entry) => [entry.file, entry.code]), [
+      ['b.json', 'invalid_argument'],
+      ['c.json', 'invalid_argument'],
+      ['d.json', 'ffmpeg_feature_missing'],
+    ]);
+  } finally {
+    await fs.remove(dir);
+  }
+});
@@ -0,0 +1,63 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { buildVideoEffectFilters } = require('../electron/effects');
+const { buildCompositeFilters } = require('../electron/composite');
+
+test('stylistic effects translate to ffmpeg filters with clamped parameters', () => {
+  assert.deepEqual(buildVideoEffectFilters([
+    { type: 'noise', strength: 250 },
+    { type: 'noise', strength: 10, temporal: false },
+    { type: 'vignette' },
+    { type: 'sharpen', amount: 1.5, size: 4 },
+    { type: 'blur', radius: 3 },
+  ]), [
+    'noise=alls=100:allf=t+u',
+    'noise=alls=10:allf=u',
+    `vignette=angle=${0.2 * Math.PI}`,
+    'unsharp=luma_msize_x=5:luma_msize_y=5:luma_amount=1.5',
+    'gblur=sigma=3',
+  ]);
+  assert.deepEqual(buildVideoEffectFilters([{ type: 'noise', strength: 0 }, { type: 'vignette', intensity: 0 }, { type: 'sharpen', amount: 0 }]), []);
+});
+
+test('denoise picks a preset per method and strength', () => {
+  assert.deepEqual(buildVideoEffectFilters([
+    { type: 'denoise' },
+    { type: 'denoise', method: 'nlmeans', strength: 'strong' },
+    { type: 'denoise', method: 'bm3d', strength: 'extreme' },
+  ]), ['hqdn3d=4:3:6:4.5', 'nlmeans=s=8:p=7:r=15', 'hqdn3d=4:3:6:4.5']);
+});
+
+test('overlay clip effects run at the placed size before alpha', () => {
+  const clip = {
+    srcPath: '/media/pip.mp4',
+    inMs: 0,
+    outMs: 1000,
+    startMs: 0,
+    endMs: 1000,
+    canvasNode: { x: 0, y: 0, width: 960, height: 540, rotation: 0, opacity: 1 },
+    effects: [{ type: 'vignette', intensity: 1 }],
+  };
+  const { filters } = buildCompositeFilters([clip], 0, 1920, 1080);
+  assert.match(filters[0], /^\[1:v\]scale=960:540,vignette=angle=[\d.]+,format=rgba,/);
+});
+
+test('a track matte masks its clip and is not drawn itself', () => {
+  const wipe = {
+    clipId: 'clip_wipe', srcPath: '/media/wipe.mov', inMs: 0, outMs: 2000, startMs: 1000, endMs: 3000,
+  };
+  const logo = {
+    clipId: 'clip_logo', srcPath: '/media/logo.png', inMs: 0, outMs: 2000, startMs: 1000, endMs: 3000,
+    canvasNode: { x: 960, y: 540, width: 480, height: 270, rotation: 0, opacity: 1 },
+    matte: { clipId: 'clip_wipe', type: 'luma', invert: true },
+  };
+  const { filters, output } = buildCompositeFilters([wipe, logo], 0, 1920, 1080);
+
+  assert.equal(filters[0], '[1:v]scale=1920:1080,format=rgba,pad=1920:1080:0:0:color=black@0,tpad=start_duration=1:color=black@0[mt0_0]');
+  assert.match(filters[1], /^\[2:v\]scale=480:270,format=rgba,pad=1920:1080:960:540:color=black@0,tpad=start_duration=1:/);
+  assert.ok(filters.includes('[mt0_0]format=gray,negate[mk1]'));
+  assert.ok(filters.includes('[oa1][mk1]blend=all_mode=multiply[ma1]'));
+  // Only the masked clip lands on the program
+  assert.deepEqual(filters.filter((f) => f.includes('overlay=')), ["[0:v][ov1]overlay=x=0:y=0:eof_action=pass:enable='between(t,1,3)'[v1]"]);
+  assert.equal(output, 'v1');
+});
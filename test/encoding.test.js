@@ -0,0 +1,112 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const os = require('os');
+const { applyQualityProfile, resolveEncoding, resolveSegmentConcurrency, encodingKey, encodingIssues, assertEncodingValid, videoQualityOptions, audioQualityOptions, requiresMov } = require('../electron/encoding');
+const { conformVideoFilters } = require('../electron/filterUtils');
+
+test('defaults match the previous hardcoded encode', () => {
+  const encoding = resolveEncoding({ bitrate: 5000 });
+  assert.deepEqual(videoQualityOptions(encoding), ['-preset veryfast', '-crf 23', '-b:v 5000k', '-pix_fmt yuv420p']);
+  assert.deepEqual(audioQualityOptions(encoding), ['-b:a 192k']);
+  assert.equal(encoding.videoCodec, 'libx264');
+  assert.equal(encoding.audioCodec, 'aac');
+});
+
+test('export settings override preset, crf, pixel format, profile and audio', () => {
+  const encoding = resolveEncoding({ preset: 'slow', crf: 18, pixFmt: 'yuv420p10le', profile: 'high10', level: '5.1', audioBitrate: 320 });
+  assert.deepEqual(videoQualityOptions(encoding), ['-preset slow', '-crf 18', '-pix_fmt yuv420p10le', '-profile:v high10', '-level:v 5.1']);
+  assert.deepEqual(audioQualityOptions(encoding), ['-b:a 320k']);
+});
+
+test('invalid values fall back or clamp instead of reaching ffmpeg', () => {
+  const encoding = resolveEncoding({ preset: 'ludicrous', crf: 99, pixFmt: 'rgb24', profile: 'high; rm', level: 'x', bitrate: -1 });
+  assert.deepEqual(videoQualityOptions(encoding), ['-preset veryfast', '-crf 51', '-pix_fmt yuv420p']);
+});
+
+test('a known scaler reaches the frame conform, anything else keeps bicubic', () => {
+  assert.equal(resolveEncoding({ scaler: 'lanczos' }).scaler, 'lanczos');
+  assert.equal(resolveEncoding({ scaler: 'lanczos:param0=3' }).scaler, null);
+  assert.deepEqual(conformVideoFilters(3840, 2160, null, 'lanczos'), [
+    'scale=3840:2160:force_original_aspect_ratio=decrease:flags=lanczos',
+    'pad=3840:2160:(ow-iw)/2:(oh-ih)/2:black',
+    'setsar=1',
+  ]);
+  assert.match(conformVideoFilters(1920, 1080, null)[0], /decrease$/);
+});
+
+test('color tags and range are written as stream tags', () => {
+  const encoding = resolveEncoding({ pixFmt: 'yuv422p10le', profile: 'high422', colorPrimaries: 'bt709', colorTransfer: 'bt709', colorMatrix: 'bt709', colorRange: 'limited' });
+  assert.deepEqual(videoQualityOptions(encoding).slice(2), [
+    '-pix_fmt yuv422p10le',
+    '-profile:v high422',
+    '-color_primaries bt709',
+    '-color_trc bt709',
+    '-colorspace bt709',
+    '-color_range tv',
+  ]);
+  assert.equal(resolveEncoding({ colorRange: 'pc' }).colorRange, null);
+  // Untagged encodes keep the cache keys they had before color tags existed
+  assert.ok(!('colorPrimaries' in encodingKey(resolveEncoding({}))));
+});
+
+test('pixel formats, profiles and HDR tags are checked against the codec', () => {
+  assert.deepEqual(encodingIssues(resolveEncoding({ pixFmt: 'yuv422p10le', profile: 'high422' })), []);
+  assert.deepEqual(encodingIssues(resolveEncoding({ videoCodec: 'h264_videotoolbox', pixFmt: 'yuv422p10le' })).map((issue) => issue.code), ['pix_fmt_unsupported']);
+  assert.deepEqual(encodingIssues(resolveEncoding({ pixFmt: 'yuv420p10le', profile: 'high' })).map((issue) => issue.code), ['profile_mismatch']);
+  assert.deepEqual(
+    encodingIssues(resolveEncoding({ colorTransfer: 'smpte2084', colorPrimaries: 'bt709' })).map((issue) => issue.code),
+    ['hdr_needs_10bit', 'hdr_needs_bt2020'],
+  );
+  assert.throws(() => assertEncodingValid(resolveEncoding({ colorTransfer: 'arib-std-b67' })), { code: 'invalid_argument' });
+});
+
+test('mezzanine profiles encode ProRes/DNxHR with PCM audio in MOV', () => {
+  const settings = applyQualityProfile({ qualityProfile: 'prores_422hq', format: 'mp4', bitrate: 5000 });
+  const encoding = resolveEncoding(settings);
+  assert.deepEqual(videoQualityOptions(encoding), ['-pix_fmt yuv422p10le', '-profile:v hq']);
+  assert.deepEqual(audioQualityOptions(encoding), []);
+  assert.equal(encoding.audioCodec, 'pcm_s16le');
+  assert.ok(requiresMov(encoding));
+  assert.ok(!requiresMov(resolveEncoding({})));
+
+  const dnxhr = resolveEncoding(applyQualityProfile({ qualityProfile: 'dnxhr_hqx' }));
+  assert.deepEqual(videoQualityOptions(dnxhr), ['-pix_fmt yuv422p10le', '-profile:v dnxhr_hqx']);
+  assert.deepEqual(encodingIssues(dnxhr), []);
+  for (const profile of ['prores_422', 'prores_4444', 'dnxhr_hq']) {
+    assert.deepEqual(encodingIssues(resolveEncoding(applyQualityProfile({ qualityProfile: profile }))), [], profile);
+  }
+  assert.deepEqual(encodingIssues(resolveEncoding({ videoCodec: 'dnxhd', pixFmt: 'yuv422p' })).map((issue) => issue.code), ['profile_mismatch']);
+  assert.deepEqual(encodingIssues(resolveEncoding({ videoCodec: 'prores_ks', pixFmt: 'yuv422p10le', profile: 'high422' })).map((issue) => issue.code), ['profile_mismatch']);
+});
+
+test('hardware encoders get bitrate control without x264 options', () => {
+  const encoding = resolveEncoding({ videoCodec: 'h264_videotoolbox', bitrate: 8000 });
+  assert.deepEqual(videoQualityOptions(encoding), ['-b:v 8000k', '-pix_fmt yuv420p']);
+});
+
+test('thread cap reaches ffmpeg and the x264 thread pool', () => {
+  const encoding = resolveEncoding({ threads: 1 });
+  assert.deepEqual(videoQualityOptions(encoding).slice(-2), ['-threads 1', '-x264-params threads=1']);
+  assert.equal(resolveEncoding({ threads: 0 }).threads, null);
+  assert.ok(resolveEncoding({ threads: 4096 }).threads <= os.cpus().length);
+});
+
+test('segments render one at a time unless parallelism is set', () => {
+  assert.equal(resolveSegmentConcurrency({}), 1);
+  assert.equal(resolveSegmentConcurrency({ parallelSegments: -3 }), 1);
+  assert.equal(resolveSegmentConcurrency({ parallelSegments: 4096 }), os.cpus().length);
+});
+
+test('quality profiles fill in unset settings only', () => {
+  const draft = applyQualityProfile({ qualityProfile: 'draft', width: 1920, height: 1080 });
+  assert.equal(draft.halfResolution, true);
+  assert.deepEqual(videoQualityOptions(resolveEncoding(draft)), ['-preset ultrafast', '-crf 30', '-pix_fmt yuv420p']);
+
+  const master = resolveEncoding(applyQualityProfile({ qualityProfile: 'master', crf: 16 }));
+  assert.equal(master.preset, 'slow');
+  assert.equal(master.crf, 16);
+  assert.equal(master.audioBitrate, 320);
+
+  const settings = { qualityProfile: 'bogus', preset: 'fast' };
+  assert.equal(applyQualityProfile(settings), settings);
+});
@@ -0,0 +1,26 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { ErrorCode, IPC_ERROR_MARKER, StarscapeError, toStarscapeError, toIpcError } = require('../electron/errors');
+
+const decode = (error) => JSON.parse(error.message.slice(IPC_ERROR_MARKER.length));
+
+test('toIpcError keeps code and context and prefixes the action', () => {
+  const error = toIpcError(new StarscapeError(ErrorCode.LOCKED, 'Clip c1 is locked', { clip_id: 'c1' }), 'Failed to apply edits');
+  assert.deepEqual(decode(error), {
+    code: 'locked',
+    message: 'Failed to apply edits: Clip c1 is locked',
+    context: { clip_id: 'c1' },
+  });
+});
+
+test('untyped errors and strings become internal errors', () => {
+  assert.equal(decode(toIpcError('ffmpeg exited with code 1', 'Failed to export project')).code, 'internal');
+  assert.equal(toStarscapeError(new Error('boom')).code, ErrorCode.INTERNAL);
+});
+
+test('ENOENT errors map to file_not_found', () => {
+  const enoent = Object.assign(new Error('no such file'), { code: 'ENOENT', path: '/missing.mov' });
+  const typed = toStarscapeError(enoent);
+  assert.equal(typed.code, ErrorCode.FILE_NOT_FOUND);
+  assert.deepEqual(typed.context, { path: '/missing.mov' });
+});
@@ -0,0 +1,26 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { EVENT_SCHEMA_VERSION, EventType, buildPayload, emitEvent } = require('../electron/events');
+
+test('buildPayload stamps the version and keeps only declared fields', () => {
+  assert.deepEqual(buildPayload(EventType.EXPORT_PROGRESS, { phase: 'concat', current: 2, total: 5, message: 'x', extra: true }), {
+    version: EVENT_SCHEMA_VERSION,
+    phase: 'concat',
+    current: 2,
+    total: 5,
+    message: 'x',
+  });
+});
+
+test('buildPayload rejects unknown events', () => {
+  assert.throws(() => buildPayload('made-up-event', {}), /Unknown event type/);
+});
+
+test('emitEvent sends to a window and skips destroyed ones', () => {
+  const sent = [];
+  const window = { isDestroyed: () => false, webContents: { send: (...args) => sent.push(args) } };
+  emitEvent(window, EventType.APP_SHUTTING_DOWN);
+  emitEvent({ ...window, isDestroyed: () => true }, EventType.APP_SHUTTING_DOWN);
+  emitEvent(null, EventType.APP_SHUTTING_DOWN);
+  assert.deepEqual(sent, [['app-shutting-down', { version: EVENT_SCHEMA_VERSION }]]);
+});
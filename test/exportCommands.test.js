@@ -0,0 +1,209 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const fs = require('fs');
+const path = require('path');
+const { createTempCache, loadProject, useMockFfmpeg, readMockCommands } = require('./helpers/harness');
+const { assertGolden, normalizePaths } = require('./helpers/golden');
+
+// The mock is a node script run through its shebang, which Windows can't spawn
+const skip = process.platform === 'win32';
+
+const { dir, cache } = createTempCache('starscape-export-');
+const commandLog = skip ? null : useMockFfmpeg(dir);
+
+// Configured after the env override so fluent-ffmpeg spawns the mock
+require('../electron/ffmpeg').configureFfmpeg();
+const { buildPlan } = require('../electron/editPlan');
+const { executeExportJob } = require('../electron/export');
+const { pathToFileUrl } = require('../electron/paths');
+const { applyQualityProfile, resolveEncoding } = require('../electron/encoding');
+const { normalizeGenerator, renderGenerator } = require('../electron/generators');
+
+const SETTINGS = { width: 1280, height: 720, bitrate: 8000, format: 'mp4', filename: 'golden export' };
+
+test('export of a main track with a gap builds the golden commands and concat list', { skip }, async () => {
+  await cache.ensureDirectories();
+  const plan = buildPlan(loadProject('basic.project.json'));
+  const result = await executeExportJob(plan, SETTINGS, cache, null, null);
+
+  const outPath = path.join(cache.renders, 'golden export.mp4');
+  assert.equal(result.path, pathToFileUrl(outPath));
+  assert.equal(result.duration_ms, 5000);
+  assert.ok(fs.existsSync(outPath));
+
+  const concatList = fs.readFileSync(cache.concatListPath(plan.id), 'utf8');
+  assertGolden('basic.concat.txt', `${normalizePaths(concatList, dir)}\n`);
+
+  const commands = readMockCommands(commandLog).filter((args) => args.includes('-i'));
+  assertGolden('basic.export-commands.json', normalizePaths(commands, dir));
+});
+
+test('re-exporting the same plan reuses cached segments', { skip }, async () => {
+  const plan = buildPlan(loadProject('basic.project.json'));
+  const before = readMockCommands(commandLog).length;
+  await executeExportJob(plan, { ...SETTINGS, filename: 'golden export 2' }, cache, null, null);
+
+  // Only the concat and the faststart remux run again; every segment comes from the cache
+  const commands = readMockCommands(commandLog).slice(before).filter((args) => args.includes('-i'));
+  assert.equal(commands.length, 2);
+  assert.ok(commands[0].includes(cache.concatListPath(plan.id)));
+  assert.ok(commands[1].join(' ').includes('+faststart'));
+});
+
+test('gap segments are re-rendered when only the pixel format changes', { skip }, async () => {
+  const plan = buildPlan(loadProject('basic.project.json'));
+  const blackSegments = (commands) => commands.filter((args) => args.some((arg) => arg.startsWith('color=black')));
+
+  let before = readMockCommands(commandLog).length;
+  await executeExportJob(plan, { ...SETTINGS, pixFmt: 'yuv420p', filename: 'gap 420' }, cache, null, null);
+  const first = blackSegments(readMockCommands(commandLog).slice(before));
+
+  before = readMockCommands(commandLog).length;
+  await executeExportJob(plan, { ...SETTINGS, pixFmt: 'yuv422p', filename: 'gap 422' }, cache, null, null);
+  const second = blackSegments(readMockCommands(commandLog).slice(before));
+  assert.equal(second.length, 1);
+  assert.ok(second[0].join(' ').includes('yuv422p'));
+  assert.ok(first.every((args) => !args.join(' ').includes('yuv422p')));
+});
+
+test('segments rendering in parallel share one probe of their source', { skip }, async () => {
+  const project = JSON.parse(loadProject('basic.project.json'));
+  project.clips.clip_2 = { ...project.clips.clip_1, id: 'clip_2', inMs: 4000, outMs: 6000, startMs: 3000, endMs: 5000 };
+  const probeLog = path.join(dir, 'ffprobe-calls.txt');
+  const fakeProbe = path.join(dir, 'ffprobe-logging');
+  fs.writeFileSync(fakeProbe, `#!/bin/sh\necho "$*" >> '${probeLog}'\nexit 1\n`, { mode: 0o755 });
+  process.env.STARSCAPE_FFPROBE_PATH = fakeProbe;
+  try {
+    await executeExportJob(buildPlan(JSON.stringify(project)), { ...SETTINGS, parallelSegments: 2, filename: 'parallel' }, cache, null, null);
+  } finally {
+    delete process.env.STARSCAPE_FFPROBE_PATH;
+  }
+
+  const probes = fs.readFileSync(probeLog, 'utf8').trim().split('\n').filter((line) => line.includes('Shoot A.mp4'));
+  assert.equal(probes.length, 1);
+});
+
+test('web optimization can be turned off', { skip }, async () => {
+  const plan = buildPlan(loadProject('basic.project.json'));
+  const before = readMockCommands(commandLog).length;
+  await executeExportJob(plan, { ...SETTINGS, webOptimized: false, filename: 'not web optimized' }, cache, null, null);
+
+  const commands = readMockCommands(commandLog).slice(before);
+  assert.ok(commands.length > 0);
+  assert.ok(!commands.some((args) => args.join(' ').includes('+faststart')));
+  assert.ok(fs.existsSync(path.join(cache.renders, 'not web optimized.mp4')));
+});
+
+test('fragmented exports are muxed front to back instead of faststarted', { skip }, async () => {
+  const plan = buildPlan(loadProject('basic.project.json'));
+  const before = readMockCommands(commandLog).length;
+  await executeExportJob(plan, { ...SETTINGS, fragmented: true, filename: 'fragmented' }, cache, null, null);
+
+  const last = readMockCommands(commandLog).slice(before).pop().join(' ');
+  assert.match(last, /-movflags \+frag_keyframe\+empty_moov\+default_base_moof/);
+  assert.ok(!last.includes('+faststart'));
+});
+
+test('segments are fitted to the export frame and rate', { skip }, async () => {
+  const plan = buildPlan(loadProject('basic.project.json'));
+  const before = readMockCommands(commandLog).length;
+  await executeExportJob(plan, { ...SETTINGS, fps: 24, filename: 'fitted' }, cache, null, null);
+
+  const filters = readMockCommands(commandLog).slice(before).flat().join(' ');
+  assert.match(filters, /scale=1280:720:force_original_aspect_ratio=decrease/);
+  assert.match(filters, /pad=1280:720/);
+  assert.match(filters, /fps=24\/1/);
+});
+
+test('export metadata is written as container tags', { skip }, async () => {
+  const plan = buildPlan(loadProject('basic.project.json'));
+  const before = readMockCommands(commandLog).length;
+  const metadata = { title: 'Golden Export', artist: 'Test Suite', comment: 'tagged' };
+  await executeExportJob(plan, { ...SETTINGS, metadata, filename: 'tagged' }, cache, null, null);
+
+  const last = readMockCommands(commandLog).slice(before).pop();
+  for (const tag of ['title=Golden Export', 'artist=Test Suite', 'comment=tagged', 'encoder=Starscape Studio']) {
+    assert.ok(last.includes(tag), `missing ${tag}`);
+  }
+});
+
+test('generator clips are synthesized from lavfi sources', { skip }, async () => {
+  const project = JSON.parse(loadProject('basic.project.json'));
+  project.assets.asset_count = { id: 'asset_count', name: 'Countdown', kind: 'generator', generator: { type: 'countdown' } };
+  project.clips.clip_0 = { id: 'clip_0', assetId: 'asset_count', inMs: 0, outMs: 3000, startMs: 0, endMs: 3000 };
+  project.clips.clip_1 = { ...project.clips.clip_1, startMs: 3000, endMs: 5000 };
+  project.tracks.track_main.clipOrder = ['clip_0', 'clip_1'];
+  const plan = buildPlan(JSON.stringify(project));
+  const before = readMockCommands(commandLog).length;
+  await executeExportJob(plan, { ...SETTINGS, filename: 'countdown' }, cache, null, null);
+
+  const commands = readMockCommands(commandLog).slice(before);
+  const generated = commands.find((args) => args.includes('lavfi') && args.some((arg) => arg.startsWith('color=c=0x202020')));
+  assert.ok(generated, 'countdown source not rendered');
+  assert.ok(generated.some((arg) => arg.includes('s=1280x720:r=30/1:d=3')));
+  assert.ok(generated.join(' ').includes('ass='));
+
+  // The synthesized file is then trimmed into a segment like any imported clip
+  const inputs = commands.flatMap((args) => args.filter((arg, i) => args[i - 1] === '-i'));
+  assert.ok(inputs.some((input) => /generator_[0-9a-f]+\.mp4$/.test(input)));
+});
+
+test('mezzanine exports synthesize generators into MOV and WAV', { skip }, async () => {
+  const project = JSON.parse(loadProject('basic.project.json'));
+  project.assets.asset_solid = { id: 'asset_solid', name: 'Slate', kind: 'generator', generator: { type: 'solid', color: '#101010' } };
+  project.clips.clip_0 = { id: 'clip_0', assetId: 'asset_solid', inMs: 0, outMs: 1000, startMs: 0, endMs: 1000 };
+  project.tracks.track_main.clipOrder = ['clip_0', 'clip_2'];
+  const settings = { ...SETTINGS, qualityProfile: 'prores_422', format: 'mov', filename: 'mezzanine' };
+  const before = readMockCommands(commandLog).length;
+  await executeExportJob(buildPlan(JSON.stringify(project)), settings, cache, null, null);
+
+  const generated = readMockCommands(commandLog).slice(before)
+    .filter((args) => args.includes('lavfi') && args.some((arg) => arg.startsWith('color=c=0x101010')));
+  assert.equal(generated.length, 1);
+  assert.match(generated[0][generated[0].length - 1], /generator_[0-9a-f]+\.mov\.partial\.mov$/);
+
+  // PCM tones can't go in M4A either
+  const encoding = resolveEncoding(applyQualityProfile(settings));
+  const tone = { generator: normalizeGenerator({ type: 'tone' }), inMs: 0, outMs: 1000 };
+  const tonePath = await renderGenerator(tone, { width: 1280, height: 720, frameRate: '30/1' }, encoding, cache, null);
+  assert.match(tonePath, /\.wav$/);
+});
+
+test('clip effects are applied in the segment encode', { skip }, async () => {
+  const project = JSON.parse(loadProject('basic.project.json'));
+  project.clips.clip_1.effects = [{ type: 'noise', strength: 30 }, { type: 'sharpen' }];
+  const before = readMockCommands(commandLog).length;
+  await executeExportJob(buildPlan(JSON.stringify(project)), { ...SETTINGS, filename: 'grain' }, cache, null, null);
+
+  const segment = readMockCommands(commandLog).slice(before).find((args) => args.join(' ').includes('Shoot A.mp4'));
+  // Effects follow the conform so they work in the output frame
+  const conformEnd = segment.indexOf('fps=30/1');
+  assert.deepEqual(segment.slice(conformEnd + 1, conformEnd + 3), [
+    'noise=alls=30:allf=t+u',
+    'unsharp=luma_msize_x=5:luma_msize_y=5:luma_amount=1',
+  ]);
+});
+
+test('a clip crop runs before the clip is fitted to the frame', { skip }, async () => {
+  const project = JSON.parse(loadProject('basic.project.json'));
+  project.clips.clip_1.crop = { x: 0, y: 140, width: 1920, height: 800 };
+  const before = readMockCommands(commandLog).length;
+  await executeExportJob(buildPlan(JSON.stringify(project)), { ...SETTINGS, filename: 'cropped' }, cache, null, null);
+
+  const segment = readMockCommands(commandLog).slice(before).find((args) => args.join(' ').includes('Shoot A.mp4'));
+  const crop = segment.indexOf('crop=1920:800:0:140');
+  assert.ok(crop >= 0);
+  assert.ok(crop < segment.findIndex((arg) => arg.startsWith('scale=')));
+});
+
+test('adjacent clips are crossfaded at the cut', { skip }, async () => {
+  const project = JSON.parse(loadProject('basic.project.json'));
+  project.clips.clip_2 = { ...project.clips.clip_2, startMs: 2000, endMs: 4000 };
+  const before = readMockCommands(commandLog).length;
+  await executeExportJob(buildPlan(JSON.stringify(project)), { ...SETTINGS, filename: 'crossfaded' }, cache, null, null);
+
+  // Unprobeable sources can't lend handles, so the program passes through untouched
+  const commands = readMockCommands(commandLog).slice(before);
+  assert.ok(!commands.some((args) => args.join(' ').includes('acrossfade')));
+  assert.ok(fs.existsSync(path.join(cache.renders, 'crossfaded.mp4')));
+});
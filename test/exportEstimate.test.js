@@ -0,0 +1,52 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { buildPlan } = require('../electron/editPlan');
+const { historicalThroughput, estimateExport } = require('../electron/exportEstimate');
+const { estimateExportSize } = require('../electron/planStats');
+const { loadProject } = require('./helpers/harness');
+
+const exportJob = (settings, metrics, fields = {}) => ({ kind: 'export', status: 'completed', settings, metrics, ...fields });
+
+test('history averages recent exports made with the same encoder settings', () => {
+  const jobList = [
+    exportJob({ format: 'mp4', qualityProfile: 'standard' }, { realtime_factor: 2, output_bytes: 10e6, output_duration_ms: 10000 }),
+    exportJob({ format: 'mp4', qualityProfile: 'standard' }, { realtime_factor: 4, output_bytes: 30e6, output_duration_ms: 10000 }),
+    exportJob({ format: 'mp4', qualityProfile: 'master' }, { realtime_factor: 0.5, output_bytes: 90e6, output_duration_ms: 10000 }),
+    exportJob({ format: 'mp4', qualityProfile: 'standard' }, { realtime_factor: 9, output_bytes: 1, output_duration_ms: 1000 }, { clip_id: 'clip_a' }),
+    { ...exportJob({ format: 'mp4', qualityProfile: 'standard' }, null), status: 'failed' },
+  ];
+  assert.deepEqual(historicalThroughput(jobList, { format: 'mp4', qualityProfile: 'standard' }), {
+    realtime_factor: 3,
+    bytes_per_second: 2e6,
+    jobs: 2,
+  });
+  assert.equal(historicalThroughput(jobList, { format: 'mov', qualityProfile: 'standard' }), null);
+});
+
+test('estimates come from history, else fall back to the bitrate model', async () => {
+  const plan = buildPlan(loadProject('basic.project.json'));
+  const settings = { format: 'mp4', qualityProfile: 'standard' };
+  const jobList = [exportJob(settings, { realtime_factor: 2, output_bytes: 4e6, output_duration_ms: 2000 })];
+
+  const fromHistory = await estimateExport(plan, settings, { jobList });
+  assert.deepEqual(fromHistory, {
+    duration_ms: 5000,
+    estimated_render_ms: 2500,
+    estimated_size_bytes: 10e6,
+    realtime_factor: 2,
+    method: 'history',
+    jobs: 1,
+  });
+
+  // The fixture's sources don't exist, so the sample encode fails and only the size is known
+  const fallback = await estimateExport(plan, settings, {
+    method: 'sample',
+    jobList,
+    isImageFile: () => false,
+    resolveFormat: async () => ({ width: 1920, height: 1080, frameRate: '30/1' }),
+    samplePath: '/nonexistent/estimate.mp4',
+  });
+  assert.equal(fallback.method, 'bitrate');
+  assert.equal(fallback.estimated_render_ms, null);
+  assert.equal(fallback.estimated_size_bytes, estimateExportSize(5000, settings));
+});
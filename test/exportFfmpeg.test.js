@@ -0,0 +1,167 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const fs = require('fs');
+const path = require('path');
+const { createTempCache, makeFixtureClip } = require('./helpers/harness');
+const { checkDependencies } = require('../electron/ffmpeg');
+
+// End-to-end export against a real ffmpeg; skipped where none runs
+test('exports lavfi fixture clips with a gap to the expected duration', { timeout: 120000 }, async (t) => {
+  const deps = await checkDependencies();
+  if (!deps.ok) {
+    t.skip('ffmpeg/ffprobe not available');
+    return;
+  }
+  const ffmpegPath = deps.ffmpeg.path;
+
+  const { buildPlan } = require('../electron/editPlan');
+  const { executeExportJob } = require('../electron/export');
+  const { probeMedia } = require('../electron/metadata');
+  const { pathToFileUrl, urlToPath } = require('../electron/paths');
+
+  const { dir, cache } = createTempCache('starscape-e2e-');
+  await cache.ensureDirectories();
+  const clipA = makeFixtureClip(ffmpegPath, path.join(dir, 'a.mp4'), { seconds: 1 });
+  const clipB = makeFixtureClip(ffmpegPath, path.join(dir, 'b b.mp4'), { seconds: 1 });
+
+  const project = {
+    id: 'fixture_e2e',
+    assets: {
+      a: { id: 'a', src: pathToFileUrl(clipA), width: 160, height: 90 },
+      b: { id: 'b', src: pathToFileUrl(clipB), width: 160, height: 90 },
+    },
+    clips: {
+      c1: { id: 'c1', assetId: 'a', inMs: 0, outMs: 1000, startMs: 0, endMs: 1000 },
+      c2: { id: 'c2', assetId: 'b', inMs: 0, outMs: 1000, startMs: 1500, endMs: 2500 },
+    },
+    tracks: { main: { id: 'main', type: 'video', role: 'main', clipOrder: ['c1', 'c2'] } },
+  };
+  const plan = buildPlan(JSON.stringify(project));
+  const settings = { width: 160, height: 90, bitrate: 500, format: 'mp4', filename: 'e2e' };
+  const result = await executeExportJob(plan, settings, cache, null, null);
+
+  const outPath = urlToPath(result.path);
+  assert.ok(fs.statSync(outPath).size > 0);
+  const meta = await probeMedia(outPath);
+  assert.equal(meta.width, 160);
+  assert.ok(Math.abs(meta.duration_ms - 2500) < 200, `duration ${meta.duration_ms}ms`);
+});
+
+test('exports audio-only and video-only clips as a valid A/V file', { timeout: 120000 }, async (t) => {
+  const deps = await checkDependencies();
+  if (!deps.ok) {
+    t.skip('ffmpeg/ffprobe not available');
+    return;
+  }
+  const { buildPlan } = require('../electron/editPlan');
+  const { executeExportJob } = require('../electron/export');
+  const { probeMedia } = require('../electron/metadata');
+  const { pathToFileUrl, urlToPath } = require('../electron/paths');
+
+  const { dir, cache } = createTempCache('starscape-e2e-');
+  await cache.ensureDirectories();
+  const voice = makeFixtureClip(deps.ffmpeg.path, path.join(dir, 'voice.m4a'), { video: false });
+  const silent = makeFixtureClip(deps.ffmpeg.path, path.join(dir, 'silent.mp4'), { audio: false });
+
+  const project = {
+    id: 'fixture_streams',
+    assets: {
+      voice: { id: 'voice', src: pathToFileUrl(voice) },
+      silent: { id: 'silent', src: pathToFileUrl(silent), width: 160, height: 90 },
+    },
+    clips: {
+      c1: { id: 'c1', assetId: 'silent', inMs: 0, outMs: 1000, startMs: 0, endMs: 1000 },
+      c2: { id: 'c2', assetId: 'voice', inMs: 0, outMs: 1000, startMs: 1000, endMs: 2000 },
+    },
+    tracks: { main: { id: 'main', type: 'video', role: 'main', clipOrder: ['c1', 'c2'] } },
+  };
+  const settings = { width: 160, height: 90, bitrate: 500, format: 'mp4', filename: 'streams' };
+  const result = await executeExportJob(buildPlan(JSON.stringify(project)), settings, cache, null, null);
+
+  const meta = await probeMedia(urlToPath(result.path));
+  assert.equal(meta.width, 160);
+  assert.equal(meta.has_audio, true);
+  assert.ok(Math.abs(meta.duration_ms - 2000) < 200, `duration ${meta.duration_ms}ms`);
+});
+
+test('conforms mixed sample rates and channel layouts before concat', { timeout: 120000 }, async (t) => {
+  const deps = await checkDependencies();
+  if (!deps.ok) {
+    t.skip('ffmpeg/ffprobe not available');
+    return;
+  }
+  const { buildPlan } = require('../electron/editPlan');
+  const { executeExportJob } = require('../electron/export');
+  const { probeMedia } = require('../electron/metadata');
+  const { pathToFileUrl, urlToPath } = require('../electron/paths');
+
+  const { dir, cache } = createTempCache('starscape-e2e-');
+  await cache.ensureDirectories();
+  const cd = makeFixtureClip(deps.ffmpeg.path, path.join(dir, 'cd.mp4'), { sampleRate: 44100, channels: 2 });
+  const mono = makeFixtureClip(deps.ffmpeg.path, path.join(dir, 'mono.mp4'), { sampleRate: 48000, channels: 1 });
+
+  const project = {
+    id: 'fixture_conform',
+    assets: {
+      cd: { id: 'cd', src: pathToFileUrl(cd), width: 160, height: 90 },
+      mono: { id: 'mono', src: pathToFileUrl(mono), width: 160, height: 90 },
+    },
+    clips: {
+      c1: { id: 'c1', assetId: 'cd', inMs: 0, outMs: 1000, startMs: 0, endMs: 1000 },
+      c2: { id: 'c2', assetId: 'mono', inMs: 0, outMs: 1000, startMs: 1000, endMs: 2000 },
+    },
+    tracks: { main: { id: 'main', type: 'video', role: 'main', clipOrder: ['c1', 'c2'] } },
+  };
+  // Source resolution so clips would otherwise be stream copied
+  const settings = { width: -1, height: -1, bitrate: 500, format: 'mp4', filename: 'conform' };
+  const result = await executeExportJob(buildPlan(JSON.stringify(project)), settings, cache, null, null);
+
+  const meta = await probeMedia(urlToPath(result.path));
+  assert.equal(meta.sample_rate, 48000);
+  assert.equal(meta.channels, 2);
+  assert.ok(Math.abs(meta.duration_ms - 2000) < 200, `duration ${meta.duration_ms}ms`);
+});
+
+test('fits mixed sizes and rates to the export frame, inheriting unset fields from the first clip', { timeout: 120000 }, async (t) => {
+  const deps = await checkDependencies();
+  if (!deps.ok) {
+    t.skip('ffmpeg/ffprobe not available');
+    return;
+  }
+  const { buildPlan } = require('../electron/editPlan');
+  const { executeExportJob } = require('../electron/export');
+  const { probeMedia } = require('../electron/metadata');
+  const { pathToFileUrl, urlToPath } = require('../electron/paths');
+
+  const { dir, cache } = createTempCache('starscape-e2e-');
+  await cache.ensureDirectories();
+  const wide = makeFixtureClip(deps.ffmpeg.path, path.join(dir, 'wide.mp4'), { size: '160x90', rate: 25 });
+  const square = makeFixtureClip(deps.ffmpeg.path, path.join(dir, 'square.mp4'), { size: '120x120', rate: 30 });
+
+  const project = {
+    id: 'fixture_frame',
+    assets: {
+      wide: { id: 'wide', src: pathToFileUrl(wide), width: 160, height: 90 },
+      square: { id: 'square', src: pathToFileUrl(square), width: 120, height: 120 },
+    },
+    clips: {
+      c1: { id: 'c1', assetId: 'wide', inMs: 0, outMs: 1000, startMs: 0, endMs: 1000 },
+      c2: { id: 'c2', assetId: 'square', inMs: 0, outMs: 1000, startMs: 1000, endMs: 2000 },
+    },
+    tracks: { main: { id: 'main', type: 'video', role: 'main', clipOrder: ['c1', 'c2'] } },
+  };
+  const plan = buildPlan(JSON.stringify(project));
+
+  const inherited = await executeExportJob(plan, { bitrate: 500, format: 'mp4', filename: 'inherited' }, cache, null, null);
+  const inheritedMeta = await probeMedia(urlToPath(inherited.path));
+  assert.equal(inheritedMeta.width, 160);
+  assert.equal(inheritedMeta.height, 90);
+  assert.equal(inheritedMeta.frame_rate, '25/1');
+
+  const explicit = await executeExportJob(plan, { width: 320, height: 180, fps: 24, bitrate: 500, format: 'mp4', filename: 'explicit' }, cache, null, null);
+  const explicitMeta = await probeMedia(urlToPath(explicit.path));
+  assert.equal(explicitMeta.width, 320);
+  assert.equal(explicitMeta.height, 180);
+  assert.equal(explicitMeta.frame_rate, '24/1');
+  assert.ok(Math.abs(explicitMeta.duration_ms - 2000) < 200, `duration ${explicitMeta.duration_ms}ms`);
+});
@@ -0,0 +1,80 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const fs = require('fs-extra');
+const os = require('os');
+const path = require('path');
+const exportHooks = require('../electron/exportHooks');
+
+test('hook actions are checked and stored per preset', async () => {
+  const dir = await fs.mkdtemp(path.join(os.tmpdir(), 'export-hooks-'));
+  const storePath = path.join(dir, 'export-hooks.json');
+
+  try {
+    exportHooks.init(storePath);
+    assert.deepEqual(exportHooks.hooksForPreset('master'), []);
+    assert.throws(() => exportHooks.setExportHooks('master', [{ type: 'upload' }]), { code: 'invalid_argument' });
+    assert.throws(() => exportHooks.setExportHooks('master', [{ type: 'copy', destination: 'relative/dir' }]), { code: 'invalid_argument' });
+
+    const saved = exportHooks.setExportHooks('master', [{ type: 'copy', destination: dir, extra: true }, { type: 'reveal' }]);
+    assert.deepEqual(saved, [{ type: 'copy', destination: dir }, { type: 'reveal' }]);
+
+    // Exports without a profile use the custom hooks; an empty list removes a preset's hooks
+    exportHooks.setExportHooks('custom', [{ type: 'reveal' }]);
+    assert.equal(exportHooks.hooksForPreset(undefined).length, 1);
+    exportHooks.setExportHooks('custom', []);
+
+    exportHooks.init(storePath);
+    assert.deepEqual(Object.keys(exportHooks.getExportHooks()), ['master']);
+
+    // Programs and destinations go through the target check
+    const onlyDir = (value) => {
+      if (value !== dir) throw Object.assign(new Error('not granted'), { code: 'path_not_allowed' });
+      return value;
+    };
+    assert.throws(() => exportHooks.setExportHooks('master', [{ type: 'command', command: '/bin/sh' }], onlyDir), { code: 'path_not_allowed' });
+    assert.throws(() => exportHooks.setExportHooks('master', [{ type: 'move', destination: '/tmp' }], onlyDir), { code: 'path_not_allowed' });
+    assert.deepEqual(exportHooks.setExportHooks('master', [{ type: 'move', destination: dir }], onlyDir), [{ type: 'move', destination: dir }]);
+  } finally {
+    await fs.remove(dir);
+  }
+});
+
+test('hooks run in order on the current output and failures are reported', async () => {
+  const dir = await fs.mkdtemp(path.join(os.tmpdir(), 'export-hooks-run-'));
+  const output = path.join(dir, 'renders', 'cut.mp4');
+  const nas = path.join(dir, 'nas');
+  const delivered = path.join(dir, 'delivered');
+
+  try {
+    await fs.outputFile(output, 'video');
+    await fs.outputFile(path.join(nas, 'cut.mp4'), 'older');
+    const revealed = [];
+    const { path: finalPath, results } = await exportHooks.runExportHooks(output, [
+      { type: 'copy', destination: nas },
+      { type: 'move', destination: delivered },
+      { type: 'command', command: process.execPath, args: ['-e', 'require("fs").writeFileSync(process.argv[1] + ".done", "")'] },
+      { type: 'command', command: path.join(dir, 'missing-script'), args: [] },
+      { type: 'reveal' },
+    ], { reveal: (filePath) => revealed.push(filePath) });
+
+    assert.equal(finalPath, path.join(delivered, 'cut.mp4'));
+    assert.deepEqual(results.map((r) => [r.type, r.success]), [
+      ['copy', true], ['move', true], ['command', true], ['command', false], ['reveal', true],
+    ]);
+    // An existing copy is kept; the new one is numbered
+    assert.equal(results[0].path, path.join(nas, 'cut_2.mp4'));
+    assert.equal(await fs.readFile(path.join(nas, 'cut.mp4'), 'utf8'), 'older');
+    assert.ok(await fs.pathExists(`${finalPath}.done`));
+    assert.ok(!(await fs.pathExists(output)));
+    assert.match(results[3].error, /ENOENT/);
+    assert.deepEqual(revealed, [finalPath]);
+
+    // Stored actions whose target no longer passes the check are reported, not run
+    const { results: denied } = await exportHooks.runExportHooks(finalPath, [{ type: 'copy', destination: nas }], {
+      checkTarget: () => { throw new Error('not granted'); },
+    });
+    assert.deepEqual(denied.map((r) => [r.success, r.error]), [[false, 'not granted']]);
+  } finally {
+    await fs.remove(dir);
+  }
+});
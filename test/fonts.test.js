@@ -0,0 +1,81 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const fs = require('fs');
+const path = require('path');
+const { createTempCache } = require('./helpers/harness');
+const { readFontFile, resolveFont } = require('../electron/fonts');
+
+/**
+ * Minimal sfnt face: an offset table with just a name table holding Windows English records
+ */
+function fontFace(names, faceOffset = 0) {
+  const strings = Object.entries(names).map(([id, value]) => {
+    const buf = Buffer.from(value, 'utf16le');
+    buf.swap16();
+    return { id: Number(id), buf };
+  });
+  const header = Buffer.alloc(6 + strings.length * 12);
+  header.writeUInt16BE(0, 0);
+  header.writeUInt16BE(strings.length, 2);
+  header.writeUInt16BE(header.length, 4);
+  let stringPos = 0;
+  strings.forEach(({ id, buf }, i) => {
+    const record = 6 + i * 12;
+    header.writeUInt16BE(3, record);
+    header.writeUInt16BE(1, record + 2);
+    header.writeUInt16BE(0x409, record + 4);
+    header.writeUInt16BE(id, record + 6);
+    header.writeUInt16BE(buf.length, record + 8);
+    header.writeUInt16BE(stringPos, record + 10);
+    stringPos += buf.length;
+  });
+  const nameTable = Buffer.concat([header, ...strings.map((s) => s.buf)]);
+
+  const offsetTable = Buffer.alloc(12 + 16);
+  offsetTable.writeUInt32BE(0x00010000, 0);
+  offsetTable.writeUInt16BE(1, 4);
+  offsetTable.write('name', 12, 'latin1');
+  // Table offsets are from the start of the file
+  offsetTable.writeUInt32BE(faceOffset + offsetTable.length, 20);
+  offsetTable.writeUInt32BE(nameTable.length, 24);
+  return Buffer.concat([offsetTable, nameTable]);
+}
+
+const { dir } = createTempCache('starscape-fonts-');
+
+test('reads family and style from a font name table', async () => {
+  const fontPath = path.join(dir, 'Brand-Bold.ttf');
+  fs.writeFileSync(fontPath, fontFace({ 1: 'Brand', 2: 'Bold' }));
+  assert.deepEqual(await readFontFile(fontPath), [{ family: 'Brand', style: 'Bold', path: fontPath, index: 0 }]);
+});
+
+test('typographic names win and collections list every face', async () => {
+  const header = Buffer.alloc(20);
+  header.write('ttcf', 0, 'latin1');
+  header.writeUInt32BE(0x00010000, 4);
+  header.writeUInt32BE(2, 8);
+  const first = fontFace({ 1: 'Brand Light', 2: 'Regular', 16: 'Brand', 17: 'Light' }, header.length);
+  header.writeUInt32BE(header.length, 12);
+  header.writeUInt32BE(header.length + first.length, 16);
+  const second = fontFace({ 1: 'Brand', 2: 'Italic' }, header.length + first.length);
+
+  const fontPath = path.join(dir, 'Brand.ttc');
+  fs.writeFileSync(fontPath, Buffer.concat([header, first, second]));
+  assert.deepEqual(await readFontFile(fontPath), [
+    { family: 'Brand', style: 'Light', path: fontPath, index: 0 },
+    { family: 'Brand', style: 'Italic', path: fontPath, index: 1 },
+  ]);
+});
+
+test('resolveFont matches family names case-insensitively and picks the closest style', async () => {
+  const fonts = [
+    { family: 'Brand', style: 'Bold Italic', path: '/fonts/Brand-BoldItalic.ttf', index: 0 },
+    { family: 'Brand', style: 'Bold', path: '/fonts/Brand-Bold.ttf', index: 0 },
+    { family: 'Brand', style: 'Regular', path: '/fonts/Brand-Regular.ttf', index: 0 },
+    { family: 'Brand', style: 'Italic', path: '/fonts/Brand-Italic.ttf', index: 0 },
+  ];
+  assert.equal((await resolveFont('brand', {}, fonts)).path, '/fonts/Brand-Regular.ttf');
+  assert.equal((await resolveFont('Brand', { bold: true }, fonts)).path, '/fonts/Brand-Bold.ttf');
+  assert.equal((await resolveFont('Brand', { bold: true, italic: true }, fonts)).path, '/fonts/Brand-BoldItalic.ttf');
+  assert.equal(await resolveFont('Missing', {}, fonts), null);
+});
@@ -0,0 +1,80 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { buildPlan } = require('../electron/editPlan');
+const { normalizeGenerator, generatorSource, buildCountdownScript, buildCreditsScript } = require('../electron/generators');
+
+const FORMAT = { width: 1280, height: 720, frameRate: '30000/1001' };
+
+test('generators are validated and filled with defaults', () => {
+  assert.deepEqual(normalizeGenerator({ type: 'solid', color: 'ff0000' }), { type: 'solid', color: '#FF0000' });
+  assert.deepEqual(normalizeGenerator({ type: 'tone', frequency: 5, volumeDb: 6 }), { type: 'tone', frequency: 20, volumeDb: 0 });
+  assert.equal(normalizeGenerator({ type: 'testPattern', pattern: 'nope' }).pattern, 'smpte');
+  assert.throws(() => normalizeGenerator({ type: 'plasma' }), /Unknown generator type: plasma/);
+});
+
+test('lavfi sources carry the output frame and duration', () => {
+  assert.equal(
+    generatorSource(normalizeGenerator({ type: 'solid', color: '#102030' }), FORMAT, 5),
+    'color=c=0x102030:s=1280x720:r=30000/1001:d=5'
+  );
+  assert.equal(
+    generatorSource(normalizeGenerator({ type: 'gradient', colors: ['#000000', '#FFFFFF'], angle: 0 }), FORMAT, 2),
+    'gradients=s=1280x720:r=30000/1001:d=2:c0=0x000000:c1=0xFFFFFF:x0=0:y0=360:x1=1280:y1=360:nb_colors=2:speed=0'
+  );
+  assert.equal(generatorSource(normalizeGenerator({ type: 'testPattern' }), FORMAT, 1), 'smptehdbars=s=1280x720:r=30000/1001:d=1');
+  assert.equal(generatorSource(normalizeGenerator({ type: 'tone' }), FORMAT, 1), 'sine=frequency=1000:sample_rate=48000:duration=1');
+});
+
+test('countdown shows the whole seconds left', () => {
+  const events = buildCountdownScript(normalizeGenerator({ type: 'countdown' }), FORMAT, 2500)
+    .split('\n')
+    .filter((line) => line.startsWith('Dialogue:'));
+  assert.deepEqual(events.map((line) => line.split(',').slice(1, 3).concat(line.split('}').pop())), [
+    ['0:00:00.00', '0:00:00.50', '3'],
+    ['0:00:00.50', '0:00:01.50', '2'],
+    ['0:00:01.50', '0:00:02.50', '1'],
+  ]);
+});
+
+test('buildPlan accepts generator assets', () => {
+  const plan = buildPlan(JSON.stringify({
+    id: 'gen',
+    assets: { bars: { id: 'bars', kind: 'generator', generator: { type: 'testPattern', pattern: 'testsrc' } } },
+    clips: { c: { id: 'c', assetId: 'bars', inMs: 0, outMs: 2000, startMs: 0, endMs: 2000 } },
+    tracks: { main: { id: 'main', type: 'video', role: 'main', clipOrder: ['c'] } },
+  }));
+  assert.equal(plan.mainTrack[0].srcPath, null);
+  assert.deepEqual(plan.mainTrack[0].generator, { type: 'testPattern', pattern: 'testsrc' });
+
+  assert.throws(() => buildPlan(JSON.stringify({
+    id: 'gen',
+    assets: { x: { id: 'x', kind: 'generator', generator: { type: 'noise' } } },
+    clips: { c: { id: 'c', assetId: 'x', inMs: 0, outMs: 1000, startMs: 0, endMs: 1000 } },
+    tracks: { main: { id: 'main', type: 'video', role: 'main', clipOrder: ['c'] } },
+  })), /Asset x: Unknown generator type: noise/);
+});
+
+test('credits roll up from below the frame at a steady speed', () => {
+  const generator = normalizeGenerator({
+    type: 'credits',
+    blocks: [{ heading: 'Directed by', lines: ['Ada {Lovelace}', ' '] }, { heading: ' ', lines: [] }, { lines: ['Thanks for watching'] }],
+  });
+  assert.deepEqual(generator.blocks, [
+    { heading: 'Directed by', lines: ['Ada {Lovelace}'] },
+    { heading: '', lines: ['Thanks for watching'] },
+  ]);
+  assert.equal(generator.speed, null);
+  assert.throws(() => normalizeGenerator({ type: 'credits', blocks: [{ lines: [''] }] }), /at least one/);
+
+  // Unset speed scrolls the frame height plus the roll (151.5 px at 720p) through the clip
+  const events = buildCreditsScript(generator, FORMAT, 10000).split('\n').filter((line) => line.startsWith('Dialogue:'));
+  assert.deepEqual(events.map((line) => line.split(',,').pop()), [
+    '{\\move(640,720,640,-151)}Directed by',
+    '{\\move(640,764,640,-108)}Ada \\{Lovelace\\}',
+    '{\\move(640,836,640,-36)}Thanks for watching',
+  ]);
+
+  // A fixed speed is scaled to the output height (120 px/s at 1080p is 80 px/s at 720p)
+  const fixed = buildCreditsScript(normalizeGenerator({ type: 'credits', speed: 120, blocks: [{ lines: ['End'] }] }), FORMAT, 5000);
+  assert.match(fixed, /\\move\(640,720,640,320\)\}End/);
+});
@@ -0,0 +1,43 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { canSmartRender, planClipHandles } = require('../electron/handles');
+const { resolveEncoding } = require('../electron/encoding');
+
+const H264 = {
+  codec_video: 'h264', video_profile: 'High', pix_fmt: 'yuv420p', bit_depth: 8, has_audio: true, codec_audio: 'aac', sample_rate: 48000, channels: 2,
+};
+
+test('trims split at the keyframes inside the range', () => {
+  const keyframes = [0, 2000, 4000, 6000, 8000, 10000];
+  assert.deepEqual(planClipHandles(1500, 9200, keyframes), { headEndMs: 2000, tailStartMs: 8000 });
+  // Cuts on keyframes need no handle on that side
+  assert.deepEqual(planClipHandles(2000, 8000, keyframes), { headEndMs: 2000, tailStartMs: 8000 });
+  // Too little between the keyframes to be worth copying
+  assert.equal(planClipHandles(2500, 5900, keyframes), null);
+  assert.equal(planClipHandles(10500, 12000, keyframes), null);
+});
+
+test('smart rendering needs the encoder to produce the source codec', () => {
+  assert.equal(canSmartRender(H264, resolveEncoding()), true);
+  assert.equal(canSmartRender(H264, resolveEncoding({ videoCodec: 'h264_videotoolbox' })), true);
+  assert.equal(canSmartRender(H264, resolveEncoding({ videoCodec: 'libx265' })), false);
+  assert.equal(canSmartRender({ ...H264, codec_video: 'prores' }, resolveEncoding()), false);
+  assert.equal(canSmartRender({ ...H264, sample_rate: 44100 }, resolveEncoding()), false);
+  assert.equal(canSmartRender({ ...H264, codec_audio: 'opus' }, resolveEncoding()), false);
+  assert.equal(canSmartRender(null, resolveEncoding()), false);
+});
+
+test('smart rendering needs the source pixel format, bit depth and profile', () => {
+  const tenBit422 = { ...H264, video_profile: 'High 4:2:2', pix_fmt: 'yuv422p10le', bit_depth: 10 };
+  assert.equal(canSmartRender(tenBit422, resolveEncoding()), false);
+  assert.equal(canSmartRender(tenBit422, resolveEncoding({ pixFmt: 'yuv422p10le' })), true);
+  assert.equal(canSmartRender(tenBit422, resolveEncoding({ pixFmt: 'yuv422p10le', videoCodec: 'h264_videotoolbox' })), false);
+  assert.equal(canSmartRender({ ...H264, bit_depth: 10 }, resolveEncoding()), false);
+  // Unknown pixel format or profile can't be matched
+  assert.equal(canSmartRender({ ...H264, pix_fmt: null }, resolveEncoding()), false);
+  assert.equal(canSmartRender({ ...H264, video_profile: null }, resolveEncoding()), false);
+  // An explicit export profile has to be the source's
+  assert.equal(canSmartRender(H264, resolveEncoding({ profile: 'high' })), true);
+  assert.equal(canSmartRender({ ...H264, video_profile: 'Main' }, resolveEncoding({ profile: 'high' })), false);
+  assert.equal(canSmartRender({ ...H264, video_profile: 'Constrained Baseline' }, resolveEncoding()), true);
+});
@@ -0,0 +1,34 @@
+const assert = require('node:assert/strict');
+const fs = require('fs');
+const path = require('path');
+
+const GOLDEN_DIR = path.join(__dirname, '..', 'golden');
+
+/**
+ * Compare against test/golden/<name>; UPDATE_GOLDEN=1 writes it instead (a missing golden fails)
+ */
+function assertGolden(name, actual) {
+  const goldenPath = path.join(GOLDEN_DIR, name);
+  const text = typeof actual === 'string' ? actual : `${JSON.stringify(actual, null, 2)}\n`;
+  if (process.env.UPDATE_GOLDEN) {
+    fs.mkdirSync(GOLDEN_DIR, { recursive: true });
+    fs.writeFileSync(goldenPath, text);
+    return;
+  }
+  assert.ok(fs.existsSync(goldenPath), `${name} has no golden file (run with UPDATE_GOLDEN=1 to record it)`);
+  assert.equal(text, fs.readFileSync(goldenPath, 'utf8'), `${name} differs from golden (rerun with UPDATE_GOLDEN=1 if intended)`);
+}
+
+/**
+ * Replace a temp directory with a stable placeholder, recursively
+ */
+function normalizePaths(value, dir) {
+  if (typeof value === 'string') return value.split(dir).join('<tmp>');
+  if (Array.isArray(value)) return value.map((item) => normalizePaths(item, dir));
+  if (value && typeof value === 'object') {
+    return Object.fromEntries(Object.entries(value).map(([key, item]) => [key, normalizePaths(item, dir)]));
+  }
+  return value;
+}
+
+module.exports = { assertGolden, normalizePaths };
@@ -0,0 +1,74 @@
+const fs = require('fs');
+const os = require('os');
+const path = require('path');
+const { execFileSync } = require('child_process');
+const { CacheDirs } = require('../../electron/cache');
+
+const FIXTURES_DIR = path.join(__dirname, '..', 'fixtures');
+const MOCK_FFMPEG = path.join(__dirname, 'mock-ffmpeg.js');
+
+/**
+ * Temporary app data directory with a CacheDirs rooted in it
+ */
+function createTempCache(prefix = 'starscape-test-') {
+  const dir = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), prefix)));
+  const cache = new CacheDirs({ getPath: () => dir });
+  return { dir, cache };
+}
+
+/**
+ * Read a fixture project as the JSON string buildPlan expects
+ */
+function loadProject(name) {
+  return fs.readFileSync(path.join(FIXTURES_DIR, name), 'utf8');
+}
+
+/**
+ * Point the app at the mock ffmpeg; must run before electron/ modules are required
+ * @returns {string} Path of the file the mock appends each command's argv to
+ */
+function useMockFfmpeg(dir) {
+  const logPath = path.join(dir, 'ffmpeg-commands.jsonl');
+  process.env.STARSCAPE_FFMPEG_PATH = MOCK_FFMPEG;
+  process.env.MOCK_FFMPEG_LOG = logPath;
+  return logPath;
+}
+
+/**
+ * Commands the mock ffmpeg received, oldest first
+ */
+function readMockCommands(logPath) {
+  if (!fs.existsSync(logPath)) return [];
+  return fs.readFileSync(logPath, 'utf8').split('\n').filter(Boolean).map((line) => JSON.parse(line));
+}
+
+/**
+ * Render a tiny test clip with lavfi sources (needs a real ffmpeg)
+ */
+function makeFixtureClip(ffmpegPath, outputPath, { seconds = 1, size = '160x90', rate = 30, video = true, audio = true, sampleRate = 48000, channels = 1 } = {}) {
+  const args = ['-hide_banner', '-loglevel', 'error', '-y'];
+  if (video) {
+    args.push('-f', 'lavfi', '-i', `testsrc2=size=${size}:rate=${rate}:duration=${seconds}`);
+  }
+  if (audio) {
+    args.push('-f', 'lavfi', '-i', `sine=frequency=440:sample_rate=${sampleRate}:duration=${seconds}`);
+  }
+  if (video) {
+    args.push('-c:v', 'libx264', '-preset', 'ultrafast', '-pix_fmt', 'yuv420p');
+  }
+  if (audio) {
+    args.push('-c:a', 'aac', '-ac', String(channels));
+  }
+  execFileSync(ffmpegPath, [...args, '-shortest', outputPath]);
+  return outputPath;
+}
+
+module.exports = {
+  FIXTURES_DIR,
+  MOCK_FFMPEG,
+  createTempCache,
+  loadProject,
+  useMockFfmpeg,
+  readMockCommands,
+  makeFixtureClip,
+};
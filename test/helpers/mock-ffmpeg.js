@@ -0,0 +1,92 @@
+#!/usr/bin/env node
+/**
+ * Stand-in ffmpeg for tests: answers capability queries with a fixed build,
+ * logs every render command to $MOCK_FFMPEG_LOG and writes a stub output file.
+ */
+const fs = require('fs');
+const path = require('path');
+
+const LISTINGS = {
+  '-version': [
+    'ffmpeg version 6.1-mock Copyright (c) 2000-2023 the FFmpeg developers',
+    'configuration: --enable-gpl --enable-libx264',
+    'libavutil      58. 29.100 / 58. 29.100',
+    'libavcodec     60. 31.102 / 60. 31.102',
+    'libavformat    60. 16.100 / 60. 16.100',
+    'libavfilter     9. 12.100 /  9. 12.100',
+  ],
+  '-formats': [
+    'File formats:',
+    ' D. = Demuxing supported',
+    ' .E = Muxing supported',
+    ' --',
+    ' D  concat          Virtual concatenation script',
+    ' D  lavfi           Libavfilter virtual input device',
+    '  E mov             QuickTime / MOV',
+    ' DE mov,mp4,m4a,3gp,3g2,mj2 QuickTime / MOV',
+    '  E mp4             MP4 (MPEG-4 Part 14)',
+    ' DE image2          image2 sequence',
+    ' DE wav             WAV / WAVE (Waveform Audio)',
+  ],
+  '-codecs': [
+    'Codecs:',
+    ' -------',
+    ' DEV.LS h264                 H.264 / AVC / MPEG-4 AVC / MPEG-4 part 10 (encoders: libx264 )',
+    ' DEV.L. prores               Apple ProRes (iCodec Pro) (encoders: prores prores_aw prores_ks )',
+    ' DEA.L. aac                  AAC (Advanced Audio Coding)',
+    ' DEA..S pcm_s16le            PCM signed 16-bit little-endian',
+    ' DEV.L. png                  PNG (Portable Network Graphics) image',
+  ],
+  '-encoders': [
+    'Encoders:',
+    ' V..... = Video',
+    ' A..... = Audio',
+    ' ------',
+    ' V....D libx264              libx264 H.264 / AVC / MPEG-4 AVC / MPEG-4 part 10 (codec h264)',
+    ' V....D png                  PNG (Portable Network Graphics) image',
+    ' VF...D prores_ks            Apple ProRes (iCodec Pro) (codec prores)',
+    ' A....D aac                  AAC (Advanced Audio Coding)',
+    ' A..... pcm_s16le            PCM signed 16-bit little-endian',
+  ],
+  '-decoders': [
+    'Decoders:',
+    ' V..... = Video',
+    ' A..... = Audio',
+    ' ------',
+    ' VFS..D h264                 H.264 / AVC / MPEG-4 AVC / MPEG-4 part 10',
+    ' VF...D png                  PNG (Portable Network Graphics) image',
+    ' A....D aac                  AAC (Advanced Audio Coding)',
+  ],
+  '-filters': [
+    'Filters:',
+    '  T.. = Timeline support',
+    '  .S. = Slice threading',
+    '  ..C = Command support',
+    ...['scale', 'pad', 'crop', 'trim', 'tpad', 'setpts', 'overlay', 'rotate', 'format', 'blend', 'drawtext',
+      'colorchannelmixer', 'eq', 'gblur', 'ssim', 'setsar', 'fps', 'ass', 'color', 'gradients', 'smptehdbars', 'testsrc2',
+      'noise', 'vignette', 'unsharp'].map((name) => ` ..C ${name.padEnd(20)} V->V       ${name}`),
+    ...['amix', 'adelay', 'atrim', 'asetpts', 'volume', 'highpass', 'lowpass', 'loudnorm', 'aresample', 'aformat', 'sine',
+      'asplit', 'concat', 'acrossfade'].map(
+      (name) => ` ..C ${name.padEnd(20)} A->A       ${name}`
+    ),
+  ],
+  '-hwaccels': ['Hardware acceleration methods:', ''],
+};
+
+const args = process.argv.slice(2);
+const query = args.find((arg) => LISTINGS[arg]);
+if (query) {
+  process.stdout.write(`${LISTINGS[query].join('\n')}\n`);
+  process.exit(0);
+}
+
+if (process.env.MOCK_FFMPEG_LOG) {
+  fs.appendFileSync(process.env.MOCK_FFMPEG_LOG, `${JSON.stringify(args)}\n`);
+}
+
+const output = args[args.length - 1];
+if (output && !output.startsWith('-') && args.includes('-i')) {
+  fs.mkdirSync(path.dirname(output), { recursive: true });
+  fs.writeFileSync(output, 'mock ffmpeg output\n');
+}
+process.exit(0);
@@ -0,0 +1,80 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const fs = require('fs-extra');
+const os = require('os');
+const path = require('path');
+const hotkeys = require('../electron/hotkeys');
+
+function fakeShortcuts(taken = []) {
+  const held = new Map();
+  return {
+    held,
+    register(accelerator, callback) {
+      if (taken.includes(accelerator) || held.has(accelerator)) return false;
+      held.set(accelerator, callback);
+      return true;
+    },
+    unregister(accelerator) {
+      held.delete(accelerator);
+    },
+  };
+}
+
+test('accelerators are validated and canonicalized', () => {
+  assert.equal(hotkeys.normalizeAccelerator('ctrl+shift+r'), 'Control+Shift+R');
+  assert.equal(hotkeys.normalizeAccelerator('CmdOrCtrl + Alt + f9'), 'CommandOrControl+Alt+F9');
+  assert.equal(hotkeys.normalizeAccelerator('Shift++'), 'Shift+Plus');
+  assert.equal(hotkeys.normalizeAccelerator('F12'), 'F12');
+  // A bare letter would swallow that key system-wide
+  assert.equal(hotkeys.normalizeAccelerator('R'), null);
+  assert.equal(hotkeys.normalizeAccelerator('Ctrl+Ctrl+R'), null);
+  assert.equal(hotkeys.normalizeAccelerator('Hyper+R'), null);
+  assert.equal(hotkeys.normalizeAccelerator('Ctrl+Shift'), null);
+});
+
+test('bindings reject unknown actions and duplicate shortcuts', () => {
+  const current = { ...hotkeys.DEFAULT_HOTKEYS };
+  assert.throws(() => hotkeys.mergeBindings(current, { 'open-editor': 'Ctrl+E' }), { code: 'invalid_argument' });
+  assert.throws(
+    () => hotkeys.mergeBindings(current, { 'stop-recording': current['start-recording'] }),
+    { code: 'invalid_argument' }
+  );
+  const merged = hotkeys.mergeBindings(current, { 'capture-screenshot': null, 'start-recording': 'alt+shift+1' });
+  assert.equal(merged['capture-screenshot'], null);
+  assert.equal(merged['start-recording'], 'Alt+Shift+1');
+});
+
+test('hotkeys register, fire their action and persist across restarts', async () => {
+  const dir = await fs.mkdtemp(path.join(os.tmpdir(), 'hotkeys-'));
+  const configPath = path.join(dir, 'hotkeys.json');
+  const fired = [];
+  const handlers = Object.fromEntries(hotkeys.HOTKEY_ACTIONS.map((action) => [action, () => fired.push(action)]));
+
+  try {
+    const shortcuts = fakeShortcuts([hotkeys.DEFAULT_HOTKEYS['capture-screenshot']]);
+    hotkeys.init(configPath, shortcuts, handlers);
+    const initial = hotkeys.getHotkeys();
+    assert.equal(initial.find((h) => h.action === 'start-recording').registered, true);
+    // Held by another app
+    assert.equal(initial.find((h) => h.action === 'capture-screenshot').registered, false);
+
+    shortcuts.held.get(hotkeys.DEFAULT_HOTKEYS['pause-recording'])();
+    assert.deepEqual(fired, ['pause-recording']);
+
+    hotkeys.setHotkeys({ 'start-recording': 'Ctrl+Alt+R', 'stop-recording': null });
+    assert.equal(shortcuts.held.has(hotkeys.DEFAULT_HOTKEYS['start-recording']), false);
+    assert.equal(shortcuts.held.has('Control+Alt+R'), true);
+    assert.equal(shortcuts.held.has(hotkeys.DEFAULT_HOTKEYS['stop-recording']), false);
+
+    const restarted = fakeShortcuts();
+    hotkeys.init(configPath, restarted, handlers);
+    const reloaded = Object.fromEntries(hotkeys.getHotkeys().map((h) => [h.action, h.accelerator]));
+    assert.equal(reloaded['start-recording'], 'Control+Alt+R');
+    assert.equal(reloaded['stop-recording'], null);
+
+    hotkeys.unregisterAll();
+    assert.equal(restarted.held.size, 0);
+  } finally {
+    await fs.remove(dir);
+  }
+});
@@ -0,0 +1,43 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { normalizeIngestRule, planIngestTranscode, ingestTranscodeOptions } = require('../electron/ingestRules');
+
+const rules = [
+  { type: 'video_codec', from: ['hevc'], to: 'h264' },
+  { type: 'max_resolution', width: 3840, height: 2160 },
+  { type: 'audio_sample_rate', rate: 48000, extensions: ['wav'] },
+].map(normalizeIngestRule);
+
+test('rules are checked when saved', () => {
+  assert.deepEqual(normalizeIngestRule({ type: 'video_codec', from: 'HEVC', to: 'h264', extra: 1 }), { type: 'video_codec', from: ['hevc'], to: 'h264' });
+  assert.deepEqual(normalizeIngestRule({ type: 'audio_sample_rate', rate: 48000, extensions: ['.WAV'] }).extensions, ['wav']);
+  assert.throws(() => normalizeIngestRule({ type: 'video_codec', from: ['hevc'], to: 'vp9' }), { code: 'invalid_argument' });
+  assert.throws(() => normalizeIngestRule({ type: 'max_resolution', width: 3840 }), { code: 'invalid_argument' });
+  assert.throws(() => normalizeIngestRule({ type: 'denoise' }), { code: 'invalid_argument' });
+});
+
+test('matching rules combine into one conversion per file', () => {
+  const hevc8k = planIngestTranscode('video', '/in/drone.mov', { codec_video: 'hevc', width: 7680, height: 4320, sample_rate: 48000 }, rules);
+  assert.deepEqual(hevc8k, {
+    videoCodec: 'libx264',
+    width: 3840,
+    height: 2160,
+    sampleRate: null,
+    ext: 'mp4',
+    reasons: ['hevc to h264', '7680x4320 to 3840x2160'],
+  });
+
+  // Scaling alone still re-encodes; an H.264 4K file is left alone
+  assert.equal(planIngestTranscode('video', '/in/a.mp4', { codec_video: 'h264', width: 5120, height: 2880 }, rules).videoCodec, 'libx264');
+  assert.equal(planIngestTranscode('video', '/in/a.mp4', { codec_video: 'h264', width: 3840, height: 2160 }, rules), null);
+
+  const wav = planIngestTranscode('audio', '/in/vo.WAV', { codec_audio: 'pcm_s24le', sample_rate: 44100 }, rules);
+  assert.deepEqual([wav.sampleRate, wav.ext], [48000, 'wav']);
+  assert.equal(planIngestTranscode('audio', '/in/song.mp3', { codec_audio: 'mp3', sample_rate: 44100 }, rules), null);
+  assert.equal(planIngestTranscode('image', '/in/still.png', { codec_video: 'png', width: 8000, height: 6000 }, rules), null);
+});
+
+test('resampled PCM keeps its sample format', () => {
+  const wav = planIngestTranscode('audio', '/in/vo.wav', { codec_audio: 'pcm_s24le', sample_rate: 96000 }, rules);
+  assert.deepEqual(ingestTranscodeOptions(wav, { codec_audio: 'pcm_s24le' }), ['-map', '0:a:0', '-c:a', 'pcm_s24le', '-ar', '48000']);
+});
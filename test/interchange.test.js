@@ -0,0 +1,67 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { buildEdl, buildOtio, markerColorName } = require('../electron/interchange');
+
+const plan = {
+  id: 'p1',
+  name: 'Promo',
+  mainTrack: [
+    { clipId: 'a', trackId: 'v1', srcPath: '/media/a.mp4', assetName: 'Interview', inMs: 1000, outMs: 3000, startMs: 0, endMs: 2000 },
+    { clipId: 'b', trackId: 'v1', srcPath: '/media/b.mp4', inMs: 0, outMs: 1000, startMs: 3000, endMs: 5000, speed: 0.5 },
+    { clipId: 'c', trackId: 'v1', srcPath: '/media/c.mp4', inMs: 0, outMs: 600, startMs: 5000, endMs: 6000, loop: true },
+  ],
+  overlayTrack: [
+    { clipId: 'logo', trackId: 'v2', srcPath: '/media/logo.png', inMs: 0, outMs: 1000, startMs: 1000, endMs: 2000 },
+  ],
+  audioTrack: [
+    { clipId: 'music', trackId: 'a1', srcPath: '/media/music.wav', inMs: 0, outMs: 6000, startMs: 0, endMs: 6000 },
+  ],
+  markers: [
+    { id: 'm1', name: 'Intro', color: '#f5a524', timeMs: 500 },
+    { id: 'm2', name: 'Act 2', color: '#3355ff', timeMs: 3500, durationMs: 1000 },
+  ],
+  frameRate: { num: 25, den: 1 },
+};
+
+test('marker colors match the nearest named color', () => {
+  assert.equal(markerColorName('#f5a524'), 'ORANGE');
+  assert.equal(markerColorName('#f5a524', ['RED', 'YELLOW']), 'YELLOW');
+  assert.equal(markerColorName('blue'), 'BLUE');
+  assert.equal(markerColorName(null), 'RED');
+});
+
+test('EDL lists main-track events with motion lines and LOC markers', () => {
+  const edl = buildEdl(plan).split('\n');
+  assert.deepEqual(edl.slice(0, 2), ['TITLE: Promo', 'FCM: NON-DROP FRAME']);
+  assert.ok(edl.includes('001  AX       V     C        00:00:01:00 00:00:03:00 00:00:00:00 00:00:02:00'));
+  assert.ok(edl.includes('* FROM CLIP NAME: Interview'));
+  assert.ok(edl.includes('* LOC: 00:00:00:13 YELLOW  Intro'));
+  // Half speed: one second of source over two on the timeline
+  assert.ok(edl.includes('002  AX       V     C        00:00:00:00 00:00:01:00 00:00:03:00 00:00:05:00'));
+  assert.ok(edl.includes('M2   AX       012.5                00:00:00:00'));
+  assert.ok(edl.includes('* LOC: 00:00:03:13 BLUE    Act 2'));
+  // The loop is written out pass by pass
+  assert.ok(edl.includes('003  AX       V     C        00:00:00:00 00:00:00:15 00:00:05:00 00:00:05:15'));
+  assert.ok(edl.includes('004  AX       V     C        00:00:00:00 00:00:00:10 00:00:05:15 00:00:06:00'));
+  assert.equal(buildEdl(plan, { fps: 29.97 }).split('\n')[1], 'FCM: DROP FRAME');
+});
+
+test('OTIO carries every track, time warps and range markers', () => {
+  const timeline = JSON.parse(buildOtio(plan));
+  assert.equal(timeline.OTIO_SCHEMA, 'Timeline.1');
+  const [main, overlay, audio] = timeline.tracks.children;
+  assert.deepEqual(timeline.tracks.children.map((track) => [track.name, track.kind]), [['main', 'Video'], ['v2', 'Video'], ['a1', 'Audio']]);
+
+  assert.deepEqual(main.children.map((item) => item.OTIO_SCHEMA), ['Clip.2', 'Gap.1', 'Clip.2', 'Clip.2', 'Clip.2']);
+  assert.equal(main.children[0].media_references.DEFAULT_MEDIA.target_url, 'file:///media/a.mp4');
+  assert.deepEqual(main.children[0].source_range.start_time, { OTIO_SCHEMA: 'RationalTime.1', rate: 25, value: 25 });
+  assert.equal(main.children[1].source_range.duration.value, 25);
+  assert.deepEqual(main.children[2].effects.map((effect) => [effect.OTIO_SCHEMA, effect.time_scalar]), [['LinearTimeWarp.1', 0.5]]);
+  assert.equal(overlay.children[0].OTIO_SCHEMA, 'Gap.1');
+  assert.equal(audio.children[0].source_range.duration.value, 150);
+
+  assert.deepEqual(timeline.tracks.markers.map((marker) => [marker.name, marker.color, marker.marked_range.start_time.value, marker.marked_range.duration.value]), [
+    ['Intro', 'ORANGE', 13, 0],
+    ['Act 2', 'BLUE', 88, 25],
+  ]);
+});
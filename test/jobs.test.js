@@ -0,0 +1,50 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { runLimited } = require('../electron/jobs');
+
+test('runLimited bounds concurrency and keeps task order', async () => {
+  let running = 0;
+  let peak = 0;
+  const tasks = [30, 5, 20, 1].map((ms, i) => async () => {
+    running++;
+    peak = Math.max(peak, running);
+    await new Promise((resolve) => setTimeout(resolve, ms));
+    running--;
+    return i;
+  });
+  assert.deepEqual(await runLimited(tasks, 2), [0, 1, 2, 3]);
+  assert.equal(peak, 2);
+});
+
+test('runLimited stops starting tasks after a failure', async () => {
+  const started = [];
+  const tasks = [0, 1, 2].map((i) => async () => {
+    started.push(i);
+    if (i === 0) throw new Error('boom');
+  });
+  await assert.rejects(runLimited(tasks, 1), /boom/);
+  assert.deepEqual(started, [0]);
+});
+
+test('finished jobs are kept and persisted without command lines or unscrubbed paths', () => {
+  const fs = require('fs');
+  const os = require('os');
+  const path = require('path');
+  const jobs = require('../electron/jobs');
+  const { scrubValue } = require('../electron/diagnostics');
+  const historyPath = path.join(fs.mkdtempSync(path.join(os.tmpdir(), 'jobs-')), 'jobs.json');
+  jobs.init(historyPath, (job) => scrubValue(job, [['/cache/', '<cache>/']]));
+
+  const job = jobs.startJob('export', { settings: { filename: '/Users/ana/Movies/out.mp4', outputDir: '/cache/renders/', width: 1920 } });
+  jobs.recordCommand(job, { spawnargs: ['ffmpeg', '-i', '/Users/ana/Movies/in.mov'] });
+  jobs.finishJob(job, new Error('Failed to open /Users/ana/Movies/in.mov'));
+
+  const stored = JSON.parse(fs.readFileSync(historyPath, 'utf8'));
+  for (const entry of [stored[0], jobs.listJobs()[0]]) {
+    assert.equal(entry.commands, undefined);
+    assert.deepEqual(entry.settings, { filename: '<path>/out.mp4', outputDir: '<cache>/renders/', width: 1920 });
+    assert.equal(entry.error, 'Failed to open <path>/in.mov');
+  }
+  // The running process still sees the full job
+  assert.equal(jobs.getLastJob().settings.filename, '/Users/ana/Movies/out.mp4');
+});
@@ -0,0 +1,25 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { addMarker, listMarkers } = require('../electron/markers');
+
+test('added markers are stored in the project and listed in time order', () => {
+  const project = JSON.stringify({ id: 'p1', markers: { m1: { id: 'm1', name: 'Outro', color: '#ff0000', timeMs: 9000 } } });
+  const { project: withPoint, marker_id: pointId } = addMarker(project, { timeMs: 1500 });
+  const { project: withRange, marker_id: rangeId } = addMarker(withPoint, { timeMs: 3000, name: 'Act 2', color: '#00ff00', durationMs: 2000 });
+
+  assert.deepEqual(JSON.parse(withRange).markers[pointId], { id: pointId, name: 'Marker 2', color: '#f5a524', timeMs: 1500 });
+  assert.deepEqual(listMarkers(withRange), [
+    { id: pointId, name: 'Marker 2', color: '#f5a524', timeMs: 1500 },
+    { id: rangeId, name: 'Act 2', color: '#00ff00', timeMs: 3000, durationMs: 2000 },
+    { id: 'm1', name: 'Outro', color: '#ff0000', timeMs: 9000 },
+  ]);
+  assert.deepEqual(listMarkers(JSON.stringify({ id: 'p2' })), []);
+});
+
+test('markers need a time on the timeline', () => {
+  const project = JSON.stringify({ id: 'p1' });
+  assert.throws(() => addMarker(project, { timeMs: -1 }), { code: 'invalid_argument' });
+  assert.throws(() => addMarker(project, {}), { code: 'invalid_argument' });
+  assert.throws(() => addMarker(project, { timeMs: 0, durationMs: -5 }), { code: 'invalid_argument' });
+  assert.throws(() => addMarker('{', { timeMs: 0 }), { code: 'invalid_argument' });
+});
@@ -0,0 +1,79 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const {
+  remuxOptions,
+  remuxFilename,
+  audioExtractTarget,
+  quickTrimOptions,
+  quickTrim,
+  concatMismatches,
+  concatNormalizeGraph,
+} = require('../electron/mediaTools');
+const { resolveEncoding } = require('../electron/encoding');
+
+test('remux copies every stream and converts subtitles for mp4', () => {
+  assert.deepEqual(remuxOptions('mp4'), [
+    '-map', '0:v?', '-map', '0:a?', '-map', '0:s?',
+    '-map_metadata', '0', '-map_chapters', '0', '-c', 'copy',
+    '-c:s', 'mov_text',
+    '-movflags', '+faststart',
+  ]);
+  assert.deepEqual(remuxOptions('mkv', 'video'), ['-map', '0:v?', '-map_metadata', '0', '-map_chapters', '0', '-c', 'copy']);
+});
+
+test('m4a keeps only the audio', () => {
+  assert.deepEqual(remuxOptions('m4a').filter((arg) => arg.startsWith('0:')), ['0:a?']);
+  assert.throws(() => remuxOptions('avi'), /Unsupported container/);
+  assert.throws(() => remuxOptions('mp4', 'subtitles'), /Unknown stream selection/);
+  assert.equal(remuxFilename('/captures/Screen Recording.mkv'), 'Screen Recording_remux');
+});
+
+test('audio extraction copies into a matching container or transcodes', () => {
+  assert.deepEqual(audioExtractTarget('copy', 'aac'), { ext: 'm4a', args: ['-map', '0:a:0', '-vn', '-c:a', 'copy'], copied: true });
+  assert.equal(audioExtractTarget('copy', 'opus').ext, 'ogg');
+  // No importable container for the source codec: AAC instead
+  assert.deepEqual(audioExtractTarget('copy', 'ac3'), { ext: 'm4a', args: ['-map', '0:a:0', '-vn', '-c:a', 'aac', '-b:a', '192k'], copied: false });
+  assert.equal(audioExtractTarget('wav', 'aac').args.at(-1), 'pcm_s16le');
+  assert.throws(() => audioExtractTarget('ogg', 'aac'), /Unsupported audio codec/);
+});
+
+test('quick trim copies at keyframes or re-encodes exactly', async () => {
+  assert.deepEqual(quickTrimOptions(false), ['-map', '0', '-c', 'copy', '-avoid_negative_ts', 'make_zero']);
+  assert.deepEqual(quickTrimOptions(true, resolveEncoding({ threads: 1 })), [
+    '-map', '0:v?', '-map', '0:a?',
+    '-c:v', 'libx264', '-preset', 'veryfast', '-crf', '23', '-pix_fmt', 'yuv420p', '-threads', '1', '-x264-params', 'threads=1',
+    '-c:a', 'aac', '-b:a', '192k',
+    '-movflags', '+faststart',
+  ]);
+  await assert.rejects(quickTrim('/in.mp4', '/out.mp4', { inMs: 5000, outMs: 5000 }), /Invalid trim range/);
+});
+
+const PART = {
+  codec_video: 'h264', width: 1920, height: 1080, frame_rate: '30/1', rotation_deg: 0,
+  has_audio: true, codec_audio: 'aac', sample_rate: 48000, channels: 2, duration_ms: 60000,
+};
+
+test('matching parts can be merged by stream copy', () => {
+  const entries = [{ path: '/rec/part1.mp4', meta: PART }, { path: '/rec/part2.mp4', meta: { ...PART, duration_ms: 1000 } }];
+  assert.deepEqual(concatMismatches(entries), []);
+  const mismatched = [...entries, { path: '/rec/part3.mp4', meta: { ...PART, frame_rate: '60/1', sample_rate: 44100 } }];
+  assert.deepEqual(concatMismatches(mismatched), [
+    'part3.mp4: frame rate 60/1 differs from 30/1',
+    'part3.mp4: sample rate 44100 differs from 48000',
+  ]);
+});
+
+test('mismatched parts are fitted to the first video and silent parts get silence', () => {
+  const { filters, hasVideo } = concatNormalizeGraph([
+    { path: '/a.mp4', meta: PART },
+    { path: '/b.mov', meta: { ...PART, width: 1280, height: 720, has_audio: false, duration_ms: 2500 } },
+  ]);
+  assert.equal(hasVideo, true);
+  assert.deepEqual(filters, [
+    '[0:v]scale=1920:1080:force_original_aspect_ratio=decrease,pad=1920:1080:(ow-iw)/2:(oh-ih)/2:black,setsar=1,fps=30/1,format=yuv420p[v0]',
+    '[0:a:0]aresample=48000,aformat=sample_rates=48000:channel_layouts=stereo[a0]',
+    '[1:v]scale=1920:1080:force_original_aspect_ratio=decrease,pad=1920:1080:(ow-iw)/2:(oh-ih)/2:black,setsar=1,fps=30/1,format=yuv420p[v1]',
+    'anullsrc=r=48000:cl=stereo,atrim=duration=2.5[a1]',
+    '[v0][a0][v1][a1]concat=n=2:v=1:a=1[v][a]',
+  ]);
+});
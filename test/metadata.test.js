@@ -0,0 +1,103 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const fs = require('fs');
+const os = require('os');
+const path = require('path');
+const { spawn } = require('child_process');
+const { superviseProcess, cancelProbes, probeMediaBatch, probeMedia } = require('../electron/metadata');
+const { StarscapeError } = require('../electron/errors');
+const { ErrorCode } = require('../electron/errors');
+
+// A child that never answers, like ffprobe against a sleeping network drive
+function hangingProcess(onExit) {
+  return (resolve, reject) => {
+    const child = spawn(process.execPath, ['-e', 'setInterval(() => {}, 1000)']);
+    child.on('exit', (code, signal) => onExit(signal));
+    child.on('error', reject);
+    return () => child.kill('SIGKILL');
+  };
+}
+
+test('a hung probe is killed and rejects with a timeout error', async () => {
+  let exitSignal = null;
+  let start;
+  const exited = new Promise((resolve) => {
+    start = hangingProcess((signal) => { exitSignal = signal; resolve(); });
+  });
+  await assert.rejects(
+    superviseProcess(start, 'ffprobe', { path: '/Volumes/nas/clip.mov' }, { timeoutMs: 100 }),
+    (err) => err.code === ErrorCode.TIMEOUT && err.context.timeout_ms === 100 && /timed out/.test(err.message)
+  );
+  await exited;
+  assert.equal(exitSignal, 'SIGKILL');
+});
+
+test('cancelProbes and abort signals stop running probes', async () => {
+  const controller = new AbortController();
+  const viaSignal = superviseProcess(hangingProcess(() => {}), 'ffprobe', {}, { signal: controller.signal });
+  const viaCancel = superviseProcess(hangingProcess(() => {}), 'ffprobe', {}, {});
+  controller.abort();
+  await assert.rejects(viaSignal, (err) => err.code === ErrorCode.CANCELLED);
+  assert.equal(cancelProbes(), 1);
+  await assert.rejects(viaCancel, (err) => err.code === ErrorCode.CANCELLED);
+});
+
+test('batch probes report each path in order with its own error', async () => {
+  const results = await probeMediaBatch(['/missing/a.mov', '/blocked/b.mov', '/missing/c.mov'], {
+    resolvePath: (filePath) => {
+      if (filePath.startsWith('/blocked')) {
+        throw new StarscapeError(ErrorCode.PATH_NOT_ALLOWED, `Path not allowed: ${filePath}`, { path: filePath });
+      }
+      return filePath;
+    },
+  });
+  assert.deepEqual(results.map((r) => r.path), ['/missing/a.mov', '/blocked/b.mov', '/missing/c.mov']);
+  assert.deepEqual(results.map((r) => r.error.code), [ErrorCode.PROBE_FAILED, ErrorCode.PATH_NOT_ALLOWED, ErrorCode.PROBE_FAILED]);
+  assert.ok(results.every((r) => r.metadata === null));
+});
+
+test('stored metadata is reused until the file changes', async () => {
+  const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'meta-cache-'));
+  const source = path.join(dir, 'clip.mov');
+  fs.writeFileSync(source, 'not really a movie');
+  const cache = { metadataPath: () => path.join(dir, 'meta.json') };
+  const stats = fs.statSync(source);
+  const metadata = { duration_ms: 4000, codec_video: 'h264', chapters: [] };
+  fs.writeFileSync(cache.metadataPath(), JSON.stringify({ version: 3, size: stats.size, mtime_ms: stats.mtimeMs, metadata }));
+
+  assert.deepEqual(await probeMedia(source, { cache }), metadata);
+
+  // A different size invalidates the entry, so the (unprobeable) file is probed again
+  fs.appendFileSync(source, '!');
+  await assert.rejects(probeMedia(source, { cache }), (err) => err.code === ErrorCode.PROBE_FAILED);
+  fs.rmSync(dir, { recursive: true, force: true });
+});
+
+test('streams, format and chapters come from a single ffprobe run', { skip: process.platform === 'win32' }, async () => {
+  const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'fake-ffprobe-'));
+  const calls = path.join(dir, 'calls.txt');
+  const output = {
+    format: { duration: '12.5' },
+    streams: [{ codec_type: 'video', codec_name: 'h264', width: 1280, height: 720, avg_frame_rate: '30/1' }],
+    chapters: [{ start_time: '0.000000', end_time: '5.000000', tags: { title: 'Intro' } }, { start_time: '5.000000', end_time: '12.500000' }],
+  };
+  const fakeProbe = path.join(dir, 'ffprobe');
+  fs.writeFileSync(fakeProbe, `#!/bin/sh\necho "$*" >> '${calls}'\ncat <<'JSON'\n${JSON.stringify(output)}\nJSON\n`, { mode: 0o755 });
+  const previous = process.env.STARSCAPE_FFPROBE_PATH;
+  process.env.STARSCAPE_FFPROBE_PATH = fakeProbe;
+  try {
+    const meta = await probeMedia('/media/talk.mov');
+    assert.equal(meta.duration_ms, 12500);
+    assert.deepEqual(meta.chapters, [
+      { title: 'Intro', start_ms: 0, end_ms: 5000 },
+      { title: 'Chapter 2', start_ms: 5000, end_ms: 12500 },
+    ]);
+    const lines = fs.readFileSync(calls, 'utf8').trim().split('\n');
+    assert.equal(lines.length, 1);
+    assert.match(lines[0], /-show_format -show_streams -show_chapters/);
+  } finally {
+    if (previous === undefined) delete process.env.STARSCAPE_FFPROBE_PATH;
+    else process.env.STARSCAPE_FFPROBE_PATH = previous;
+    fs.rmSync(dir, { recursive: true, force: true });
+  }
+});
@@ -0,0 +1,40 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { recordingLayout, conformVideoOptions, importObsRecording } = require('../electron/obsImport');
+const { audioExtractTarget } = require('../electron/mediaTools');
+const { resolveEncoding } = require('../electron/encoding');
+
+const OBS_STREAMS = [
+  { index: 0, codec_type: 'video', codec_name: 'h264', r_frame_rate: '60/1', avg_frame_rate: '5980/100', start_time: '0.000000' },
+  { index: 1, codec_type: 'audio', codec_name: 'aac', channels: 2, start_time: '0.021000', tags: { title: 'Desktop Audio' } },
+  { index: 2, codec_type: 'audio', codec_name: 'aac', channels: 1, start_time: '0.021000', tags: {} },
+  { index: 3, codec_type: 'audio', codec_name: 'opus', channels: 1, start_time: '-0.007000', tags: { TITLE: 'Mic' } },
+];
+
+test('OBS layout lists every audio track and flags variable frame rate video', () => {
+  const layout = recordingLayout(OBS_STREAMS);
+  assert.deepEqual(layout.video, { index: 0, codec: 'h264', frame_rate: '60/1', vfr: true, start_ms: 0 });
+  assert.equal(layout.multitrack, true);
+  assert.deepEqual(layout.audio.map(({ index, title, start_ms }) => [index, title, start_ms]), [
+    [0, 'Desktop Audio', 21],
+    [1, 'Track 2', 21],
+    [2, 'Mic', -7],
+  ]);
+
+  const steady = recordingLayout([{ ...OBS_STREAMS[0], avg_frame_rate: '60/1' }, OBS_STREAMS[1]]);
+  assert.equal(steady.video.vfr, false);
+  assert.equal(steady.multitrack, false);
+  // Matroska without an average rate can't be trusted to be constant
+  assert.equal(recordingLayout([{ ...OBS_STREAMS[0], avg_frame_rate: '0/0' }]).video.vfr, true);
+  assert.equal(recordingLayout([{ codec_type: 'video', disposition: { attached_pic: 1 } }]).video, null);
+});
+
+test('conform re-encodes only the video at a constant rate; tracks are extracted by index', () => {
+  const options = conformVideoOptions('60/1', resolveEncoding());
+  assert.deepEqual(options.slice(0, 8), ['-map', '0:v:0', '-an', '-sn', '-vf', 'fps=60/1', '-fps_mode', 'cfr']);
+  assert.deepEqual(audioExtractTarget('copy', 'opus', 2).args, ['-map', '0:a:2', '-vn', '-c:a', 'copy']);
+});
+
+test('unknown conform modes are rejected before probing', async () => {
+  await assert.rejects(importObsRecording('/captures/obs.mkv', {}, { conform: 'sometimes' }), { code: 'invalid_argument' });
+});
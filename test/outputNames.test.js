@@ -0,0 +1,44 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const path = require('path');
+const { DEFAULT_FILENAME_TEMPLATE, outputNameFields, expandFilenameTemplate, uniqueOutputPath } = require('../electron/outputNames');
+
+test('the default template names exports after the project, preset, date and size', () => {
+  const fields = outputNameFields(
+    { id: 'proj_1', name: 'Launch Teaser' },
+    { qualityProfile: 'master' },
+    { width: 1080, height: 1920 },
+    new Date(2026, 2, 7, 9, 5, 30),
+  );
+  assert.equal(expandFilenameTemplate(DEFAULT_FILENAME_TEMPLATE, fields), 'Launch_Teaser_master_2026-03-07_1080x1920');
+  assert.equal(expandFilenameTemplate('{id}-{time}', fields), 'proj_1-090530');
+
+  // Unnamed projects fall back to the id, and settings without a profile are custom
+  const unnamed = outputNameFields({ id: 'proj_1' }, {}, { width: 1920, height: 1080 });
+  assert.equal(expandFilenameTemplate('{project}_{preset}', unnamed), 'proj_1_custom');
+});
+
+test('templates reject unknown fields and unsafe characters are replaced', () => {
+  const fields = outputNameFields({ id: 'p', name: 'a/b: c' }, {}, { width: 1, height: 1 });
+  assert.equal(expandFilenameTemplate('{project}', fields), 'a_b__c');
+  assert.throws(() => expandFilenameTemplate('{client}_{date}', fields), { code: 'invalid_argument' });
+  assert.throws(() => expandFilenameTemplate('  ', fields), { code: 'invalid_argument' });
+});
+
+test('taken names are numbered instead of overwritten', () => {
+  const taken = new Set([path.join('/renders', 'cut.mp4'), path.join('/renders', 'cut_2.mp4')]);
+  const exists = (candidate) => taken.has(candidate);
+  assert.equal(uniqueOutputPath('/renders', 'cut', 'mp4', exists), path.join('/renders', 'cut_3.mp4'));
+  assert.equal(uniqueOutputPath('/renders', 'cut', 'mov', exists), path.join('/renders', 'cut.mov'));
+});
+
+test('default render names are numbered next to earlier results', () => {
+  const fs = require('fs');
+  const { createTempCache } = require('./helpers/harness');
+  const { dir, cache } = createTempCache('output-names-');
+  fs.mkdirSync(cache.renders, { recursive: true });
+  fs.writeFileSync(path.join(cache.renders, 'talk_remux.mkv'), '');
+  assert.equal(cache.uniqueRenderOutputPath('talk_remux', 'mkv'), path.join(cache.renders, 'talk_remux_2.mkv'));
+  assert.equal(cache.uniqueRenderOutputPath('a:b_trim', 'mp4'), path.join(cache.renders, 'a_b_trim.mp4'));
+  fs.rmSync(dir, { recursive: true, force: true });
+});
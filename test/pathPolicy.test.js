@@ -0,0 +1,54 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const fs = require('fs');
+const os = require('os');
+const path = require('path');
+const pathPolicy = require('../electron/pathPolicy');
+
+const root = fs.realpathSync(fs.mkdtempSync(path.join(os.tmpdir(), 'starscape-policy-')));
+const cacheDir = path.join(root, 'cache');
+const outside = path.join(root, 'outside.mov');
+fs.mkdirSync(cacheDir);
+fs.writeFileSync(path.join(cacheDir, 'clip.mp4'), '');
+fs.writeFileSync(outside, '');
+pathPolicy.init([cacheDir], path.join(root, 'grants.json'));
+
+test('paths under app roots are allowed for read and write', () => {
+  assert.equal(pathPolicy.checkPath(path.join(cacheDir, 'clip.mp4')), path.join(cacheDir, 'clip.mp4'));
+  assert.equal(pathPolicy.checkPath(path.join(cacheDir, 'new', 'out.mp4'), 'write'), path.join(cacheDir, 'new', 'out.mp4'));
+});
+
+test('traversal and relative paths are rejected', () => {
+  assert.throws(() => pathPolicy.checkPath(path.join(cacheDir, '..', 'outside.mov')), /Path not allowed/);
+  assert.throws(() => pathPolicy.checkPath('clip.mp4'), /not absolute/);
+});
+
+test('symlinks are resolved before checking', { skip: process.platform === 'win32' }, () => {
+  const link = path.join(cacheDir, 'link.mov');
+  fs.symlinkSync(outside, link);
+  assert.throws(() => pathPolicy.checkPath(link), /Path not allowed/);
+});
+
+test('dialog grants allow reads but not writes', () => {
+  pathPolicy.grant([outside]);
+  assert.equal(pathPolicy.checkPath(`file://${outside}`), outside);
+  assert.throws(() => pathPolicy.checkPath(outside, 'write'), /Path not allowed/);
+});
+
+test('checkFileName rejects names that leave the directory', () => {
+  assert.equal(pathPolicy.checkFileName('recording.webm'), 'recording.webm');
+  assert.throws(() => pathPolicy.checkFileName('../recording.webm'), /invalid file name/);
+  assert.throws(() => pathPolicy.checkFileName('..'), /invalid file name/);
+});
+
+test('hook targets need their own grant, even under app roots', () => {
+  const script = path.join(cacheDir, 'payload.bat');
+  fs.writeFileSync(script, '');
+  assert.throws(() => pathPolicy.checkHookTarget(script), { code: 'path_not_allowed' });
+  assert.throws(() => pathPolicy.checkHookTarget(outside), { code: 'path_not_allowed' });
+
+  pathPolicy.grantHookTarget([root]);
+  assert.equal(pathPolicy.checkHookTarget(root), root);
+  // A granted folder doesn't cover what's inside it
+  assert.throws(() => pathPolicy.checkHookTarget(outside), { code: 'path_not_allowed' });
+});
@@ -0,0 +1,93 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { urlToPath, pathToFileUrl, toLongPath, stripLongPathPrefix, concatListEntry, setLibraryRoot, toLibraryUrl, resolveAssetSrc } = require('../electron/paths');
+
+test('urlToPath decodes POSIX file URLs', () => {
+  assert.equal(urlToPath('file:///Users/me/My%20Movie.mov', 'darwin'), '/Users/me/My Movie.mov');
+  assert.equal(urlToPath('file:///Users/me/%E6%98%A0%E7%94%BB%20%F0%9F%8E%AC.mp4', 'darwin'), '/Users/me/映画 🎬.mp4');
+});
+
+test('urlToPath keeps a literal percent sign that was never encoded', () => {
+  assert.equal(urlToPath('file:///tmp/100%.mp4', 'linux'), '/tmp/100%.mp4');
+});
+
+test('urlToPath handles Windows drive letters', () => {
+  assert.equal(urlToPath('file:///C:/Users/me/clip%201.mp4', 'win32'), 'C:\\Users\\me\\clip 1.mp4');
+  assert.equal(urlToPath('file:///c:/x.mp4', 'darwin'), 'c:\\x.mp4');
+});
+
+test('urlToPath accepts the malformed file://C:\\ form older builds returned', () => {
+  assert.equal(urlToPath('file://C:\\Users\\me\\out.mp4', 'win32'), 'C:\\Users\\me\\out.mp4');
+});
+
+test('urlToPath maps file://host/share URLs to UNC paths', () => {
+  assert.equal(urlToPath('file://nas/media/Shoot%20A/a.mov', 'win32'), '\\\\nas\\media\\Shoot A\\a.mov');
+  assert.equal(urlToPath('file://localhost/etc/hosts', 'linux'), '/etc/hosts');
+});
+
+test('urlToPath resolves media:// URLs', () => {
+  assert.equal(urlToPath('media:///Users/me/clip.mov', 'darwin'), '/Users/me/clip.mov');
+  assert.equal(urlToPath('media://Users/me/a%20b.mov', 'darwin'), '/Users/me/a b.mov');
+  assert.equal(urlToPath('media://C:\\media\\a.mov', 'win32'), 'C:\\media\\a.mov');
+});
+
+test('urlToPath leaves plain paths alone', () => {
+  assert.equal(urlToPath('/Users/me/a b.mov'), '/Users/me/a b.mov');
+  assert.equal(urlToPath('C:\\media\\a.mov'), 'C:\\media\\a.mov');
+});
+
+test('pathToFileUrl encodes POSIX, drive and UNC paths', () => {
+  assert.equal(pathToFileUrl('/Users/me/a b#1.mov'), 'file:///Users/me/a%20b%231.mov');
+  assert.equal(pathToFileUrl('C:\\Users\\me\\a b.mp4'), 'file:///C:/Users/me/a%20b.mp4');
+  assert.equal(pathToFileUrl('\\\\nas\\media\\a.mov'), 'file://nas/media/a.mov');
+});
+
+test('pathToFileUrl round-trips through urlToPath', () => {
+  for (const [filePath, platform] of [
+    ['/tmp/100% ünïcødé 🎬.mp4', 'linux'],
+    ['C:\\Users\\me\\片段.mp4', 'win32'],
+    ['\\\\nas\\media\\Shoot A\\a.mov', 'win32'],
+  ]) {
+    assert.equal(urlToPath(pathToFileUrl(filePath), platform), filePath);
+  }
+});
+
+test('toLongPath prefixes long Windows paths only', () => {
+  const long = `C:\\Projects\\${'片'.repeat(250)}.mp4`;
+  assert.equal(toLongPath(long, 'win32'), `\\\\?\\${long}`);
+  assert.equal(toLongPath(toLongPath(long, 'win32'), 'win32'), `\\\\?\\${long}`);
+  assert.equal(toLongPath('C:\\short.mp4', 'win32'), 'C:\\short.mp4');
+  assert.equal(toLongPath(`/home/${'a'.repeat(300)}`, 'linux'), `/home/${'a'.repeat(300)}`);
+
+  const unc = `\\\\nas\\media\\${'b'.repeat(260)}.mov`;
+  assert.equal(toLongPath(unc, 'win32'), `\\\\?\\UNC\\nas\\media\\${'b'.repeat(260)}.mov`);
+  assert.equal(stripLongPathPrefix(toLongPath(unc, 'win32')), unc);
+});
+
+test('pathToFileUrl drops the long path prefix', () => {
+  assert.equal(pathToFileUrl('\\\\?\\C:\\media\\a.mp4'), 'file:///C:/media/a.mp4');
+});
+
+test('concatListEntry quotes apostrophes and keeps unicode', () => {
+  assert.equal(concatListEntry("/Users/o'brien/🎬 clip.mp4"), "file '/Users/o'\\''brien/🎬 clip.mp4'");
+});
+
+test('library URLs are relative to the cache and old absolute cache paths move with it', { skip: process.platform === 'win32' }, () => {
+  setLibraryRoot('/new/appdata/com.starscape.studio/cache');
+  try {
+    assert.equal(toLibraryUrl('/new/appdata/com.starscape.studio/cache/media/a b.mp4'), 'media://library/media/a%20b.mp4');
+    assert.equal(toLibraryUrl('/elsewhere/clip.mov'), null);
+    assert.equal(urlToPath('media://library/media/a%20b.mp4'), '/new/appdata/com.starscape.studio/cache/media/a b.mp4');
+    assert.equal(urlToPath('media://library/../../etc/hosts'), '/new/appdata/com.starscape.studio/cache/etc/hosts');
+
+    // A project saved on another machine, before library URLs existed
+    assert.equal(
+      resolveAssetSrc('media:///Users/old/Library/Application Support/com.starscape.studio/cache/media/x.mp4'),
+      '/new/appdata/com.starscape.studio/cache/media/x.mp4'
+    );
+    assert.equal(resolveAssetSrc('media/x.mp4'), '/new/appdata/com.starscape.studio/cache/media/x.mp4');
+    assert.equal(resolveAssetSrc('file:///Users/me/clip.mov'), '/Users/me/clip.mov');
+  } finally {
+    setLibraryRoot(null);
+  }
+});
@@ -0,0 +1,63 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { hdrFormat, planPhoneFixups } = require('../electron/phoneFootage');
+const { normalizeIngestRule, ingestTranscodeOptions } = require('../electron/ingestRules');
+
+const IPHONE = {
+  codec_video: 'hevc',
+  width: 3840,
+  height: 2160,
+  fps: 240,
+  rotation_deg: 90,
+  color_transfer: 'arib-std-b67',
+  dolby_vision_profile: 8,
+  channels: 2,
+  audio_codecs: ['aac', 'apac'],
+};
+
+test('HDR formats are told apart by transfer and Dolby Vision record', () => {
+  assert.equal(hdrFormat({ color_transfer: 'smpte2084' }), 'hdr10');
+  assert.equal(hdrFormat({ color_transfer: 'arib-std-b67' }), 'hlg');
+  assert.equal(hdrFormat({ color_transfer: 'arib-std-b67', dolby_vision_profile: 8 }), 'dolby_vision');
+  assert.equal(hdrFormat({ color_transfer: 'bt709' }), null);
+});
+
+test('iPhone HDR slow-mo gets one conversion covering every fixup', () => {
+  const rules = [normalizeIngestRule({ type: 'max_resolution', width: 1920, height: 1080 })];
+  const { fixups, transcode } = planPhoneFixups('video', '/media/IMG_0001.MOV', IPHONE, { rules });
+  assert.deepEqual(fixups.changes, [
+    '3840x2160 to 1920x1080',
+    'hevc to h264',
+    'dolby_vision tone-mapped to SDR',
+    'spatial audio track dropped, stereo track kept',
+    '90° rotation baked in',
+    '240 fps slow-motion kept at full rate',
+  ]);
+  assert.equal(fixups.slow_motion, true);
+  assert.equal(fixups.spatial_audio, true);
+  assert.equal(transcode.ext, 'mp4');
+
+  const options = ingestTranscodeOptions(transcode, IPHONE);
+  assert.deepEqual(options.slice(0, 4), ['-map', '0:v:0', '-map', '0:a:0']);
+  const filters = options[options.indexOf('-vf') + 1];
+  assert.match(filters, /^zscale=tin=arib-std-b67:.*tonemap=tonemap=hable.*,scale=1080:1920$/);
+  assert.equal(options[options.indexOf('-color_trc') + 1], 'bt709');
+});
+
+test('SDR H.264 needs nothing; audio-only fixes copy the video', () => {
+  const android = { codec_video: 'h264', fps: 30, rotation_deg: 0, color_transfer: 'bt709', channels: 2, audio_codecs: ['aac'] };
+  assert.equal(planPhoneFixups('video', '/media/VID.mp4', android).transcode, null);
+  assert.equal(planPhoneFixups('video', '/media/VID.mp4', android).fixups.hdr, null);
+
+  const { transcode } = planPhoneFixups('video', '/media/VID.mp4', { ...android, channels: 4 });
+  assert.deepEqual(ingestTranscodeOptions(transcode, android), [
+    '-map', '0:v:0', '-map', '0:a:0', '-c:v', 'copy', '-c:a', 'aac', '-b:a', '320k', '-ac', '2', '-movflags', '+faststart',
+  ]);
+});
+
+test('HDR is kept when ffmpeg lacks the tone-mapping filters', () => {
+  const { fixups, transcode } = planPhoneFixups('video', '/media/IMG.MOV', { ...IPHONE, audio_codecs: ['aac'] }, { toneMap: false });
+  assert.ok(fixups.changes.includes('dolby_vision kept: ffmpeg lacks zscale/tonemap'));
+  assert.equal(transcode.toneMap, undefined);
+  assert.equal(planPhoneFixups('audio', '/media/a.wav', { sample_rate: 44100 }).fixups, null);
+});
@@ -0,0 +1,176 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { buildPlan, isolateClip, sourceTimeAt } = require('../electron/editPlan');
+const { loadProject } = require('./helpers/harness');
+const { assertGolden } = require('./helpers/golden');
+const { speedVideoFilters } = require('../electron/filterUtils');
+
+// Asset URLs are POSIX, so the resolved srcPaths only match the goldens off Windows
+const posixOnly = { skip: process.platform === 'win32' };
+
+test('buildPlan matches golden for a main track with a gap', posixOnly, () => {
+  assertGolden('basic.plan.json', buildPlan(loadProject('basic.project.json')));
+});
+
+test('buildPlan matches golden for overlay, adjustment, audio and markers', posixOnly, () => {
+  assertGolden('layered.plan.json', buildPlan(loadProject('layered.project.json')));
+});
+
+test('buildPlan matches golden when snapping to 29.97 fps', posixOnly, () => {
+  assertGolden('layered.plan.29.97.json', buildPlan(loadProject('layered.project.json'), { fps: 29.97 }));
+});
+
+test('clips with their own audio range or offset detach their audio into the mixdown', () => {
+  const project = JSON.parse(loadProject('basic.project.json'));
+  // L-cut: clip_1's audio runs 500 ms into the gap; J-cut: clip_2's audio leads its picture by 250 ms
+  project.clips.clip_1.audioOutMs = 3500;
+  project.clips.clip_2.audioOffsetMs = -250;
+  project.clips.clip_2.audioInMs = 0;
+  const plan = buildPlan(JSON.stringify(project));
+
+  assert.deepEqual(plan.mainTrack.map((clip) => clip.audioDetached), [true, true]);
+  assert.deepEqual(
+    plan.audioTrack.map(({ clipId, inMs, outMs, startMs, endMs }) => ({ clipId, inMs, outMs, startMs, endMs })),
+    [
+      { clipId: 'clip_1', inMs: 1000, outMs: 3500, startMs: 0, endMs: 2500 },
+      { clipId: 'clip_2', inMs: 0, outMs: 2000, startMs: 2750, endMs: 4750 },
+    ]
+  );
+
+  project.clips.clip_1.audioOutMs = 500;
+  assert.throws(() => buildPlan(JSON.stringify(project)), /Clip clip_1 has invalid audio timing/);
+});
+
+test('channel maps follow the clip and its detached audio', () => {
+  const project = JSON.parse(loadProject('basic.project.json'));
+  project.clips.clip_1.audioChannels = { mode: 'select', channel: 0 };
+  project.clips.clip_1.audioOffsetMs = 100;
+  project.clips.clip_2.audioChannels = { mode: 'surround' };
+  const plan = buildPlan(JSON.stringify(project));
+  assert.deepEqual(plan.mainTrack[0].audioChannels, { mode: 'select', channel: 0 });
+  assert.deepEqual(plan.audioTrack[0].audioChannels, { mode: 'select', channel: 0 });
+  assert.equal(plan.mainTrack[1].audioChannels, undefined);
+});
+
+test('a clip crop reaches the plan only with a usable size', () => {
+  const project = JSON.parse(loadProject('basic.project.json'));
+  project.clips.clip_1.crop = { x: 0, y: 140, width: 1920, height: 800 };
+  project.clips.clip_2.crop = { x: 0, y: 0, width: 0, height: 800 };
+  const plan = buildPlan(JSON.stringify(project));
+  assert.deepEqual(plan.mainTrack[0].crop, { x: 0, y: 140, width: 1920, height: 800 });
+  assert.equal(plan.mainTrack[1].crop, undefined);
+});
+
+test('clip gain follows the clip and its detached audio', () => {
+  const project = JSON.parse(loadProject('basic.project.json'));
+  project.clips.clip_1.gainDb = -3;
+  project.clips.clip_1.audioOffsetMs = 100;
+  const plan = buildPlan(JSON.stringify(project));
+  assert.equal(plan.mainTrack[0].gainDb, -3);
+  assert.equal(plan.audioTrack[0].gainDb, -3);
+  assert.equal(plan.mainTrack[1].gainDb, undefined);
+});
+
+test('audio fields matching the video keep the audio attached', () => {
+  const project = JSON.parse(loadProject('basic.project.json'));
+  Object.assign(project.clips.clip_1, { audioInMs: 1000, audioOutMs: 3000, audioOffsetMs: 0 });
+  const plan = buildPlan(JSON.stringify(project));
+  assert.equal(plan.mainTrack[0].audioDetached, undefined);
+  assert.deepEqual(plan.audioTrack, []);
+});
+
+test('freeze frames are held inside the clip span and push the rest of the clip later', () => {
+  const project = JSON.parse(loadProject('layered.project.json'));
+  const pieces = (plan) => plan.mainTrack
+    .filter((clip) => clip.clipId === 'clip_1')
+    .map(({ inMs, outMs, startMs, endMs, freezeFrame }) => [inMs, outMs, startMs, endMs, !!freezeFrame]);
+  // 4 s of source with a 1 s hold at 2 s fills 0-5 s
+  assert.deepEqual(pieces(buildPlan(JSON.stringify(project))), [
+    [0, 2000, 0, 2000, false],
+    [2000, 3000, 2000, 3000, true],
+    [2000, 4000, 3000, 5000, false],
+  ]);
+
+  // A span too short for the holds cuts the source, not the hold
+  project.clips.clip_1.endMs = 2500;
+  assert.deepEqual(pieces(buildPlan(JSON.stringify(project))), [
+    [0, 2000, 0, 2000, false],
+    [2000, 2500, 2000, 2500, true],
+  ]);
+});
+
+test('overlays past the last main clip extend the program with black', () => {
+  const project = JSON.parse(loadProject('layered.project.json'));
+  project.clips.clip_logo.endMs = 8000;
+  project.clips.clip_logo.outMs = 7000;
+  const filler = buildPlan(JSON.stringify(project)).mainTrack.at(-1);
+  assert.deepEqual(
+    { startMs: filler.startMs, endMs: filler.endMs, inMs: filler.inMs, outMs: filler.outMs, generator: filler.generator },
+    { startMs: 5000, endMs: 8000, inMs: 0, outMs: 3000, generator: { type: 'solid', color: '#000000' } }
+  );
+
+  // An overlay-only project is all filler, starting where the first overlay does
+  project.tracks.track_main.clipOrder = [];
+  const overlayOnly = buildPlan(JSON.stringify(project));
+  assert.deepEqual(overlayOnly.mainTrack.map(({ startMs, endMs }) => [startMs, endMs]), [[1000, 8000]]);
+});
+
+test('isolating a clip keeps its pieces and the adjustments over it, starting at 0', () => {
+  const plan = buildPlan(loadProject('layered.project.json'));
+
+  const main = isolateClip(plan, 'clip_1');
+  assert.equal(main.id, 'fixture_layered_clip_1');
+  assert.deepEqual(main.mainTrack.map(({ startMs, endMs, freezeFrame }) => ({ startMs, endMs, freezeFrame })), [
+    { startMs: 0, endMs: 2000, freezeFrame: undefined },
+    { startMs: 2000, endMs: 3000, freezeFrame: true },
+    { startMs: 3000, endMs: 5000, freezeFrame: undefined },
+  ]);
+  assert.deepEqual(main.mainTrack[0].audioEffects, [{ type: 'highpass', frequency: 100 }]);
+  assert.deepEqual([main.overlayTrack, main.audioTrack, main.captionTrack, main.markers], [[], [], [], []]);
+
+  // The overlay plays full frame from 0; the grade is cut to its span
+  const overlay = isolateClip(plan, 'clip_logo');
+  assert.deepEqual(overlay.mainTrack.map(({ startMs, endMs, inMs, outMs }) => ({ startMs, endMs, inMs, outMs })), [
+    { startMs: 0, endMs: 2000, inMs: 0, outMs: 2000 },
+  ]);
+  assert.equal(overlay.mainTrack[0].canvasNode, undefined);
+  assert.equal(overlay.mainTrack[0].blendMode, undefined);
+  assert.deepEqual(overlay.adjustmentTrack.map(({ startMs, endMs }) => [startMs, endMs]), [[0, 2000]]);
+
+  assert.throws(() => isolateClip(plan, 'clip_missing'), { code: 'not_found' });
+});
+
+test('retimed clips carry their speed, and interpolate only slow motion that opts in', () => {
+  const project = JSON.parse(loadProject('basic.project.json'));
+  const { inMs, outMs, startMs } = project.clips.clip_1;
+  project.clips.clip_1 = { ...project.clips.clip_1, speed: 0.25, interpolateFrames: true, endMs: startMs + (outMs - inMs) * 4 };
+  project.clips.clip_2.speed = 1;
+  const plan = buildPlan(JSON.stringify(project));
+  assert.equal(plan.mainTrack[0].speed, 0.25);
+  assert.equal(plan.mainTrack[0].interpolateFrames, true);
+  assert.equal(plan.mainTrack[1].speed, undefined);
+
+  assert.deepEqual(speedVideoFilters(plan.mainTrack[0], '30000/1001'), [
+    'setpts=(PTS-STARTPTS)/0.25',
+    'minterpolate=fps=30000/1001:mi_mode=mci:mc_mode=aobmc:me_mode=bidir:vsbmc=1',
+  ]);
+  assert.deepEqual(speedVideoFilters({ speed: 0.5, interpolateFrames: true }, '30/1'), ['setpts=(PTS-STARTPTS)/0.5']);
+  assert.deepEqual(speedVideoFilters({ speed: 0.25 }, '30/1'), ['setpts=(PTS-STARTPTS)/0.25']);
+  assert.deepEqual(speedVideoFilters({}, '30/1'), []);
+
+  // Previews and contact sheets read the source at the clip's speed
+  assert.equal(sourceTimeAt(plan.mainTrack[0], plan.mainTrack[0].startMs + 400), inMs + 100);
+  assert.equal(sourceTimeAt({ inMs: 500, startMs: 1000, freezeFrame: true }, 1800), 500);
+});
+
+test('clip speed is range checked and kept off loops, freezes and detached audio', () => {
+  const withClip = (changes) => {
+    const project = JSON.parse(loadProject('basic.project.json'));
+    Object.assign(project.clips.clip_1, changes);
+    return JSON.stringify(project);
+  };
+  assert.throws(() => buildPlan(withClip({ speed: 20 })), /Clip clip_1 has invalid speed/);
+  assert.throws(() => buildPlan(withClip({ speed: 2, loop: true })), /can't change speed while it loops/);
+  assert.throws(() => buildPlan(withClip({ speed: 2, freezeFrames: [{ atMs: 1500, durationMs: 500 }] })), /can't change speed/);
+  assert.throws(() => buildPlan(withClip({ speed: 2, audioOffsetMs: 100 })), /can't change speed with detached audio/);
+});
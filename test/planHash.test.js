@@ -0,0 +1,39 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { canonicalize, hashValue, hashPlan, hashClip } = require('../electron/planHash');
+const { buildPlan } = require('../electron/editPlan');
+const { loadProject } = require('./helpers/harness');
+
+test('canonicalize sorts keys and drops undefined members', () => {
+  assert.equal(canonicalize({ b: 1, a: [2, { d: undefined, c: -0 }] }), '{"a":[2,{"c":0}],"b":1}');
+  assert.equal(hashValue({ x: 1, y: 2 }), hashValue({ y: 2, x: 1 }));
+  assert.throws(() => canonicalize({ x: NaN }), /non-finite/);
+});
+
+test('identical edits hash the same regardless of project id', () => {
+  const project = JSON.parse(loadProject('layered.project.json'));
+  const copy = { ...project, id: 'another_project' };
+  assert.equal(hashPlan(buildPlan(JSON.stringify(project))), hashPlan(buildPlan(JSON.stringify(copy))));
+});
+
+test('changing a trim or an effect changes the plan hash', () => {
+  const project = JSON.parse(loadProject('layered.project.json'));
+  const base = hashPlan(buildPlan(JSON.stringify(project)));
+
+  const trimmed = structuredClone(project);
+  trimmed.clips.clip_1.outMs = 3500;
+  assert.notEqual(hashPlan(buildPlan(JSON.stringify(trimmed))), base);
+
+  const regraded = structuredClone(project);
+  regraded.clips.clip_grade.effects[0].saturation = 1.3;
+  assert.notEqual(hashPlan(buildPlan(JSON.stringify(regraded))), base);
+});
+
+test('clip hashes ignore timeline position but not loop duration', () => {
+  const clip = { clipId: 'a', trackId: 't', srcPath: '/m/a.mov', inMs: 0, outMs: 1000, startMs: 0, endMs: 1000 };
+  assert.equal(hashClip(clip), hashClip({ ...clip, clipId: 'b', startMs: 5000, endMs: 6000 }));
+  assert.notEqual(hashClip(clip), hashClip({ ...clip, outMs: 1200, endMs: 1200 }));
+
+  const loop = { ...clip, loop: true, endMs: 4000 };
+  assert.notEqual(hashClip(loop), hashClip({ ...loop, endMs: 3000 }));
+});
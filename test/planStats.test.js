@@ -0,0 +1,46 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { estimateExportSize, getPlanStats } = require('../electron/planStats');
+const { loadProject } = require('./helpers/harness');
+
+test('stats cover the program, each track and its gaps', () => {
+  const stats = getPlanStats(loadProject('basic.project.json'));
+  assert.equal(stats.duration_ms, 5000);
+  assert.equal(stats.clip_count, 2);
+  assert.deepEqual(stats.tracks, [{
+    track_id: 'track_main',
+    role: 'main',
+    clip_count: 2,
+    duration_ms: 4000,
+    start_ms: 0,
+    end_ms: 5000,
+    gaps: [{ start_ms: 2000, end_ms: 3000, duration_ms: 1000 }],
+  }]);
+});
+
+test('freeze-frame pieces count as one clip on a gapless track', () => {
+  const stats = getPlanStats(loadProject('layered.project.json'));
+  const byId = Object.fromEntries(stats.tracks.map((track) => [track.track_id, track]));
+  assert.equal(byId.track_main.clip_count, 1);
+  assert.equal(byId.track_main.duration_ms, 5000);
+  assert.deepEqual(byId.track_main.gaps, []);
+  assert.equal(byId.track_overlay.role, 'overlay');
+  assert.equal(byId.track_captions.role, 'caption');
+  assert.equal(stats.duration_ms, 5000);
+  assert.equal(stats.timeline_end_ms, 5000);
+});
+
+test('size estimates follow each profile and explicit bitrates', () => {
+  const stats = getPlanStats(loadProject('basic.project.json'), { width: 1920, height: 1080, fps: 30 });
+  const { draft, standard, master } = stats.estimated_size_bytes;
+  assert.ok(draft < standard && standard < master);
+  // 8000 kbps video + 192 kbps audio for 10 s
+  assert.equal(estimateExportSize(10000, { bitrate: 8000 }), 10240000);
+});
+
+test('mezzanine profiles are sized by their fixed rate and PCM audio, not the bitrate', () => {
+  const { master, prores_422, prores_422hq } = getPlanStats(loadProject('basic.project.json'), { width: 1920, height: 1080, fps: 30 }).estimated_size_bytes;
+  assert.ok(master < prores_422 && prores_422 < prores_422hq);
+  const prores = estimateExportSize(10000, { width: 1920, height: 1080, fps: 30, bitrate: 8000, qualityProfile: 'prores_422' });
+  assert.equal(prores, Math.round(((1920 * 1080 * 30 * 2.36) / 1000 + 1536) * 1000 / 8 * 10));
+});
@@ -0,0 +1,47 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { buildPlan } = require('../electron/editPlan');
+const { templateFields, fillTemplateValue, fillTemplate } = require('../electron/projectTemplates');
+
+const template = JSON.stringify({
+  id: 'scorecard',
+  projectName: 'Scorecard {{ name }}',
+  assets: { photo: { id: 'photo', src: '{{photo}}', duration_ms: 5000 } },
+  clips: {
+    c: { id: 'c', assetId: 'photo', inMs: 0, outMs: 3000, startMs: 0, endMs: 3000 },
+    cap: { id: 'cap', caption: { text: '{{name}} scored {{score}}!' }, startMs: 500, endMs: 2500 },
+  },
+  tracks: {
+    main: { id: 'main', type: 'video', role: 'main', clipOrder: ['c'] },
+    captions: { id: 'captions', type: 'video', role: 'caption', clipOrder: ['cap'] },
+  },
+});
+
+test('placeholders are listed and filled in every string', () => {
+  assert.deepEqual(templateFields(JSON.parse(template)), ['name', 'photo', 'score']);
+
+  const project = JSON.parse(fillTemplate(template, { name: 'Ada', score: 97, photo: '/data/ada.png' }));
+  assert.equal(project.projectName, 'Scorecard Ada');
+  assert.equal(project.assets.photo.src, '/data/ada.png');
+  assert.equal(project.clips.cap.caption.text, 'Ada scored 97!');
+
+  // The filled project is an ordinary project; the substituted asset fills the template's slot
+  const plan = buildPlan(JSON.stringify(project));
+  assert.equal(plan.mainTrack[0].srcPath, '/data/ada.png');
+  assert.equal(plan.captionTrack[0].text, 'Ada scored 97!');
+});
+
+test('missing or non-text values are rejected before rendering', () => {
+  assert.throws(() => fillTemplate(template, { name: 'Ada', score: { value: 1 } }), (err) => {
+    assert.equal(err.code, 'invalid_argument');
+    assert.deepEqual(err.context.fields, ['photo', 'score']);
+    return true;
+  });
+  assert.throws(() => fillTemplate(template, [{ name: 'Ada' }]), { code: 'invalid_argument' });
+
+  // Settings fill the same way; values are inserted literally, never re-expanded
+  assert.deepEqual(fillTemplateValue({ filenameTemplate: '{{name}}_{date}', fps: 30 }, { name: '{{x}}' }), {
+    filenameTemplate: '{{x}}_{date}',
+    fps: 30,
+  });
+});
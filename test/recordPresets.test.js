@@ -0,0 +1,46 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const fs = require('fs-extra');
+const os = require('os');
+const path = require('path');
+const recordPresets = require('../electron/recordPresets');
+
+test('record settings are filled in and clamped', () => {
+  assert.deepEqual(recordPresets.normalizeRecordSettings({}), recordPresets.RECORD_DEFAULTS);
+  const settings = recordPresets.normalizeRecordSettings({ fps: 240, max_width: 100, video_bitrate_kbps: 10 });
+  assert.equal(settings.fps, 120);
+  assert.equal(settings.max_width, 320);
+  assert.equal(settings.video_bitrate_kbps, 250);
+  assert.throws(() => recordPresets.normalizeRecordSettings({ video_codec: 'prores' }), { code: 'invalid_argument' });
+});
+
+test('presets are listed, applied with overrides and persisted', async () => {
+  const dir = await fs.mkdtemp(path.join(os.tmpdir(), 'record-presets-'));
+  const storePath = path.join(dir, 'recording-presets.json');
+
+  try {
+    recordPresets.init(storePath);
+    assert.deepEqual(recordPresets.listRecordPresets().map((p) => p.id), ['tutorial', 'gameplay', 'meeting']);
+    assert.equal(recordPresets.activeRecordSettings().fps, 30);
+
+    const applied = recordPresets.applyRecordPreset('gameplay', { display_index: 2 });
+    assert.equal(applied.settings.fps, 60);
+    assert.equal(applied.settings.video_codec, 'h264');
+    assert.equal(applied.settings.display_index, 2);
+    assert.throws(() => recordPresets.applyRecordPreset('missing'), { code: 'not_found' });
+
+    const saved = recordPresets.saveRecordPreset('Podcast Cam', { fps: 24, microphone: true });
+    assert.equal(saved.id, 'podcast-cam');
+    assert.equal(saved.builtin, false);
+    assert.throws(() => recordPresets.saveRecordPreset('Meeting', {}), { code: 'invalid_argument' });
+
+    // A restart keeps saved presets and the active one
+    recordPresets.init(storePath);
+    const presets = recordPresets.listRecordPresets();
+    assert.equal(presets.find((p) => p.active).id, 'gameplay');
+    assert.equal(presets.find((p) => p.id === 'podcast-cam').settings.fps, 24);
+    assert.equal(recordPresets.activeRecordSettings().fps, 60);
+  } finally {
+    await fs.remove(dir);
+  }
+});
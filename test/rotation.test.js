@@ -0,0 +1,30 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { normalizeRotation, streamRotation } = require('../electron/metadata');
+const { rotationFilters } = require('../electron/filterUtils');
+
+test('streamRotation reads the legacy rotate tag', () => {
+  assert.equal(streamRotation({ tags: { rotate: '90' } }), 90);
+  assert.equal(streamRotation({ tags: { rotate: '-90' } }), 270);
+});
+
+test('streamRotation converts the counter-clockwise display matrix', () => {
+  assert.equal(streamRotation({ side_data_list: [{ side_data_type: 'Display Matrix', rotation: -90 }] }), 90);
+  assert.equal(streamRotation({ side_data_list: [{ side_data_type: 'Display Matrix', rotation: 180 }] }), 180);
+  assert.equal(streamRotation({ side_data_list: [{ side_data_type: 'Display Matrix', rotation: 90 }] }), 270);
+  assert.equal(streamRotation({ codec_type: 'video' }), null);
+});
+
+test('normalizeRotation snaps to quarter turns', () => {
+  assert.equal(normalizeRotation(-0), 0);
+  assert.equal(normalizeRotation(450), 90);
+  assert.equal(normalizeRotation(89.6), 90);
+  assert.equal(normalizeRotation('x'), 0);
+});
+
+test('rotationFilters turn frames clockwise', () => {
+  assert.deepEqual(rotationFilters(0), []);
+  assert.deepEqual(rotationFilters(90), ['transpose=clock']);
+  assert.deepEqual(rotationFilters(180), ['hflip', 'vflip']);
+  assert.deepEqual(rotationFilters(270), ['transpose=cclock']);
+});
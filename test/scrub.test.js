@@ -0,0 +1,59 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { EventEmitter } = require('events');
+const { PassThrough } = require('stream');
+const { MjpegSplitter, ScrubSession } = require('../electron/scrub');
+
+const jpeg = (n) => Buffer.from([0xff, 0xd8, n, 0xff, 0xd9]);
+
+// Stand-in ffmpeg streaming `count` tiny JPEGs, numbered from 0
+function fakeFfmpeg(spawned, count = 120) {
+  return (args) => {
+    const proc = new EventEmitter();
+    proc.stdout = new PassThrough();
+    proc.kill = () => proc.emit('close');
+    spawned.push(args);
+    for (let n = 0; n < count; n++) proc.stdout.write(jpeg(n));
+    return proc;
+  };
+}
+
+test('splitter reassembles frames split across chunks', () => {
+  const splitter = new MjpegSplitter();
+  const stream = Buffer.concat([jpeg(1), jpeg(2)]);
+  splitter.push(stream.subarray(0, 3));
+  assert.equal(splitter.next(), null);
+  splitter.push(stream.subarray(3));
+  assert.deepEqual([...splitter.next()], [...jpeg(1)]);
+  assert.deepEqual([...splitter.next()], [...jpeg(2)]);
+  assert.equal(splitter.next(), null);
+});
+
+test('forward seeks read ahead in the stream; backward and distant seeks restart it', async () => {
+  const spawned = [];
+  const frames = [];
+  const session = new ScrubSession('s1', '/media/a.mov', { fps: 15, spawnProcess: fakeFfmpeg(spawned), onFrame: (f) => frames.push(f) });
+
+  assert.deepEqual(await session.seek(0), { seq: 1, at_ms: 0 });
+  assert.deepEqual(await session.seek(1000), { seq: 2, at_ms: 1000 });
+  assert.equal(spawned.length, 1);
+  assert.equal(frames[1].jpeg[2], 15);
+
+  await session.seek(500);
+  assert.equal(spawned.length, 2);
+  assert.equal(spawned[1][spawned[1].indexOf('-ss') + 1], '0.5');
+
+  await session.seek(10000);
+  assert.equal(spawned.length, 3);
+  session.close();
+});
+
+test('a newer seek supersedes a pending one', async () => {
+  const spawned = [];
+  const session = new ScrubSession('s2', '/media/a.mov', { fps: 15, spawnProcess: fakeFfmpeg(spawned, 0) });
+  const first = session.seek(2000);
+  const second = session.seek(2100);
+  assert.deepEqual(await first, { seq: 1, superseded: true });
+  session.close();
+  assert.deepEqual(await second, { seq: 2, superseded: true });
+});
@@ -0,0 +1,52 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { EventEmitter } = require('events');
+const { PassThrough } = require('stream');
+const fs = require('fs-extra');
+const os = require('os');
+const path = require('path');
+const { RecordingSegmenter, segmentArgs } = require('../electron/segmenter');
+
+// Stand-in ffmpeg that writes one segment per chunk it receives and exits when its input closes
+function fakeSegmenter(spawned) {
+  return (args) => {
+    const proc = new EventEmitter();
+    const pattern = args[args.length - 1];
+    let count = 0;
+    proc.stdin = new PassThrough();
+    proc.stdin.on('data', (chunk) => {
+      fs.writeFileSync(pattern.replace('%03d', String(count++).padStart(3, '0')), chunk);
+    });
+    proc.stdin.on('end', () => setImmediate(() => proc.emit('close', 0)));
+    spawned.push(args);
+    return proc;
+  };
+}
+
+test('segment arguments roll over at the requested length', () => {
+  const args = segmentArgs('/media/rec_%03d.webm', 600);
+  assert.equal(args[args.indexOf('segment') - 1], '-f');
+  assert.equal(args[args.indexOf('-segment_time') + 1], '600');
+  assert.equal(args.at(-1), '/media/rec_%03d.webm');
+});
+
+test('chunks are written through and every segment is returned on finish', async () => {
+  const dir = await fs.mkdtemp(path.join(os.tmpdir(), 'segmenter-'));
+  const spawned = [];
+  try {
+    // A segment from another recording in the same folder is not picked up
+    await fs.writeFile(path.join(dir, 'rec_other_000.webm'), 'x');
+    const segmenter = new RecordingSegmenter(dir, 'rec', 10, { spawnProcess: fakeSegmenter(spawned) });
+    assert.equal(spawned[0][spawned[0].indexOf('-segment_time') + 1], '600');
+
+    await segmenter.write(Buffer.from('one'));
+    await new Promise((resolve) => setImmediate(resolve));
+    await segmenter.write(Buffer.from('two'));
+    const segments = await segmenter.finish();
+
+    assert.deepEqual(segments.map((p) => path.basename(p)), ['rec_000.webm', 'rec_001.webm']);
+    await assert.rejects(segmenter.write(Buffer.from('late')), { code: 'invalid_argument' });
+  } finally {
+    await fs.remove(dir);
+  }
+});
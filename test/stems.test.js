@@ -0,0 +1,39 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { groupStems, stemStreamOptions } = require('../electron/stems');
+
+const audioTracks = {
+  track_vo: { audioRole: 'voice', ducking: null },
+  track_music: { audioRole: 'music', ducking: { enabled: true } },
+  track_fx: { audioRole: null, ducking: null },
+};
+
+test('audio clips are grouped into stems by track role', () => {
+  const clips = [
+    { clipId: 'vo', trackId: 'track_vo' },
+    { clipId: 'bed', trackId: 'track_music' },
+    { clipId: 'whoosh', trackId: 'track_fx' },
+  ];
+  const stems = groupStems(clips, audioTracks, true);
+  assert.deepEqual(stems.map(({ name, clips: stemClips, includeBase }) => [name, stemClips.map((c) => c.clipId), includeBase]), [
+    ['dialogue', ['vo'], true],
+    ['music', ['bed'], false],
+    ['sfx', ['whoosh'], false],
+  ]);
+
+  // Empty stems are left out; a silent program with only music has no dialogue stem
+  assert.deepEqual(groupStems([clips[1]], audioTracks, false).map((stem) => stem.name), ['music']);
+});
+
+test('stem streams follow the mix, as PCM in MOV and encoded in MP4', () => {
+  const encoding = { audioCodec: 'aac', audioBitrate: 192 };
+  const mov = stemStreamOptions(['dialogue', 'music'], 'mov', encoding);
+  assert.deepEqual(mov.slice(0, 10), ['-map', '0:v?', '-map', '0:a', '-c:v', 'copy', '-c:a:0', 'copy', '-metadata:s:a:0', 'title=Mix']);
+  assert.deepEqual(mov.slice(10), [
+    '-map', '1:a', '-c:a:1', 'pcm_s24le', '-metadata:s:a:1', 'title=Dialogue',
+    '-map', '2:a', '-c:a:2', 'pcm_s24le', '-metadata:s:a:2', 'title=Music',
+  ]);
+  assert.deepEqual(stemStreamOptions(['sfx'], 'mp4', encoding).slice(10), [
+    '-map', '1:a', '-c:a:1', 'aac', '-b:a:1', '192k', '-metadata:s:a:1', 'title=SFX',
+  ]);
+});
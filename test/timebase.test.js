@@ -0,0 +1,63 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { toRational, quantizeMs, quantizeClip, quantizeProject } = require('../electron/timebase');
+
+const NTSC = toRational(29.97);
+const FILM = toRational(23.976);
+
+test('NTSC rates resolve to exact N*1000/1001 rationals', () => {
+  assert.deepEqual(NTSC, { num: 30000, den: 1001 });
+  assert.deepEqual(FILM, { num: 24000, den: 1001 });
+  assert.deepEqual(toRational('30000/1001'), NTSC);
+  assert.deepEqual(toRational(25), { num: 25, den: 1 });
+  assert.equal(toRational(0), null);
+  assert.equal(toRational('30/0'), null);
+});
+
+test('quantizeMs rounds to the nearest frame start at 29.97 and 23.976', () => {
+  // 29.97: a frame lasts 33.3667 ms, so one second of timeline is 30 frames = 1001 ms
+  assert.equal(quantizeMs(1000, NTSC), 1001);
+  assert.equal(quantizeMs(16, NTSC), 0);
+  assert.equal(quantizeMs(17, NTSC), 33.367);
+  assert.equal(quantizeMs(50, NTSC), 33.367);
+  // 23.976: a frame lasts 41.7083 ms
+  assert.equal(quantizeMs(1000, FILM), 1001);
+  assert.equal(quantizeMs(20, FILM), 0);
+  assert.equal(quantizeMs(21, FILM), 41.708);
+  assert.equal(quantizeMs(100, FILM), 83.417);
+});
+
+test('quantizeClip snaps the timeline and keeps the source range the same length', () => {
+  assert.deepEqual(quantizeClip({ inMs: 1000, outMs: 3000, startMs: 0, endMs: 2000 }, NTSC), {
+    inMs: 1001, outMs: 3003, startMs: 0, endMs: 2002,
+  });
+  // Retimed clips cover speed times their span in source
+  assert.deepEqual(quantizeClip({ inMs: 1000, outMs: 5000, startMs: 0, endMs: 2000, speed: 2 }, NTSC), {
+    inMs: 1001, outMs: 5005, startMs: 0, endMs: 2002, speed: 2,
+  });
+  // Loops keep their own range; a clip never snaps shorter than a frame
+  assert.deepEqual(quantizeClip({ inMs: 0, outMs: 500, startMs: 0, endMs: 2000, loop: true }, NTSC), {
+    inMs: 0, outMs: 500.5, startMs: 0, endMs: 2002, loop: true,
+  });
+  assert.deepEqual(quantizeClip({ inMs: 0, outMs: 10, startMs: 0, endMs: 10 }, FILM), {
+    inMs: 0, outMs: 41.708, startMs: 0, endMs: 41.708,
+  });
+});
+
+test('quantizeProject snaps clips and markers and leaves incomplete clips alone', () => {
+  const project = {
+    id: 'p1',
+    clips: {
+      a: { inMs: 0, outMs: 1000, startMs: 1000, endMs: 2000 },
+      b: { inMs: 0, startMs: 10 },
+    },
+    markers: { m1: { timeMs: 1000 }, m2: { label: 'no time' } },
+  };
+  const snapped = JSON.parse(quantizeProject(JSON.stringify(project), '24000/1001'));
+  assert.deepEqual(snapped.clips.a, { inMs: 0, outMs: 1001, startMs: 1001, endMs: 2002 });
+  assert.deepEqual(snapped.clips.b, { inMs: 0, startMs: 10 });
+  assert.deepEqual(snapped.markers, { m1: { timeMs: 1001 }, m2: { label: 'no time' } });
+
+  assert.throws(() => quantizeProject(JSON.stringify(project), 'x'), /Invalid frame rate/);
+  assert.throws(() => quantizeProject('{', 29.97), /Invalid project JSON/);
+});
@@ -0,0 +1,46 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { framesToTimecode, timecodeToFrames, msToTimecode, timecodeToMs } = require('../electron/timecode');
+
+test('non-drop-frame timecode counts every frame label', () => {
+  assert.equal(framesToTimecode(0, 25), '00:00:00:00');
+  assert.equal(framesToTimecode(25 * 3600 + 24, 25), '01:00:00:24');
+  assert.equal(framesToTimecode(1800, 29.97, { dropFrame: false }), '00:01:00:00');
+  assert.equal(timecodeToFrames('00:01:00:00', 29.97), 1800);
+  assert.equal(timecodeToFrames('00:01:00;00', 29.97, { dropFrame: false }), 1800);
+  for (const frame of [0, 1, 1799, 1800, 17982, 107892]) {
+    assert.equal(timecodeToFrames(framesToTimecode(frame, 24), 24), frame);
+    assert.equal(timecodeToFrames(framesToTimecode(frame, '30000/1001', { dropFrame: false }), '30000/1001'), frame);
+  }
+});
+
+test('drop-frame timecode skips two labels a minute except every tenth', () => {
+  assert.equal(framesToTimecode(1799, 29.97), '00:00:59;29');
+  assert.equal(framesToTimecode(1800, 29.97), '00:01:00;02');
+  assert.equal(framesToTimecode(17982, 29.97), '00:10:00;00');
+  assert.equal(framesToTimecode(107892, 29.97), '01:00:00;00');
+  assert.equal(framesToTimecode(3600, 59.94), '00:01:00;04');
+  for (const frame of [0, 1799, 1800, 1801, 3597, 17981, 17982, 17983, 107891, 107892]) {
+    assert.equal(timecodeToFrames(framesToTimecode(frame, 29.97), 29.97), frame);
+    assert.equal(timecodeToFrames(framesToTimecode(frame, 59.94), 59.94), frame);
+  }
+});
+
+test('drop-frame parsing rejects the labels the count skips', () => {
+  assert.throws(() => timecodeToFrames('00:01:00;00', 29.97), /Invalid timecode: 00:01:00;00/);
+  assert.throws(() => timecodeToFrames('00:01:00;01', 29.97), /Invalid timecode/);
+  assert.throws(() => timecodeToFrames('00:09:00;01', 29.97), /Invalid timecode/);
+  assert.throws(() => timecodeToFrames('00:01:00;03', 59.94), /Invalid timecode/);
+  assert.equal(timecodeToFrames('00:01:00;02', 29.97), 1800);
+  assert.equal(timecodeToFrames('00:10:00;00', 29.97), 17982);
+  assert.equal(timecodeToFrames('00:20:00;01', 29.97), 35965);
+  // Non-drop-frame labels at the same positions are fine
+  assert.equal(timecodeToFrames('00:01:00:00', 29.97), 1800);
+});
+
+test('timecode converts to and from milliseconds at frame starts', () => {
+  assert.equal(msToTimecode(60060, 29.97), '00:01:00;02');
+  assert.equal(timecodeToMs('00:01:00;02', 29.97), 60060);
+  assert.equal(msToTimecode(1000, 24), '00:00:01:00');
+  assert.equal(timecodeToMs('00:00:01:12', 24), 1500);
+});
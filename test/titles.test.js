@@ -0,0 +1,55 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { listTitleTemplates, resolveTitleParams, buildTitleScript } = require('../electron/titles');
+
+function dialogue(script) {
+  return script.split('\n').filter((line) => line.startsWith('Dialogue:'));
+}
+
+test('templates list their fields and logo slot', () => {
+  const templates = listTitleTemplates();
+  const bar = templates.find((t) => t.id === 'lower-third-bar');
+  assert.deepEqual(bar.fields.map((f) => f.key), ['title', 'subtitle']);
+  assert.equal(bar.has_logo_slot, true);
+  assert.equal(templates.find((t) => t.id === 'lower-third-box').has_logo_slot, false);
+});
+
+test('params fall back to template defaults and are clamped', () => {
+  const resolved = resolveTitleParams({
+    templateId: 'lower-third-box',
+    fields: { title: 'Ada Lovelace' },
+    logoPath: '/media/logo.png',
+    animation: 'spin',
+    width: 1281,
+    durationMs: 10,
+  });
+  assert.deepEqual(resolved.fields, { title: 'Ada Lovelace', subtitle: 'Director' });
+  assert.equal(resolved.logoPath, null);
+  assert.equal(resolved.animation, 'fade');
+  assert.equal(resolved.width, 1280);
+  assert.equal(resolved.durationMs, 1000);
+  assert.throws(() => resolveTitleParams({ templateId: 'nope' }), /Unknown title template/);
+});
+
+test('slide animation moves the bar and text in from off-frame', () => {
+  const script = buildTitleScript(resolveTitleParams({
+    templateId: 'lower-third-bar',
+    fields: { title: 'Ada {Lovelace}', subtitle: 'Mathematician' },
+    colors: { accent: '#FF0000' },
+  }));
+  const [bar, title, subtitle] = dialogue(script);
+  assert.match(bar, /^Dialogue: 0,0:00:00\.00,0:00:05\.00,Bar,.*\\move\(-768,832,115,832,0,400\).*\\p1}m 0 0 l 12 0/);
+  assert.match(title, /\\move\(-768,886,144,886,0,400\)\\fad\(0,400\)}Ada \\{Lovelace\\}$/);
+  assert.match(subtitle, /,Subtitle,.*}Mathematician$/);
+  assert.match(script, /Style: Bar,Arial,10,&H000000FF,/);
+});
+
+test('empty subtitle is omitted and RTL text sets the style encoding', () => {
+  const script = buildTitleScript(resolveTitleParams({
+    templateId: 'title-card',
+    fields: { title: 'שלום' },
+    animation: 'none',
+  }));
+  assert.deepEqual(dialogue(script), ['Dialogue: 0,0:00:00.00,0:00:04.00,Title,,0,0,0,,{\\pos(960,540)}שלום']);
+  assert.match(script, /Style: Title,.*,-1$/m);
+});
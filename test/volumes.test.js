@@ -0,0 +1,43 @@
+const test = require('node:test');
+const assert = require('node:assert/strict');
+const { parseProcMounts, parseDarwinMounts, parseWindowsDrives, classifyPath } = require('../electron/volumes');
+
+test('linux mounts are classified by filesystem, mount point and device', () => {
+  const mounts = parseProcMounts([
+    '/dev/nvme0n1p2 / ext4 rw,relatime 0 0',
+    'nas:/export/media /mnt/nas nfs4 rw 0 0',
+    '//nas/share /mnt/smb cifs rw 0 0',
+    '/dev/sdb1 /run/media/ana/CARD\\040A exfat rw 0 0',
+    '/dev/sdc1 /mnt/usb vfat rw 0 0',
+  ].join('\n'), (device) => device.startsWith('/dev/sdc'));
+
+  assert.deepEqual(classifyPath('/home/ana/clip.mp4', mounts, 'linux'), { kind: 'local', mount_point: '/', fs_type: 'ext4' });
+  assert.equal(classifyPath('/mnt/nas/day1/a.mov', mounts, 'linux').kind, 'network');
+  assert.equal(classifyPath('/mnt/smb/a.mov', mounts, 'linux').kind, 'network');
+  assert.deepEqual(classifyPath('/run/media/ana/CARD A/DCIM/a.mp4', mounts, 'linux'), {
+    kind: 'removable',
+    mount_point: '/run/media/ana/CARD A',
+    fs_type: 'exfat',
+  });
+  assert.equal(classifyPath('/mnt/usb/a.mp4', mounts, 'linux').kind, 'removable');
+  // A sibling whose name starts like a mount point isn't inside it
+  assert.equal(classifyPath('/mnt/usb2/a.mp4', mounts, 'linux').kind, 'local');
+});
+
+test('macOS and Windows volumes are classified', () => {
+  const darwin = parseDarwinMounts([
+    '/dev/disk3s1s1 on / (apfs, sealed, local, read-only, journaled)',
+    '//ana@nas._smb._tcp.local/Media on /Volumes/Media (smbfs, nodev, nosuid, mounted by ana)',
+    '/dev/disk5s1 on /Volumes/Untitled (exfat, local, nodev, nosuid, noowners)',
+  ].join('\n'));
+  assert.equal(classifyPath('/Users/ana/a.mov', darwin, 'darwin').kind, 'local');
+  assert.equal(classifyPath('/Volumes/Media/a.mov', darwin, 'darwin').kind, 'network');
+  assert.equal(classifyPath('/Volumes/Untitled/a.mov', darwin, 'darwin').kind, 'removable');
+
+  const win = parseWindowsDrives('C: 3\r\nE: 2\r\nZ: 4\r\n');
+  assert.equal(classifyPath('\\\\?\\C:\\Users\\ana\\a.mp4', win, 'win32').kind, 'local');
+  assert.equal(classifyPath('e:\\DCIM\\a.mp4', win, 'win32').kind, 'removable');
+  assert.equal(classifyPath('Z:\\a.mp4', win, 'win32').kind, 'network');
+  assert.equal(classifyPath('\\\\nas\\share\\a.mp4', win, 'win32').kind, 'network');
+  assert.equal(classifyPath('\\\\?\\UNC\\nas\\share\\a.mp4', win, 'win32').kind, 'network');
+});